@@ -10,6 +10,7 @@ fn evm(c: &mut Criterion) {
     bench::evm_build::run(c);
     bench::gas_cost_estimator::run(c);
     bench::subcall::run(c);
+    bench::opcodes::run(c);
 }
 criterion_group!(benches, evm);
 criterion_main!(benches);
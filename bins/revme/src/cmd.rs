@@ -1,7 +1,9 @@
 pub mod bench;
 pub mod blockchaintest;
 pub mod bytecode;
+pub mod code_cache;
 pub mod evmrunner;
+pub mod reprice;
 pub mod statetest;
 
 use clap::Parser;
@@ -23,6 +25,10 @@ pub enum MainCmd {
     Blockchaintest(blockchaintest::Cmd),
     /// Execute Ethereum blockchain tests.
     Btest(blockchaintest::Cmd),
+    /// Generate a state test fixture from the execution of a transaction.
+    Fixture(statetest::fixture::Cmd),
+    /// Replay blocks with modified gas params and report the gas deltas.
+    Reprice(reprice::Cmd),
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -33,6 +39,10 @@ pub enum Error {
     Blockchaintest(#[from] blockchaintest::Error),
     #[error(transparent)]
     EvmRunnerErrors(#[from] evmrunner::Errors),
+    #[error(transparent)]
+    Fixture(#[from] statetest::fixture::FixtureError),
+    #[error(transparent)]
+    Reprice(#[from] reprice::Error),
     #[error("Custom error: {0}")]
     Custom(&'static str),
 }
@@ -49,6 +59,8 @@ impl MainCmd {
                 cmd.run();
             }
             Self::Blockchaintest(cmd) | Self::Btest(cmd) => cmd.run()?,
+            Self::Fixture(cmd) => cmd.run()?,
+            Self::Reprice(cmd) => cmd.run()?,
         }
         Ok(())
     }
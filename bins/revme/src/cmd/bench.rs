@@ -2,6 +2,9 @@ pub mod analysis;
 pub mod burntpix;
 pub mod evm_build;
 pub mod gas_cost_estimator;
+pub mod inspector_overhead;
+pub mod memory;
+pub mod opcodes;
 pub mod snailtracer;
 pub mod subcall;
 pub mod transfer;
@@ -19,6 +22,8 @@ pub enum BenchName {
     EvmBuild,
     TransferMulti,
     GasCostEstimator,
+    InspectorOverhead,
+    Opcodes,
 }
 
 impl BenchName {
@@ -31,6 +36,8 @@ impl BenchName {
         BenchName::TransferMulti,
         BenchName::EvmBuild,
         BenchName::GasCostEstimator,
+        BenchName::InspectorOverhead,
+        BenchName::Opcodes,
     ];
 
     pub fn as_str(self) -> &'static str {
@@ -43,6 +50,8 @@ impl BenchName {
             BenchName::EvmBuild => "evm-build",
             BenchName::TransferMulti => "transfer-multi",
             BenchName::GasCostEstimator => "gas-cost-estimator",
+            BenchName::InspectorOverhead => "inspector-overhead",
+            BenchName::Opcodes => "opcodes",
         }
     }
 }
@@ -61,6 +70,11 @@ pub struct Cmd {
     /// Samples represents size of the sample for benchmarks ran
     #[arg(short = 's', long)]
     pub samples: Option<usize>,
+    /// Report allocations and peak RSS of the benchmark
+    ///
+    /// Allocation counting requires the `alloc-profiling` feature.
+    #[arg(long)]
+    pub memory: bool,
 }
 
 impl Cmd {
@@ -74,30 +88,45 @@ impl Cmd {
             .measurement_time(std::time::Duration::from_secs_f64(self.time.unwrap_or(1.5)))
             .sample_size(self.samples.unwrap_or(10));
 
+        if self.memory {
+            let report = memory::profile(|| self.run_bench(&mut criterion));
+            println!("{} memory: {report}", self.name.as_str());
+        } else {
+            self.run_bench(&mut criterion);
+        }
+    }
+
+    fn run_bench(&self, criterion: &mut criterion::Criterion) {
         match self.name {
             BenchName::Analysis => {
-                analysis::run(&mut criterion);
+                analysis::run(criterion);
             }
             BenchName::Burntpix => {
-                burntpix::run(&mut criterion);
+                burntpix::run(criterion);
             }
             BenchName::Snailtracer => {
-                snailtracer::run(&mut criterion);
+                snailtracer::run(criterion);
             }
             BenchName::Subcall => {
-                subcall::run(&mut criterion);
+                subcall::run(criterion);
             }
             BenchName::Transfer => {
-                transfer::run(&mut criterion);
+                transfer::run(criterion);
             }
             BenchName::EvmBuild => {
-                evm_build::run(&mut criterion);
+                evm_build::run(criterion);
             }
             BenchName::TransferMulti => {
-                transfer_multi::run(&mut criterion);
+                transfer_multi::run(criterion);
             }
             BenchName::GasCostEstimator => {
-                gas_cost_estimator::run(&mut criterion);
+                gas_cost_estimator::run(criterion);
+            }
+            BenchName::InspectorOverhead => {
+                inspector_overhead::run(criterion);
+            }
+            BenchName::Opcodes => {
+                opcodes::run(criterion);
             }
         }
     }
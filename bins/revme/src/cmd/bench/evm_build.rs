@@ -1,8 +1,53 @@
 use criterion::Criterion;
-use revm::{Context, MainBuilder, MainContext};
+use revm::{
+    bytecode::Bytecode,
+    context::TxEnv,
+    database::{BenchmarkDB, BENCH_CALLER, BENCH_TARGET},
+    primitives::{TxKind, U256},
+    Context, ExecuteEvm, MainBuilder, MainContext,
+};
+
+/// Number of transactions in the replayed block.
+const BLOCK_TXS: usize = 16;
 
 pub fn run(criterion: &mut Criterion) {
     criterion.bench_function("evm-build", |b| {
         b.iter(|| Context::mainnet().build_mainnet());
     });
+
+    // Replays a block of transfers with a new EVM for every transaction and with a single EVM
+    // that is reset between transactions.
+    let context = Context::mainnet()
+        .with_db(BenchmarkDB::new_bytecode(Bytecode::new()))
+        .modify_cfg_chained(|cfg| cfg.disable_nonce_check = true);
+    let tx = TxEnv::builder()
+        .caller(BENCH_CALLER)
+        .kind(TxKind::Call(BENCH_TARGET))
+        .value(U256::from(1))
+        .gas_price(1)
+        .gas_priority_fee(None)
+        .build()
+        .unwrap();
+
+    let mut group = criterion.benchmark_group("evm-reuse");
+    group.bench_function("rebuild", |b| {
+        b.iter(|| {
+            for _ in 0..BLOCK_TXS {
+                let mut evm = context.clone().build_mainnet();
+                evm.transact_one(tx.clone()).unwrap();
+            }
+        });
+    });
+
+    let mut evm = context.clone().build_mainnet();
+    group.bench_function("reset", |b| {
+        b.iter(|| {
+            for _ in 0..BLOCK_TXS {
+                evm.transact_one(tx.clone()).unwrap();
+                evm.reset_for_next_tx();
+            }
+            evm.reset_for_next_block();
+        });
+    });
+    group.finish();
 }
@@ -0,0 +1,63 @@
+use criterion::Criterion;
+
+use revm::{
+    bytecode::Bytecode,
+    context::TxEnv,
+    database::{BenchmarkDB, BENCH_CALLER, BENCH_TARGET},
+    inspector::NoOpInspector,
+    primitives::{bytes, hex, Bytes, TxKind},
+    Context, ExecuteEvm, InspectEvm, MainBuilder, MainContext,
+};
+
+/// Compares the snailtracer executed by an EVM without an inspector, by an EVM with an inspector
+/// that is not used and with inspection.
+///
+/// The first two must not differ, the interpreter loop used without inspection has no hook
+/// checks.
+pub fn run(criterion: &mut Criterion) {
+    let bytecode = Bytecode::new_raw(Bytes::from(hex::decode(super::snailtracer::BYTES).unwrap()));
+    let context = Context::mainnet()
+        .with_db(BenchmarkDB::new_bytecode(bytecode))
+        .modify_cfg_chained(|c| {
+            c.disable_nonce_check = true;
+            c.tx_gas_limit_cap = Some(u64::MAX);
+        });
+
+    let tx = TxEnv::builder()
+        .caller(BENCH_CALLER)
+        .kind(TxKind::Call(BENCH_TARGET))
+        .data(bytes!("30627b7c"))
+        .gas_limit(1_000_000_000)
+        .build()
+        .unwrap();
+
+    let mut group = criterion.benchmark_group("inspector-overhead");
+
+    let mut evm = context.clone().build_mainnet();
+    group.bench_function("without-inspector", |b| {
+        b.iter_batched(
+            || tx.clone(),
+            |input| evm.transact_one(input).unwrap(),
+            criterion::BatchSize::SmallInput,
+        );
+    });
+
+    let mut evm = context.build_mainnet_with_inspector(NoOpInspector {});
+    group.bench_function("with-unused-inspector", |b| {
+        b.iter_batched(
+            || tx.clone(),
+            |input| evm.transact_one(input).unwrap(),
+            criterion::BatchSize::SmallInput,
+        );
+    });
+
+    group.bench_function("inspect", |b| {
+        b.iter_batched(
+            || tx.clone(),
+            |input| evm.inspect_one_tx(input).unwrap(),
+            criterion::BatchSize::SmallInput,
+        );
+    });
+
+    group.finish();
+}
@@ -0,0 +1,200 @@
+//! Memory profiling for benchmarks.
+//!
+//! Allocation counting is done by [`CountingAllocator`] that needs to be registered as the
+//! global allocator, which `revme` does when the `alloc-profiling` feature is enabled.
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    fmt,
+    sync::atomic::{AtomicU64, AtomicUsize, Ordering},
+};
+
+static ALLOCATIONS: AtomicU64 = AtomicU64::new(0);
+static DEALLOCATIONS: AtomicU64 = AtomicU64::new(0);
+static ALLOCATED_BYTES: AtomicU64 = AtomicU64::new(0);
+static CURRENT_BYTES: AtomicUsize = AtomicUsize::new(0);
+static PEAK_BYTES: AtomicUsize = AtomicUsize::new(0);
+
+/// Global allocator that wraps [`System`] and counts allocations.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct CountingAllocator;
+
+impl CountingAllocator {
+    #[inline]
+    fn on_alloc(size: usize) {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        ALLOCATED_BYTES.fetch_add(size as u64, Ordering::Relaxed);
+        let current = CURRENT_BYTES.fetch_add(size, Ordering::Relaxed) + size;
+        PEAK_BYTES.fetch_max(current, Ordering::Relaxed);
+    }
+
+    #[inline]
+    fn on_dealloc(size: usize) {
+        DEALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        CURRENT_BYTES.fetch_sub(size, Ordering::Relaxed);
+    }
+}
+
+// SAFETY: All allocation is delegated to the system allocator.
+unsafe impl GlobalAlloc for CountingAllocator {
+    #[inline]
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = unsafe { System.alloc(layout) };
+        if !ptr.is_null() {
+            Self::on_alloc(layout.size());
+        }
+        ptr
+    }
+
+    #[inline]
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = unsafe { System.alloc_zeroed(layout) };
+        if !ptr.is_null() {
+            Self::on_alloc(layout.size());
+        }
+        ptr
+    }
+
+    #[inline]
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) };
+        Self::on_dealloc(layout.size());
+    }
+
+    #[inline]
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = unsafe { System.realloc(ptr, layout, new_size) };
+        if !new_ptr.is_null() {
+            Self::on_dealloc(layout.size());
+            Self::on_alloc(new_size);
+        }
+        new_ptr
+    }
+}
+
+/// Snapshot of the allocation counters.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct AllocStats {
+    /// Number of allocations.
+    pub allocations: u64,
+    /// Number of deallocations.
+    pub deallocations: u64,
+    /// Total number of allocated bytes.
+    pub allocated_bytes: u64,
+    /// Peak number of live heap bytes.
+    pub peak_bytes: usize,
+}
+
+impl AllocStats {
+    /// Returns the current counters.
+    ///
+    /// Counters are only updated if [`CountingAllocator`] is the global allocator.
+    pub fn current() -> Self {
+        Self {
+            allocations: ALLOCATIONS.load(Ordering::Relaxed),
+            deallocations: DEALLOCATIONS.load(Ordering::Relaxed),
+            allocated_bytes: ALLOCATED_BYTES.load(Ordering::Relaxed),
+            peak_bytes: PEAK_BYTES.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Resets the peak of live heap bytes to the current number of live bytes.
+    pub fn reset_peak() {
+        PEAK_BYTES.store(CURRENT_BYTES.load(Ordering::Relaxed), Ordering::Relaxed);
+    }
+
+    /// Returns the counters accumulated since `start`.
+    ///
+    /// The peak is not a counter and is taken from `self`.
+    pub fn since(self, start: Self) -> Self {
+        Self {
+            allocations: self.allocations - start.allocations,
+            deallocations: self.deallocations - start.deallocations,
+            allocated_bytes: self.allocated_bytes - start.allocated_bytes,
+            peak_bytes: self.peak_bytes,
+        }
+    }
+}
+
+/// Memory report of a single benchmark.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MemoryReport {
+    /// Allocation counters, [`None`] if the `alloc-profiling` feature is disabled.
+    pub alloc: Option<AllocStats>,
+    /// Peak resident set size of the process in bytes, if available.
+    pub peak_rss: Option<u64>,
+}
+
+impl fmt::Display for MemoryReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.alloc {
+            Some(alloc) => write!(
+                f,
+                "allocations: {}, deallocations: {}, allocated: {} bytes, peak heap: {} bytes",
+                alloc.allocations, alloc.deallocations, alloc.allocated_bytes, alloc.peak_bytes
+            )?,
+            None => write!(f, "allocations: n/a (enable `alloc-profiling` feature)")?,
+        }
+        match self.peak_rss {
+            Some(rss) => write!(f, ", peak RSS: {rss} bytes"),
+            None => write!(f, ", peak RSS: n/a"),
+        }
+    }
+}
+
+/// Runs `f` and returns the memory report of its execution.
+pub fn profile(f: impl FnOnce()) -> MemoryReport {
+    AllocStats::reset_peak();
+    let start = AllocStats::current();
+    f();
+    let alloc = AllocStats::current().since(start);
+    MemoryReport {
+        alloc: cfg!(feature = "alloc-profiling").then_some(alloc),
+        peak_rss: peak_rss(),
+    }
+}
+
+/// Returns the peak resident set size of the process in bytes.
+///
+/// Only supported on Linux, where it is read from `VmHWM` in `/proc/self/status`.
+pub fn peak_rss() -> Option<u64> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    let line = status.lines().find(|line| line.starts_with("VmHWM:"))?;
+    let kb: u64 = line
+        .trim_start_matches("VmHWM:")
+        .trim()
+        .trim_end_matches("kB")
+        .trim()
+        .parse()
+        .ok()?;
+    Some(kb * 1024)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stats_since() {
+        let start = AllocStats {
+            allocations: 2,
+            deallocations: 1,
+            allocated_bytes: 64,
+            peak_bytes: 64,
+        };
+        let end = AllocStats {
+            allocations: 5,
+            deallocations: 4,
+            allocated_bytes: 256,
+            peak_bytes: 128,
+        };
+        assert_eq!(
+            end.since(start),
+            AllocStats {
+                allocations: 3,
+                deallocations: 3,
+                allocated_bytes: 192,
+                peak_bytes: 128,
+            }
+        );
+    }
+}
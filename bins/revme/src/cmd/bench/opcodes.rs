@@ -0,0 +1,421 @@
+//! Microbenchmarks of single opcodes.
+//!
+//! Every case is a contract running a tight loop, the body of the loop repeats the benchmarked
+//! opcode [`UNROLL`] times together with the pushes and pops needed to keep the stack balanced.
+//! The reported ns/op include those and the loop overhead, see the `jumpdest` case for the
+//! cost of an almost empty loop.
+use criterion::{Criterion, Throughput};
+use revm::{
+    bytecode::opcode,
+    context::TxEnv,
+    database::{InMemoryDB, BENCH_CALLER, BENCH_TARGET},
+    handler::MainnetContext,
+    primitives::{TxKind, U256},
+    state::{AccountInfo, Bytecode},
+    Context, ExecuteEvm, MainBuilder, MainContext, MainnetEvm,
+};
+use std::time::{Duration, Instant};
+
+/// Number of times the opcode is repeated in the body of the loop.
+pub const UNROLL: u8 = 32;
+
+/// Offset of the `JUMPDEST` the loop jumps back to.
+const LOOP_START: u8 = 3;
+
+/// Number of runs the summary is averaged over.
+const SUMMARY_RUNS: u32 = 10;
+
+/// Benchmarked opcode.
+#[derive(Clone, Copy, Debug)]
+pub struct OpcodeCase {
+    /// Name of the case.
+    pub name: &'static str,
+    /// Class of the opcode.
+    pub class: &'static str,
+    /// Number of loop iterations.
+    pub iterations: u16,
+    /// Stack balanced body of the loop for the repetition `k`.
+    ///
+    /// The loop counter is on top of the stack and is unique per iteration.
+    pub body: fn(u8) -> Vec<u8>,
+}
+
+impl OpcodeCase {
+    /// Number of times the opcode is executed by a transaction.
+    pub fn ops(&self) -> u64 {
+        self.iterations as u64 * UNROLL as u64
+    }
+
+    /// Returns the contract running the loop.
+    pub fn bytecode(&self) -> Bytecode {
+        let [hi, lo] = self.iterations.to_be_bytes();
+        let mut code = vec![opcode::PUSH2, hi, lo, opcode::JUMPDEST];
+        for k in 0..UNROLL {
+            code.extend((self.body)(k));
+        }
+        code.extend([
+            opcode::PUSH1,
+            0x01,
+            opcode::SWAP1,
+            opcode::SUB,
+            opcode::DUP1,
+            opcode::PUSH1,
+            LOOP_START,
+            opcode::JUMPI,
+            opcode::POP,
+            opcode::STOP,
+        ]);
+        Bytecode::new_raw(code.into())
+    }
+}
+
+/// `PUSH1 b PUSH1 a OP POP`, for binary operations.
+fn binary(op: u8, a: u8, b: u8) -> Vec<u8> {
+    vec![opcode::PUSH1, b, opcode::PUSH1, a, op, opcode::POP]
+}
+
+/// `PUSH1 c PUSH1 b PUSH1 a OP POP`, for ternary operations.
+fn ternary(op: u8, a: u8, b: u8, c: u8) -> Vec<u8> {
+    vec![
+        opcode::PUSH1,
+        c,
+        opcode::PUSH1,
+        b,
+        opcode::PUSH1,
+        a,
+        op,
+        opcode::POP,
+    ]
+}
+
+/// Pushes a key unique for every repetition of every iteration, `counter << 5 | k`.
+///
+/// `dup` is the `DUP` opcode reaching the loop counter.
+fn unique_key(dup: u8, k: u8) -> Vec<u8> {
+    vec![
+        dup,
+        opcode::PUSH1,
+        5,
+        opcode::SHL,
+        opcode::PUSH1,
+        k,
+        opcode::OR,
+    ]
+}
+
+/// All benchmarked opcodes.
+pub const CASES: &[OpcodeCase] = &[
+    OpcodeCase {
+        name: "jumpdest",
+        class: "control",
+        iterations: 10_000,
+        body: |_| vec![opcode::JUMPDEST],
+    },
+    OpcodeCase {
+        name: "push1",
+        class: "stack",
+        iterations: 10_000,
+        body: |k| vec![opcode::PUSH1, k, opcode::POP],
+    },
+    OpcodeCase {
+        name: "dup1",
+        class: "stack",
+        iterations: 10_000,
+        body: |_| vec![opcode::DUP1, opcode::POP],
+    },
+    OpcodeCase {
+        name: "add",
+        class: "arithmetic",
+        iterations: 10_000,
+        body: |k| binary(opcode::ADD, k, 7),
+    },
+    OpcodeCase {
+        name: "mul",
+        class: "arithmetic",
+        iterations: 10_000,
+        body: |k| binary(opcode::MUL, k, 7),
+    },
+    OpcodeCase {
+        name: "div",
+        class: "arithmetic",
+        iterations: 10_000,
+        body: |k| binary(opcode::DIV, 0xff, k | 1),
+    },
+    OpcodeCase {
+        name: "exp",
+        class: "arithmetic",
+        iterations: 10_000,
+        body: |k| binary(opcode::EXP, k, 0xff),
+    },
+    OpcodeCase {
+        name: "addmod",
+        class: "arithmetic",
+        iterations: 10_000,
+        body: |k| ternary(opcode::ADDMOD, k, 0xff, 7),
+    },
+    OpcodeCase {
+        name: "mulmod",
+        class: "arithmetic",
+        iterations: 10_000,
+        body: |k| ternary(opcode::MULMOD, k, 0xff, 7),
+    },
+    OpcodeCase {
+        name: "lt",
+        class: "comparison",
+        iterations: 10_000,
+        body: |k| binary(opcode::LT, k, 7),
+    },
+    OpcodeCase {
+        name: "and",
+        class: "bitwise",
+        iterations: 10_000,
+        body: |k| binary(opcode::AND, k, 7),
+    },
+    OpcodeCase {
+        name: "shl",
+        class: "bitwise",
+        iterations: 10_000,
+        body: |k| binary(opcode::SHL, k, 0xff),
+    },
+    OpcodeCase {
+        name: "mstore",
+        class: "memory",
+        iterations: 10_000,
+        body: |k| vec![opcode::PUSH1, k, opcode::PUSH1, k, opcode::MSTORE],
+    },
+    OpcodeCase {
+        name: "mload",
+        class: "memory",
+        iterations: 10_000,
+        body: |k| vec![opcode::PUSH1, k, opcode::MLOAD, opcode::POP],
+    },
+    OpcodeCase {
+        name: "keccak256",
+        class: "hashing",
+        iterations: 10_000,
+        body: |k| {
+            vec![
+                opcode::PUSH1,
+                32,
+                opcode::PUSH1,
+                k,
+                opcode::KECCAK256,
+                opcode::POP,
+            ]
+        },
+    },
+    OpcodeCase {
+        name: "sload_hot",
+        class: "storage",
+        iterations: 10_000,
+        body: |k| vec![opcode::PUSH1, k, opcode::SLOAD, opcode::POP],
+    },
+    OpcodeCase {
+        name: "sload_cold",
+        class: "storage",
+        iterations: 1_000,
+        body: |k| {
+            let mut body = unique_key(opcode::DUP1, k);
+            body.extend([opcode::SLOAD, opcode::POP]);
+            body
+        },
+    },
+    OpcodeCase {
+        name: "sstore_hot",
+        class: "storage",
+        iterations: 10_000,
+        body: |k| vec![opcode::PUSH1, 1, opcode::PUSH1, k, opcode::SSTORE],
+    },
+    OpcodeCase {
+        name: "sstore_cold",
+        class: "storage",
+        iterations: 1_000,
+        body: |k| {
+            let mut body = vec![opcode::PUSH1, 1];
+            body.extend(unique_key(opcode::DUP2, k));
+            body.push(opcode::SSTORE);
+            body
+        },
+    },
+    OpcodeCase {
+        name: "balance_cold",
+        class: "account",
+        iterations: 1_000,
+        body: |k| {
+            let mut body = unique_key(opcode::DUP1, k);
+            body.extend([opcode::BALANCE, opcode::POP]);
+            body
+        },
+    },
+    OpcodeCase {
+        name: "call_empty",
+        class: "call",
+        iterations: 1_000,
+        body: |_| {
+            vec![
+                opcode::PUSH1,
+                0,
+                opcode::PUSH1,
+                0,
+                opcode::PUSH1,
+                0,
+                opcode::PUSH1,
+                0,
+                opcode::PUSH1,
+                0,
+                opcode::PUSH2,
+                0xde,
+                0xad,
+                opcode::GAS,
+                opcode::CALL,
+                opcode::POP,
+            ]
+        },
+    },
+    OpcodeCase {
+        name: "staticcall_identity",
+        class: "call",
+        iterations: 1_000,
+        body: |_| {
+            vec![
+                opcode::PUSH1,
+                32,
+                opcode::PUSH1,
+                0,
+                opcode::PUSH1,
+                32,
+                opcode::PUSH1,
+                0,
+                opcode::PUSH1,
+                0x04,
+                opcode::GAS,
+                opcode::STATICCALL,
+                opcode::POP,
+            ]
+        },
+    },
+];
+
+/// Builds the EVM running the case, its state is discarded after every transaction.
+fn build_evm(case: &OpcodeCase) -> MainnetEvm<MainnetContext<InMemoryDB>> {
+    let mut db = InMemoryDB::default();
+    db.insert_account_info(
+        BENCH_CALLER,
+        AccountInfo {
+            balance: U256::from(u128::MAX),
+            ..Default::default()
+        },
+    );
+    db.insert_account_info(
+        BENCH_TARGET,
+        AccountInfo {
+            code: Some(case.bytecode()),
+            ..Default::default()
+        },
+    );
+    Context::mainnet()
+        .with_db(db)
+        .modify_cfg_chained(|c| {
+            c.disable_nonce_check = true;
+            c.tx_gas_limit_cap = Some(u64::MAX);
+        })
+        .build_mainnet()
+}
+
+fn tx() -> TxEnv {
+    TxEnv::builder()
+        .caller(BENCH_CALLER)
+        .kind(TxKind::Call(BENCH_TARGET))
+        .gas_limit(1_000_000_000)
+        .build()
+        .unwrap()
+}
+
+/// Timing of a case.
+#[derive(Clone, Copy, Debug)]
+pub struct OpcodeTiming {
+    /// Benchmarked case.
+    pub case: OpcodeCase,
+    /// Mean time of a transaction.
+    pub elapsed: Duration,
+    /// Gas used by a transaction.
+    pub gas_used: u64,
+}
+
+impl OpcodeTiming {
+    /// Nanoseconds per executed opcode.
+    pub fn ns_per_op(&self) -> f64 {
+        self.elapsed.as_nanos() as f64 / self.case.ops() as f64
+    }
+
+    /// Gas executed per nanosecond.
+    pub fn gas_per_ns(&self) -> f64 {
+        self.gas_used as f64 / self.elapsed.as_nanos().max(1) as f64
+    }
+}
+
+/// Runs the case `runs` times and returns the mean timing.
+pub fn measure(case: &OpcodeCase, runs: u32) -> OpcodeTiming {
+    let mut evm = build_evm(case);
+    let tx = tx();
+    let mut gas_used = 0;
+    let mut elapsed = Duration::ZERO;
+    for _ in 0..runs {
+        let input = tx.clone();
+        let start = Instant::now();
+        let result = evm.transact(input).unwrap().result;
+        elapsed += start.elapsed();
+        assert!(result.is_success(), "{}: {result:?}", case.name);
+        gas_used = result.tx_gas_used();
+    }
+    OpcodeTiming {
+        case: *case,
+        elapsed: elapsed / runs.max(1),
+        gas_used,
+    }
+}
+
+pub fn run(criterion: &mut Criterion) {
+    let mut group = criterion.benchmark_group("opcodes");
+    for case in CASES {
+        let mut evm = build_evm(case);
+        let tx = tx();
+        group.throughput(Throughput::Elements(case.ops()));
+        group.bench_function(case.name, |b| {
+            b.iter_batched(
+                || tx.clone(),
+                |input| evm.transact(input).unwrap(),
+                criterion::BatchSize::SmallInput,
+            );
+        });
+    }
+    group.finish();
+
+    println!(
+        "\n{:<20} {:<12} {:>10} {:>10}",
+        "opcode", "class", "ns/op", "gas/ns"
+    );
+    for case in CASES {
+        let timing = measure(case, SUMMARY_RUNS);
+        println!(
+            "{:<20} {:<12} {:>10.2} {:>10.3}",
+            case.name,
+            case.class,
+            timing.ns_per_op(),
+            timing.gas_per_ns()
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cases_succeed() {
+        for case in CASES {
+            let timing = measure(case, 1);
+            assert!(timing.gas_used > 0, "{}", case.name);
+        }
+    }
+}
@@ -46,4 +46,4 @@ pub fn run(criterion: &mut Criterion) {
     });
 }
 
-const BYTES: &str = include_str!("snailtracer.hex");
+pub(super) const BYTES: &str = include_str!("snailtracer.hex");
@@ -1,7 +1,7 @@
 pub mod post_block;
 pub mod pre_block;
 
-use crate::dir_utils::find_all_json_tests;
+use crate::{cmd::code_cache::CodeCache, dir_utils::find_all_json_tests};
 use clap::Parser;
 
 use revm::statetest_types::blockchain::{
@@ -9,7 +9,7 @@ use revm::statetest_types::blockchain::{
 };
 use revm::{
     bytecode::Bytecode,
-    context::{cfg::CfgEnv, ContextTr},
+    context::{cfg::CfgEnv, Cfg, ContextTr},
     context_interface::{block::BlobExcessGasAndPrice, result::HaltReason},
     database::{states::bundle_state::BundleRetention, EmptyDB, State},
     handler::EvmTr,
@@ -101,6 +101,7 @@ fn run_tests(
 
     let start_time = Instant::now();
     let total_files = test_files.len();
+    let code_cache = CodeCache::default();
 
     for (file_index, file_path) in test_files.into_iter().enumerate() {
         let current_file = file_index + 1;
@@ -124,7 +125,7 @@ fn run_tests(
             continue;
         }
 
-        let result = run_test_file(&file_path, json_output, print_env_on_error);
+        let result = run_test_file(&file_path, json_output, print_env_on_error, &code_cache);
 
         match result {
             Ok(test_count) => {
@@ -196,6 +197,7 @@ fn run_tests(
         println!("  Failed:  {failed}");
         println!("  Skipped: {skipped}");
         println!("  Time:    {:.2}s", duration.as_secs_f64());
+        code_cache.print();
     }
 
     if failed > 0 {
@@ -210,6 +212,7 @@ fn run_test_file(
     file_path: &Path,
     json_output: bool,
     print_env_on_error: bool,
+    code_cache: &CodeCache,
 ) -> Result<usize, Error> {
     let content =
         fs::read_to_string(file_path).map_err(|e| Error::FileRead(file_path.to_path_buf(), e))?;
@@ -232,7 +235,8 @@ fn run_test_file(
             println!("  Running: {test_name}");
         }
         // Execute the blockchain test
-        let result = execute_blockchain_test(&test_case, print_env_on_error, json_output);
+        let result =
+            execute_blockchain_test(&test_case, print_env_on_error, json_output, code_cache);
 
         match result {
             Ok(()) => {
@@ -658,6 +662,7 @@ fn execute_blockchain_test(
     test_case: &BlockchainTestCase,
     print_env_on_error: bool,
     json_output: bool,
+    code_cache: &CodeCache,
 ) -> Result<(), TestExecutionError> {
     // Skip all transition forks for now.
     if matches!(
@@ -687,7 +692,8 @@ fn execute_blockchain_test(
     let genesis_state = test_case.pre.clone().into_genesis_state();
     for (address, account) in genesis_state {
         let code_hash = revm::primitives::keccak256(&account.code);
-        let bytecode = (!account.code.is_empty()).then(|| Bytecode::new_raw(account.code.clone()));
+        let bytecode = (!account.code.is_empty())
+            .then(|| code_cache.get_or_analyze(code_hash, &account.code, Bytecode::new_raw));
         let account_info = AccountInfo {
             balance: account.balance,
             nonce: account.nonce,
@@ -756,9 +762,9 @@ fn execute_blockchain_test(
         if let Some(block_header) = block.block_header.as_ref() {
             block_hash = Some(block_header.hash);
             beacon_root = block_header.parent_beacon_block_root;
-            block_env = block_header.to_block_env(Some(BlobExcessGasAndPrice::new_with_spec(
+            block_env = block_header.to_block_env(Some(BlobExcessGasAndPrice::new_with_params(
                 parent_excess_blob_gas,
-                spec_id,
+                cfg.blob_params(),
             )));
             this_excess_blob_gas = block_header.excess_blob_gas.map(|i| i.to::<u64>());
         } else {
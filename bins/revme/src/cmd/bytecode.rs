@@ -1,5 +1,15 @@
 use clap::Parser;
-use revm::primitives::{hex, Bytes};
+use revm::{
+    bytecode::{
+        eip7702::{EIP7702_MAGIC_BYTES, EIP7702_VERSION},
+        opcode, Bytecode, OpCode,
+    },
+    primitives::{hex, keccak256, Address, Bytes, B256},
+};
+use serde::Serialize;
+
+/// EOF magic bytes (EIP-3540).
+const EOF_MAGIC_BYTES: &[u8] = &[0xEF, 0x00];
 
 /// `bytecode` subcommand - simplified to handle legacy bytecode only.
 #[derive(Parser, Debug)]
@@ -7,6 +17,9 @@ pub struct Cmd {
     /// Bytecode in hex format string.
     #[arg()]
     bytes: Option<String>,
+    /// Output the analysis in JSON format.
+    #[arg(long)]
+    json: bool,
 }
 
 #[inline]
@@ -15,46 +28,323 @@ fn trim_decode(input: &str) -> Option<Bytes> {
     hex::decode(trimmed).ok().map(Into::into)
 }
 
-impl Cmd {
-    /// Runs bytecode command.
-    pub fn run(&self) -> Result<(), super::Error> {
-        if let Some(input_bytes) = &self.bytes {
-            let Some(bytes) = trim_decode(input_bytes) else {
-                // Fail on invalid hex to propagate a non-zero exit code
-                return Err(super::Error::Custom("Invalid hex string"));
-            };
+/// Machine-readable description of a bytecode.
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct BytecodeAnalysis {
+    /// Kind of the bytecode: `legacy`, `eip7702` or `eof`.
+    pub kind: &'static str,
+    /// Length of the bytecode in bytes.
+    pub length: usize,
+    /// Keccak256 hash of the bytecode.
+    pub hash: B256,
+    /// Delegated address for EIP-7702 bytecode.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub delegated_address: Option<Address>,
+    /// Decoded instructions.
+    pub instructions: Vec<Instruction>,
+    /// Straight-line code segments split at jump destinations and terminating instructions.
+    pub segments: Vec<CodeSegment>,
+    /// Validation errors found in the bytecode.
+    pub errors: Vec<Diagnostic>,
+}
+
+/// Single decoded instruction.
+#[derive(Clone, Debug, Serialize)]
+pub struct Instruction {
+    /// Offset of the opcode in the bytecode.
+    pub offset: usize,
+    /// Opcode byte.
+    pub opcode: u8,
+    /// Opcode name, `UNKNOWN` for undefined opcodes.
+    pub name: &'static str,
+    /// Immediate bytes following the opcode.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub immediate: Option<Bytes>,
+}
 
-            if bytes.starts_with(&[0xEF, 0x00]) {
-                // Fail on EOF bytecode as it's not supported
-                return Err(super::Error::Custom(
-                    "EOF bytecode is not supported - EOF has been removed from ethereum plan.",
-                ));
+/// Straight-line code segment with its stack bounds.
+#[derive(Clone, Debug, Serialize)]
+pub struct CodeSegment {
+    /// Offset of the first instruction.
+    pub start: usize,
+    /// Offset one past the last byte of the segment.
+    pub end: usize,
+    /// Number of stack items the segment requires on entry.
+    pub stack_required: usize,
+    /// Maximum stack height reached, relative to the entry height.
+    pub max_stack_growth: isize,
+    /// Stack height change at the end of the segment.
+    pub stack_diff: isize,
+}
+
+/// Validation error with its offset and explanation.
+#[derive(Clone, Debug, Serialize)]
+pub struct Diagnostic {
+    /// Offset in the bytecode where the error occurs.
+    pub offset: usize,
+    /// Short error name.
+    pub error: &'static str,
+    /// Human-readable explanation of the error.
+    pub message: String,
+}
+
+impl BytecodeAnalysis {
+    /// Analyzes the raw bytecode.
+    pub fn new(bytes: &[u8]) -> Self {
+        let mut analysis = Self {
+            length: bytes.len(),
+            hash: keccak256(bytes),
+            ..Default::default()
+        };
+
+        if bytes.starts_with(EOF_MAGIC_BYTES) {
+            analysis.kind = "eof";
+            analysis.errors.push(Diagnostic {
+                offset: 0,
+                error: "EofNotSupported",
+                message: "Bytecode starts with the EOF magic 0xEF00. EOF (EIP-3540) has been \
+                          removed from the Ethereum plan and such code can't be deployed on any \
+                          active fork."
+                    .into(),
+            });
+            return analysis;
+        }
+
+        if bytes.starts_with(EIP7702_MAGIC_BYTES) {
+            analysis.kind = "eip7702";
+            match Bytecode::new_eip7702_raw(Bytes::copy_from_slice(bytes)) {
+                Ok(bytecode) => analysis.delegated_address = bytecode.eip7702_address(),
+                Err(e) => analysis.errors.push(Diagnostic {
+                    offset: 0,
+                    error: "InvalidEip7702",
+                    message: format!(
+                        "{e}. EIP-7702 delegation designator must be 0xEF01, version \
+                         0x{EIP7702_VERSION:02x} and a 20 byte address (23 bytes in total)."
+                    ),
+                }),
             }
+            return analysis;
+        }
 
-            println!("Legacy bytecode:");
-            println!("  Length: {} bytes", bytes.len());
-            println!("  Hex: 0x{}", hex::encode(&bytes));
-
-            // Basic analysis
-            let mut opcodes = Vec::new();
-            let mut i = 0;
-            while i < bytes.len() {
-                let opcode = bytes[i];
-                opcodes.push(format!("{opcode:02x}"));
-
-                // Skip immediate bytes for PUSH instructions
-                if (0x60..=0x7f).contains(&opcode) {
-                    let push_size = (opcode - 0x5f) as usize;
-                    i += push_size;
+        analysis.kind = "legacy";
+        analysis.analyze_legacy(bytes);
+        analysis
+    }
+
+    /// Decodes instructions and splits them into segments.
+    fn analyze_legacy(&mut self, bytes: &[u8]) {
+        let mut segment: Option<CodeSegment> = None;
+        let mut height = 0isize;
+        let mut i = 0;
+        while i < bytes.len() {
+            let op = bytes[i];
+            let info = OpCode::info_by_op(op);
+
+            if op == opcode::JUMPDEST {
+                self.segments.extend(segment.take());
+            }
+            let current = segment.get_or_insert_with(|| {
+                height = 0;
+                CodeSegment {
+                    start: i,
+                    end: i,
+                    stack_required: 0,
+                    max_stack_growth: 0,
+                    stack_diff: 0,
                 }
-                i += 1;
+            });
+
+            let immediate_size = info.map_or(0, |info| info.immediate_size() as usize);
+            let immediate_end = i + 1 + immediate_size;
+            if immediate_end > bytes.len() {
+                self.errors.push(Diagnostic {
+                    offset: i,
+                    error: "TruncatedImmediate",
+                    message: format!(
+                        "{} expects {immediate_size} immediate bytes but only {} remain. \
+                         Missing bytes are read as zeros.",
+                        OpCode::name_by_op(op),
+                        bytes.len() - i - 1
+                    ),
+                });
             }
+            let end = immediate_end.min(bytes.len());
 
-            println!("  Opcodes: {}", opcodes.join(" "));
-        } else {
+            let Some(info) = info else {
+                self.errors.push(Diagnostic {
+                    offset: i,
+                    error: "UnknownOpcode",
+                    message: format!(
+                        "Opcode 0x{op:02x} is not defined. Execution halts with `OpcodeNotFound` \
+                         if it is reached."
+                    ),
+                });
+                self.instructions.push(Instruction {
+                    offset: i,
+                    opcode: op,
+                    name: "UNKNOWN",
+                    immediate: None,
+                });
+                current.end = end;
+                // Undefined opcodes halt the execution.
+                self.segments.extend(segment.take());
+                i = end;
+                continue;
+            };
+
+            let inputs = info.inputs() as isize;
+            current.stack_required = current
+                .stack_required
+                .max((inputs - height).max(0) as usize);
+            height += info.io_diff() as isize;
+            current.max_stack_growth = current.max_stack_growth.max(height);
+            current.stack_diff = height;
+            current.end = end;
+
+            self.instructions.push(Instruction {
+                offset: i,
+                opcode: op,
+                name: info.name(),
+                immediate: (immediate_size > 0).then(|| Bytes::copy_from_slice(&bytes[i + 1..end])),
+            });
+
+            if info.is_terminating() || OpCode::is_jump_by_op(op) || op == opcode::JUMPI {
+                self.segments.extend(segment.take());
+            }
+            i = end;
+        }
+        self.segments.extend(segment);
+    }
+}
+
+impl Cmd {
+    /// Runs bytecode command.
+    pub fn run(&self) -> Result<(), super::Error> {
+        let Some(input_bytes) = &self.bytes else {
             println!("No bytecode provided. EOF interactive mode has been removed.");
             println!("Please provide bytecode as a hex string argument.");
+            return Ok(());
+        };
+        let Some(bytes) = trim_decode(input_bytes) else {
+            // Fail on invalid hex to propagate a non-zero exit code
+            return Err(super::Error::Custom("Invalid hex string"));
+        };
+
+        let analysis = BytecodeAnalysis::new(&bytes);
+
+        if self.json {
+            println!("{}", serde_json::to_string_pretty(&analysis).unwrap());
+        } else {
+            analysis.print(&bytes);
+        }
+
+        if analysis.kind == "eof" {
+            // Fail on EOF bytecode as it's not supported
+            return Err(super::Error::Custom(
+                "EOF bytecode is not supported - EOF has been removed from ethereum plan.",
+            ));
         }
         Ok(())
     }
 }
+
+impl BytecodeAnalysis {
+    /// Prints the analysis in a human-readable format.
+    fn print(&self, bytes: &[u8]) {
+        match self.kind {
+            "legacy" => {
+                println!("Legacy bytecode:");
+                println!("  Length: {} bytes", self.length);
+                println!("  Hash: {}", self.hash);
+                println!("  Hex: 0x{}", hex::encode(bytes));
+                let opcodes: Vec<_> = self
+                    .instructions
+                    .iter()
+                    .map(|inst| format!("{:02x}", inst.opcode))
+                    .collect();
+                println!("  Opcodes: {}", opcodes.join(" "));
+                println!("  Segments:");
+                for segment in &self.segments {
+                    println!(
+                        "    [{}..{}) requires {} stack items, max growth {}, diff {}",
+                        segment.start,
+                        segment.end,
+                        segment.stack_required,
+                        segment.max_stack_growth,
+                        segment.stack_diff
+                    );
+                }
+            }
+            "eip7702" => {
+                println!("EIP-7702 bytecode:");
+                println!("  Hex: 0x{}", hex::encode(bytes));
+                if let Some(address) = self.delegated_address {
+                    println!("  Delegated address: {address}");
+                }
+            }
+            _ => println!("EOF bytecode:"),
+        }
+
+        if !self.errors.is_empty() {
+            println!("  Errors:");
+            for error in &self.errors {
+                println!(
+                    "    {} at offset {}: {}",
+                    error.error, error.offset, error.message
+                );
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn legacy_segments() {
+        // PUSH1 0x04 JUMP JUMPDEST ADD STOP
+        let analysis = BytecodeAnalysis::new(&hex!("6004565b0100"));
+        assert_eq!(analysis.kind, "legacy");
+        assert!(analysis.errors.is_empty());
+        assert_eq!(analysis.instructions.len(), 5);
+        assert_eq!(analysis.segments.len(), 2);
+
+        let first = &analysis.segments[0];
+        assert_eq!((first.start, first.end), (0, 3));
+        assert_eq!(first.stack_required, 0);
+        assert_eq!(first.max_stack_growth, 1);
+        assert_eq!(first.stack_diff, 0);
+
+        let second = &analysis.segments[1];
+        assert_eq!((second.start, second.end), (3, 6));
+        assert_eq!(second.stack_required, 2);
+        assert_eq!(second.stack_diff, -1);
+    }
+
+    #[test]
+    fn legacy_errors() {
+        // UNKNOWN(0x0c) PUSH2 0x01
+        let analysis = BytecodeAnalysis::new(&hex!("0c6101"));
+        let errors: Vec<_> = analysis
+            .errors
+            .iter()
+            .map(|e| (e.offset, e.error))
+            .collect();
+        assert_eq!(
+            errors,
+            vec![(0, "UnknownOpcode"), (1, "TruncatedImmediate")]
+        );
+    }
+
+    #[test]
+    fn eof_and_eip7702() {
+        let analysis = BytecodeAnalysis::new(&hex!("ef0001"));
+        assert_eq!(analysis.kind, "eof");
+        assert_eq!(analysis.errors[0].error, "EofNotSupported");
+
+        let analysis = BytecodeAnalysis::new(&hex!("ef0100"));
+        assert_eq!(analysis.kind, "eip7702");
+        assert_eq!(analysis.errors[0].error, "InvalidEip7702");
+    }
+}
@@ -0,0 +1,85 @@
+use revm::{
+    bytecode::Bytecode,
+    primitives::{B256Map, Bytes, B256},
+};
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    RwLock,
+};
+
+/// Cache of analyzed bytecode keyed by code hash, shared by the test runner threads.
+///
+/// Test suites reuse the same contracts in many tests and forks, with the cache every
+/// contract is analyzed once per run instead of once per test.
+#[derive(Debug, Default)]
+pub struct CodeCache {
+    codes: RwLock<B256Map<Bytecode>>,
+    hits: AtomicUsize,
+}
+
+impl CodeCache {
+    /// Returns the analyzed bytecode of `code`, analyzing it with `analyze` on a cache miss.
+    pub fn get_or_analyze(
+        &self,
+        code_hash: B256,
+        code: &Bytes,
+        analyze: impl FnOnce(Bytes) -> Bytecode,
+    ) -> Bytecode {
+        if let Some(bytecode) = self.codes.read().unwrap().get(&code_hash) {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+            return bytecode.clone();
+        }
+        // Analysis is done outside of the lock, another thread may race us to the same code.
+        let bytecode = analyze(code.clone());
+        self.codes
+            .write()
+            .unwrap()
+            .entry(code_hash)
+            .or_insert(bytecode)
+            .clone()
+    }
+
+    /// Returns the number of analyzed contracts.
+    pub fn len(&self) -> usize {
+        self.codes.read().unwrap().len()
+    }
+
+    /// Returns `true` if no contract was analyzed.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the number of lookups that reused an analyzed contract.
+    pub fn hits(&self) -> usize {
+        self.hits.load(Ordering::Relaxed)
+    }
+
+    /// Prints the number of analyzed and reused contracts.
+    pub fn print(&self) {
+        println!(
+            "Analyzed {} contracts, reused {} times",
+            self.len(),
+            self.hits()
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use revm::primitives::keccak256;
+
+    #[test]
+    fn analyzes_once() {
+        let cache = CodeCache::default();
+        let code = Bytes::from_static(&[0x5b, 0x00]);
+        let code_hash = keccak256(&code);
+
+        let first = cache.get_or_analyze(code_hash, &code, Bytecode::new_raw);
+        let second = cache.get_or_analyze(code_hash, &code, |_| unreachable!());
+
+        assert_eq!(first, second);
+        assert_eq!(cache.len(), 1);
+        assert_eq!(cache.hits(), 1);
+    }
+}
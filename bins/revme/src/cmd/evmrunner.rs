@@ -1,13 +1,29 @@
+mod abi;
+mod fork;
+
+pub use abi::FunctionSig;
+pub use fork::{Fork, ForkDB};
+
 use clap::Parser;
 use revm::{
     bytecode::{Bytecode, BytecodeDecodeError},
-    context::TxEnv,
+    context::{BlockEnv, CfgEnv, TxEnv},
+    context_interface::{result::ExecutionResult, Cfg},
     database::{BenchmarkDB, BENCH_CALLER, BENCH_TARGET},
-    inspector::{inspectors::TracerEip3155, InspectEvm},
-    primitives::{hex, TxKind},
-    Context, Database, ExecuteEvm, MainBuilder, MainContext,
+    inspector::{
+        inspectors::{TraceStep, TraceVerifier, TracerEip3155},
+        InspectEvm,
+    },
+    primitives::{hardfork::SpecId, hex, Address, Bytes, TxKind, U256},
+    Context, Database, ExecuteEvm, Journal, MainBuilder, MainContext,
+};
+use std::{
+    borrow::Cow,
+    fs,
+    io::{BufReader, Error as IoError},
+    path::{Path, PathBuf},
+    time::Instant,
 };
-use std::{borrow::Cow, fs, io::Error as IoError, path::PathBuf, time::Instant};
 
 #[derive(Debug, thiserror::Error)]
 pub enum Errors {
@@ -23,6 +39,29 @@ pub enum Errors {
     Io(#[from] IoError),
     #[error(transparent)]
     BytecodeDecodeError(#[from] BytecodeDecodeError),
+    #[error("Invalid function signature: {0}")]
+    InvalidSignature(String),
+    #[error("Invalid number of arguments: expected {expected}, got {got}")]
+    InvalidArgsCount { expected: usize, got: usize },
+    #[error(transparent)]
+    Abi(#[from] alloy_dyn_abi::Error),
+    #[error("execution diverged from the trace")]
+    TraceDivergence,
+    #[error("Fork error: {0}")]
+    Fork(String),
+}
+
+/// Parses a [`SpecId`] case-insensitively, accepting both the hardfork name (`Spurious`)
+/// and the variant name (`SPURIOUS_DRAGON`).
+fn parse_spec(s: &str) -> Result<SpecId, String> {
+    let s = s.trim();
+    (0..=SpecId::NEXT as u8)
+        .filter_map(SpecId::try_from_u8)
+        .find(|spec| {
+            <&str>::from(*spec).eq_ignore_ascii_case(s)
+                || format!("{spec:?}").eq_ignore_ascii_case(s)
+        })
+        .ok_or_else(|| format!("unknown spec: {s}"))
 }
 
 /// Evm runner command allows running arbitrary evm bytecode
@@ -31,7 +70,7 @@ pub enum Errors {
 #[derive(Parser, Debug)]
 pub struct Cmd {
     /// Hex-encoded EVM bytecode to be executed
-    #[arg(required_unless_present = "path")]
+    #[arg(required_unless_present_any = ["path", "to"])]
     bytecode: Option<String>,
     /// Path to a file containing the hex-encoded EVM bytecode to be executed
     ///
@@ -44,8 +83,17 @@ pub struct Cmd {
     bench: bool,
 
     /// Hex-encoded input/calldata bytes
-    #[arg(long, default_value = "")]
+    #[arg(long, default_value = "", conflicts_with = "sig")]
     input: String,
+    /// Function signature used to ABI-encode the calldata from `--args`
+    ///
+    /// Output types can be appended to decode the return data,
+    /// e.g. `balanceOf(address)(uint256)`.
+    #[arg(long)]
+    sig: Option<String>,
+    /// Function arguments, encoded according to `--sig`
+    #[arg(long, num_args = 0.., requires = "sig", allow_hyphen_values = true)]
+    args: Vec<String>,
     /// Gas limit
     #[arg(long, default_value = "1000000000")]
     gas_limit: u64,
@@ -59,51 +107,162 @@ pub struct Cmd {
     /// Output results in JSON format
     #[arg(long)]
     json: bool,
+    /// Comma-separated list of specs to execute the transaction with and compare gas usage,
+    /// e.g. `LONDON,SHANGHAI,CANCUN,PRAGUE`
+    #[arg(long, value_delimiter = ',', value_parser = parse_spec, conflicts_with_all = ["bench", "trace"])]
+    compare_specs: Vec<SpecId>,
+    /// Path to an EIP-3155 trace of the same execution, e.g. written by another client
+    ///
+    /// The execution is compared step by step with the trace and the first divergence is
+    /// reported.
+    #[arg(long, value_name = "PATH", conflicts_with_all = ["bench", "trace", "compare_specs"])]
+    verify_trace: Option<PathBuf>,
+    /// RPC URL of a chain to fork, the transaction is executed against its state
+    ///
+    /// Accounts and storage are fetched on demand and cached for the run. The block
+    /// environment and the spec are taken from the forked block. The caller is funded to pay for
+    /// the gas at the base fee of the block.
+    #[arg(long, value_name = "URL", conflicts_with_all = ["compare_specs", "verify_trace"])]
+    fork_url: Option<String>,
+    /// Number of the block to fork at, the latest block if not set
+    ///
+    /// State is read at the end of the block.
+    #[arg(long, value_name = "NUMBER", requires = "fork_url")]
+    fork_block: Option<u64>,
+    /// Address of the called account on the forked chain
+    ///
+    /// Without bytecode, the code of the account is executed. With bytecode, the code of the
+    /// account is replaced and its balance and storage are kept.
+    #[arg(long, value_name = "ADDRESS", requires = "fork_url")]
+    to: Option<Address>,
+}
+
+/// Outcome of the execution under a single spec.
+#[derive(Debug, serde::Serialize)]
+struct SpecOutcome {
+    spec: SpecId,
+    outcome: String,
+    gas_used: u64,
+    gas_refunded: u64,
 }
 
 impl Cmd {
     /// Runs evm runner command.
     pub fn run(&self) -> Result<(), Errors> {
-        let bytecode_str: Cow<'_, str> = if let Some(path) = &self.path {
+        let bytecode_str: Option<Cow<'_, str>> = if let Some(path) = &self.path {
             // Check if path exists.
             if !path.exists() {
                 return Err(Errors::PathNotExists);
             }
-            fs::read_to_string(path)?.into()
-        } else if let Some(bytecode) = &self.bytecode {
-            bytecode.as_str().into()
+            Some(fs::read_to_string(path)?.into())
         } else {
-            unreachable!()
+            self.bytecode.as_deref().map(Into::into)
         };
 
-        let bytecode = hex::decode(bytecode_str.trim().trim_start_matches("0x"))
-            .map_err(|_| Errors::InvalidBytecode)?;
-        let input = hex::decode(self.input.trim().trim_start_matches("0x"))
-            .map_err(|_| Errors::InvalidInput)?
-            .into();
+        let bytecode = bytecode_str
+            .map(|bytecode| {
+                hex::decode(bytecode.trim().trim_start_matches("0x"))
+                    .map_err(|_| Errors::InvalidBytecode)
+            })
+            .transpose()?;
+        let sig = self.sig.as_deref().map(FunctionSig::parse).transpose()?;
+        let input = if let Some(sig) = &sig {
+            sig.encode_call(&self.args)?
+        } else {
+            hex::decode(self.input.trim().trim_start_matches("0x"))
+                .map_err(|_| Errors::InvalidInput)?
+        }
+        .into();
+
+        let bytecode = bytecode
+            .map(|bytecode| Bytecode::new_raw_checked(bytecode.into()))
+            .transpose()?;
+        if let Some(url) = &self.fork_url {
+            return self.run_fork(url, bytecode, input, sig.as_ref());
+        }
+        // Bytecode can only be omitted when forking.
+        let Some(bytecode) = bytecode else {
+            unreachable!()
+        };
+        if !self.compare_specs.is_empty() {
+            return self.compare_specs(bytecode, input);
+        }
 
-        let mut db = BenchmarkDB::new_bytecode(Bytecode::new_raw_checked(bytecode.into())?);
+        let mut db = BenchmarkDB::new_bytecode(bytecode);
 
         let nonce = db
             .basic(BENCH_CALLER)
             .unwrap()
             .map_or(0, |account| account.nonce);
 
+        let tx = TxEnv::builder()
+            .caller(BENCH_CALLER)
+            .kind(TxKind::Call(BENCH_TARGET))
+            .data(input)
+            .nonce(nonce)
+            .gas_limit(self.gas_limit)
+            .build()
+            .unwrap();
+
+        if let Some(path) = &self.verify_trace {
+            return verify_trace(path, db, tx);
+        }
+
         // BenchmarkDB is dummy state that implements Database trait.
         // The bytecode is deployed at zero address.
-        let mut evm = Context::mainnet()
-            .with_db(db)
-            .build_mainnet_with_inspector(TracerEip3155::new(Box::new(std::io::stdout())));
+        self.execute(Context::mainnet().with_db(db), tx, sig.as_ref())
+    }
+
+    /// Executes the transaction against the state of the chain from `--fork-url`.
+    fn run_fork(
+        &self,
+        url: &str,
+        bytecode: Option<Bytecode>,
+        input: Bytes,
+        sig: Option<&FunctionSig>,
+    ) -> Result<(), Errors> {
+        let mut fork = Fork::connect(url, self.fork_block)?;
+        let target = self.to.unwrap_or(BENCH_TARGET);
+        if let Some(bytecode) = bytecode {
+            fork.set_code(target, bytecode)?;
+        }
+
+        let cfg = CfgEnv::new_with_spec(fork.spec);
+        let gas_limit = self
+            .gas_limit
+            .min(fork.block.gas_limit)
+            .min(cfg.tx_gas_limit_cap());
+        let gas_price = fork.block.basefee;
+        fork.fund(BENCH_CALLER, U256::from(gas_limit) * U256::from(gas_price))?;
+        let nonce = fork.nonce(BENCH_CALLER)?;
 
         let tx = TxEnv::builder()
             .caller(BENCH_CALLER)
-            .kind(TxKind::Call(BENCH_TARGET))
+            .kind(TxKind::Call(target))
             .data(input)
             .nonce(nonce)
-            .gas_limit(self.gas_limit)
+            .gas_limit(gas_limit)
+            .gas_price(gas_price.into())
             .build()
             .unwrap();
 
+        let ctx = Context::mainnet()
+            .with_db(fork.db)
+            .with_block(fork.block)
+            .with_cfg(cfg);
+        self.execute(ctx, tx, sig)
+    }
+
+    /// Executes the transaction and prints the result.
+    fn execute<DB: Database>(
+        &self,
+        ctx: Context<BlockEnv, TxEnv, CfgEnv, DB, Journal<DB>, ()>,
+        tx: TxEnv,
+        sig: Option<&FunctionSig>,
+    ) -> Result<(), Errors> {
+        let mut evm =
+            ctx.build_mainnet_with_inspector(TracerEip3155::new(Box::new(std::io::stdout())));
+
         if self.bench {
             let mut criterion = criterion::Criterion::default()
                 .warm_up_time(std::time::Duration::from_millis(300))
@@ -131,22 +290,29 @@ impl Cmd {
         .map_err(|_| Errors::EVMError)?;
         let time = time.elapsed();
 
+        // Only successful calls return data that is encoded with the output types.
+        let decoded = sig
+            .zip(r.result.is_success().then(|| r.result.output()).flatten())
+            .and_then(|(sig, output)| sig.decode_output(output))
+            .transpose()?;
+
         if self.json {
-            let json = if self.state {
-                serde_json::json!({
-                    "result": r.result,
-                    "state": r.state,
-                    "elapsed": time.as_secs_f64(),
-                })
-            } else {
-                serde_json::json!({
-                    "result": r.result,
-                    "elapsed": time.as_secs_f64(),
-                })
-            };
+            let mut json = serde_json::json!({
+                "result": r.result,
+                "elapsed": time.as_secs_f64(),
+            });
+            if let Some(decoded) = &decoded {
+                json["decoded"] = serde_json::json!(decoded);
+            }
+            if self.state {
+                json["state"] = serde_json::json!(r.state);
+            }
             println!("{}", serde_json::to_string_pretty(&json).unwrap());
         } else {
             println!("Result: {:#?}", r.result);
+            if let Some(decoded) = &decoded {
+                println!("Decoded: ({})", decoded.join(", "));
+            }
             if self.state {
                 println!("State: {:#?}", r.state);
             }
@@ -154,4 +320,105 @@ impl Cmd {
         }
         Ok(())
     }
+
+    /// Executes the transaction under every spec from `--compare-specs` and prints the gas
+    /// usage and outcome of each run.
+    fn compare_specs(&self, bytecode: Bytecode, input: Bytes) -> Result<(), Errors> {
+        let mut outcomes = Vec::with_capacity(self.compare_specs.len());
+        for &spec in &self.compare_specs {
+            let mut db = BenchmarkDB::new_bytecode(bytecode.clone());
+            let nonce = db
+                .basic(BENCH_CALLER)
+                .unwrap()
+                .map_or(0, |account| account.nonce);
+
+            let mut evm = Context::mainnet()
+                .with_db(db)
+                .modify_cfg_chained(|cfg| cfg.set_spec_and_mainnet_gas_params(spec))
+                .build_mainnet();
+
+            let tx = TxEnv::builder()
+                .caller(BENCH_CALLER)
+                .kind(TxKind::Call(BENCH_TARGET))
+                .data(input.clone())
+                .nonce(nonce)
+                .gas_limit(self.gas_limit)
+                .build()
+                .unwrap();
+
+            let outcome = match evm.transact(tx) {
+                Ok(r) => {
+                    let gas = r.result.gas();
+                    SpecOutcome {
+                        spec,
+                        outcome: match &r.result {
+                            ExecutionResult::Success { reason, .. } => {
+                                format!("success ({reason})")
+                            }
+                            ExecutionResult::Revert { .. } => "revert".to_string(),
+                            ExecutionResult::Halt { reason, .. } => format!("halt ({reason})"),
+                        },
+                        gas_used: gas.tx_gas_used(),
+                        gas_refunded: gas.final_refunded(),
+                    }
+                }
+                Err(e) => SpecOutcome {
+                    spec,
+                    outcome: format!("error ({e})"),
+                    gas_used: 0,
+                    gas_refunded: 0,
+                },
+            };
+            outcomes.push(outcome);
+        }
+
+        if self.json {
+            println!("{}", serde_json::to_string_pretty(&outcomes).unwrap());
+            return Ok(());
+        }
+
+        println!(
+            "{:<12} {:>14} {:>14} {:>10}  Outcome",
+            "Spec", "Gas used", "Refunded", "Diff"
+        );
+        let base = outcomes.first().map_or(0, |o| o.gas_used);
+        for o in &outcomes {
+            let diff = o.gas_used as i128 - base as i128;
+            println!(
+                "{:<12} {:>14} {:>14} {:>+10}  {}",
+                o.spec.to_string(),
+                o.gas_used,
+                o.gas_refunded,
+                diff,
+                o.outcome
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Executes the transaction step-locked against the EIP-3155 trace and reports the first
+/// divergence.
+fn verify_trace(path: &Path, db: BenchmarkDB, tx: TxEnv) -> Result<(), Errors> {
+    if !path.exists() {
+        return Err(Errors::PathNotExists);
+    }
+    let expected = TraceStep::read_trace(BufReader::new(fs::File::open(path)?))?;
+    let n_expected = expected.len();
+
+    let mut evm = Context::mainnet()
+        .with_db(db)
+        .build_mainnet_with_inspector(TraceVerifier::new(expected));
+    evm.inspect_tx(tx).map_err(|_| Errors::EVMError)?;
+
+    match evm.inspector.finish() {
+        Ok(steps) => {
+            println!("Execution matches the trace ({steps} of {n_expected} steps)");
+            Ok(())
+        }
+        Err(divergence) => {
+            println!("{divergence}");
+            Err(Errors::TraceDivergence)
+        }
+    }
 }
@@ -0,0 +1,199 @@
+use alloy_dyn_abi::{DynSolType, DynSolValue};
+use revm::primitives::{hex, keccak256};
+
+use super::Errors;
+
+/// Parsed function signature in the `name(inputs)(outputs)` format.
+///
+/// Output types are optional and can also be given with the `returns` keyword,
+/// e.g. `balanceOf(address) returns (uint256)`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FunctionSig {
+    /// Function name.
+    pub name: String,
+    /// Tuple of input types.
+    pub inputs: DynSolType,
+    /// Tuple of output types, if present.
+    pub outputs: Option<DynSolType>,
+}
+
+impl FunctionSig {
+    /// Parses a function signature.
+    pub fn parse(sig: &str) -> Result<Self, Errors> {
+        let sig = sig.trim();
+        let invalid = || Errors::InvalidSignature(sig.to_string());
+
+        let open = sig.find('(').ok_or_else(invalid)?;
+        let name = sig[..open].trim();
+        if name.is_empty() {
+            return Err(invalid());
+        }
+
+        let close = matching_paren(sig, open).ok_or_else(invalid)?;
+        let inputs = DynSolType::parse(&sig[open..=close])?;
+
+        let rest = sig[close + 1..].trim();
+        let rest = rest.strip_prefix("returns").unwrap_or(rest).trim();
+        let outputs = if rest.is_empty() {
+            None
+        } else {
+            if !rest.starts_with('(') || matching_paren(rest, 0) != Some(rest.len() - 1) {
+                return Err(invalid());
+            }
+            Some(DynSolType::parse(rest)?)
+        };
+
+        Ok(Self {
+            name: name.to_string(),
+            inputs,
+            outputs,
+        })
+    }
+
+    /// Returns the canonical signature used to compute the selector.
+    pub fn canonical(&self) -> String {
+        format!("{}{}", self.name, self.inputs.sol_type_name())
+    }
+
+    /// Returns the 4-byte function selector.
+    pub fn selector(&self) -> [u8; 4] {
+        keccak256(self.canonical().as_bytes())[..4]
+            .try_into()
+            .unwrap()
+    }
+
+    /// Encodes the calldata from string arguments.
+    pub fn encode_call(&self, args: &[String]) -> Result<Vec<u8>, Errors> {
+        let DynSolType::Tuple(types) = &self.inputs else {
+            unreachable!("inputs are always parsed as a tuple")
+        };
+        if types.len() != args.len() {
+            return Err(Errors::InvalidArgsCount {
+                expected: types.len(),
+                got: args.len(),
+            });
+        }
+
+        let values = types
+            .iter()
+            .zip(args)
+            .map(|(ty, arg)| ty.coerce_str(arg))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut calldata = self.selector().to_vec();
+        calldata.extend(DynSolValue::Tuple(values).abi_encode_params());
+        Ok(calldata)
+    }
+
+    /// Decodes the return data with the output types.
+    ///
+    /// Returns [`None`] if the signature has no output types.
+    pub fn decode_output(&self, data: &[u8]) -> Option<Result<Vec<String>, Errors>> {
+        let outputs = self.outputs.as_ref()?;
+        Some(
+            outputs
+                .abi_decode_params(data)
+                .map(|value| match value {
+                    DynSolValue::Tuple(values) => values.iter().map(format_value).collect(),
+                    value => vec![format_value(&value)],
+                })
+                .map_err(Into::into),
+        )
+    }
+}
+
+/// Returns the index of the parenthesis closing the one at `open`.
+fn matching_paren(s: &str, open: usize) -> Option<usize> {
+    let mut depth = 0usize;
+    for (i, c) in s[open..].char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(open + i);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Formats a decoded value in a human readable way.
+fn format_value(value: &DynSolValue) -> String {
+    if let Some(address) = value.as_address() {
+        address.to_checksum(None)
+    } else if let Some(b) = value.as_bool() {
+        b.to_string()
+    } else if let Some((uint, _)) = value.as_uint() {
+        uint.to_string()
+    } else if let Some((int, _)) = value.as_int() {
+        int.to_string()
+    } else if let Some(s) = value.as_str() {
+        format!("{s:?}")
+    } else if let Some(bytes) = value.as_bytes() {
+        format!("0x{}", hex::encode(bytes))
+    } else if let Some((word, size)) = value.as_fixed_bytes() {
+        format!("0x{}", hex::encode(&word[..size]))
+    } else if let Some(values) = value.as_tuple() {
+        let values: Vec<_> = values.iter().map(format_value).collect();
+        format!("({})", values.join(", "))
+    } else if let Some(values) = value.as_array().or_else(|| value.as_fixed_array()) {
+        let values: Vec<_> = values.iter().map(format_value).collect();
+        format!("[{}]", values.join(", "))
+    } else {
+        format!("{value:?}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_signature() {
+        let sig = FunctionSig::parse("balanceOf(address)(uint256)").unwrap();
+        assert_eq!(sig.name, "balanceOf");
+        assert_eq!(sig.canonical(), "balanceOf(address)");
+        assert_eq!(sig.selector(), hex!("70a08231"));
+        assert!(sig.outputs.is_some());
+
+        let sig = FunctionSig::parse("transfer(address to, uint amount) returns (bool)").unwrap();
+        assert_eq!(sig.canonical(), "transfer(address,uint256)");
+        assert_eq!(sig.selector(), hex!("a9059cbb"));
+
+        assert!(FunctionSig::parse("(address)").is_err());
+        assert!(FunctionSig::parse("transfer(address").is_err());
+        assert!(FunctionSig::parse("transfer(address)uint256").is_err());
+    }
+
+    #[test]
+    fn encode_and_decode() {
+        let sig = FunctionSig::parse("transfer(address,uint256)(bool)").unwrap();
+        let calldata = sig
+            .encode_call(&[
+                "0x0000000000000000000000000000000000000001".to_string(),
+                "1000".to_string(),
+            ])
+            .unwrap();
+        assert_eq!(
+            calldata,
+            hex!("a9059cbb000000000000000000000000000000000000000000000000000000000000000100000000000000000000000000000000000000000000000000000000000003e8")
+        );
+
+        assert!(matches!(
+            sig.encode_call(&["1".to_string()]),
+            Err(Errors::InvalidArgsCount {
+                expected: 2,
+                got: 1
+            })
+        ));
+
+        let output = [0u8; 31].into_iter().chain([1]).collect::<Vec<_>>();
+        assert_eq!(
+            sig.decode_output(&output).unwrap().unwrap(),
+            vec!["true".to_string()]
+        );
+    }
+}
@@ -0,0 +1,102 @@
+use alloy_provider::{network::Ethereum, DynProvider, Provider, ProviderBuilder};
+use revm::{
+    context::{BlockEnv, CfgEnv},
+    database::{AlloyDB, BlockId, CacheDB, State},
+    database_interface::WrapDatabaseAsync,
+    primitives::{
+        hardfork::{HardforkSchedule, SpecId},
+        Address, U256,
+    },
+    state::{AccountInfo, Bytecode},
+    Database,
+};
+use tokio::runtime::Runtime;
+
+use super::Errors;
+
+/// Database of the remote chain, cached in memory.
+pub type ForkDB = State<CacheDB<WrapDatabaseAsync<AlloyDB<Ethereum, DynProvider>>>>;
+
+/// State of a remote chain at a block, fetched over RPC.
+pub struct Fork {
+    /// State at the end of the block.
+    pub db: ForkDB,
+    /// Environment of the block.
+    pub block: BlockEnv,
+    /// Spec of the block on mainnet.
+    pub spec: SpecId,
+}
+
+impl Fork {
+    /// Connects to the RPC endpoint and forks the state at the end of the block, or of the
+    /// latest block if `block` is `None`.
+    ///
+    /// Spec is selected from the number and timestamp of the block with the mainnet hardfork
+    /// schedule.
+    pub fn connect(url: &str, block: Option<u64>) -> Result<Self, Errors> {
+        let runtime = Runtime::new()?;
+        let provider = runtime
+            .block_on(ProviderBuilder::new().connect(url))
+            .map_err(|e| Errors::Fork(e.to_string()))?
+            .erased();
+        let block_id = block.map_or_else(BlockId::latest, BlockId::number);
+        let header = runtime
+            .block_on(provider.get_block(block_id))
+            .map_err(|e| Errors::Fork(e.to_string()))?
+            .ok_or_else(|| Errors::Fork(format!("block {block_id} not found")))?
+            .header;
+
+        let spec = HardforkSchedule::mainnet().spec_at(header.number, header.timestamp);
+        let block = BlockEnv::from_header(&header, CfgEnv::new_with_spec(spec));
+        // Pin the state to the fetched block so a moving `latest` does not mix blocks.
+        let alloy_db = AlloyDB::new(provider, BlockId::number(header.number));
+        let db = State::builder()
+            .with_database(CacheDB::new(WrapDatabaseAsync::with_runtime(
+                alloy_db, runtime,
+            )))
+            .build();
+
+        Ok(Self { db, block, spec })
+    }
+
+    /// Replaces the code of the account, keeping its balance, nonce and storage.
+    pub fn set_code(&mut self, address: Address, code: Bytecode) -> Result<(), Errors> {
+        let info = self.basic(address)?;
+        self.db
+            .database
+            .insert_account_info(address, info.with_code(code));
+        Ok(())
+    }
+
+    /// Adds `amount` to the balance of the account.
+    pub fn fund(&mut self, address: Address, amount: U256) -> Result<(), Errors> {
+        let mut info = self.basic(address)?;
+        info.balance = info.balance.saturating_add(amount);
+        self.db.database.insert_account_info(address, info);
+        Ok(())
+    }
+
+    /// Returns the nonce of the account.
+    pub fn nonce(&mut self, address: Address) -> Result<u64, Errors> {
+        Ok(self.basic(address)?.nonce)
+    }
+
+    /// Loads the account from the remote chain, the default account if it does not exist.
+    fn basic(&mut self, address: Address) -> Result<AccountInfo, Errors> {
+        let mut info = self
+            .db
+            .database
+            .basic(address)
+            .map_err(|e| Errors::Fork(e.to_string()))?
+            .unwrap_or_default();
+        if info.code.is_none() && !info.is_empty_code_hash() {
+            let code = self
+                .db
+                .database
+                .code_by_hash(info.code_hash)
+                .map_err(|e| Errors::Fork(e.to_string()))?;
+            info.code = Some(code);
+        }
+        Ok(info)
+    }
+}
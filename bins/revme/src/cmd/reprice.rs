@@ -0,0 +1,188 @@
+use clap::Parser;
+use revm::{
+    bytecode::Bytecode,
+    context::{BlockEnv, TxEnv},
+    context_interface::cfg::GasId,
+    database::{CacheState, State},
+    primitives::{
+        hardfork::{ForkCondition, HardforkSchedule},
+        keccak256, AddressMap, B256,
+    },
+    replay::{ReplayBlock, ReplayError},
+    reprice::{reprice_range, RepricedResult},
+    state,
+    statetest_types::{AccountInfo, SpecName},
+};
+use serde::Deserialize;
+use std::{collections::BTreeMap, fs, io, path::PathBuf};
+
+/// Error of the `reprice` command.
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+    #[error("input has no blocks")]
+    NoBlocks,
+    #[error("block {0} is missing from the input")]
+    MissingBlock(u64),
+    #[error("{0}")]
+    Replay(String),
+}
+
+/// Blocks replayed by the `reprice` command.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RepriceInput {
+    /// Fork all blocks are executed with, picked from the mainnet schedule if not set.
+    #[serde(default)]
+    pub fork: Option<SpecName>,
+    /// State before the first block, in the state test format.
+    pub pre: AddressMap<AccountInfo>,
+    /// Consecutive blocks to replay.
+    pub blocks: Vec<InputBlock>,
+}
+
+/// Block of the [`RepriceInput`].
+#[derive(Debug, Deserialize)]
+pub struct InputBlock {
+    /// Environment of the block.
+    pub block: BlockEnv,
+    /// Hash of the block.
+    #[serde(default)]
+    pub hash: B256,
+    /// Transactions of the block in execution order.
+    pub transactions: Vec<TxEnv>,
+}
+
+/// `reprice` subcommand
+///
+/// Replays blocks with the mainnet gas params and with overridden gas params, and reports the
+/// change of the gas used by every block and the transactions that fail only when repriced.
+#[derive(Parser, Debug)]
+pub struct Cmd {
+    /// JSON file with the prestate and the blocks to replay
+    input: PathBuf,
+    /// Overridden gas parameter, e.g. `--gas cold_storage_cost=4200`, can be repeated
+    #[arg(long = "gas", value_name = "NAME=VALUE", value_parser = parse_gas_override)]
+    overrides: Vec<(GasId, u64)>,
+}
+
+fn parse_gas_override(s: &str) -> Result<(GasId, u64), String> {
+    let (name, value) = s
+        .split_once('=')
+        .ok_or_else(|| format!("expected NAME=VALUE, got `{s}`"))?;
+    let id = GasId::from_name(name.trim()).ok_or_else(|| format!("unknown gas id `{name}`"))?;
+    let value = value
+        .trim()
+        .parse()
+        .map_err(|e| format!("invalid value `{value}`: {e}"))?;
+    Ok((id, value))
+}
+
+impl Cmd {
+    /// Runs `reprice` command.
+    pub fn run(&self) -> Result<(), Error> {
+        let input: RepriceInput = serde_json::from_str(&fs::read_to_string(&self.input)?)?;
+        let mut blocks: BTreeMap<u64, ReplayBlock> = input
+            .blocks
+            .into_iter()
+            .map(|block| {
+                let number = block.block.number.saturating_to();
+                let block = ReplayBlock {
+                    block: block.block,
+                    hash: block.hash,
+                    transactions: block.transactions,
+                };
+                (number, block)
+            })
+            .collect();
+        let (Some(&start), Some(&end)) = (blocks.keys().next(), blocks.keys().next_back()) else {
+            return Err(Error::NoBlocks);
+        };
+
+        let new_state = || {
+            State::builder()
+                .with_cached_prestate(prestate(&input.pre))
+                .build()
+        };
+        let (mut baseline, mut repriced) = (new_state(), new_state());
+        let source = |number| blocks.remove(&number).ok_or(Error::MissingBlock(number));
+        let mut repricing = reprice_range(
+            &mut baseline,
+            &mut repriced,
+            source,
+            start,
+            end,
+            |_, params| params.override_gas(self.overrides.iter().copied()),
+        );
+        if let Some(fork) = input.fork {
+            repricing = repricing.with_schedule(HardforkSchedule::new([(
+                fork.to_spec_id(),
+                ForkCondition::Block(0),
+            )]));
+        }
+
+        let (mut baseline_gas_used, mut repriced_gas_used, mut newly_failing) = (0, 0, 0);
+        for outcome in repricing {
+            let outcome = outcome.map_err(|e| match e {
+                ReplayError::Source { error, .. } => error,
+                e => Error::Replay(e.to_string()),
+            })?;
+            println!("{outcome}");
+            for failing in &outcome.newly_failing {
+                let repriced = match &failing.repriced {
+                    RepricedResult::Executed(result) => format!("{result:?}"),
+                    RepricedResult::Invalid(error) => format!("invalid: {error}"),
+                };
+                println!("  tx {}: {repriced}", failing.index);
+            }
+            baseline_gas_used += outcome.baseline_gas_used;
+            repriced_gas_used += outcome.repriced_gas_used;
+            newly_failing += outcome.newly_failing.len();
+        }
+        println!(
+            "\nTotal: {baseline_gas_used} -> {repriced_gas_used} gas ({:+}), {newly_failing} newly failing transactions",
+            repriced_gas_used as i128 - baseline_gas_used as i128
+        );
+        Ok(())
+    }
+}
+
+/// Returns the cached state of the accounts.
+fn prestate(pre: &AddressMap<AccountInfo>) -> CacheState {
+    let mut cache_state = CacheState::new();
+    for (address, info) in pre {
+        let code_hash = keccak256(&info.code);
+        if !info.code.is_empty() {
+            let bytecode = Bytecode::new_raw_checked(info.code.clone())
+                .unwrap_or(Bytecode::new_legacy(info.code.clone()));
+            cache_state.contracts.insert(code_hash, bytecode);
+        }
+        let account = state::AccountInfo {
+            balance: info.balance,
+            nonce: info.nonce,
+            code_hash,
+            ..Default::default()
+        };
+        cache_state.insert_account_with_storage(*address, account, info.storage.clone());
+    }
+    cache_state
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_gas_overrides() {
+        assert_eq!(
+            parse_gas_override("cold_storage_cost=4200"),
+            Ok((GasId::cold_storage_cost(), 4200))
+        );
+        assert!(parse_gas_override("cold_storage_cost").is_err());
+        assert!(parse_gas_override("unknown=1").is_err());
+        assert!(parse_gas_override("cold_storage_cost=x").is_err());
+    }
+}
@@ -1,16 +1,25 @@
+mod expect_fail;
+pub mod fetch;
+pub mod fixture;
+mod gas_csv;
 pub mod merkle_trie;
 mod runner;
+mod timing;
 pub mod utils;
 
 pub use runner::{TestError as Error, TestErrorKind};
 
 use crate::dir_utils::find_all_json_tests;
-use clap::Parser;
-use runner::{run, TestError};
-use std::path::PathBuf;
+use clap::{Parser, Subcommand};
+use expect_fail::ExpectFail;
+use gas_csv::GasCsv;
+use runner::{run, RunOptions, TestError};
+use std::{path::PathBuf, sync::Arc};
+use timing::Timing;
 
 /// `statetest` subcommand
 #[derive(Parser, Debug)]
+#[command(subcommand_negates_reqs = true)]
 pub struct Cmd {
     /// Path to folder or file containing the tests
     ///
@@ -40,12 +49,80 @@ pub struct Cmd {
     /// Keep going after a test failure
     #[arg(long, alias = "no-fail-fast")]
     keep_going: bool,
+    /// Write the gas limit, gas used, refund, effective gas price, status and first reverted
+    /// frame of every executed transaction to a CSV file
+    #[arg(long, value_name = "PATH")]
+    gas_csv: Option<PathBuf>,
+    /// TOML file listing the tests that are expected to fail, with the reason
+    ///
+    /// Expected failures are not reported as errors, tests that pass although they are listed
+    /// fail the run.
+    ///
+    /// ```toml
+    /// [failures]
+    /// "test_name" = "EIP-XXXX is not implemented yet"
+    /// ```
+    #[arg(long, value_name = "PATH")]
+    expect_fail: Option<PathBuf>,
+    /// Record the execution time of every test and print the N slowest tests and the
+    /// throughput in gas per second
+    #[arg(long, value_name = "N", num_args = 0..=1, default_missing_value = "10")]
+    timing: Option<usize>,
+    #[command(subcommand)]
+    command: Option<StatetestCmd>,
+}
+
+/// Subcommands of `statetest`.
+#[derive(Subcommand, Debug)]
+pub enum StatetestCmd {
+    /// Download a release of the execution spec tests fixtures into the cache directory.
+    Fetch(fetch::Cmd),
 }
 
 impl Cmd {
     /// Runs `statetest` command.
     pub fn run(&self) -> Result<(), TestError> {
-        for path in &self.paths {
+        if let Some(StatetestCmd::Fetch(fetch)) = &self.command {
+            let path = fetch.fetch().map_err(|e| TestError {
+                name: "Fetch fixtures".to_string(),
+                path: String::new(),
+                kind: e.into(),
+            })?;
+            if fetch.run_tests() {
+                return self.run_paths(&[path]);
+            }
+            println!("State tests are in {}", path.display());
+            return Ok(());
+        }
+        self.run_paths(&self.paths)
+    }
+
+    /// Runs the tests found in the paths.
+    fn run_paths(&self, paths: &[PathBuf]) -> Result<(), TestError> {
+        let gas_csv = match &self.gas_csv {
+            Some(path) => Some(Arc::new(GasCsv::create(path).map_err(|e| TestError {
+                name: "Gas CSV".to_string(),
+                path: path.display().to_string(),
+                kind: TestErrorKind::GasCsv(e),
+            })?)),
+            None => None,
+        };
+        let expect_fail = match &self.expect_fail {
+            Some(path) => Some(Arc::new(ExpectFail::load(path).map_err(|e| TestError {
+                name: "Expected failures".to_string(),
+                path: path.display().to_string(),
+                kind: e.into(),
+            })?)),
+            None => None,
+        };
+        let options = RunOptions {
+            gas_csv,
+            expect_fail,
+            timing: self.timing.map(|slowest| Arc::new(Timing::new(slowest))),
+            code_cache: Arc::default(),
+        };
+
+        for path in paths {
             if !path.exists() {
                 return Err(TestError {
                     name: "Path validation".to_string(),
@@ -72,6 +149,7 @@ impl Cmd {
                 self.json_outcome,
                 self.keep_going,
                 self.omit_progress,
+                options.clone(),
             )?
         }
         Ok(())
@@ -0,0 +1,129 @@
+use super::runner::TestError;
+use serde::Deserialize;
+use std::{collections::BTreeMap, io, mem, path::Path, sync::Mutex};
+use thiserror::Error;
+
+/// Error of loading the list of expected failures.
+#[derive(Debug, Error)]
+pub enum ExpectFailError {
+    #[error("failed to read expected failures: {0}")]
+    Io(#[from] io::Error),
+    #[error("failed to parse expected failures: {0}")]
+    Toml(#[from] toml::de::Error),
+}
+
+/// Contents of the expected failures file.
+///
+/// ```toml
+/// [failures]
+/// "test_name" = "reason, e.g. EIP-XXXX is not implemented yet"
+/// ```
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ExpectFailFile {
+    /// Reasons of the expected failures keyed by test name.
+    #[serde(default)]
+    failures: BTreeMap<String, String>,
+}
+
+/// Outcome of the tests that were expected to fail.
+#[derive(Debug, Default)]
+pub struct ExpectFailOutcome {
+    /// Tests that failed as expected, with the reason they are expected to fail.
+    pub expected_failures: Vec<(String, String)>,
+    /// Tests that were expected to fail but passed.
+    pub unexpected_passes: Vec<(String, String)>,
+}
+
+/// Tests that are expected to fail, shared by the runner threads.
+///
+/// Expected failures are not reported as errors, so forks tracking in-progress EIPs can keep
+/// CI green. Tests that pass although they are listed are collected so the stale entries are
+/// noticed and removed.
+#[derive(Debug, Default)]
+pub struct ExpectFail {
+    failures: BTreeMap<String, String>,
+    outcome: Mutex<ExpectFailOutcome>,
+}
+
+impl ExpectFail {
+    /// Loads the expected failures from a TOML file.
+    pub fn load(path: &Path) -> Result<Self, ExpectFailError> {
+        Self::parse(&std::fs::read_to_string(path)?)
+    }
+
+    /// Parses the expected failures from TOML.
+    pub fn parse(s: &str) -> Result<Self, ExpectFailError> {
+        let file: ExpectFailFile = toml::from_str(s)?;
+        Ok(Self {
+            failures: file.failures,
+            outcome: Mutex::default(),
+        })
+    }
+
+    /// Returns `true` if the test is expected to fail.
+    pub fn is_expected(&self, name: &str) -> bool {
+        self.failures.contains_key(name)
+    }
+
+    /// Records the result of a test that is expected to fail.
+    pub fn record(&self, name: &str, result: Result<(), TestError>) {
+        let reason = self.failures.get(name).cloned().unwrap_or_default();
+        let mut outcome = self.outcome.lock().unwrap();
+        match result {
+            Ok(()) => outcome.unexpected_passes.push((name.to_string(), reason)),
+            Err(_) => outcome.expected_failures.push((name.to_string(), reason)),
+        }
+    }
+
+    /// Takes the outcome recorded since the last call.
+    pub fn take_outcome(&self) -> ExpectFailOutcome {
+        mem::take(&mut *self.outcome.lock().unwrap())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cmd::statetest::TestErrorKind;
+
+    #[test]
+    fn records_expected_failures_and_unexpected_passes() {
+        let expect_fail = ExpectFail::parse(
+            r#"
+            [failures]
+            "test_a" = "EIP-1 not implemented"
+            "test_b" = "EIP-2 not implemented"
+            "#,
+        )
+        .unwrap();
+        assert!(expect_fail.is_expected("test_a"));
+        assert!(!expect_fail.is_expected("test_c"));
+
+        expect_fail.record(
+            "test_a",
+            Err(TestError {
+                name: "test_a".to_string(),
+                path: String::new(),
+                kind: TestErrorKind::Panic,
+            }),
+        );
+        expect_fail.record("test_b", Ok(()));
+
+        let outcome = expect_fail.take_outcome();
+        assert_eq!(
+            outcome.expected_failures,
+            [("test_a".to_string(), "EIP-1 not implemented".to_string())]
+        );
+        assert_eq!(
+            outcome.unexpected_passes,
+            [("test_b".to_string(), "EIP-2 not implemented".to_string())]
+        );
+        assert!(expect_fail.take_outcome().unexpected_passes.is_empty());
+    }
+
+    #[test]
+    fn rejects_unknown_fields() {
+        assert!(ExpectFail::parse("[failure]\n").is_err());
+    }
+}
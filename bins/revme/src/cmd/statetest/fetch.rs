@@ -0,0 +1,207 @@
+use clap::Parser;
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    process::Command,
+};
+use thiserror::Error;
+
+/// Release of the execution spec tests the runner is tested against, same as in
+/// `scripts/run-tests.sh`.
+pub const DEFAULT_RELEASE: &str = "v5.4.0";
+
+/// Base URL of the execution spec tests releases.
+const FIXTURES_URL: &str = "https://github.com/ethereum/execution-spec-tests/releases/download";
+
+/// Error of fetching a fixture release.
+#[derive(Debug, Error)]
+pub enum FetchError {
+    #[error("failed to prepare fixtures directory: {0}")]
+    Io(#[from] io::Error),
+    #[error("failed to run `{program}`: {source}")]
+    Spawn {
+        program: &'static str,
+        source: io::Error,
+    },
+    #[error("failed to download {url}")]
+    Download { url: String },
+    #[error("failed to extract {archive}")]
+    Extract { archive: String },
+    #[error("release {0} does not contain state tests")]
+    NoStateTests(String),
+}
+
+/// `statetest fetch` subcommand
+///
+/// Downloads a release of the execution spec tests fixtures with `curl` and unpacks it with `tar`
+/// into the cache directory. Releases that are already cached are not downloaded again.
+#[derive(Parser, Debug)]
+pub struct Cmd {
+    /// Tag of the execution spec tests release
+    #[arg(long, default_value = DEFAULT_RELEASE)]
+    release: String,
+    /// Fetch the stable fixtures instead of the develop fixtures
+    ///
+    /// Stable fixtures only contain the forks that are live on mainnet.
+    #[arg(long)]
+    stable: bool,
+    /// Directory the releases are cached in
+    ///
+    /// Defaults to `$XDG_CACHE_HOME/revme/fixtures` or `$HOME/.cache/revme/fixtures`.
+    #[arg(long, value_name = "PATH")]
+    cache_dir: Option<PathBuf>,
+    /// Download the release again even if it is cached
+    #[arg(long)]
+    force: bool,
+    /// Run the state tests of the release after fetching it
+    ///
+    /// Options of `statetest` given before `fetch` apply to the run.
+    #[arg(long)]
+    run: bool,
+}
+
+impl Cmd {
+    /// Returns `true` if the state tests should be run after fetching.
+    pub const fn run_tests(&self) -> bool {
+        self.run
+    }
+
+    /// Fetches the release if it is not cached and returns the directory of its state tests.
+    pub fn fetch(&self) -> Result<PathBuf, FetchError> {
+        let cache_dir = match &self.cache_dir {
+            Some(dir) => dir.clone(),
+            None => default_cache_dir()?,
+        };
+        let dir = release_dir(&cache_dir, &self.release, self.stable);
+
+        if self.force && dir.exists() {
+            fs::remove_dir_all(&dir)?;
+        }
+        if dir.exists() {
+            println!("Using cached fixtures in {}", dir.display());
+        } else {
+            self.download(&dir)?;
+        }
+
+        let state_tests = dir.join("state_tests");
+        if !state_tests.is_dir() {
+            return Err(FetchError::NoStateTests(self.release.clone()));
+        }
+        Ok(state_tests)
+    }
+
+    /// Downloads the release and unpacks it into `dir`.
+    ///
+    /// Release is unpacked next to `dir` and moved into place once it is complete, an interrupted
+    /// fetch does not leave a partial release in the cache.
+    fn download(&self, dir: &Path) -> Result<(), FetchError> {
+        let mut partial = dir.as_os_str().to_owned();
+        partial.push(".partial");
+        let partial = PathBuf::from(partial);
+        if partial.exists() {
+            fs::remove_dir_all(&partial)?;
+        }
+        fs::create_dir_all(&partial)?;
+
+        let archive_name = archive_name(self.stable);
+        let archive = partial.join(archive_name);
+        let url = archive_url(&self.release, self.stable);
+        println!("Downloading {url}...");
+        let downloaded = Command::new("curl")
+            .args(["-fL", "--retry", "3", "--retry-delay", "2", "-o"])
+            .arg(&archive)
+            .arg(&url)
+            .status()
+            .map_err(|source| FetchError::Spawn {
+                program: "curl",
+                source,
+            })?;
+        if !downloaded.success() {
+            return Err(FetchError::Download { url });
+        }
+
+        println!("Extracting {archive_name}...");
+        // Archive contains a single top level `fixtures` directory.
+        let extracted = Command::new("tar")
+            .arg("-xzf")
+            .arg(&archive)
+            .args(["--strip-components=1", "-C"])
+            .arg(&partial)
+            .status()
+            .map_err(|source| FetchError::Spawn {
+                program: "tar",
+                source,
+            })?;
+        if !extracted.success() {
+            return Err(FetchError::Extract {
+                archive: archive.display().to_string(),
+            });
+        }
+        fs::remove_file(&archive)?;
+        fs::rename(&partial, dir)?;
+
+        println!("Fixtures cached in {}", dir.display());
+        Ok(())
+    }
+}
+
+/// Returns the default directory the releases are cached in.
+fn default_cache_dir() -> Result<PathBuf, FetchError> {
+    let cache = match std::env::var_os("XDG_CACHE_HOME") {
+        Some(dir) => PathBuf::from(dir),
+        None => {
+            let home = std::env::var_os("HOME").ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::NotFound,
+                    "neither XDG_CACHE_HOME nor HOME is set, use --cache-dir",
+                )
+            })?;
+            PathBuf::from(home).join(".cache")
+        }
+    };
+    Ok(cache.join("revme").join("fixtures"))
+}
+
+/// Returns the name of the fixtures archive in the release.
+const fn archive_name(stable: bool) -> &'static str {
+    if stable {
+        "fixtures_stable.tar.gz"
+    } else {
+        "fixtures_develop.tar.gz"
+    }
+}
+
+/// Returns the URL of the fixtures archive of the release.
+fn archive_url(release: &str, stable: bool) -> String {
+    format!("{FIXTURES_URL}/{release}/{}", archive_name(stable))
+}
+
+/// Returns the directory the release is cached in.
+///
+/// Path separators in the tag are replaced so every release is a single directory of the cache.
+fn release_dir(cache_dir: &Path, release: &str, stable: bool) -> PathBuf {
+    let release = release.replace(['/', '\\'], "_");
+    let flavor = if stable { "stable" } else { "develop" };
+    cache_dir.join(format!("{release}-{flavor}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn release_paths() {
+        assert_eq!(
+            archive_url("v5.4.0", true),
+            "https://github.com/ethereum/execution-spec-tests/releases/download/v5.4.0/fixtures_stable.tar.gz"
+        );
+        assert_eq!(
+            release_dir(Path::new("cache"), "v5.4.0", false),
+            Path::new("cache/v5.4.0-develop")
+        );
+        assert_eq!(
+            release_dir(Path::new("cache"), "../v1", true),
+            Path::new("cache/.._v1-stable")
+        );
+    }
+}
@@ -0,0 +1,249 @@
+//! Generation of state test fixtures from arbitrary executions.
+//!
+//! Bugs found while replaying production transactions can be minimized to a prestate, an
+//! environment and a transaction, and turned into a standard `GeneralStateTest` fixture that is
+//! shared with other clients.
+
+use super::{
+    merkle_trie::{compute_test_roots, log_rlp_hash},
+    runner::cfg_env,
+};
+use clap::Parser;
+use revm::{
+    database::{self, EmptyDB},
+    primitives::{AddressMap, B256},
+    statetest_types::{
+        AccountInfo, Env, SpecName, Test, TestError, TestSuite, TestUnit, TransactionParts,
+        TxPartIndices,
+    },
+    Context, ExecuteCommitEvm, MainBuilder, MainContext,
+};
+use serde::Deserialize;
+use serde_json::json;
+use std::{collections::BTreeMap, fs, io, path::PathBuf};
+
+/// Error of the fixture generation.
+#[derive(Debug, thiserror::Error)]
+pub enum FixtureError {
+    #[error("fork {0:?} can't be used to generate a fixture")]
+    UnsupportedFork(SpecName),
+    #[error("transaction has no {0}")]
+    MissingTransactionPart(&'static str),
+    #[error("invalid transaction: {0}")]
+    Transaction(#[from] TestError),
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+}
+
+/// Execution a fixture is generated from.
+///
+/// Uses the format of the state tests, the transaction has the usual `data`, `gasLimit` and
+/// `value` lists and is executed with every combination of them. The transaction needs a
+/// `secretKey` for the fixture to be portable to other clients.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FixtureInput {
+    /// Name of the test in the fixture.
+    #[serde(default = "default_name")]
+    pub name: String,
+    /// Fork the transaction is executed with.
+    pub fork: SpecName,
+    /// Environment of the block.
+    pub env: Env,
+    /// State before the transaction.
+    pub pre: AddressMap<AccountInfo>,
+    /// Transaction to execute.
+    pub transaction: TransactionParts,
+}
+
+fn default_name() -> String {
+    "generated".to_string()
+}
+
+/// Executes the transaction and returns a fixture that expects the resulting post state and
+/// logs.
+///
+/// Transactions that are invalid are expected to fail with the error of the execution.
+pub fn generate_fixture(input: FixtureInput) -> Result<TestSuite, FixtureError> {
+    if matches!(
+        input.fork,
+        SpecName::Unknown | SpecName::Constantinople | SpecName::ByzantiumToConstantinopleAt5
+    ) {
+        return Err(FixtureError::UnsupportedFork(input.fork));
+    }
+    let transaction = &input.transaction;
+    for (part, len) in [
+        ("data", transaction.data.len()),
+        ("gas limit", transaction.gas_limit.len()),
+        ("value", transaction.value.len()),
+    ] {
+        if len == 0 {
+            return Err(FixtureError::MissingTransactionPart(part));
+        }
+    }
+
+    let mut unit = TestUnit {
+        info: Some(json!({ "comment": "Generated by revme" })),
+        env: input.env,
+        pre: input.pre,
+        post: BTreeMap::new(),
+        transaction: input.transaction,
+        out: None,
+    };
+    let cache_state = unit.state();
+    let mut cfg = cfg_env(&unit, input.fork.to_spec_id());
+    let block = unit.block_env(&mut cfg);
+
+    let mut tests = Vec::new();
+    for data in 0..unit.transaction.data.len() {
+        for gas in 0..unit.transaction.gas_limit.len() {
+            for value in 0..unit.transaction.value.len() {
+                let indexes = TxPartIndices { data, gas, value };
+                let mut test = Test::new(indexes, B256::ZERO, log_rlp_hash(&[]));
+                let tx = test.tx_env(&unit)?;
+
+                let mut state = database::State::builder()
+                    .with_cached_prestate(cache_state.clone())
+                    .with_bundle_update()
+                    .build();
+                let exec_result = Context::mainnet()
+                    .with_block(&block)
+                    .with_tx(&tx)
+                    .with_cfg(cfg.clone())
+                    .with_db(&mut state)
+                    .build_mainnet()
+                    .transact_commit(&tx);
+
+                let roots = compute_test_roots(&exec_result, &state);
+                test.hash = roots.state_root;
+                test.logs = roots.logs_root;
+                test.post_state = post_state(&state);
+                test.expect_exception = exec_result.err().map(|e| e.to_string());
+                tests.push(test);
+            }
+        }
+    }
+    unit.post.insert(input.fork, tests);
+
+    Ok(TestSuite(BTreeMap::from([(input.name, unit)])))
+}
+
+/// Returns the accounts of the state in the state test format.
+fn post_state(state: &database::State<EmptyDB>) -> AddressMap<AccountInfo> {
+    state
+        .cache
+        .trie_account()
+        .into_iter()
+        .map(|(address, account)| {
+            let code = state
+                .cache
+                .contracts
+                .get(&account.info.code_hash)
+                .map(|code| code.original_bytes())
+                .unwrap_or_default();
+            let storage = account
+                .storage
+                .iter()
+                .filter(|(_, value)| !value.is_zero())
+                .map(|(key, value)| (*key, *value))
+                .collect();
+            let info = AccountInfo {
+                balance: account.info.balance,
+                code,
+                nonce: account.info.nonce,
+                storage,
+            };
+            (address, info)
+        })
+        .collect()
+}
+
+/// `fixture` subcommand
+///
+/// Executes a transaction on top of a prestate and writes a state test fixture that expects
+/// the resulting post state, e.g. to share a bug found while replaying a production
+/// transaction.
+#[derive(Parser, Debug)]
+pub struct Cmd {
+    /// JSON file with the `fork`, `env`, `pre` and `transaction` of the execution, in the state
+    /// test format
+    input: PathBuf,
+    /// Path the fixture is written to, printed to stdout if not set
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+}
+
+impl Cmd {
+    /// Runs `fixture` command.
+    pub fn run(&self) -> Result<(), FixtureError> {
+        let input: FixtureInput = serde_json::from_str(&fs::read_to_string(&self.input)?)?;
+        let fixture = serde_json::to_string_pretty(&generate_fixture(input)?)?;
+        match &self.output {
+            Some(path) => fs::write(path, fixture)?,
+            None => println!("{fixture}"),
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use revm::primitives::{address, U256};
+
+    #[test]
+    fn generated_fixture_round_trips() {
+        let input: FixtureInput = serde_json::from_str(
+            r#"{
+                "name": "transfer",
+                "fork": "Prague",
+                "env": {
+                    "currentCoinbase": "0x2adc25665018aa1fe0e6bc666dac8fc2697ff9ba",
+                    "currentGasLimit": "0x0f4240",
+                    "currentNumber": "0x01",
+                    "currentTimestamp": "0x03e8",
+                    "currentBaseFee": "0x0a",
+                    "currentRandom": "0x0000000000000000000000000000000000000000000000000000000000020000",
+                    "currentExcessBlobGas": "0x00"
+                },
+                "pre": {
+                    "0xa94f5374fce5edbc8e2a8697c15331677e6ebf0b": {
+                        "balance": "0x3b9aca00",
+                        "code": "0x",
+                        "nonce": "0x00",
+                        "storage": {}
+                    }
+                },
+                "transaction": {
+                    "data": ["0x"],
+                    "gasLimit": ["0x5208", "0x01"],
+                    "gasPrice": "0x0a",
+                    "nonce": "0x00",
+                    "secretKey": "0x45a915e4d060149eb4365960e6a7a45f334393093061116b197e3240065ff2d8",
+                    "to": "0x1000000000000000000000000000000000000000",
+                    "value": ["0x01"]
+                }
+            }"#,
+        )
+        .unwrap();
+
+        let fixture = generate_fixture(input).unwrap();
+        let tests = &fixture.0["transfer"].post[&SpecName::Prague];
+        assert_eq!(tests.len(), 2);
+
+        let transfer = &tests[0];
+        assert!(transfer.expect_exception.is_none());
+        let receiver =
+            &transfer.post_state[&address!("0x1000000000000000000000000000000000000000")];
+        assert_eq!(receiver.balance, U256::from(1));
+
+        // Gas limit below the intrinsic gas.
+        assert!(tests[1].expect_exception.is_some());
+
+        let json = serde_json::to_string(&fixture).unwrap();
+        let decoded: TestSuite = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded, fixture);
+    }
+}
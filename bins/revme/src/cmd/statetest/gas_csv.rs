@@ -0,0 +1,161 @@
+use revm::{
+    context_interface::result::{ExecutionResult, HaltReason},
+    interpreter::{CallInputs, CallOutcome, CreateInputs, CreateOutcome, InterpreterTypes},
+    primitives::{hardfork::SpecId, Address},
+    Inspector,
+};
+use std::{fs::File, io, path::Path, sync::Mutex};
+
+/// Header of the gas CSV.
+const HEADER: [&str; 11] = [
+    "path",
+    "test",
+    "fork",
+    "index",
+    "gas_limit",
+    "gas_used",
+    "refund",
+    "effective_gas_price",
+    "status",
+    "first_revert_depth",
+    "first_revert_address",
+];
+
+/// Frame that reverted first.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RevertFrame {
+    /// Depth of the frame, the transaction frame has depth zero.
+    pub depth: usize,
+    /// Called address, `None` for creates.
+    pub address: Option<Address>,
+}
+
+/// Inspector that records the first frame that reverted.
+///
+/// Frames are reported when they return, so the first reverted frame is the innermost frame
+/// where the revert originated.
+#[derive(Debug, Default)]
+pub struct RevertFrameInspector {
+    depth: usize,
+    first_revert: Option<RevertFrame>,
+}
+
+impl RevertFrameInspector {
+    /// Returns the first frame that reverted.
+    pub fn first_revert(&self) -> Option<RevertFrame> {
+        self.first_revert
+    }
+
+    fn frame_end(&mut self, address: Option<Address>, reverted: bool) {
+        self.depth = self.depth.saturating_sub(1);
+        if reverted && self.first_revert.is_none() {
+            self.first_revert = Some(RevertFrame {
+                depth: self.depth,
+                address,
+            });
+        }
+    }
+}
+
+impl<CTX, INTR: InterpreterTypes> Inspector<CTX, INTR> for RevertFrameInspector {
+    fn call(&mut self, _context: &mut CTX, _inputs: &mut CallInputs) -> Option<CallOutcome> {
+        self.depth += 1;
+        None
+    }
+
+    fn call_end(&mut self, _context: &mut CTX, inputs: &CallInputs, outcome: &mut CallOutcome) {
+        let reverted = outcome.result.result.is_revert();
+        self.frame_end(Some(inputs.target_address), reverted);
+    }
+
+    fn create(&mut self, _context: &mut CTX, _inputs: &mut CreateInputs) -> Option<CreateOutcome> {
+        self.depth += 1;
+        None
+    }
+
+    fn create_end(
+        &mut self,
+        _context: &mut CTX,
+        _inputs: &CreateInputs,
+        outcome: &mut CreateOutcome,
+    ) {
+        let reverted = outcome.result.result.is_revert();
+        self.frame_end(None, reverted);
+    }
+}
+
+/// Gas of a single executed test transaction.
+pub struct GasCsvRow<'a> {
+    /// Path of the test file.
+    pub path: &'a str,
+    /// Name of the test.
+    pub test: &'a str,
+    /// Fork the transaction was executed with.
+    pub fork: SpecId,
+    /// Index of the transaction in the post state of the fork.
+    pub index: usize,
+    /// Gas limit of the transaction.
+    pub gas_limit: u64,
+    /// Gas price paid per unit of gas.
+    pub effective_gas_price: u128,
+    /// Execution result, or the error of an invalid transaction.
+    pub result: Result<&'a ExecutionResult<HaltReason>, String>,
+    /// Frame that reverted first.
+    pub first_revert: Option<RevertFrame>,
+}
+
+/// CSV file with one row of gas figures per executed transaction, shared by the runner threads.
+pub struct GasCsv {
+    writer: Mutex<csv::Writer<File>>,
+}
+
+impl GasCsv {
+    /// Creates the file and writes the header.
+    pub fn create(path: &Path) -> csv::Result<Self> {
+        let mut writer = csv::Writer::from_path(path)?;
+        writer.write_record(HEADER)?;
+        Ok(Self {
+            writer: Mutex::new(writer),
+        })
+    }
+
+    /// Appends a row.
+    pub fn write_row(&self, row: &GasCsvRow<'_>) -> csv::Result<()> {
+        let (gas_used, refund, status) = match &row.result {
+            Ok(result) => {
+                let status = match result {
+                    ExecutionResult::Success { .. } => "success".to_string(),
+                    ExecutionResult::Revert { .. } => "revert".to_string(),
+                    ExecutionResult::Halt { reason, .. } => format!("halt: {reason:?}"),
+                };
+                (result.tx_gas_used(), result.gas().final_refunded(), status)
+            }
+            Err(error) => (0, 0, format!("error: {error}")),
+        };
+        let (revert_depth, revert_address) = match row.first_revert {
+            Some(frame) => (
+                frame.depth.to_string(),
+                frame.address.map(|a| a.to_string()).unwrap_or_default(),
+            ),
+            None => (String::new(), String::new()),
+        };
+        self.writer.lock().unwrap().write_record([
+            row.path,
+            row.test,
+            row.fork.to_string().as_str(),
+            row.index.to_string().as_str(),
+            row.gas_limit.to_string().as_str(),
+            gas_used.to_string().as_str(),
+            refund.to_string().as_str(),
+            row.effective_gas_price.to_string().as_str(),
+            status.as_str(),
+            revert_depth.as_str(),
+            revert_address.as_str(),
+        ])
+    }
+
+    /// Flushes the buffered rows to the file.
+    pub fn flush(&self) -> io::Result<()> {
+        self.writer.lock().unwrap().flush()
+    }
+}
@@ -1,8 +1,21 @@
-use crate::cmd::statetest::merkle_trie::{compute_test_roots, TestValidationResult};
+use crate::cmd::{
+    code_cache::CodeCache,
+    statetest::{
+        expect_fail::{ExpectFail, ExpectFailError},
+        fetch::FetchError,
+        gas_csv::{GasCsv, GasCsvRow, RevertFrameInspector},
+        merkle_trie::{compute_test_roots, TestValidationResult},
+        timing::{TestTiming, Timing},
+    },
+};
 use indicatif::{ProgressBar, ProgressDrawTarget};
 use revm::{
+    bytecode::Bytecode,
     context::{block::BlockEnv, cfg::CfgEnv, tx::TxEnv},
-    context_interface::result::{EVMError, ExecutionResult, HaltReason, InvalidTransaction},
+    context_interface::{
+        result::{EVMError, ExecutionResult, HaltReason, InvalidTransaction},
+        Transaction,
+    },
     database::{self, bal::EvmDatabaseError},
     database_interface::EmptyDB,
     inspector::{inspectors::TracerEip3155, InspectCommitEvm},
@@ -60,6 +73,27 @@ pub enum TestErrorKind {
     InvalidPath,
     #[error("no JSON test files found in path")]
     NoJsonFiles,
+    #[error("failed to write gas CSV: {0}")]
+    GasCsv(csv::Error),
+    #[error(transparent)]
+    ExpectFail(#[from] ExpectFailError),
+    #[error("{0} tests expected to fail passed")]
+    UnexpectedPasses(usize),
+    #[error(transparent)]
+    Fetch(#[from] FetchError),
+}
+
+/// Optional outputs and expectations of a test run, shared by the runner threads.
+#[derive(Clone, Default)]
+pub struct RunOptions {
+    /// CSV file the gas of every executed transaction is written to.
+    pub gas_csv: Option<Arc<GasCsv>>,
+    /// Tests that are expected to fail.
+    pub expect_fail: Option<Arc<ExpectFail>>,
+    /// Recorder of the execution time of every test transaction.
+    pub timing: Option<Arc<Timing>>,
+    /// Analyzed bytecode shared by the runner threads.
+    pub code_cache: Arc<CodeCache>,
 }
 
 /// Check if a test should be skipped based on its filename
@@ -103,6 +137,8 @@ fn skip_test(path: &Path) -> bool {
 
 struct TestExecutionContext<'a> {
     name: &'a str,
+    path: &'a str,
+    index: usize,
     unit: &'a TestUnit,
     test: &'a Test,
     cfg: &'a CfgEnv,
@@ -112,6 +148,8 @@ struct TestExecutionContext<'a> {
     elapsed: &'a Arc<Mutex<Duration>>,
     trace: bool,
     print_json_outcome: bool,
+    gas_csv: Option<&'a GasCsv>,
+    timing: Option<&'a Timing>,
 }
 
 struct DebugContext<'a> {
@@ -272,6 +310,20 @@ fn check_evm_execution(
     Ok(())
 }
 
+/// Configuration the transactions of the test are executed with
+pub(crate) fn cfg_env(unit: &TestUnit, spec: SpecId) -> CfgEnv {
+    let mut cfg = CfgEnv::default();
+    cfg.chain_id = unit
+        .env
+        .current_chain_id
+        .unwrap_or(U256::ONE)
+        .try_into()
+        .unwrap_or(1);
+    // Max blobs per transaction are taken from the mainnet blob schedule.
+    cfg.set_spec_and_mainnet_gas_params(spec);
+    cfg
+}
+
 /// Execute a single test suite file containing multiple tests
 ///
 /// # Arguments
@@ -279,11 +331,13 @@ fn check_evm_execution(
 /// * `elapsed` - Shared counter for total execution time
 /// * `trace` - Whether to enable EVM tracing
 /// * `print_json_outcome` - Whether to print JSON formatted results
+/// * `options` - Optional outputs and expectations of the run
 pub fn execute_test_suite(
     path: &Path,
     elapsed: &Arc<Mutex<Duration>>,
     trace: bool,
     print_json_outcome: bool,
+    options: &RunOptions,
 ) -> Result<(), TestError> {
     if skip_test(path) {
         return Ok(());
@@ -297,107 +351,132 @@ pub fn execute_test_suite(
         kind: e.into(),
     })?;
 
-    for (name, unit) in suite.0 {
-        // Prepare initial state
-        let cache_state = unit.state();
-
-        // Setup base configuration
-        let mut cfg = CfgEnv::default();
-        cfg.chain_id = unit
-            .env
-            .current_chain_id
-            .unwrap_or(U256::ONE)
-            .try_into()
-            .unwrap_or(1);
-
-        // Post and execution
-        for (spec_name, tests) in &unit.post {
-            // Skip Constantinople spec
-            if *spec_name == SpecName::Constantinople {
-                continue;
-            }
+    for (name, unit) in &suite.0 {
+        let expect_fail = options
+            .expect_fail
+            .as_deref()
+            .filter(|expect_fail| expect_fail.is_expected(name));
+        let result = execute_test_unit(
+            &path,
+            name,
+            unit,
+            elapsed,
+            trace,
+            print_json_outcome,
+            options,
+            expect_fail.is_none(),
+        );
+        match expect_fail {
+            Some(expect_fail) => expect_fail.record(name, result),
+            None => result?,
+        }
+    }
+    Ok(())
+}
 
-            // Unknown/unsupported spec (e.g. a transition fork not yet mapped to a
-            // `SpecId`). Report it and skip rather than panicking in `to_spec_id`.
-            if *spec_name == SpecName::Unknown {
-                eprintln!("Error: unknown spec in post state, skipping: path={path}");
-                continue;
-            }
+/// Execute all transactions of a single test
+///
+/// If `debug_on_failure` is set, the first failure of the run is executed again with tracing.
+#[allow(clippy::too_many_arguments)]
+fn execute_test_unit(
+    path: &str,
+    name: &str,
+    unit: &TestUnit,
+    elapsed: &Arc<Mutex<Duration>>,
+    trace: bool,
+    print_json_outcome: bool,
+    options: &RunOptions,
+    debug_on_failure: bool,
+) -> Result<(), TestError> {
+    // Prepare initial state
+    let cache_state = unit.state_with_bytecode(|code_hash, code| {
+        options.code_cache.get_or_analyze(code_hash, code, |code| {
+            Bytecode::new_raw_checked(code.clone()).unwrap_or(Bytecode::new_legacy(code))
+        })
+    });
+
+    // Post and execution
+    for (spec_name, tests) in &unit.post {
+        // Skip Constantinople spec
+        if *spec_name == SpecName::Constantinople {
+            continue;
+        }
 
-            cfg.set_spec_and_mainnet_gas_params(spec_name.to_spec_id());
+        // Unknown/unsupported spec (e.g. a transition fork not yet mapped to a
+        // `SpecId`). Report it and skip rather than panicking in `to_spec_id`.
+        if *spec_name == SpecName::Unknown {
+            eprintln!("Error: unknown spec in post state, skipping: path={path}");
+            continue;
+        }
 
-            // Configure max blobs per spec
-            if cfg.spec().is_enabled_in(SpecId::OSAKA) {
-                cfg.set_max_blobs_per_tx(6);
-            } else if cfg.spec().is_enabled_in(SpecId::PRAGUE) {
-                cfg.set_max_blobs_per_tx(9);
-            } else {
-                cfg.set_max_blobs_per_tx(6);
-            }
+        let mut cfg = cfg_env(unit, spec_name.to_spec_id());
 
-            // Setup block environment for this spec
-            let block = unit.block_env(&mut cfg);
-
-            for (index, test) in tests.iter().enumerate() {
-                // Setup transaction environment
-                let tx = match test.tx_env(&unit) {
-                    Ok(tx) => tx,
-                    Err(_) if test.expect_exception.is_some() => continue,
-                    Err(_) => {
-                        return Err(TestError {
-                            name,
-                            path,
-                            kind: TestErrorKind::UnknownPrivateKey(
-                                unit.transaction.secret_key.unwrap_or_default(),
-                            ),
-                        });
-                    }
-                };
-
-                // Execute the test
-                let result = execute_single_test(TestExecutionContext {
-                    name: &name,
-                    unit: &unit,
-                    test,
-                    cfg: &cfg,
-                    block: &block,
-                    tx: &tx,
-                    cache_state: &cache_state,
-                    elapsed,
-                    trace,
-                    print_json_outcome,
-                });
+        // Setup block environment for this spec
+        let block = unit.block_env(&mut cfg);
 
-                if let Err(e) = result {
-                    // Handle error with debug trace if needed
-                    static FAILED: AtomicBool = AtomicBool::new(false);
-                    if print_json_outcome || FAILED.swap(true, Ordering::SeqCst) {
-                        return Err(TestError {
-                            name,
-                            path,
-                            kind: e,
-                        });
-                    }
-
-                    // Re-run with trace for debugging
-                    debug_failed_test(DebugContext {
-                        name: &name,
-                        path: &path,
-                        index,
-                        test,
-                        cfg: &cfg,
-                        block: &block,
-                        tx: &tx,
-                        cache_state: &cache_state,
-                        error: &e,
+        for (index, test) in tests.iter().enumerate() {
+            // Setup transaction environment
+            let tx = match test.tx_env(unit) {
+                Ok(tx) => tx,
+                Err(_) if test.expect_exception.is_some() => continue,
+                Err(_) => {
+                    return Err(TestError {
+                        name: name.to_string(),
+                        path: path.to_string(),
+                        kind: TestErrorKind::UnknownPrivateKey(
+                            unit.transaction.secret_key.unwrap_or_default(),
+                        ),
                     });
+                }
+            };
+
+            // Execute the test
+            let result = execute_single_test(TestExecutionContext {
+                name,
+                path,
+                index,
+                unit,
+                test,
+                cfg: &cfg,
+                block: &block,
+                tx: &tx,
+                cache_state: &cache_state,
+                elapsed,
+                trace,
+                print_json_outcome,
+                gas_csv: options.gas_csv.as_deref(),
+                timing: options.timing.as_deref(),
+            });
 
+            if let Err(e) = result {
+                // Handle error with debug trace if needed
+                static FAILED: AtomicBool = AtomicBool::new(false);
+                if !debug_on_failure || print_json_outcome || FAILED.swap(true, Ordering::SeqCst) {
                     return Err(TestError {
-                        path,
-                        name,
+                        name: name.to_string(),
+                        path: path.to_string(),
                         kind: e,
                     });
                 }
+
+                // Re-run with trace for debugging
+                debug_failed_test(DebugContext {
+                    name,
+                    path,
+                    index,
+                    test,
+                    cfg: &cfg,
+                    block: &block,
+                    tx: &tx,
+                    cache_state: &cache_state,
+                    error: &e,
+                });
+
+                return Err(TestError {
+                    path: path.to_string(),
+                    name: name.to_string(),
+                    kind: e,
+                });
             }
         }
     }
@@ -420,21 +499,55 @@ fn execute_single_test(ctx: TestExecutionContext) -> Result<(), TestErrorKind> {
 
     // Execute
     let timer = Instant::now();
-    let (db, exec_result) = if ctx.trace {
-        let mut evm = evm_context
-            .build_mainnet_with_inspector(TracerEip3155::buffered(stderr()).without_summary());
+    let (db, exec_result, first_revert) = if ctx.trace {
+        let mut evm = evm_context.build_mainnet_with_inspector((
+            TracerEip3155::buffered(stderr()).without_summary(),
+            RevertFrameInspector::default(),
+        ));
+        let res = evm.inspect_tx_commit(ctx.tx);
+        let first_revert = evm.inspector.1.first_revert();
+        let db = evm.ctx.journaled_state.database;
+        (db, res, first_revert)
+    } else if ctx.gas_csv.is_some() {
+        let mut evm = evm_context.build_mainnet_with_inspector(RevertFrameInspector::default());
         let res = evm.inspect_tx_commit(ctx.tx);
+        let first_revert = evm.inspector.first_revert();
         let db = evm.ctx.journaled_state.database;
-        (db, res)
+        (db, res, first_revert)
     } else {
         let mut evm = evm_context.build_mainnet();
         let res = evm.transact_commit(ctx.tx);
         let db = evm.ctx.journaled_state.database;
-        (db, res)
+        (db, res, None)
     };
-    *ctx.elapsed.lock().unwrap() += timer.elapsed();
+    let elapsed = timer.elapsed();
+    *ctx.elapsed.lock().unwrap() += elapsed;
+
+    if let Some(timing) = ctx.timing {
+        timing.record(TestTiming {
+            path: ctx.path.to_string(),
+            name: ctx.name.to_string(),
+            fork: *ctx.cfg.spec(),
+            index: ctx.index,
+            elapsed,
+            gas_used: exec_result.as_ref().map_or(0, ExecutionResult::tx_gas_used),
+        });
+    }
 
-    let exec_result = exec_result;
+    if let Some(gas_csv) = ctx.gas_csv {
+        gas_csv
+            .write_row(&GasCsvRow {
+                path: ctx.path,
+                test: ctx.name,
+                fork: *ctx.cfg.spec(),
+                index: ctx.index,
+                gas_limit: ctx.tx.gas_limit,
+                effective_gas_price: ctx.tx.effective_gas_price(ctx.block.basefee as u128),
+                result: exec_result.as_ref().map_err(ToString::to_string),
+                first_revert,
+            })
+            .map_err(TestErrorKind::GasCsv)?;
+    }
     // Check results
     check_evm_execution(
         ctx.test,
@@ -517,10 +630,11 @@ struct TestRunnerState {
     queue: Arc<Mutex<(usize, Vec<PathBuf>)>>,
     elapsed: Arc<Mutex<Duration>>,
     errors: Arc<Mutex<Vec<TestError>>>,
+    options: RunOptions,
 }
 
 impl TestRunnerState {
-    fn new(test_files: Vec<PathBuf>, omit_progress: bool) -> Self {
+    fn new(test_files: Vec<PathBuf>, omit_progress: bool, options: RunOptions) -> Self {
         let n_files = test_files.len();
         let draw_target = if omit_progress {
             ProgressDrawTarget::hidden()
@@ -536,6 +650,7 @@ impl TestRunnerState {
             queue: Arc::new(Mutex::new((0usize, test_files))),
             elapsed: Arc::new(Mutex::new(Duration::ZERO)),
             errors: Arc::new(Mutex::new(Vec::new())),
+            options,
         }
     }
 
@@ -563,6 +678,7 @@ fn run_test_worker(state: TestRunnerState, config: TestRunnerConfig) -> Result<(
             &state.elapsed,
             config.trace,
             config.print_outcome,
+            &state.options,
         );
 
         state.console_bar.inc(1);
@@ -593,6 +709,7 @@ fn determine_thread_count(single_thread: bool, n_files: usize) -> usize {
 /// * `trace` - Enable EVM execution tracing
 /// * `print_outcome` - Print test outcomes in JSON format
 /// * `keep_going` - Continue running tests even if some fail
+/// * `options` - Optional outputs and expectations of the run
 pub fn run(
     test_files: Vec<PathBuf>,
     single_thread: bool,
@@ -600,10 +717,11 @@ pub fn run(
     print_outcome: bool,
     keep_going: bool,
     omit_progress: bool,
+    options: RunOptions,
 ) -> Result<(), TestError> {
     let config = TestRunnerConfig::new(single_thread, trace, print_outcome, keep_going);
     let n_files = test_files.len();
-    let state = TestRunnerState::new(test_files, omit_progress);
+    let state = TestRunnerState::new(test_files, omit_progress, options);
     let num_threads = determine_thread_count(config.single_thread, n_files);
 
     // Spawn worker threads
@@ -635,12 +753,47 @@ pub fn run(
 
     state.console_bar.finish();
 
+    if let Some(gas_csv) = &state.options.gas_csv {
+        if let Err(e) = gas_csv.flush() {
+            thread_errors.push(TestError {
+                name: "Gas CSV".to_string(),
+                path: String::new(),
+                kind: TestErrorKind::GasCsv(e.into()),
+            });
+        }
+    }
+
     // Print summary
     println!(
         "Finished execution. Total CPU time: {:.6}s",
         state.elapsed.lock().unwrap().as_secs_f64()
     );
 
+    state.options.code_cache.print();
+
+    if let Some(timing) = &state.options.timing {
+        timing.take_report().print();
+    }
+
+    if let Some(expect_fail) = &state.options.expect_fail {
+        let outcome = expect_fail.take_outcome();
+        println!(
+            "{} tests failed as expected",
+            outcome.expected_failures.len()
+        );
+        if !outcome.unexpected_passes.is_empty() {
+            println!("\nUnexpected passes:");
+            for (name, reason) in &outcome.unexpected_passes {
+                println!("  {name} (expected to fail: {reason})");
+            }
+            thread_errors.push(TestError {
+                name: "Expected failures".to_string(),
+                path: String::new(),
+                kind: TestErrorKind::UnexpectedPasses(outcome.unexpected_passes.len()),
+            });
+        }
+    }
+
     let n_errors = state.n_errors.load(Ordering::SeqCst);
     let n_thread_errors = thread_errors.len();
 
@@ -0,0 +1,143 @@
+use revm::primitives::hardfork::SpecId;
+use std::{mem, sync::Mutex, time::Duration};
+
+/// Execution time of a single test transaction.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TestTiming {
+    /// Path of the test file.
+    pub path: String,
+    /// Name of the test.
+    pub name: String,
+    /// Fork the transaction was executed with.
+    pub fork: SpecId,
+    /// Index of the transaction in the post state of the fork.
+    pub index: usize,
+    /// Time spent executing the transaction.
+    pub elapsed: Duration,
+    /// Gas used by the transaction, zero for invalid transactions.
+    pub gas_used: u64,
+}
+
+/// Summary of the recorded timings.
+#[derive(Debug, Default)]
+pub struct TimingReport {
+    /// Slowest tests, slowest first.
+    pub slowest: Vec<TestTiming>,
+    /// Number of executed transactions.
+    pub transactions: usize,
+    /// Total time spent executing the transactions.
+    pub elapsed: Duration,
+    /// Total gas used by the transactions.
+    pub gas_used: u64,
+}
+
+impl TimingReport {
+    /// Returns the gas executed per second.
+    pub fn gas_per_second(&self) -> f64 {
+        let secs = self.elapsed.as_secs_f64();
+        if secs == 0.0 {
+            0.0
+        } else {
+            self.gas_used as f64 / secs
+        }
+    }
+
+    /// Prints the report.
+    pub fn print(&self) {
+        println!(
+            "\nExecuted {} transactions using {} gas in {:.6}s ({:.2} Mgas/s)",
+            self.transactions,
+            self.gas_used,
+            self.elapsed.as_secs_f64(),
+            self.gas_per_second() / 1e6
+        );
+        if self.slowest.is_empty() {
+            return;
+        }
+        println!("\nSlowest tests:");
+        for timing in &self.slowest {
+            println!(
+                "  {:>12.3?} {:>12} gas  {} [{:?} #{}] ({})",
+                timing.elapsed,
+                timing.gas_used,
+                timing.name,
+                timing.fork,
+                timing.index,
+                timing.path
+            );
+        }
+    }
+}
+
+/// Per-test execution times, shared by the runner threads.
+///
+/// Turns the conformance suite into a coarse performance regression harness.
+#[derive(Debug, Default)]
+pub struct Timing {
+    slowest: usize,
+    timings: Mutex<Vec<TestTiming>>,
+}
+
+impl Timing {
+    /// Creates a recorder that reports the `slowest` slowest tests.
+    pub fn new(slowest: usize) -> Self {
+        Self {
+            slowest,
+            timings: Mutex::default(),
+        }
+    }
+
+    /// Records the execution time of a test transaction.
+    pub fn record(&self, timing: TestTiming) {
+        self.timings.lock().unwrap().push(timing);
+    }
+
+    /// Takes the timings recorded since the last call and summarizes them.
+    pub fn take_report(&self) -> TimingReport {
+        let mut timings = mem::take(&mut *self.timings.lock().unwrap());
+        let transactions = timings.len();
+        let elapsed = timings.iter().map(|timing| timing.elapsed).sum();
+        let gas_used = timings.iter().map(|timing| timing.gas_used).sum();
+        timings.sort_unstable_by(|a, b| b.elapsed.cmp(&a.elapsed));
+        timings.truncate(self.slowest);
+        TimingReport {
+            slowest: timings,
+            transactions,
+            elapsed,
+            gas_used,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn timing(name: &str, millis: u64, gas_used: u64) -> TestTiming {
+        TestTiming {
+            path: "test.json".to_string(),
+            name: name.to_string(),
+            fork: SpecId::PRAGUE,
+            index: 0,
+            elapsed: Duration::from_millis(millis),
+            gas_used,
+        }
+    }
+
+    #[test]
+    fn reports_slowest_tests_and_throughput() {
+        let recorder = Timing::new(2);
+        recorder.record(timing("fast", 1, 1_000));
+        recorder.record(timing("slow", 3, 5_000));
+        recorder.record(timing("medium", 2, 2_000));
+
+        let report = recorder.take_report();
+        let slowest: Vec<_> = report.slowest.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(slowest, ["slow", "medium"]);
+        assert_eq!(report.transactions, 3);
+        assert_eq!(report.gas_used, 8_000);
+        assert_eq!(report.gas_per_second(), 8_000.0 / 0.006);
+
+        assert_eq!(recorder.take_report().transactions, 0);
+    }
+}
@@ -2,6 +2,11 @@ use clap::Parser;
 use revme::cmd::MainCmd;
 use std::process::ExitCode;
 
+#[cfg(feature = "alloc-profiling")]
+#[global_allocator]
+static ALLOC: revme::cmd::bench::memory::CountingAllocator =
+    revme::cmd::bench::memory::CountingAllocator;
+
 fn main() -> ExitCode {
     if std::env::var_os("RUST_BACKTRACE").is_none() {
         unsafe { std::env::set_var("RUST_BACKTRACE", "1") };
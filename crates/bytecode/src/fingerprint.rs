@@ -0,0 +1,127 @@
+//! Normalized fingerprints and similarity of bytecodes.
+//!
+//! Contract clones usually differ only in constants embedded in `PUSH` instructions, like
+//! addresses of dependencies, and in the metadata appended by the compiler. The fingerprint
+//! hashes the sequence of opcodes with both removed, so indexers can cluster clones.
+
+use crate::{metadata::strip_metadata, opcode, Bytecode};
+use primitives::{keccak256, B256};
+use std::{collections::BTreeSet, vec::Vec};
+
+/// Number of consecutive opcodes compared by [`similarity`].
+const SHINGLE_SIZE: usize = 4;
+
+/// Returns the opcodes of legacy bytecode without push data and [compiler
+/// metadata](crate::metadata).
+pub fn normalized_opcodes(code: &[u8]) -> Vec<u8> {
+    let code = strip_metadata(code);
+    let mut opcodes = Vec::with_capacity(code.len());
+    let mut i = 0;
+    while i < code.len() {
+        let op = code[i];
+        opcodes.push(op);
+        let immediate_size = opcode::OPCODE_INFO[op as usize]
+            .map(|info| info.immediate_size() as usize)
+            .unwrap_or_default();
+        i += 1 + immediate_size;
+    }
+    opcodes
+}
+
+/// Returns the fingerprint of legacy bytecode, the hash of its [normalized
+/// opcodes](normalized_opcodes).
+///
+/// Bytecodes that differ only in push data and compiler metadata have the same fingerprint.
+pub fn fingerprint(code: &[u8]) -> B256 {
+    keccak256(normalized_opcodes(code))
+}
+
+/// Returns the similarity of two legacy bytecodes, from `0.0` for unrelated to `1.0` for the
+/// same [normalized opcodes](normalized_opcodes).
+///
+/// The similarity is the Jaccard index of the sets of sequences of four consecutive normalized
+/// opcodes, so it is not affected by push data and compiler metadata, and degrades gracefully
+/// with inserted or removed code.
+pub fn similarity(a: &[u8], b: &[u8]) -> f64 {
+    let a = shingles(&normalized_opcodes(a));
+    let b = shingles(&normalized_opcodes(b));
+    let union = a.union(&b).count();
+    if union == 0 {
+        return 1.0;
+    }
+    a.intersection(&b).count() as f64 / union as f64
+}
+
+/// Returns the sequences of consecutive opcodes, the opcodes themselves for short code.
+fn shingles(opcodes: &[u8]) -> BTreeSet<&[u8]> {
+    if opcodes.len() < SHINGLE_SIZE {
+        return BTreeSet::from_iter((!opcodes.is_empty()).then_some(opcodes));
+    }
+    opcodes.windows(SHINGLE_SIZE).collect()
+}
+
+impl Bytecode {
+    /// Returns the [fingerprint] of the bytecode, see [`fingerprint()`].
+    ///
+    /// Only legacy bytecode is normalized, the fingerprint of other bytecode is the hash of its
+    /// original bytes.
+    pub fn fingerprint(&self) -> B256 {
+        if self.is_legacy() {
+            fingerprint(self.original_byte_slice())
+        } else {
+            keccak256(self.original_byte_slice())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::opcode::{ADD, CALLER, MSTORE, PUSH1, PUSH20, RETURN, SSTORE, STOP};
+    use primitives::hex;
+
+    /// `a2 64 'ipfs' <34 bytes> 64 'solc' 43 <3 bytes>` followed by its length.
+    fn metadata(seed: u8) -> Vec<u8> {
+        let mut metadata = hex::decode("a264697066735822").unwrap();
+        metadata.extend([seed; 34]);
+        metadata.extend(hex::decode("64736f6c6343000814").unwrap());
+        let len = metadata.len() as u16;
+        metadata.extend(len.to_be_bytes());
+        metadata
+    }
+
+    fn contract(dependency: u8, seed: u8) -> Vec<u8> {
+        let mut code = vec![PUSH20];
+        code.extend([dependency; 20]);
+        code.extend([CALLER, SSTORE, PUSH1, 0x20, PUSH1, 0x00, RETURN]);
+        code.extend(metadata(seed));
+        code
+    }
+
+    #[test]
+    fn clones_have_the_same_fingerprint() {
+        assert_eq!(fingerprint(&contract(1, 1)), fingerprint(&contract(2, 2)));
+        assert_eq!(
+            normalized_opcodes(&contract(1, 1)),
+            [PUSH20, CALLER, SSTORE, PUSH1, PUSH1, RETURN]
+        );
+        assert_eq!(similarity(&contract(1, 1), &contract(2, 2)), 1.0);
+
+        let bytecode = Bytecode::new_legacy(contract(3, 3).into());
+        assert_eq!(bytecode.fingerprint(), fingerprint(&contract(1, 1)));
+    }
+
+    #[test]
+    fn similarity_of_modified_code() {
+        let original = contract(1, 1);
+        let mut modified = original.clone();
+        // Append code after the metadata, which is then part of the code.
+        modified.extend([PUSH1, 0x01, PUSH1, 0x02, ADD, MSTORE, STOP]);
+
+        assert_ne!(fingerprint(&original), fingerprint(&modified));
+        let score = similarity(&original, &modified);
+        assert!(score > 0.0 && score < 1.0, "{score}");
+        assert_eq!(similarity(&original, &[STOP]), 0.0);
+        assert_eq!(similarity(&[], &[]), 1.0);
+    }
+}
@@ -13,9 +13,11 @@ pub mod bytecode;
 mod decode_errors;
 /// EIP-7702 bytecode.
 pub mod eip7702;
+pub mod fingerprint;
 /// Iterator for the bytecode.
 mod iter;
 mod legacy;
+pub mod metadata;
 pub mod opcode;
 pub mod utils;
 
@@ -0,0 +1,331 @@
+//! Parsing and stripping of the CBOR metadata appended to deployed bytecode by solc and vyper.
+//!
+//! solc appends a CBOR map with the hash of the metadata file and the compiler version,
+//! followed by the length of the map as a big-endian `u16`. vyper appends a map with its version
+//! and, since 0.3.10, an array ending with that map, whose length includes the two length bytes.
+
+use primitives::{Bytes, B256};
+use std::{string::String, vec::Vec};
+
+/// Maximum nesting of CBOR arrays and maps in the metadata.
+const MAX_DEPTH: usize = 4;
+
+/// Version of a compiler.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Version {
+    /// Major version.
+    pub major: u8,
+    /// Minor version.
+    pub minor: u8,
+    /// Patch version.
+    pub patch: u8,
+}
+
+/// Compiler that produced the bytecode.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Compiler {
+    /// Release of solc.
+    Solc(Version),
+    /// Prerelease of solc, e.g. a nightly build, with its full version string.
+    SolcPrerelease(String),
+    /// vyper.
+    Vyper(Version),
+}
+
+/// Hash of the metadata file of the contract, used to fetch the sources for verification.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum SourceHash {
+    /// IPFS multihash of the metadata file, the bytes of a CIDv0.
+    Ipfs(Bytes),
+    /// Swarm hash of the metadata file, used by solc 0.4.x.
+    Bzzr0(B256),
+    /// Swarm hash of the metadata file, used by solc 0.5.x.
+    Bzzr1(B256),
+}
+
+/// Metadata appended to deployed bytecode by the compiler.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ContractMetadata {
+    /// Hash of the metadata file.
+    pub source_hash: Option<SourceHash>,
+    /// Compiler that produced the bytecode.
+    pub compiler: Option<Compiler>,
+    /// Whether experimental compiler features were used.
+    pub experimental: bool,
+    /// Length of the metadata at the end of the bytecode, including the length bytes.
+    pub len: usize,
+}
+
+/// Parses the metadata at the end of the bytecode.
+///
+/// Returns `None` if the bytecode doesn't end with a CBOR metadata trailer.
+pub fn parse_metadata(code: &[u8]) -> Option<ContractMetadata> {
+    let (rest, len) = code.split_last_chunk::<2>()?;
+    let len = u16::from_be_bytes(*len) as usize;
+    // solc doesn't include the length bytes in the length, vyper 0.3.10 and later does.
+    [len, len.checked_sub(2)?].into_iter().find_map(|cbor_len| {
+        let start = rest.len().checked_sub(cbor_len)?;
+        let value = Decoder::decode(&rest[start..])?;
+        ContractMetadata::from_value(value, cbor_len + 2)
+    })
+}
+
+/// Returns the bytecode without the metadata appended by the compiler, see [`parse_metadata`].
+///
+/// The result is the canonical runtime code that is compared when verifying a contract and
+/// that is fingerprinted to find clones.
+pub fn strip_metadata(code: &[u8]) -> &[u8] {
+    match parse_metadata(code) {
+        Some(metadata) => &code[..code.len() - metadata.len],
+        None => code,
+    }
+}
+
+impl ContractMetadata {
+    fn from_value(value: Value<'_>, len: usize) -> Option<Self> {
+        let entries = match value {
+            Value::Map(entries) => entries,
+            // vyper 0.3.10 and later append an array that ends with the map.
+            Value::Array(items) => match items.into_iter().last()? {
+                Value::Map(entries) => entries,
+                _ => return None,
+            },
+            _ => return None,
+        };
+        if entries.is_empty() {
+            return None;
+        }
+
+        let mut metadata = Self {
+            source_hash: None,
+            compiler: None,
+            experimental: false,
+            len,
+        };
+        for (key, value) in entries {
+            let Value::Text(key) = key else {
+                return None;
+            };
+            match (key, value) {
+                ("ipfs", Value::Bytes(hash)) => {
+                    metadata.source_hash = Some(SourceHash::Ipfs(Bytes::copy_from_slice(hash)));
+                }
+                ("bzzr0", Value::Bytes(hash)) if hash.len() == 32 => {
+                    metadata.source_hash = Some(SourceHash::Bzzr0(B256::from_slice(hash)));
+                }
+                ("bzzr1", Value::Bytes(hash)) if hash.len() == 32 => {
+                    metadata.source_hash = Some(SourceHash::Bzzr1(B256::from_slice(hash)));
+                }
+                ("solc", Value::Bytes(&[major, minor, patch])) => {
+                    metadata.compiler = Some(Compiler::Solc(Version {
+                        major,
+                        minor,
+                        patch,
+                    }));
+                }
+                ("solc", Value::Text(version)) => {
+                    metadata.compiler = Some(Compiler::SolcPrerelease(version.into()));
+                }
+                ("vyper", Value::Array(version)) => {
+                    let [major, minor, patch] = version.as_slice() else {
+                        return None;
+                    };
+                    let part = |value: &Value<'_>| match value {
+                        Value::Uint(n) => u8::try_from(*n).ok(),
+                        _ => None,
+                    };
+                    metadata.compiler = Some(Compiler::Vyper(Version {
+                        major: part(major)?,
+                        minor: part(minor)?,
+                        patch: part(patch)?,
+                    }));
+                }
+                ("experimental", Value::Bool(experimental)) => {
+                    metadata.experimental = experimental;
+                }
+                _ => {}
+            }
+        }
+        Some(metadata)
+    }
+}
+
+/// Subset of CBOR values used in the metadata.
+#[derive(Debug)]
+enum Value<'a> {
+    Uint(u64),
+    Bytes(&'a [u8]),
+    Text(&'a str),
+    Array(Vec<Value<'a>>),
+    Map(Vec<(Value<'a>, Value<'a>)>),
+    Bool(bool),
+}
+
+/// Decoder of the definite-length CBOR values used in the metadata.
+struct Decoder<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Decoder<'a> {
+    /// Decodes a value that spans all of the data.
+    fn decode(data: &'a [u8]) -> Option<Value<'a>> {
+        let mut decoder = Self { data };
+        let value = decoder.value(0)?;
+        decoder.data.is_empty().then_some(value)
+    }
+
+    fn take(&mut self, n: usize) -> Option<&'a [u8]> {
+        if n > self.data.len() {
+            return None;
+        }
+        let (taken, rest) = self.data.split_at(n);
+        self.data = rest;
+        Some(taken)
+    }
+
+    fn argument(&mut self, info: u8) -> Option<u64> {
+        Some(match info {
+            0..=23 => info as u64,
+            24 => self.take(1)?[0] as u64,
+            25 => u16::from_be_bytes(self.take(2)?.try_into().ok()?) as u64,
+            26 => u32::from_be_bytes(self.take(4)?.try_into().ok()?) as u64,
+            27 => u64::from_be_bytes(self.take(8)?.try_into().ok()?),
+            _ => return None,
+        })
+    }
+
+    fn value(&mut self, depth: usize) -> Option<Value<'a>> {
+        if depth > MAX_DEPTH {
+            return None;
+        }
+        let initial = self.take(1)?[0];
+        let (major, info) = (initial >> 5, initial & 0x1f);
+        if major == 7 {
+            return match info {
+                20 => Some(Value::Bool(false)),
+                21 => Some(Value::Bool(true)),
+                _ => None,
+            };
+        }
+        let argument = self.argument(info)?;
+        match major {
+            0 => Some(Value::Uint(argument)),
+            2 => Some(Value::Bytes(self.take(usize::try_from(argument).ok()?)?)),
+            3 => {
+                let text = self.take(usize::try_from(argument).ok()?)?;
+                core::str::from_utf8(text).ok().map(Value::Text)
+            }
+            4 => {
+                let mut items = Vec::new();
+                for _ in 0..argument {
+                    items.push(self.value(depth + 1)?);
+                }
+                Some(Value::Array(items))
+            }
+            5 => {
+                let mut entries = Vec::new();
+                for _ in 0..argument {
+                    entries.push((self.value(depth + 1)?, self.value(depth + 1)?));
+                }
+                Some(Value::Map(entries))
+            }
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::opcode::{CALLVALUE, PUSH1, STOP};
+    use primitives::hex;
+
+    const CODE: [u8; 4] = [PUSH1, 0x80, CALLVALUE, STOP];
+
+    fn with_trailer(cbor: &[u8], len: usize) -> Vec<u8> {
+        let mut code = CODE.to_vec();
+        code.extend_from_slice(cbor);
+        code.extend((len as u16).to_be_bytes());
+        code
+    }
+
+    #[test]
+    fn solc_metadata() {
+        // {"ipfs": <34 bytes>, "solc": 0.8.20}
+        let mut cbor = hex::decode("a2646970667358221220").unwrap();
+        cbor.extend([0xab; 32]);
+        cbor.extend(hex::decode("64736f6c6343000814").unwrap());
+        let code = with_trailer(&cbor, cbor.len());
+
+        let metadata = parse_metadata(&code).unwrap();
+        let Some(SourceHash::Ipfs(hash)) = &metadata.source_hash else {
+            panic!("expected an IPFS hash: {metadata:?}");
+        };
+        assert_eq!(hash.len(), 34);
+        assert_eq!(&hash[..2], [0x12, 0x20]);
+        assert_eq!(
+            metadata.compiler,
+            Some(Compiler::Solc(Version {
+                major: 0,
+                minor: 8,
+                patch: 20
+            }))
+        );
+        assert!(!metadata.experimental);
+        assert_eq!(strip_metadata(&code), CODE);
+    }
+
+    #[test]
+    fn solc_swarm_metadata() {
+        // {"bzzr0": <32 bytes>}
+        let mut cbor = hex::decode("a165627a7a72305820").unwrap();
+        cbor.extend([0xcd; 32]);
+        let code = with_trailer(&cbor, cbor.len());
+
+        let metadata = parse_metadata(&code).unwrap();
+        assert_eq!(
+            metadata.source_hash,
+            Some(SourceHash::Bzzr0(B256::repeat_byte(0xcd)))
+        );
+        assert_eq!(metadata.compiler, None);
+        assert_eq!(strip_metadata(&code), CODE);
+    }
+
+    #[test]
+    fn vyper_metadata() {
+        let vyper_0_3_4 = Some(Compiler::Vyper(Version {
+            major: 0,
+            minor: 3,
+            patch: 4,
+        }));
+        // {"vyper": [0, 3, 4]}
+        let cbor = hex::decode("a165767970657283000304").unwrap();
+        let code = with_trailer(&cbor, cbor.len());
+        assert_eq!(parse_metadata(&code).unwrap().compiler, vyper_0_3_4);
+        assert_eq!(strip_metadata(&code), CODE);
+
+        // [runtime size, [], immutables size, {"vyper": [0, 3, 10]}], length includes itself.
+        let cbor = hex::decode("841901008000a16576797065728300030a").unwrap();
+        let code = with_trailer(&cbor, cbor.len() + 2);
+        let metadata = parse_metadata(&code).unwrap();
+        assert_eq!(
+            metadata.compiler,
+            Some(Compiler::Vyper(Version {
+                major: 0,
+                minor: 3,
+                patch: 10
+            }))
+        );
+        assert_eq!(metadata.len, cbor.len() + 2);
+        assert_eq!(strip_metadata(&code), CODE);
+    }
+
+    #[test]
+    fn code_without_metadata() {
+        assert_eq!(parse_metadata(&CODE), None);
+        assert_eq!(parse_metadata(&[]), None);
+        assert_eq!(strip_metadata(&CODE), CODE);
+        // Trailing length that points into the code.
+        assert_eq!(parse_metadata(&with_trailer(&[], 3)), None);
+    }
+}
@@ -6,10 +6,7 @@
 //!
 //! [`BlobExcessGasAndPrice`] is used to store the blob gas price and excess blob gas.s
 use primitives::{
-    eip4844::{
-        BLOB_BASE_FEE_UPDATE_FRACTION_CANCUN, BLOB_BASE_FEE_UPDATE_FRACTION_PRAGUE,
-        MIN_BLOB_GASPRICE,
-    },
+    eip4844::{BlobParams, BLOB_BASE_COST, GAS_PER_BLOB, MIN_BLOB_GASPRICE},
     hardfork::SpecId,
 };
 
@@ -41,15 +38,22 @@ impl BlobExcessGasAndPrice {
         }
     }
 
-    /// Creates a new instance by calculating the blob gas price based on the spec.
+    /// Creates a new instance by calculating the blob gas price based on the mainnet
+    /// [`BlobParams`] of the spec.
     pub fn new_with_spec(excess_blob_gas: u64, spec: SpecId) -> Self {
+        Self::new_with_params(excess_blob_gas, BlobParams::mainnet(spec))
+    }
+
+    /// Creates a new instance by calculating the blob gas price with the blob parameters, e.g.
+    /// the ones of [`Cfg::blob_params`](crate::Cfg::blob_params).
+    ///
+    /// Cancun parameters are used if blobs are not enabled.
+    pub fn new_with_params(excess_blob_gas: u64, params: Option<BlobParams>) -> Self {
         Self::new(
             excess_blob_gas,
-            if spec.is_enabled_in(SpecId::PRAGUE) {
-                BLOB_BASE_FEE_UPDATE_FRACTION_PRAGUE
-            } else {
-                BLOB_BASE_FEE_UPDATE_FRACTION_CANCUN
-            },
+            params
+                .unwrap_or(BlobParams::CANCUN)
+                .base_fee_update_fraction,
         )
     }
 }
@@ -67,6 +71,34 @@ pub fn calc_blob_gasprice(excess_blob_gas: u64, blob_base_fee_update_fraction: u
     )
 }
 
+/// Calculates the excess blob gas of the next block.
+///
+/// Since Osaka the blob base fee is bounded by a reserve price tied to the base fee
+/// ([EIP-7918](https://eips.ethereum.org/EIPS/eip-7918)), when the parent is above the target
+/// and the reserve price is above its blob base fee the excess blob gas only increases.
+pub fn calc_next_excess_blob_gas(
+    params: BlobParams,
+    parent_excess_blob_gas: u64,
+    parent_blob_gas_used: u64,
+    parent_base_fee: u64,
+    spec: SpecId,
+) -> u64 {
+    if parent_excess_blob_gas + parent_blob_gas_used < params.target_blob_gas_per_block() {
+        return 0;
+    }
+    if spec.is_enabled_in(SpecId::OSAKA) {
+        let reserve_price = BLOB_BASE_COST as u128 * parent_base_fee as u128;
+        let blob_base_fee =
+            calc_blob_gasprice(parent_excess_blob_gas, params.base_fee_update_fraction);
+        if reserve_price > GAS_PER_BLOB as u128 * blob_base_fee {
+            return parent_excess_blob_gas
+                + parent_blob_gas_used * params.max.saturating_sub(params.target)
+                    / params.max.max(1);
+        }
+    }
+    params.next_excess_blob_gas(parent_excess_blob_gas, parent_blob_gas_used)
+}
+
 /// Calculates the base fee per blob gas. Calls [`calc_blob_gasprice`] internally.
 /// Name of the function is aligned with EIP-4844 spec.
 pub fn get_base_fee_per_blob_gas(excess_blob_gas: u64, blob_base_fee_update_fraction: u64) -> u128 {
@@ -129,4 +161,32 @@ mod tests {
             assert_eq!(actual, expected, "test: {t:?}");
         }
     }
+
+    #[test]
+    fn next_excess_blob_gas_reserve_price() {
+        let params = BlobParams::OSAKA;
+        let used = 7 * GAS_PER_BLOB;
+        let high_base_fee = 1_000_000_000;
+
+        // Reserve price above the blob base fee, excess grows by the scaled blob gas used.
+        assert_eq!(
+            calc_next_excess_blob_gas(params, 0, used, high_base_fee, SpecId::OSAKA),
+            used * (params.max - params.target) / params.max
+        );
+        // Same parent before Osaka follows the EIP-4844 formula.
+        assert_eq!(
+            calc_next_excess_blob_gas(params, 0, used, high_base_fee, SpecId::PRAGUE),
+            GAS_PER_BLOB
+        );
+        // Reserve price below the blob base fee.
+        assert_eq!(
+            calc_next_excess_blob_gas(params, 0, used, 0, SpecId::OSAKA),
+            GAS_PER_BLOB
+        );
+        // Below the target the excess is reset regardless of the reserve price.
+        assert_eq!(
+            calc_next_excess_blob_gas(params, 0, 3 * GAS_PER_BLOB, high_base_fee, SpecId::OSAKA),
+            0
+        );
+    }
 }
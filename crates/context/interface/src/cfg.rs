@@ -3,11 +3,13 @@
 pub mod gas;
 pub mod gas_params;
 
-pub use gas_params::{GasId, GasParams};
+pub use gas_params::{GasId, GasParamDiff, GasParams};
 
 use auto_impl::auto_impl;
 use core::{fmt::Debug, hash::Hash};
-use primitives::{hardfork::SpecId, Address, TxKind, U256};
+use primitives::{
+    eip1559::BaseFeeParams, eip4844::BlobParams, hardfork::SpecId, Address, TxKind, U256,
+};
 
 /// Configuration for the EVM.
 #[auto_impl(&, &mut, Box, Arc)]
@@ -91,6 +93,43 @@ pub trait Cfg {
     /// Returns the gas params for the EVM.
     fn gas_params(&self) -> &GasParams;
 
+    /// Returns the EIP-1559 base fee parameters of the chain.
+    ///
+    /// Defaults to [`BaseFeeParams::ETHEREUM`].
+    fn base_fee_params(&self) -> BaseFeeParams {
+        BaseFeeParams::ETHEREUM
+    }
+
+    /// Returns the blob parameters of the spec, [`None`] if blobs are not enabled.
+    ///
+    /// Defaults to the Ethereum mainnet parameters, see [`BlobParams::mainnet`].
+    fn blob_params(&self) -> Option<BlobParams> {
+        BlobParams::mainnet(self.spec().into())
+    }
+
+    /// Returns the function that overrides the address derivation of created contracts.
+    ///
+    /// Defaults to `None`, contracts are created at the standard `CREATE`/`CREATE2` address.
+    fn create_address_fn(&self) -> Option<CreateAddressFn> {
+        None
+    }
+
+    /// Returns the policy of the gas refund applied at the end of the transaction.
+    ///
+    /// Defaults to [`RefundPolicy::GasParams`], the refund is capped by the
+    /// [`GasParams::max_refund_quotient`].
+    fn refund_policy(&self) -> RefundPolicy {
+        RefundPolicy::default()
+    }
+
+    /// Returns the policy of the gas forwarded to the frames of calls and creates.
+    ///
+    /// Defaults to [`CallGasPolicy::GasParams`], the EIP-150 rule that retains
+    /// `1/64` of the remaining gas on mainnet.
+    fn call_gas_policy(&self) -> CallGasPolicy {
+        CallGasPolicy::default()
+    }
+
     /// Returns whether EIP-8037 (Amsterdam) state creation gas cost increase is enabled.
     ///
     /// When enabled, storage creation gas is tracked separately from regular gas
@@ -122,6 +161,75 @@ pub enum AnalysisKind {
 /// Transaction destination
 pub type TransactTo = TxKind;
 
+/// Policy of the gas refund applied at the end of the transaction.
+///
+/// Refunds of the individual operations, e.g. clearing a storage slot, are configured in the
+/// [`GasParams`], the policy decides how the accumulated refund is capped.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum RefundPolicy {
+    /// Refund is capped by the gas used divided by the [`GasParams::max_refund_quotient`] of the
+    /// spec, `2` before London and `5` since EIP-3529.
+    #[default]
+    GasParams,
+    /// Refund is capped by the gas used divided by the quotient.
+    ///
+    /// Quotient of `0` or `1` caps the refund only by the gas used.
+    Capped(u64),
+    /// Refunds are disabled, including the EIP-7702 refund of authorizations to existing
+    /// accounts.
+    Disabled,
+}
+
+impl RefundPolicy {
+    /// Returns the quotient the gas used is divided by to cap the refund, [`None`] if refunds are
+    /// disabled.
+    pub fn max_refund_quotient(&self, gas_params: &GasParams) -> Option<u64> {
+        match self {
+            Self::GasParams => Some(gas_params.max_refund_quotient()),
+            Self::Capped(quotient) => Some((*quotient).max(1)),
+            Self::Disabled => None,
+        }
+    }
+}
+
+/// Policy of the gas forwarded to the frame of a `CALL`-like or `CREATE`-like opcode.
+///
+/// Applies since EIP-150 (Tangerine), before it the requested gas is forwarded as is. Gas of a
+/// call is still capped by the gas requested on the stack.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum CallGasPolicy {
+    /// Caller retains the remaining gas divided by the
+    /// [`GasId::call_stipend_reduction`] quotient, `1/64` on mainnet.
+    #[default]
+    GasParams,
+    /// All remaining gas is forwarded.
+    All,
+    /// Caller retains a fixed amount of gas, the rest is forwarded.
+    FixedReserve(u64),
+}
+
+impl CallGasPolicy {
+    /// Returns the maximum gas forwarded to the new frame out of the remaining gas of the caller.
+    pub fn max_forwarded_gas(&self, gas_params: &GasParams, remaining: u64) -> u64 {
+        match self {
+            Self::GasParams => gas_params.call_stipend_reduction(remaining),
+            Self::All => remaining,
+            Self::FixedReserve(reserve) => remaining.saturating_sub(*reserve),
+        }
+    }
+}
+
+/// Derives the address of a contract created by `caller` with the given scheme, caller nonce
+/// and init code.
+///
+/// Used to override the standard `CREATE`/`CREATE2` address, e.g. for chain-specific
+/// namespacing or deterministic deployment schemes. Returning `None` falls back to the
+/// standard address.
+pub type CreateAddressFn =
+    fn(caller: Address, scheme: CreateScheme, nonce: u64, init_code: &[u8]) -> Option<Address>;
+
 /// Create scheme
 #[derive(Clone, Copy, Default, Debug, Eq, PartialEq, Hash)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -140,3 +248,29 @@ pub enum CreateScheme {
         address: Address,
     },
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn call_gas_policies() {
+        let gas_params = GasParams::new_spec(SpecId::PRAGUE);
+        assert_eq!(
+            CallGasPolicy::GasParams.max_forwarded_gas(&gas_params, 6400),
+            6300
+        );
+        assert_eq!(
+            CallGasPolicy::All.max_forwarded_gas(&gas_params, 6400),
+            6400
+        );
+        assert_eq!(
+            CallGasPolicy::FixedReserve(5000).max_forwarded_gas(&gas_params, 6400),
+            1400
+        );
+        assert_eq!(
+            CallGasPolicy::FixedReserve(5000).max_forwarded_gas(&gas_params, 4000),
+            0
+        );
+    }
+}
@@ -12,7 +12,7 @@ use primitives::{
     hardfork::SpecId::{self},
     OnceLock, U256,
 };
-use std::sync::Arc;
+use std::{sync::Arc, vec::Vec};
 
 /// Gas table for dynamic gas constants.
 #[derive(Clone)]
@@ -127,6 +127,17 @@ impl GasParams {
         &self.table
     }
 
+    /// Returns the gas ids whose values differ in `other`, ordered by id.
+    pub fn diff(&self, other: &Self) -> Vec<GasParamDiff> {
+        (0..=u8::MAX)
+            .map(GasId::new)
+            .filter_map(|id| {
+                let value = (self.get(id), other.get(id));
+                (value.0 != value.1).then_some(GasParamDiff { id, value })
+            })
+            .collect()
+    }
+
     /// Creates a new `GasParams` for the given spec.
     #[inline(never)]
     pub fn new_spec(spec: SpecId) -> Self {
@@ -1189,6 +1200,15 @@ pub(crate) const fn log2floor(value: U256) -> u64 {
     255u64.saturating_sub(value.leading_zeros() as u64)
 }
 
+/// Difference of a single gas parameter between two tables, see [`GasParams::diff`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct GasParamDiff {
+    /// Gas identifier.
+    pub id: GasId,
+    /// Value in the first and the second table.
+    pub value: (u64, u64),
+}
+
 /// Gas identifier that maps onto index in gas table.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct GasId(u8);
@@ -1688,6 +1708,27 @@ mod tests {
         }
     }
 
+    #[test]
+    fn gas_params_diff() {
+        let cancun = GasParams::new_spec(SpecId::CANCUN);
+        assert!(cancun.diff(&GasParams::new_spec(SpecId::CANCUN)).is_empty());
+
+        let mut repriced = cancun.clone();
+        repriced.override_gas([(GasId::logtopic(), 1000)]);
+        assert_eq!(
+            cancun.diff(&repriced),
+            [GasParamDiff {
+                id: GasId::logtopic(),
+                value: (gas::LOGTOPIC, 1000),
+            }]
+        );
+
+        let diff = GasParams::new_spec(SpecId::ISTANBUL).diff(&GasParams::new_spec(SpecId::BERLIN));
+        assert!(diff
+            .iter()
+            .any(|diff| diff.id == GasId::cold_account_additional_cost()));
+    }
+
     #[test]
     fn test_gas_id_name_and_from_str_coverage() {
         let mut unique_names = HashSet::new();
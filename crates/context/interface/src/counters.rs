@@ -0,0 +1,54 @@
+//! Hot-path counters of the execution, see [`ExecutionCounters`].
+use core::ops::AddAssign;
+
+/// Counters of the operations performed while executing a transaction.
+///
+/// Counters give a cheap execution profile for monitoring, without the overhead of an inspector.
+/// They are only updated when the `counters` feature of the interpreter, context and handler
+/// crates is enabled, and are read from [`LocalContextTr::counters`](crate::LocalContextTr::counters)
+/// after the transaction is executed.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ExecutionCounters {
+    /// Number of executed instructions.
+    pub instructions: u64,
+    /// Number of memory expansions.
+    pub memory_expansions: u64,
+    /// Number of call and create frames requested by the executed instructions.
+    pub calls: u64,
+    /// Number of storage loads.
+    pub sloads: u64,
+    /// Number of storage stores.
+    pub sstores: u64,
+    /// Number of accounts loaded cold.
+    pub cold_account_loads: u64,
+    /// Number of storage slots loaded cold, by either a load or a store.
+    pub cold_storage_loads: u64,
+}
+
+impl ExecutionCounters {
+    /// Creates counters set to zero.
+    pub const fn new() -> Self {
+        Self {
+            instructions: 0,
+            memory_expansions: 0,
+            calls: 0,
+            sloads: 0,
+            sstores: 0,
+            cold_account_loads: 0,
+            cold_storage_loads: 0,
+        }
+    }
+}
+
+impl AddAssign for ExecutionCounters {
+    fn add_assign(&mut self, rhs: Self) {
+        self.instructions += rhs.instructions;
+        self.memory_expansions += rhs.memory_expansions;
+        self.calls += rhs.calls;
+        self.sloads += rhs.sloads;
+        self.sstores += rhs.sstores;
+        self.cold_account_loads += rhs.cold_account_loads;
+        self.cold_storage_loads += rhs.cold_storage_loads;
+    }
+}
@@ -1,7 +1,7 @@
 //! Host interface for external blockchain state access.
 
 use crate::{
-    cfg::GasParams,
+    cfg::{CallGasPolicy, CreateAddressFn, GasParams},
     context::{SStoreResult, SelfDestructResult, StateLoad},
     journaled_state::{AccountInfoLoad, AccountLoad},
 };
@@ -69,6 +69,18 @@ pub trait Host {
     /// Returns whether state gas (EIP-8037) is enabled.
     fn is_amsterdam_eip8037_enabled(&self) -> bool;
 
+    /// Returns the function that overrides the address derivation of created contracts,
+    /// calls `ContextTr::cfg().create_address_fn()`.
+    fn create_address_fn(&self) -> Option<CreateAddressFn> {
+        None
+    }
+
+    /// Returns the policy of the gas forwarded to new frames,
+    /// calls `ContextTr::cfg().call_gas_policy()`.
+    fn call_gas_policy(&self) -> CallGasPolicy {
+        CallGasPolicy::default()
+    }
+
     /* Database */
 
     /// Block hash, calls `ContextTr::journal_mut().db().block_hash(number)`
@@ -8,6 +8,7 @@ extern crate alloc as std;
 pub mod block;
 pub mod cfg;
 pub mod context;
+pub mod counters;
 pub mod host;
 pub mod journaled_state;
 pub mod local;
@@ -19,8 +20,9 @@ pub use primitives;
 pub use state;
 
 pub use block::Block;
-pub use cfg::{Cfg, CreateScheme, TransactTo};
+pub use cfg::{Cfg, CreateAddressFn, CreateScheme, TransactTo};
 pub use context::{ContextError, ContextSetters, ContextTr};
+pub use counters::ExecutionCounters;
 pub use database_interface::{erased_error::ErasedError, DBErrorMarker, Database};
 pub use either;
 pub use host::{DummyHost, Host};
@@ -1,4 +1,5 @@
 //! Local context trait [`LocalContextTr`] and related types.
+use crate::ExecutionCounters;
 use core::{
     cell::{Ref, RefCell},
     ops::Range,
@@ -234,6 +235,18 @@ pub trait LocalContextTr {
     ///
     /// Returns `Some(String)` if a precompile error message was recorded.
     fn take_precompile_error_context(&mut self) -> Option<String>;
+
+    /// Counters of the last executed transaction, see [`ExecutionCounters`].
+    ///
+    /// Returns [`None`] if the local context does not collect counters.
+    fn counters(&self) -> Option<&ExecutionCounters> {
+        None
+    }
+
+    /// Mutable counters of the executing transaction, see [`LocalContextTr::counters`].
+    fn counters_mut(&mut self) -> Option<&mut ExecutionCounters> {
+        None
+    }
 }
 
 #[cfg(test)]
@@ -7,11 +7,14 @@
 //! [`InvalidHeader`] is the error that is returned when the header is invalid.
 //!
 //! [`SuccessReason`] is the reason that the transaction successfully completed.
-use crate::{context::ContextError, transaction::TransactionError};
+use crate::{
+    context::ContextError,
+    transaction::{BlobSidecarError, TransactionError},
+};
 use core::fmt::{self, Debug};
 use database_interface::DBErrorMarker;
 use primitives::{Address, Bytes, Log, U256};
-use state::EvmState;
+use state::{EvmState, TouchedAccounts};
 use std::{borrow::Cow, boxed::Box, string::String, sync::Arc, vec::Vec};
 
 /// Trait for the halt reason.
@@ -42,6 +45,14 @@ impl<R, S> ExecResultAndState<R, S> {
     }
 }
 
+impl<R> ExecResultAndState<R, EvmState> {
+    /// Returns the summary of the accounts read, written, created and destroyed by the
+    /// execution, without their info and storage.
+    pub fn touched_accounts(&self) -> TouchedAccounts {
+        TouchedAccounts::from_state(&self.state)
+    }
+}
+
 /// Gas accounting result from transaction execution.
 ///
 /// Self-contained gas snapshot with all values needed for downstream consumers.
@@ -926,6 +937,8 @@ pub enum InvalidTransaction {
     },
     /// Blob transaction contains a versioned hash with an incorrect version
     BlobVersionNotSupported,
+    /// Blob sidecar of the transaction does not match its versioned hashes.
+    InvalidBlobSidecar(BlobSidecarError),
     /// EIP-7702 is not enabled.
     AuthorizationListNotSupported,
     /// EIP-7702 transaction has invalid fields set.
@@ -1033,6 +1046,7 @@ impl fmt::Display for InvalidTransaction {
                 write!(f, "too many blobs, have {have}, max {max}")
             }
             Self::BlobVersionNotSupported => write!(f, "blob version not supported"),
+            Self::InvalidBlobSidecar(e) => write!(f, "invalid blob sidecar: {e}"),
             Self::AuthorizationListNotSupported => write!(f, "authorization list not supported"),
             Self::AuthorizationListInvalidFields => {
                 write!(f, "authorization list tx has invalid fields")
@@ -1142,6 +1156,12 @@ pub enum HaltReason {
     OutOfFunds,
     /// Call is too deep.
     CallTooDeep,
+    /// Invariant checked by an inspector was violated.
+    InvariantViolation,
+    /// Instruction budget set by an inspector was exhausted.
+    StepLimitExceeded,
+    /// Wall-clock time limit set by an inspector was exceeded.
+    TimeLimitExceeded,
 }
 
 impl core::error::Error for HaltReason {}
@@ -1171,6 +1191,9 @@ impl fmt::Display for HaltReason {
             Self::CallNotAllowedInsideStatic => write!(f, "call not allowed inside static call"),
             Self::OutOfFunds => write!(f, "out of funds"),
             Self::CallTooDeep => write!(f, "call too deep"),
+            Self::InvariantViolation => write!(f, "invariant violation"),
+            Self::StepLimitExceeded => write!(f, "step limit exceeded"),
+            Self::TimeLimitExceeded => write!(f, "time limit exceeded"),
         }
     }
 }
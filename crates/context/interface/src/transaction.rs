@@ -1,6 +1,7 @@
 //! Transaction trait [`Transaction`] and associated types.
 mod alloy_types;
 pub mod eip2930;
+pub mod eip4844;
 pub mod eip7702;
 mod either;
 pub mod transaction_type;
@@ -10,6 +11,7 @@ pub use alloy_types::{
     SignedAuthorization,
 };
 pub use eip2930::AccessListItemTr;
+pub use eip4844::{BlobSidecar, BlobSidecarError};
 pub use eip7702::AuthorizationTr;
 pub use transaction_type::TransactionType;
 
@@ -101,6 +103,13 @@ pub trait Transaction {
     /// Note : EIP-4844 transaction field.
     fn max_fee_per_blob_gas(&self) -> u128;
 
+    /// Blobs, commitments and proofs of the versioned hashes, if they are known.
+    ///
+    /// Note : EIP-4844 field that is not part of the signed transaction.
+    fn blob_sidecar(&self) -> Option<&BlobSidecar> {
+        None
+    }
+
     /// Total gas for all blobs. Max number of blocks is already checked
     /// so we dont need to check for overflow.
     fn total_blob_gas(&self) -> u64 {
@@ -0,0 +1,104 @@
+//! EIP-4844 blob sidecar.
+use core::fmt;
+use primitives::{Bytes, FixedBytes};
+use std::vec::Vec;
+
+/// Blobs of an EIP-4844 transaction with their KZG commitments and proofs.
+///
+/// The sidecar is not part of the transaction that is executed, it is gossiped alongside it.
+/// Block builders simulating blob transactions can attach it to the transaction to have it
+/// validated against the versioned hashes of the transaction.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BlobSidecar {
+    /// Blobs, each [`BYTES_PER_BLOB`](primitives::eip4844::BYTES_PER_BLOB) long.
+    pub blobs: Vec<Bytes>,
+    /// KZG commitments of the blobs.
+    pub commitments: Vec<FixedBytes<48>>,
+    /// KZG proofs of the blobs.
+    pub proofs: Vec<FixedBytes<48>>,
+}
+
+impl BlobSidecar {
+    /// Creates a new sidecar.
+    pub const fn new(
+        blobs: Vec<Bytes>,
+        commitments: Vec<FixedBytes<48>>,
+        proofs: Vec<FixedBytes<48>>,
+    ) -> Self {
+        Self {
+            blobs,
+            commitments,
+            proofs,
+        }
+    }
+
+    /// Returns the number of blobs.
+    pub fn len(&self) -> usize {
+        self.blobs.len()
+    }
+
+    /// Returns `true` if the sidecar has no blobs.
+    pub fn is_empty(&self) -> bool {
+        self.blobs.is_empty()
+    }
+}
+
+/// Error of the validation of a [`BlobSidecar`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum BlobSidecarError {
+    /// Number of blobs, commitments or proofs does not match the number of versioned hashes.
+    LengthMismatch {
+        /// Number of versioned hashes of the transaction.
+        versioned_hashes: usize,
+        /// Number of blobs.
+        blobs: usize,
+        /// Number of commitments.
+        commitments: usize,
+        /// Number of proofs.
+        proofs: usize,
+    },
+    /// Blob is not [`BYTES_PER_BLOB`](primitives::eip4844::BYTES_PER_BLOB) long.
+    InvalidBlobLength {
+        /// Index of the blob.
+        index: usize,
+    },
+    /// Versioned hash does not match the commitment.
+    VersionedHashMismatch {
+        /// Index of the versioned hash.
+        index: usize,
+    },
+    /// Batch verification of the blob proofs failed.
+    InvalidProof,
+    /// KZG library rejected the input, e.g. a blob with a non canonical field element.
+    Kzg,
+    /// No KZG library with blob verification is compiled in.
+    Unsupported,
+}
+
+impl fmt::Display for BlobSidecarError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::LengthMismatch {
+                versioned_hashes,
+                blobs,
+                commitments,
+                proofs,
+            } => write!(
+                f,
+                "sidecar length mismatch, {versioned_hashes} versioned hashes, {blobs} blobs, \
+                 {commitments} commitments, {proofs} proofs"
+            ),
+            Self::InvalidBlobLength { index } => write!(f, "invalid length of blob {index}"),
+            Self::VersionedHashMismatch { index } => {
+                write!(f, "versioned hash {index} does not match the commitment")
+            }
+            Self::InvalidProof => write!(f, "invalid blob proof"),
+            Self::Kzg => write!(f, "invalid KZG input"),
+            Self::Unsupported => write!(f, "blob verification is not supported"),
+        }
+    }
+}
+
+impl core::error::Error for BlobSidecarError {}
@@ -1,4 +1,4 @@
-use super::Transaction;
+use super::{BlobSidecar, Transaction};
 use either::Either;
 use primitives::{Address, Bytes, TxKind, B256, U256};
 
@@ -98,6 +98,13 @@ where
         }
     }
 
+    fn blob_sidecar(&self) -> Option<&BlobSidecar> {
+        match self {
+            Either::Left(l) => l.blob_sidecar(),
+            Either::Right(r) => r.blob_sidecar(),
+        }
+    }
+
     fn authorization_list_len(&self) -> usize {
         match self {
             Either::Left(l) => l.authorization_list_len(),
@@ -1,6 +1,79 @@
 //! This module contains [`BlockEnv`] and it implements [`Block`] trait.
-use context_interface::block::{BlobExcessGasAndPrice, Block};
-use primitives::{eip4844::BLOB_BASE_FEE_UPDATE_FRACTION_PRAGUE, Address, B256, U256};
+use context_interface::{
+    block::{calc_next_excess_blob_gas, BlobExcessGasAndPrice, Block},
+    Cfg,
+};
+use primitives::{
+    eip1559::INITIAL_BASE_FEE, eip4844::BLOB_BASE_FEE_UPDATE_FRACTION_PRAGUE, hardfork::SpecId,
+    Address, B256, U256,
+};
+
+#[cfg(feature = "alloy")]
+mod header;
+
+/// The gas limit of a block can change by at most `1 / GAS_LIMIT_BOUND_DIVISOR` of the gas
+/// limit of its parent.
+pub const GAS_LIMIT_BOUND_DIVISOR: u64 = 1024;
+
+/// Minimum gas limit of a block.
+pub const MIN_GAS_LIMIT: u64 = 5000;
+
+/// Fields of the latest block header that the next block is derived from.
+///
+/// See [`BlockEnv::next_block`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ParentHeader {
+    /// Number of the block.
+    pub number: u64,
+    /// Timestamp of the block.
+    pub timestamp: u64,
+    /// Beneficiary of the block.
+    pub beneficiary: Address,
+    /// Gas limit of the block.
+    pub gas_limit: u64,
+    /// Gas used by the block.
+    pub gas_used: u64,
+    /// Base fee of the block, [`None`] before London.
+    pub base_fee: Option<u64>,
+    /// Mix hash of the block, the `prevrandao` after the merge.
+    pub mix_hash: B256,
+    /// Excess blob gas of the block, [`None`] before Cancun.
+    pub excess_blob_gas: Option<u64>,
+    /// Blob gas used by the block, [`None`] before Cancun.
+    pub blob_gas_used: Option<u64>,
+    /// Slot number of the block.
+    pub slot_num: u64,
+}
+
+impl ParentHeader {
+    /// Returns the gas limit of the next block, moved towards the desired gas limit by at most
+    /// the step allowed by [`GAS_LIMIT_BOUND_DIVISOR`].
+    pub const fn next_gas_limit(&self, desired_gas_limit: u64) -> u64 {
+        let parent = self.gas_limit;
+        let delta = (parent / GAS_LIMIT_BOUND_DIVISOR).saturating_sub(1);
+        let desired = if desired_gas_limit < MIN_GAS_LIMIT {
+            MIN_GAS_LIMIT
+        } else {
+            desired_gas_limit
+        };
+        if parent < desired {
+            let limit = parent.saturating_add(delta);
+            if limit > desired {
+                desired
+            } else {
+                limit
+            }
+        } else {
+            let limit = parent - delta;
+            if limit < desired {
+                desired
+            } else {
+                limit
+            }
+        }
+    }
+}
 
 /// The block environment
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
@@ -49,6 +122,51 @@ pub struct BlockEnv {
 }
 
 impl BlockEnv {
+    /// Returns the environment of the block following the parent, e.g. to simulate
+    /// transactions against the pending block.
+    ///
+    /// * The number is incremented and the timestamp is clamped to be after the parent.
+    /// * The gas limit is inherited, use [`ParentHeader::next_gas_limit`] to move it.
+    /// * The base fee is projected with the [`Cfg::base_fee_params`], [`INITIAL_BASE_FEE`] on
+    ///   the first London block.
+    /// * The excess blob gas is projected with the [`Cfg::blob_params`].
+    /// * The beneficiary and the `prevrandao` are inherited as the ones of the next block are
+    ///   not known yet.
+    pub fn next_block(parent: &ParentHeader, cfg: impl Cfg, timestamp: u64) -> Self {
+        let spec: SpecId = cfg.spec().into();
+        let basefee = match parent.base_fee {
+            _ if !spec.is_enabled_in(SpecId::LONDON) => 0,
+            Some(base_fee) => {
+                cfg.base_fee_params()
+                    .next_base_fee(parent.gas_used, parent.gas_limit, base_fee)
+            }
+            None => INITIAL_BASE_FEE,
+        };
+        let blob_excess_gas_and_price = cfg.blob_params().map(|params| {
+            let excess_blob_gas = calc_next_excess_blob_gas(
+                params,
+                parent.excess_blob_gas.unwrap_or_default(),
+                parent.blob_gas_used.unwrap_or_default(),
+                parent.base_fee.unwrap_or_default(),
+                spec,
+            );
+            BlobExcessGasAndPrice::new(excess_blob_gas, params.base_fee_update_fraction)
+        });
+        let is_merge = spec.is_enabled_in(SpecId::MERGE);
+
+        Self {
+            number: U256::from(parent.number.saturating_add(1)),
+            beneficiary: parent.beneficiary,
+            timestamp: U256::from(timestamp.max(parent.timestamp.saturating_add(1))),
+            gas_limit: parent.gas_limit,
+            basefee,
+            difficulty: U256::ZERO,
+            prevrandao: is_merge.then_some(parent.mix_hash),
+            blob_excess_gas_and_price,
+            slot_num: parent.slot_num.saturating_add(1),
+        }
+    }
+
     /// Takes `blob_excess_gas` saves it inside env
     /// and calculates `blob_fee` with [`BlobExcessGasAndPrice`].
     pub fn set_blob_excess_gas_and_price(
@@ -128,3 +246,56 @@ impl Default for BlockEnv {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CfgEnv;
+    use primitives::eip4844::{BlobParams, GAS_PER_BLOB};
+
+    fn parent() -> ParentHeader {
+        ParentHeader {
+            number: 100,
+            timestamp: 1_000,
+            gas_limit: 30_000_000,
+            gas_used: 30_000_000,
+            base_fee: Some(INITIAL_BASE_FEE),
+            excess_blob_gas: Some(0),
+            blob_gas_used: Some(BlobParams::PRAGUE.max_blob_gas_per_block()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn next_block() {
+        let cfg = CfgEnv::new_with_spec(SpecId::PRAGUE);
+        let block = BlockEnv::next_block(&parent(), &cfg, 1_012);
+        assert_eq!(block.number, U256::from(101));
+        assert_eq!(block.timestamp, U256::from(1_012));
+        assert_eq!(block.gas_limit, 30_000_000);
+        // Full parent block increases the base fee by 12.5%.
+        assert_eq!(block.basefee, 1_125_000_000);
+        assert_eq!(
+            block.blob_excess_gas_and_price.unwrap().excess_blob_gas,
+            3 * GAS_PER_BLOB
+        );
+
+        // Timestamp is clamped to be after the parent.
+        let block = BlockEnv::next_block(&parent(), &cfg, 0);
+        assert_eq!(block.timestamp, U256::from(1_001));
+
+        // No blobs before Cancun.
+        let block = BlockEnv::next_block(&parent(), CfgEnv::new_with_spec(SpecId::SHANGHAI), 0);
+        assert_eq!(block.blob_excess_gas_and_price, None);
+    }
+
+    #[test]
+    fn next_gas_limit() {
+        let parent = parent();
+        // Step is `30_000_000 / 1024 - 1`.
+        assert_eq!(parent.next_gas_limit(60_000_000), 30_029_295);
+        assert_eq!(parent.next_gas_limit(30_010_000), 30_010_000);
+        assert_eq!(parent.next_gas_limit(0), 29_970_705);
+        assert_eq!(parent.next_gas_limit(30_000_000), 30_000_000);
+    }
+}
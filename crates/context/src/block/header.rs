@@ -0,0 +1,135 @@
+//! Conversions of alloy block headers into [`BlockEnv`] and [`ParentHeader`].
+use super::{BlockEnv, ParentHeader};
+use alloy_consensus::Header;
+use context_interface::{block::BlobExcessGasAndPrice, Cfg};
+use primitives::{hardfork::SpecId, U256};
+
+impl BlockEnv {
+    /// Creates the environment of the block with the given header.
+    ///
+    /// The RPC block type of alloy dereferences to the header, so `&block.header` can be passed
+    /// directly.
+    ///
+    /// Fields that depend on the spec are selected by the spec of `cfg`:
+    /// * Before the merge `prevrandao` is [`None`] and `DIFFICULTY` returns the difficulty,
+    ///   after it `prevrandao` is the mix hash of the header.
+    /// * Blob gas price is derived from the excess blob gas with the [`Cfg::blob_params`], it is
+    ///   [`None`] before Cancun.
+    pub fn from_header(header: &Header, cfg: impl Cfg) -> Self {
+        let spec: SpecId = cfg.spec().into();
+        let blob_excess_gas_and_price = cfg.blob_params().map(|params| {
+            BlobExcessGasAndPrice::new(
+                header.excess_blob_gas.unwrap_or_default(),
+                params.base_fee_update_fraction,
+            )
+        });
+        Self {
+            number: U256::from(header.number),
+            beneficiary: header.beneficiary,
+            timestamp: U256::from(header.timestamp),
+            gas_limit: header.gas_limit,
+            basefee: header.base_fee_per_gas.unwrap_or_default(),
+            difficulty: header.difficulty,
+            prevrandao: spec.is_enabled_in(SpecId::MERGE).then_some(header.mix_hash),
+            blob_excess_gas_and_price,
+            slot_num: 0,
+        }
+    }
+
+    /// Creates the environment of the block from an RPC block object, e.g. a
+    /// `serde_json::Value` returned by `eth_getBlockByNumber`.
+    ///
+    /// Only the header fields are read, see [`BlockEnv::from_header`].
+    #[cfg(feature = "serde")]
+    pub fn from_rpc_block<'de, D: serde::Deserializer<'de>>(
+        block: D,
+        cfg: impl Cfg,
+    ) -> Result<Self, D::Error> {
+        let header = <Header as serde::Deserialize>::deserialize(block)?;
+        Ok(Self::from_header(&header, cfg))
+    }
+}
+
+impl From<&Header> for ParentHeader {
+    fn from(header: &Header) -> Self {
+        Self {
+            number: header.number,
+            timestamp: header.timestamp,
+            beneficiary: header.beneficiary,
+            gas_limit: header.gas_limit,
+            gas_used: header.gas_used,
+            base_fee: header.base_fee_per_gas,
+            mix_hash: header.mix_hash,
+            excess_blob_gas: header.excess_blob_gas,
+            blob_gas_used: header.blob_gas_used,
+            slot_num: 0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CfgEnv;
+    use primitives::{address, b256, eip4844::BLOB_BASE_FEE_UPDATE_FRACTION_PRAGUE};
+
+    fn header() -> Header {
+        Header {
+            number: 20_000_000,
+            beneficiary: address!("0x95222290dd7278aa3ddd389cc1e1d165cc4bafe5"),
+            timestamp: 1_717_281_407,
+            gas_limit: 30_000_000,
+            gas_used: 11_000_000,
+            base_fee_per_gas: Some(5_000_000_000),
+            mix_hash: b256!("0x0101010101010101010101010101010101010101010101010101010101010101"),
+            excess_blob_gas: Some(393_216),
+            blob_gas_used: Some(131_072),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn from_header() {
+        let block = BlockEnv::from_header(&header(), CfgEnv::new_with_spec(SpecId::PRAGUE));
+        assert_eq!(block.number, U256::from(20_000_000));
+        assert_eq!(block.timestamp, U256::from(1_717_281_407));
+        assert_eq!(block.basefee, 5_000_000_000);
+        assert_eq!(block.prevrandao, Some(header().mix_hash));
+        assert_eq!(
+            block.blob_excess_gas_and_price,
+            Some(BlobExcessGasAndPrice::new(
+                393_216,
+                BLOB_BASE_FEE_UPDATE_FRACTION_PRAGUE
+            ))
+        );
+
+        // Difficulty is used and there are no blobs before the merge.
+        let mut header = header();
+        header.difficulty = U256::from(1_000);
+        let block = BlockEnv::from_header(&header, CfgEnv::new_with_spec(SpecId::LONDON));
+        assert_eq!(block.prevrandao, None);
+        assert_eq!(block.difficulty, U256::from(1_000));
+        assert_eq!(block.blob_excess_gas_and_price, None);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn from_rpc_block() {
+        let mut block = serde_json::to_value(header()).unwrap();
+        block["hash"] = serde_json::json!(primitives::B256::ZERO);
+        block["transactions"] = serde_json::json!([]);
+        let block =
+            BlockEnv::from_rpc_block(&block, CfgEnv::new_with_spec(SpecId::PRAGUE)).unwrap();
+        assert_eq!(
+            block,
+            BlockEnv::from_header(&header(), CfgEnv::new_with_spec(SpecId::PRAGUE))
+        );
+    }
+
+    #[test]
+    fn parent_header() {
+        let parent = ParentHeader::from(&header());
+        assert_eq!(parent.gas_used, 11_000_000);
+        assert_eq!(parent.blob_gas_used, Some(131_072));
+    }
+}
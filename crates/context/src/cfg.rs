@@ -1,8 +1,14 @@
 //! This module contains [`CfgEnv`] and implements [`Cfg`] trait for it.
 pub use context_interface::Cfg;
 
-use context_interface::cfg::GasParams;
-use primitives::{eip170, eip3860, eip7825, eip7954, hardfork::SpecId};
+use context_interface::cfg::{CallGasPolicy, CreateAddressFn, GasParams, RefundPolicy};
+use primitives::{
+    eip1559::BaseFeeParams,
+    eip170, eip3860,
+    eip4844::{BlobParams, BlobSchedule},
+    eip7825, eip7954,
+    hardfork::SpecId,
+};
 
 /// EVM configuration
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -48,9 +54,9 @@ pub struct CfgEnv<SPEC = SpecId> {
     pub limit_contract_initcode_size: Option<usize>,
     /// Skips the nonce validation against the account's nonce
     pub disable_nonce_check: bool,
-    /// Blob max count. EIP-7840 Add blob schedule to EL config files.
+    /// Blob max count per transaction override.
     ///
-    /// If this config is not set, the check for max blobs will be skipped.
+    /// If this config is not set, the max count of the [`CfgEnv::blob_schedule`] is used.
     pub max_blobs_per_tx: Option<u64>,
     /// Blob base fee update fraction. EIP-4844 Blob base fee update fraction.
     ///
@@ -60,6 +66,11 @@ pub struct CfgEnv<SPEC = SpecId> {
     /// Default values for Cancun is [`primitives::eip4844::BLOB_BASE_FEE_UPDATE_FRACTION_CANCUN`]
     /// and for Prague is [`primitives::eip4844::BLOB_BASE_FEE_UPDATE_FRACTION_PRAGUE`].
     pub blob_base_fee_update_fraction: Option<u64>,
+    /// Blob parameters per spec. EIP-7840 Add blob schedule to EL config files.
+    ///
+    /// Defaults to [`BlobSchedule::mainnet`]. [`CfgEnv::max_blobs_per_tx`] and
+    /// [`CfgEnv::blob_base_fee_update_fraction`] override the values of the schedule.
+    pub blob_schedule: BlobSchedule,
     /// Configures the gas limit cap for the transaction.
     ///
     /// If `None`, default value defined by spec will be used.
@@ -67,6 +78,24 @@ pub struct CfgEnv<SPEC = SpecId> {
     /// Introduced in Osaka in [EIP-7825: Transaction Gas Limit Cap](https://eips.ethereum.org/EIPS/eip-7825)
     /// with initials cap of 30M.
     pub tx_gas_limit_cap: Option<u64>,
+    /// EIP-1559 base fee parameters, used to calculate the base fee of the next block.
+    ///
+    /// Defaults to [`BaseFeeParams::ETHEREUM`]. See also [`CfgEnv::next_base_fee`].
+    pub base_fee_params: BaseFeeParams,
+    /// Overrides the address derivation of contracts created by `CREATE`/`CREATE2` and
+    /// create transactions.
+    ///
+    /// Defaults to `None`, the standard addresses are used.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub create_address_fn: Option<CreateAddressFn>,
+    /// Policy of the gas refund applied at the end of the transaction.
+    ///
+    /// Defaults to [`RefundPolicy::GasParams`].
+    pub refund_policy: RefundPolicy,
+    /// Policy of the gas forwarded to the frames of calls and creates.
+    ///
+    /// Defaults to [`CallGasPolicy::GasParams`].
+    pub call_gas_policy: CallGasPolicy,
     /// A hard memory limit in bytes beyond which
     /// [OutOfGasError::Memory][context_interface::result::OutOfGasError::Memory] cannot be resized.
     ///
@@ -259,8 +288,13 @@ impl<SPEC> CfgEnv<SPEC> {
             spec,
             disable_nonce_check: self.disable_nonce_check,
             tx_gas_limit_cap: self.tx_gas_limit_cap,
+            base_fee_params: self.base_fee_params,
+            create_address_fn: self.create_address_fn,
+            refund_policy: self.refund_policy,
+            call_gas_policy: self.call_gas_policy,
             max_blobs_per_tx: self.max_blobs_per_tx,
             blob_base_fee_update_fraction: self.blob_base_fee_update_fraction,
+            blob_schedule: self.blob_schedule,
             gas_params,
             #[cfg(feature = "memory_limit")]
             memory_limit: self.memory_limit,
@@ -287,6 +321,12 @@ impl<SPEC> CfgEnv<SPEC> {
         }
     }
 
+    /// Sets the blob parameters per spec.
+    pub fn with_blob_schedule(mut self, blob_schedule: BlobSchedule) -> Self {
+        self.blob_schedule = blob_schedule;
+        self
+    }
+
     /// Sets the blob target
     pub const fn with_max_blobs_per_tx(mut self, max_blobs_per_tx: u64) -> Self {
         self.set_max_blobs_per_tx(max_blobs_per_tx);
@@ -298,7 +338,7 @@ impl<SPEC> CfgEnv<SPEC> {
         self.max_blobs_per_tx = Some(max_blobs_per_tx);
     }
 
-    /// Clears the blob target and max count over hardforks.
+    /// Clears the max count override, the max count of the blob schedule is used.
     pub const fn clear_max_blobs_per_tx(&mut self) {
         self.max_blobs_per_tx = None;
     }
@@ -324,6 +364,37 @@ impl<SPEC> CfgEnv<SPEC> {
         self
     }
 
+    /// Sets the EIP-1559 base fee parameters.
+    pub const fn with_base_fee_params(mut self, base_fee_params: BaseFeeParams) -> Self {
+        self.base_fee_params = base_fee_params;
+        self
+    }
+
+    /// Sets the function that overrides the address derivation of created contracts.
+    pub const fn with_create_address_fn(mut self, create_address_fn: CreateAddressFn) -> Self {
+        self.create_address_fn = Some(create_address_fn);
+        self
+    }
+
+    /// Sets the policy of the gas refund applied at the end of the transaction.
+    pub const fn with_refund_policy(mut self, refund_policy: RefundPolicy) -> Self {
+        self.refund_policy = refund_policy;
+        self
+    }
+
+    /// Sets the policy of the gas forwarded to the frames of calls and creates.
+    pub const fn with_call_gas_policy(mut self, call_gas_policy: CallGasPolicy) -> Self {
+        self.call_gas_policy = call_gas_policy;
+        self
+    }
+
+    /// Calculates the base fee of the next block with the configured [`BaseFeeParams`].
+    #[inline]
+    pub const fn next_base_fee(&self, gas_used: u64, gas_limit: u64, base_fee: u64) -> u64 {
+        self.base_fee_params
+            .next_base_fee(gas_used, gas_limit, base_fee)
+    }
+
     /// Sets the enable EIP-8037 (Amsterdam) state creation gas cost flag.
     pub const fn with_enable_amsterdam_eip8037(mut self, enable: bool) -> Self {
         self.enable_amsterdam_eip8037 = enable;
@@ -351,7 +422,12 @@ impl<SPEC: Into<SpecId> + Clone> CfgEnv<SPEC> {
             disable_nonce_check: false,
             max_blobs_per_tx: None,
             tx_gas_limit_cap: None,
+            base_fee_params: BaseFeeParams::ETHEREUM,
+            create_address_fn: None,
+            refund_policy: RefundPolicy::GasParams,
+            call_gas_policy: CallGasPolicy::GasParams,
             blob_base_fee_update_fraction: None,
+            blob_schedule: BlobSchedule::mainnet(),
             gas_params,
             #[cfg(feature = "memory_limit")]
             memory_limit: (1 << 32) - 1,
@@ -380,18 +456,14 @@ impl<SPEC: Into<SpecId> + Clone> CfgEnv<SPEC> {
 
     /// Returns the blob base fee update fraction from [CfgEnv::blob_base_fee_update_fraction].
     ///
-    /// If this field is not set, return the default value for the spec.
-    ///
-    /// Default values for Cancun is [`primitives::eip4844::BLOB_BASE_FEE_UPDATE_FRACTION_CANCUN`]
-    /// and for Prague is [`primitives::eip4844::BLOB_BASE_FEE_UPDATE_FRACTION_PRAGUE`].
+    /// If this field is not set, return the value of the [`CfgEnv::blob_schedule`] for the spec,
+    /// or [`primitives::eip4844::BLOB_BASE_FEE_UPDATE_FRACTION_CANCUN`] before blobs.
     pub fn blob_base_fee_update_fraction(&self) -> u64 {
         self.blob_base_fee_update_fraction.unwrap_or_else(|| {
-            let spec: SpecId = self.spec.clone().into();
-            if spec.is_enabled_in(SpecId::PRAGUE) {
-                primitives::eip4844::BLOB_BASE_FEE_UPDATE_FRACTION_PRAGUE
-            } else {
-                primitives::eip4844::BLOB_BASE_FEE_UPDATE_FRACTION_CANCUN
-            }
+            self.blob_schedule
+                .get(self.spec.clone().into())
+                .map_or(BlobParams::CANCUN, |params| params)
+                .base_fee_update_fraction
         })
     }
 
@@ -456,7 +528,43 @@ impl<SPEC: Into<SpecId> + Clone> Cfg for CfgEnv<SPEC> {
 
     #[inline]
     fn max_blobs_per_tx(&self) -> Option<u64> {
-        self.max_blobs_per_tx
+        self.max_blobs_per_tx.or_else(|| {
+            self.blob_schedule
+                .get(self.spec.clone().into())
+                .map(|params| params.max_blobs_per_tx)
+        })
+    }
+
+    #[inline]
+    fn blob_params(&self) -> Option<BlobParams> {
+        let mut params = self.blob_schedule.get(self.spec.clone().into())?;
+        if let Some(max_blobs_per_tx) = self.max_blobs_per_tx {
+            params.max_blobs_per_tx = max_blobs_per_tx;
+        }
+        if let Some(fraction) = self.blob_base_fee_update_fraction {
+            params.base_fee_update_fraction = fraction;
+        }
+        Some(params)
+    }
+
+    #[inline]
+    fn base_fee_params(&self) -> BaseFeeParams {
+        self.base_fee_params
+    }
+
+    #[inline]
+    fn create_address_fn(&self) -> Option<CreateAddressFn> {
+        self.create_address_fn
+    }
+
+    #[inline]
+    fn refund_policy(&self) -> RefundPolicy {
+        self.refund_policy
+    }
+
+    #[inline]
+    fn call_gas_policy(&self) -> CallGasPolicy {
+        self.call_gas_policy
     }
 
     fn max_code_size(&self) -> usize {
@@ -617,6 +725,33 @@ mod test {
     #[test]
     fn blob_max_and_target_count() {
         let cfg: CfgEnv = Default::default();
+        assert_eq!(cfg.max_blobs_per_tx(), Some(6));
+        assert_eq!(cfg.blob_params(), Some(BlobParams::OSAKA));
+
+        let cfg = CfgEnv::new_with_spec(SpecId::PRAGUE).with_max_blobs_per_tx(12);
+        assert_eq!(cfg.max_blobs_per_tx(), Some(12));
+        assert_eq!(cfg.blob_params().unwrap().max, 9);
+
+        let cfg = CfgEnv::new_with_spec(SpecId::SHANGHAI);
         assert_eq!(cfg.max_blobs_per_tx(), None);
+        assert_eq!(cfg.blob_params(), None);
+    }
+
+    #[test]
+    fn blob_schedule_overrides() {
+        let bpo = BlobParams {
+            target: 10,
+            max: 15,
+            max_blobs_per_tx: 6,
+            base_fee_update_fraction: 8_346_193,
+        };
+        let mut schedule = BlobSchedule::mainnet();
+        schedule.set(SpecId::OSAKA, bpo);
+        let mut cfg = CfgEnv::new_with_spec(SpecId::OSAKA).with_blob_schedule(schedule);
+        assert_eq!(cfg.blob_params(), Some(bpo));
+        assert_eq!(cfg.blob_base_fee_update_fraction(), 8_346_193);
+
+        cfg.blob_base_fee_update_fraction = Some(1);
+        assert_eq!(cfg.blob_params().unwrap().base_fee_update_fraction, 1);
     }
 }
@@ -0,0 +1,195 @@
+//! Consistency check of the configuration, block and transaction environment.
+use context_interface::{Block, Cfg, Transaction};
+use core::fmt;
+use primitives::hardfork::SpecId;
+use std::vec::Vec;
+
+/// Field of the environment that does not match the spec or the other environments, see
+/// [`validate_env`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum EnvMismatch {
+    /// Block has the excess blob gas set before Cancun.
+    ExcessBlobGasBeforeCancun {
+        /// Spec of the configuration.
+        spec: SpecId,
+    },
+    /// Block has no excess blob gas since Cancun.
+    MissingExcessBlobGas {
+        /// Spec of the configuration.
+        spec: SpecId,
+    },
+    /// Transaction has blob hashes or a max fee per blob gas before Cancun.
+    BlobTxBeforeCancun {
+        /// Spec of the configuration.
+        spec: SpecId,
+    },
+    /// Block has no `prevrandao` since the merge.
+    MissingPrevrandao {
+        /// Spec of the configuration.
+        spec: SpecId,
+    },
+    /// Block has a non-zero basefee before London.
+    BasefeeBeforeLondon {
+        /// Spec of the configuration.
+        spec: SpecId,
+        /// Basefee of the block.
+        basefee: u64,
+    },
+    /// Transaction has a max priority fee before London.
+    PriorityFeeBeforeLondon {
+        /// Spec of the configuration.
+        spec: SpecId,
+    },
+    /// Chain id of the transaction differs from the chain id of the configuration.
+    ChainIdMismatch {
+        /// Chain id of the configuration.
+        cfg: u64,
+        /// Chain id of the transaction.
+        tx: u64,
+    },
+}
+
+impl fmt::Display for EnvMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ExcessBlobGasBeforeCancun { spec } => {
+                write!(
+                    f,
+                    "excess blob gas of the block is set in {spec}, before Cancun"
+                )
+            }
+            Self::MissingExcessBlobGas { spec } => {
+                write!(f, "excess blob gas of the block is not set in {spec}")
+            }
+            Self::BlobTxBeforeCancun { spec } => {
+                write!(f, "transaction has blob fields in {spec}, before Cancun")
+            }
+            Self::MissingPrevrandao { spec } => {
+                write!(f, "prevrandao of the block is not set in {spec}")
+            }
+            Self::BasefeeBeforeLondon { spec, basefee } => {
+                write!(
+                    f,
+                    "basefee of the block is {basefee} in {spec}, before London"
+                )
+            }
+            Self::PriorityFeeBeforeLondon { spec } => {
+                write!(f, "transaction has a priority fee in {spec}, before London")
+            }
+            Self::ChainIdMismatch { cfg, tx } => {
+                write!(
+                    f,
+                    "chain id of the transaction is {tx}, configured chain id is {cfg}"
+                )
+            }
+        }
+    }
+}
+
+impl core::error::Error for EnvMismatch {}
+
+/// Checks that the block and transaction fields match the spec of the configuration and that the
+/// transaction targets the configured chain.
+///
+/// Execution ignores fields that do not exist in the spec and the chain id check can be disabled,
+/// so a misconfigured replay, e.g. a Cancun block executed with a Shanghai spec, silently produces
+/// wrong results. Use it when the environment is assembled from external data, before executing.
+///
+/// Returns every mismatch that was found.
+pub fn validate_env(
+    cfg: impl Cfg,
+    block: impl Block,
+    tx: impl Transaction,
+) -> Result<(), Vec<EnvMismatch>> {
+    let spec: SpecId = cfg.spec().into();
+    let mut mismatches = Vec::new();
+
+    let is_cancun = spec.is_enabled_in(SpecId::CANCUN);
+    match (is_cancun, block.blob_excess_gas_and_price().is_some()) {
+        (false, true) => mismatches.push(EnvMismatch::ExcessBlobGasBeforeCancun { spec }),
+        (true, false) => mismatches.push(EnvMismatch::MissingExcessBlobGas { spec }),
+        _ => {}
+    }
+    if !is_cancun && (!tx.blob_versioned_hashes().is_empty() || tx.max_fee_per_blob_gas() != 0) {
+        mismatches.push(EnvMismatch::BlobTxBeforeCancun { spec });
+    }
+
+    if spec.is_enabled_in(SpecId::MERGE) && block.prevrandao().is_none() {
+        mismatches.push(EnvMismatch::MissingPrevrandao { spec });
+    }
+
+    if !spec.is_enabled_in(SpecId::LONDON) {
+        if block.basefee() != 0 {
+            mismatches.push(EnvMismatch::BasefeeBeforeLondon {
+                spec,
+                basefee: block.basefee(),
+            });
+        }
+        if tx.max_priority_fee_per_gas().is_some() {
+            mismatches.push(EnvMismatch::PriorityFeeBeforeLondon { spec });
+        }
+    }
+
+    if let Some(tx_chain_id) = tx.chain_id() {
+        if tx_chain_id != cfg.chain_id() {
+            mismatches.push(EnvMismatch::ChainIdMismatch {
+                cfg: cfg.chain_id(),
+                tx: tx_chain_id,
+            });
+        }
+    }
+
+    if mismatches.is_empty() {
+        Ok(())
+    } else {
+        Err(mismatches)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BlockEnv, CfgEnv, TxEnv};
+
+    #[test]
+    fn consistent_env() {
+        let cfg = CfgEnv::<SpecId>::default();
+        let tx = TxEnv::builder()
+            .chain_id(Some(cfg.chain_id))
+            .build()
+            .unwrap();
+        assert_eq!(validate_env(&cfg, BlockEnv::default(), &tx), Ok(()));
+    }
+
+    #[test]
+    fn mismatched_env() {
+        let cfg = CfgEnv::new_with_spec(SpecId::BERLIN);
+        let block = BlockEnv {
+            basefee: 7,
+            ..Default::default()
+        };
+        let tx = TxEnv::builder()
+            .chain_id(Some(cfg.chain_id + 1))
+            .max_fee_per_blob_gas(1)
+            .build_fill();
+        assert_eq!(
+            validate_env(&cfg, &block, &tx),
+            Err(vec![
+                EnvMismatch::ExcessBlobGasBeforeCancun {
+                    spec: SpecId::BERLIN
+                },
+                EnvMismatch::BlobTxBeforeCancun {
+                    spec: SpecId::BERLIN
+                },
+                EnvMismatch::BasefeeBeforeLondon {
+                    spec: SpecId::BERLIN,
+                    basefee: 7,
+                },
+                EnvMismatch::ChainIdMismatch {
+                    cfg: cfg.chain_id,
+                    tx: cfg.chain_id + 1,
+                },
+            ])
+        );
+    }
+}
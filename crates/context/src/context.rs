@@ -1,7 +1,7 @@
 //! This module contains [`Context`] struct and implements [`ContextTr`] trait for it.
 use crate::{block::BlockEnv, cfg::CfgEnv, journal::Journal, tx::TxEnv, LocalContext};
 use context_interface::{
-    cfg::GasParams,
+    cfg::{CallGasPolicy, CreateAddressFn, GasParams},
     context::{ContextError, ContextSetters, SStoreResult, SelfDestructResult, StateLoad},
     host::LoadError,
     journaled_state::AccountInfoLoad,
@@ -426,6 +426,53 @@ where
     }
 }
 
+impl<BLOCK, TX, CFG, DB, CHAIN, LOCAL> Context<BLOCK, TX, CFG, DB, Journal<DB>, CHAIN, LOCAL>
+where
+    BLOCK: Block + Clone,
+    TX: Transaction + Clone,
+    CFG: Cfg + Clone,
+    DB: Database + DatabaseRef,
+    CHAIN: Clone,
+    LOCAL: LocalContextTr + Default,
+{
+    /// Forks the context into an independent context that reads from this database.
+    ///
+    /// The fork gets a copy of the journal, so the accounts and storage loaded so far stay
+    /// warm, while the database is shared read-only through [`WrapDatabaseRef`]. Changes made
+    /// by the fork never reach this context, which allows branching a simulation at a decision
+    /// point and exploring every path from the same warmed state.
+    ///
+    /// See [`Context::fork_with`] to wrap the shared database, e.g. in a `CacheDB` so the fork
+    /// can commit transactions.
+    pub fn fork(
+        &self,
+    ) -> Context<BLOCK, TX, CFG, WrapDatabaseRef<&DB>, Journal<WrapDatabaseRef<&DB>>, CHAIN, LOCAL>
+    {
+        self.fork_with(WrapDatabaseRef)
+    }
+
+    /// Forks the context like [`Context::fork`], using `wrap` to build the database of the fork
+    /// from the shared database.
+    pub fn fork_with<'a, ODB: Database>(
+        &'a self,
+        wrap: impl FnOnce(&'a DB) -> ODB,
+    ) -> Context<BLOCK, TX, CFG, ODB, Journal<ODB>, CHAIN, LOCAL> {
+        Context {
+            block: self.block.clone(),
+            tx: self.tx.clone(),
+            cfg: self.cfg.clone(),
+            journaled_state: Journal::new_with_inner(
+                wrap(&self.journaled_state.database),
+                self.journaled_state.inner.clone(),
+            ),
+            chain: self.chain.clone(),
+            // The shared memory buffer is not shared with the fork.
+            local: LOCAL::default(),
+            error: Ok(()),
+        }
+    }
+}
+
 impl<
         BLOCK: Block,
         TX: Transaction,
@@ -467,6 +514,14 @@ impl<
         self.cfg().is_amsterdam_eip8037_enabled()
     }
 
+    fn create_address_fn(&self) -> Option<CreateAddressFn> {
+        self.cfg().create_address_fn()
+    }
+
+    fn call_gas_policy(&self) -> CallGasPolicy {
+        self.cfg().call_gas_policy()
+    }
+
     fn block_number(&self) -> U256 {
         self.block().number()
     }
@@ -571,7 +626,8 @@ impl<
         value: StorageValue,
         skip_cold_load: bool,
     ) -> Result<StateLoad<SStoreResult>, LoadError> {
-        self.journal_mut()
+        let res = self
+            .journal_mut()
             .sstore_skip_cold_load(address, key, value, skip_cold_load)
             .map_err(|e| {
                 cold_path();
@@ -580,7 +636,13 @@ impl<
                     *self.error() = Err(err.into());
                 }
                 ret
-            })
+            });
+        #[cfg(feature = "counters")]
+        if let (Ok(load), Some(counters)) = (&res, self.local.counters_mut()) {
+            counters.sstores += 1;
+            counters.cold_storage_loads += u64::from(load.is_cold);
+        }
+        res
     }
 
     #[inline]
@@ -590,7 +652,8 @@ impl<
         key: StorageKey,
         skip_cold_load: bool,
     ) -> Result<StateLoad<StorageValue>, LoadError> {
-        self.journal_mut()
+        let res = self
+            .journal_mut()
             .sload_skip_cold_load(address, key, skip_cold_load)
             .map_err(|e| {
                 cold_path();
@@ -599,7 +662,13 @@ impl<
                     *self.error() = Err(err.into());
                 }
                 ret
-            })
+            });
+        #[cfg(feature = "counters")]
+        if let (Ok(load), Some(counters)) = (&res, self.local.counters_mut()) {
+            counters.sloads += 1;
+            counters.cold_storage_loads += u64::from(load.is_cold);
+        }
+        res
     }
 
     #[inline]
@@ -614,7 +683,13 @@ impl<
             load_code,
             skip_cold_load,
         ) {
-            Ok(a) => Ok(a),
+            Ok(a) => {
+                #[cfg(feature = "counters")]
+                if let Some(counters) = self.local.counters_mut() {
+                    counters.cold_account_loads += u64::from(a.is_cold);
+                }
+                Ok(a)
+            }
             Err(e) => {
                 cold_path();
                 let (ret, err) = e.into_parts();
@@ -626,3 +701,44 @@ impl<
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use database::{CacheDB, EmptyDB};
+    use state::AccountInfo;
+
+    #[test]
+    fn fork_is_independent() {
+        let address = Address::with_last_byte(1);
+        let mut db = CacheDB::<EmptyDB>::default();
+        db.insert_account_info(address, AccountInfo::from_balance(U256::from(10)));
+        let mut ctx: Context<BlockEnv, TxEnv, CfgEnv, CacheDB<EmptyDB>> =
+            Context::new(db, SpecId::PRAGUE);
+        ctx.journal_mut().load_account(address).unwrap();
+
+        let mut left = ctx.fork();
+        let mut right = ctx.fork();
+        assert!(left.journaled_state.state.contains_key(&address));
+
+        left.journaled_state
+            .state
+            .get_mut(&address)
+            .unwrap()
+            .info
+            .balance = U256::from(20);
+        let other = Address::with_last_byte(2);
+        right.journal_mut().load_account(other).unwrap();
+
+        assert_eq!(
+            right.journaled_state.state[&address].info.balance,
+            U256::from(10)
+        );
+        assert!(!left.journaled_state.state.contains_key(&other));
+        assert!(!ctx.journaled_state.state.contains_key(&other));
+        assert_eq!(
+            ctx.journaled_state.state[&address].info.balance,
+            U256::from(10)
+        );
+    }
+}
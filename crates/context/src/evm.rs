@@ -4,7 +4,7 @@ use core::{
     ops::{Deref, DerefMut},
 };
 
-use context_interface::FrameStack;
+use context_interface::{ContextTr, FrameStack, JournalTr, LocalContextTr};
 
 /// Main EVM structure that contains all data needed for execution.
 #[derive(Debug, Clone)]
@@ -93,6 +93,39 @@ impl<CTX, INSP, I, P, F> Evm<CTX, INSP, I, P, F> {
     }
 }
 
+impl<CTX: ContextTr, INSP, I, P, F> Evm<CTX, INSP, I, P, F> {
+    /// Prepares the EVM for the next transaction without dropping any of its buffers.
+    ///
+    /// Reverts the journal entries of an unfinished transaction, clears logs, transient storage,
+    /// the shared memory, the frame stack and the context error. Their allocations, the frames
+    /// with their interpreter stacks and the accounts loaded in the journal are kept, so the
+    /// next transaction sees the changes of the previous ones, same as with `transact_one`.
+    ///
+    /// Successful and failed transactions already leave the EVM in this state, this is needed
+    /// after the execution got interrupted, for example by a panicking inspector or by
+    /// driving the frames manually. Replaying a block, as in the `block_traces` example, should
+    /// reuse a single EVM and call this and [`Evm::reset_for_next_block`] instead of rebuilding
+    /// it for every transaction.
+    #[inline]
+    pub fn reset_for_next_tx(&mut self) {
+        self.ctx.local_mut().clear();
+        self.ctx.journal_mut().discard_tx();
+        *self.ctx.error() = Ok(());
+        self.frame_stack.clear();
+    }
+
+    /// Prepares the EVM for the next block without dropping any of its buffers.
+    ///
+    /// Same as [`Evm::reset_for_next_tx`] but additionally drops the accounts loaded in the
+    /// journal. Changes that are not yet committed to the database are lost, finalize the
+    /// journal and commit them before calling this. The database and its caches are untouched.
+    #[inline]
+    pub fn reset_for_next_block(&mut self) {
+        self.reset_for_next_tx();
+        let _ = self.ctx.journal_mut().finalize();
+    }
+}
+
 impl<CTX, INSP, I, P, F> Deref for Evm<CTX, INSP, I, P, F> {
     type Target = CTX;
 
@@ -106,3 +139,51 @@ impl<CTX, INSP, I, P, F> DerefMut for Evm<CTX, INSP, I, P, F> {
         &mut self.ctx
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BlockEnv, CfgEnv, Context, TxEnv};
+    use context_interface::context::ContextError;
+    use database::EmptyDB;
+    use primitives::{hardfork::SpecId, Address, Log, U256};
+
+    #[test]
+    fn reset_keeps_loaded_accounts_until_next_block() {
+        let address = Address::with_last_byte(1);
+        let ctx: Context<BlockEnv, TxEnv, CfgEnv, EmptyDB> =
+            Context::new(EmptyDB::default(), SpecId::PRAGUE);
+        let mut evm: Evm<_, (), (), (), ()> = Evm::new(ctx, (), ());
+
+        let journal = evm.ctx.journal_mut();
+        journal.load_account(address).unwrap();
+        journal.tstore(address, U256::from(1), U256::from(2));
+        journal.log(Log::default());
+        let _ = journal.checkpoint();
+        evm.ctx
+            .local_mut()
+            .shared_memory_buffer()
+            .borrow_mut()
+            .push(1);
+        *evm.ctx.error() = Err(ContextError::Custom("interrupted".into()));
+
+        evm.reset_for_next_tx();
+        assert!(evm.ctx.error().is_ok());
+        assert!(evm.ctx.journal_ref().logs().is_empty());
+        assert_eq!(evm.ctx.journal_ref().depth(), 0);
+        assert!(evm
+            .ctx
+            .local_ref()
+            .shared_memory_buffer()
+            .borrow()
+            .is_empty());
+        assert_eq!(
+            evm.ctx.journal_mut().tload(address, U256::from(1)),
+            U256::ZERO
+        );
+        assert!(evm.ctx.journaled_state.state.contains_key(&address));
+
+        evm.reset_for_next_block();
+        assert!(evm.ctx.journaled_state.state.is_empty());
+    }
+}
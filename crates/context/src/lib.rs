@@ -15,16 +15,22 @@ pub use context_interface::*;
 
 pub mod block;
 pub mod cfg;
+pub mod consistency;
 pub mod context;
 pub mod evm;
 pub mod journal;
 pub mod local;
+pub mod multi_chain;
+#[cfg(feature = "alloy-rpc")]
+pub mod rpc;
 pub mod tx;
 
-pub use block::BlockEnv;
+pub use block::{BlockEnv, ParentHeader};
 pub use cfg::{Cfg, CfgEnv};
+pub use consistency::EnvMismatch;
 pub use context::*;
 pub use evm::Evm;
 pub use journal::*;
 pub use local::LocalContext;
+pub use multi_chain::{ChainConfig, MultiChainBuilder};
 pub use tx::TxEnv;
@@ -1,5 +1,5 @@
 //! Local context that is filled by execution.
-use context_interface::LocalContextTr;
+use context_interface::{ExecutionCounters, LocalContextTr};
 use core::cell::RefCell;
 use std::{rc::Rc, string::String, vec::Vec};
 
@@ -10,6 +10,11 @@ pub struct LocalContext {
     pub shared_memory_buffer: Rc<RefCell<Vec<u8>>>,
     /// Optional precompile error message to bubble up.
     pub precompile_error_message: Option<String>,
+    /// Counters of the last executed transaction.
+    ///
+    /// Counters are reset when the execution of a transaction starts and kept when the context is
+    /// cleared, so they can be read after the transaction.
+    pub counters: ExecutionCounters,
 }
 
 impl Default for LocalContext {
@@ -17,6 +22,7 @@ impl Default for LocalContext {
         Self {
             shared_memory_buffer: Rc::new(RefCell::new(Vec::with_capacity(1024 * 4))),
             precompile_error_message: None,
+            counters: ExecutionCounters::new(),
         }
     }
 }
@@ -39,6 +45,14 @@ impl LocalContextTr for LocalContext {
     fn take_precompile_error_context(&mut self) -> Option<String> {
         self.precompile_error_message.take()
     }
+
+    fn counters(&self) -> Option<&ExecutionCounters> {
+        Some(&self.counters)
+    }
+
+    fn counters_mut(&mut self) -> Option<&mut ExecutionCounters> {
+        Some(&mut self.counters)
+    }
 }
 
 impl LocalContext {
@@ -0,0 +1,175 @@
+//! Builder of contexts for several chains, see [`MultiChainBuilder`].
+use crate::{BlockEnv, CfgEnv, Context, Journal, TxEnv};
+use context_interface::Database;
+use primitives::{eip1559::BaseFeeParams, hardfork::SpecId};
+use std::{collections::BTreeMap, string::String, vec::Vec};
+
+/// Configuration of a single chain.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ChainConfig {
+    /// Human readable name of the chain.
+    pub name: String,
+    /// Configuration of the EVM, the chain ID is taken from it.
+    pub cfg: CfgEnv,
+    /// Block environment the contexts start with.
+    pub block: BlockEnv,
+}
+
+impl ChainConfig {
+    /// Creates a new chain configuration with the given chain ID and spec and default block.
+    pub fn new(name: impl Into<String>, chain_id: u64, spec: SpecId) -> Self {
+        let mut cfg = CfgEnv::new_with_spec(spec);
+        cfg.chain_id = chain_id;
+        Self {
+            name: name.into(),
+            cfg,
+            block: BlockEnv::default(),
+        }
+    }
+
+    /// Ethereum mainnet at the given spec.
+    pub fn mainnet(spec: SpecId) -> Self {
+        Self::new("mainnet", 1, spec)
+    }
+
+    /// OP mainnet configuration at the given spec.
+    ///
+    /// Only the chain ID and the base fee parameters differ from mainnet, OP-specific
+    /// execution rules require an OP handler.
+    pub fn op_mainnet(spec: SpecId) -> Self {
+        let mut chain = Self::new("op-mainnet", 10, spec);
+        chain.cfg.base_fee_params = BaseFeeParams::OPTIMISM_CANYON;
+        chain
+    }
+
+    /// Returns the chain ID.
+    pub const fn chain_id(&self) -> u64 {
+        self.cfg.chain_id
+    }
+
+    /// Modifies the configuration of the EVM.
+    pub fn with_cfg(mut self, f: impl FnOnce(&mut CfgEnv)) -> Self {
+        f(&mut self.cfg);
+        self
+    }
+
+    /// Modifies the block environment.
+    pub fn with_block(mut self, f: impl FnOnce(&mut BlockEnv)) -> Self {
+        f(&mut self.block);
+        self
+    }
+
+    /// Builds a context for the chain over the given database.
+    pub fn build_context<DB: Database>(
+        &self,
+        db: DB,
+    ) -> Context<BlockEnv, TxEnv, CfgEnv, DB, Journal<DB>, ()> {
+        Context::new(db, self.cfg.spec)
+            .with_cfg(self.cfg.clone())
+            .with_block(self.block.clone())
+    }
+}
+
+/// Builds contexts for several configured chains from a single configuration source.
+///
+/// Cross-chain simulators register every chain once, by hand or by deserializing the
+/// configurations, and build contexts by chain ID instead of maintaining setup code per chain.
+/// Databases are provided per context, so chains can share a read-only cache by passing
+/// references to it (e.g. with [`Context::with_ref_db`]) or get isolated state.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MultiChainBuilder {
+    /// Configured chains by chain ID.
+    chains: BTreeMap<u64, ChainConfig>,
+}
+
+impl MultiChainBuilder {
+    /// Creates a builder without chains.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a builder from the chain configurations, later chains replace earlier chains
+    /// with the same chain ID.
+    pub fn from_chains(chains: impl IntoIterator<Item = ChainConfig>) -> Self {
+        chains.into_iter().fold(Self::new(), Self::with_chain)
+    }
+
+    /// Adds the chain, replacing the chain with the same chain ID.
+    pub fn with_chain(mut self, chain: ChainConfig) -> Self {
+        self.insert(chain);
+        self
+    }
+
+    /// Adds the chain and returns the replaced chain with the same chain ID.
+    pub fn insert(&mut self, chain: ChainConfig) -> Option<ChainConfig> {
+        self.chains.insert(chain.chain_id(), chain)
+    }
+
+    /// Returns the configuration of the chain.
+    pub fn chain(&self, chain_id: u64) -> Option<&ChainConfig> {
+        self.chains.get(&chain_id)
+    }
+
+    /// Returns the configurations of all chains ordered by chain ID.
+    pub fn chains(&self) -> impl Iterator<Item = &ChainConfig> {
+        self.chains.values()
+    }
+
+    /// Builds a context for the chain over the given database.
+    ///
+    /// Returns `None` if the chain is not configured.
+    pub fn build_context<DB: Database>(
+        &self,
+        chain_id: u64,
+        db: DB,
+    ) -> Option<Context<BlockEnv, TxEnv, CfgEnv, DB, Journal<DB>, ()>> {
+        self.chain(chain_id).map(|chain| chain.build_context(db))
+    }
+
+    /// Builds a context for every chain, ordered by chain ID, with the database returned by
+    /// `db` for the chain.
+    pub fn build_contexts<DB: Database>(
+        &self,
+        mut db: impl FnMut(&ChainConfig) -> DB,
+    ) -> Vec<Context<BlockEnv, TxEnv, CfgEnv, DB, Journal<DB>, ()>> {
+        self.chains()
+            .map(|chain| chain.build_context(db(chain)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use context_interface::{Block, Cfg};
+    use database::EmptyDB;
+
+    #[test]
+    fn builds_configured_chains() {
+        let custom = ChainConfig::new("custom", 1337, SpecId::CANCUN)
+            .with_cfg(|cfg| cfg.disable_nonce_check = true)
+            .with_block(|block| block.gas_limit = 1_000);
+        let builder = MultiChainBuilder::from_chains([
+            ChainConfig::op_mainnet(SpecId::PRAGUE),
+            custom,
+            ChainConfig::mainnet(SpecId::PRAGUE),
+        ]);
+
+        assert!(builder.build_context(5, EmptyDB::new()).is_none());
+        let ctx = builder.build_context(1337, EmptyDB::new()).unwrap();
+        assert_eq!(ctx.cfg.chain_id(), 1337);
+        assert_eq!(ctx.cfg.spec(), SpecId::CANCUN);
+        assert!(ctx.cfg.is_nonce_check_disabled());
+        assert_eq!(ctx.block.gas_limit(), 1_000);
+
+        let contexts = builder.build_contexts(|_| EmptyDB::new());
+        let chain_ids: Vec<_> = contexts.iter().map(|ctx| ctx.cfg.chain_id).collect();
+        assert_eq!(chain_ids, [1, 10, 1337]);
+        assert_eq!(
+            contexts[1].cfg.base_fee_params(),
+            BaseFeeParams::OPTIMISM_CANYON
+        );
+    }
+}
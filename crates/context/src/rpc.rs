@@ -0,0 +1,189 @@
+//! Conversions of execution results into the alloy RPC types returned by `eth_getLogs` and
+//! `eth_getTransactionReceipt`.
+use alloy_consensus::{Eip658Value, Receipt, ReceiptEnvelope, ReceiptWithBloom, TxType};
+use alloy_eips::eip2718::Eip2718Error;
+use alloy_rpc_types_eth::{Log as RpcLog, TransactionReceipt};
+use context_interface::{result::ExecutionResult, Block, Transaction};
+use primitives::{alloy_primitives::logs_bloom, Log, B256};
+use std::vec::Vec;
+
+/// Position of a transaction in the chain, supplied by the caller as it is not known to the EVM.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct TxMeta {
+    /// Hash of the transaction.
+    pub transaction_hash: B256,
+    /// Index of the transaction in the block.
+    pub transaction_index: u64,
+    /// Hash of the block.
+    pub block_hash: B256,
+    /// Number of the block.
+    pub block_number: u64,
+    /// Timestamp of the block.
+    pub block_timestamp: u64,
+    /// Index in the block of the first log emitted by the transaction.
+    pub first_log_index: u64,
+    /// Gas used by the transactions of the block preceding this one.
+    pub preceding_gas_used: u64,
+}
+
+impl TxMeta {
+    /// Returns the metadata of the next transaction of the block, following one that used
+    /// `gas_used` gas and emitted `logs` logs.
+    pub fn next(&self, transaction_hash: B256, gas_used: u64, logs: usize) -> Self {
+        Self {
+            transaction_hash,
+            transaction_index: self.transaction_index + 1,
+            first_log_index: self.first_log_index + logs as u64,
+            preceding_gas_used: self.preceding_gas_used + gas_used,
+            ..*self
+        }
+    }
+}
+
+/// Converts logs emitted by a transaction into RPC logs.
+pub fn to_rpc_logs(logs: &[Log], meta: &TxMeta) -> Vec<RpcLog> {
+    logs.iter()
+        .enumerate()
+        .map(|(index, log)| RpcLog {
+            inner: log.clone(),
+            block_hash: Some(meta.block_hash),
+            block_number: Some(meta.block_number),
+            block_timestamp: Some(meta.block_timestamp),
+            transaction_hash: Some(meta.transaction_hash),
+            transaction_index: Some(meta.transaction_index),
+            log_index: Some(meta.first_log_index + index as u64),
+            removed: false,
+        })
+        .collect()
+}
+
+/// Converts the result of a transaction into an RPC receipt.
+///
+/// Logs of failed transactions are dropped, as they are not part of the receipt. Fails if the
+/// transaction type is not an Ethereum one.
+pub fn to_rpc_receipt<H>(
+    result: &ExecutionResult<H>,
+    tx: &impl Transaction,
+    block: &impl Block,
+    meta: &TxMeta,
+) -> Result<TransactionReceipt, Eip2718Error> {
+    let tx_type = TxType::try_from(tx.tx_type())?;
+    let logs = if result.is_success() {
+        result.logs()
+    } else {
+        &[]
+    };
+    let gas_used = result.tx_gas_used();
+    let receipt = Receipt {
+        status: Eip658Value::Eip658(result.is_success()),
+        cumulative_gas_used: meta.preceding_gas_used + gas_used,
+        logs: to_rpc_logs(logs, meta),
+    };
+    let receipt = ReceiptWithBloom {
+        receipt,
+        logs_bloom: logs_bloom(logs),
+    };
+    let is_eip4844 = tx_type == TxType::Eip4844;
+    Ok(TransactionReceipt {
+        inner: ReceiptEnvelope::from_typed(tx_type, receipt),
+        transaction_hash: meta.transaction_hash,
+        transaction_index: Some(meta.transaction_index),
+        block_hash: Some(meta.block_hash),
+        block_number: Some(meta.block_number),
+        gas_used,
+        effective_gas_price: tx.effective_gas_price(block.basefee() as u128),
+        blob_gas_used: is_eip4844.then(|| tx.total_blob_gas()),
+        blob_gas_price: block.blob_gasprice().filter(|_| is_eip4844),
+        from: tx.caller(),
+        to: tx.kind().to().copied(),
+        contract_address: result.created_address(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BlockEnv, TxEnv};
+    use context_interface::result::{Output, ResultGas, SuccessReason};
+    use primitives::{address, Bytes, LogData, TxKind};
+    use std::vec;
+
+    fn meta() -> TxMeta {
+        TxMeta {
+            transaction_hash: B256::with_last_byte(1),
+            transaction_index: 2,
+            block_hash: B256::with_last_byte(3),
+            block_number: 4,
+            block_timestamp: 5,
+            first_log_index: 6,
+            preceding_gas_used: 100_000,
+        }
+    }
+
+    fn log() -> Log {
+        Log {
+            address: address!("0x00000000000000000000000000000000000000aa"),
+            data: LogData::new_unchecked(vec![B256::with_last_byte(7)], Bytes::new()),
+        }
+    }
+
+    #[test]
+    fn converts_receipt() {
+        let result: ExecutionResult = ExecutionResult::Success {
+            reason: SuccessReason::Stop,
+            gas: ResultGas::new(30_000, 0, 0),
+            logs: vec![log(), log()],
+            output: Output::Call(Bytes::new()),
+        };
+        let tx = TxEnv::builder()
+            .caller(address!("0x00000000000000000000000000000000000000bb"))
+            .kind(TxKind::Call(address!(
+                "0x00000000000000000000000000000000000000cc"
+            )))
+            .gas_price(10)
+            .gas_priority_fee(Some(1))
+            .build()
+            .unwrap();
+        let block = BlockEnv {
+            basefee: 7,
+            ..Default::default()
+        };
+
+        let receipt = to_rpc_receipt(&result, &tx, &block, &meta()).unwrap();
+        assert!(receipt.inner.status());
+        assert_eq!(receipt.gas_used, 30_000);
+        assert_eq!(receipt.effective_gas_price, 8);
+        assert_eq!(receipt.from, tx.caller);
+        assert_eq!(receipt.to, tx.kind.to().copied());
+        assert_eq!(receipt.blob_gas_used, None);
+        assert_eq!(receipt.inner.cumulative_gas_used(), 130_000);
+
+        let logs = receipt.inner.logs();
+        assert_eq!(logs.len(), 2);
+        assert_eq!(logs[1].log_index, Some(7));
+        assert_eq!(logs[1].transaction_index, Some(2));
+        assert_eq!(logs[1].inner, log());
+    }
+
+    #[test]
+    fn drops_logs_of_failed_transactions() {
+        let result: ExecutionResult = ExecutionResult::Revert {
+            gas: ResultGas::new(30_000, 0, 0),
+            logs: vec![log()],
+            output: Bytes::new(),
+        };
+        let receipt =
+            to_rpc_receipt(&result, &TxEnv::default(), &BlockEnv::default(), &meta()).unwrap();
+        assert!(!receipt.inner.status());
+        assert!(receipt.inner.logs().is_empty());
+    }
+
+    #[test]
+    fn next_meta() {
+        let next = meta().next(B256::with_last_byte(9), 21_000, 3);
+        assert_eq!(next.transaction_index, 3);
+        assert_eq!(next.first_log_index, 9);
+        assert_eq!(next.preceding_gas_used, 121_000);
+        assert_eq!(next.block_hash, meta().block_hash);
+    }
+}
@@ -3,8 +3,8 @@ use crate::TransactionType;
 use context_interface::{
     either::Either,
     transaction::{
-        AccessList, AccessListItem, Authorization, RecoveredAuthority, RecoveredAuthorization,
-        SignedAuthorization, Transaction,
+        AccessList, AccessListItem, Authorization, BlobSidecar, RecoveredAuthority,
+        RecoveredAuthorization, SignedAuthorization, Transaction,
     },
 };
 use core::fmt::Debug;
@@ -12,6 +12,11 @@ use database_interface::{BENCH_CALLER, BENCH_TARGET};
 use primitives::{eip7825, Address, Bytes, TxKind, B256, U256};
 use std::{vec, vec::Vec};
 
+#[cfg(feature = "alloy")]
+mod raw;
+#[cfg(feature = "alloy")]
+pub use raw::RawTxError;
+
 /// The Transaction Environment is a struct that contains all fields that can be found in all Ethereum transaction,
 /// including EIP-4844, EIP-7702, EIP-7873, etc.  It implements the [`Transaction`] trait, which is used inside the EVM to execute a transaction.
 ///
@@ -77,6 +82,18 @@ pub struct TxEnv {
     /// [EIP-4844]: https://eips.ethereum.org/EIPS/eip-4844
     pub max_fee_per_blob_gas: u128,
 
+    /// Blobs, commitments and proofs of the blob versioned hashes
+    ///
+    /// Not part of the signed [EIP-4844] transaction, it is only validated if it is set and the
+    /// `blob-sidecar` feature of the handler is enabled.
+    ///
+    /// [EIP-4844]: https://eips.ethereum.org/EIPS/eip-4844
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub blob_sidecar: Option<BlobSidecar>,
+
     /// List of authorizations
     ///
     /// `authorization_list` contains the signature that authorizes this
@@ -236,6 +253,10 @@ impl Transaction for TxEnv {
         &self.blob_hashes
     }
 
+    fn blob_sidecar(&self) -> Option<&BlobSidecar> {
+        self.blob_sidecar.as_ref()
+    }
+
     fn max_priority_fee_per_gas(&self) -> Option<u128> {
         self.gas_priority_fee
     }
@@ -257,6 +278,7 @@ pub struct TxEnvBuilder {
     gas_priority_fee: Option<u128>,
     blob_hashes: Vec<B256>,
     max_fee_per_blob_gas: u128,
+    blob_sidecar: Option<BlobSidecar>,
     authorization_list: Vec<Either<SignedAuthorization, RecoveredAuthorization>>,
 }
 
@@ -277,6 +299,7 @@ impl TxEnvBuilder {
             gas_priority_fee: None,
             blob_hashes: Vec::new(),
             max_fee_per_blob_gas: 0,
+            blob_sidecar: None,
             authorization_list: Vec::new(),
         }
     }
@@ -387,6 +410,12 @@ impl TxEnvBuilder {
         self
     }
 
+    /// Set the blob sidecar
+    pub fn blob_sidecar(mut self, blob_sidecar: Option<BlobSidecar>) -> Self {
+        self.blob_sidecar = blob_sidecar;
+        self
+    }
+
     /// Set the authorization list
     pub fn authorization_list(
         mut self,
@@ -485,6 +514,7 @@ impl TxEnvBuilder {
             gas_priority_fee: self.gas_priority_fee,
             blob_hashes: self.blob_hashes,
             max_fee_per_blob_gas: self.max_fee_per_blob_gas,
+            blob_sidecar: self.blob_sidecar,
             authorization_list: self.authorization_list,
         };
 
@@ -577,6 +607,7 @@ impl TxEnvBuilder {
             gas_priority_fee: self.gas_priority_fee,
             blob_hashes: self.blob_hashes,
             max_fee_per_blob_gas: self.max_fee_per_blob_gas,
+            blob_sidecar: self.blob_sidecar,
             authorization_list: self.authorization_list,
         };
 
@@ -663,6 +694,7 @@ impl TxEnv {
             gas_priority_fee,
             blob_hashes,
             max_fee_per_blob_gas,
+            blob_sidecar,
             authorization_list,
         } = self;
 
@@ -680,6 +712,7 @@ impl TxEnv {
             .gas_priority_fee(gas_priority_fee)
             .blob_hashes(blob_hashes)
             .max_fee_per_blob_gas(max_fee_per_blob_gas)
+            .blob_sidecar(blob_sidecar)
             .authorization_list(authorization_list)
     }
 }
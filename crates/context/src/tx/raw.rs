@@ -0,0 +1,175 @@
+//! Decoding of [`TxEnv`] from raw signed transactions.
+use super::TxEnv;
+use alloy_consensus::{
+    crypto::RecoveryError, transaction::SignerRecoverable, Transaction as _, TxEnvelope,
+};
+use alloy_eips::eip2718::{Decodable2718, Eip2718Error, Typed2718};
+use context_interface::either::Either;
+use primitives::Address;
+
+/// Error returned by [`TxEnv::from_raw`].
+#[derive(Debug)]
+pub enum RawTxError {
+    /// Bytes are not a valid EIP-2718 transaction envelope.
+    Decode(Eip2718Error),
+    /// Signer could not be recovered from the signature.
+    Recovery(RecoveryError),
+}
+
+impl core::fmt::Display for RawTxError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Decode(err) => write!(f, "invalid transaction envelope: {err}"),
+            Self::Recovery(err) => write!(f, "signer recovery failed: {err}"),
+        }
+    }
+}
+
+impl core::error::Error for RawTxError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            Self::Decode(err) => Some(err),
+            Self::Recovery(err) => Some(err),
+        }
+    }
+}
+
+impl From<Eip2718Error> for RawTxError {
+    fn from(err: Eip2718Error) -> Self {
+        Self::Decode(err)
+    }
+}
+
+impl From<RecoveryError> for RawTxError {
+    fn from(err: RecoveryError) -> Self {
+        Self::Recovery(err)
+    }
+}
+
+impl TxEnv {
+    /// Decodes a raw signed transaction, as sent to `eth_sendRawTransaction`, and recovers its
+    /// signer.
+    ///
+    /// Legacy transactions and all typed envelopes are supported. Access list, blob hashes and
+    /// EIP-7702 authorizations are copied, the authorizations are left for the handler to
+    /// recover. Blob sidecars are not part of the signed transaction and are not set.
+    pub fn from_raw(bytes: &[u8]) -> Result<Self, RawTxError> {
+        let envelope = TxEnvelope::decode_2718_exact(bytes)?;
+        let caller = envelope.recover_signer()?;
+        Ok(Self::from_envelope(&envelope, caller))
+    }
+
+    /// Creates the environment of a decoded transaction sent by `caller`.
+    pub fn from_envelope(envelope: &TxEnvelope, caller: Address) -> Self {
+        Self {
+            tx_type: envelope.ty(),
+            caller,
+            gas_limit: envelope.gas_limit(),
+            gas_price: envelope.max_fee_per_gas(),
+            kind: envelope.kind(),
+            value: envelope.value(),
+            data: envelope.input().clone(),
+            nonce: envelope.nonce(),
+            chain_id: envelope.chain_id(),
+            access_list: envelope.access_list().cloned().unwrap_or_default(),
+            gas_priority_fee: envelope.max_priority_fee_per_gas(),
+            blob_hashes: envelope
+                .blob_versioned_hashes()
+                .map(<[_]>::to_vec)
+                .unwrap_or_default(),
+            max_fee_per_blob_gas: envelope.max_fee_per_blob_gas().unwrap_or_default(),
+            blob_sidecar: None,
+            authorization_list: envelope
+                .authorization_list()
+                .map(|list| list.iter().cloned().map(Either::Left).collect())
+                .unwrap_or_default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TransactionType;
+    use alloy_consensus::{SignableTransaction, TxEip1559, TxLegacy};
+    use alloy_eips::eip2718::Encodable2718;
+    use context_interface::transaction::{AccessList, AccessListItem};
+    use primitives::{address, alloy_primitives::Signature, b256, hex, TxKind, U256};
+    use std::vec;
+
+    fn signature() -> Signature {
+        Signature::new(
+            U256::from_be_bytes(hex!(
+                "840cfc572845f5786e702984c2a582528cad4b49b2a10b9db1be7fca90058565"
+            )),
+            U256::from_be_bytes(hex!(
+                "25e7109ceb98168d95b09b18bbf6b685130e0562f233877d492b94eee0c5b6d1"
+            )),
+            false,
+        )
+    }
+
+    #[test]
+    fn decodes_eip1559() {
+        let tx = TxEip1559 {
+            chain_id: 1,
+            nonce: 7,
+            gas_limit: 50_000,
+            max_fee_per_gas: 30,
+            max_priority_fee_per_gas: 2,
+            to: TxKind::Call(address!("0x00000000000000000000000000000000000000aa")),
+            value: U256::from(5),
+            access_list: AccessList(vec![AccessListItem {
+                address: address!("0x00000000000000000000000000000000000000bb"),
+                storage_keys: vec![b256!(
+                    "0x0000000000000000000000000000000000000000000000000000000000000001"
+                )],
+            }]),
+            input: hex!("c0ffee").into(),
+        };
+        let signed = tx.clone().into_signed(signature());
+        let caller = signed.recover_signer().unwrap();
+        let raw = TxEnvelope::from(signed).encoded_2718();
+
+        let env = TxEnv::from_raw(&raw).unwrap();
+        assert_eq!(env.tx_type, TransactionType::Eip1559);
+        assert_eq!(env.caller, caller);
+        assert_eq!(env.gas_limit, 50_000);
+        assert_eq!(env.gas_price, 30);
+        assert_eq!(env.gas_priority_fee, Some(2));
+        assert_eq!(env.kind, tx.to);
+        assert_eq!(env.value, tx.value);
+        assert_eq!(env.data, tx.input);
+        assert_eq!(env.nonce, 7);
+        assert_eq!(env.chain_id, Some(1));
+        assert_eq!(env.access_list, tx.access_list);
+    }
+
+    #[test]
+    fn decodes_legacy() {
+        let tx = TxLegacy {
+            chain_id: Some(1),
+            nonce: 1,
+            gas_price: 20,
+            gas_limit: 21_000,
+            to: TxKind::Create,
+            value: U256::ZERO,
+            input: hex!("6000").into(),
+        };
+        let raw = TxEnvelope::from(tx.into_signed(signature())).encoded_2718();
+
+        let env = TxEnv::from_raw(&raw).unwrap();
+        assert_eq!(env.tx_type, TransactionType::Legacy);
+        assert_eq!(env.gas_price, 20);
+        assert_eq!(env.gas_priority_fee, None);
+        assert_eq!(env.kind, TxKind::Create);
+    }
+
+    #[test]
+    fn rejects_invalid_bytes() {
+        assert!(matches!(
+            TxEnv::from_raw(&[0x02, 0x01]),
+            Err(RawTxError::Decode(_))
+        ));
+    }
+}
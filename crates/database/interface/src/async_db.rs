@@ -11,7 +11,11 @@ use core::{
 use corosensei::{stack::DefaultStack, Coroutine, CoroutineResult, Yielder};
 use primitives::{Address, AddressMap, StorageKey, StorageValue, B256};
 use state::{Account, AccountId, AccountInfo, Bytecode};
-use std::{cell::Cell, fmt, io};
+use std::{
+    cell::Cell,
+    fmt, io,
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+};
 use tokio::{
     runtime::{Handle, Runtime},
     task,
@@ -894,6 +898,193 @@ impl<T: DatabaseAsync + DatabaseCommit> DatabaseCommit for WrapDatabaseAsync<T>
     }
 }
 
+/// Adapter that exposes a blocking [`Database`] through the async [`DatabaseAsync`] interface.
+///
+/// This is the inverse of [`WrapDatabaseAsync`]. Every query runs on the blocking thread pool of
+/// the Tokio runtime with [`spawn_blocking`](Handle::spawn_blocking), so async services that embed
+/// a disk-backed database don't stall their executor.
+///
+/// The database is shared behind a mutex, so queries of all clones are serialized.
+#[derive(Debug)]
+pub struct SpawnBlockingDb<T> {
+    db: Arc<Mutex<T>>,
+    handle: Handle,
+}
+
+impl<T> Clone for SpawnBlockingDb<T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            db: self.db.clone(),
+            handle: self.handle.clone(),
+        }
+    }
+}
+
+impl<T> SpawnBlockingDb<T> {
+    /// Creates a new adapter using the current Tokio runtime handle.
+    ///
+    /// Returns `None` if no Tokio runtime is available.
+    #[inline]
+    pub fn new(db: T) -> Option<Self> {
+        Some(Self::with_handle(db, Handle::try_current().ok()?))
+    }
+
+    /// Creates a new adapter with a Tokio runtime handle.
+    #[inline]
+    pub fn with_handle(db: T, handle: Handle) -> Self {
+        Self {
+            db: Arc::new(Mutex::new(db)),
+            handle,
+        }
+    }
+
+    /// Locks and returns the wrapped database.
+    ///
+    /// This blocks until in-flight queries complete.
+    #[inline]
+    pub fn lock(&self) -> MutexGuard<'_, T> {
+        self.db.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Consumes the adapter and returns the wrapped database.
+    ///
+    /// Returns `None` if the database is still shared with a clone of the adapter.
+    #[inline]
+    pub fn into_inner(self) -> Option<T> {
+        let db = Arc::into_inner(self.db)?;
+        Some(db.into_inner().unwrap_or_else(PoisonError::into_inner))
+    }
+
+    /// Runs the query on the blocking thread pool.
+    ///
+    /// Panics of the query are propagated to the caller.
+    fn spawn<R, E>(
+        &self,
+        query: impl FnOnce(&mut T) -> Result<R, E> + Send + 'static,
+    ) -> impl Future<Output = Result<R, AsyncError<E>>> + Send
+    where
+        T: Send + 'static,
+        R: Send + 'static,
+        E: Send + 'static,
+    {
+        let db = self.db.clone();
+        let handle = self.handle.clone();
+        async move {
+            let task = handle.spawn_blocking(move || {
+                query(&mut db.lock().unwrap_or_else(PoisonError::into_inner))
+            });
+            match task.await {
+                Ok(result) => result.map_err(AsyncError::Inner),
+                Err(error) if error.is_panic() => std::panic::resume_unwind(error.into_panic()),
+                Err(_) => Err(AsyncError::Cancelled),
+            }
+        }
+    }
+}
+
+impl<T> DatabaseAsync for SpawnBlockingDb<T>
+where
+    T: Database + Send + 'static,
+    T::Error: Send,
+{
+    type Error = AsyncError<T::Error>;
+
+    #[inline]
+    fn basic_async(
+        &mut self,
+        address: Address,
+    ) -> impl Future<Output = Result<Option<AccountInfo>, Self::Error>> + Send {
+        self.spawn(move |db| db.basic(address))
+    }
+
+    #[inline]
+    fn code_by_hash_async(
+        &mut self,
+        code_hash: B256,
+    ) -> impl Future<Output = Result<Bytecode, Self::Error>> + Send {
+        self.spawn(move |db| db.code_by_hash(code_hash))
+    }
+
+    #[inline]
+    fn storage_async(
+        &mut self,
+        address: Address,
+        index: StorageKey,
+    ) -> impl Future<Output = Result<StorageValue, Self::Error>> + Send {
+        self.spawn(move |db| db.storage(address, index))
+    }
+
+    #[inline]
+    fn storage_by_account_id_async(
+        &mut self,
+        address: Address,
+        account_id: AccountId,
+        storage_key: StorageKey,
+    ) -> impl Future<Output = Result<StorageValue, Self::Error>> + Send {
+        self.spawn(move |db| db.storage_by_account_id(address, account_id, storage_key))
+    }
+
+    #[inline]
+    fn block_hash_async(
+        &mut self,
+        number: u64,
+    ) -> impl Future<Output = Result<B256, Self::Error>> + Send {
+        self.spawn(move |db| db.block_hash(number))
+    }
+}
+
+impl<T> DatabaseAsyncRef for SpawnBlockingDb<T>
+where
+    T: DatabaseRef + Send + 'static,
+    T::Error: Send,
+{
+    type Error = AsyncError<T::Error>;
+
+    #[inline]
+    fn basic_async_ref(
+        &self,
+        address: Address,
+    ) -> impl Future<Output = Result<Option<AccountInfo>, Self::Error>> + Send {
+        self.spawn(move |db| db.basic_ref(address))
+    }
+
+    #[inline]
+    fn code_by_hash_async_ref(
+        &self,
+        code_hash: B256,
+    ) -> impl Future<Output = Result<Bytecode, Self::Error>> + Send {
+        self.spawn(move |db| db.code_by_hash_ref(code_hash))
+    }
+
+    #[inline]
+    fn storage_async_ref(
+        &self,
+        address: Address,
+        index: StorageKey,
+    ) -> impl Future<Output = Result<StorageValue, Self::Error>> + Send {
+        self.spawn(move |db| db.storage_ref(address, index))
+    }
+
+    #[inline]
+    fn storage_by_account_id_async_ref(
+        &self,
+        address: Address,
+        account_id: AccountId,
+        storage_key: StorageKey,
+    ) -> impl Future<Output = Result<StorageValue, Self::Error>> + Send {
+        self.spawn(move |db| db.storage_by_account_id_ref(address, account_id, storage_key))
+    }
+
+    #[inline]
+    fn block_hash_async_ref(
+        &self,
+        number: u64,
+    ) -> impl Future<Output = Result<B256, Self::Error>> + Send {
+        self.spawn(move |db| db.block_hash_ref(number))
+    }
+}
+
 // Hold a tokio runtime handle or full runtime.
 #[derive(Debug)]
 enum HandleOrRuntime {
@@ -913,7 +1104,10 @@ impl HandleOrRuntime {
 
 #[cfg(test)]
 mod tests {
-    use super::{block_on_current, on_fiber, AsyncDb, AsyncError, DatabaseAsync};
+    use super::{
+        block_on_current, on_fiber, AsyncDb, AsyncError, DatabaseAsync, DatabaseAsyncRef,
+        SpawnBlockingDb,
+    };
     use crate::Database;
     use core::{convert::Infallible, fmt, future::Future, pin::Pin, task::Poll};
     use primitives::{Address, StorageKey, StorageValue, B256};
@@ -1007,6 +1201,23 @@ mod tests {
         assert_eq!(value, StorageValue::from(9));
     }
 
+    #[test]
+    fn spawn_blocking_database_runs_on_blocking_pool() {
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let address = Address::with_last_byte(1);
+        let mock =
+            crate::MockDB::new().with_storage(address, StorageKey::from(7), StorageValue::from(9));
+        let mut db = SpawnBlockingDb::with_handle(mock, runtime.handle().clone());
+
+        let value = runtime
+            .block_on(db.storage_async(address, StorageKey::from(7)))
+            .unwrap();
+        assert_eq!(value, StorageValue::from(9));
+        let info = runtime.block_on(db.basic_async_ref(address)).unwrap();
+        assert!(info.is_none());
+        assert!(db.into_inner().is_some());
+    }
+
     #[test]
     fn blocking_constructor_uses_current_tokio_runtime() {
         let runtime = tokio::runtime::Runtime::new().unwrap();
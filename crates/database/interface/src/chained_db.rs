@@ -0,0 +1,227 @@
+//! Database that layers one database over another.
+use crate::{Database, DatabaseCommit, DatabaseRef};
+use primitives::{Address, AddressMap, StorageKey, StorageValue, B256};
+use state::{Account, AccountInfo, Bytecode};
+
+/// Selects which queries of [`ChainedDB`] fall through to the fallback database.
+///
+/// A query falls through when the primary database returns missing data:
+/// * `basic`: the account is `None`.
+/// * `code_by_hash`: the bytecode is empty.
+/// * `storage`: the value is zero.
+/// * `block_hash`: the hash is zero.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FallThrough {
+    /// Fall through on missing accounts.
+    pub basic: bool,
+    /// Fall through on missing code.
+    pub code_by_hash: bool,
+    /// Fall through on zero storage values.
+    pub storage: bool,
+    /// Fall through on zero block hashes.
+    pub block_hash: bool,
+}
+
+impl Default for FallThrough {
+    fn default() -> Self {
+        Self::ALL
+    }
+}
+
+impl FallThrough {
+    /// Every query falls through.
+    pub const ALL: Self = Self {
+        basic: true,
+        code_by_hash: true,
+        storage: true,
+        block_hash: true,
+    };
+
+    /// No query falls through, the fallback database is never queried.
+    pub const NONE: Self = Self {
+        basic: false,
+        code_by_hash: false,
+        storage: false,
+        block_hash: false,
+    };
+}
+
+/// Database that queries `A` first and falls back to `B` when `A` does not have the data.
+///
+/// It can be used to layer a local snapshot over a remote database, or test overrides over
+/// production state. Which queries fall through is configured with [`FallThrough`].
+///
+/// Note that a zero storage value or a missing account can't be used to override the data of
+/// the fallback database unless falling through is disabled for that query.
+///
+/// Changes are committed to the primary database.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ChainedDB<A, B> {
+    /// Primary database.
+    pub primary: A,
+    /// Fallback database.
+    pub fallback: B,
+    /// Queries that fall through to the fallback database.
+    pub fall_through: FallThrough,
+}
+
+impl<A, B> ChainedDB<A, B> {
+    /// Creates a new chained database where every query falls through.
+    pub fn new(primary: A, fallback: B) -> Self {
+        Self {
+            primary,
+            fallback,
+            fall_through: FallThrough::ALL,
+        }
+    }
+
+    /// Sets the queries that fall through to the fallback database.
+    pub fn with_fall_through(self, fall_through: FallThrough) -> Self {
+        Self {
+            fall_through,
+            ..self
+        }
+    }
+
+    /// Consumes the database and returns the primary and fallback databases.
+    pub fn into_inner(self) -> (A, B) {
+        (self.primary, self.fallback)
+    }
+}
+
+impl<A, B> Database for ChainedDB<A, B>
+where
+    A: Database,
+    B: Database<Error = A::Error>,
+{
+    type Error = A::Error;
+
+    fn basic(&mut self, address: Address) -> Result<Option<AccountInfo>, Self::Error> {
+        match self.primary.basic(address)? {
+            None if self.fall_through.basic => self.fallback.basic(address),
+            info => Ok(info),
+        }
+    }
+
+    fn code_by_hash(&mut self, code_hash: B256) -> Result<Bytecode, Self::Error> {
+        let code = self.primary.code_by_hash(code_hash)?;
+        if self.fall_through.code_by_hash && code.is_empty() {
+            return self.fallback.code_by_hash(code_hash);
+        }
+        Ok(code)
+    }
+
+    fn storage(
+        &mut self,
+        address: Address,
+        index: StorageKey,
+    ) -> Result<StorageValue, Self::Error> {
+        let value = self.primary.storage(address, index)?;
+        if self.fall_through.storage && value.is_zero() {
+            return self.fallback.storage(address, index);
+        }
+        Ok(value)
+    }
+
+    fn block_hash(&mut self, number: u64) -> Result<B256, Self::Error> {
+        let hash = self.primary.block_hash(number)?;
+        if self.fall_through.block_hash && hash.is_zero() {
+            return self.fallback.block_hash(number);
+        }
+        Ok(hash)
+    }
+}
+
+impl<A, B> DatabaseRef for ChainedDB<A, B>
+where
+    A: DatabaseRef,
+    B: DatabaseRef<Error = A::Error>,
+{
+    type Error = A::Error;
+
+    fn basic_ref(&self, address: Address) -> Result<Option<AccountInfo>, Self::Error> {
+        match self.primary.basic_ref(address)? {
+            None if self.fall_through.basic => self.fallback.basic_ref(address),
+            info => Ok(info),
+        }
+    }
+
+    fn code_by_hash_ref(&self, code_hash: B256) -> Result<Bytecode, Self::Error> {
+        let code = self.primary.code_by_hash_ref(code_hash)?;
+        if self.fall_through.code_by_hash && code.is_empty() {
+            return self.fallback.code_by_hash_ref(code_hash);
+        }
+        Ok(code)
+    }
+
+    fn storage_ref(
+        &self,
+        address: Address,
+        index: StorageKey,
+    ) -> Result<StorageValue, Self::Error> {
+        let value = self.primary.storage_ref(address, index)?;
+        if self.fall_through.storage && value.is_zero() {
+            return self.fallback.storage_ref(address, index);
+        }
+        Ok(value)
+    }
+
+    fn block_hash_ref(&self, number: u64) -> Result<B256, Self::Error> {
+        let hash = self.primary.block_hash_ref(number)?;
+        if self.fall_through.block_hash && hash.is_zero() {
+            return self.fallback.block_hash_ref(number);
+        }
+        Ok(hash)
+    }
+}
+
+impl<A: DatabaseCommit, B> DatabaseCommit for ChainedDB<A, B> {
+    fn commit(&mut self, changes: AddressMap<Account>) {
+        self.primary.commit(changes)
+    }
+
+    fn commit_iter(&mut self, changes: &mut dyn Iterator<Item = (Address, Account)>) {
+        self.primary.commit_iter(changes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MockDB;
+    use primitives::U256;
+
+    #[test]
+    fn falls_through_on_missing_data() {
+        let address = Address::with_last_byte(1);
+        let primary = MockDB::new().with_storage(address, U256::from(1), U256::from(10));
+        let fallback = MockDB::new()
+            .with_account(address, AccountInfo::from_balance(U256::from(5)))
+            .with_storage(address, U256::from(1), U256::from(20))
+            .with_storage(address, U256::from(2), U256::from(30));
+        let db = ChainedDB::new(primary, fallback);
+
+        assert_eq!(
+            db.basic_ref(address).unwrap().map(|info| info.balance),
+            Some(U256::from(5))
+        );
+        assert_eq!(db.storage_ref(address, U256::from(1)), Ok(U256::from(10)));
+        assert_eq!(db.storage_ref(address, U256::from(2)), Ok(U256::from(30)));
+    }
+
+    #[test]
+    fn fall_through_disabled() {
+        let address = Address::with_last_byte(1);
+        let fallback = MockDB::new()
+            .with_account(address, AccountInfo::from_balance(U256::from(5)))
+            .with_storage(address, U256::from(2), U256::from(30));
+        let db = ChainedDB::new(MockDB::new(), fallback).with_fall_through(FallThrough {
+            storage: false,
+            ..FallThrough::ALL
+        });
+
+        assert!(db.basic_ref(address).unwrap().is_some());
+        assert_eq!(db.storage_ref(address, U256::from(2)), Ok(U256::ZERO));
+    }
+}
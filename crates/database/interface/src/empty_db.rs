@@ -1,9 +1,8 @@
 //! Empty database implementation.
-use crate::{DBErrorMarker, Database, DatabaseRef};
+use crate::{mock_db::empty_db_block_hash, DBErrorMarker, Database, DatabaseRef};
 use core::{convert::Infallible, fmt, marker::PhantomData};
-use primitives::{keccak256, Address, StorageKey, StorageValue, B256};
+use primitives::{Address, StorageKey, StorageValue, B256};
 use state::{AccountInfo, Bytecode};
-use std::string::ToString;
 
 /// An empty database that always returns default values when queried
 pub type EmptyDB = EmptyDBTyped<Infallible>;
@@ -108,7 +107,7 @@ impl<E: DBErrorMarker + core::error::Error + Send + Sync + 'static> DatabaseRef
 
     #[inline]
     fn block_hash_ref(&self, number: u64) -> Result<B256, Self::Error> {
-        Ok(keccak256(number.to_string().as_bytes()))
+        Ok(empty_db_block_hash(number))
     }
 }
 
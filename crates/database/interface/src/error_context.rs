@@ -0,0 +1,288 @@
+//! Database wrapper that adds the failed operation to database errors.
+use crate::{DBErrorMarker, Database, DatabaseCommit, DatabaseRef};
+use core::{error::Error, fmt};
+use primitives::{Address, AddressMap, StorageKey, StorageValue, B256};
+use state::{Account, AccountId, AccountInfo, Bytecode};
+
+/// Database operation that failed, see [`DBErrorWithContext`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum DBOperation {
+    /// [`Database::basic`] of the address.
+    Basic(Address),
+    /// [`Database::code_by_hash`] of the code hash.
+    CodeByHash(B256),
+    /// [`Database::storage`] of the address and slot.
+    Storage(Address, StorageKey),
+    /// [`Database::block_hash`] of the block number.
+    BlockHash(u64),
+}
+
+impl fmt::Display for DBOperation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Basic(address) => write!(f, "account {address}"),
+            Self::CodeByHash(code_hash) => write!(f, "code {code_hash}"),
+            Self::Storage(address, index) => write!(f, "storage slot {index:#x} of {address}"),
+            Self::BlockHash(number) => write!(f, "hash of block {number}"),
+        }
+    }
+}
+
+/// Database error together with the operation that caused it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DBErrorWithContext<E> {
+    /// Failed operation.
+    pub operation: DBOperation,
+    /// Error returned by the database.
+    pub error: E,
+}
+
+impl<E> DBErrorWithContext<E> {
+    /// Creates a new error of the operation.
+    pub const fn new(operation: DBOperation, error: E) -> Self {
+        Self { operation, error }
+    }
+
+    /// Returns the error returned by the database.
+    pub fn into_inner(self) -> E {
+        self.error
+    }
+}
+
+impl<E: fmt::Display> fmt::Display for DBErrorWithContext<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "failed to load {}: {}", self.operation, self.error)
+    }
+}
+
+impl<E: Error + 'static> Error for DBErrorWithContext<E> {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+impl<E: DBErrorMarker> DBErrorMarker for DBErrorWithContext<E> {
+    fn is_fatal(&self) -> bool {
+        self.error.is_fatal()
+    }
+}
+
+/// Wraps a database and adds the failed operation, with its address, slot, code hash or block
+/// number, to the errors of the database.
+///
+/// Errors of the wrapped database are returned unchanged by the layers above it, such as
+/// `State` or `CacheDB`, so a provider error from deep in a block replay tells what was being
+/// loaded when it is wrapped:
+///
+/// ```text
+/// failed to load storage slot 0x1 of 0x...: connection reset
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct WithErrorContext<DB> {
+    db: DB,
+}
+
+impl<DB> WithErrorContext<DB> {
+    /// Wraps the database.
+    pub const fn new(db: DB) -> Self {
+        Self { db }
+    }
+
+    /// Returns the inner database.
+    pub fn inner(&self) -> &DB {
+        &self.db
+    }
+
+    /// Returns the mutable inner database.
+    pub fn inner_mut(&mut self) -> &mut DB {
+        &mut self.db
+    }
+
+    /// Consumes the wrapper and returns the inner database.
+    pub fn into_inner(self) -> DB {
+        self.db
+    }
+}
+
+impl<DB: DatabaseCommit> DatabaseCommit for WithErrorContext<DB> {
+    #[inline]
+    fn commit(&mut self, changes: AddressMap<Account>) {
+        self.db.commit(changes)
+    }
+
+    #[inline]
+    fn commit_iter(&mut self, changes: &mut dyn Iterator<Item = (Address, Account)>) {
+        self.db.commit_iter(changes)
+    }
+}
+
+impl<DB: Database> Database for WithErrorContext<DB> {
+    type Error = DBErrorWithContext<DB::Error>;
+
+    #[inline]
+    fn basic(&mut self, address: Address) -> Result<Option<AccountInfo>, Self::Error> {
+        self.db
+            .basic(address)
+            .map_err(|e| DBErrorWithContext::new(DBOperation::Basic(address), e))
+    }
+
+    #[inline]
+    fn code_by_hash(&mut self, code_hash: B256) -> Result<Bytecode, Self::Error> {
+        self.db
+            .code_by_hash(code_hash)
+            .map_err(|e| DBErrorWithContext::new(DBOperation::CodeByHash(code_hash), e))
+    }
+
+    #[inline]
+    fn storage(
+        &mut self,
+        address: Address,
+        index: StorageKey,
+    ) -> Result<StorageValue, Self::Error> {
+        self.db
+            .storage(address, index)
+            .map_err(|e| DBErrorWithContext::new(DBOperation::Storage(address, index), e))
+    }
+
+    #[inline]
+    fn storage_by_account_id(
+        &mut self,
+        address: Address,
+        account_id: AccountId,
+        storage_key: StorageKey,
+    ) -> Result<StorageValue, Self::Error> {
+        self.db
+            .storage_by_account_id(address, account_id, storage_key)
+            .map_err(|e| DBErrorWithContext::new(DBOperation::Storage(address, storage_key), e))
+    }
+
+    #[inline]
+    fn block_hash(&mut self, number: u64) -> Result<B256, Self::Error> {
+        self.db
+            .block_hash(number)
+            .map_err(|e| DBErrorWithContext::new(DBOperation::BlockHash(number), e))
+    }
+}
+
+impl<DB: DatabaseRef> DatabaseRef for WithErrorContext<DB> {
+    type Error = DBErrorWithContext<DB::Error>;
+
+    #[inline]
+    fn basic_ref(&self, address: Address) -> Result<Option<AccountInfo>, Self::Error> {
+        self.db
+            .basic_ref(address)
+            .map_err(|e| DBErrorWithContext::new(DBOperation::Basic(address), e))
+    }
+
+    #[inline]
+    fn code_by_hash_ref(&self, code_hash: B256) -> Result<Bytecode, Self::Error> {
+        self.db
+            .code_by_hash_ref(code_hash)
+            .map_err(|e| DBErrorWithContext::new(DBOperation::CodeByHash(code_hash), e))
+    }
+
+    #[inline]
+    fn storage_ref(
+        &self,
+        address: Address,
+        index: StorageKey,
+    ) -> Result<StorageValue, Self::Error> {
+        self.db
+            .storage_ref(address, index)
+            .map_err(|e| DBErrorWithContext::new(DBOperation::Storage(address, index), e))
+    }
+
+    #[inline]
+    fn storage_by_account_id_ref(
+        &self,
+        address: Address,
+        account_id: AccountId,
+        storage_key: StorageKey,
+    ) -> Result<StorageValue, Self::Error> {
+        self.db
+            .storage_by_account_id_ref(address, account_id, storage_key)
+            .map_err(|e| DBErrorWithContext::new(DBOperation::Storage(address, storage_key), e))
+    }
+
+    #[inline]
+    fn block_hash_ref(&self, number: u64) -> Result<B256, Self::Error> {
+        self.db
+            .block_hash_ref(number)
+            .map_err(|e| DBErrorWithContext::new(DBOperation::BlockHash(number), e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::string::ToString;
+
+    #[derive(Debug)]
+    struct Unavailable;
+
+    impl fmt::Display for Unavailable {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str("provider unavailable")
+        }
+    }
+
+    impl Error for Unavailable {}
+
+    impl DBErrorMarker for Unavailable {
+        fn is_fatal(&self) -> bool {
+            false
+        }
+    }
+
+    struct FailingDB;
+
+    impl DatabaseRef for FailingDB {
+        type Error = Unavailable;
+
+        fn basic_ref(&self, _address: Address) -> Result<Option<AccountInfo>, Self::Error> {
+            Err(Unavailable)
+        }
+
+        fn code_by_hash_ref(&self, _code_hash: B256) -> Result<Bytecode, Self::Error> {
+            Err(Unavailable)
+        }
+
+        fn storage_ref(
+            &self,
+            _address: Address,
+            _index: StorageKey,
+        ) -> Result<StorageValue, Self::Error> {
+            Err(Unavailable)
+        }
+
+        fn block_hash_ref(&self, _number: u64) -> Result<B256, Self::Error> {
+            Err(Unavailable)
+        }
+    }
+
+    #[test]
+    fn adds_operation_to_errors() {
+        let db = WithErrorContext::new(FailingDB);
+        let address = Address::with_last_byte(1);
+
+        let err = db.storage_ref(address, StorageKey::from(1)).unwrap_err();
+        assert_eq!(
+            err.operation,
+            DBOperation::Storage(address, StorageKey::from(1))
+        );
+        assert_eq!(
+            err.to_string(),
+            "failed to load storage slot 0x1 of 0x0000000000000000000000000000000000000001: \
+             provider unavailable"
+        );
+        assert!(!err.is_fatal());
+        assert!(err.source().is_some());
+
+        let err = db.block_hash_ref(7).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "failed to load hash of block 7: provider unavailable"
+        );
+    }
+}
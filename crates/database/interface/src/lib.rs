@@ -33,16 +33,26 @@ pub use state;
 #[cfg(feature = "asyncdb")]
 pub mod async_db;
 pub mod bal;
+pub mod chained_db;
 pub mod either;
 pub mod empty_db;
 pub mod erased_error;
+pub mod error_context;
+pub mod mock_db;
+pub mod read_only_db;
 pub mod state_hook;
 pub mod try_commit;
 
 #[cfg(feature = "asyncdb")]
-pub use async_db::{AsyncDb, AsyncError, AsyncResult, DatabaseAsync, WrapDatabaseAsync};
+pub use async_db::{
+    AsyncDb, AsyncError, AsyncResult, DatabaseAsync, SpawnBlockingDb, WrapDatabaseAsync,
+};
+pub use chained_db::{ChainedDB, FallThrough};
 pub use empty_db::{EmptyDB, EmptyDBTyped};
 pub use erased_error::ErasedError;
+pub use error_context::{DBErrorWithContext, DBOperation, WithErrorContext};
+pub use mock_db::MockDB;
+pub use read_only_db::{OnWrite, ReadOnlyDB, ReadOnlyViolation};
 pub use state_hook::{NoopHook, OnStateHook};
 pub use try_commit::{ArcUpgradeError, TryDatabaseCommit};
 
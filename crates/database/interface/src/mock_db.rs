@@ -0,0 +1,191 @@
+//! Mock database with configurable synthetic defaults.
+use crate::{Database, DatabaseRef};
+use core::convert::Infallible;
+use primitives::{
+    keccak256, Address, AddressMap, B256Map, HashMap, StorageKey, StorageValue, B256,
+};
+use state::{AccountInfo, Bytecode};
+use std::string::ToString;
+
+/// Block hash function of [`EmptyDB`](crate::EmptyDB), `keccak256` of the decimal block number.
+pub fn empty_db_block_hash(number: u64) -> B256 {
+    keccak256(number.to_string().as_bytes())
+}
+
+/// Database that returns synthetic defaults for everything that was not registered.
+///
+/// Unlike [`EmptyDB`](crate::EmptyDB), it can pretend that every address holds an account,
+/// which makes it easy to unit-test handlers that require an existing account without
+/// building a full `CacheDB`.
+#[derive(Clone, Debug)]
+pub struct MockDB {
+    /// Registered accounts.
+    accounts: AddressMap<AccountInfo>,
+    /// Registered storage.
+    storage: HashMap<(Address, StorageKey), StorageValue>,
+    /// Code of registered accounts and of the default code.
+    contracts: B256Map<Bytecode>,
+    /// Account returned for unregistered addresses.
+    default_account: Option<AccountInfo>,
+    /// Function used to compute block hashes.
+    block_hash: fn(u64) -> B256,
+}
+
+impl Default for MockDB {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MockDB {
+    /// Creates a new mock database that behaves like [`EmptyDB`](crate::EmptyDB).
+    pub fn new() -> Self {
+        Self {
+            accounts: AddressMap::default(),
+            storage: HashMap::default(),
+            contracts: B256Map::default(),
+            default_account: None,
+            block_hash: empty_db_block_hash,
+        }
+    }
+
+    /// Registers the account info of the address.
+    pub fn with_account(mut self, address: Address, info: AccountInfo) -> Self {
+        if let Some(code) = &info.code {
+            self.contracts.insert(info.code_hash, code.clone());
+        }
+        self.accounts.insert(address, info);
+        self
+    }
+
+    /// Registers the storage value of the address.
+    pub fn with_storage(mut self, address: Address, key: StorageKey, value: StorageValue) -> Self {
+        self.storage.insert((address, key), value);
+        self
+    }
+
+    /// Sets the account info returned for every unregistered address.
+    pub fn with_default_account(mut self, info: AccountInfo) -> Self {
+        if let Some(code) = &info.code {
+            self.contracts.insert(info.code_hash, code.clone());
+        }
+        self.default_account = Some(info);
+        self
+    }
+
+    /// Sets the code of every unregistered address.
+    ///
+    /// Unregistered addresses get the default account, or an account with nonce `1` if the
+    /// default account is not set, with the given code.
+    pub fn with_default_code(mut self, code: Bytecode) -> Self {
+        let info = self
+            .default_account
+            .take()
+            .unwrap_or_else(|| AccountInfo::default().with_nonce(1));
+        self.with_default_account(info.with_code(code))
+    }
+
+    /// Sets the function used to compute block hashes.
+    ///
+    /// Defaults to [`empty_db_block_hash`].
+    pub fn with_block_hash_fn(mut self, block_hash: fn(u64) -> B256) -> Self {
+        self.block_hash = block_hash;
+        self
+    }
+}
+
+impl Database for MockDB {
+    type Error = Infallible;
+
+    #[inline]
+    fn basic(&mut self, address: Address) -> Result<Option<AccountInfo>, Self::Error> {
+        self.basic_ref(address)
+    }
+
+    #[inline]
+    fn code_by_hash(&mut self, code_hash: B256) -> Result<Bytecode, Self::Error> {
+        self.code_by_hash_ref(code_hash)
+    }
+
+    #[inline]
+    fn storage(
+        &mut self,
+        address: Address,
+        index: StorageKey,
+    ) -> Result<StorageValue, Self::Error> {
+        self.storage_ref(address, index)
+    }
+
+    #[inline]
+    fn block_hash(&mut self, number: u64) -> Result<B256, Self::Error> {
+        self.block_hash_ref(number)
+    }
+}
+
+impl DatabaseRef for MockDB {
+    type Error = Infallible;
+
+    fn basic_ref(&self, address: Address) -> Result<Option<AccountInfo>, Self::Error> {
+        Ok(self
+            .accounts
+            .get(&address)
+            .or(self.default_account.as_ref())
+            .cloned())
+    }
+
+    fn code_by_hash_ref(&self, code_hash: B256) -> Result<Bytecode, Self::Error> {
+        Ok(self.contracts.get(&code_hash).cloned().unwrap_or_default())
+    }
+
+    fn storage_ref(
+        &self,
+        address: Address,
+        index: StorageKey,
+    ) -> Result<StorageValue, Self::Error> {
+        Ok(self
+            .storage
+            .get(&(address, index))
+            .copied()
+            .unwrap_or_default())
+    }
+
+    fn block_hash_ref(&self, number: u64) -> Result<B256, Self::Error> {
+        Ok((self.block_hash)(number))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::EmptyDB;
+    use primitives::{Bytes, U256};
+
+    #[test]
+    fn behaves_like_empty_db() {
+        let db = MockDB::new();
+        assert_eq!(db.basic_ref(Address::ZERO), Ok(None));
+        assert_eq!(db.block_hash_ref(7), EmptyDB::new().block_hash_ref(7));
+    }
+
+    #[test]
+    fn synthetic_defaults() {
+        let registered = Address::with_last_byte(1);
+        let code = Bytecode::new_legacy(Bytes::from_static(&[0x00]));
+        let db = MockDB::new()
+            .with_account(registered, AccountInfo::from_balance(U256::from(1)))
+            .with_storage(registered, U256::from(2), U256::from(3))
+            .with_default_code(code.clone())
+            .with_block_hash_fn(|number| B256::with_last_byte(number as u8));
+
+        let info = db.basic_ref(registered).unwrap().unwrap();
+        assert_eq!(info.balance, U256::from(1));
+        assert!(info.code.is_none());
+        assert_eq!(db.storage_ref(registered, U256::from(2)), Ok(U256::from(3)));
+
+        let unknown = db.basic_ref(Address::with_last_byte(2)).unwrap().unwrap();
+        assert_eq!(unknown.nonce, 1);
+        assert_eq!(db.code_by_hash_ref(unknown.code_hash), Ok(code));
+
+        assert_eq!(db.block_hash_ref(5), Ok(B256::with_last_byte(5)));
+    }
+}
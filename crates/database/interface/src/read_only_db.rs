@@ -0,0 +1,236 @@
+//! Database wrapper that forbids commits.
+use crate::{Database, DatabaseCommit, DatabaseRef};
+use core::{error::Error, fmt};
+use primitives::{Address, AddressMap, StorageKey, StorageValue, B256};
+use state::{Account, AccountId, AccountInfo, Bytecode};
+use std::vec::Vec;
+
+/// What [`ReadOnlyDB`] does when changes are committed to it.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum OnWrite {
+    /// Panic on commit.
+    #[default]
+    Panic,
+    /// Discard the changes and record them, see [`ReadOnlyDB::attempted_writes`].
+    Record,
+}
+
+/// Error returned by [`ReadOnlyDB::ensure_read_only`] when changes were committed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ReadOnlyViolation {
+    /// Number of rejected commits.
+    pub commits: usize,
+}
+
+impl fmt::Display for ReadOnlyViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} commit(s) attempted on a read-only database",
+            self.commits
+        )
+    }
+}
+
+impl Error for ReadOnlyViolation {}
+
+/// Wraps a database and guarantees that it is never mutated through [`DatabaseCommit`].
+///
+/// Reads are forwarded to the inner database. Commits that change at least one account
+/// either panic or are discarded and recorded, depending on [`OnWrite`]. Commits of untouched
+/// accounts are ignored.
+#[derive(Clone, Debug, Default)]
+pub struct ReadOnlyDB<DB> {
+    db: DB,
+    on_write: OnWrite,
+    /// Touched accounts of every rejected commit.
+    attempted_writes: Vec<AddressMap<Account>>,
+}
+
+impl<DB> ReadOnlyDB<DB> {
+    /// Wraps the database, panicking on commit.
+    pub fn new(db: DB) -> Self {
+        Self {
+            db,
+            on_write: OnWrite::Panic,
+            attempted_writes: Vec::new(),
+        }
+    }
+
+    /// Wraps the database, recording the changes instead of panicking on commit.
+    pub fn recording(db: DB) -> Self {
+        Self::new(db).with_on_write(OnWrite::Record)
+    }
+
+    /// Sets what happens on commit.
+    pub fn with_on_write(self, on_write: OnWrite) -> Self {
+        Self { on_write, ..self }
+    }
+
+    /// Returns the inner database.
+    pub fn inner(&self) -> &DB {
+        &self.db
+    }
+
+    /// Consumes the wrapper and returns the inner database.
+    pub fn into_inner(self) -> DB {
+        self.db
+    }
+
+    /// Returns the touched accounts of every rejected commit.
+    pub fn attempted_writes(&self) -> &[AddressMap<Account>] {
+        &self.attempted_writes
+    }
+
+    /// Takes the recorded writes.
+    pub fn take_attempted_writes(&mut self) -> Vec<AddressMap<Account>> {
+        core::mem::take(&mut self.attempted_writes)
+    }
+
+    /// Returns an error if any commit was attempted.
+    pub fn ensure_read_only(&self) -> Result<(), ReadOnlyViolation> {
+        if self.attempted_writes.is_empty() {
+            Ok(())
+        } else {
+            Err(ReadOnlyViolation {
+                commits: self.attempted_writes.len(),
+            })
+        }
+    }
+}
+
+impl<DB> DatabaseCommit for ReadOnlyDB<DB> {
+    fn commit(&mut self, changes: AddressMap<Account>) {
+        self.commit_iter(&mut changes.into_iter())
+    }
+
+    fn commit_iter(&mut self, changes: &mut dyn Iterator<Item = (Address, Account)>) {
+        let touched: AddressMap<Account> = changes
+            .filter(|(_, account)| account.is_touched())
+            .collect();
+        if touched.is_empty() {
+            return;
+        }
+        match self.on_write {
+            OnWrite::Panic => panic!(
+                "attempted to commit {} account(s) to a read-only database",
+                touched.len()
+            ),
+            OnWrite::Record => self.attempted_writes.push(touched),
+        }
+    }
+}
+
+impl<DB: Database> Database for ReadOnlyDB<DB> {
+    type Error = DB::Error;
+
+    #[inline]
+    fn basic(&mut self, address: Address) -> Result<Option<AccountInfo>, Self::Error> {
+        self.db.basic(address)
+    }
+
+    #[inline]
+    fn code_by_hash(&mut self, code_hash: B256) -> Result<Bytecode, Self::Error> {
+        self.db.code_by_hash(code_hash)
+    }
+
+    #[inline]
+    fn storage(
+        &mut self,
+        address: Address,
+        index: StorageKey,
+    ) -> Result<StorageValue, Self::Error> {
+        self.db.storage(address, index)
+    }
+
+    #[inline]
+    fn storage_by_account_id(
+        &mut self,
+        address: Address,
+        account_id: AccountId,
+        storage_key: StorageKey,
+    ) -> Result<StorageValue, Self::Error> {
+        self.db
+            .storage_by_account_id(address, account_id, storage_key)
+    }
+
+    #[inline]
+    fn block_hash(&mut self, number: u64) -> Result<B256, Self::Error> {
+        self.db.block_hash(number)
+    }
+}
+
+impl<DB: DatabaseRef> DatabaseRef for ReadOnlyDB<DB> {
+    type Error = DB::Error;
+
+    #[inline]
+    fn basic_ref(&self, address: Address) -> Result<Option<AccountInfo>, Self::Error> {
+        self.db.basic_ref(address)
+    }
+
+    #[inline]
+    fn code_by_hash_ref(&self, code_hash: B256) -> Result<Bytecode, Self::Error> {
+        self.db.code_by_hash_ref(code_hash)
+    }
+
+    #[inline]
+    fn storage_ref(
+        &self,
+        address: Address,
+        index: StorageKey,
+    ) -> Result<StorageValue, Self::Error> {
+        self.db.storage_ref(address, index)
+    }
+
+    #[inline]
+    fn storage_by_account_id_ref(
+        &self,
+        address: Address,
+        account_id: AccountId,
+        storage_key: StorageKey,
+    ) -> Result<StorageValue, Self::Error> {
+        self.db
+            .storage_by_account_id_ref(address, account_id, storage_key)
+    }
+
+    #[inline]
+    fn block_hash_ref(&self, number: u64) -> Result<B256, Self::Error> {
+        self.db.block_hash_ref(number)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::EmptyDB;
+
+    fn changes() -> AddressMap<Account> {
+        let mut touched = Account::default();
+        touched.mark_touch();
+        AddressMap::from_iter([
+            (Address::with_last_byte(1), touched),
+            (Address::with_last_byte(2), Account::default()),
+        ])
+    }
+
+    #[test]
+    fn records_writes() {
+        let mut db = ReadOnlyDB::recording(EmptyDB::new());
+        db.commit(AddressMap::from_iter([(Address::ZERO, Account::default())]));
+        assert_eq!(db.ensure_read_only(), Ok(()));
+
+        db.commit(changes());
+        assert_eq!(db.ensure_read_only(), Err(ReadOnlyViolation { commits: 1 }));
+        let writes = db.take_attempted_writes();
+        assert_eq!(writes.len(), 1);
+        assert!(writes[0].contains_key(&Address::with_last_byte(1)));
+        assert!(!writes[0].contains_key(&Address::with_last_byte(2)));
+    }
+
+    #[test]
+    #[should_panic(expected = "read-only database")]
+    fn panics_on_write() {
+        ReadOnlyDB::new(EmptyDB::new()).commit(changes());
+    }
+}
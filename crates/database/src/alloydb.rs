@@ -1,16 +1,17 @@
 //! Alloy provider database implementation.
 
+use crate::rate_limiter::RateLimiter;
 pub use alloy_eips::BlockId;
 use alloy_provider::{
     network::{primitives::HeaderResponse, BlockResponse},
     Network, Provider,
 };
-use alloy_transport::TransportError;
-use core::error::Error;
+use alloy_transport::{RpcError, TransportError, TransportErrorKind};
+use core::{error::Error, num::NonZeroU32};
 use database_interface::{async_db::DatabaseAsyncRef, DBErrorMarker};
 use primitives::{Address, StorageKey, StorageValue, B256};
 use state::{AccountInfo, Bytecode};
-use std::fmt::Display;
+use std::{fmt::Display, io, sync::Arc};
 
 /// Error type for AlloyDB database operations.
 #[derive(Debug)]
@@ -25,7 +26,45 @@ pub enum AlloyDBError {
     BlockNotFound(u64),
 }
 
-impl DBErrorMarker for AlloyDBError {}
+impl DBErrorMarker for AlloyDBError {
+    /// Transport errors that are likely to succeed when retried, like timeouts, dropped
+    /// connections and rate limits, are not fatal, so they can be retried with a
+    /// [`RetryPolicy`](crate::RetryPolicy).
+    fn is_fatal(&self) -> bool {
+        match self {
+            Self::Transport(err) => !is_transient(err),
+            Self::BlockNotFound(_) => true,
+        }
+    }
+}
+
+/// Returns `true` if the request may succeed when it is sent again.
+fn is_transient(err: &TransportError) -> bool {
+    // Rate limits and temporarily unavailable backends.
+    if err.is_retry_err() {
+        return true;
+    }
+    // Timeouts and resets of the connection are reported by the client as custom errors.
+    let RpcError::Transport(TransportErrorKind::Custom(err)) = err else {
+        return false;
+    };
+    let mut source: Option<&(dyn Error + 'static)> = Some(&**err);
+    while let Some(err) = source {
+        if let Some(err) = err.downcast_ref::<io::Error>() {
+            return matches!(
+                err.kind(),
+                io::ErrorKind::TimedOut
+                    | io::ErrorKind::ConnectionReset
+                    | io::ErrorKind::ConnectionAborted
+                    | io::ErrorKind::ConnectionRefused
+                    | io::ErrorKind::BrokenPipe
+                    | io::ErrorKind::UnexpectedEof
+            );
+        }
+        source = err.source();
+    }
+    false
+}
 
 impl Display for AlloyDBError {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
@@ -54,12 +93,16 @@ impl From<TransportError> for AlloyDBError {
 /// An alloy-powered REVM [Database][database_interface::Database].
 ///
 /// When accessing the database, it'll use the given provider to fetch the corresponding account's data.
+///
+/// Requests can be throttled with a [`RateLimiter`], see [`AlloyDB::with_rate_limit`].
 #[derive(Debug)]
 pub struct AlloyDB<N: Network, P: Provider<N>> {
     /// The provider to fetch the data from.
     provider: P,
     /// The block number on which the queries will be based on.
     block_number: BlockId,
+    /// Limiter of the requests sent to the provider.
+    rate_limiter: Option<Arc<RateLimiter>>,
     _marker: core::marker::PhantomData<fn() -> N>,
 }
 
@@ -69,10 +112,43 @@ impl<N: Network, P: Provider<N>> AlloyDB<N, P> {
         Self {
             provider,
             block_number,
+            rate_limiter: None,
             _marker: core::marker::PhantomData,
         }
     }
 
+    /// Limits the requests sent to the provider to `requests_per_second` on average and `burst`
+    /// at once.
+    pub fn with_rate_limit(self, requests_per_second: NonZeroU32, burst: NonZeroU32) -> Self {
+        self.with_rate_limiter(Arc::new(RateLimiter::new(requests_per_second, burst)))
+    }
+
+    /// Limits the requests sent to the provider with the limiter.
+    ///
+    /// The limiter can be shared with other databases and tasks using the same endpoint, e.g.
+    /// prefetching tasks, so all requests count towards the same limit.
+    pub fn with_rate_limiter(mut self, rate_limiter: Arc<RateLimiter>) -> Self {
+        self.rate_limiter = Some(rate_limiter);
+        self
+    }
+
+    /// Returns the limiter of the requests sent to the provider.
+    pub fn rate_limiter(&self) -> Option<&Arc<RateLimiter>> {
+        self.rate_limiter.as_ref()
+    }
+
+    /// Waits until `requests` requests can be sent to the provider.
+    async fn throttle(&self, requests: u32) {
+        if let Some(rate_limiter) = &self.rate_limiter {
+            rate_limiter.acquire(requests).await;
+        }
+    }
+
+    /// Returns the block on which the queries are based on.
+    pub const fn block_number(&self) -> BlockId {
+        self.block_number
+    }
+
     /// Sets the block number on which the queries will be based on.
     pub const fn set_block_number(&mut self, block_number: BlockId) {
         self.block_number = block_number;
@@ -83,6 +159,7 @@ impl<N: Network, P: Provider<N>> DatabaseAsyncRef for AlloyDB<N, P> {
     type Error = AlloyDBError;
 
     async fn basic_async_ref(&self, address: Address) -> Result<Option<AccountInfo>, Self::Error> {
+        self.throttle(3).await;
         let nonce = self
             .provider
             .get_transaction_count(address)
@@ -107,6 +184,7 @@ impl<N: Network, P: Provider<N>> DatabaseAsyncRef for AlloyDB<N, P> {
     }
 
     async fn block_hash_async_ref(&self, number: u64) -> Result<B256, Self::Error> {
+        self.throttle(1).await;
         let block = self
             .provider
             // SAFETY: We know number <= u64::MAX, so we can safely convert it to u64
@@ -129,6 +207,7 @@ impl<N: Network, P: Provider<N>> DatabaseAsyncRef for AlloyDB<N, P> {
         address: Address,
         index: StorageKey,
     ) -> Result<StorageValue, Self::Error> {
+        self.throttle(1).await;
         Ok(self
             .provider
             .get_storage_at(address, index)
@@ -143,6 +222,36 @@ mod tests {
     use alloy_provider::ProviderBuilder;
     use database_interface::{DatabaseRef, WrapDatabaseAsync};
 
+    #[test]
+    fn transient_errors_are_not_fatal() {
+        let timeout = || {
+            AlloyDBError::from(TransportErrorKind::custom(io::Error::from(
+                io::ErrorKind::TimedOut,
+            )))
+        };
+        assert!(!timeout().is_fatal());
+        assert!(
+            !AlloyDBError::from(TransportErrorKind::custom(io::Error::from(
+                io::ErrorKind::ConnectionReset
+            )))
+            .is_fatal()
+        );
+        assert!(AlloyDBError::from(TransportErrorKind::custom_str("invalid response")).is_fatal());
+        assert!(AlloyDBError::BlockNotFound(1).is_fatal());
+
+        // Timed out reads are retried.
+        let mut attempts = 0;
+        let result = crate::RetryPolicy::new(3).run(|| {
+            attempts += 1;
+            if attempts < 3 {
+                Err(timeout())
+            } else {
+                Ok(attempts)
+            }
+        });
+        assert_eq!(result.unwrap(), 3);
+    }
+
     #[tokio::test]
     #[ignore = "flaky RPC"]
     async fn can_get_basic() {
@@ -0,0 +1,194 @@
+use core::{
+    mem::size_of,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+/// Hits and misses of lookups in a cache.
+///
+/// A lookup is a hit if it was served without asking the underlying database.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct HitRatio {
+    /// Lookups served from the cache.
+    pub hits: u64,
+    /// Lookups forwarded to the underlying database.
+    pub misses: u64,
+}
+
+impl HitRatio {
+    /// Returns the total number of lookups.
+    pub const fn lookups(&self) -> u64 {
+        self.hits + self.misses
+    }
+
+    /// Returns the share of lookups served from the cache, `0.0` if there were no lookups.
+    pub const fn ratio(&self) -> f64 {
+        match self.lookups() {
+            0 => 0.0,
+            lookups => self.hits as f64 / lookups as f64,
+        }
+    }
+}
+
+impl core::ops::Add for HitRatio {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self {
+            hits: self.hits + rhs.hits,
+            misses: self.misses + rhs.misses,
+        }
+    }
+}
+
+/// Counter of hits and misses of a single kind of lookup.
+///
+/// Counters are atomic so they can be updated by lookups through
+/// [`DatabaseRef`](database_interface::DatabaseRef).
+#[derive(Debug, Default)]
+pub struct HitCounter {
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl Clone for HitCounter {
+    fn clone(&self) -> Self {
+        let ratio = self.get();
+        Self {
+            hits: AtomicU64::new(ratio.hits),
+            misses: AtomicU64::new(ratio.misses),
+        }
+    }
+}
+
+impl HitCounter {
+    /// Counts a lookup served from the cache.
+    #[inline]
+    pub fn hit(&self) {
+        self.hits.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Counts a lookup forwarded to the underlying database.
+    #[inline]
+    pub fn miss(&self) {
+        self.misses.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Returns the hits and misses counted since the last reset.
+    pub fn get(&self) -> HitRatio {
+        HitRatio {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Resets the counter.
+    pub fn reset(&self) {
+        self.hits.store(0, Ordering::Relaxed);
+        self.misses.store(0, Ordering::Relaxed);
+    }
+}
+
+/// Hit counters of the lookups of a database cache.
+#[derive(Clone, Debug, Default)]
+pub struct CacheCounters {
+    /// Account lookups.
+    pub accounts: HitCounter,
+    /// Storage slot lookups.
+    pub storage: HitCounter,
+    /// Contract code lookups.
+    pub contracts: HitCounter,
+    /// Block hash lookups.
+    pub block_hashes: HitCounter,
+}
+
+impl CacheCounters {
+    /// Resets all counters.
+    pub fn reset(&self) {
+        self.accounts.reset();
+        self.storage.reset();
+        self.contracts.reset();
+        self.block_hashes.reset();
+    }
+}
+
+/// Size and hit ratios of a database cache.
+///
+/// Byte sizes are estimated from the size of the map entries and the length of the bytecode,
+/// they don't include the unused capacity of the maps or shared allocations.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CacheStats {
+    /// Number of cached accounts, including accounts known not to exist.
+    pub accounts: usize,
+    /// Number of cached storage slots.
+    pub storage_slots: usize,
+    /// Number of cached contracts.
+    pub contracts: usize,
+    /// Number of cached block hashes.
+    pub block_hashes: usize,
+    /// Estimated size of the cached accounts in bytes, without their storage.
+    pub account_bytes: usize,
+    /// Estimated size of the cached storage slots in bytes.
+    pub storage_bytes: usize,
+    /// Estimated size of the cached contracts in bytes.
+    pub contract_bytes: usize,
+    /// Estimated size of the cached block hashes in bytes.
+    pub block_hash_bytes: usize,
+    /// Account lookups since the last reset.
+    pub account_hits: HitRatio,
+    /// Storage slot lookups since the last reset.
+    pub storage_hits: HitRatio,
+    /// Contract code lookups since the last reset.
+    pub contract_hits: HitRatio,
+    /// Block hash lookups since the last reset.
+    pub block_hash_hits: HitRatio,
+}
+
+impl CacheStats {
+    /// Returns the estimated size of the cache in bytes.
+    pub const fn estimated_bytes(&self) -> usize {
+        self.account_bytes + self.storage_bytes + self.contract_bytes + self.block_hash_bytes
+    }
+
+    /// Returns the hits and misses of all lookups since the last reset.
+    pub fn hits(&self) -> HitRatio {
+        self.account_hits + self.storage_hits + self.contract_hits + self.block_hash_hits
+    }
+
+    /// Sets the hit ratios from the counters.
+    pub(crate) fn with_counters(mut self, counters: &CacheCounters) -> Self {
+        self.account_hits = counters.accounts.get();
+        self.storage_hits = counters.storage.get();
+        self.contract_hits = counters.contracts.get();
+        self.block_hash_hits = counters.block_hashes.get();
+        self
+    }
+}
+
+/// Returns the estimated size of `len` map entries.
+#[inline]
+pub(crate) const fn entries_bytes<K, V>(len: usize) -> usize {
+    len * size_of::<(K, V)>()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hit_ratio() {
+        let counter = HitCounter::default();
+        assert_eq!(counter.get().ratio(), 0.0);
+
+        counter.hit();
+        counter.hit();
+        counter.hit();
+        counter.miss();
+        assert_eq!(counter.get(), HitRatio { hits: 3, misses: 1 });
+        assert_eq!(counter.get().ratio(), 0.75);
+
+        counter.reset();
+        assert_eq!(counter.get().lookups(), 0);
+    }
+}
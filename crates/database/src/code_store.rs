@@ -0,0 +1,275 @@
+use bytecode::Bytecode;
+use database_interface::{Database, DatabaseCommit, DatabaseRef};
+use primitives::{Address, AddressMap, B256Map, StorageKey, StorageValue, B256};
+use state::{Account, AccountId, AccountInfo};
+use std::sync::{Arc, OnceLock, RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+/// Store of contract code deduplicated by code hash.
+///
+/// [`Bytecode`] is reference counted, interning the code loaded by each [`State`](crate::State)
+/// or [`CacheDB`](crate::CacheDB) makes all of them share one copy of each contract. Services
+/// running many forks of the same chain then hold popular contracts, like tokens and routers,
+/// once instead of once per fork.
+///
+/// Code is interned by wrapping the database of the caches in [`WithCodeStore`]. A
+/// process-wide store is returned by [`CodeStore::global`]. Entries are never evicted, call
+/// [`CodeStore::clear`] to release the code that is not used by any cache anymore.
+#[derive(Debug, Default)]
+pub struct CodeStore {
+    codes: RwLock<B256Map<Bytecode>>,
+}
+
+impl CodeStore {
+    /// Creates an empty store.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the process-wide store.
+    pub fn global() -> Arc<Self> {
+        static GLOBAL: OnceLock<Arc<CodeStore>> = OnceLock::new();
+        GLOBAL.get_or_init(Default::default).clone()
+    }
+
+    /// Returns the code with the given hash.
+    pub fn get(&self, code_hash: &B256) -> Option<Bytecode> {
+        self.read().get(code_hash).cloned()
+    }
+
+    /// Returns the stored code with the given hash, storing `code` if there is none.
+    ///
+    /// Empty code is returned as is.
+    pub fn intern(&self, code_hash: B256, code: Bytecode) -> Bytecode {
+        if code.is_empty() {
+            return code;
+        }
+        if let Some(stored) = self.get(&code_hash) {
+            return stored;
+        }
+        self.write().entry(code_hash).or_insert(code).clone()
+    }
+
+    /// Returns the number of stored contracts.
+    pub fn len(&self) -> usize {
+        self.read().len()
+    }
+
+    /// Returns `true` if no contract is stored.
+    pub fn is_empty(&self) -> bool {
+        self.read().is_empty()
+    }
+
+    /// Removes all contracts from the store.
+    ///
+    /// Caches keep their copies, code loaded afterwards is stored again.
+    pub fn clear(&self) {
+        self.write().clear();
+    }
+
+    fn read(&self) -> RwLockReadGuard<'_, B256Map<Bytecode>> {
+        self.codes.read().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn write(&self) -> RwLockWriteGuard<'_, B256Map<Bytecode>> {
+        self.codes.write().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Database wrapper that interns the code loaded from the inner database in a [`CodeStore`].
+///
+/// ```ignore
+/// let state = State::builder()
+///     .with_database(WithCodeStore::global(db))
+///     .build();
+/// ```
+#[derive(Clone, Debug)]
+pub struct WithCodeStore<DB> {
+    db: DB,
+    store: Arc<CodeStore>,
+}
+
+impl<DB> WithCodeStore<DB> {
+    /// Wraps the database, interning its code in the store.
+    pub const fn new(db: DB, store: Arc<CodeStore>) -> Self {
+        Self { db, store }
+    }
+
+    /// Wraps the database, interning its code in the [global](CodeStore::global) store.
+    pub fn global(db: DB) -> Self {
+        Self::new(db, CodeStore::global())
+    }
+
+    /// Returns the store.
+    pub fn store(&self) -> &Arc<CodeStore> {
+        &self.store
+    }
+
+    /// Returns the inner database.
+    pub fn inner(&self) -> &DB {
+        &self.db
+    }
+
+    /// Returns the mutable inner database.
+    pub fn inner_mut(&mut self) -> &mut DB {
+        &mut self.db
+    }
+
+    /// Consumes the wrapper and returns the inner database.
+    pub fn into_inner(self) -> DB {
+        self.db
+    }
+
+    /// Interns the code of the account.
+    fn intern_info(&self, mut info: Option<AccountInfo>) -> Option<AccountInfo> {
+        if let Some(info) = &mut info {
+            if let Some(code) = info.code.take() {
+                info.code = Some(self.store.intern(info.code_hash, code));
+            }
+        }
+        info
+    }
+}
+
+impl<DB: DatabaseCommit> DatabaseCommit for WithCodeStore<DB> {
+    #[inline]
+    fn commit(&mut self, changes: AddressMap<Account>) {
+        self.db.commit(changes)
+    }
+
+    #[inline]
+    fn commit_iter(&mut self, changes: &mut dyn Iterator<Item = (Address, Account)>) {
+        self.db.commit_iter(changes)
+    }
+}
+
+impl<DB: Database> Database for WithCodeStore<DB> {
+    type Error = DB::Error;
+
+    #[inline]
+    fn basic(&mut self, address: Address) -> Result<Option<AccountInfo>, Self::Error> {
+        let info = self.db.basic(address)?;
+        Ok(self.intern_info(info))
+    }
+
+    #[inline]
+    fn code_by_hash(&mut self, code_hash: B256) -> Result<Bytecode, Self::Error> {
+        if let Some(code) = self.store.get(&code_hash) {
+            return Ok(code);
+        }
+        let code = self.db.code_by_hash(code_hash)?;
+        Ok(self.store.intern(code_hash, code))
+    }
+
+    #[inline]
+    fn storage(
+        &mut self,
+        address: Address,
+        index: StorageKey,
+    ) -> Result<StorageValue, Self::Error> {
+        self.db.storage(address, index)
+    }
+
+    #[inline]
+    fn storage_by_account_id(
+        &mut self,
+        address: Address,
+        account_id: AccountId,
+        storage_key: StorageKey,
+    ) -> Result<StorageValue, Self::Error> {
+        self.db
+            .storage_by_account_id(address, account_id, storage_key)
+    }
+
+    #[inline]
+    fn block_hash(&mut self, number: u64) -> Result<B256, Self::Error> {
+        self.db.block_hash(number)
+    }
+}
+
+impl<DB: DatabaseRef> DatabaseRef for WithCodeStore<DB> {
+    type Error = DB::Error;
+
+    #[inline]
+    fn basic_ref(&self, address: Address) -> Result<Option<AccountInfo>, Self::Error> {
+        let info = self.db.basic_ref(address)?;
+        Ok(self.intern_info(info))
+    }
+
+    #[inline]
+    fn code_by_hash_ref(&self, code_hash: B256) -> Result<Bytecode, Self::Error> {
+        if let Some(code) = self.store.get(&code_hash) {
+            return Ok(code);
+        }
+        let code = self.db.code_by_hash_ref(code_hash)?;
+        Ok(self.store.intern(code_hash, code))
+    }
+
+    #[inline]
+    fn storage_ref(
+        &self,
+        address: Address,
+        index: StorageKey,
+    ) -> Result<StorageValue, Self::Error> {
+        self.db.storage_ref(address, index)
+    }
+
+    #[inline]
+    fn storage_by_account_id_ref(
+        &self,
+        address: Address,
+        account_id: AccountId,
+        storage_key: StorageKey,
+    ) -> Result<StorageValue, Self::Error> {
+        self.db
+            .storage_by_account_id_ref(address, account_id, storage_key)
+    }
+
+    #[inline]
+    fn block_hash_ref(&self, number: u64) -> Result<B256, Self::Error> {
+        self.db.block_hash_ref(number)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CacheDB, EmptyDB};
+    use primitives::Bytes;
+
+    #[test]
+    fn interns_code() {
+        let store = CodeStore::new();
+        let code = Bytecode::new_raw(Bytes::from_static(&[0x60, 0x00]));
+        let hash = code.hash_slow();
+
+        let first = store.intern(hash, code.clone());
+        let second = store.intern(hash, Bytecode::new_raw(Bytes::from_static(&[0x60, 0x00])));
+        assert_eq!(first, second);
+        assert!(core::ptr::eq(first.bytes_slice(), second.bytes_slice()));
+        assert_eq!(store.len(), 1);
+
+        // Empty code is not stored.
+        store.intern(B256::ZERO, Bytecode::default());
+        assert_eq!(store.len(), 1);
+    }
+
+    #[test]
+    fn forks_share_code() {
+        let address = Address::with_last_byte(1);
+        let store = Arc::new(CodeStore::new());
+        // Each fork loads its own copy of the code from its database.
+        let fork = || {
+            let mut db = CacheDB::<EmptyDB>::default();
+            let code = Bytecode::new_raw(Bytes::from_static(&[0x60, 0x01]));
+            db.insert_account_info(address, AccountInfo::default().with_code(code));
+            CacheDB::new(WithCodeStore::new(db, store.clone()))
+        };
+
+        let (mut first, mut second) = (fork(), fork());
+        let first = first.basic(address).unwrap().unwrap();
+        let second = second.basic(address).unwrap().unwrap();
+        let (first, second) = (first.code.unwrap(), second.code.unwrap());
+        assert!(core::ptr::eq(first.bytes_slice(), second.bytes_slice()));
+        assert_eq!(store.len(), 1);
+    }
+}
@@ -1,3 +1,6 @@
+use crate::cache_stats::{entries_bytes, CacheCounters, CacheStats};
+#[cfg(feature = "std")]
+use crate::{negative_cache, NegativeCache};
 use core::convert::Infallible;
 use database_interface::{
     Database, DatabaseCommit, DatabaseRef, EmptyDB, BENCH_CALLER, BENCH_CALLER_BALANCE,
@@ -50,6 +53,8 @@ impl Default for Cache {
 /// A [Database] implementation that stores all state changes in memory.
 ///
 /// This implementation wraps a [DatabaseRef] that is used to load data ([AccountInfo]).
+/// Errors of the wrapped database are returned as is, see
+/// [WithErrorContext](database_interface::WithErrorContext) to add the failed operation to them.
 #[derive(Debug, Clone)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CacheDB<ExtDB> {
@@ -59,6 +64,15 @@ pub struct CacheDB<ExtDB> {
     ///
     /// Note: This is read-only, data is never written to this database.
     pub db: ExtDB,
+    /// Accounts the underlying database reported as not existing, see [`NegativeCache`].
+    ///
+    /// Disabled by default, enabled with [`CacheDB::with_negative_cache`].
+    #[cfg(feature = "std")]
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub negative_cache: Option<NegativeCache>,
+    /// Hit counters of the lookups in the cache, see [`CacheDB::cache_stats`].
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub cache_counters: CacheCounters,
 }
 
 impl<ExtDB: Default> Default for CacheDB<ExtDB> {
@@ -107,7 +121,57 @@ impl<ExtDB> CacheDB<ExtDB> {
         Self {
             cache: Cache::default(),
             db,
+            #[cfg(feature = "std")]
+            negative_cache: None,
+            cache_counters: CacheCounters::default(),
+        }
+    }
+
+    /// Returns the size of the cache and the hit ratios of the lookups since the last
+    /// [`CacheDB::reset_cache_stats`].
+    ///
+    /// Logs are not part of the cache and are not included.
+    pub fn cache_stats(&self) -> CacheStats {
+        let storage_slots = self
+            .cache
+            .accounts
+            .values()
+            .map(|account| account.storage.len())
+            .sum();
+        CacheStats {
+            accounts: self.cache.accounts.len(),
+            storage_slots,
+            contracts: self.cache.contracts.len(),
+            block_hashes: self.cache.block_hashes.len(),
+            account_bytes: entries_bytes::<Address, DbAccount>(self.cache.accounts.len()),
+            storage_bytes: entries_bytes::<StorageKey, StorageValue>(storage_slots),
+            contract_bytes: entries_bytes::<B256, Bytecode>(self.cache.contracts.len())
+                + self
+                    .cache
+                    .contracts
+                    .values()
+                    .map(Bytecode::len)
+                    .sum::<usize>(),
+            block_hash_bytes: entries_bytes::<U256, B256>(self.cache.block_hashes.len()),
+            ..Default::default()
         }
+        .with_counters(&self.cache_counters)
+    }
+
+    /// Resets the hit counters of [`CacheDB::cache_stats`].
+    pub fn reset_cache_stats(&self) {
+        self.cache_counters.reset();
+    }
+
+    /// Enables caching of the accounts the underlying database reported as not existing.
+    ///
+    /// Absent accounts are then looked up only once, also through [`DatabaseRef`]. Cached
+    /// entries are invalidated on every commit, call [`NegativeCache::invalidate`] if the
+    /// underlying database changes.
+    #[cfg(feature = "std")]
+    pub fn with_negative_cache(mut self) -> Self {
+        self.negative_cache = Some(NegativeCache::new());
+        self
     }
 
     /// Inserts the account's code into the cache.
@@ -142,6 +206,14 @@ impl<ExtDB> CacheDB<ExtDB> {
         }
     }
 
+    /// Invalidates the negative cache, as committed accounts may now exist.
+    fn invalidate_negative_cache(&self) {
+        #[cfg(feature = "std")]
+        if let Some(negative_cache) = &self.negative_cache {
+            negative_cache.invalidate();
+        }
+    }
+
     fn commit_account(&mut self, address: Address, mut account: Account) {
         if !account.is_touched() {
             return;
@@ -188,16 +260,28 @@ impl<ExtDB: DatabaseRef> CacheDB<ExtDB> {
     /// If the account was not found in the cache, it will be loaded from the underlying database.
     pub fn load_account(&mut self, address: Address) -> Result<&mut DbAccount, ExtDB::Error> {
         let db = &self.db;
+        #[cfg(feature = "std")]
+        let absent = self.negative_cache.as_ref();
+        let counter = &self.cache_counters.accounts;
         match self.cache.accounts.entry(address) {
-            Entry::Occupied(entry) => Ok(entry.into_mut()),
-            Entry::Vacant(entry) => Ok(entry.insert(
-                db.basic_ref(address)?
-                    .map(|info| DbAccount {
+            Entry::Occupied(entry) => {
+                counter.hit();
+                Ok(entry.into_mut())
+            }
+            Entry::Vacant(entry) => {
+                counter.miss();
+                #[cfg(feature = "std")]
+                let info = negative_cache::basic_ref(absent, db, address)?;
+                #[cfg(not(feature = "std"))]
+                let info = db.basic_ref(address)?;
+                Ok(entry.insert(
+                    info.map(|info| DbAccount {
                         info,
                         ..Default::default()
                     })
                     .unwrap_or_else(DbAccount::new_not_existing),
-            )),
+                ))
+            }
         }
     }
 
@@ -315,12 +399,14 @@ impl<ExtDB: DatabaseRef> CacheDB<ExtDB> {
 
 impl<ExtDB> DatabaseCommit for CacheDB<ExtDB> {
     fn commit(&mut self, changes: AddressMap<Account>) {
+        self.invalidate_negative_cache();
         for (address, account) in changes {
             self.commit_account(address, account);
         }
     }
 
     fn commit_iter(&mut self, changes: &mut dyn Iterator<Item = (Address, Account)>) {
+        self.invalidate_negative_cache();
         for (address, account) in changes {
             self.commit_account(address, account);
         }
@@ -336,8 +422,12 @@ impl<ExtDB: DatabaseRef> Database for CacheDB<ExtDB> {
 
     fn code_by_hash(&mut self, code_hash: B256) -> Result<Bytecode, Self::Error> {
         match self.cache.contracts.entry(code_hash) {
-            Entry::Occupied(entry) => Ok(entry.get().clone()),
+            Entry::Occupied(entry) => {
+                self.cache_counters.contracts.hit();
+                Ok(entry.get().clone())
+            }
             Entry::Vacant(entry) => {
+                self.cache_counters.contracts.miss();
                 // If you return code bytes when basic fn is called this function is not needed.
                 Ok(entry.insert(self.db.code_by_hash_ref(code_hash)?).clone())
             }
@@ -355,15 +445,21 @@ impl<ExtDB: DatabaseRef> Database for CacheDB<ExtDB> {
         match self.cache.accounts.entry(address) {
             Entry::Occupied(mut acc_entry) => {
                 let acc_entry = acc_entry.get_mut();
+                self.cache_counters.accounts.hit();
                 match acc_entry.storage.entry(index) {
-                    Entry::Occupied(entry) => Ok(*entry.get()),
+                    Entry::Occupied(entry) => {
+                        self.cache_counters.storage.hit();
+                        Ok(*entry.get())
+                    }
                     Entry::Vacant(entry) => {
                         if matches!(
                             acc_entry.account_state,
                             AccountState::StorageCleared | AccountState::NotExisting
                         ) {
+                            self.cache_counters.storage.hit();
                             Ok(StorageValue::ZERO)
                         } else {
+                            self.cache_counters.storage.miss();
                             let slot = self.db.storage_ref(address, index)?;
                             entry.insert(slot);
                             Ok(slot)
@@ -373,13 +469,20 @@ impl<ExtDB: DatabaseRef> Database for CacheDB<ExtDB> {
             }
             Entry::Vacant(acc_entry) => {
                 // Acc needs to be loaded for us to access slots.
+                self.cache_counters.accounts.miss();
+                #[cfg(feature = "std")]
+                let info =
+                    negative_cache::basic_ref(self.negative_cache.as_ref(), &self.db, address)?;
+                #[cfg(not(feature = "std"))]
                 let info = self.db.basic_ref(address)?;
                 let (account, value) = if info.is_some() {
+                    self.cache_counters.storage.miss();
                     let value = self.db.storage_ref(address, index)?;
                     let mut account: DbAccount = info.into();
                     account.storage.insert(index, value);
                     (account, value)
                 } else {
+                    self.cache_counters.storage.hit();
                     (info.into(), StorageValue::ZERO)
                 };
                 acc_entry.insert(account);
@@ -390,8 +493,12 @@ impl<ExtDB: DatabaseRef> Database for CacheDB<ExtDB> {
 
     fn block_hash(&mut self, number: u64) -> Result<B256, Self::Error> {
         match self.cache.block_hashes.entry(U256::from(number)) {
-            Entry::Occupied(entry) => Ok(*entry.get()),
+            Entry::Occupied(entry) => {
+                self.cache_counters.block_hashes.hit();
+                Ok(*entry.get())
+            }
             Entry::Vacant(entry) => {
+                self.cache_counters.block_hashes.miss();
                 let hash = self.db.block_hash_ref(number)?;
                 entry.insert(hash);
                 Ok(hash)
@@ -405,15 +512,33 @@ impl<ExtDB: DatabaseRef> DatabaseRef for CacheDB<ExtDB> {
 
     fn basic_ref(&self, address: Address) -> Result<Option<AccountInfo>, Self::Error> {
         match self.cache.accounts.get(&address) {
-            Some(acc) => Ok(acc.info()),
-            None => self.db.basic_ref(address),
+            Some(acc) => {
+                self.cache_counters.accounts.hit();
+                Ok(acc.info())
+            }
+            #[cfg(feature = "std")]
+            None => {
+                self.cache_counters.accounts.miss();
+                negative_cache::basic_ref(self.negative_cache.as_ref(), &self.db, address)
+            }
+            #[cfg(not(feature = "std"))]
+            None => {
+                self.cache_counters.accounts.miss();
+                self.db.basic_ref(address)
+            }
         }
     }
 
     fn code_by_hash_ref(&self, code_hash: B256) -> Result<Bytecode, Self::Error> {
         match self.cache.contracts.get(&code_hash) {
-            Some(entry) => Ok(entry.clone()),
-            None => self.db.code_by_hash_ref(code_hash),
+            Some(entry) => {
+                self.cache_counters.contracts.hit();
+                Ok(entry.clone())
+            }
+            None => {
+                self.cache_counters.contracts.miss();
+                self.db.code_by_hash_ref(code_hash)
+            }
         }
     }
 
@@ -424,26 +549,49 @@ impl<ExtDB: DatabaseRef> DatabaseRef for CacheDB<ExtDB> {
     ) -> Result<StorageValue, Self::Error> {
         match self.cache.accounts.get(&address) {
             Some(acc_entry) => match acc_entry.storage.get(&index) {
-                Some(entry) => Ok(*entry),
+                Some(entry) => {
+                    self.cache_counters.storage.hit();
+                    Ok(*entry)
+                }
                 None => {
                     if matches!(
                         acc_entry.account_state,
                         AccountState::StorageCleared | AccountState::NotExisting
                     ) {
+                        self.cache_counters.storage.hit();
                         Ok(StorageValue::ZERO)
                     } else {
+                        self.cache_counters.storage.miss();
                         self.db.storage_ref(address, index)
                     }
                 }
             },
-            None => self.db.storage_ref(address, index),
+            #[cfg(feature = "std")]
+            None if self
+                .negative_cache
+                .as_ref()
+                .is_some_and(|negative_cache| negative_cache.contains(&address)) =>
+            {
+                self.cache_counters.storage.hit();
+                Ok(StorageValue::ZERO)
+            }
+            None => {
+                self.cache_counters.storage.miss();
+                self.db.storage_ref(address, index)
+            }
         }
     }
 
     fn block_hash_ref(&self, number: u64) -> Result<B256, Self::Error> {
         match self.cache.block_hashes.get(&U256::from(number)) {
-            Some(entry) => Ok(*entry),
-            None => self.db.block_hash_ref(number),
+            Some(entry) => {
+                self.cache_counters.block_hashes.hit();
+                Ok(*entry)
+            }
+            None => {
+                self.cache_counters.block_hashes.miss();
+                self.db.block_hash_ref(number)
+            }
         }
     }
 }
@@ -756,4 +904,87 @@ mod tests {
             nonce
         );
     }
+
+    #[test]
+    fn test_cache_stats() {
+        let account = Address::with_last_byte(42);
+        let mut db = CacheDB::new(EmptyDB::default());
+        db.insert_account_info(account, AccountInfo::default());
+        db.insert_account_storage(account, StorageKey::from(1), StorageValue::from(2))
+            .unwrap();
+        db.reset_cache_stats();
+
+        db.basic(account).unwrap();
+        db.basic(Address::with_last_byte(1)).unwrap();
+        db.storage(account, StorageKey::from(1)).unwrap();
+
+        let stats = db.cache_stats();
+        assert_eq!(stats.accounts, 2);
+        assert_eq!(stats.storage_slots, 1);
+        assert_eq!(stats.account_hits.hits, 2);
+        assert_eq!(stats.account_hits.misses, 1);
+        assert_eq!(stats.storage_hits.hits, 1);
+        assert!(stats.estimated_bytes() > 0);
+
+        db.reset_cache_stats();
+        assert_eq!(db.cache_stats().hits().lookups(), 0);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_negative_cache() {
+        use core::{
+            convert::Infallible,
+            sync::atomic::{AtomicUsize, Ordering},
+        };
+        use database_interface::DatabaseRef;
+        use primitives::B256;
+        use state::Bytecode;
+
+        /// Database without accounts that counts the account lookups.
+        #[derive(Default)]
+        struct CountingDB {
+            lookups: AtomicUsize,
+        }
+
+        impl DatabaseRef for CountingDB {
+            type Error = Infallible;
+
+            fn basic_ref(&self, _address: Address) -> Result<Option<AccountInfo>, Self::Error> {
+                self.lookups.fetch_add(1, Ordering::Relaxed);
+                Ok(None)
+            }
+
+            fn code_by_hash_ref(&self, _code_hash: B256) -> Result<Bytecode, Self::Error> {
+                Ok(Bytecode::default())
+            }
+
+            fn storage_ref(
+                &self,
+                _address: Address,
+                _index: StorageKey,
+            ) -> Result<StorageValue, Self::Error> {
+                Ok(StorageValue::ZERO)
+            }
+
+            fn block_hash_ref(&self, _number: u64) -> Result<B256, Self::Error> {
+                Ok(B256::ZERO)
+            }
+        }
+
+        let address = Address::with_last_byte(1);
+        let mut db = CacheDB::new(CountingDB::default()).with_negative_cache();
+        assert_eq!(db.basic_ref(address), Ok(None));
+        assert_eq!(db.basic_ref(address), Ok(None));
+        assert_eq!(db.db.lookups.load(Ordering::Relaxed), 1);
+
+        // Commits invalidate the cache.
+        db.commit(HashMap::default());
+        assert_eq!(db.basic_ref(address), Ok(None));
+        assert_eq!(db.db.lookups.load(Ordering::Relaxed), 2);
+
+        // Mutable lookups use the cached entry.
+        assert_eq!(db.basic(address), Ok(None));
+        assert_eq!(db.db.lookups.load(Ordering::Relaxed), 2);
+    }
 }
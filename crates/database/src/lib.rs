@@ -6,6 +6,10 @@ extern crate alloc as std;
 
 #[cfg(feature = "alloydb")]
 mod alloydb;
+#[cfg(feature = "alloydb")]
+mod rate_limiter;
+#[cfg(feature = "alloydb")]
+mod state_provider;
 
 pub use bytecode;
 pub use database_interface;
@@ -14,17 +18,35 @@ pub use state;
 
 pub use database_interface::*;
 
+/// Cache size and hit ratio statistics.
+pub mod cache_stats;
+/// Contract code deduplicated across caches.
+#[cfg(feature = "std")]
+pub mod code_store;
 /// In-memory database implementations.
 pub mod in_memory_db;
+/// Cache of accounts known not to exist.
+#[cfg(feature = "std")]
+pub mod negative_cache;
 /// State management and tracking.
 pub mod states;
 
 #[cfg(feature = "alloydb")]
 pub use alloydb::{AlloyDB, AlloyDBError, BlockId};
+#[cfg(feature = "alloydb")]
+pub use rate_limiter::RateLimiter;
+#[cfg(feature = "alloydb")]
+pub use state_provider::{StateFuture, StateProviderAtBlock};
 
+pub use cache_stats::{CacheCounters, CacheStats, HitCounter, HitRatio};
+#[cfg(feature = "std")]
+pub use code_store::{CodeStore, WithCodeStore};
 pub use in_memory_db::*;
+#[cfg(feature = "std")]
+pub use negative_cache::NegativeCache;
 pub use states::{
-    AccountRevert, AccountStatus, BundleAccount, BundleState, CacheState, DBBox,
-    OriginalValuesKnown, PlainAccount, RevertToSlot, State, StateBuilder, StateDBBox,
-    StorageWithOriginalValues, TransitionAccount, TransitionState,
+    AccountFilter, AccountRevert, AccountStatus, BundleAccount, BundleState, CacheState, DBBox,
+    GenesisAccount, GenesisAlloc, MergePolicy, OnRetry, OnTransitionHook, OriginalValuesKnown,
+    PlainAccount, PlainTransitionState, RetryPolicy, RevertToSlot, State, StateBuilder, StateDBBox,
+    StateWriter, StorageWithOriginalValues, TouchedAccount, TransitionAccount, TransitionState,
 };
@@ -0,0 +1,138 @@
+use core::sync::atomic::{AtomicU64, Ordering};
+use database_interface::DatabaseRef;
+use primitives::{Address, AddressMap};
+use state::AccountInfo;
+use std::sync::{RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+/// Cache of accounts the underlying database of a [`CacheDB`](crate::CacheDB) reported as not
+/// existing.
+///
+/// Lookups through [`DatabaseRef`](database_interface::DatabaseRef) don't store loaded accounts,
+/// so a simulator probing the same absent addresses, e.g. CREATE2 counterfactuals or airdrop
+/// recipients, would ask the backend for each probe.
+///
+/// Entries are recorded with the generation of the cache read before the lookup and are only
+/// valid for that generation. The generation is bumped on every commit to the
+/// [`CacheDB`](crate::CacheDB) and by [`NegativeCache::invalidate`], e.g. after the backend is
+/// moved to another block, so a lookup that raced with the invalidation is not cached.
+#[derive(Debug, Default)]
+pub struct NegativeCache {
+    /// Current generation.
+    generation: AtomicU64,
+    /// Absent accounts and the generation they were recorded in.
+    absent: RwLock<AddressMap<u64>>,
+}
+
+impl Clone for NegativeCache {
+    fn clone(&self) -> Self {
+        Self {
+            generation: AtomicU64::new(self.generation()),
+            absent: RwLock::new(self.read().clone()),
+        }
+    }
+}
+
+impl NegativeCache {
+    /// Creates an empty cache.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the current generation.
+    pub fn generation(&self) -> u64 {
+        self.generation.load(Ordering::Acquire)
+    }
+
+    /// Returns `true` if the account is recorded as absent in the current generation.
+    pub fn contains(&self, address: &Address) -> bool {
+        self.read().get(address) == Some(&self.generation())
+    }
+
+    /// Records the account as absent, as looked up in `generation`.
+    ///
+    /// Nothing is recorded if the cache was invalidated since.
+    pub fn insert(&self, address: Address, generation: u64) {
+        let mut absent = self.write();
+        if generation == self.generation() {
+            absent.insert(address, generation);
+        }
+    }
+
+    /// Returns the number of accounts recorded as absent.
+    pub fn len(&self) -> usize {
+        self.read().len()
+    }
+
+    /// Returns `true` if no account is recorded as absent.
+    pub fn is_empty(&self) -> bool {
+        self.read().is_empty()
+    }
+
+    /// Loads the account from `db`, unless it is recorded as absent, and records it if it does not
+    /// exist.
+    pub fn basic_ref<DB: DatabaseRef>(
+        &self,
+        db: &DB,
+        address: Address,
+    ) -> Result<Option<AccountInfo>, DB::Error> {
+        if self.contains(&address) {
+            return Ok(None);
+        }
+        let generation = self.generation();
+        let info = db.basic_ref(address)?;
+        if info.is_none() {
+            self.insert(address, generation);
+        }
+        Ok(info)
+    }
+
+    /// Bumps the generation and removes all recorded accounts.
+    pub fn invalidate(&self) {
+        let mut absent = self.write();
+        self.generation.fetch_add(1, Ordering::AcqRel);
+        absent.clear();
+    }
+
+    fn read(&self) -> RwLockReadGuard<'_, AddressMap<u64>> {
+        self.absent.read().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn write(&self) -> RwLockWriteGuard<'_, AddressMap<u64>> {
+        self.absent.write().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Loads the account from `db`, through the negative cache if it is enabled.
+#[inline]
+pub(crate) fn basic_ref<DB: DatabaseRef>(
+    negative_cache: Option<&NegativeCache>,
+    db: &DB,
+    address: Address,
+) -> Result<Option<AccountInfo>, DB::Error> {
+    match negative_cache {
+        Some(negative_cache) => negative_cache.basic_ref(db, address),
+        None => db.basic_ref(address),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn invalidation_drops_entries() {
+        let cache = NegativeCache::new();
+        let address = Address::with_last_byte(1);
+
+        let generation = cache.generation();
+        cache.insert(address, generation);
+        assert!(cache.contains(&address));
+
+        cache.invalidate();
+        assert!(!cache.contains(&address));
+
+        // Lookup started before the invalidation is not recorded.
+        cache.insert(address, generation);
+        assert!(cache.is_empty());
+    }
+}
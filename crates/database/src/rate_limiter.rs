@@ -0,0 +1,90 @@
+//! Token bucket rate limiter for RPC requests.
+
+use core::{num::NonZeroU32, time::Duration};
+use std::{sync::Mutex, time::Instant};
+
+/// Token bucket rate limiter of requests sent to an RPC endpoint.
+///
+/// The bucket holds up to `burst` tokens and is refilled with `requests_per_second` tokens per
+/// second, each request takes one token. Requests that find the bucket empty reserve their
+/// token anyway and wait until it is refilled, so waiting requests are served in order.
+///
+/// The limiter is shared with an [`Arc`](std::sync::Arc), all databases and tasks holding the
+/// same limiter share the limit.
+#[derive(Debug)]
+pub struct RateLimiter {
+    /// Tokens added per second.
+    rate: f64,
+    /// Capacity of the bucket.
+    burst: f64,
+    /// Tokens left and the time they were counted at.
+    bucket: Mutex<(f64, Instant)>,
+}
+
+impl RateLimiter {
+    /// Creates a limiter allowing `requests_per_second` requests on average and up to `burst`
+    /// requests at once.
+    ///
+    /// The bucket starts full.
+    pub fn new(requests_per_second: NonZeroU32, burst: NonZeroU32) -> Self {
+        let burst = f64::from(burst.get());
+        Self {
+            rate: f64::from(requests_per_second.get()),
+            burst,
+            bucket: Mutex::new((burst, Instant::now())),
+        }
+    }
+
+    /// Returns the number of requests allowed per second.
+    pub fn requests_per_second(&self) -> f64 {
+        self.rate
+    }
+
+    /// Returns the number of requests allowed at once.
+    pub fn burst(&self) -> f64 {
+        self.burst
+    }
+
+    /// Waits until `requests` requests can be sent.
+    pub async fn acquire(&self, requests: u32) {
+        let wait = self.reserve(requests, Instant::now());
+        if !wait.is_zero() {
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    /// Takes `requests` tokens at `now` and returns the time to wait until they are refilled.
+    fn reserve(&self, requests: u32, now: Instant) -> Duration {
+        let mut bucket = self.bucket.lock().unwrap_or_else(|e| e.into_inner());
+        let (tokens, last) = &mut *bucket;
+        let elapsed = now.saturating_duration_since(*last).as_secs_f64();
+        *tokens = (*tokens + elapsed * self.rate).min(self.burst) - f64::from(requests);
+        *last = now.max(*last);
+        if *tokens >= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(-*tokens / self.rate)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bursts_then_waits() {
+        let limiter = RateLimiter::new(NonZeroU32::new(10).unwrap(), NonZeroU32::new(3).unwrap());
+        let now = Instant::now();
+        assert_eq!(limiter.reserve(3, now), Duration::ZERO);
+
+        // Bucket is empty, each request waits for the previous ones.
+        assert_eq!(limiter.reserve(1, now), Duration::from_millis(100));
+        assert_eq!(limiter.reserve(1, now), Duration::from_millis(200));
+
+        // Tokens are refilled over time, but not above the burst.
+        let later = now + Duration::from_secs(10);
+        assert_eq!(limiter.reserve(3, later), Duration::ZERO);
+        assert_eq!(limiter.reserve(1, later), Duration::from_millis(100));
+    }
+}
@@ -0,0 +1,189 @@
+//! Providers of the historical state of a chain.
+
+use crate::alloydb::{AlloyDB, BlockId};
+use alloy_provider::{Network, Provider};
+use core::{future::Future, pin::Pin};
+use database_interface::{async_db::DatabaseAsyncRef, ErasedError};
+use primitives::{Address, StorageKey, StorageValue, B256};
+use state::{AccountInfo, Bytecode};
+use std::boxed::Box;
+
+/// Future returned by [`StateProviderAtBlock`].
+pub type StateFuture<'a, T> = Pin<Box<dyn Future<Output = Result<T, ErasedError>> + Send + 'a>>;
+
+/// Backend that serves the state of a chain at a block.
+///
+/// [`AlloyDB`] serves the state with the standard `eth_*` RPC methods. Other backends, like
+/// Erigon's API, `debug_dbGet` or local snapshots, implement this trait to serve the same replay
+/// tooling.
+///
+/// The trait is object safe so the backend can be selected at runtime. A boxed provider
+/// implements [`DatabaseAsyncRef`] and is used as a database by wrapping it in
+/// [`WrapDatabaseAsync`](database_interface::WrapDatabaseAsync).
+pub trait StateProviderAtBlock: Send + Sync {
+    /// Returns the block the state is served at.
+    fn block_id(&self) -> BlockId;
+
+    /// Sets the block the state is served at.
+    fn set_block_id(&mut self, block: BlockId);
+
+    /// Gets basic account information at the block.
+    fn basic_at_block(&self, address: Address) -> StateFuture<'_, Option<AccountInfo>>;
+
+    /// Gets account code by its hash.
+    fn code_by_hash_at_block(&self, code_hash: B256) -> StateFuture<'_, Bytecode>;
+
+    /// Gets storage value of address at index at the block.
+    fn storage_at_block(
+        &self,
+        address: Address,
+        index: StorageKey,
+    ) -> StateFuture<'_, StorageValue>;
+
+    /// Gets block hash by block number.
+    fn block_hash_at_block(&self, number: u64) -> StateFuture<'_, B256>;
+}
+
+impl<N: Network, P: Provider<N>> StateProviderAtBlock for AlloyDB<N, P> {
+    fn block_id(&self) -> BlockId {
+        self.block_number()
+    }
+
+    fn set_block_id(&mut self, block: BlockId) {
+        self.set_block_number(block);
+    }
+
+    fn basic_at_block(&self, address: Address) -> StateFuture<'_, Option<AccountInfo>> {
+        Box::pin(async move {
+            self.basic_async_ref(address)
+                .await
+                .map_err(ErasedError::new)
+        })
+    }
+
+    fn code_by_hash_at_block(&self, code_hash: B256) -> StateFuture<'_, Bytecode> {
+        Box::pin(async move {
+            self.code_by_hash_async_ref(code_hash)
+                .await
+                .map_err(ErasedError::new)
+        })
+    }
+
+    fn storage_at_block(
+        &self,
+        address: Address,
+        index: StorageKey,
+    ) -> StateFuture<'_, StorageValue> {
+        Box::pin(async move {
+            self.storage_async_ref(address, index)
+                .await
+                .map_err(ErasedError::new)
+        })
+    }
+
+    fn block_hash_at_block(&self, number: u64) -> StateFuture<'_, B256> {
+        Box::pin(async move {
+            self.block_hash_async_ref(number)
+                .await
+                .map_err(ErasedError::new)
+        })
+    }
+}
+
+impl DatabaseAsyncRef for Box<dyn StateProviderAtBlock + '_> {
+    type Error = ErasedError;
+
+    fn basic_async_ref(
+        &self,
+        address: Address,
+    ) -> impl Future<Output = Result<Option<AccountInfo>, Self::Error>> + Send {
+        self.basic_at_block(address)
+    }
+
+    fn code_by_hash_async_ref(
+        &self,
+        code_hash: B256,
+    ) -> impl Future<Output = Result<Bytecode, Self::Error>> + Send {
+        self.code_by_hash_at_block(code_hash)
+    }
+
+    fn storage_async_ref(
+        &self,
+        address: Address,
+        index: StorageKey,
+    ) -> impl Future<Output = Result<StorageValue, Self::Error>> + Send {
+        self.storage_at_block(address, index)
+    }
+
+    fn block_hash_async_ref(
+        &self,
+        number: u64,
+    ) -> impl Future<Output = Result<B256, Self::Error>> + Send {
+        self.block_hash_at_block(number)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use database_interface::{DatabaseRef, WrapDatabaseAsync};
+    use primitives::{AddressMap, U256};
+
+    /// Serves accounts from a snapshot taken at a block.
+    struct Snapshot {
+        block: BlockId,
+        accounts: AddressMap<AccountInfo>,
+    }
+
+    impl StateProviderAtBlock for Snapshot {
+        fn block_id(&self) -> BlockId {
+            self.block
+        }
+
+        fn set_block_id(&mut self, block: BlockId) {
+            self.block = block;
+        }
+
+        fn basic_at_block(&self, address: Address) -> StateFuture<'_, Option<AccountInfo>> {
+            Box::pin(async move { Ok(self.accounts.get(&address).cloned()) })
+        }
+
+        fn code_by_hash_at_block(&self, _code_hash: B256) -> StateFuture<'_, Bytecode> {
+            Box::pin(async { Ok(Bytecode::default()) })
+        }
+
+        fn storage_at_block(
+            &self,
+            _address: Address,
+            _index: StorageKey,
+        ) -> StateFuture<'_, StorageValue> {
+            Box::pin(async { Ok(StorageValue::ZERO) })
+        }
+
+        fn block_hash_at_block(&self, _number: u64) -> StateFuture<'_, B256> {
+            Box::pin(async { Ok(B256::ZERO) })
+        }
+    }
+
+    #[test]
+    fn boxed_provider_is_a_database() {
+        let address = Address::with_last_byte(1);
+        let mut accounts = AddressMap::default();
+        accounts.insert(address, AccountInfo::from_balance(U256::from(7)));
+        let provider: Box<dyn StateProviderAtBlock> = Box::new(Snapshot {
+            block: BlockId::number(10),
+            accounts,
+        });
+
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap();
+        let mut db = WrapDatabaseAsync::with_runtime(provider, runtime);
+        let account = db.basic_ref(address).unwrap().unwrap();
+        assert_eq!(account.balance, U256::from(7));
+        assert!(db.basic_ref(Address::ZERO).unwrap().is_none());
+
+        db.inner_mut().set_block_id(BlockId::number(11));
+        assert_eq!(db.inner().block_id(), BlockId::number(11));
+    }
+}
@@ -1,5 +1,7 @@
 //! State management and tracking for the EVM.
 
+/// Filter of existing accounts.
+pub mod account_filter;
 /// Account status tracking.
 pub mod account_status;
 /// Block hash cache.
@@ -14,29 +16,45 @@ pub mod cache;
 pub mod cache_account;
 /// State changeset tracking.
 pub mod changes;
+/// Genesis-style account allocation.
+pub mod genesis_alloc;
 /// Plain account representation.
 pub mod plain_account;
+/// Transition state without original values.
+pub mod plain_transition_state;
+/// Retry policy for transient database errors.
+pub mod retry;
 /// State revert tracking.
 pub mod reverts;
 /// Main state implementation.
 pub mod state;
 /// State builder utilities.
 pub mod state_builder;
+/// Write-back of bundle state to storage backends.
+pub mod state_writer;
 /// Transition account representation.
 pub mod transition_account;
+/// Hook for the accounts touched by transitions.
+pub mod transition_hook;
 /// Transition state management.
 pub mod transition_state;
 
+pub use account_filter::AccountFilter;
 /// Account status for Block and Bundle states.
 pub use account_status::AccountStatus;
 pub use bundle_account::BundleAccount;
-pub use bundle_state::{BundleBuilder, BundleState, OriginalValuesKnown};
+pub use bundle_state::{BundleBuilder, BundleState, MergePolicy, OriginalValuesKnown};
 pub use cache::CacheState;
 pub use cache_account::CacheAccount;
 pub use changes::{PlainStateReverts, PlainStorageChangeset, PlainStorageRevert, StateChangeset};
+pub use genesis_alloc::{GenesisAccount, GenesisAlloc};
 pub use plain_account::{PlainAccount, StorageSlot, StorageWithOriginalValues};
+pub use plain_transition_state::{PlainTransitionAccount, PlainTransitionState};
+pub use retry::{OnRetry, RetryPolicy};
 pub use reverts::{AccountRevert, RevertToSlot};
 pub use state::{DBBox, State, StateDBBox};
 pub use state_builder::StateBuilder;
+pub use state_writer::StateWriter;
 pub use transition_account::TransitionAccount;
+pub use transition_hook::{OnTransitionHook, TouchedAccount};
 pub use transition_state::TransitionState;
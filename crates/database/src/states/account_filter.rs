@@ -0,0 +1,128 @@
+use primitives::Address;
+use std::{vec, vec::Vec};
+
+/// Bloom filter of the addresses of existing accounts.
+///
+/// Built from a snapshot of the state, it lets [`State`](super::State) answer most lookups of
+/// accounts that don't exist without asking the database, see
+/// [`StateBuilder::with_account_filter`](super::StateBuilder::with_account_filter).
+///
+/// The filter has no false negatives: an account added to it is always reported as possibly
+/// existing. Accounts that were not added are reported as possibly existing with a small
+/// probability, which depends on the number of bits per account, about 1% for 10 bits.
+///
+/// Accounts created after the snapshot don't need to be added, as they are in the cache of the
+/// state once created.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AccountFilter {
+    /// Bits of the filter.
+    bits: Vec<u64>,
+    /// Number of bit positions set for each address.
+    hashes: u32,
+}
+
+impl AccountFilter {
+    /// Creates an empty filter sized for `accounts` accounts with `bits_per_account` bits each.
+    pub fn new(accounts: usize, bits_per_account: usize) -> Self {
+        let num_bits = accounts.max(1) * bits_per_account.max(1);
+        // Optimal number of hashes is `bits_per_account * ln(2)`.
+        let hashes = (bits_per_account * 693 / 1000).clamp(1, 16) as u32;
+        Self {
+            bits: vec![0; num_bits.div_ceil(64)],
+            hashes,
+        }
+    }
+
+    /// Creates a filter of the addresses with `bits_per_account` bits for each of them.
+    pub fn from_addresses<'a>(
+        addresses: impl ExactSizeIterator<Item = &'a Address>,
+        bits_per_account: usize,
+    ) -> Self {
+        let mut filter = Self::new(addresses.len(), bits_per_account);
+        for address in addresses {
+            filter.insert(address);
+        }
+        filter
+    }
+
+    /// Adds the address to the filter.
+    pub fn insert(&mut self, address: &Address) {
+        for bit in self.bit_positions(address) {
+            self.bits[bit / 64] |= 1 << (bit % 64);
+        }
+    }
+
+    /// Returns `false` if the account certainly does not exist, `true` if it may exist.
+    pub fn may_contain(&self, address: &Address) -> bool {
+        self.bit_positions(address)
+            .all(|bit| self.bits[bit / 64] & (1 << (bit % 64)) != 0)
+    }
+
+    /// Returns the size of the filter in bytes.
+    pub const fn size_in_bytes(&self) -> usize {
+        self.bits.len() * 8
+    }
+
+    /// Returns the bit positions of the address.
+    ///
+    /// The positions are derived with double hashing from two hashes that mix all 20 bytes of the
+    /// address, as addresses are not uniformly distributed, e.g. precompiles and system contracts
+    /// only differ in the last bytes.
+    fn bit_positions(&self, address: &Address) -> impl Iterator<Item = usize> {
+        let bytes = address.as_slice();
+        let word0 = u64::from_le_bytes(bytes[..8].try_into().unwrap());
+        let word1 = u64::from_le_bytes(bytes[8..16].try_into().unwrap());
+        let word2 = u64::from(u32::from_le_bytes(bytes[16..].try_into().unwrap()));
+        let h1 = mix(word0 ^ mix(word2));
+        let h2 = mix(word1 ^ mix(word2 ^ h1)) | 1;
+        let num_bits = self.bits.len() as u64 * 64;
+        (0..u64::from(self.hashes))
+            .map(move |i| (h1.wrapping_add(i.wrapping_mul(h2)) % num_bits) as usize)
+    }
+}
+
+/// Finalizer of `splitmix64`, spreads every input bit over the whole word.
+const fn mix(mut x: u64) -> u64 {
+    x = (x ^ (x >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94d049bb133111eb);
+    x ^ (x >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use primitives::keccak256;
+
+    fn address(i: u32) -> Address {
+        Address::from_word(keccak256(i.to_le_bytes()))
+    }
+
+    #[test]
+    fn no_false_negatives() {
+        let addresses: Vec<_> = (0..1_000).map(address).collect();
+        let filter = AccountFilter::from_addresses(addresses.iter(), 10);
+        assert!(addresses.iter().all(|address| filter.may_contain(address)));
+
+        // About 1% false positives are expected.
+        let false_positives = (1_000..11_000)
+            .filter(|i| filter.may_contain(&address(*i)))
+            .count();
+        assert!(false_positives < 300, "{false_positives}");
+    }
+
+    #[test]
+    fn low_addresses() {
+        let precompiles: Vec<_> = (1..=10).map(Address::with_last_byte).collect();
+        let filter = AccountFilter::from_addresses(precompiles.iter(), 10);
+        assert!(precompiles
+            .iter()
+            .all(|address| filter.may_contain(address)));
+
+        // Addresses that only differ in the last bytes must not share their bit positions.
+        let false_positives = (11..=1_010u16)
+            .filter(|i| filter.may_contain(&Address::left_padding_from(&i.to_be_bytes())))
+            .count();
+        assert!(false_positives < 50, "{false_positives}");
+    }
+}
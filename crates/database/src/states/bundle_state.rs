@@ -392,6 +392,17 @@ impl BundleRetention {
     }
 }
 
+/// Policy for [`BundleState::merge`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MergePolicy {
+    /// Other bundle is built on top of this one, same as [`BundleState::extend`].
+    Sequential,
+    /// Values of the other bundle win for accounts and slots present in both bundles.
+    Theirs,
+    /// Values of this bundle win for accounts and slots present in both bundles.
+    Ours,
+}
+
 /// Bundle state contain only values that got changed
 ///
 /// For every account it contains both original and present state.
@@ -773,6 +784,85 @@ impl BundleState {
         self.reverts.extend(other.reverts);
     }
 
+    /// Merges the other bundle into this one.
+    ///
+    /// With [`MergePolicy::Sequential`] the other bundle is assumed to be built on top of
+    /// this one and [`BundleState::extend`] is used.
+    ///
+    /// Otherwise the bundles are assumed to be independent, e.g. outcomes of chunks of a block
+    /// range executed in parallel. Accounts and slots found in only one bundle are taken as is,
+    /// conflicts are resolved by the policy and original values of this bundle are kept. An
+    /// account destroyed in either bundle is taken as a whole from the winning bundle, as its
+    /// storage can't be combined slot by slot with storage that still builds on the pre-state.
+    ///
+    /// Reverts of independent bundles each revert to the state their bundle was built on, so
+    /// neither applies to the merged state and all reverts are dropped. Take them with
+    /// [`BundleState::take_all_reverts`] before merging if they are needed.
+    pub fn merge(&mut self, other: Self, policy: MergePolicy) {
+        let theirs = match policy {
+            MergePolicy::Sequential => return self.extend(other),
+            MergePolicy::Theirs => true,
+            MergePolicy::Ours => false,
+        };
+
+        self.state.reserve(other.state.len());
+        for (address, other_account) in other.state {
+            match self.state.entry(address) {
+                Entry::Occupied(mut entry) => {
+                    let this = entry.get_mut();
+                    self.state_size -= this.size_hint();
+
+                    if this.was_destroyed() || other_account.was_destroyed() {
+                        if theirs {
+                            let original_info = this.original_info.take();
+                            let storage = mem::replace(this, other_account).storage;
+                            this.original_info = original_info;
+                            for (key, slot) in this.storage.iter_mut() {
+                                if let Some(original) = storage.get(key) {
+                                    slot.previous_or_original_value =
+                                        original.previous_or_original_value;
+                                }
+                            }
+                        }
+                    } else {
+                        if theirs {
+                            this.info = other_account.info;
+                            this.status = other_account.status;
+                        }
+                        this.storage.reserve(other_account.storage.len());
+                        for (key, storage_slot) in other_account.storage {
+                            match this.storage.entry(key) {
+                                Entry::Occupied(mut slot) => {
+                                    if theirs {
+                                        slot.get_mut().present_value = storage_slot.present_value;
+                                    }
+                                }
+                                Entry::Vacant(slot) => {
+                                    slot.insert(storage_slot);
+                                }
+                            }
+                        }
+                    }
+
+                    self.state_size += this.size_hint();
+                }
+                Entry::Vacant(entry) => {
+                    self.state_size += other_account.size_hint();
+                    entry.insert(other_account);
+                }
+            }
+        }
+
+        if theirs {
+            self.contracts.extend(other.contracts);
+        } else {
+            for (hash, bytecode) in other.contracts {
+                self.contracts.entry(hash).or_insert(bytecode);
+            }
+        }
+        self.take_all_reverts();
+    }
+
     /// Takes first N raw reverts from the [BundleState].
     pub fn take_n_reverts(&mut self, reverts_to_take: usize) -> Reverts {
         // Split is done as [0, num) and [num, len].
@@ -835,6 +925,41 @@ impl BundleState {
         false
     }
 
+    /// Reverts the state changes of the latest transition and returns the discarded changes.
+    ///
+    /// The changeset contains the accounts and storage slots reverted by the transition with
+    /// the values they had after it. Contracts are kept in the bundle and not returned.
+    ///
+    /// Returns `None` if there are no reverts.
+    pub fn unwind_latest(&mut self) -> Option<StateChangeset> {
+        let reverts = self.reverts.last()?;
+        let mut changes = StateChangeset::default();
+        for (address, revert) in reverts {
+            let account = self.state.get(address);
+            if !matches!(revert.account, AccountInfoRevert::DoNothing) {
+                let info = account.and_then(|account| account.info.clone());
+                changes.accounts.push((*address, info));
+            }
+            if revert.wipe_storage || !revert.storage.is_empty() {
+                let storage = revert
+                    .storage
+                    .keys()
+                    .map(|key| {
+                        let value = account.and_then(|account| account.storage_slot(*key));
+                        (*key, value.unwrap_or_default())
+                    })
+                    .collect();
+                changes.storage.push(PlainStorageChangeset {
+                    address: *address,
+                    wipe_storage: revert.wipe_storage,
+                    storage,
+                });
+            }
+        }
+        self.revert_latest();
+        Some(changes)
+    }
+
     /// Reverts the state changes by N transitions back.
     ///
     /// See also [Self::revert_latest]
@@ -1229,6 +1354,115 @@ mod tests {
         assert_eq!(taken_reverts, bundle2.reverts);
     }
 
+    #[test]
+    fn merge_with_policy() {
+        let address1 = account1();
+        let address2 = account2();
+        let slot = StorageKey::from(1);
+        let info = |nonce| AccountInfo {
+            nonce,
+            ..Default::default()
+        };
+
+        let ours = BundleState::builder(1..=1)
+            .state_present_account_info(address1, info(1))
+            .state_storage(
+                address1,
+                HashMap::from_iter([(slot, (StorageValue::ZERO, StorageValue::from(1)))]),
+            )
+            .build();
+        let theirs = BundleState::builder(2..=2)
+            .state_present_account_info(address1, info(2))
+            .state_present_account_info(address2, info(3))
+            .state_storage(
+                address1,
+                HashMap::from_iter([
+                    (slot, (StorageValue::from(7), StorageValue::from(2))),
+                    (
+                        StorageKey::from(2),
+                        (StorageValue::ZERO, StorageValue::from(3)),
+                    ),
+                ]),
+            )
+            .build();
+
+        let mut test = ours.clone();
+        test.merge(theirs.clone(), MergePolicy::Theirs);
+        let account = test.account(&address1).unwrap();
+        assert_eq!(account.info, Some(info(2)));
+        let merged = account.storage.get(&slot).unwrap();
+        // Original value of this bundle is kept.
+        assert_eq!(merged.previous_or_original_value, StorageValue::ZERO);
+        assert_eq!(merged.present_value, StorageValue::from(2));
+        assert_eq!(account.storage.len(), 2);
+        assert_eq!(test.account(&address2).unwrap().info, Some(info(3)));
+        // Reverts of independent bundles are dropped.
+        assert!(test.reverts.is_empty());
+        assert_eq!(test.size_hint(), 4);
+
+        let mut test = ours;
+        test.merge(theirs, MergePolicy::Ours);
+        let account = test.account(&address1).unwrap();
+        assert_eq!(account.info, Some(info(1)));
+        assert_eq!(
+            account.storage.get(&slot).unwrap().present_value,
+            StorageValue::from(1)
+        );
+        assert_eq!(account.storage.len(), 2);
+        assert_eq!(test.account(&address2).unwrap().info, Some(info(3)));
+    }
+
+    #[test]
+    fn merge_destroyed_account() {
+        let address = account1();
+        let info = |nonce| AccountInfo {
+            nonce,
+            ..Default::default()
+        };
+        let bundle = |block, nonce, slot: u64, value: u64, status| {
+            let mut bundle = BundleState::builder(block..=block)
+                .state_present_account_info(address, info(nonce))
+                .state_storage(
+                    address,
+                    HashMap::from_iter([(
+                        StorageKey::from(slot),
+                        (StorageValue::from(5), StorageValue::from(value)),
+                    )]),
+                )
+                .build();
+            bundle.state.get_mut(&address).unwrap().status = status;
+            bundle
+        };
+        let destroyed = bundle(1, 1, 1, 1, AccountStatus::DestroyedChanged);
+        let changed = bundle(2, 2, 2, 2, AccountStatus::Changed);
+
+        // Destroyed account of this bundle wins as a whole, the slots of the other bundle
+        // are not written into the wiped storage.
+        let mut test = destroyed.clone();
+        test.merge(changed.clone(), MergePolicy::Ours);
+        assert_eq!(test.account(&address), destroyed.account(&address));
+
+        // Account of the other bundle replaces the destroyed one as a whole.
+        let mut test = destroyed.clone();
+        test.merge(changed.clone(), MergePolicy::Theirs);
+        assert_eq!(test.account(&address), changed.account(&address));
+        assert_eq!(test.size_hint(), 2);
+
+        // Same when the other bundle destroyed the account.
+        let mut test = changed.clone();
+        test.merge(destroyed.clone(), MergePolicy::Ours);
+        assert_eq!(test.account(&address), changed.account(&address));
+
+        let mut test = changed;
+        test.merge(destroyed.clone(), MergePolicy::Theirs);
+        let account = test.account(&address).unwrap();
+        assert_eq!(account.status, AccountStatus::DestroyedChanged);
+        assert_eq!(
+            account.storage,
+            destroyed.account(&address).unwrap().storage
+        );
+    }
+
     #[test]
     fn prepend_state() {
         let address1 = account1();
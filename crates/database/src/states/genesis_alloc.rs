@@ -0,0 +1,352 @@
+use super::{plain_account::PlainStorage, BundleState, CacheState};
+use bytecode::Bytecode;
+use primitives::{Address, Bytes, StorageKey, StorageValue, KECCAK_EMPTY, U256};
+use state::AccountInfo;
+use std::collections::BTreeMap;
+
+/// Genesis-style allocation of accounts, as found in the `alloc` field of a genesis file.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
+pub struct GenesisAlloc {
+    /// Allocated accounts.
+    pub accounts: BTreeMap<Address, GenesisAccount>,
+}
+
+/// Account of the [`GenesisAlloc`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GenesisAccount {
+    /// Account balance.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub balance: U256,
+    /// Account nonce, given as a number or a hex/decimal string.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, deserialize_with = "serde_impl::deserialize_nonce")
+    )]
+    pub nonce: u64,
+    /// Account code.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub code: Option<Bytes>,
+    /// Account storage.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "BTreeMap::is_empty")
+    )]
+    pub storage: BTreeMap<StorageKey, StorageValue>,
+}
+
+impl From<BTreeMap<Address, GenesisAccount>> for GenesisAlloc {
+    fn from(accounts: BTreeMap<Address, GenesisAccount>) -> Self {
+        Self { accounts }
+    }
+}
+
+impl FromIterator<(Address, GenesisAccount)> for GenesisAlloc {
+    fn from_iter<T: IntoIterator<Item = (Address, GenesisAccount)>>(iter: T) -> Self {
+        Self {
+            accounts: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl GenesisAlloc {
+    /// Inserts the accounts into the cache as already loaded accounts.
+    ///
+    /// Accounts that are already in the cache are overwritten.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the code of an account starts with `0xEF01` but is not a valid
+    /// EIP-7702 delegation.
+    pub fn insert_into(&self, cache: &mut CacheState) {
+        for (address, account) in &self.accounts {
+            let mut info = AccountInfo {
+                balance: account.balance,
+                nonce: account.nonce,
+                code_hash: KECCAK_EMPTY,
+                ..Default::default()
+            };
+            if let Some(code) = account.code.as_ref().filter(|code| !code.is_empty()) {
+                let bytecode = Bytecode::new_raw(code.clone());
+                info.code_hash = bytecode.hash_slow();
+                cache.contracts.insert(info.code_hash, bytecode.clone());
+                info.code = Some(bytecode);
+            }
+            let storage: PlainStorage = account.storage.iter().map(|(k, v)| (*k, *v)).collect();
+            cache.insert_account_with_storage(*address, info, storage);
+        }
+    }
+
+    /// Returns the alloc of the post-state of the bundle applied on top of this alloc.
+    ///
+    /// This alloc is the pre-state of the bundle, e.g. the alloc the state was built with. The
+    /// result can be used as the genesis of a new chain that starts with the outcome of the
+    /// transactions of the bundle, see [`GenesisAlloc::to_json`].
+    pub fn with_bundle(mut self, bundle: &BundleState) -> Self {
+        self.apply_bundle(bundle);
+        self
+    }
+
+    /// Applies the post-state of the bundle to the alloc, see [`GenesisAlloc::with_bundle`].
+    ///
+    /// Destroyed accounts are removed, storage slots set to zero are removed.
+    pub fn apply_bundle(&mut self, bundle: &BundleState) {
+        for (address, bundle_account) in &bundle.state {
+            let Some(info) = &bundle_account.info else {
+                self.accounts.remove(address);
+                continue;
+            };
+            let account = self.accounts.entry(*address).or_default();
+            account.balance = info.balance;
+            account.nonce = info.nonce;
+            account.code = info
+                .code
+                .as_ref()
+                .or_else(|| bundle.contracts.get(&info.code_hash))
+                .map(|code| code.original_bytes())
+                .or_else(|| {
+                    account
+                        .code
+                        .take()
+                        .filter(|_| info.code_hash != KECCAK_EMPTY)
+                })
+                .filter(|code| !code.is_empty());
+            // Storage of the pre-state is not valid anymore.
+            if bundle_account.status.is_storage_known() {
+                account.storage.clear();
+            }
+            for (key, slot) in &bundle_account.storage {
+                if slot.present_value.is_zero() {
+                    account.storage.remove(key);
+                } else {
+                    account.storage.insert(*key, slot.present_value);
+                }
+            }
+        }
+    }
+
+    /// Serializes the alloc to JSON, in the format of the `alloc` field of a geth genesis file.
+    #[cfg(feature = "genesis")]
+    pub fn to_json(&self) -> Result<std::string::String, serde_json::Error> {
+        serde_json::to_string_pretty(self)
+    }
+
+    /// Parses the alloc from JSON.
+    ///
+    /// Both a bare alloc and a full genesis file with an `alloc` field are accepted.
+    #[cfg(feature = "genesis")]
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str::<serde_json::Value>(json)?.try_into()
+    }
+
+    /// Reads the alloc from a JSON file, see [`GenesisAlloc::from_json`].
+    #[cfg(feature = "genesis")]
+    pub fn from_file(path: impl AsRef<std::path::Path>) -> std::io::Result<Self> {
+        let json = std::fs::read_to_string(path)?;
+        Self::from_json(&json).map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+    }
+}
+
+#[cfg(feature = "genesis")]
+impl TryFrom<serde_json::Value> for GenesisAlloc {
+    type Error = serde_json::Error;
+
+    fn try_from(mut value: serde_json::Value) -> Result<Self, Self::Error> {
+        if let Some(alloc) = value.get_mut("alloc") {
+            value = alloc.take();
+        }
+        serde_json::from_value(value)
+    }
+}
+
+#[cfg(feature = "serde")]
+mod serde_impl {
+    use serde::{de, Deserialize, Deserializer};
+    use std::string::String;
+
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Nonce {
+        Number(u64),
+        String(String),
+    }
+
+    pub(super) fn deserialize_nonce<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<u64, D::Error> {
+        match Nonce::deserialize(deserializer)? {
+            Nonce::Number(nonce) => Ok(nonce),
+            Nonce::String(nonce) => match nonce.strip_prefix("0x") {
+                Some(hex) => u64::from_str_radix(hex, 16),
+                None => nonce.parse(),
+            }
+            .map_err(de::Error::custom),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{states::bundle_state::BundleRetention, StateBuilder};
+    use database_interface::{Database, DatabaseCommit};
+    use state::{Account, EvmStorageSlot, TransactionId};
+    use std::vec::Vec;
+
+    #[test]
+    fn state_with_alloc() {
+        let address = Address::with_last_byte(1);
+        let code = Bytes::from_static(&[0x60, 0x01, 0x00]);
+        let alloc = GenesisAlloc::from_iter([(
+            address,
+            GenesisAccount {
+                balance: U256::from(10),
+                nonce: 2,
+                code: Some(code.clone()),
+                storage: BTreeMap::from([(U256::from(1), U256::from(3))]),
+            },
+        )]);
+
+        let mut state = StateBuilder::new().with_alloc(alloc).build();
+        let info = state.basic(address).unwrap().unwrap();
+        assert_eq!(info.balance, U256::from(10));
+        assert_eq!(info.nonce, 2);
+        assert_eq!(
+            state.code_by_hash(info.code_hash).unwrap().original_bytes(),
+            code
+        );
+        assert_eq!(
+            state.storage(address, U256::from(1)).unwrap(),
+            U256::from(3)
+        );
+        assert!(state.basic(Address::with_last_byte(2)).unwrap().is_none());
+    }
+
+    #[test]
+    fn alloc_with_bundle() {
+        let (first, second, third) = (
+            Address::with_last_byte(1),
+            Address::with_last_byte(2),
+            Address::with_last_byte(3),
+        );
+        let code = Bytes::from_static(&[0x60, 0x01, 0x00]);
+        let alloc = GenesisAlloc::from_iter([
+            (
+                first,
+                GenesisAccount {
+                    balance: U256::from(10),
+                    storage: BTreeMap::from([
+                        (U256::from(1), U256::from(1)),
+                        (U256::from(2), U256::from(2)),
+                    ]),
+                    ..Default::default()
+                },
+            ),
+            (
+                second,
+                GenesisAccount {
+                    balance: U256::from(20),
+                    ..Default::default()
+                },
+            ),
+        ]);
+
+        let mut state = StateBuilder::new()
+            .with_alloc(alloc.clone())
+            .with_bundle_update()
+            .build();
+        let bytecode = Bytecode::new_raw(code.clone());
+        let mut deployed = Account::from(AccountInfo {
+            nonce: 1,
+            code_hash: bytecode.hash_slow(),
+            code: Some(bytecode),
+            ..Default::default()
+        })
+        .with_storage(
+            [(
+                U256::from(1),
+                EvmStorageSlot::new_changed(U256::ZERO, U256::from(5), TransactionId::ZERO),
+            )]
+            .into_iter(),
+        );
+        deployed.mark_created();
+        deployed.mark_touch();
+        let mut changed = Account::from(state.basic(first).unwrap().unwrap()).with_storage(
+            [(
+                U256::from(1),
+                EvmStorageSlot::new_changed(U256::from(1), U256::ZERO, TransactionId::ZERO),
+            )]
+            .into_iter(),
+        );
+        changed.info.balance = U256::from(9);
+        changed.mark_touch();
+        let mut destroyed = Account::from(state.basic(second).unwrap().unwrap());
+        destroyed.mark_selfdestruct();
+        destroyed.mark_touch();
+        state.commit_iter(
+            &mut [(first, changed), (second, destroyed), (third, deployed)].into_iter(),
+        );
+        state.merge_transitions(BundleRetention::PlainState);
+
+        let alloc = alloc.with_bundle(&state.take_bundle());
+        assert_eq!(
+            alloc.accounts.keys().copied().collect::<Vec<_>>(),
+            [first, third]
+        );
+        assert_eq!(
+            alloc.accounts[&first],
+            GenesisAccount {
+                balance: U256::from(9),
+                storage: BTreeMap::from([(U256::from(2), U256::from(2))]),
+                ..Default::default()
+            }
+        );
+        assert_eq!(
+            alloc.accounts[&third],
+            GenesisAccount {
+                nonce: 1,
+                code: Some(code),
+                storage: BTreeMap::from([(U256::from(1), U256::from(5))]),
+                ..Default::default()
+            }
+        );
+    }
+
+    #[cfg(feature = "genesis")]
+    #[test]
+    fn alloc_from_json() {
+        let json = r#"{
+            "config": {},
+            "alloc": {
+                "0x0000000000000000000000000000000000000001": {
+                    "balance": "0x10",
+                    "nonce": "0x2",
+                    "storage": {
+                        "0x0000000000000000000000000000000000000000000000000000000000000001": "0x03"
+                    }
+                },
+                "0x0000000000000000000000000000000000000002": {
+                    "balance": "0x0",
+                    "nonce": 7,
+                    "code": "0x6001"
+                }
+            }
+        }"#;
+        let alloc = GenesisAlloc::from_json(json).unwrap();
+        assert_eq!(alloc.accounts.len(), 2);
+
+        let first = &alloc.accounts[&Address::with_last_byte(1)];
+        assert_eq!(first.balance, U256::from(16));
+        assert_eq!(first.nonce, 2);
+        assert_eq!(first.storage[&U256::from(1)], U256::from(3));
+
+        let second = &alloc.accounts[&Address::with_last_byte(2)];
+        assert_eq!(second.nonce, 7);
+        assert_eq!(second.code, Some(Bytes::from_static(&[0x60, 0x01])));
+    }
+}
@@ -0,0 +1,120 @@
+use std::borrow::Cow;
+
+use super::{
+    plain_account::PlainStorage, AccountStatus, PlainStorageChangeset, StateChangeset,
+    TransitionAccount,
+};
+use bytecode::Bytecode;
+use primitives::{Address, AddressMap, B256Map, KECCAK_EMPTY};
+use state::{AccountInfo, EvmStorage};
+use std::vec::Vec;
+
+/// Present state of an account changed by transitions, without original values.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PlainTransitionAccount {
+    /// Present account info without code, `None` if the account was destroyed.
+    pub info: Option<AccountInfo>,
+    /// Present values of the changed storage slots.
+    pub storage: PlainStorage,
+    /// If storage was wiped by a selfdestruct.
+    pub wipe_storage: bool,
+}
+
+/// Present state of the accounts changed by transitions.
+///
+/// Counterpart of [`TransitionState`](super::TransitionState) for the cases where only the end
+/// state matters. Original values are not kept, so neither reverts nor a
+/// [`BundleState`](super::BundleState) can be built from it, and every slot takes half the
+/// memory.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PlainTransitionState {
+    /// Changed accounts.
+    pub accounts: AddressMap<PlainTransitionAccount>,
+    /// Newly created contracts.
+    pub contracts: B256Map<Bytecode>,
+}
+
+impl PlainTransitionState {
+    /// Take the contents of this [`PlainTransitionState`] and replace it with an empty one.
+    pub fn take(&mut self) -> PlainTransitionState {
+        core::mem::take(self)
+    }
+
+    /// Returns `true` if no account was changed.
+    pub fn is_empty(&self) -> bool {
+        self.accounts.is_empty() && self.contracts.is_empty()
+    }
+
+    /// Add transitions to the plain transition state.
+    pub fn add_transitions<'a>(
+        &mut self,
+        transitions: impl IntoIterator<Item = (Address, TransitionAccount<Option<Cow<'a, EvmStorage>>>)>,
+    ) {
+        for (address, transition) in transitions {
+            self.add_transition(address, transition);
+        }
+    }
+
+    /// Add one transition to the plain transition state.
+    ///
+    /// Only the present account info and the present values of the changed slots are kept.
+    pub fn add_transition(
+        &mut self,
+        address: Address,
+        transition: TransitionAccount<Option<Cow<'_, EvmStorage>>>,
+    ) {
+        let account = self.accounts.entry(address).or_default();
+
+        if let Some(info) = &transition.info {
+            let previous_code_hash = transition.previous_info.as_ref().map(|i| i.code_hash);
+            if previous_code_hash != Some(info.code_hash) && info.code_hash != KECCAK_EMPTY {
+                if let Some(code) = &info.code {
+                    self.contracts.insert(info.code_hash, code.clone());
+                }
+            }
+        }
+
+        // Selfdestruct drops the storage changed so far.
+        if matches!(
+            transition.status,
+            AccountStatus::Destroyed | AccountStatus::DestroyedAgain
+        ) {
+            account.storage.clear();
+            account.wipe_storage = true;
+        }
+        account.info = transition.info.as_ref().map(AccountInfo::copy_without_code);
+
+        if let Some(storage) = transition.storage {
+            account.storage.extend(
+                storage
+                    .iter()
+                    .filter(|(_, slot)| slot.is_changed())
+                    .map(|(key, slot)| (*key, slot.present_value)),
+            );
+        }
+    }
+
+    /// Consumes the plain transition state and creates the changeset of the end state.
+    ///
+    /// As original values are unknown, all accounts and slots that got touched by a change
+    /// are included, same as with [`OriginalValuesKnown::No`](super::OriginalValuesKnown::No).
+    pub fn into_changeset(self) -> StateChangeset {
+        let mut accounts = Vec::with_capacity(self.accounts.len());
+        let mut storage = Vec::with_capacity(self.accounts.len());
+        for (address, account) in self.accounts {
+            accounts.push((address, account.info));
+            if !account.storage.is_empty() || account.wipe_storage {
+                storage.push(PlainStorageChangeset {
+                    address,
+                    wipe_storage: account.wipe_storage,
+                    storage: account.storage.into_iter().collect(),
+                });
+            }
+        }
+        StateChangeset {
+            accounts,
+            storage,
+            contracts: self.contracts.into_iter().collect(),
+        }
+    }
+}
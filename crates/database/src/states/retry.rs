@@ -0,0 +1,154 @@
+use core::{error::Error, fmt};
+use database_interface::DBErrorMarker;
+use std::boxed::Box;
+
+/// Callback invoked before a failed database read is retried.
+pub trait OnRetry: Send + Sync + 'static {
+    /// Invoked with the transient error and the number of the failed attempt, starting at `1`.
+    ///
+    /// Can be used to log the error or to back off before the next attempt.
+    fn on_retry(&self, error: &dyn Error, attempt: u32);
+}
+
+impl<F> OnRetry for F
+where
+    F: Fn(&dyn Error, u32) + Send + Sync + 'static,
+{
+    fn on_retry(&self, error: &dyn Error, attempt: u32) {
+        self(error, attempt)
+    }
+}
+
+/// Policy for retrying database reads of [`State`](super::State) that failed with a transient
+/// error.
+///
+/// An error is transient if [`DBErrorMarker::is_fatal`] returns `false`, fatal errors are
+/// returned right away. Reads are attempted at most [`RetryPolicy::max_attempts`] times, after
+/// that the last error is returned.
+pub struct RetryPolicy {
+    /// Maximum number of attempts of a single read, including the first one.
+    pub max_attempts: u32,
+    /// Callback invoked before each retry.
+    on_retry: Option<Box<dyn OnRetry>>,
+}
+
+impl fmt::Debug for RetryPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RetryPolicy")
+            .field("max_attempts", &self.max_attempts)
+            .field("on_retry", &self.on_retry.is_some())
+            .finish()
+    }
+}
+
+impl RetryPolicy {
+    /// Creates a policy that attempts each read at most `max_attempts` times.
+    pub fn new(max_attempts: u32) -> Self {
+        Self {
+            max_attempts,
+            on_retry: None,
+        }
+    }
+
+    /// Sets the callback invoked before each retry.
+    #[must_use]
+    pub fn with_on_retry(mut self, on_retry: impl OnRetry) -> Self {
+        self.on_retry = Some(Box::new(on_retry));
+        self
+    }
+
+    /// Runs `read` until it succeeds, fails with a fatal error or runs out of attempts.
+    pub fn run<T, E: DBErrorMarker>(&self, mut read: impl FnMut() -> Result<T, E>) -> Result<T, E> {
+        let mut attempt = 1;
+        loop {
+            match read() {
+                Err(error) if !error.is_fatal() && attempt < self.max_attempts => {
+                    if let Some(on_retry) = &self.on_retry {
+                        on_retry.on_retry(&error, attempt);
+                    }
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+/// Runs `read` with the policy if it is set.
+#[inline]
+pub(crate) fn retry<T, E: DBErrorMarker>(
+    policy: Option<&RetryPolicy>,
+    mut read: impl FnMut() -> Result<T, E>,
+) -> Result<T, E> {
+    match policy {
+        Some(policy) => policy.run(read),
+        None => read(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::sync::atomic::{AtomicU32, Ordering};
+    use std::sync::Arc;
+
+    #[derive(Debug)]
+    struct DbError {
+        fatal: bool,
+    }
+
+    impl fmt::Display for DbError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str("db error")
+        }
+    }
+
+    impl Error for DbError {}
+
+    impl DBErrorMarker for DbError {
+        fn is_fatal(&self) -> bool {
+            self.fatal
+        }
+    }
+
+    #[test]
+    fn retries_transient_errors() {
+        let retries = Arc::new(AtomicU32::new(0));
+        let counter = retries.clone();
+        let policy = RetryPolicy::new(3).with_on_retry(move |_: &dyn Error, attempt: u32| {
+            assert_eq!(counter.fetch_add(1, Ordering::Relaxed) + 1, attempt);
+        });
+
+        let mut calls = 0;
+        let result = policy.run(|| {
+            calls += 1;
+            if calls < 3 {
+                Err(DbError { fatal: false })
+            } else {
+                Ok(calls)
+            }
+        });
+        assert_eq!(result.unwrap(), 3);
+        assert_eq!(retries.load(Ordering::Relaxed), 2);
+
+        // Gives up after the last attempt.
+        let mut calls = 0;
+        let result = policy.run(|| {
+            calls += 1;
+            Err::<(), _>(DbError { fatal: false })
+        });
+        assert!(result.is_err());
+        assert_eq!(calls, 3);
+    }
+
+    #[test]
+    fn does_not_retry_fatal_errors() {
+        let mut calls = 0;
+        let result = RetryPolicy::new(3).run(|| {
+            calls += 1;
+            Err::<(), _>(DbError { fatal: true })
+        });
+        assert!(result.is_err());
+        assert_eq!(calls, 1);
+    }
+}
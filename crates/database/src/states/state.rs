@@ -1,20 +1,29 @@
 use crate::states::block_hash_cache::BlockHashCache;
 
 use super::{
-    bundle_state::BundleRetention, cache::CacheState, plain_account::PlainStorage, BundleState,
-    CacheAccount, StateBuilder, TransitionAccount, TransitionState,
+    bundle_state::BundleRetention,
+    cache::CacheState,
+    plain_account::PlainStorage,
+    retry::{retry, RetryPolicy},
+    transition_hook::{OnTransitionHook, TouchedAccount},
+    AccountFilter, BundleState, CacheAccount, PlainTransitionState, StateBuilder, StateChangeset,
+    TransitionAccount, TransitionState,
 };
+use crate::cache_stats::{entries_bytes, CacheCounters, CacheStats, HitCounter};
 use bytecode::Bytecode;
 use database_interface::{
     bal::{BalState, EvmDatabaseError},
     Database, DatabaseCommit, DatabaseRef, EmptyDB, OnStateHook,
 };
-use primitives::{hash_map, Address, AddressMap, HashMap, StorageKey, StorageValue, B256};
+use primitives::{
+    eip2935::{HISTORY_SERVE_WINDOW, HISTORY_STORAGE_ADDRESS},
+    hash_map, Address, AddressMap, HashMap, StorageKey, StorageValue, B256, U256,
+};
 use state::{
     bal::{alloy::AlloyBal, Bal, BlockAccessIndex},
     Account, AccountId, AccountInfo, EvmStorage,
 };
-use std::{borrow::Cow, boxed::Box, sync::Arc};
+use std::{borrow::Cow, boxed::Box, sync::Arc, vec::Vec};
 
 /// Database boxed with a lifetime and Send
 pub type DBBox<'a, E> = Box<dyn Database<Error = E> + Send + 'a>;
@@ -28,6 +37,10 @@ pub type StateDBBox<'a, E> = State<DBBox<'a, E>>;
 ///
 /// State clear flag is handled by the EVM journal in `finalize()` based on
 /// the spec. The database layer always applies post-EIP-161 commit semantics.
+///
+/// Errors of the database are returned as is, wrap it in a
+/// [`WithErrorContext`](database_interface::WithErrorContext) to know which account, storage
+/// slot, code or block hash failed to load.
 #[derive(derive_more::Debug)]
 pub struct State<DB> {
     /// Cached state contains both changed from evm execution and cached/loaded account/storages
@@ -47,6 +60,10 @@ pub struct State<DB> {
     ///
     /// Build reverts and state that gets applied to the state.
     pub transition_state: Option<TransitionState>,
+    /// Present values of the changed state, without original values and reverts
+    ///
+    /// Set instead of the transition state if only the end state matters.
+    pub plain_transition_state: Option<PlainTransitionState>,
     /// After block finishes we merge those changes inside bundle
     ///
     /// Bundle is used to update database and create changesets.
@@ -66,6 +83,15 @@ pub struct State<DB> {
     ///
     /// The fork block is different or some blocks are not saved inside database.
     pub block_hashes: BlockHashCache,
+    /// Number of the block being executed, if set, hashes of the blocks in its serve window are
+    /// first read from the ring buffer of the
+    /// [EIP-2935](https://eips.ethereum.org/EIPS/eip-2935) history storage contract,
+    /// falling back to the database if the slot is empty (e.g. before Prague).
+    ///
+    /// Blocks outside of the serve window, older than `HISTORY_SERVE_WINDOW` blocks or not
+    /// older than the current block, are always read from the database, as their slot holds the
+    /// hash of another block.
+    pub block_hashes_from_history: Option<u64>,
     /// BAL state.
     ///
     /// Can contain both the BAL for reads and BAL builder that is used to build BAL.
@@ -73,6 +99,20 @@ pub struct State<DB> {
     /// Hook invoked whenever state changes are committed.
     #[debug(skip)]
     pub state_hook: Option<Box<dyn OnStateHook>>,
+    /// Hook invoked with the accounts and slots touched by the transitions of each commit.
+    #[debug(skip)]
+    pub transition_hook: Option<Box<dyn OnTransitionHook>>,
+    /// Policy for retrying database reads that failed with a transient error.
+    ///
+    /// If not set, database errors are returned right away.
+    pub retry_policy: Option<RetryPolicy>,
+    /// Hit counters of the lookups in the cache, see [`State::cache_stats`].
+    pub cache_counters: CacheCounters,
+    /// Filter of the accounts existing in the database.
+    ///
+    /// Accounts the filter reports as not existing are loaded as not existing without asking the
+    /// database.
+    pub account_filter: Option<Arc<AccountFilter>>,
 }
 
 // Have ability to call State::builder without having to specify the type.
@@ -120,6 +160,8 @@ impl<DB: Database> State<DB> {
         // Add transition to transition state.
         if let Some(s) = self.transition_state.as_mut() {
             s.add_transitions(transitions)
+        } else if let Some(s) = self.plain_transition_state.as_mut() {
+            s.add_transitions(transitions)
         }
     }
 
@@ -135,6 +177,53 @@ impl<DB: Database> State<DB> {
         }
     }
 
+    /// Takes the end state tracked by [`StateBuilder::with_plain_state_update`].
+    ///
+    /// Returns an empty changeset if the state is not tracked.
+    pub fn take_plain_changeset(&mut self) -> StateChangeset {
+        self.plain_transition_state
+            .as_mut()
+            .map(|state| state.take().into_changeset())
+            .unwrap_or_default()
+    }
+
+    /// Unwinds the last `n` blocks that were merged with [`BundleRetention::Reverts`], e.g. to
+    /// handle a shallow reorg in place.
+    ///
+    /// The reverts of the bundle are applied block by block and the accounts they touch are
+    /// refreshed in the cache, accounts that are no longer changed by the bundle are evicted and
+    /// loaded again from the database. Transitions that are not merged yet are discarded.
+    ///
+    /// Returns the discarded changes of every unwound block, latest block first. Fewer than `n`
+    /// blocks are unwound if the bundle has fewer reverts.
+    pub fn unwind_blocks(&mut self, n: usize) -> Vec<StateChangeset> {
+        let mut touched = Vec::new();
+        if let Some(transition_state) = self.transition_state.as_mut() {
+            touched.extend(transition_state.take().transitions.into_keys());
+        }
+
+        let mut unwound = Vec::with_capacity(n.min(self.bundle_state.reverts.len()));
+        for _ in 0..n {
+            let Some(reverts) = self.bundle_state.reverts.last() else {
+                break;
+            };
+            touched.extend(reverts.iter().map(|(address, _)| *address));
+            unwound.extend(self.bundle_state.unwind_latest());
+        }
+
+        for address in touched {
+            match self.bundle_state.account(&address) {
+                Some(account) => {
+                    self.cache.accounts.insert(address, account.into());
+                }
+                None => {
+                    self.cache.accounts.remove(&address);
+                }
+            }
+        }
+        unwound
+    }
+
     /// Get a mutable reference to the [`CacheAccount`] for the given address.
     ///
     /// If the account is not found in the cache, it will be loaded from the
@@ -145,6 +234,9 @@ impl<DB: Database> State<DB> {
             self.use_preloaded_bundle,
             &self.bundle_state,
             &mut self.database,
+            self.retry_policy.as_ref(),
+            self.account_filter.as_deref(),
+            &self.cache_counters.accounts,
             address,
         )
     }
@@ -156,11 +248,15 @@ impl<DB: Database> State<DB> {
     ///
     /// This function accepts destructed fields of [`Self`] as arguments and
     /// returns a cached account with the lifetime of the provided cache reference.
+    #[expect(clippy::too_many_arguments)]
     fn load_cache_account_with<'a>(
         cache: &'a mut CacheState,
         use_preloaded_bundle: bool,
         bundle_state: &BundleState,
         database: &mut DB,
+        retry_policy: Option<&RetryPolicy>,
+        account_filter: Option<&AccountFilter>,
+        counter: &HitCounter,
         address: Address,
     ) -> Result<&'a mut CacheAccount, DB::Error> {
         Ok(match cache.accounts.entry(address) {
@@ -168,11 +264,19 @@ impl<DB: Database> State<DB> {
                 if use_preloaded_bundle {
                     // Load account from bundle state
                     if let Some(account) = bundle_state.account(&address).map(Into::into) {
+                        counter.hit();
                         return Ok(entry.insert(account));
                     }
                 }
-                // If not found in bundle, load it from database
-                let info = database.basic(address)?;
+                // If filtered out, account does not exist in database
+                let info = if account_filter.is_some_and(|filter| !filter.may_contain(&address)) {
+                    counter.hit();
+                    None
+                } else {
+                    // If not found in bundle, load it from database
+                    counter.miss();
+                    retry(retry_policy, || database.basic(address))?
+                };
                 let account = match info {
                     None => CacheAccount::new_loaded_not_existing(),
                     Some(acc) if acc.is_empty() => {
@@ -182,7 +286,10 @@ impl<DB: Database> State<DB> {
                 };
                 entry.insert(account)
             }
-            hash_map::Entry::Occupied(entry) => entry.into_mut(),
+            hash_map::Entry::Occupied(entry) => {
+                counter.hit();
+                entry.into_mut()
+            }
         })
     }
 
@@ -259,6 +366,43 @@ impl<DB: Database> State<DB> {
         self
     }
 
+    /// Sets the hook invoked with the accounts and slots touched by each commit.
+    #[inline]
+    pub fn set_transition_hook(&mut self, hook: Option<Box<dyn OnTransitionHook>>) {
+        self.transition_hook = hook;
+    }
+
+    /// Sets the hook invoked with the accounts and slots touched by each commit.
+    #[inline]
+    #[must_use]
+    pub fn with_transition_hook(mut self, hook: Option<Box<dyn OnTransitionHook>>) -> Self {
+        self.set_transition_hook(hook);
+        self
+    }
+
+    /// Sets the policy for retrying database reads that failed with a transient error.
+    #[inline]
+    pub fn set_retry_policy(&mut self, policy: Option<RetryPolicy>) {
+        self.retry_policy = policy;
+    }
+
+    /// Sets the policy for retrying database reads that failed with a transient error.
+    #[inline]
+    #[must_use]
+    pub fn with_retry_policy(mut self, policy: Option<RetryPolicy>) -> Self {
+        self.set_retry_policy(policy);
+        self
+    }
+
+    /// Sets the number of the block being executed to read block hashes from the EIP-2935
+    /// history storage contract, [`None`] to read them from the database.
+    ///
+    /// See [`State::block_hashes_from_history`].
+    #[inline]
+    pub const fn set_block_hashes_from_history(&mut self, current_block: Option<u64>) {
+        self.block_hashes_from_history = current_block;
+    }
+
     /// Returns whether the state has a BAL configured.
     #[inline]
     pub const fn has_bal(&self) -> bool {
@@ -274,30 +418,77 @@ impl<DB: Database> State<DB> {
             self.use_preloaded_bundle,
             &self.bundle_state,
             &mut self.database,
+            self.retry_policy.as_ref(),
+            self.account_filter.as_deref(),
+            &self.cache_counters.accounts,
             address,
         )?;
 
         // Account will always be some, but if it is not, StorageValue::ZERO will be returned.
         let is_storage_known = account.status.is_storage_known();
-        Ok(account
-            .account
-            .as_mut()
-            .map(|account| match account.storage.entry(index) {
-                hash_map::Entry::Occupied(entry) => Ok(*entry.get()),
-                hash_map::Entry::Vacant(entry) => {
-                    // If account was destroyed or account is newly built
-                    // we return zero and don't ask database.
-                    let value = if is_storage_known {
-                        StorageValue::ZERO
-                    } else {
-                        self.database.storage(address, index)?
-                    };
-                    entry.insert(value);
-                    Ok(value)
-                }
-            })
-            .transpose()?
-            .unwrap_or_default())
+        let Some(account) = account.account.as_mut() else {
+            self.cache_counters.storage.hit();
+            return Ok(StorageValue::ZERO);
+        };
+        match account.storage.entry(index) {
+            hash_map::Entry::Occupied(entry) => {
+                self.cache_counters.storage.hit();
+                Ok(*entry.get())
+            }
+            hash_map::Entry::Vacant(entry) => {
+                // If account was destroyed or account is newly built
+                // we return zero and don't ask database.
+                let value = if is_storage_known {
+                    self.cache_counters.storage.hit();
+                    StorageValue::ZERO
+                } else {
+                    self.cache_counters.storage.miss();
+                    retry(self.retry_policy.as_ref(), || {
+                        self.database.storage(address, index)
+                    })?
+                };
+                entry.insert(value);
+                Ok(value)
+            }
+        }
+    }
+
+    /// Returns the size of the cache and the hit ratios of the lookups since the last
+    /// [`State::reset_cache_stats`].
+    ///
+    /// Bundle state and transitions are not part of the cache and are not included.
+    pub fn cache_stats(&self) -> CacheStats {
+        let storage_slots = self
+            .cache
+            .accounts
+            .values()
+            .filter_map(|account| account.account.as_ref())
+            .map(|account| account.storage.len())
+            .sum();
+        let block_hashes = self.block_hashes.iter().count();
+        CacheStats {
+            accounts: self.cache.accounts.len(),
+            storage_slots,
+            contracts: self.cache.contracts.len(),
+            block_hashes,
+            account_bytes: entries_bytes::<Address, CacheAccount>(self.cache.accounts.len()),
+            storage_bytes: entries_bytes::<StorageKey, StorageValue>(storage_slots),
+            contract_bytes: entries_bytes::<B256, Bytecode>(self.cache.contracts.len())
+                + self
+                    .cache
+                    .contracts
+                    .values()
+                    .map(Bytecode::len)
+                    .sum::<usize>(),
+            block_hash_bytes: entries_bytes::<u64, B256>(block_hashes),
+            ..Default::default()
+        }
+        .with_counters(&self.cache_counters)
+    }
+
+    /// Resets the hit counters of [`State::cache_stats`].
+    pub fn reset_cache_stats(&self) {
+        self.cache_counters.reset();
     }
 }
 
@@ -327,19 +518,24 @@ impl<DB: Database> Database for State<DB> {
 
     fn code_by_hash(&mut self, code_hash: B256) -> Result<Bytecode, Self::Error> {
         let res = match self.cache.contracts.entry(code_hash) {
-            hash_map::Entry::Occupied(entry) => Ok(entry.get().clone()),
+            hash_map::Entry::Occupied(entry) => {
+                self.cache_counters.contracts.hit();
+                Ok(entry.get().clone())
+            }
             hash_map::Entry::Vacant(entry) => {
                 if self.use_preloaded_bundle {
                     if let Some(code) = self.bundle_state.contracts.get(&code_hash) {
+                        self.cache_counters.contracts.hit();
                         entry.insert(code.clone());
                         return Ok(code.clone());
                     }
                 }
                 // If not found in bundle ask database
-                let code = self
-                    .database
-                    .code_by_hash(code_hash)
-                    .map_err(EvmDatabaseError::Database)?;
+                self.cache_counters.contracts.miss();
+                let code = retry(self.retry_policy.as_ref(), || {
+                    self.database.code_by_hash(code_hash)
+                })
+                .map_err(EvmDatabaseError::Database)?;
                 entry.insert(code.clone());
                 Ok(code)
             }
@@ -381,14 +577,28 @@ impl<DB: Database> Database for State<DB> {
     fn block_hash(&mut self, number: u64) -> Result<B256, Self::Error> {
         // Check cache first
         if let Some(hash) = self.block_hashes.get(number) {
+            self.cache_counters.block_hashes.hit();
             return Ok(hash);
         }
+        self.cache_counters.block_hashes.miss();
+
+        // Post-Prague, the hash of the block is kept in the history storage contract.
+        if let Some(slot) = history_slot(self.block_hashes_from_history, number) {
+            let hash = self
+                .storage(HISTORY_STORAGE_ADDRESS, slot)
+                .map_err(EvmDatabaseError::Database)?;
+            if !hash.is_zero() {
+                let hash = B256::from(hash);
+                self.block_hashes.insert(number, hash);
+                return Ok(hash);
+            }
+        }
 
         // Not in cache, fetch from database
-        let hash = self
-            .database
-            .block_hash(number)
-            .map_err(EvmDatabaseError::Database)?;
+        let hash = retry(self.retry_policy.as_ref(), || {
+            self.database.block_hash(number)
+        })
+        .map_err(EvmDatabaseError::Database)?;
 
         // Insert into cache
         self.block_hashes.insert(number, hash);
@@ -401,16 +611,28 @@ impl<DB: Database> DatabaseCommit for State<DB> {
     fn commit(&mut self, changes: AddressMap<Account>) {
         self.bal_state.commit(&changes);
 
+        let collect_touched = self.transition_hook.is_some();
+        let mut touched = Vec::new();
+
         if let Some(hook) = self.state_hook.as_mut() {
-            let transitions = self.cache.apply_evm_state_iter(
-                changes
-                    .iter()
-                    .map(|(address, account)| (*address, Cow::Borrowed(account))),
-                |_, _| {},
-            );
+            let transitions = self
+                .cache
+                .apply_evm_state_iter(
+                    changes
+                        .iter()
+                        .map(|(address, account)| (*address, Cow::Borrowed(account))),
+                    |_, _| {},
+                )
+                .inspect(|(address, transition)| {
+                    if collect_touched {
+                        touched.push(TouchedAccount::new(*address, transition));
+                    }
+                });
 
             if let Some(s) = self.transition_state.as_mut() {
                 s.add_transitions(transitions)
+            } else if let Some(s) = self.plain_transition_state.as_mut() {
+                s.add_transitions(transitions)
             } else {
                 // Advance the iter to apply all state updates.
                 transitions.for_each(|_| {});
@@ -418,24 +640,37 @@ impl<DB: Database> DatabaseCommit for State<DB> {
 
             hook.on_state(changes);
         } else {
-            let transitions = self.cache.apply_evm_state_iter(
-                changes
-                    .into_iter()
-                    .map(|(address, account)| (address, Cow::Owned(account))),
-                |_, _| {},
-            );
+            let transitions = self
+                .cache
+                .apply_evm_state_iter(
+                    changes
+                        .into_iter()
+                        .map(|(address, account)| (address, Cow::Owned(account))),
+                    |_, _| {},
+                )
+                .inspect(|(address, transition)| {
+                    if collect_touched {
+                        touched.push(TouchedAccount::new(*address, transition));
+                    }
+                });
 
             if let Some(s) = self.transition_state.as_mut() {
                 s.add_transitions(transitions)
+            } else if let Some(s) = self.plain_transition_state.as_mut() {
+                s.add_transitions(transitions)
             } else {
                 // Advance the iter to apply all state updates.
                 transitions.for_each(|_| {});
             }
         }
+
+        if let Some(hook) = self.transition_hook.as_mut() {
+            hook.on_transitions(&touched);
+        }
     }
 
     fn commit_iter(&mut self, changes: &mut dyn Iterator<Item = (Address, Account)>) {
-        if self.state_hook.is_some() {
+        if self.state_hook.is_some() || self.transition_hook.is_some() {
             let changes = changes.collect::<AddressMap<_>>();
             self.commit(changes);
             return;
@@ -450,6 +685,15 @@ impl<DB: Database> DatabaseCommit for State<DB> {
                     s.add_transition(address, transition);
                 }
             }
+        } else if let Some(s) = self.plain_transition_state.as_mut() {
+            for (address, account) in changes {
+                self.bal_state.commit_one(address, &account);
+                if let Some(transition) =
+                    self.cache.apply_account_state(address, Cow::Owned(account))
+                {
+                    s.add_transition(address, transition);
+                }
+            }
         } else {
             for (address, account) in changes {
                 self.bal_state.commit_one(address, &account);
@@ -479,13 +723,27 @@ impl<DB: DatabaseRef> DatabaseRef for State<DB> {
             }
         }
 
+        // Account filtered out does not exist in database
+        if loaded_account.is_none()
+            && self
+                .account_filter
+                .as_ref()
+                .is_some_and(|filter| !filter.may_contain(&address))
+        {
+            loaded_account = Some(None);
+        }
+
         // If not found, load it from database
         if loaded_account.is_none() {
+            self.cache_counters.accounts.miss();
             loaded_account = Some(
-                self.database
-                    .basic_ref(address)
-                    .map_err(EvmDatabaseError::Database)?,
+                retry(self.retry_policy.as_ref(), || {
+                    self.database.basic_ref(address)
+                })
+                .map_err(EvmDatabaseError::Database)?,
             );
+        } else {
+            self.cache_counters.accounts.hit();
         }
 
         // safe to unwrap as it in some in condition above
@@ -503,18 +761,22 @@ impl<DB: DatabaseRef> DatabaseRef for State<DB> {
     fn code_by_hash_ref(&self, code_hash: B256) -> Result<Bytecode, Self::Error> {
         // Check if code is in cache
         if let Some(code) = self.cache.contracts.get(&code_hash) {
+            self.cache_counters.contracts.hit();
             return Ok(code.clone());
         }
         // If bundle state is used, check if code is in bundle state
         if self.use_preloaded_bundle {
             if let Some(code) = self.bundle_state.contracts.get(&code_hash) {
+                self.cache_counters.contracts.hit();
                 return Ok(code.clone());
             }
         }
         // If not found, load it from database
-        self.database
-            .code_by_hash_ref(code_hash)
-            .map_err(EvmDatabaseError::Database)
+        self.cache_counters.contracts.miss();
+        retry(self.retry_policy.as_ref(), || {
+            self.database.code_by_hash_ref(code_hash)
+        })
+        .map_err(EvmDatabaseError::Database)
     }
 
     fn storage_ref(
@@ -532,33 +794,54 @@ impl<DB: DatabaseRef> DatabaseRef for State<DB> {
             if let Some(plain_account) = &account.account {
                 // If storage is known, we can return it
                 if let Some(storage_value) = plain_account.storage.get(&index) {
+                    self.cache_counters.storage.hit();
                     return Ok(*storage_value);
                 }
                 // If account was destroyed or account is newly built
                 // we return zero and don't ask database.
                 if account.status.is_storage_known() {
+                    self.cache_counters.storage.hit();
                     return Ok(StorageValue::ZERO);
                 }
             }
         }
 
         // If not found, load it from database
-        self.database
-            .storage_ref(address, index)
-            .map_err(EvmDatabaseError::Database)
+        self.cache_counters.storage.miss();
+        retry(self.retry_policy.as_ref(), || {
+            self.database.storage_ref(address, index)
+        })
+        .map_err(EvmDatabaseError::Database)
     }
 
     fn block_hash_ref(&self, number: u64) -> Result<B256, Self::Error> {
         if let Some(hash) = self.block_hashes.get(number) {
+            self.cache_counters.block_hashes.hit();
             return Ok(hash);
         }
+        self.cache_counters.block_hashes.miss();
+        if let Some(slot) = history_slot(self.block_hashes_from_history, number) {
+            let hash = self.storage_ref(HISTORY_STORAGE_ADDRESS, slot)?;
+            if !hash.is_zero() {
+                return Ok(B256::from(hash));
+            }
+        }
         // If not found, load it from database
-        self.database
-            .block_hash_ref(number)
-            .map_err(EvmDatabaseError::Database)
+        retry(self.retry_policy.as_ref(), || {
+            self.database.block_hash_ref(number)
+        })
+        .map_err(EvmDatabaseError::Database)
     }
 }
 
+/// Returns the slot of the EIP-2935 history storage contract that keeps the hash of the block,
+/// if the block is in the serve window of the current block.
+fn history_slot(current_block: Option<u64>, number: u64) -> Option<StorageKey> {
+    let current_block = current_block?;
+    (number < current_block && current_block - number <= HISTORY_SERVE_WINDOW)
+        .then(|| U256::from(number % HISTORY_SERVE_WINDOW))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -566,7 +849,7 @@ mod tests {
         states::{reverts::AccountInfoRevert, StorageSlot},
         AccountRevert, AccountStatus, BundleAccount, RevertToSlot,
     };
-    use primitives::{keccak256, BLOCK_HASH_HISTORY, U256};
+    use primitives::{address, keccak256, BLOCK_HASH_HISTORY, U256};
     use state::{EvmStorageSlot, TransactionId};
 
     fn evm_storage<const N: usize>(
@@ -575,6 +858,139 @@ mod tests {
         Some(Cow::Owned(HashMap::from_iter(slots)))
     }
 
+    #[test]
+    fn retries_transient_database_errors() {
+        #[derive(Debug)]
+        struct Flaky;
+
+        impl core::fmt::Display for Flaky {
+            fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                f.write_str("flaky")
+            }
+        }
+
+        impl core::error::Error for Flaky {}
+
+        impl database_interface::DBErrorMarker for Flaky {
+            fn is_fatal(&self) -> bool {
+                false
+            }
+        }
+
+        /// Fails every read until it has failed `failures` times.
+        struct FlakyDB {
+            failures: u32,
+        }
+
+        impl FlakyDB {
+            fn read<T: Default>(&mut self) -> Result<T, Flaky> {
+                if self.failures == 0 {
+                    return Ok(T::default());
+                }
+                self.failures -= 1;
+                Err(Flaky)
+            }
+        }
+
+        impl Database for FlakyDB {
+            type Error = Flaky;
+
+            fn basic(&mut self, _address: Address) -> Result<Option<AccountInfo>, Self::Error> {
+                self.read()
+            }
+
+            fn code_by_hash(&mut self, _code_hash: B256) -> Result<Bytecode, Self::Error> {
+                self.read()
+            }
+
+            fn storage(
+                &mut self,
+                _address: Address,
+                _index: StorageKey,
+            ) -> Result<StorageValue, Self::Error> {
+                self.read()
+            }
+
+            fn block_hash(&mut self, _number: u64) -> Result<B256, Self::Error> {
+                self.read()
+            }
+        }
+
+        let address = Address::with_last_byte(1);
+        let mut state = State::builder()
+            .with_database(FlakyDB { failures: 2 })
+            .build()
+            .with_retry_policy(Some(RetryPolicy::new(3)));
+        assert_eq!(state.basic(address).unwrap(), None);
+
+        // Gives up once the attempts are used.
+        state.database.failures = 3;
+        assert!(matches!(
+            state.block_hash(1),
+            Err(EvmDatabaseError::Database(Flaky))
+        ));
+
+        // Without a policy the first error is returned.
+        state.set_retry_policy(None);
+        state.database.failures = 1;
+        assert!(state
+            .storage(Address::with_last_byte(2), StorageKey::from(1))
+            .is_err());
+    }
+
+    #[test]
+    fn transition_hook() {
+        let touched = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let hook_touched = Arc::clone(&touched);
+        let mut state = State::builder().build().with_transition_hook(Some(Box::new(
+            move |accounts: &[TouchedAccount]| {
+                hook_touched.lock().unwrap().extend_from_slice(accounts)
+            },
+        )));
+
+        let address = Address::with_last_byte(1);
+        let (slot1, slot2) = (StorageKey::from(1), StorageKey::from(2));
+        let account = Account::from(AccountInfo {
+            nonce: 1,
+            ..Default::default()
+        })
+        .with_storage(
+            [
+                (
+                    slot1,
+                    EvmStorageSlot::new_changed(
+                        StorageValue::ZERO,
+                        StorageValue::from(1),
+                        TransactionId::ZERO,
+                    ),
+                ),
+                (
+                    slot2,
+                    EvmStorageSlot::new(StorageValue::from(2), TransactionId::ZERO),
+                ),
+            ]
+            .into_iter(),
+        )
+        .with_touched_mark();
+        // Untouched accounts produce no transition.
+        let untouched = Account::from(AccountInfo::default());
+
+        state.commit_iter(
+            &mut [(address, account), (Address::with_last_byte(2), untouched)].into_iter(),
+        );
+
+        let touched = touched.lock().unwrap();
+        assert_eq!(
+            *touched,
+            [TouchedAccount {
+                address,
+                destroyed: false,
+                storage_was_destroyed: false,
+                slots: Vec::from([slot1]),
+            }]
+        );
+    }
+
     #[test]
     fn has_bal_helper() {
         let state = State::builder().build();
@@ -610,6 +1026,42 @@ mod tests {
         assert_eq!(state.block_hashes.get(test_number), Some(block_test_hash));
     }
 
+    #[test]
+    fn block_hash_from_history_contract() {
+        let hash = B256::repeat_byte(0x11);
+        let number = HISTORY_SERVE_WINDOW + 5;
+        let current_block = number + 5;
+        let mut state = State::builder()
+            .with_eip2935_block_hashes(current_block)
+            .build();
+        state.insert_account_with_storage(
+            HISTORY_STORAGE_ADDRESS,
+            AccountInfo {
+                nonce: 1,
+                ..Default::default()
+            },
+            HashMap::from_iter([(U256::from(5), hash.into())]),
+        );
+
+        assert_eq!(state.block_hash_ref(number).unwrap(), hash);
+        assert_eq!(state.block_hash(number).unwrap(), hash);
+        assert_eq!(state.block_hashes.get(number), Some(hash));
+
+        // Empty slot falls back to the database.
+        assert_eq!(
+            state.block_hash(current_block - 1).unwrap(),
+            keccak256(U256::from(current_block - 1).to_string().as_bytes())
+        );
+
+        // Blocks outside of the serve window share the slot but are read from the database.
+        for number in [5, number + HISTORY_SERVE_WINDOW] {
+            let expected = keccak256(U256::from(number).to_string().as_bytes());
+            assert_eq!(state.block_hash_ref(number).unwrap(), expected);
+            assert_eq!(state.block_hash(number).unwrap(), expected);
+            assert_eq!(state.block_hashes.get(number), Some(expected));
+        }
+    }
+
     /// Test that block 0 can be correctly fetched and cached.
     /// This is a regression test for a bug where the cache was initialized with
     /// `(0, B256::ZERO)` entries, causing block 0 lookups to incorrectly match
@@ -1142,4 +1594,136 @@ mod tests {
             )])])
         )
     }
+
+    #[test]
+    fn unwind_blocks() {
+        let mut state = State::builder().with_bundle_update().build();
+        let address = Address::with_last_byte(1);
+        let slot = StorageKey::from(1);
+
+        // Block 1 sets the nonce and the slot to 1, block 2 to 2.
+        for value in 1..=2u64 {
+            let account = Account::from(AccountInfo {
+                nonce: value,
+                ..Default::default()
+            })
+            .with_storage(
+                [(
+                    slot,
+                    EvmStorageSlot::new_changed(
+                        StorageValue::from(value - 1),
+                        StorageValue::from(value),
+                        TransactionId::ZERO,
+                    ),
+                )]
+                .into_iter(),
+            )
+            .with_touched_mark();
+            state.commit_iter(&mut [(address, account)].into_iter());
+            state.merge_transitions(BundleRetention::Reverts);
+        }
+
+        let unwound = state.unwind_blocks(1);
+        assert_eq!(unwound.len(), 1);
+        assert_eq!(unwound[0].accounts[0].1.as_ref().unwrap().nonce, 2);
+        assert_eq!(
+            unwound[0].storage[0].storage,
+            [(slot, StorageValue::from(2))]
+        );
+        assert_eq!(state.basic(address).unwrap().unwrap().nonce, 1);
+        assert_eq!(
+            Database::storage(&mut state, address, slot).unwrap(),
+            StorageValue::from(1)
+        );
+
+        // Only one block is left.
+        assert_eq!(state.unwind_blocks(2).len(), 1);
+        assert!(state.bundle_state.is_empty());
+        assert_eq!(state.basic(address).unwrap(), None);
+    }
+
+    #[test]
+    fn plain_state_update() {
+        let mut state = State::builder().with_plain_state_update().build();
+        let address = Address::with_last_byte(1);
+        let slot = StorageKey::from(1);
+
+        for value in 1..=2u64 {
+            let account = Account::from(AccountInfo {
+                nonce: value,
+                ..Default::default()
+            })
+            .with_storage(
+                [(
+                    slot,
+                    EvmStorageSlot::new_changed(
+                        StorageValue::from(value - 1),
+                        StorageValue::from(value),
+                        TransactionId::ZERO,
+                    ),
+                )]
+                .into_iter(),
+            )
+            .with_touched_mark();
+            state.commit_iter(&mut [(address, account)].into_iter());
+        }
+        assert!(state.transition_state.is_none());
+
+        let changeset = state.take_plain_changeset();
+        assert_eq!(changeset.accounts.len(), 1);
+        assert_eq!(changeset.accounts[0].1.as_ref().unwrap().nonce, 2);
+        assert_eq!(
+            changeset.storage[0].storage,
+            [(slot, StorageValue::from(2))]
+        );
+        assert!(!changeset.storage[0].wipe_storage);
+
+        // Changes were taken.
+        assert!(state.take_plain_changeset().accounts.is_empty());
+        assert!(state.bundle_state.is_empty());
+    }
+
+    #[test]
+    fn account_filter_skips_absent_accounts() {
+        // Precompiles and system contracts only differ in their last bytes.
+        let existing = [
+            address!("0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2"),
+            Address::with_last_byte(1),
+        ];
+        let absent = [
+            address!("0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48"),
+            Address::with_last_byte(2),
+        ];
+        let mut db = crate::CacheDB::new(crate::EmptyDB::default());
+        for address in existing {
+            db.insert_account_info(
+                address,
+                AccountInfo {
+                    nonce: 1,
+                    ..Default::default()
+                },
+            );
+        }
+
+        let filter = AccountFilter::from_addresses(existing.iter(), 10);
+        let mut state = State::builder()
+            .with_database(db)
+            .with_account_filter(Arc::new(filter))
+            .build();
+
+        for address in absent {
+            assert_eq!(state.basic_ref(address).unwrap(), None);
+            assert_eq!(state.basic(address).unwrap(), None);
+        }
+        for address in existing {
+            assert_eq!(state.basic(address).unwrap().unwrap().nonce, 1);
+        }
+
+        // Only the existing accounts were loaded from the database.
+        let hits = state.cache_stats().account_hits;
+        assert_eq!(hits.misses, 2);
+        for address in absent {
+            assert!(state.cache.accounts[&address].account.is_none());
+        }
+    }
 }
@@ -1,6 +1,9 @@
 use crate::states::block_hash_cache::BlockHashCache;
 
-use super::{cache::CacheState, state::DBBox, BundleState, State, TransitionState};
+use super::{
+    cache::CacheState, state::DBBox, AccountFilter, BundleState, GenesisAlloc,
+    PlainTransitionState, State, TransitionState,
+};
 use database_interface::{
     bal::BalState, DBErrorMarker, Database, DatabaseRef, EmptyDB, WrapDatabaseRef,
 };
@@ -21,10 +24,18 @@ pub struct StateBuilder<DB> {
     ///
     /// Default is false.
     with_bundle_update: bool,
+    /// Do we want to track only the present values of the changed state?
+    ///
+    /// Default is false.
+    with_plain_state_update: bool,
     /// If we want to set different block hashes,
     with_block_hashes: BlockHashCache,
+    /// Read block hashes from the EIP-2935 history storage contract.
+    with_eip2935_block_hashes: Option<u64>,
     /// BAL state.
     bal_state: BalState,
+    /// Filter of the accounts existing in the database.
+    account_filter: Option<Arc<AccountFilter>>,
 }
 
 impl StateBuilder<EmptyDB> {
@@ -51,8 +62,11 @@ impl<DB: Database> StateBuilder<DB> {
             with_cache_prestate: None,
             with_bundle_prestate: None,
             with_bundle_update: false,
+            with_plain_state_update: false,
             with_block_hashes: BlockHashCache::new(),
+            with_eip2935_block_hashes: None,
             bal_state: BalState::default(),
+            account_filter: None,
         }
     }
 
@@ -65,8 +79,11 @@ impl<DB: Database> StateBuilder<DB> {
             with_cache_prestate: self.with_cache_prestate,
             with_bundle_prestate: self.with_bundle_prestate,
             with_bundle_update: self.with_bundle_update,
+            with_plain_state_update: self.with_plain_state_update,
             with_block_hashes: self.with_block_hashes,
+            with_eip2935_block_hashes: self.with_eip2935_block_hashes,
             bal_state: self.bal_state,
+            account_filter: self.account_filter,
         }
     }
 
@@ -120,6 +137,20 @@ impl<DB: Database> StateBuilder<DB> {
         }
     }
 
+    /// Tracks only the present values of the changed accounts and storage.
+    ///
+    /// Original values and reverts are not kept, which halves the memory of every changed slot.
+    /// Useful for simulations where only the end state matters, it can be taken with
+    /// [`State::take_plain_changeset`].
+    ///
+    /// **Note**: Ignored if [`with_bundle_update`](Self::with_bundle_update) is set.
+    pub fn with_plain_state_update(self) -> Self {
+        Self {
+            with_plain_state_update: true,
+            ..self
+        }
+    }
+
     /// It will use different cache for the state.
     ///
     /// **Note**: If set, it will ignore bundle prestate.
@@ -132,6 +163,28 @@ impl<DB: Database> StateBuilder<DB> {
         }
     }
 
+    /// Preloads the accounts, code and storage of a genesis-style alloc into the cache.
+    ///
+    /// Accounts are inserted as already loaded, on top of the cached prestate if one was set,
+    /// so the database is not queried for them.
+    ///
+    /// **Note**: Same as with [`with_cached_prestate`](Self::with_cached_prestate), bundle
+    /// prestate is ignored.
+    pub fn with_alloc(mut self, alloc: impl Into<GenesisAlloc>) -> Self {
+        alloc
+            .into()
+            .insert_into(self.with_cache_prestate.get_or_insert_with(CacheState::new));
+        self
+    }
+
+    /// Preloads the alloc from a JSON file, see [`with_alloc`](Self::with_alloc).
+    ///
+    /// The file can be either a bare alloc or a full genesis file with an `alloc` field.
+    #[cfg(feature = "genesis")]
+    pub fn with_alloc_file(self, path: impl AsRef<std::path::Path>) -> std::io::Result<Self> {
+        Ok(self.with_alloc(GenesisAlloc::from_file(path)?))
+    }
+
     /// Sets the block hashes for the state.
     pub fn with_block_hashes(self, block_hashes: BlockHashCache) -> Self {
         Self {
@@ -140,6 +193,17 @@ impl<DB: Database> StateBuilder<DB> {
         }
     }
 
+    /// Reads the hashes of the blocks before `current_block` from the ring buffer of the EIP-2935
+    /// history storage contract, falling back to the database if the slot is empty.
+    ///
+    /// See [`State::block_hashes_from_history`].
+    pub fn with_eip2935_block_hashes(self, current_block: u64) -> Self {
+        Self {
+            with_eip2935_block_hashes: Some(current_block),
+            ..self
+        }
+    }
+
     /// With BAL.
     pub fn with_bal(mut self, bal: Arc<Bal>) -> Self {
         self.bal_state.bal = Some(bal);
@@ -160,6 +224,15 @@ impl<DB: Database> StateBuilder<DB> {
         self
     }
 
+    /// With a filter of the accounts existing in the database.
+    ///
+    /// Accounts the filter reports as not existing are loaded as not existing without asking the
+    /// database. The filter must contain every account of the database, see [`AccountFilter`].
+    pub fn with_account_filter(mut self, filter: Arc<AccountFilter>) -> Self {
+        self.account_filter = Some(filter);
+        self
+    }
+
     /// Builds the State with the configured settings.
     pub fn build(mut self) -> State<DB> {
         let use_preloaded_bundle = if self.with_cache_prestate.is_some() {
@@ -172,11 +245,18 @@ impl<DB: Database> StateBuilder<DB> {
             cache: self.with_cache_prestate.unwrap_or_default(),
             database: self.database,
             transition_state: self.with_bundle_update.then(TransitionState::default),
+            plain_transition_state: (self.with_plain_state_update && !self.with_bundle_update)
+                .then(PlainTransitionState::default),
             bundle_state: self.with_bundle_prestate.unwrap_or_default(),
             use_preloaded_bundle,
             block_hashes: self.with_block_hashes,
+            block_hashes_from_history: self.with_eip2935_block_hashes,
             bal_state: self.bal_state,
             state_hook: None,
+            transition_hook: None,
+            retry_policy: None,
+            cache_counters: Default::default(),
+            account_filter: self.account_filter,
         }
     }
 }
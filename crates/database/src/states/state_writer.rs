@@ -0,0 +1,177 @@
+use super::{BundleState, OriginalValuesKnown, StateChangeset};
+use crate::in_memory_db::{AccountState, CacheDB};
+use bytecode::Bytecode;
+use database_interface::DatabaseRef;
+use primitives::{Address, StorageKey, StorageValue, B256};
+use state::AccountInfo;
+
+/// Storage backend the plain state changes of a [`BundleState`] are written to.
+///
+/// Most databases store accounts, storage and bytecodes in separate tables, this trait is the
+/// write side of them. [`BundleState::write_to`] flattens the bundle and calls it.
+pub trait StateWriter {
+    /// Error of the backend.
+    type Error;
+
+    /// Writes the account info, `None` deletes the account.
+    ///
+    /// The code of the account info is not set, bytecodes are written with
+    /// [`StateWriter::write_bytecode`].
+    fn write_account(
+        &mut self,
+        address: Address,
+        info: Option<AccountInfo>,
+    ) -> Result<(), Self::Error>;
+
+    /// Writes the storage slots of the account.
+    ///
+    /// If `wipe_storage` is set, the account was destroyed and its storage is cleared before
+    /// the slots are written.
+    fn write_storage(
+        &mut self,
+        address: Address,
+        wipe_storage: bool,
+        storage: &[(StorageKey, StorageValue)],
+    ) -> Result<(), Self::Error>;
+
+    /// Writes the bytecode by its hash.
+    fn write_bytecode(&mut self, code_hash: B256, bytecode: Bytecode) -> Result<(), Self::Error>;
+
+    /// Writes the receipts of the block.
+    ///
+    /// Backends that do not store receipts ignore them, this is the default.
+    fn write_receipts<R>(&mut self, block_number: u64, receipts: &[R]) -> Result<(), Self::Error> {
+        let _ = (block_number, receipts);
+        Ok(())
+    }
+}
+
+impl StateChangeset {
+    /// Writes the changeset to the backend.
+    ///
+    /// Bytecodes are written first, then accounts and then storage.
+    pub fn write_to<W: StateWriter>(self, writer: &mut W) -> Result<(), W::Error> {
+        for (code_hash, bytecode) in self.contracts {
+            writer.write_bytecode(code_hash, bytecode)?;
+        }
+        for (address, info) in self.accounts {
+            writer.write_account(address, info)?;
+        }
+        for storage in self.storage {
+            writer.write_storage(storage.address, storage.wipe_storage, &storage.storage)?;
+        }
+        Ok(())
+    }
+}
+
+impl BundleState {
+    /// Writes the plain state of the bundle to the backend.
+    ///
+    /// The original values of the bundle are assumed to be known, use
+    /// [`BundleState::to_plain_state`] and [`StateChangeset::write_to`] otherwise.
+    pub fn write_to<W: StateWriter>(&self, writer: &mut W) -> Result<(), W::Error> {
+        self.to_plain_state(OriginalValuesKnown::Yes)
+            .write_to(writer)
+    }
+}
+
+impl<ExtDB: DatabaseRef> StateWriter for CacheDB<ExtDB> {
+    type Error = ExtDB::Error;
+
+    fn write_account(
+        &mut self,
+        address: Address,
+        info: Option<AccountInfo>,
+    ) -> Result<(), Self::Error> {
+        match info {
+            Some(info) => self.insert_account_info(address, info),
+            None => {
+                let account = self.cache.accounts.entry(address).or_default();
+                account.info = AccountInfo::default();
+                account.storage.clear();
+                account.account_state = AccountState::NotExisting;
+            }
+        }
+        Ok(())
+    }
+
+    fn write_storage(
+        &mut self,
+        address: Address,
+        wipe_storage: bool,
+        storage: &[(StorageKey, StorageValue)],
+    ) -> Result<(), Self::Error> {
+        let account = self.load_account(address)?;
+        if wipe_storage {
+            account.storage.clear();
+            if account.account_state != AccountState::NotExisting {
+                account.account_state = AccountState::StorageCleared;
+            }
+        }
+        account.storage.extend(storage.iter().copied());
+        Ok(())
+    }
+
+    fn write_bytecode(&mut self, code_hash: B256, bytecode: Bytecode) -> Result<(), Self::Error> {
+        self.cache.contracts.insert(code_hash, bytecode);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{states::bundle_state::BundleRetention, InMemoryDB, State};
+    use database_interface::DatabaseCommit;
+    use primitives::U256;
+    use state::{Account, EvmStorageSlot, TransactionId};
+
+    #[test]
+    fn write_bundle_to_cache_db() {
+        let mut state = State::builder().with_bundle_update().build();
+        let address = Address::with_last_byte(1);
+        let bytecode = Bytecode::new_legacy([0x00].into());
+        let code_hash = bytecode.hash_slow();
+        let account = Account::from(AccountInfo {
+            balance: U256::from(10),
+            nonce: 1,
+            code_hash,
+            code: Some(bytecode.clone()),
+            ..Default::default()
+        })
+        .with_storage(
+            [(
+                StorageKey::from(1),
+                EvmStorageSlot::new_changed(
+                    StorageValue::ZERO,
+                    StorageValue::from(5),
+                    TransactionId::ZERO,
+                ),
+            )]
+            .into_iter(),
+        )
+        .with_touched_mark();
+        state.commit_iter(&mut [(address, account)].into_iter());
+        state.merge_transitions(BundleRetention::PlainState);
+
+        let mut db = InMemoryDB::default();
+        state.bundle_state.write_to(&mut db).unwrap();
+
+        let info = db.basic_ref(address).unwrap().unwrap();
+        assert_eq!((info.balance, info.nonce), (U256::from(10), 1));
+        assert_eq!(db.code_by_hash_ref(code_hash).unwrap(), bytecode);
+        assert_eq!(
+            db.storage_ref(address, StorageKey::from(1)).unwrap(),
+            StorageValue::from(5)
+        );
+
+        // Deleted accounts are written as not existing.
+        StateChangeset {
+            accounts: [(address, None)].into(),
+            ..Default::default()
+        }
+        .write_to(&mut db)
+        .unwrap();
+        assert_eq!(db.basic_ref(address).unwrap(), None);
+    }
+}
@@ -0,0 +1,61 @@
+use super::TransitionAccount;
+use primitives::{Address, StorageKey};
+use state::EvmStorage;
+use std::{borrow::Cow, vec::Vec};
+
+/// Account and storage slots changed by a transition.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TouchedAccount {
+    /// Address of the account.
+    pub address: Address,
+    /// Whether the account was destroyed or does not exist after the transition.
+    pub destroyed: bool,
+    /// Whether the whole storage of the account was cleared.
+    pub storage_was_destroyed: bool,
+    /// Storage slots changed by the transition.
+    pub slots: Vec<StorageKey>,
+}
+
+impl TouchedAccount {
+    /// Creates a new touched account from the transition.
+    pub fn new(
+        address: Address,
+        transition: &TransitionAccount<Option<Cow<'_, EvmStorage>>>,
+    ) -> Self {
+        Self {
+            address,
+            destroyed: transition.info.is_none(),
+            storage_was_destroyed: transition.storage_was_destroyed,
+            slots: transition
+                .storage
+                .as_ref()
+                .map(|storage| {
+                    storage
+                        .iter()
+                        .filter(|(_, slot)| slot.is_changed())
+                        .map(|(key, _)| *key)
+                        .collect()
+                })
+                .unwrap_or_default(),
+        }
+    }
+}
+
+/// A hook that is called with the accounts and slots touched by the transitions of each commit.
+///
+/// It is invoked after the transitions are applied to the cache, so an external trie
+/// prefetcher can start fetching the trie nodes needed for the state root while the rest of
+/// the block is executing.
+pub trait OnTransitionHook: Send + 'static {
+    /// Invoked with the accounts touched by a single commit.
+    fn on_transitions(&mut self, touched: &[TouchedAccount]);
+}
+
+impl<F> OnTransitionHook for F
+where
+    F: FnMut(&[TouchedAccount]) + Send + 'static,
+{
+    fn on_transitions(&mut self, touched: &[TouchedAccount]) {
+        self(touched)
+    }
+}
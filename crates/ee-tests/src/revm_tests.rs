@@ -4,7 +4,8 @@ use revm::{
     bytecode::opcode,
     context::{CfgEnv, ContextTr, TxEnv},
     database::{BenchmarkDB, BENCH_CALLER, BENCH_TARGET},
-    primitives::{address, b256, hardfork::SpecId, Bytes, TxKind, KECCAK_EMPTY, U256},
+    interpreter::CreateScheme,
+    primitives::{address, b256, hardfork::SpecId, Address, Bytes, TxKind, KECCAK_EMPTY, U256},
     state::{AccountStatus, Bytecode},
     Context, ExecuteCommitEvm, ExecuteEvm, MainBuilder, MainContext,
 };
@@ -796,3 +797,65 @@ fn test_custom_opcode_transaction() {
         "DOUBLE(7) should store 14 in slot 0"
     );
 }
+
+const CREATED_ADDRESS: Address = address!("0x00000000000000000000000000000000000c0001");
+const CREATED2_ADDRESS: Address = address!("0x00000000000000000000000000000000000c0002");
+
+/// Places every created contract at a fixed address per scheme.
+fn fixed_create_address(
+    _caller: Address,
+    scheme: CreateScheme,
+    _nonce: u64,
+    _init_code: &[u8],
+) -> Option<Address> {
+    match scheme {
+        CreateScheme::Create => Some(CREATED_ADDRESS),
+        CreateScheme::Create2 { .. } => Some(CREATED2_ADDRESS),
+        CreateScheme::Custom { .. } => None,
+    }
+}
+
+/// Test that the create address override applies to create transactions and to `CREATE2`.
+#[test]
+fn test_create_address_override() {
+    let mut evm = Context::mainnet()
+        .with_cfg(
+            CfgEnv::new_with_spec(SpecId::AMSTERDAM).with_create_address_fn(fixed_create_address),
+        )
+        .modify_block_chained(|block| block.gas_limit = 100_000_000)
+        .with_db(BenchmarkDB::new_bytecode(Bytecode::new()))
+        .build_mainnet();
+
+    // Init code that deploys an empty contract with `CREATE2`.
+    let init_code = [
+        opcode::PUSH1,
+        0x00,
+        opcode::PUSH1,
+        0x00,
+        opcode::PUSH1,
+        0x00,
+        opcode::PUSH1,
+        0x00,
+        opcode::CREATE2,
+        opcode::STOP,
+    ];
+    let result = evm
+        .transact_one(
+            TxEnv::builder_for_bench()
+                .kind(TxKind::Create)
+                .data(Bytes::copy_from_slice(&init_code))
+                .gas_limit(1_000_000)
+                .gas_price(0)
+                .build_fill(),
+        )
+        .unwrap();
+
+    assert!(
+        result.is_success(),
+        "Transaction should succeed: {result:?}"
+    );
+    assert_eq!(result.created_address(), Some(CREATED_ADDRESS));
+    let state = &evm.ctx.journal_mut().state;
+    assert_eq!(state[&CREATED_ADDRESS].info.nonce, 2);
+    assert_eq!(state[&CREATED2_ADDRESS].info.nonce, 1);
+}
@@ -0,0 +1,230 @@
+//! Call mocking for test frameworks, see [`CallMocks`].
+use crate::FrameResult;
+use interpreter::{
+    interpreter_action::FrameInit, CallOutcome, FrameInput, Gas, InstructionResult,
+    InterpreterResult,
+};
+use primitives::{Address, Bytes};
+use std::vec::Vec;
+
+/// Synthetic result of a mocked call.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct CallMock {
+    /// Address whose code would be executed by the call.
+    pub target: Address,
+    /// Prefix of the calldata, an empty prefix matches every call to the target.
+    pub calldata: Bytes,
+    /// Data returned by the call.
+    pub output: Bytes,
+    /// Gas used by the call. The call runs out of gas if it is above the gas limit of the call.
+    pub gas_used: u64,
+    /// Whether the call reverts.
+    pub revert: bool,
+}
+
+impl CallMock {
+    /// Creates a mock that returns `output` for calls to `target` whose calldata starts with
+    /// `calldata`.
+    pub fn new(target: Address, calldata: Bytes, output: Bytes) -> Self {
+        Self {
+            target,
+            calldata,
+            output,
+            ..Default::default()
+        }
+    }
+
+    /// Sets the gas used by the call.
+    pub fn with_gas_used(self, gas_used: u64) -> Self {
+        Self { gas_used, ..self }
+    }
+
+    /// Makes the call revert with the output.
+    pub fn with_revert(self) -> Self {
+        Self {
+            revert: true,
+            ..self
+        }
+    }
+
+    /// Builds the result of the call.
+    fn outcome(&self, inputs: &interpreter::CallInputs) -> CallOutcome {
+        let mut gas = Gas::new_with_regular_gas_and_reservoir(inputs.gas_limit, inputs.reservoir);
+        let result = if !gas.record_regular_cost(self.gas_used) {
+            gas.spend_all();
+            InterpreterResult::new(InstructionResult::OutOfGas, Bytes::new(), gas)
+        } else if self.revert {
+            InterpreterResult::new(InstructionResult::Revert, self.output.clone(), gas)
+        } else {
+            InterpreterResult::new(InstructionResult::Return, self.output.clone(), gas)
+        };
+        let mut outcome = CallOutcome::new(result, inputs.return_memory_offset.clone());
+        outcome.charged_new_account_state_gas = inputs.charged_new_account_state_gas;
+        outcome
+    }
+}
+
+/// Set of call mocks that short-circuit matching calls with a synthetic result, like
+/// `vm.mockCall` of test frameworks.
+///
+/// It is meant to be used from [`Handler::frame_intercept`](crate::Handler::frame_intercept).
+/// When several mocks match a call, the one with the longest calldata prefix wins.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CallMocks {
+    mocks: Vec<CallMock>,
+}
+
+impl CallMocks {
+    /// Creates an empty set of mocks.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the mock, replacing the mock with the same target and calldata prefix.
+    pub fn insert(&mut self, mock: CallMock) {
+        self.remove(mock.target, &mock.calldata);
+        self.mocks.push(mock);
+    }
+
+    /// Removes the mock with the given target and calldata prefix.
+    pub fn remove(&mut self, target: Address, calldata: &[u8]) -> Option<CallMock> {
+        let index = self
+            .mocks
+            .iter()
+            .position(|mock| mock.target == target && mock.calldata == calldata)?;
+        Some(self.mocks.swap_remove(index))
+    }
+
+    /// Removes all mocks.
+    pub fn clear(&mut self) {
+        self.mocks.clear();
+    }
+
+    /// Returns `true` if there are no mocks.
+    pub fn is_empty(&self) -> bool {
+        self.mocks.is_empty()
+    }
+
+    /// Returns the mock matching a call to `target` with the given calldata.
+    pub fn find(&self, target: Address, calldata: &[u8]) -> Option<&CallMock> {
+        self.mocks
+            .iter()
+            .filter(|mock| mock.target == target && calldata.starts_with(&mock.calldata))
+            .max_by_key(|mock| mock.calldata.len())
+    }
+
+    /// Returns the synthetic result of the frame if it is a mocked call.
+    pub fn intercept(&self, frame_init: &FrameInit) -> Option<FrameResult> {
+        if self.mocks.is_empty() {
+            return None;
+        }
+        let FrameInput::Call(inputs) = &frame_init.frame_input else {
+            return None;
+        };
+        let calldata = inputs.input.as_bytes_memory(&frame_init.memory);
+        let mock = self.find(inputs.bytecode_address, &calldata)?;
+        Some(FrameResult::Call(mock.outcome(inputs)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{EvmTr, Handler, MainBuilder, MainContext, MainnetContext, MainnetEvm};
+    use bytecode::opcode;
+    use context::{
+        result::{EVMError, ExecutionResult, HaltReason, Output},
+        Context, TxEnv,
+    };
+    use context_interface::ContextSetters;
+    use core::convert::Infallible;
+    use database::{BenchmarkDB, BENCH_CALLER, BENCH_TARGET};
+    use primitives::{address, TxKind};
+    use state::Bytecode;
+
+    const ORACLE: Address = address!("0x00000000000000000000000000000000000000aa");
+
+    type TestEvm = MainnetEvm<MainnetContext<BenchmarkDB>>;
+
+    struct MockHandler(CallMocks);
+
+    impl Handler for MockHandler {
+        type Evm = TestEvm;
+        type Error = EVMError<Infallible>;
+        type HaltReason = HaltReason;
+
+        fn frame_intercept(
+            &mut self,
+            _evm: &mut Self::Evm,
+            frame_init: &FrameInit,
+        ) -> Result<Option<FrameResult>, Self::Error> {
+            Ok(self.0.intercept(frame_init))
+        }
+    }
+
+    #[test]
+    fn mocked_call_returns_output() {
+        // STATICCALL the oracle with `0x01` as calldata and return its 32 byte output.
+        let bytecode = Bytecode::new_raw(
+            [
+                opcode::PUSH1,
+                0x01,
+                opcode::PUSH1,
+                0x00,
+                opcode::MSTORE8,
+                opcode::PUSH1,
+                0x20,
+                opcode::PUSH1,
+                0x00,
+                opcode::PUSH1,
+                0x01,
+                opcode::PUSH1,
+                0x00,
+                opcode::PUSH1,
+                0xaa,
+                opcode::GAS,
+                opcode::STATICCALL,
+                opcode::POP,
+                opcode::PUSH1,
+                0x20,
+                opcode::PUSH1,
+                0x00,
+                opcode::RETURN,
+            ]
+            .to_vec()
+            .into(),
+        );
+        let mut evm = Context::mainnet()
+            .with_db(BenchmarkDB::new_bytecode(bytecode))
+            .build_mainnet();
+        let basefee = evm.ctx.block.basefee as u128;
+        evm.ctx().set_tx(
+            TxEnv::builder()
+                .caller(BENCH_CALLER)
+                .kind(TxKind::Call(BENCH_TARGET))
+                .gas_limit(100_000)
+                .gas_price(basefee)
+                .build()
+                .unwrap(),
+        );
+
+        let output = Bytes::from([0x42; 32]);
+        let mut mocks = CallMocks::new();
+        mocks.insert(CallMock::new(
+            ORACLE,
+            Bytes::from_static(&[0x01]),
+            output.clone(),
+        ));
+        mocks.insert(CallMock::new(ORACLE, Bytes::new(), Bytes::from([0x00; 32])));
+
+        let result = MockHandler(mocks).run(&mut evm).unwrap();
+        let ExecutionResult::Success {
+            output: Output::Call(returned),
+            ..
+        } = result
+        else {
+            panic!("unexpected result {result:?}");
+        };
+        assert_eq!(returned, output);
+    }
+}
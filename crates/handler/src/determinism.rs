@@ -0,0 +1,265 @@
+//! Self-check that executes a transaction twice and compares the outcomes.
+use crate::ExecuteEvm;
+use context_interface::result::{ExecutionResult, ResultAndState};
+use core::fmt;
+use primitives::Address;
+use state::EvmState;
+use std::vec::Vec;
+
+/// Part of the outcome that differed between the two executions, see
+/// [`DeterminismCheckEvm::transact_checked`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Divergence {
+    /// Only one of the executions returned an error.
+    Error,
+    /// Executions ended differently, e.g. one succeeded and the other reverted, or they halted
+    /// with different reasons.
+    Status,
+    /// Gas used or refunded differs.
+    Gas,
+    /// Returned or reverted data differs.
+    Output,
+    /// Emitted logs differ.
+    Logs,
+    /// Changes of the account differ, or the account was loaded by only one of the executions.
+    Account(Address),
+}
+
+impl fmt::Display for Divergence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Error => write!(f, "only one execution failed"),
+            Self::Status => write!(f, "execution status differs"),
+            Self::Gas => write!(f, "gas differs"),
+            Self::Output => write!(f, "output differs"),
+            Self::Logs => write!(f, "logs differ"),
+            Self::Account(address) => write!(f, "changes of account {address} differ"),
+        }
+    }
+}
+
+/// Error of [`DeterminismCheckEvm::transact_checked`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DeterminismError<E> {
+    /// Both executions failed, the error of the first one.
+    Evm(E),
+    /// Executions diverged.
+    Nondeterministic(Vec<Divergence>),
+}
+
+impl<E: fmt::Display> fmt::Display for DeterminismError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Evm(error) => error.fmt(f),
+            Self::Nondeterministic(divergences) => {
+                write!(f, "nondeterministic execution: ")?;
+                for (i, divergence) in divergences.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    divergence.fmt(f)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl<E: fmt::Debug + fmt::Display> core::error::Error for DeterminismError<E> {}
+
+/// Debug mode that executes every transaction twice and compares the outcomes.
+///
+/// Execution of the EVM is deterministic, divergences are caused by extensions that keep state
+/// outside of the journal, e.g. a custom precompile with a counter or a database wrapper that
+/// returns different values for the same query. Both executions are finalized without committing,
+/// so they read the same state from the database.
+///
+/// Executing twice doubles the cost of every transaction, the check is meant for testing
+/// integrations and not for production.
+pub trait DeterminismCheckEvm: ExecuteEvm {
+    /// Halt reason of the execution result.
+    type HaltReason;
+
+    /// Executes the transaction twice and returns the outcome of the first execution if both
+    /// outcomes are equal.
+    ///
+    /// Compares the execution status, gas, output, logs and the changes of every account
+    /// including its storage.
+    fn transact_checked(
+        &mut self,
+        tx: Self::Tx,
+    ) -> Result<ResultAndState<Self::HaltReason>, DeterminismError<Self::Error>>;
+}
+
+impl<EVM, H> DeterminismCheckEvm for EVM
+where
+    EVM: ExecuteEvm<ExecutionResult = ExecutionResult<H>, State = EvmState>,
+    EVM::Tx: Clone,
+    H: PartialEq,
+{
+    type HaltReason = H;
+
+    fn transact_checked(
+        &mut self,
+        tx: Self::Tx,
+    ) -> Result<ResultAndState<H>, DeterminismError<Self::Error>> {
+        let first = self.transact(tx.clone());
+        let second = self.transact(tx);
+        match (first, second) {
+            (Ok(first), Ok(second)) => {
+                let divergences = divergences(&first, &second);
+                if divergences.is_empty() {
+                    Ok(first)
+                } else {
+                    Err(DeterminismError::Nondeterministic(divergences))
+                }
+            }
+            (Err(error), Err(_)) => Err(DeterminismError::Evm(error)),
+            _ => Err(DeterminismError::Nondeterministic(std::vec![
+                Divergence::Error
+            ])),
+        }
+    }
+}
+
+/// Returns the parts of the outcomes that differ, accounts are ordered by address.
+fn divergences<H: PartialEq>(
+    first: &ResultAndState<H>,
+    second: &ResultAndState<H>,
+) -> Vec<Divergence> {
+    let mut divergences = Vec::new();
+    let (a, b) = (&first.result, &second.result);
+    let same_status = match (a, b) {
+        (
+            ExecutionResult::Success { reason: a, .. },
+            ExecutionResult::Success { reason: b, .. },
+        ) => a == b,
+        (ExecutionResult::Revert { .. }, ExecutionResult::Revert { .. }) => true,
+        (ExecutionResult::Halt { reason: a, .. }, ExecutionResult::Halt { reason: b, .. }) => {
+            a == b
+        }
+        _ => false,
+    };
+    if !same_status {
+        divergences.push(Divergence::Status);
+    }
+    if a.gas() != b.gas() {
+        divergences.push(Divergence::Gas);
+    }
+    if a.output() != b.output() {
+        divergences.push(Divergence::Output);
+    }
+    if a.logs() != b.logs() {
+        divergences.push(Divergence::Logs);
+    }
+
+    let mut accounts: Vec<Address> = first
+        .state
+        .iter()
+        .filter(|(address, account)| second.state.get(*address) != Some(*account))
+        .map(|(address, _)| *address)
+        .chain(
+            second
+                .state
+                .keys()
+                .filter(|address| !first.state.contains_key(*address))
+                .copied(),
+        )
+        .collect();
+    accounts.sort_unstable();
+    divergences.extend(accounts.into_iter().map(Divergence::Account));
+    divergences
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{MainBuilder, MainContext};
+    use bytecode::{opcode, Bytecode};
+    use context::{Context, TxEnv};
+    use database::{BenchmarkDB, BENCH_CALLER, BENCH_TARGET};
+    use database_interface::Database;
+    use primitives::{StorageKey, StorageValue, TxKind, B256, U256};
+    use state::AccountInfo;
+
+    /// Returns a different value every time a slot is read.
+    struct CountingDB {
+        db: BenchmarkDB,
+        reads: u64,
+    }
+
+    impl Database for CountingDB {
+        type Error = <BenchmarkDB as Database>::Error;
+
+        fn basic(&mut self, address: Address) -> Result<Option<AccountInfo>, Self::Error> {
+            self.db.basic(address)
+        }
+
+        fn code_by_hash(&mut self, code_hash: B256) -> Result<Bytecode, Self::Error> {
+            self.db.code_by_hash(code_hash)
+        }
+
+        fn storage(
+            &mut self,
+            _address: Address,
+            _index: StorageKey,
+        ) -> Result<StorageValue, Self::Error> {
+            self.reads += 1;
+            Ok(U256::from(self.reads))
+        }
+
+        fn block_hash(&mut self, number: u64) -> Result<B256, Self::Error> {
+            self.db.block_hash(number)
+        }
+    }
+
+    fn tx() -> TxEnv {
+        TxEnv::builder()
+            .caller(BENCH_CALLER)
+            .kind(TxKind::Call(BENCH_TARGET))
+            .gas_limit(100_000)
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn deterministic_execution() {
+        let bytecode =
+            Bytecode::new_raw([opcode::PUSH0, opcode::SLOAD, opcode::STOP].to_vec().into());
+        let mut evm = Context::mainnet()
+            .with_db(BenchmarkDB::new_bytecode(bytecode))
+            .build_mainnet();
+        assert!(evm.transact_checked(tx()).unwrap().result.is_success());
+    }
+
+    #[test]
+    fn nondeterministic_database() {
+        // Returns the value of slot 0.
+        let bytecode = Bytecode::new_raw(
+            [
+                opcode::PUSH0,
+                opcode::SLOAD,
+                opcode::PUSH0,
+                opcode::MSTORE,
+                opcode::PUSH1,
+                0x20,
+                opcode::PUSH0,
+                opcode::RETURN,
+            ]
+            .to_vec()
+            .into(),
+        );
+        let db = CountingDB {
+            db: BenchmarkDB::new_bytecode(bytecode),
+            reads: 0,
+        };
+        let mut evm = Context::mainnet().with_db(db).build_mainnet();
+        assert_eq!(
+            evm.transact_checked(tx()),
+            Err(DeterminismError::Nondeterministic(vec![
+                Divergence::Output,
+                Divergence::Account(BENCH_TARGET),
+            ]))
+        );
+    }
+}
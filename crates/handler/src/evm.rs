@@ -3,7 +3,7 @@ use crate::{
     ItemOrResult, PrecompileProvider,
 };
 use auto_impl::auto_impl;
-use context::{ContextTr, Database, Evm, FrameStack};
+use context::{ContextTr, Database, Evm, FrameStack, JournalTr, LocalContextTr};
 use context_interface::context::ContextError;
 use interpreter::{interpreter::EthInterpreter, interpreter_action::FrameInit, InterpreterResult};
 
@@ -123,6 +123,20 @@ pub trait EvmTr {
         &mut self,
         result: <Self::Frame as FrameTr>::FrameResult,
     ) -> Result<Option<<Self::Frame as FrameTr>::FrameResult>, ContextDbError<Self::Context>>;
+
+    /// Prepares the EVM for an unrelated transaction without dropping any of its buffers.
+    ///
+    /// Reverts an unfinished transaction, drops the accounts loaded in the journal and clears
+    /// the local context, the context error and the frame stack, same as
+    /// [`Evm::reset_for_next_block`].
+    fn reset_for_next_block(&mut self) {
+        let ctx = self.ctx();
+        ctx.local_mut().clear();
+        ctx.journal_mut().discard_tx();
+        *ctx.error() = Ok(());
+        let _ = ctx.journal_mut().finalize();
+        self.frame_stack().clear();
+    }
 }
 
 impl<CTX, INSP, I, P> EvmTr for Evm<CTX, INSP, I, P, EthFrame<EthInterpreter>>
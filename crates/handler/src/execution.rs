@@ -53,6 +53,7 @@ pub fn create_init_frame<CTX: ContextTr>(
     let create_state_gas = params.create_state_gas();
     let warm_access_cost = params.warm_storage_read_cost();
     let cold_account_additional_cost = params.cold_account_additional_cost();
+    let create_address_fn = ctx.cfg().create_address_fn();
     let (tx, journal) = ctx.tx_journal_mut();
     let input = tx.input().clone();
 
@@ -130,7 +131,9 @@ pub fn create_init_frame<CTX: ContextTr>(
                 // The tx nonce was validated against the caller's nonce, which
                 // a create transaction bumps only at frame creation — after
                 // this point.
-                let created_address = tx.caller().create(tx.nonce());
+                let created_address = create_address_fn
+                    .and_then(|f| f(tx.caller(), CreateScheme::Create, tx.nonce(), &input))
+                    .unwrap_or_else(|| tx.caller().create(tx.nonce()));
                 let target_is_empty = journal.load_account(created_address)?.info.is_empty();
                 if target_is_empty {
                     if !gas.record_state_cost(create_state_gas) {
@@ -3,6 +3,8 @@ use crate::{
     CallFrame, CreateFrame, FrameData, FrameResult, ItemOrResult,
 };
 use context::result::FromStringError;
+#[cfg(feature = "counters")]
+use context_interface::LocalContextTr;
 use context_interface::{
     context::{take_error, ContextError},
     journaled_state::{account::JournaledAccountTr, JournalCheckpoint, JournalTr},
@@ -292,6 +294,8 @@ impl EthFrame<EthInterpreter> {
             return return_error(InstructionResult::CallTooDeep);
         }
 
+        let create_address_fn = context.cfg().create_address_fn();
+
         // Fetch balance of caller.
         let journal = context.journal_mut();
         let mut caller_info = journal.load_account_mut(inputs.caller())?;
@@ -309,8 +313,9 @@ impl EthFrame<EthInterpreter> {
         };
 
         // Create address — uses OnceCell cache so that if an inspector already called
-        // `created_address`, the expensive keccak256 is not recomputed.
-        let created_address = inputs.created_address(old_nonce);
+        // `created_address`, the expensive keccak256 is not recomputed. The configured
+        // override, if any, takes precedence.
+        let created_address = inputs.created_address_with(old_nonce, create_address_fn);
         let init_code_hash = matches!(inputs.scheme(), CreateScheme::Create2 { .. })
             .then(|| inputs.init_code_hash());
 
@@ -401,6 +406,16 @@ impl EthFrame<EthInterpreter> {
         context: &mut CTX,
         next_action: InterpreterAction,
     ) -> Result<FrameInitOrResult<Self>, ERROR> {
+        #[cfg(feature = "counters")]
+        {
+            let mut frame_counters = self.interpreter.take_counters();
+            if let Some(counters) = context.local_mut().counters_mut() {
+                frame_counters.calls =
+                    u64::from(matches!(next_action, InterpreterAction::NewFrame(_)));
+                *counters += frame_counters;
+            }
+        }
+
         // Run interpreter
 
         let mut interpreter_result = match next_action {
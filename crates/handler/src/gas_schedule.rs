@@ -0,0 +1,206 @@
+//! Effective gas costs of a spec and their differences between specs.
+use context_interface::cfg::{GasParamDiff, GasParams};
+use interpreter::instructions::{gas_table_spec, GasTable};
+use precompile::{PrecompileId, PrecompileSpecId, Precompiles};
+use primitives::{hardfork::SpecId, Address};
+use std::vec::Vec;
+
+/// Gas costs that are in effect for a spec.
+///
+/// Consists of the static gas of the opcodes, the dynamic gas parameters and the precompiles. Start
+/// from the mainnet schedule of a spec with [`GasSchedule::new`] and override the fields to
+/// describe a custom schedule.
+///
+/// # Example
+///
+/// ```
+/// use bytecode::opcode;
+/// use context_interface::cfg::GasId;
+/// use primitives::hardfork::SpecId;
+/// use revm_handler::gas_schedule::GasSchedule;
+///
+/// let prague = GasSchedule::new(SpecId::PRAGUE);
+/// let mut repriced = prague.clone();
+/// repriced.gas_table[opcode::SLOAD as usize] = 200;
+/// repriced.gas_params.override_gas([(GasId::logtopic(), 500)]);
+///
+/// let diff = prague.diff(&repriced);
+/// assert_eq!(diff.opcodes.len(), 1);
+/// assert_eq!(diff.params.len(), 1);
+/// assert!(diff.precompiles.is_empty());
+/// ```
+#[derive(Clone, Debug)]
+pub struct GasSchedule {
+    /// Static gas of the opcodes.
+    pub gas_table: GasTable,
+    /// Dynamic gas parameters.
+    pub gas_params: GasParams,
+    /// Precompiles, their pricing is part of the precompile functions.
+    pub precompiles: Precompiles,
+}
+
+impl GasSchedule {
+    /// Returns the mainnet gas schedule of the spec.
+    pub fn new(spec: SpecId) -> Self {
+        Self {
+            gas_table: gas_table_spec(spec),
+            gas_params: GasParams::new_spec(spec),
+            precompiles: Precompiles::new(PrecompileSpecId::from_spec_id(spec)).clone(),
+        }
+    }
+
+    /// Returns the differences of the gas costs in `other`.
+    pub fn diff(&self, other: &Self) -> GasScheduleDiff {
+        let opcodes = (0..=u8::MAX)
+            .filter_map(|opcode| {
+                let gas = (
+                    self.gas_table[opcode as usize],
+                    other.gas_table[opcode as usize],
+                );
+                (gas.0 != gas.1).then_some(OpcodeGasDiff { opcode, gas })
+            })
+            .collect();
+
+        let mut precompiles: Vec<_> = self
+            .precompiles
+            .inner()
+            .values()
+            .filter_map(|precompile| {
+                let address = *precompile.address();
+                match other.precompiles.get(&address) {
+                    None => Some(PrecompileDiff::Removed {
+                        address,
+                        id: precompile.id().clone(),
+                    }),
+                    Some(new) if !precompile.is_same(new) => Some(PrecompileDiff::Replaced {
+                        address,
+                        id: new.id().clone(),
+                    }),
+                    Some(_) => None,
+                }
+            })
+            .chain(
+                other
+                    .precompiles
+                    .inner()
+                    .values()
+                    .filter(|precompile| !self.precompiles.contains(precompile.address()))
+                    .map(|precompile| PrecompileDiff::Added {
+                        address: *precompile.address(),
+                        id: precompile.id().clone(),
+                    }),
+            )
+            .collect();
+        precompiles.sort_by_key(|diff| *diff.address());
+
+        GasScheduleDiff {
+            opcodes,
+            params: self.gas_params.diff(&other.gas_params),
+            precompiles,
+        }
+    }
+}
+
+/// Differences between two gas schedules, see [`GasSchedule::diff`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct GasScheduleDiff {
+    /// Opcodes whose static gas differs, ordered by opcode.
+    pub opcodes: Vec<OpcodeGasDiff>,
+    /// Gas parameters whose value differs, ordered by id.
+    pub params: Vec<GasParamDiff>,
+    /// Precompiles that were added, removed or replaced, ordered by address.
+    pub precompiles: Vec<PrecompileDiff>,
+}
+
+impl GasScheduleDiff {
+    /// Returns `true` if both schedules have the same gas costs.
+    pub fn is_empty(&self) -> bool {
+        self.opcodes.is_empty() && self.params.is_empty() && self.precompiles.is_empty()
+    }
+}
+
+/// Difference of the static gas of a single opcode.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct OpcodeGasDiff {
+    /// Opcode.
+    pub opcode: u8,
+    /// Static gas in the first and the second schedule.
+    pub gas: (u16, u16),
+}
+
+/// Difference of a single precompile.
+///
+/// Pricing of a precompile is implemented by its function, a replaced function usually means the
+/// precompile was repriced.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PrecompileDiff {
+    /// Precompile exists only in the second schedule.
+    Added {
+        /// Address of the precompile.
+        address: Address,
+        /// Identifier of the precompile.
+        id: PrecompileId,
+    },
+    /// Precompile exists only in the first schedule.
+    Removed {
+        /// Address of the precompile.
+        address: Address,
+        /// Identifier of the precompile.
+        id: PrecompileId,
+    },
+    /// Precompile is implemented by a different function in the second schedule.
+    Replaced {
+        /// Address of the precompile.
+        address: Address,
+        /// Identifier of the precompile in the second schedule.
+        id: PrecompileId,
+    },
+}
+
+impl PrecompileDiff {
+    /// Returns the address of the precompile.
+    pub const fn address(&self) -> &Address {
+        match self {
+            Self::Added { address, .. }
+            | Self::Removed { address, .. }
+            | Self::Replaced { address, .. } => address,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bytecode::opcode;
+    use context_interface::cfg::GasId;
+    use precompile::u64_to_address;
+
+    #[test]
+    fn diff_between_specs() {
+        let istanbul = GasSchedule::new(SpecId::ISTANBUL);
+        assert!(istanbul
+            .diff(&GasSchedule::new(SpecId::ISTANBUL))
+            .is_empty());
+
+        let diff = istanbul.diff(&GasSchedule::new(SpecId::BERLIN));
+        assert!(diff.opcodes.contains(&OpcodeGasDiff {
+            opcode: opcode::SLOAD,
+            gas: (800, 100),
+        }));
+        assert!(diff
+            .params
+            .iter()
+            .any(|diff| diff.id == GasId::cold_account_additional_cost()));
+        // EIP-2565 reprices modexp.
+        assert!(matches!(
+            diff.precompiles.as_slice(),
+            [PrecompileDiff::Replaced { address, .. }] if *address == u64_to_address(5)
+        ));
+
+        let diff = GasSchedule::new(SpecId::SHANGHAI).diff(&GasSchedule::new(SpecId::CANCUN));
+        assert!(diff.precompiles.contains(&PrecompileDiff::Added {
+            address: u64_to_address(0x0A),
+            id: PrecompileId::KzgPointEvaluation,
+        }));
+    }
+}
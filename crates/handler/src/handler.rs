@@ -202,6 +202,7 @@ pub trait Handler {
         self.validate_env(evm)?;
         let mut init_and_floor_gas = self.validate_initial_tx_gas(evm)?;
         self.validate_against_state_and_deduct_caller(evm, &mut init_and_floor_gas)?;
+        self.validate_custom(evm, &init_and_floor_gas)?;
         Ok(init_and_floor_gas)
     }
 
@@ -385,15 +386,37 @@ pub trait Handler {
     /// Includes additional costs for access list and authorization list.
     ///
     /// Verifies the initial cost does not exceed the transaction gas limit.
+    /// The intrinsic gas is computed by [`Handler::initial_tx_gas`].
     #[inline]
     fn validate_initial_tx_gas(
         &self,
         evm: &mut Self::Evm,
     ) -> Result<InitialAndFloorGas, Self::Error> {
+        let gas = self.initial_tx_gas(evm);
+        let ctx = evm.ctx_ref();
+        let gas = validation::validate_initial_and_floor_gas(
+            ctx.tx(),
+            ctx.cfg().spec().into(),
+            gas,
+            ctx.cfg().is_eip7623_disabled(),
+            ctx.cfg().is_amsterdam_eip8037_enabled(),
+            ctx.cfg().tx_gas_limit_cap(),
+        )?;
+
+        Ok(gas)
+    }
+
+    /// Computes the intrinsic gas of the transaction and the EIP-7623 floor gas.
+    ///
+    /// Uses the [`GasParams`](gas_params::GasParams) of the configuration by default. Chains
+    /// that price calldata differently or charge additional intrinsic components can override
+    /// it, the result is still validated against the transaction gas limit by
+    /// [`Handler::validate_initial_tx_gas`].
+    #[inline]
+    fn initial_tx_gas(&self, evm: &mut Self::Evm) -> InitialAndFloorGas {
         let ctx = evm.ctx_ref();
-        let is_amsterdam_eip2780_enabled = ctx.cfg().is_amsterdam_eip2780_enabled();
         let tx = ctx.tx();
-        let eip2780 = is_amsterdam_eip2780_enabled.then(|| {
+        let eip2780 = ctx.cfg().is_amsterdam_eip2780_enabled().then(|| {
             // Self-transfer: a `Call` whose recipient is the sender itself.
             let is_self_transfer = tx.kind().to() == Some(&tx.caller());
             gas_params::Eip2780TxInfo {
@@ -401,17 +424,26 @@ pub trait Handler {
                 is_self_transfer,
             }
         });
-        let gas = validation::validate_initial_tx_gas_with_gas_params(
-            tx,
-            ctx.cfg().spec().into(),
-            ctx.cfg().gas_params(),
-            ctx.cfg().is_eip7623_disabled(),
-            ctx.cfg().is_amsterdam_eip8037_enabled(),
-            ctx.cfg().tx_gas_limit_cap(),
-            eip2780,
-        )?;
+        ctx.cfg().gas_params().initial_tx_gas_for_tx(tx, eip2780)
+    }
 
-        Ok(gas)
+    /// Runs additional transaction validation rules after the standard validation.
+    ///
+    /// Does nothing by default. Override it to add rules such as sender allowlists, gas floor
+    /// policies or paymaster checks without reimplementing [`Handler::validate`]. Returning an
+    /// error rejects the transaction, custom error payloads can be created with
+    /// [`FromStringError::from_string`] or carried by the handler error type.
+    ///
+    /// The caller is already deducted when this runs, the changes are discarded by
+    /// [`Handler::catch_error`] if the transaction is rejected.
+    #[inline]
+    fn validate_custom(
+        &self,
+        evm: &mut Self::Evm,
+        init_and_floor_gas: &InitialAndFloorGas,
+    ) -> Result<(), Self::Error> {
+        let _ = (evm, init_and_floor_gas);
+        Ok(())
     }
 
     /* PRE EXECUTION */
@@ -444,13 +476,40 @@ pub trait Handler {
     /// added back in this case may exceed the maximum fee.
     ///
     /// Unused fees are returned to caller after execution completes.
+    ///
+    /// The nonce is validated before the balance, by [`Handler::validate_and_bump_nonce`] if it
+    /// handles the nonce.
     #[inline]
     fn validate_against_state_and_deduct_caller(
         &self,
         evm: &mut Self::Evm,
         _init_and_floor_gas: &mut InitialAndFloorGas,
     ) -> Result<(), Self::Error> {
-        pre_execution::validate_against_state_and_deduct_caller(evm.ctx())
+        if self.validate_and_bump_nonce(evm)? {
+            pre_execution::validate_caller_and_deduct_fee(evm.ctx())
+        } else {
+            pre_execution::validate_against_state_and_deduct_caller(evm.ctx())
+        }
+    }
+
+    /// Validates the transaction nonce and bumps the caller's nonce in place of the standard
+    /// nonce rules.
+    ///
+    /// Returns `true` if the nonce was handled. By default it returns `false` and the standard
+    /// rules apply: the transaction nonce must be equal to the caller's nonce (unless the nonce
+    /// check is disabled) and the nonce of call transactions is bumped, the nonce of create
+    /// transactions is bumped when the create frame is made, see
+    /// [`pre_execution::validate_and_bump_nonce`].
+    ///
+    /// Account abstraction schemes such as 2D nonces or nonces kept in a contract can
+    /// override it instead of disabling the nonce check globally. Implementors must bump the
+    /// nonce only for [`TxKind::Call`] transactions. The create frame still bumps the nonce of
+    /// create transactions and derives the contract address from the caller's nonce before the
+    /// bump, so for [`TxKind::Create`] the hook should leave the caller's nonce at the value the
+    /// address is derived from.
+    #[inline]
+    fn validate_and_bump_nonce(&self, _evm: &mut Self::Evm) -> Result<bool, Self::Error> {
+        Ok(false)
     }
 
     /* EXECUTION */
@@ -473,6 +532,10 @@ pub trait Handler {
         gas: &mut GasTracker,
     ) -> Result<Option<FrameInit>, Self::Error> {
         let ctx = evm.ctx_mut();
+        #[cfg(feature = "counters")]
+        if let Some(counters) = ctx.local_mut().counters_mut() {
+            *counters = Default::default();
+        }
         let mut memory = SharedMemory::new_with_buffer(ctx.local().shared_memory_buffer().clone());
         memory.set_memory_limit(ctx.cfg().memory_limit());
 
@@ -557,6 +620,9 @@ pub trait Handler {
         evm: &mut Self::Evm,
         first_frame_input: <<Self::Evm as EvmTr>::Frame as FrameTr>::FrameInit,
     ) -> Result<FrameResult, Self::Error> {
+        if let Some(frame_result) = self.frame_intercept(evm, &first_frame_input)? {
+            return Ok(frame_result);
+        }
         let res = evm.frame_init(first_frame_input)?;
 
         if let ItemOrResult::Result(frame_result) = res {
@@ -567,15 +633,17 @@ pub trait Handler {
             let call_or_result = evm.frame_run()?;
 
             let result = match call_or_result {
-                ItemOrResult::Item(init) => {
-                    match evm.frame_init(init)? {
+                ItemOrResult::Item(init) => match self.frame_intercept(evm, &init)? {
+                    // Do not pop the frame since no new frame was created
+                    Some(result) => result,
+                    None => match evm.frame_init(init)? {
                         ItemOrResult::Item(_) => {
                             continue;
                         }
                         // Do not pop the frame since no new frame was created
                         ItemOrResult::Result(result) => result,
-                    }
-                }
+                    },
+                },
                 ItemOrResult::Result(result) => result,
             };
 
@@ -585,6 +653,24 @@ pub trait Handler {
         }
     }
 
+    /// Called before a frame is created, returning a result short-circuits the frame.
+    ///
+    /// The returned result is handed to the parent frame as if the frame was executed: no
+    /// journal checkpoint is made, value is not transferred and the nonce of the creator is
+    /// not bumped. Gas of the result has to be derived from the gas limit of the frame input.
+    ///
+    /// Does nothing by default. Test frameworks can use it with [`CallMocks`](crate::CallMocks)
+    /// to mock calls to specific addresses.
+    #[inline]
+    fn frame_intercept(
+        &mut self,
+        evm: &mut Self::Evm,
+        frame_init: &FrameInit,
+    ) -> Result<Option<FrameResult>, Self::Error> {
+        let _ = (evm, frame_init);
+        Ok(None)
+    }
+
     /* POST EXECUTION */
 
     /// Validates that the minimum gas floor requirements are satisfied.
@@ -608,8 +694,10 @@ pub trait Handler {
         exec_result: &mut <<Self::Evm as EvmTr>::Frame as FrameTr>::FrameResult,
         eip7702_refund: i64,
     ) -> Result<(), Self::Error> {
-        post_execution::refund(
-            evm.ctx().cfg().gas_params(),
+        let cfg = evm.ctx().cfg();
+        post_execution::refund_with_policy(
+            cfg.refund_policy(),
+            cfg.gas_params(),
             exec_result.gas_mut(),
             eip7702_refund,
         );
@@ -629,13 +717,36 @@ pub trait Handler {
     }
 
     /// Transfers transaction fees to the block beneficiary's account.
+    ///
+    /// Calculates the fees with [`post_execution::tx_fees`] and hands them to
+    /// [`Handler::distribute_fees`]. Nothing is paid if fee charge is disabled.
     #[inline]
     fn reward_beneficiary(
         &self,
         evm: &mut Self::Evm,
         exec_result: &mut <<Self::Evm as EvmTr>::Frame as FrameTr>::FrameResult,
     ) -> Result<(), Self::Error> {
-        post_execution::reward_beneficiary(evm.ctx(), exec_result.gas()).map_err(From::from)
+        // If fee charge was disabled (e.g. eth_call simulations), the caller was
+        // never charged for gas so there are no fees to transfer to the beneficiary.
+        if evm.ctx_ref().cfg().is_fee_charge_disabled() {
+            return Ok(());
+        }
+        let fees = post_execution::tx_fees(evm.ctx_ref(), exec_result.gas());
+        self.distribute_fees(evm, fees)
+    }
+
+    /// Distributes the transaction fees.
+    ///
+    /// By default the priority fee is paid to the block beneficiary and the base fee is burned.
+    /// Chains can override it to redirect the base fee to a vault instead of burning it, split
+    /// the fees between multiple accounts or skip the beneficiary payment.
+    #[inline]
+    fn distribute_fees(
+        &self,
+        evm: &mut Self::Evm,
+        fees: post_execution::TxFees,
+    ) -> Result<(), Self::Error> {
+        post_execution::distribute_fees(evm.ctx(), fees).map_err(From::from)
     }
 
     /// Processes the final execution output.
@@ -678,3 +789,293 @@ pub trait Handler {
         Err(error)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ExecuteEvm, MainBuilder, MainContext, MainnetContext, MainnetEvm};
+    use context::journaled_state::account::JournaledAccountTr;
+    use context::{
+        result::{EVMError, HaltReason},
+        Context, TxEnv,
+    };
+    use context_interface::ContextSetters;
+    use core::convert::Infallible;
+    use database::{BenchmarkDB, BENCH_CALLER, BENCH_TARGET};
+    use primitives::Address;
+    use state::Bytecode;
+
+    type TestEvm = MainnetEvm<MainnetContext<BenchmarkDB>>;
+
+    /// Handler that overrides the hooks under test.
+    #[derive(Default)]
+    struct TestHandler {
+        /// Callers rejected by [`Handler::validate_custom`].
+        denied: Option<Address>,
+        /// Gas added to the intrinsic gas by [`Handler::initial_tx_gas`].
+        extra_intrinsic_gas: u64,
+        /// Receiver of the base fee in [`Handler::distribute_fees`].
+        fee_vault: Option<Address>,
+        /// Allows nonce gaps in [`Handler::validate_and_bump_nonce`].
+        allow_nonce_gaps: bool,
+    }
+
+    impl Handler for TestHandler {
+        type Evm = TestEvm;
+        type Error = EVMError<Infallible>;
+        type HaltReason = HaltReason;
+
+        fn validate_custom(
+            &self,
+            evm: &mut Self::Evm,
+            _init_and_floor_gas: &InitialAndFloorGas,
+        ) -> Result<(), Self::Error> {
+            if self.denied == Some(evm.ctx().tx().caller()) {
+                return Err(Self::Error::from_string("caller is not allowed".into()));
+            }
+            Ok(())
+        }
+
+        fn initial_tx_gas(&self, evm: &mut Self::Evm) -> InitialAndFloorGas {
+            let ctx = evm.ctx_ref();
+            let gas = ctx.cfg().gas_params().initial_tx_gas_for_tx(ctx.tx(), None);
+            gas.with_initial_regular_gas(gas.initial_regular_gas() + self.extra_intrinsic_gas)
+        }
+
+        fn distribute_fees(
+            &self,
+            evm: &mut Self::Evm,
+            fees: post_execution::TxFees,
+        ) -> Result<(), Self::Error> {
+            post_execution::distribute_fees(evm.ctx(), fees)?;
+            if let Some(vault) = self.fee_vault {
+                evm.ctx()
+                    .journal_mut()
+                    .load_account_mut(vault)?
+                    .incr_balance(fees.base_fee);
+            }
+            Ok(())
+        }
+
+        fn validate_and_bump_nonce(&self, evm: &mut Self::Evm) -> Result<bool, Self::Error> {
+            if !self.allow_nonce_gaps {
+                return Ok(false);
+            }
+            let (_, tx, _, journal, _, _) = evm.ctx().all_mut();
+            let mut caller = journal.load_account_mut(tx.caller())?.data;
+            if tx.nonce() < caller.account().info.nonce {
+                return Err(InvalidTransaction::NonceTooLow {
+                    tx: tx.nonce(),
+                    state: caller.account().info.nonce,
+                }
+                .into());
+            }
+            // The create frame bumps the nonce of create transactions.
+            let bump = u64::from(tx.kind().is_call());
+            caller.set_nonce(tx.nonce() + bump);
+            Ok(true)
+        }
+    }
+
+    fn test_evm() -> TestEvm {
+        let mut evm = Context::mainnet()
+            .with_db(BenchmarkDB::new_bytecode(Bytecode::new()))
+            .build_mainnet();
+        // Tests run several transactions from the same caller.
+        evm.ctx.cfg.disable_nonce_check = true;
+        evm
+    }
+
+    fn run(
+        handler: &mut TestHandler,
+        evm: &mut TestEvm,
+    ) -> Result<ExecutionResult, EVMError<Infallible>> {
+        run_with_nonce(handler, evm, 0)
+    }
+
+    fn run_with_nonce(
+        handler: &mut TestHandler,
+        evm: &mut TestEvm,
+        nonce: u64,
+    ) -> Result<ExecutionResult, EVMError<Infallible>> {
+        let basefee = evm.ctx.block.basefee as u128;
+        evm.ctx().set_tx(
+            TxEnv::builder()
+                .caller(BENCH_CALLER)
+                .kind(TxKind::Call(BENCH_TARGET))
+                .nonce(nonce)
+                .gas_limit(100_000)
+                .gas_price(basefee + 1)
+                .build()
+                .unwrap(),
+        );
+        handler.run(evm)
+    }
+
+    #[test]
+    fn custom_validation_rejects_tx() {
+        let mut evm = test_evm();
+        let mut handler = TestHandler {
+            denied: Some(BENCH_CALLER),
+            ..Default::default()
+        };
+        assert_eq!(
+            run(&mut handler, &mut evm),
+            Err(EVMError::Custom("caller is not allowed".into()))
+        );
+
+        handler.denied = None;
+        assert!(run(&mut handler, &mut evm).unwrap().is_success());
+    }
+
+    #[test]
+    fn intrinsic_gas_override() {
+        let mut evm = test_evm();
+        let mut handler = TestHandler::default();
+        let base = run(&mut handler, &mut evm).unwrap().tx_gas_used();
+
+        handler.extra_intrinsic_gas = 1_000;
+        let result = run(&mut handler, &mut evm).unwrap();
+        assert_eq!(result.tx_gas_used(), base + 1_000);
+
+        handler.extra_intrinsic_gas = 100_000;
+        assert!(matches!(
+            run(&mut handler, &mut evm),
+            Err(EVMError::Transaction(
+                InvalidTransaction::CallGasCostMoreThanGasLimit { .. }
+            ))
+        ));
+    }
+
+    #[test]
+    fn base_fee_redirected_to_vault() {
+        let vault = Address::with_last_byte(0xaa);
+        let mut evm = test_evm();
+        evm.ctx.block.basefee = 7;
+        let mut handler = TestHandler {
+            fee_vault: Some(vault),
+            ..Default::default()
+        };
+        let gas_used = run(&mut handler, &mut evm).unwrap().tx_gas_used();
+
+        let state = evm.finalize();
+        assert_eq!(state[&vault].info.balance, U256::from(7 * gas_used));
+        assert_eq!(
+            state[&evm.ctx.block.beneficiary].info.balance,
+            U256::from(gas_used)
+        );
+    }
+
+    #[test]
+    fn nonce_override() {
+        let mut evm = test_evm();
+        evm.ctx.cfg.disable_nonce_check = false;
+        let mut handler = TestHandler::default();
+        assert_eq!(
+            run_with_nonce(&mut handler, &mut evm, 5),
+            Err(EVMError::Transaction(InvalidTransaction::NonceTooHigh {
+                tx: 5,
+                state: 0
+            }))
+        );
+
+        // Nonce is validated before the balance.
+        evm.ctx().set_tx(
+            TxEnv::builder()
+                .caller(BENCH_CALLER)
+                .kind(TxKind::Call(BENCH_TARGET))
+                .nonce(5)
+                .value(U256::MAX)
+                .gas_limit(100_000)
+                .build()
+                .unwrap(),
+        );
+        assert_eq!(
+            handler.run(&mut evm),
+            Err(EVMError::Transaction(InvalidTransaction::NonceTooHigh {
+                tx: 5,
+                state: 0
+            }))
+        );
+
+        handler.allow_nonce_gaps = true;
+        assert!(run_with_nonce(&mut handler, &mut evm, 5)
+            .unwrap()
+            .is_success());
+        let state = evm.finalize();
+        assert_eq!(state[&BENCH_CALLER].info.nonce, 6);
+    }
+
+    #[test]
+    fn nonce_override_create() {
+        let mut evm = test_evm();
+        evm.ctx.cfg.disable_nonce_check = false;
+        let mut handler = TestHandler {
+            allow_nonce_gaps: true,
+            ..Default::default()
+        };
+        let basefee = evm.ctx.block.basefee as u128;
+        evm.ctx().set_tx(
+            TxEnv::builder()
+                .caller(BENCH_CALLER)
+                .kind(TxKind::Create)
+                .nonce(5)
+                .gas_limit(100_000)
+                .gas_price(basefee + 1)
+                .build()
+                .unwrap(),
+        );
+        let result = handler.run(&mut evm).unwrap();
+        assert_eq!(result.created_address(), Some(BENCH_CALLER.create(5)));
+        let state = evm.finalize();
+        assert_eq!(state[&BENCH_CALLER].info.nonce, 6);
+    }
+
+    #[cfg(feature = "counters")]
+    #[test]
+    fn execution_counters() {
+        use context_interface::ExecutionCounters;
+        use state::bytecode::opcode;
+
+        let bytecode = Bytecode::new_raw(
+            [
+                opcode::PUSH1,
+                0x01,
+                opcode::PUSH1,
+                0x00,
+                opcode::SSTORE,
+                opcode::PUSH1,
+                0x00,
+                opcode::SLOAD,
+                opcode::PUSH1,
+                0x00,
+                opcode::MSTORE,
+                opcode::STOP,
+            ]
+            .to_vec()
+            .into(),
+        );
+        let mut evm = Context::mainnet()
+            .with_db(BenchmarkDB::new_bytecode(bytecode))
+            .build_mainnet();
+        let tx = TxEnv::builder()
+            .caller(BENCH_CALLER)
+            .kind(TxKind::Call(BENCH_TARGET))
+            .gas_limit(100_000)
+            .build()
+            .unwrap();
+        assert!(evm.transact_one(tx).unwrap().is_success());
+
+        assert_eq!(
+            evm.ctx.local.counters,
+            ExecutionCounters {
+                instructions: 8,
+                memory_expansions: 1,
+                sloads: 1,
+                sstores: 1,
+                cold_storage_loads: 1,
+                ..Default::default()
+            }
+        );
+    }
+}
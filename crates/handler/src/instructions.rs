@@ -1,10 +1,10 @@
 use auto_impl::auto_impl;
 use interpreter::{
     instructions::{gas_table_spec, GasTable, InstructionTable},
-    Host, Instruction, InterpreterTypes,
+    Host, Instruction, InstructionContext, InstructionExecResult, InterpreterTypes,
 };
 use primitives::hardfork::SpecId;
-use std::boxed::Box;
+use std::{boxed::Box, vec::Vec};
 
 /// Stores instructions for EVM.
 #[auto_impl(&mut, Box)]
@@ -135,6 +135,161 @@ where
     pub fn gas_table_mut(&mut self) -> &mut GasTable {
         &mut self.inner.gas_table
     }
+    /// Returns the opcodes whose instruction or static gas differ in `other`.
+    pub fn diff(&self, other: &Self) -> Vec<InstructionDiff> {
+        (0..=u8::MAX)
+            .filter_map(|opcode| {
+                let i = opcode as usize;
+                let instruction_changed =
+                    !self.inner.instruction_table[i].is_same(other.inner.instruction_table[i]);
+                let gas = (self.inner.gas_table[i], other.inner.gas_table[i]);
+                (instruction_changed || gas.0 != gas.1).then_some(InstructionDiff {
+                    opcode,
+                    instruction_changed,
+                    gas,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Difference of a single opcode between two instruction tables, see [`EthInstructions::diff`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct InstructionDiff {
+    /// Opcode.
+    pub opcode: u8,
+    /// Whether the opcode is implemented by a different function.
+    pub instruction_changed: bool,
+    /// Static gas in the first and the second table.
+    pub gas: (u16, u16),
+}
+
+/// Set of instruction overrides applied by [`InstructionTableBuilder::apply`].
+///
+/// Implemented for closures taking the builder, they can capture configuration and decide
+/// which instructions to install. Instructions are function pointers and can't capture it
+/// themselves, configuration needed during execution has to be read from the host.
+pub trait InstructionOverrides<WIRE: InterpreterTypes, HOST: Host> {
+    /// Applies the overrides to the builder.
+    fn apply(&self, builder: &mut InstructionTableBuilder<WIRE, HOST>);
+}
+
+impl<WIRE, HOST, F> InstructionOverrides<WIRE, HOST> for F
+where
+    WIRE: InterpreterTypes,
+    HOST: Host,
+    F: Fn(&mut InstructionTableBuilder<WIRE, HOST>),
+{
+    fn apply(&self, builder: &mut InstructionTableBuilder<WIRE, HOST>) {
+        self(builder)
+    }
+}
+
+/// Builder of [`EthInstructions`] that starts from the mainnet instructions of a spec.
+///
+/// # Example
+///
+/// ```
+/// use bytecode::opcode;
+/// use interpreter::{host::DummyHost, instructions::control, interpreter::EthInterpreter};
+/// use primitives::hardfork::SpecId;
+/// use revm_handler::instructions::InstructionTableBuilder;
+///
+/// let disable_selfdestruct = true;
+/// let instructions = InstructionTableBuilder::<EthInterpreter, DummyHost>::new(SpecId::PRAGUE)
+///     .apply(|b: &mut InstructionTableBuilder<_, _>| {
+///         if disable_selfdestruct {
+///             b.disable(opcode::SELFDESTRUCT);
+///         }
+///     })
+///     .with_fn(opcode::BASEFEE, control::stop, 0)
+///     .build();
+/// assert_eq!(instructions.gas_table()[opcode::BASEFEE as usize], 0);
+/// ```
+#[derive(Debug)]
+pub struct InstructionTableBuilder<WIRE: InterpreterTypes, HOST: Host> {
+    instructions: EthInstructions<WIRE, HOST>,
+}
+
+impl<WIRE: InterpreterTypes, HOST: Host> Clone for InstructionTableBuilder<WIRE, HOST> {
+    fn clone(&self) -> Self {
+        Self {
+            instructions: self.instructions.clone(),
+        }
+    }
+}
+
+impl<WIRE: InterpreterTypes, HOST: Host> InstructionTableBuilder<WIRE, HOST> {
+    /// Starts from the mainnet instructions and static gas of the spec.
+    pub fn new(spec: SpecId) -> Self {
+        Self::from_instructions(EthInstructions::new_mainnet_with_spec(spec))
+    }
+
+    /// Starts from existing instructions.
+    pub fn from_instructions(instructions: EthInstructions<WIRE, HOST>) -> Self {
+        Self { instructions }
+    }
+
+    /// Sets the instruction and static gas of the opcode.
+    pub fn set(&mut self, opcode: u8, instruction: Instruction<WIRE, HOST>, gas: u16) -> &mut Self {
+        self.instructions
+            .insert_instruction(opcode, instruction, gas);
+        self
+    }
+
+    /// Sets the instruction function and static gas of the opcode.
+    pub fn set_fn(
+        &mut self,
+        opcode: u8,
+        f: fn(InstructionContext<'_, HOST, WIRE>) -> InstructionExecResult,
+        gas: u16,
+    ) -> &mut Self {
+        self.set(opcode, Instruction::new(f), gas)
+    }
+
+    /// Sets the static gas of the opcode.
+    pub fn set_gas(&mut self, opcode: u8, gas: u16) -> &mut Self {
+        self.instructions.insert_gas(opcode, gas);
+        self
+    }
+
+    /// Makes the opcode invalid.
+    pub fn disable(&mut self, opcode: u8) -> &mut Self {
+        self.set(opcode, Instruction::unknown(), 0)
+    }
+
+    /// Sets the instruction and static gas of the opcode.
+    pub fn with(mut self, opcode: u8, instruction: Instruction<WIRE, HOST>, gas: u16) -> Self {
+        self.set(opcode, instruction, gas);
+        self
+    }
+
+    /// Sets the instruction function and static gas of the opcode.
+    pub fn with_fn(
+        mut self,
+        opcode: u8,
+        f: fn(InstructionContext<'_, HOST, WIRE>) -> InstructionExecResult,
+        gas: u16,
+    ) -> Self {
+        self.set_fn(opcode, f, gas);
+        self
+    }
+
+    /// Applies a set of overrides, later sets override earlier ones.
+    pub fn apply(mut self, overrides: impl InstructionOverrides<WIRE, HOST>) -> Self {
+        overrides.apply(&mut self);
+        self
+    }
+
+    /// Returns the instructions built so far.
+    pub fn instructions(&self) -> &EthInstructions<WIRE, HOST> {
+        &self.instructions
+    }
+
+    /// Builds the instructions.
+    pub fn build(self) -> EthInstructions<WIRE, HOST> {
+        self.instructions
+    }
 }
 
 impl<IT, CTX> InstructionProvider for EthInstructions<IT, CTX>
@@ -155,3 +310,53 @@ where
         self.gas_table()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bytecode::opcode;
+    use interpreter::{host::DummyHost, instructions::control, interpreter::EthInterpreter};
+
+    type Builder = InstructionTableBuilder<EthInterpreter, DummyHost>;
+
+    #[test]
+    fn builder_overrides_and_diff() {
+        let mainnet = Builder::new(SpecId::PRAGUE).build();
+
+        let gas = 42;
+        let repriced = |b: &mut Builder| {
+            b.set_gas(opcode::ADD, gas);
+        };
+        let custom = Builder::new(SpecId::PRAGUE)
+            .apply(repriced)
+            .apply(|b: &mut Builder| {
+                b.disable(opcode::SELFDESTRUCT)
+                    .set_fn(opcode::BASEFEE, control::stop, 1);
+            })
+            .build();
+
+        assert_eq!(
+            mainnet.diff(&custom),
+            [
+                InstructionDiff {
+                    opcode: opcode::ADD,
+                    instruction_changed: false,
+                    gas: (3, 42),
+                },
+                InstructionDiff {
+                    opcode: opcode::BASEFEE,
+                    instruction_changed: true,
+                    gas: (2, 1),
+                },
+                InstructionDiff {
+                    opcode: opcode::SELFDESTRUCT,
+                    instruction_changed: true,
+                    gas: (5000, 0),
+                },
+            ]
+        );
+        assert!(mainnet
+            .diff(&Builder::new(SpecId::PRAGUE).build())
+            .is_empty());
+    }
+}
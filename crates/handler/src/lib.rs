@@ -9,12 +9,18 @@ extern crate alloc as std;
 
 /// EVM execution API traits and implementations.
 pub mod api;
+/// Call mocking through the frame interception hook.
+pub mod call_mock;
+/// Debug mode that executes transactions twice and compares the outcomes.
+pub mod determinism;
 /// Core EVM traits for execution and frame management.
 pub mod evm;
 /// EVM execution logic and utilities.
 pub mod execution;
 mod frame;
 mod frame_data;
+/// Effective gas costs of a spec and their differences between specs.
+pub mod gas_schedule;
 /// Handler implementation for orchestrating EVM execution.
 pub mod handler;
 /// EVM instruction set implementations and tables.
@@ -22,10 +28,15 @@ pub mod instructions;
 mod item_or_result;
 mod mainnet_builder;
 mod mainnet_handler;
+/// Pool of reusable EVM instances.
+#[cfg(feature = "std")]
+pub mod pool;
 /// Post-execution operations including gas refunds and state finalization.
 pub mod post_execution;
 pub mod pre_execution;
 mod precompile_provider;
+/// Pull-based execution of a transaction one instruction at a time.
+pub mod step_iter;
 /// System call implementations for special EVM operations.
 pub mod system_call;
 /// Transaction and environment validation utilities.
@@ -44,6 +55,8 @@ pub use state;
 #[cfg(feature = "asyncdb")]
 pub use api::ExecuteEvmAsync;
 pub use api::{ExecuteCommitEvm, ExecuteEvm};
+pub use call_mock::{CallMock, CallMocks};
+pub use determinism::{DeterminismCheckEvm, DeterminismError, Divergence};
 pub use evm::{EvmTr, FrameTr};
 pub use frame::{handle_reservoir_remaining_gas, return_create, ContextTrDbError, EthFrame};
 pub use frame_data::{CallFrame, CreateFrame, FrameData, FrameResult};
@@ -51,10 +64,13 @@ pub use handler::{EvmTrError, Handler};
 pub use item_or_result::{FrameInitOrResult, ItemOrResult};
 pub use mainnet_builder::{MainBuilder, MainContext, MainnetContext, MainnetEvm};
 pub use mainnet_handler::MainnetHandler;
+#[cfg(feature = "std")]
+pub use pool::{EvmPool, PooledEvm};
 pub use pre_execution::PreExecutionOutput;
 pub use precompile_provider::{
-    precompile_output_to_interpreter_result, EthPrecompiles, PrecompileProvider,
+    precompile_output_to_interpreter_result, EthPrecompiles, PrecompileProvider, TablePrecompiles,
 };
+pub use step_iter::{Step, StepEvm, StepIter};
 #[cfg(feature = "asyncdb")]
 pub use system_call::SystemCallEvmAsync;
 pub use system_call::{SystemCallCommitEvm, SystemCallEvm, SystemCallTx, SYSTEM_ADDRESS};
@@ -0,0 +1,182 @@
+//! Pool of reusable EVM instances, see [`EvmPool`].
+use crate::EvmTr;
+use core::{
+    fmt,
+    ops::{Deref, DerefMut},
+};
+use std::{
+    boxed::Box,
+    sync::{Mutex, PoisonError},
+    vec::Vec,
+};
+
+/// Resets the EVM so it can execute an unrelated transaction.
+///
+/// Loaded state and logs are dropped from the journal, the local context, the frame stack and
+/// the context error are cleared, see [`EvmTr::reset_for_next_block`]. Allocated buffers and
+/// analyzed bytecode caches are kept.
+pub fn reset_evm<E: EvmTr>(evm: &mut E) {
+    evm.reset_for_next_block();
+}
+
+/// Thread-safe pool of pre-built EVM instances.
+///
+/// Services that simulate many transactions concurrently can check out an EVM per request with
+/// [`EvmPool::get`] instead of building a new one, which avoids reallocating interpreter
+/// buffers and rebuilding instruction and precompile tables. The EVM is reset with
+/// [`reset_evm`] and returned to the pool when the [`PooledEvm`] is dropped.
+///
+/// The pool can be shared between threads when the EVM is `Send`. Note that the default
+/// [`LocalContext`](context::LocalContext) holds its memory buffer in an `Rc`, so EVMs using it
+/// can only be pooled per thread.
+pub struct EvmPool<E> {
+    /// EVMs that are ready to be checked out.
+    idle: Mutex<Vec<E>>,
+    /// Builds a new EVM when the pool is empty.
+    build: Box<dyn Fn() -> E + Send + Sync>,
+    /// Maximum number of idle EVMs kept by the pool.
+    max_idle: usize,
+}
+
+impl<E> fmt::Debug for EvmPool<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EvmPool")
+            .field("idle", &self.idle())
+            .field("max_idle", &self.max_idle)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<E: EvmTr> EvmPool<E> {
+    /// Creates an empty pool that builds EVMs on demand with `build`.
+    pub fn new(build: impl Fn() -> E + Send + Sync + 'static) -> Self {
+        Self {
+            idle: Mutex::new(Vec::new()),
+            build: Box::new(build),
+            max_idle: usize::MAX,
+        }
+    }
+
+    /// Creates a pool with `count` pre-built EVMs.
+    pub fn with_prebuilt(count: usize, build: impl Fn() -> E + Send + Sync + 'static) -> Self {
+        let pool = Self::new(build);
+        pool.lock().extend((0..count).map(|_| (pool.build)()));
+        pool
+    }
+
+    /// Sets the maximum number of idle EVMs kept by the pool, EVMs returned to a full pool
+    /// are dropped.
+    pub fn with_max_idle(mut self, max_idle: usize) -> Self {
+        self.max_idle = max_idle;
+        self.lock().truncate(max_idle);
+        self
+    }
+
+    /// Returns the number of idle EVMs.
+    pub fn idle(&self) -> usize {
+        self.lock().len()
+    }
+
+    /// Checks out an idle EVM, or builds a new one if the pool is empty.
+    pub fn get(&self) -> PooledEvm<'_, E> {
+        let evm = self.lock().pop().unwrap_or_else(|| (self.build)());
+        PooledEvm {
+            evm: Some(evm),
+            pool: self,
+        }
+    }
+
+    /// Resets the EVM and returns it to the pool.
+    fn release(&self, mut evm: E) {
+        reset_evm(&mut evm);
+        let mut idle = self.lock();
+        if idle.len() < self.max_idle {
+            idle.push(evm);
+        }
+    }
+
+    /// Locks the idle EVMs, a poisoned lock is recovered as the EVMs are always reset.
+    fn lock(&self) -> std::sync::MutexGuard<'_, Vec<E>> {
+        self.idle.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// EVM checked out from an [`EvmPool`], it is returned to the pool when dropped.
+pub struct PooledEvm<'a, E: EvmTr> {
+    /// Always `Some` until dropped or detached.
+    evm: Option<E>,
+    pool: &'a EvmPool<E>,
+}
+
+impl<E: EvmTr + fmt::Debug> fmt::Debug for PooledEvm<'_, E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("PooledEvm").field(&self.evm).finish()
+    }
+}
+
+impl<E: EvmTr> PooledEvm<'_, E> {
+    /// Takes the EVM out of the pool for good.
+    pub fn detach(mut self) -> E {
+        self.evm.take().expect("EVM is present until dropped")
+    }
+}
+
+impl<E: EvmTr> Deref for PooledEvm<'_, E> {
+    type Target = E;
+
+    fn deref(&self) -> &Self::Target {
+        self.evm.as_ref().expect("EVM is present until dropped")
+    }
+}
+
+impl<E: EvmTr> DerefMut for PooledEvm<'_, E> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.evm.as_mut().expect("EVM is present until dropped")
+    }
+}
+
+impl<E: EvmTr> Drop for PooledEvm<'_, E> {
+    fn drop(&mut self) {
+        if let Some(evm) = self.evm.take() {
+            self.pool.release(evm);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ExecuteEvm, MainBuilder, MainContext, MainnetContext, MainnetEvm};
+    use context::{Context, TxEnv};
+    use database::BenchmarkDB;
+    use state::Bytecode;
+
+    fn build() -> MainnetEvm<MainnetContext<BenchmarkDB>> {
+        Context::mainnet()
+            .with_db(BenchmarkDB::new_bytecode(Bytecode::new()))
+            .build_mainnet()
+    }
+
+    #[test]
+    fn evms_are_reused() {
+        let pool = EvmPool::with_prebuilt(1, build).with_max_idle(1);
+        assert_eq!(pool.idle(), 1);
+
+        let tx = || TxEnv::builder_for_bench().build_fill();
+        {
+            let mut first = pool.get();
+            let mut second = pool.get();
+            assert_eq!(pool.idle(), 0);
+            assert!(first.transact(tx()).unwrap().result.is_success());
+            assert!(second.transact(tx()).unwrap().result.is_success());
+        }
+        // Only one EVM is kept.
+        assert_eq!(pool.idle(), 1);
+
+        let mut evm = pool.get();
+        assert!(evm.ctx.journaled_state.state.is_empty());
+        assert!(evm.transact(tx()).unwrap().result.is_success());
+        let _ = evm.detach();
+        assert_eq!(pool.idle(), 0);
+    }
+}
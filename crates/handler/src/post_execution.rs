@@ -1,7 +1,7 @@
 use crate::FrameResult;
 use context::journaled_state::account::JournaledAccountTr;
 use context_interface::{
-    cfg::GasParams,
+    cfg::{GasParams, RefundPolicy},
     journaled_state::JournalTr,
     result::{ExecutionResult, HaltReason, HaltReasonTr, ResultGas},
     Block, Cfg, ContextTr, Database, LocalContextTr, Transaction,
@@ -59,8 +59,24 @@ pub const fn eip7623_check_gas_floor(gas: &mut Gas, init_and_floor_gas: InitialA
 
 /// Calculates and applies gas refunds based on the configured gas parameters.
 pub fn refund(gas_params: &GasParams, gas: &mut Gas, eip7702_refund: i64) {
+    refund_with_policy(RefundPolicy::GasParams, gas_params, gas, eip7702_refund);
+}
+
+/// Calculates and applies gas refunds based on the refund policy.
+///
+/// If refunds are disabled, the refund counter is cleared and the EIP-7702 refund is dropped.
+pub fn refund_with_policy(
+    policy: RefundPolicy,
+    gas_params: &GasParams,
+    gas: &mut Gas,
+    eip7702_refund: i64,
+) {
+    let Some(max_refund_quotient) = policy.max_refund_quotient(gas_params) else {
+        gas.set_refund(0);
+        return;
+    };
     gas.record_refund(eip7702_refund);
-    gas.set_final_refund(gas_params.max_refund_quotient());
+    gas.set_final_refund(max_refund_quotient);
 }
 
 /// Reimburses the caller for unused gas.
@@ -93,6 +109,41 @@ pub fn reimburse_caller<CTX: ContextTr>(
     Ok(())
 }
 
+/// Transaction fees distributed at the end of the transaction.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct TxFees {
+    /// Gas paid for, excluding the EIP-8037 reservoir.
+    pub gas_used: u64,
+    /// Fee paid to the beneficiary, the priority fee after London and the whole fee before.
+    pub priority_fee: U256,
+    /// Base fee that is burned by EIP-1559, zero before London.
+    pub base_fee: U256,
+}
+
+/// Calculates the fees paid by the transaction for the used gas.
+#[inline]
+pub fn tx_fees<CTX: ContextTr>(context: &CTX, gas: &Gas) -> TxFees {
+    let basefee = context.block().basefee() as u128;
+    let effective_gas_price = context.tx().effective_gas_price(basefee);
+
+    // EIP-1559 discard basefee for coinbase transfer. Basefee amount of gas is discarded.
+    let (coinbase_gas_price, burned_gas_price) =
+        if context.cfg().spec().into().is_enabled_in(SpecId::LONDON) {
+            let coinbase_gas_price = effective_gas_price.saturating_sub(basefee);
+            (coinbase_gas_price, effective_gas_price - coinbase_gas_price)
+        } else {
+            (effective_gas_price, 0)
+        };
+
+    // Exclude reservoir gas (EIP-8037) from the used gas — reservoir is unused and reimbursed.
+    let gas_used = gas.used().saturating_sub(gas.reservoir());
+    TxFees {
+        gas_used,
+        priority_fee: U256::from(coinbase_gas_price) * U256::from(gas_used),
+        base_fee: U256::from(burned_gas_price) * U256::from(gas_used),
+    }
+}
+
 /// Rewards the beneficiary with transaction fees.
 #[inline]
 pub fn reward_beneficiary<CTX: ContextTr>(
@@ -104,24 +155,20 @@ pub fn reward_beneficiary<CTX: ContextTr>(
     if context.cfg().is_fee_charge_disabled() {
         return Ok(());
     }
-    let (block, tx, cfg, journal, _, _) = context.all_mut();
-    let basefee = block.basefee() as u128;
-    let effective_gas_price = tx.effective_gas_price(basefee);
-
-    // Transfer fee to coinbase/beneficiary.
-    // EIP-1559 discard basefee for coinbase transfer. Basefee amount of gas is discarded.
-    let coinbase_gas_price = if cfg.spec().into().is_enabled_in(SpecId::LONDON) {
-        effective_gas_price.saturating_sub(basefee)
-    } else {
-        effective_gas_price
-    };
+    let fees = tx_fees(context, gas);
+    distribute_fees(context, fees)
+}
 
-    // Reward beneficiary.
-    // Exclude reservoir gas (EIP-8037) from the used gas — reservoir is unused and reimbursed.
-    let effective_used = gas.used().saturating_sub(gas.reservoir());
+/// Pays the priority fee to the block beneficiary, the base fee is burned.
+#[inline]
+pub fn distribute_fees<CTX: ContextTr>(
+    context: &mut CTX,
+    fees: TxFees,
+) -> Result<(), <CTX::Db as Database>::Error> {
+    let (block, _, _, journal, _, _) = context.all_mut();
     journal
         .load_account_mut(block.beneficiary())?
-        .incr_balance(U256::from(coinbase_gas_price * effective_used as u128));
+        .incr_balance(fees.priority_fee);
 
     Ok(())
 }
@@ -182,3 +229,26 @@ pub fn output<CTX: ContextTr<Journal: JournalTr>, HALTREASON: HaltReasonTr>(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn refund_policies() {
+        let gas_params = GasParams::new_spec(SpecId::PRAGUE);
+        let refunded = |policy| {
+            let mut gas = Gas::new(100_000);
+            assert!(gas.record_cost(50_000));
+            gas.record_refund(20_000);
+            refund_with_policy(policy, &gas_params, &mut gas, 1_000);
+            gas.refunded()
+        };
+
+        // EIP-3529 caps the refund to a fifth of the gas used.
+        assert_eq!(refunded(RefundPolicy::GasParams), 10_000);
+        assert_eq!(refunded(RefundPolicy::Capped(2)), 21_000);
+        assert_eq!(refunded(RefundPolicy::Capped(0)), 21_000);
+        assert_eq!(refunded(RefundPolicy::Disabled), 0);
+    }
+}
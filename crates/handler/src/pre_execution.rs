@@ -164,6 +164,9 @@ pub fn calculate_caller_fee(
 }
 
 /// Validates caller state and deducts transaction costs from the caller's balance.
+///
+/// The caller is loaded once, its code is validated according to EIP-3607, then its nonce with
+/// [`validate_and_bump_caller_nonce`] and its balance with [`deduct_caller_fee`].
 #[inline]
 pub fn validate_against_state_and_deduct_caller<
     CTX: ContextTr,
@@ -176,17 +179,96 @@ pub fn validate_against_state_and_deduct_caller<
     // Load caller's account.
     let mut caller = journal.load_account_with_code_mut(tx.caller())?.data;
 
-    validate_account_nonce_and_code_with_components(&caller.account().info, tx, cfg)?;
+    validate_caller_code(&caller.account().info, cfg)?;
+    validate_and_bump_caller_nonce(&mut caller, tx, cfg)?;
+    deduct_caller_fee(&mut caller, tx, block, cfg)?;
+    Ok(())
+}
 
-    let new_balance = calculate_caller_fee(*caller.balance(), tx, block, cfg)?;
+/// Validates caller code according to EIP-3607 and deducts transaction costs from the caller's
+/// balance.
+///
+/// Unlike [`validate_against_state_and_deduct_caller`], the nonce is neither validated nor
+/// bumped, see [`validate_and_bump_nonce`].
+#[inline]
+pub fn validate_caller_and_deduct_fee<
+    CTX: ContextTr,
+    ERROR: From<InvalidTransaction> + From<<CTX::Db as Database>::Error>,
+>(
+    context: &mut CTX,
+) -> Result<(), ERROR> {
+    let (block, tx, cfg, journal, _, _) = context.all_mut();
 
-    caller.set_balance(new_balance);
+    // Load caller's account.
+    let mut caller = journal.load_account_with_code_mut(tx.caller())?.data;
+
+    validate_caller_code(&caller.account().info, cfg)?;
+    deduct_caller_fee(&mut caller, tx, block, cfg)?;
+    Ok(())
+}
+
+/// Validates the transaction nonce against the caller's nonce and bumps it for call transactions.
+///
+/// Nonce of create transactions is bumped when the create frame is made.
+#[inline]
+pub fn validate_and_bump_nonce<
+    CTX: ContextTr,
+    ERROR: From<InvalidTransaction> + From<<CTX::Db as Database>::Error>,
+>(
+    context: &mut CTX,
+) -> Result<(), ERROR> {
+    let (_, tx, cfg, journal, _, _) = context.all_mut();
+
+    let mut caller = journal.load_account_mut(tx.caller())?.data;
+
+    validate_and_bump_caller_nonce(&mut caller, tx, cfg)?;
+    Ok(())
+}
+
+/// Validates caller code according to EIP-3607.
+#[inline]
+fn validate_caller_code(
+    caller_info: &AccountInfo,
+    cfg: impl Cfg,
+) -> Result<(), InvalidTransaction> {
+    validate_account_nonce_and_code(caller_info, 0, cfg.is_eip3607_disabled(), true)
+}
+
+/// Validates the transaction nonce against the nonce of the loaded caller and bumps it for call
+/// transactions.
+#[inline]
+pub fn validate_and_bump_caller_nonce(
+    caller: &mut impl JournaledAccountTr,
+    tx: impl Transaction,
+    cfg: impl Cfg,
+) -> Result<(), InvalidTransaction> {
+    validate_account_nonce_and_code(
+        &caller.account().info,
+        tx.nonce(),
+        true,
+        cfg.is_nonce_check_disabled(),
+    )?;
     if tx.kind().is_call() {
         caller.bump_nonce();
     }
     Ok(())
 }
 
+/// Checks the balance of the loaded caller and deducts the transaction costs from it.
+///
+/// See [`calculate_caller_fee`].
+#[inline]
+pub fn deduct_caller_fee(
+    caller: &mut impl JournaledAccountTr,
+    tx: impl Transaction,
+    block: impl Block,
+    cfg: impl Cfg,
+) -> Result<(), InvalidTransaction> {
+    let new_balance = calculate_caller_fee(*caller.balance(), tx, block, cfg)?;
+    caller.set_balance(new_balance);
+    Ok(())
+}
+
 /// Gas decisions made by the pre-execution phase, carried to the execution
 /// phase.
 #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
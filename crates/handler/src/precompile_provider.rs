@@ -2,7 +2,9 @@ use auto_impl::auto_impl;
 use context::{Cfg, LocalContextTr};
 use context_interface::{ContextTr, JournalTr};
 use interpreter::{CallInputs, Gas, InstructionResult, InterpreterResult};
-use precompile::{PrecompileOutput, PrecompileSpecId, PrecompileStatus, Precompiles};
+use precompile::{
+    PrecompileOutput, PrecompileSpecId, PrecompileStatus, PrecompileTable, Precompiles,
+};
 use primitives::{hardfork::SpecId, Address, AddressSet, Bytes};
 use std::string::{String, ToString};
 
@@ -115,6 +117,39 @@ pub fn precompile_output_to_interpreter_result(
     InterpreterResult::new(result, output.bytes, gas)
 }
 
+/// Runs the precompile at the bytecode address of the call, if there is one.
+fn run_precompiles<CTX: ContextTr>(
+    precompiles: &Precompiles,
+    context: &mut CTX,
+    inputs: &CallInputs,
+) -> Result<Option<InterpreterResult>, String> {
+    let Some(precompile) = precompiles.get(&inputs.bytecode_address) else {
+        return Ok(None);
+    };
+
+    let output = precompile
+        .execute(
+            &inputs.input.as_bytes(context),
+            inputs.gas_limit,
+            inputs.reservoir,
+        )
+        .map_err(|e| e.to_string())?;
+
+    // If this is a top-level precompile call (depth == 1), persist the error message
+    // into the local context so it can be returned as output in the final result.
+    // Only do this for non-OOG halt errors.
+    if let Some(halt_reason) = output.halt_reason() {
+        if !halt_reason.is_oog() && context.journal().depth() == 1 {
+            context
+                .local_mut()
+                .set_precompile_error_context(halt_reason.to_string());
+        }
+    }
+
+    let result = precompile_output_to_interpreter_result(output, inputs.gas_limit);
+    Ok(Some(result))
+}
+
 impl<CTX: ContextTr> PrecompileProvider<CTX> for EthPrecompiles {
     type Output = InterpreterResult;
 
@@ -134,39 +169,72 @@ impl<CTX: ContextTr> PrecompileProvider<CTX> for EthPrecompiles {
         context: &mut CTX,
         inputs: &CallInputs,
     ) -> Result<Option<InterpreterResult>, String> {
-        let Some(precompile) = self.precompiles.get(&inputs.bytecode_address) else {
-            return Ok(None);
-        };
+        run_precompiles(self.precompiles, context, inputs)
+    }
 
-        let output = precompile
-            .execute(
-                &inputs.input.as_bytes(context),
-                inputs.gas_limit,
-                inputs.reservoir,
-            )
-            .map_err(|e| e.to_string())?;
-
-        // If this is a top-level precompile call (depth == 1), persist the error message
-        // into the local context so it can be returned as output in the final result.
-        // Only do this for non-OOG halt errors.
-        if let Some(halt_reason) = output.halt_reason() {
-            if !halt_reason.is_oog() && context.journal().depth() == 1 {
-                context
-                    .local_mut()
-                    .set_precompile_error_context(halt_reason.to_string());
-            }
+    fn warm_addresses(&self) -> &AddressSet {
+        Self::warm_addresses(self)
+    }
+
+    fn contains(&self, address: &Address) -> bool {
+        Self::contains(self, address)
+    }
+}
+
+/// The [`PrecompileProvider`] for ethereum precompiles changed by a [`PrecompileTable`].
+///
+/// Used by chains that relocate, disable or add precompiles, the table is applied to the
+/// precompiles of every spec the provider is set to.
+#[derive(Clone, Debug)]
+pub struct TablePrecompiles {
+    /// Precompiles of the current spec with the table applied.
+    pub precompiles: Precompiles,
+    /// Changes applied to the precompiles of the spec.
+    pub table: PrecompileTable,
+    /// Current spec.
+    pub spec: SpecId,
+}
+
+impl TablePrecompiles {
+    /// Create a new precompile provider with the given spec and table.
+    pub fn new(spec: SpecId, table: PrecompileTable) -> Self {
+        Self {
+            precompiles: table.apply(Precompiles::new(PrecompileSpecId::from_spec_id(spec))),
+            table,
+            spec,
         }
+    }
+}
+
+impl<CTX: ContextTr> PrecompileProvider<CTX> for TablePrecompiles {
+    type Output = InterpreterResult;
 
-        let result = precompile_output_to_interpreter_result(output, inputs.gas_limit);
-        Ok(Some(result))
+    fn set_spec(&mut self, spec: <CTX::Cfg as Cfg>::Spec) -> bool {
+        let spec = spec.into();
+        if spec == self.spec {
+            return false;
+        }
+        self.precompiles = self
+            .table
+            .apply(Precompiles::new(PrecompileSpecId::from_spec_id(spec)));
+        self.spec = spec;
+        true
+    }
+
+    fn run(
+        &mut self,
+        context: &mut CTX,
+        inputs: &CallInputs,
+    ) -> Result<Option<InterpreterResult>, String> {
+        run_precompiles(&self.precompiles, context, inputs)
     }
 
     fn warm_addresses(&self) -> &AddressSet {
-        Self::warm_addresses(self)
+        self.precompiles.addresses_set()
     }
 
     fn contains(&self, address: &Address) -> bool {
-        Self::contains(self, address)
+        self.precompiles.contains(address)
     }
 }
 
@@ -340,4 +408,24 @@ mod tests {
             ExecutionResult::Revert { .. } => panic!("expected Halt(PrecompileOOG), got Revert"),
         }
     }
+
+    #[test]
+    fn table_applies_to_every_spec() {
+        type Ctx = crate::MainnetContext<InMemoryDB>;
+        let identity = *precompile::identity::FUN.address();
+        let relocated = Address::with_last_byte(0x42);
+        let mut provider = TablePrecompiles::new(
+            SpecId::PRAGUE,
+            PrecompileTable::new().relocate(identity, relocated),
+        );
+        assert!(!PrecompileProvider::<Ctx>::contains(&provider, &identity));
+        assert!(PrecompileProvider::<Ctx>::contains(&provider, &relocated));
+
+        assert!(PrecompileProvider::<Ctx>::set_spec(
+            &mut provider,
+            SpecId::BERLIN
+        ));
+        assert!(!provider.precompiles.contains(&identity));
+        assert!(provider.precompiles.addresses_set().contains(&relocated));
+    }
 }
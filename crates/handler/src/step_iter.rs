@@ -0,0 +1,380 @@
+//! Pull-based execution of a transaction, see [`StepIter`].
+use crate::{
+    evm::{ContextDbError, EvmTr},
+    execution,
+    instructions::InstructionProvider,
+    EthFrame, FrameResult, Handler, ItemOrResult, MainnetHandler, PrecompileProvider,
+};
+use context::{
+    result::{EVMError, ExecutionResult, HaltReason, InvalidTransaction},
+    ContextSetters, ContextTr, Database, Evm, JournalTr, LocalContextTr,
+};
+use interpreter::{
+    interpreter::EthInterpreter, interpreter_types::Jumps, GasTracker, InitialAndFloorGas,
+    InterpreterResult,
+};
+use primitives::U256;
+use state::EvmState;
+use std::vec::Vec;
+
+/// Mainnet EVM driven by a [`StepIter`].
+type EthEvm<CTX, INSP, INST, PRECOMPILES> =
+    Evm<CTX, INSP, INST, PRECOMPILES, EthFrame<EthInterpreter>>;
+
+/// Error of a transaction executed by a [`StepIter`].
+pub type StepIterError<CTX> =
+    EVMError<<<CTX as ContextTr>::Db as Database>::Error, InvalidTransaction>;
+
+/// Instruction executed by a [`StepIter`].
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Step {
+    /// Depth of the frame executing the instruction, the first frame has depth zero.
+    pub depth: usize,
+    /// Program counter of the instruction.
+    pub pc: usize,
+    /// Opcode of the instruction.
+    pub opcode: u8,
+    /// Gas remaining before the instruction is executed.
+    pub gas_remaining: u64,
+    /// Stack before the instruction is executed, captured with [`StepIter::with_stack`].
+    pub stack: Option<Vec<U256>>,
+}
+
+/// Extension of the EVM that executes a transaction one instruction at a time.
+pub trait StepEvm {
+    /// Transaction type.
+    type Tx;
+    /// Iterator over the executed instructions.
+    type StepIter<'a>: Iterator<Item = Step>
+    where
+        Self: 'a;
+
+    /// Validates the transaction and returns an iterator that executes it one instruction
+    /// per call to [`Iterator::next`].
+    fn step_iter(&mut self, tx: Self::Tx) -> Self::StepIter<'_>;
+}
+
+impl<CTX, INSP, INST, PRECOMPILES> StepEvm for EthEvm<CTX, INSP, INST, PRECOMPILES>
+where
+    CTX: ContextTr<Journal: JournalTr<State = EvmState>> + ContextSetters,
+    INST: InstructionProvider<Context = CTX, InterpreterTypes = EthInterpreter>,
+    PRECOMPILES: PrecompileProvider<CTX, Output = InterpreterResult>,
+{
+    type Tx = <CTX as ContextTr>::Tx;
+    type StepIter<'a>
+        = StepIter<'a, CTX, INSP, INST, PRECOMPILES>
+    where
+        Self: 'a;
+
+    fn step_iter(&mut self, tx: Self::Tx) -> Self::StepIter<'_> {
+        StepIter::new(self, tx)
+    }
+}
+
+/// Gas accounting of the transaction that is being executed.
+struct Execution {
+    gas: GasTracker,
+    init_and_floor_gas: InitialAndFloorGas,
+    eip7702_refund: i64,
+}
+
+/// Progress of the execution after a phase.
+enum Progress {
+    /// Frames are left to execute.
+    Running,
+    /// Execution has finished with the result of the first frame, `None` if the runtime gas
+    /// phase ran out of gas.
+    Finished(Option<FrameResult>),
+}
+
+/// Iterator that executes a transaction one instruction per call to [`Iterator::next`].
+///
+/// It runs the same phases as [`MainnetHandler`], but the caller drives the interpreter loop, so
+/// debuggers and analysis tools can consume execution lazily, pause it at any instruction, or
+/// stop it early without implementing an inspector.
+///
+/// Once the iterator is exhausted the result is available with [`StepIter::result`] and, like
+/// with [`ExecuteEvm::transact_one`](crate::ExecuteEvm::transact_one), the state changes are
+/// kept in the journal. Dropping the iterator before it is exhausted discards the transaction.
+pub struct StepIter<'a, CTX: ContextTr, INSP, INST, PRECOMPILES> {
+    evm: &'a mut EthEvm<CTX, INSP, INST, PRECOMPILES>,
+    handler: MainnetHandler<
+        EthEvm<CTX, INSP, INST, PRECOMPILES>,
+        StepIterError<CTX>,
+        EthFrame<EthInterpreter>,
+    >,
+    /// Gas of the transaction, `None` once the execution has finished.
+    execution: Option<Execution>,
+    /// Result of the transaction, set once the execution has finished.
+    result: Option<Result<ExecutionResult<HaltReason>, StepIterError<CTX>>>,
+    /// Whether the stack is captured for every step.
+    capture_stack: bool,
+}
+
+impl<CTX: ContextTr, INSP, INST, PRECOMPILES> core::fmt::Debug
+    for StepIter<'_, CTX, INSP, INST, PRECOMPILES>
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("StepIter")
+            .field("finished", &self.execution.is_none())
+            .field("capture_stack", &self.capture_stack)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<'a, CTX, INSP, INST, PRECOMPILES> StepIter<'a, CTX, INSP, INST, PRECOMPILES>
+where
+    CTX: ContextTr<Journal: JournalTr<State = EvmState>> + ContextSetters,
+    INST: InstructionProvider<Context = CTX, InterpreterTypes = EthInterpreter>,
+    PRECOMPILES: PrecompileProvider<CTX, Output = InterpreterResult>,
+{
+    /// Sets the transaction, validates it and creates its first frame.
+    ///
+    /// If the transaction is invalid or does not execute any instruction, the iterator is
+    /// already exhausted.
+    pub fn new(evm: &'a mut EthEvm<CTX, INSP, INST, PRECOMPILES>, tx: CTX::Tx) -> Self {
+        evm.ctx.set_tx(tx);
+        let mut iter = Self {
+            evm,
+            handler: MainnetHandler::default(),
+            execution: None,
+            result: None,
+            capture_stack: false,
+        };
+        match iter.begin() {
+            Ok(Progress::Running) => {}
+            Ok(Progress::Finished(frame_result)) => {
+                let result = iter.end(frame_result);
+                iter.settle(result);
+            }
+            Err(e) => iter.settle(Err(e)),
+        }
+        iter
+    }
+
+    /// Captures the stack for every step.
+    pub fn with_stack(mut self) -> Self {
+        self.capture_stack = true;
+        self
+    }
+
+    /// Returns `true` if the execution has finished.
+    pub fn is_finished(&self) -> bool {
+        self.result.is_some()
+    }
+
+    /// Returns the result of the transaction, `None` if the execution has not finished.
+    pub fn result(&self) -> Option<&Result<ExecutionResult<HaltReason>, StepIterError<CTX>>> {
+        self.result.as_ref()
+    }
+
+    /// Executes the remaining instructions and returns the result of the transaction.
+    pub fn finish(mut self) -> Result<ExecutionResult<HaltReason>, StepIterError<CTX>> {
+        self.by_ref().for_each(drop);
+        self.result
+            .take()
+            .expect("result is set once the iterator is exhausted")
+    }
+
+    /// Runs validation and pre-execution, and creates the first frame.
+    ///
+    /// Mirrors [`Handler::run_without_catch_error`] and [`Handler::execution`] up to the
+    /// execution loop.
+    fn begin(&mut self) -> Result<Progress, StepIterError<CTX>> {
+        let init_and_floor_gas = self.handler.validate(self.evm)?;
+        let gas = self.handler.tx_gas(self.evm, &init_and_floor_gas);
+        let execution = self.execution.insert(Execution {
+            gas,
+            init_and_floor_gas,
+            eip7702_refund: 0,
+        });
+
+        let Some(pre_execution) = self.handler.pre_execution(self.evm, &mut execution.gas)? else {
+            return Ok(Progress::Finished(None));
+        };
+        execution.eip7702_refund = pre_execution.eip7702_refund as i64;
+
+        let Some(first_frame_input) = self
+            .handler
+            .first_frame_input(self.evm, &mut execution.gas)?
+        else {
+            execution::runtime_oog_unwind(&mut self.evm.ctx, pre_execution.checkpoint)?;
+            return Ok(Progress::Finished(None));
+        };
+        self.evm.ctx.journal_mut().checkpoint_commit();
+
+        if let Some(frame_result) = self.handler.frame_intercept(self.evm, &first_frame_input)? {
+            return Ok(Progress::Finished(Some(frame_result)));
+        }
+        Ok(match self.evm.frame_init(first_frame_input)? {
+            ItemOrResult::Item(_) => Progress::Running,
+            ItemOrResult::Result(frame_result) => Progress::Finished(Some(frame_result)),
+        })
+    }
+
+    /// Executes the next instruction of the frame on top of the stack.
+    ///
+    /// Mirrors one iteration of [`Interpreter::run_plain`](interpreter::Interpreter::run_plain),
+    /// and [`Handler::run_exec_loop`] when the instruction stops the frame.
+    fn step(&mut self) -> Result<(Step, Progress), StepIterError<CTX>> {
+        let frame = self.evm.frame_stack.get();
+        let interpreter = &mut frame.interpreter;
+        let step = Step {
+            depth: frame.depth,
+            pc: interpreter.bytecode.pc(),
+            opcode: interpreter.bytecode.opcode(),
+            gas_remaining: interpreter.gas.remaining(),
+            stack: self.capture_stack.then(|| interpreter.stack.data().clone()),
+        };
+
+        let instructions = &self.evm.instruction;
+        let Err(instruction_result) = interpreter.step(
+            instructions.instruction_table(),
+            instructions.gas_table(),
+            &mut self.evm.ctx,
+        ) else {
+            return Ok((step, Progress::Running));
+        };
+        let action = interpreter.take_step_action(instruction_result);
+
+        let next =
+            frame.process_next_action::<_, ContextDbError<CTX>>(&mut self.evm.ctx, action)?;
+        if next.is_result() {
+            frame.set_finished(true);
+        }
+        let frame_result = match next {
+            ItemOrResult::Item(init) => match self.handler.frame_intercept(self.evm, &init)? {
+                Some(frame_result) => frame_result,
+                None => match self.evm.frame_init(init)? {
+                    ItemOrResult::Item(_) => return Ok((step, Progress::Running)),
+                    ItemOrResult::Result(frame_result) => frame_result,
+                },
+            },
+            ItemOrResult::Result(frame_result) => frame_result,
+        };
+
+        let progress = match self.evm.frame_return_result(frame_result)? {
+            Some(frame_result) => Progress::Finished(Some(frame_result)),
+            None => Progress::Running,
+        };
+        Ok((step, progress))
+    }
+
+    /// Settles the gas of the first frame and builds the result of the transaction.
+    ///
+    /// Mirrors [`Handler::run_without_catch_error`] after the execution loop.
+    fn end(
+        &mut self,
+        frame_result: Option<FrameResult>,
+    ) -> Result<ExecutionResult<HaltReason>, StepIterError<CTX>> {
+        let mut execution = self
+            .execution
+            .take()
+            .expect("execution is set until it has finished");
+        let mut exec_result = match frame_result {
+            Some(mut frame_result) => {
+                self.handler
+                    .last_frame_result(self.evm, &mut frame_result, &mut execution.gas)?;
+                frame_result
+            }
+            None => self.handler.runtime_oog_result(
+                self.evm,
+                &execution.init_and_floor_gas,
+                &mut execution.gas,
+            )?,
+        };
+        let result_gas = self.handler.post_execution(
+            self.evm,
+            &mut exec_result,
+            execution.init_and_floor_gas,
+            execution.eip7702_refund,
+        )?;
+        self.handler
+            .execution_result(self.evm, exec_result, result_gas)
+    }
+
+    /// Stores the result of the transaction, cleaning up after an error.
+    fn settle(&mut self, result: Result<ExecutionResult<HaltReason>, StepIterError<CTX>>) {
+        self.execution = None;
+        self.result = Some(result.or_else(|e| self.handler.catch_error(self.evm, e)));
+    }
+}
+
+impl<CTX, INSP, INST, PRECOMPILES> Iterator for StepIter<'_, CTX, INSP, INST, PRECOMPILES>
+where
+    CTX: ContextTr<Journal: JournalTr<State = EvmState>> + ContextSetters,
+    INST: InstructionProvider<Context = CTX, InterpreterTypes = EthInterpreter>,
+    PRECOMPILES: PrecompileProvider<CTX, Output = InterpreterResult>,
+{
+    type Item = Step;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.execution.as_ref()?;
+        match self.step() {
+            Ok((step, Progress::Running)) => Some(step),
+            Ok((step, Progress::Finished(frame_result))) => {
+                let result = self.end(frame_result);
+                self.settle(result);
+                Some(step)
+            }
+            Err(e) => {
+                self.settle(Err(e));
+                None
+            }
+        }
+    }
+}
+
+impl<CTX: ContextTr, INSP, INST, PRECOMPILES> Drop for StepIter<'_, CTX, INSP, INST, PRECOMPILES> {
+    fn drop(&mut self) {
+        if self.execution.is_some() {
+            // Execution was stopped early, discard the transaction like `Handler::catch_error`.
+            self.evm.ctx.local_mut().clear();
+            self.evm.ctx.journal_mut().discard_tx();
+            self.evm.frame_stack.clear();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{MainBuilder, MainContext};
+    use bytecode::opcode;
+    use context::{Context, TxEnv};
+    use database::BenchmarkDB;
+    use state::Bytecode;
+
+    #[test]
+    fn steps_are_pulled_by_the_caller() {
+        let bytecode = Bytecode::new_raw(
+            [
+                opcode::PUSH1,
+                0x01,
+                opcode::PUSH1,
+                0x02,
+                opcode::ADD,
+                opcode::STOP,
+            ]
+            .to_vec()
+            .into(),
+        );
+        let mut evm = Context::mainnet()
+            .with_db(BenchmarkDB::new_bytecode(bytecode))
+            .build_mainnet();
+
+        let mut steps = evm
+            .step_iter(TxEnv::builder_for_bench().build_fill())
+            .with_stack();
+        let first = steps.next().unwrap();
+        assert_eq!((first.depth, first.pc, first.opcode), (0, 0, opcode::PUSH1));
+        assert!(!steps.is_finished());
+
+        let rest: Vec<_> = steps.by_ref().collect();
+        let opcodes: Vec<_> = rest.iter().map(|step| step.opcode).collect();
+        assert_eq!(opcodes, [opcode::PUSH1, opcode::ADD, opcode::STOP]);
+        assert_eq!(rest[1].stack, Some(vec![U256::from(1), U256::from(2)]));
+        assert!(rest[1].gas_remaining < first.gas_remaining);
+        assert!(steps.finish().unwrap().is_success());
+    }
+}
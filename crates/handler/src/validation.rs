@@ -1,5 +1,6 @@
+use crate::pre_execution::{calculate_caller_fee, validate_account_nonce_and_code_with_components};
 use context_interface::{
-    cfg::GasParams,
+    cfg::{gas_params::Eip2780TxInfo, GasParams},
     result::{InvalidHeader, InvalidTransaction},
     transaction::{Transaction, TransactionType},
     Block, Cfg, ContextTr,
@@ -7,21 +8,72 @@ use context_interface::{
 use core::cmp;
 use interpreter::InitialAndFloorGas;
 use primitives::{eip4844, hardfork::SpecId, B256};
+use state::AccountInfo;
 
 /// Validates the execution environment including block and transaction parameters.
 pub fn validate_env<CTX: ContextTr, ERROR: From<InvalidHeader> + From<InvalidTransaction>>(
     context: CTX,
 ) -> Result<(), ERROR> {
-    let spec = context.cfg().spec().into();
+    validate_env_with_components(context.tx(), context.block(), context.cfg())
+}
+
+/// Validates the block header and the transaction against the block and configuration.
+///
+/// Same as [`validate_env`] without requiring a context.
+pub fn validate_env_with_components<ERROR: From<InvalidHeader> + From<InvalidTransaction>>(
+    tx: impl Transaction,
+    block: impl Block,
+    cfg: impl Cfg,
+) -> Result<(), ERROR> {
+    let spec: SpecId = cfg.spec().into();
     // `prevrandao` is required for the merge
-    if spec.is_enabled_in(SpecId::MERGE) && context.block().prevrandao().is_none() {
+    if spec.is_enabled_in(SpecId::MERGE) && block.prevrandao().is_none() {
         return Err(InvalidHeader::PrevrandaoNotSet.into());
     }
     // `excess_blob_gas` is required for Cancun
-    if spec.is_enabled_in(SpecId::CANCUN) && context.block().blob_excess_gas_and_price().is_none() {
+    if spec.is_enabled_in(SpecId::CANCUN) && block.blob_excess_gas_and_price().is_none() {
         return Err(InvalidHeader::ExcessBlobGasNotSet.into());
     }
-    validate_tx_env::<CTX>(context, spec).map_err(Into::into)
+    validate_tx_env_with_components(tx, block, cfg, spec).map_err(Into::into)
+}
+
+/// Validates a transaction the way execution does, without a database or a context.
+///
+/// Runs the checks of [`Handler::validate`](crate::Handler::validate) against the given caller
+/// account: the block header, the transaction against the block and configuration, the initial
+/// gas, the caller nonce and code, and the caller balance. Errors are the ones execution would
+/// return, e.g. [`EVMError`](context_interface::result::EVMError), but no frame is created and no
+/// state is loaded or changed, so RPC servers can reject invalid transactions before simulating
+/// them.
+///
+/// Custom handlers that override the validation are not taken into account.
+///
+/// Returns the initial and floor gas of the transaction.
+pub fn validate_tx<ERROR: From<InvalidHeader> + From<InvalidTransaction>>(
+    tx: impl Transaction,
+    block: impl Block,
+    cfg: impl Cfg,
+    caller_info: &AccountInfo,
+) -> Result<InitialAndFloorGas, ERROR> {
+    validate_env_with_components::<ERROR>(&tx, &block, &cfg)?;
+
+    let eip2780 = cfg.is_amsterdam_eip2780_enabled().then(|| Eip2780TxInfo {
+        value: tx.value(),
+        is_self_transfer: tx.kind().to() == Some(&tx.caller()),
+    });
+    let gas = validate_initial_tx_gas_with_gas_params(
+        &tx,
+        cfg.spec().into(),
+        cfg.gas_params(),
+        cfg.is_eip7623_disabled(),
+        cfg.is_amsterdam_eip8037_enabled(),
+        cfg.tx_gas_limit_cap(),
+        eip2780,
+    )?;
+
+    validate_account_nonce_and_code_with_components(caller_info, &tx, &cfg)?;
+    calculate_caller_fee(caller_info.balance, &tx, &block, &cfg)?;
+    Ok(gas)
 }
 
 /// Validate legacy transaction gas price against basefee.
@@ -117,28 +169,51 @@ pub fn validate_eip4844_tx(
     Ok(())
 }
 
+/// Validate the blob sidecar of an EIP-4844 transaction against its versioned hashes.
+///
+/// Blob proofs are verified in a single batch.
+#[cfg(feature = "blob-sidecar")]
+pub fn validate_eip4844_sidecar(
+    blobs: &[B256],
+    sidecar: &context_interface::transaction::BlobSidecar,
+) -> Result<(), InvalidTransaction> {
+    precompile::kzg_point_evaluation::sidecar::validate_blob_sidecar(blobs, sidecar)
+        .map_err(InvalidTransaction::InvalidBlobSidecar)
+}
+
 /// Validate transaction against block and configuration for mainnet.
 pub fn validate_tx_env<CTX: ContextTr>(
     context: CTX,
     spec_id: SpecId,
+) -> Result<(), InvalidTransaction> {
+    validate_tx_env_with_components(context.tx(), context.block(), context.cfg(), spec_id)
+}
+
+/// Validate transaction against block and configuration for mainnet.
+///
+/// Same as [`validate_tx_env`] without requiring a context.
+pub fn validate_tx_env_with_components(
+    tx: impl Transaction,
+    block: impl Block,
+    cfg: impl Cfg,
+    spec_id: SpecId,
 ) -> Result<(), InvalidTransaction> {
     // Check if the transaction's chain id is correct
-    let tx = context.tx();
     let tx_type = tx.tx_type();
 
-    let base_fee = if context.cfg().is_base_fee_check_disabled() {
+    let base_fee = if cfg.is_base_fee_check_disabled() {
         None
     } else {
-        Some(context.block().basefee() as u128)
+        Some(block.basefee() as u128)
     };
 
     let tx_type = TransactionType::from(tx_type);
 
     // Check chain_id if config is enabled.
     // EIP-155: Simple replay attack protection
-    if context.cfg().tx_chain_id_check() {
+    if cfg.tx_chain_id_check() {
         if let Some(chain_id) = tx.chain_id() {
-            if chain_id != context.cfg().chain_id() {
+            if chain_id != cfg.chain_id() {
                 return Err(InvalidTransaction::InvalidChainId);
             }
         } else if !tx_type.is_legacy() && !tx_type.is_custom() {
@@ -148,9 +223,9 @@ pub fn validate_tx_env<CTX: ContextTr>(
     }
 
     // tx gas cap is not enforced if state gas is enabled.
-    if !context.cfg().is_amsterdam_eip8037_enabled() {
+    if !cfg.is_amsterdam_eip8037_enabled() {
         // EIP-7825: Transaction Gas Limit Cap
-        let cap = context.cfg().tx_gas_limit_cap();
+        let cap = cfg.tx_gas_limit_cap();
         if tx.gas_limit() > cap {
             return Err(InvalidTransaction::TxGasLimitGreaterThanCap {
                 gas_limit: tx.gas_limit(),
@@ -159,7 +234,7 @@ pub fn validate_tx_env<CTX: ContextTr>(
         }
     }
 
-    let disable_priority_fee_check = context.cfg().is_priority_fee_check_disabled();
+    let disable_priority_fee_check = cfg.is_priority_fee_check_disabled();
 
     match tx_type {
         TransactionType::Legacy => {
@@ -176,21 +251,26 @@ pub fn validate_tx_env<CTX: ContextTr>(
             if !spec_id.is_enabled_in(SpecId::LONDON) {
                 return Err(InvalidTransaction::Eip1559NotSupported);
             }
-            validate_priority_fee_for_tx(tx, base_fee, disable_priority_fee_check)?;
+            validate_priority_fee_for_tx(&tx, base_fee, disable_priority_fee_check)?;
         }
         TransactionType::Eip4844 => {
             if !spec_id.is_enabled_in(SpecId::CANCUN) {
                 return Err(InvalidTransaction::Eip4844NotSupported);
             }
 
-            validate_priority_fee_for_tx(tx, base_fee, disable_priority_fee_check)?;
+            validate_priority_fee_for_tx(&tx, base_fee, disable_priority_fee_check)?;
 
             validate_eip4844_tx(
                 tx.blob_versioned_hashes(),
                 tx.max_fee_per_blob_gas(),
-                context.block().blob_gasprice().unwrap_or_default(),
-                context.cfg().max_blobs_per_tx(),
+                block.blob_gasprice().unwrap_or_default(),
+                cfg.max_blobs_per_tx(),
             )?;
+
+            #[cfg(feature = "blob-sidecar")]
+            if let Some(sidecar) = tx.blob_sidecar() {
+                validate_eip4844_sidecar(tx.blob_versioned_hashes(), sidecar)?;
+            }
         }
         TransactionType::Eip7702 => {
             // Check if EIP-7702 transaction is enabled.
@@ -198,7 +278,7 @@ pub fn validate_tx_env<CTX: ContextTr>(
                 return Err(InvalidTransaction::Eip7702NotSupported);
             }
 
-            validate_priority_fee_for_tx(tx, base_fee, disable_priority_fee_check)?;
+            validate_priority_fee_for_tx(&tx, base_fee, disable_priority_fee_check)?;
 
             let auth_list_len = tx.authorization_list_len();
             // The transaction is considered invalid if the length of authorization_list is zero.
@@ -214,15 +294,14 @@ pub fn validate_tx_env<CTX: ContextTr>(
     // Check if gas_limit is more than block_gas_limit
     // TODO(eip8037) should we enforce to `min(tx.gas_limit(), 16M) < block.gas_limit`?
     // This would enforce that regular gas is constrained.
-    if !context.cfg().is_block_gas_limit_disabled() && tx.gas_limit() > context.block().gas_limit()
-    {
+    if !cfg.is_block_gas_limit_disabled() && tx.gas_limit() > block.gas_limit() {
         return Err(InvalidTransaction::CallerGasLimitMoreThanBlock);
     }
 
     // EIP-3860: Limit and meter initcode. Still valid with EIP-7907 and increase of initcode size.
     if spec_id.is_enabled_in(SpecId::SHANGHAI)
         && tx.kind().is_create()
-        && tx.input().len() > context.cfg().max_initcode_size()
+        && tx.input().len() > cfg.max_initcode_size()
     {
         return Err(InvalidTransaction::CreateInitCodeSizeLimit);
     }
@@ -271,8 +350,29 @@ pub fn validate_initial_tx_gas_with_gas_params(
     tx_gas_limit_cap: u64,
     eip2780: Option<context_interface::cfg::gas_params::Eip2780TxInfo>,
 ) -> Result<InitialAndFloorGas, InvalidTransaction> {
-    let mut gas = gas_params.initial_tx_gas_for_tx(&tx, eip2780);
+    let gas = gas_params.initial_tx_gas_for_tx(&tx, eip2780);
+    validate_initial_and_floor_gas(
+        tx,
+        spec,
+        gas,
+        is_eip7623_disabled,
+        is_amsterdam_eip8037_enabled,
+        tx_gas_limit_cap,
+    )
+}
 
+/// Validate already calculated initial and floor gas against the transaction gas limit.
+///
+/// Floor gas is cleared if EIP-7623 is disabled and initial state gas is cleared if
+/// EIP-8037 is not enabled.
+pub fn validate_initial_and_floor_gas(
+    tx: impl Transaction,
+    spec: SpecId,
+    mut gas: InitialAndFloorGas,
+    is_eip7623_disabled: bool,
+    is_amsterdam_eip8037_enabled: bool,
+    tx_gas_limit_cap: u64,
+) -> Result<InitialAndFloorGas, InvalidTransaction> {
     if is_eip7623_disabled {
         gas.set_floor_gas(0);
     }
@@ -783,4 +883,93 @@ mod tests {
             })
         ));
     }
+
+    #[cfg(feature = "blob-sidecar")]
+    #[test]
+    fn test_eip4844_sidecar_mismatch() {
+        use context::transaction::{BlobSidecar, BlobSidecarError};
+        use primitives::eip4844::{BYTES_PER_BLOB, VERSIONED_HASH_VERSION_KZG};
+
+        let mut blob_hash = B256::ZERO;
+        blob_hash[0] = VERSIONED_HASH_VERSION_KZG;
+        // Commitment and proof of the zero blob, that do not match the versioned hash.
+        let mut infinity = [0; 48];
+        infinity[0] = 0xc0;
+        let sidecar = BlobSidecar::new(
+            vec![vec![0; BYTES_PER_BLOB].into()],
+            vec![infinity.into()],
+            vec![infinity.into()],
+        );
+
+        let ctx = Context::mainnet().with_db(CacheDB::<EmptyDB>::default());
+        let mut evm = ctx.build_mainnet();
+        let tx = TxEnv::builder()
+            .kind(TxKind::Call(address!(
+                "0xc000000000000000000000000000000000000000"
+            )))
+            .gas_priority_fee(Some(0))
+            .blob_hashes(vec![blob_hash])
+            .max_fee_per_blob_gas(1)
+            .blob_sidecar(Some(sidecar))
+            .build()
+            .unwrap();
+
+        assert!(matches!(
+            evm.transact(tx),
+            Err(EVMError::Transaction(
+                InvalidTransaction::InvalidBlobSidecar(BlobSidecarError::VersionedHashMismatch {
+                    index: 0
+                })
+            ))
+        ));
+    }
+
+    #[test]
+    fn standalone_validation() {
+        use super::validate_tx;
+        use context::{BlockEnv, CfgEnv};
+        use primitives::U256;
+
+        let tx = TxEnv::builder()
+            .kind(TxKind::Call(address!(
+                "0xc000000000000000000000000000000000000000"
+            )))
+            .gas_limit(100_000)
+            .gas_price(1)
+            .nonce(1)
+            .build()
+            .unwrap();
+        let (block, cfg) = (BlockEnv::default(), CfgEnv::<SpecId>::default());
+        let caller = AccountInfo::default()
+            .with_balance(U256::from(100_000))
+            .with_nonce(1);
+
+        let gas =
+            validate_tx::<EVMError<core::convert::Infallible>>(&tx, &block, &cfg, &caller).unwrap();
+        assert_eq!(gas.initial_total_gas(), 21_000);
+
+        assert!(matches!(
+            validate_tx::<EVMError<core::convert::Infallible>>(
+                &tx,
+                &block,
+                &cfg,
+                &caller.clone().with_nonce(0),
+            ),
+            Err(EVMError::Transaction(InvalidTransaction::NonceTooHigh {
+                tx: 1,
+                state: 0
+            }))
+        ));
+        assert!(matches!(
+            validate_tx::<EVMError<core::convert::Infallible>>(
+                &tx,
+                &block,
+                &cfg,
+                &caller.clone().with_balance(U256::from(1)),
+            ),
+            Err(EVMError::Transaction(
+                InvalidTransaction::LackOfFundForMaxFee { .. }
+            ))
+        ));
+    }
 }
@@ -0,0 +1,261 @@
+//! AccessListInspector - Inspector that generates the EIP-2930 access list of a transaction.
+extern crate alloc;
+
+use crate::{InspectEvm, Inspector, InspectorEvmTr};
+use alloc::{
+    collections::{BTreeMap, BTreeSet},
+    vec::Vec,
+};
+use context::{
+    result::ExecutionResult,
+    transaction::{AccessList, AccessListItem, TransactionType},
+    ContextTr, JournalTr, TxEnv,
+};
+use handler::ExecuteEvm;
+use interpreter::{
+    interpreter_types::{InputsTr, Jumps, StackTr},
+    CallInputs, CallOutcome, CreateInputs, CreateOutcome, Interpreter, InterpreterTypes,
+};
+use primitives::{Address, AddressSet, B256};
+use state::bytecode::opcode;
+
+/// Inspector that records the accounts and storage slots accessed by a transaction.
+///
+/// Follows `eth_createAccessList`: accounts accessed by `BALANCE`, `EXTCODE*`, `CALL*` and
+/// `SELFDESTRUCT` are listed unless they are precompiles, the sender, the recipient or the
+/// created contract, as those are warm regardless of the access list. Storage slots accessed by
+/// `SLOAD` and `SSTORE` are always listed together with their account.
+///
+/// Entries are accumulated on top of the access list the inspector was created with, use
+/// [`create_access_list`] to execute a transaction until its access list is complete.
+#[derive(Clone, Debug, Default)]
+pub struct AccessListInspector {
+    accessed: BTreeMap<Address, BTreeSet<B256>>,
+    excluded: AddressSet,
+}
+
+impl AccessListInspector {
+    /// Creates a new inspector that extends the given access list.
+    pub fn new(access_list: &AccessList) -> Self {
+        let accessed = access_list
+            .0
+            .iter()
+            .map(|item| {
+                (
+                    item.address,
+                    item.storage_keys.iter().copied().collect::<BTreeSet<_>>(),
+                )
+            })
+            .fold(BTreeMap::new(), |mut accessed, (address, keys)| {
+                accessed
+                    .entry(address)
+                    .or_insert_with(BTreeSet::new)
+                    .extend(keys);
+                accessed
+            });
+        Self {
+            accessed,
+            excluded: AddressSet::default(),
+        }
+    }
+
+    /// Returns the recorded access list, sorted by address and storage key.
+    pub fn access_list(&self) -> AccessList {
+        AccessList(
+            self.accessed
+                .iter()
+                .map(|(address, keys)| AccessListItem {
+                    address: *address,
+                    storage_keys: keys.iter().copied().collect(),
+                })
+                .collect::<Vec<_>>(),
+        )
+    }
+
+    /// Consumes the inspector and returns the recorded access list.
+    pub fn into_access_list(self) -> AccessList {
+        self.access_list()
+    }
+
+    fn record_account<CTX: ContextTr>(&mut self, context: &mut CTX, address: Address) {
+        if self.excluded.contains(&address)
+            || context.journal().precompile_addresses().contains(&address)
+        {
+            return;
+        }
+        self.accessed.entry(address).or_default();
+    }
+}
+
+impl<CTX: ContextTr, INTR: InterpreterTypes> Inspector<CTX, INTR> for AccessListInspector {
+    fn step(&mut self, interp: &mut Interpreter<INTR>, context: &mut CTX) {
+        let stack = interp.stack.data();
+        match interp.bytecode.opcode() {
+            opcode::SLOAD | opcode::SSTORE => {
+                if let Some(key) = stack.last() {
+                    self.accessed
+                        .entry(interp.input.target_address())
+                        .or_default()
+                        .insert(B256::from(*key));
+                }
+            }
+            opcode::BALANCE
+            | opcode::EXTCODESIZE
+            | opcode::EXTCODECOPY
+            | opcode::EXTCODEHASH
+            | opcode::SELFDESTRUCT => {
+                if let Some(address) = stack.last() {
+                    let address = Address::from_word(B256::from(*address));
+                    self.record_account(context, address);
+                }
+            }
+            opcode::CALL | opcode::CALLCODE | opcode::DELEGATECALL | opcode::STATICCALL => {
+                if let Some(address) = stack.len().checked_sub(2).map(|i| stack[i]) {
+                    let address = Address::from_word(B256::from(address));
+                    self.record_account(context, address);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn call(&mut self, context: &mut CTX, inputs: &mut CallInputs) -> Option<CallOutcome> {
+        if context.journal().depth() == 0 {
+            self.excluded = AddressSet::from_iter([inputs.caller, inputs.target_address]);
+        }
+        None
+    }
+
+    fn create(&mut self, context: &mut CTX, inputs: &mut CreateInputs) -> Option<CreateOutcome> {
+        if context.journal().depth() == 0 {
+            self.excluded = AddressSet::from_iter([inputs.caller()]);
+        }
+        None
+    }
+
+    fn create_end(
+        &mut self,
+        context: &mut CTX,
+        _inputs: &CreateInputs,
+        outcome: &mut CreateOutcome,
+    ) {
+        if context.journal().depth() != 0 {
+            return;
+        }
+        // The created address is only known at the end, drop it unless its storage was accessed.
+        if let Some(address) = outcome.address {
+            self.excluded.insert(address);
+            if self
+                .accessed
+                .get(&address)
+                .is_some_and(|keys| keys.is_empty())
+            {
+                self.accessed.remove(&address);
+            }
+        }
+    }
+}
+
+/// Access list of a transaction created by [`create_access_list`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AccessListResult<HaltReasonTy> {
+    /// Access list of the transaction, including the access list it was created with.
+    pub access_list: AccessList,
+    /// Gas used by the transaction with the access list applied.
+    pub gas_used: u64,
+    /// Result of the transaction with the access list applied.
+    pub result: ExecutionResult<HaltReasonTy>,
+}
+
+/// Creates the access list of a transaction, the building block of `eth_createAccessList`.
+///
+/// The transaction is executed with its access list extended by the accessed accounts and slots
+/// until no new entries are found, as the access list changes the gas available to the
+/// execution. Legacy transactions are executed as EIP-2930 transactions. The state of the EVM is
+/// not changed.
+pub fn create_access_list<EVM, HaltReasonTy>(
+    evm: &mut EVM,
+    mut tx: TxEnv,
+) -> Result<AccessListResult<HaltReasonTy>, <EVM as ExecuteEvm>::Error>
+where
+    EVM: InspectEvm<
+            Inspector = AccessListInspector,
+            Tx = TxEnv,
+            ExecutionResult = ExecutionResult<HaltReasonTy>,
+        > + InspectorEvmTr<Inspector = AccessListInspector>,
+{
+    if tx.tx_type == TransactionType::Legacy as u8 {
+        tx.tx_type = TransactionType::Eip2930 as u8;
+    }
+    loop {
+        evm.set_inspector(AccessListInspector::new(&tx.access_list));
+        let result = evm.inspect_one_tx(tx.clone());
+        // Discard the state changes.
+        let _ = evm.finalize();
+        let result = result?;
+
+        let access_list = InspectorEvmTr::inspector(evm).access_list();
+        if access_list == tx.access_list {
+            return Ok(AccessListResult {
+                access_list,
+                gas_used: result.gas_used(),
+                result,
+            });
+        }
+        tx.access_list = access_list;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use context::Context;
+    use database::{BenchmarkDB, BENCH_CALLER, BENCH_TARGET};
+    use handler::{MainBuilder, MainContext};
+    use primitives::{TxKind, U256};
+    use state::bytecode::Bytecode;
+
+    #[test]
+    fn test_create_access_list() {
+        let other = Address::with_last_byte(0xAA);
+        let mut bytecode = std::vec![opcode::PUSH1, 0x1, opcode::SLOAD, opcode::POP];
+        // `BALANCE` of the caller, a precompile and another account.
+        for address in [BENCH_CALLER, Address::with_last_byte(1), other] {
+            bytecode.push(opcode::PUSH20);
+            bytecode.extend_from_slice(address.as_slice());
+            bytecode.extend_from_slice(&[opcode::BALANCE, opcode::POP]);
+        }
+        bytecode.push(opcode::STOP);
+
+        let mut evm = Context::mainnet()
+            .with_db(BenchmarkDB::new_bytecode(Bytecode::new_raw(
+                bytecode.into(),
+            )))
+            .build_mainnet_with_inspector(AccessListInspector::default());
+        let tx = TxEnv::builder()
+            .caller(BENCH_CALLER)
+            .kind(TxKind::Call(BENCH_TARGET))
+            .gas_limit(100_000)
+            .build()
+            .unwrap();
+        let without_list = evm.transact_one(tx.clone()).unwrap().gas_used();
+        let _ = evm.finalize();
+
+        let result = create_access_list(&mut evm, tx).unwrap();
+        assert_eq!(
+            result.access_list,
+            AccessList(std::vec![
+                AccessListItem {
+                    address: other,
+                    storage_keys: std::vec![],
+                },
+                AccessListItem {
+                    address: BENCH_TARGET,
+                    storage_keys: std::vec![B256::from(U256::from(1))],
+                },
+            ])
+        );
+        assert!(result.result.is_success());
+        assert!(result.gas_used < without_list);
+    }
+}
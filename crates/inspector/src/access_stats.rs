@@ -0,0 +1,251 @@
+//! AccessStatsInspector - Inspector that counts EIP-2929 cold and warm state accesses.
+extern crate alloc;
+
+use crate::{Inspector, JournalExt};
+use alloc::vec::Vec;
+use context::{Cfg, ContextTr, JournalEntry, JournalTr};
+use interpreter::{
+    interpreter_types::{Jumps, LoopControl},
+    CallInputs, CallOutcome, CreateInputs, CreateOutcome, Interpreter, InterpreterTypes,
+};
+use state::bytecode::opcode;
+
+/// Cold and warm state accesses of a transaction.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct AccessStats {
+    /// Number of accounts accessed for the first time in the transaction.
+    pub cold_accounts: u64,
+    /// Number of accesses to accounts that were already warm.
+    pub warm_accounts: u64,
+    /// Number of storage slots accessed for the first time in the transaction.
+    pub cold_slots: u64,
+    /// Number of accesses to storage slots that were already warm.
+    pub warm_slots: u64,
+    /// Gas charged on top of the warm access cost for the cold accesses.
+    ///
+    /// This is an upper bound of the gas an access list saves, listing an account or slot
+    /// costs slightly less than its cold surcharge.
+    pub cold_gas: u64,
+}
+
+impl AccessStats {
+    /// Returns the sum of the statistics.
+    pub fn sum<'a>(stats: impl IntoIterator<Item = &'a Self>) -> Self {
+        stats
+            .into_iter()
+            .fold(Self::default(), |total, stats| Self {
+                cold_accounts: total.cold_accounts + stats.cold_accounts,
+                warm_accounts: total.warm_accounts + stats.warm_accounts,
+                cold_slots: total.cold_slots + stats.cold_slots,
+                warm_slots: total.warm_slots + stats.warm_slots,
+                cold_gas: total.cold_gas + stats.cold_gas,
+            })
+    }
+}
+
+/// Kind of state accessed by an opcode.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum AccessKind {
+    Account,
+    Slot,
+}
+
+/// Access that is being executed.
+#[derive(Clone, Copy, Debug)]
+struct PendingAccess {
+    kind: AccessKind,
+    journal_len: usize,
+}
+
+/// Inspector that counts cold and warm account and storage accesses of every transaction.
+///
+/// Accesses are counted for the opcodes whose gas depends on the warmth of the accessed state,
+/// `SLOAD`, `SSTORE`, `BALANCE`, `EXTCODE*`, `CALL*` and `SELFDESTRUCT`. Cold accesses are the
+/// ones that warm the account or slot in the journal, accesses to precompiles, the coinbase and
+/// the access list are warm.
+///
+/// The inspector is meant to be reused for many transactions, transaction boundaries are
+/// detected when the top level frame ends.
+#[derive(Clone, Debug, Default)]
+pub struct AccessStatsInspector {
+    current: AccessStats,
+    transactions: Vec<AccessStats>,
+    pending: Option<PendingAccess>,
+}
+
+impl AccessStatsInspector {
+    /// Creates a new access statistics inspector.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the statistics of the finished transactions, in execution order.
+    pub fn transactions(&self) -> &[AccessStats] {
+        &self.transactions
+    }
+
+    /// Returns the statistics of all finished transactions.
+    pub fn total(&self) -> AccessStats {
+        AccessStats::sum(&self.transactions)
+    }
+
+    /// Takes the statistics of the finished transactions.
+    pub fn take_transactions(&mut self) -> Vec<AccessStats> {
+        core::mem::take(&mut self.transactions)
+    }
+
+    fn tx_end<CTX: ContextTr>(&mut self, context: &mut CTX) {
+        if context.journal().depth() != 0 {
+            return;
+        }
+        self.transactions.push(core::mem::take(&mut self.current));
+    }
+}
+
+impl<CTX, INTR> Inspector<CTX, INTR> for AccessStatsInspector
+where
+    CTX: ContextTr<Journal: JournalExt>,
+    INTR: InterpreterTypes,
+{
+    fn step(&mut self, interp: &mut Interpreter<INTR>, context: &mut CTX) {
+        let kind = match interp.bytecode.opcode() {
+            opcode::SLOAD | opcode::SSTORE => AccessKind::Slot,
+            opcode::BALANCE
+            | opcode::EXTCODESIZE
+            | opcode::EXTCODECOPY
+            | opcode::EXTCODEHASH
+            | opcode::CALL
+            | opcode::CALLCODE
+            | opcode::DELEGATECALL
+            | opcode::STATICCALL
+            | opcode::SELFDESTRUCT => AccessKind::Account,
+            _ => return,
+        };
+        self.pending = Some(PendingAccess {
+            kind,
+            journal_len: context.journal().journal().len(),
+        });
+    }
+
+    fn step_end(&mut self, interp: &mut Interpreter<INTR>, context: &mut CTX) {
+        let Some(pending) = self.pending.take() else {
+            return;
+        };
+        let journal = context.journal().journal();
+        let (mut cold_accounts, mut cold_slots) = (0, 0);
+        for entry in &journal[pending.journal_len.min(journal.len())..] {
+            match entry {
+                JournalEntry::AccountWarmed { .. } => cold_accounts += 1,
+                JournalEntry::StorageWarmed { .. } => cold_slots += 1,
+                _ => {}
+            }
+        }
+        let cold = cold_accounts + cold_slots;
+        // Failed before accessing the state, e.g. on a stack underflow.
+        if cold == 0
+            && interp
+                .bytecode
+                .instruction_result()
+                .is_some_and(|result| result.is_error())
+        {
+            return;
+        }
+
+        let gas_params = context.cfg().gas_params();
+        self.current.cold_accounts += cold_accounts;
+        self.current.cold_slots += cold_slots;
+        self.current.cold_gas += cold_accounts * gas_params.cold_account_additional_cost()
+            + cold_slots * gas_params.cold_storage_additional_cost();
+        if cold == 0 {
+            match pending.kind {
+                AccessKind::Account => self.current.warm_accounts += 1,
+                AccessKind::Slot => self.current.warm_slots += 1,
+            }
+        }
+    }
+
+    fn call_end(&mut self, context: &mut CTX, _inputs: &CallInputs, _outcome: &mut CallOutcome) {
+        self.tx_end(context);
+    }
+
+    fn create_end(
+        &mut self,
+        context: &mut CTX,
+        _inputs: &CreateInputs,
+        _outcome: &mut CreateOutcome,
+    ) {
+        self.tx_end(context);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::InspectEvm;
+    use context::{Context, TxEnv};
+    use database::{BenchmarkDB, BENCH_CALLER, BENCH_TARGET};
+    use handler::{MainBuilder, MainContext};
+    use primitives::TxKind;
+    use state::bytecode::Bytecode;
+
+    #[test]
+    fn test_access_stats() {
+        let bytecode = Bytecode::new_raw(
+            [
+                // Cold and warm `SLOAD` of slot 0.
+                opcode::PUSH1,
+                0x0,
+                opcode::SLOAD,
+                opcode::POP,
+                opcode::PUSH1,
+                0x0,
+                opcode::SLOAD,
+                opcode::POP,
+                // Cold and warm `BALANCE` of 0xff.
+                opcode::PUSH1,
+                0xff,
+                opcode::BALANCE,
+                opcode::POP,
+                opcode::PUSH1,
+                0xff,
+                opcode::BALANCE,
+                opcode::POP,
+                // Caller is warm.
+                opcode::CALLER,
+                opcode::BALANCE,
+                opcode::POP,
+                opcode::STOP,
+            ]
+            .to_vec()
+            .into(),
+        );
+
+        let mut evm = Context::mainnet()
+            .with_db(BenchmarkDB::new_bytecode(bytecode))
+            .build_mainnet_with_inspector(AccessStatsInspector::new());
+
+        for nonce in 0..2 {
+            evm.inspect_one_tx(
+                TxEnv::builder()
+                    .caller(BENCH_CALLER)
+                    .kind(TxKind::Call(BENCH_TARGET))
+                    .nonce(nonce)
+                    .gas_limit(1_000_000)
+                    .build()
+                    .unwrap(),
+            )
+            .unwrap();
+        }
+
+        let expected = AccessStats {
+            cold_accounts: 1,
+            warm_accounts: 2,
+            cold_slots: 1,
+            warm_slots: 1,
+            cold_gas: 2_500 + 2_000,
+        };
+        // Every transaction starts cold.
+        assert_eq!(evm.inspector.transactions(), [expected, expected]);
+        assert_eq!(evm.inspector.total().cold_gas, 2 * expected.cold_gas);
+    }
+}
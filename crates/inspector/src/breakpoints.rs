@@ -0,0 +1,209 @@
+//! Breakpoints - Conditions that inspectors can embed to decide when to capture execution.
+extern crate alloc;
+
+use alloc::vec::Vec;
+use interpreter::{
+    interpreter_types::{InputsTr, Jumps, StackTr},
+    CallInputs, Interpreter, InterpreterTypes,
+};
+use primitives::{Address, StorageKey};
+use state::bytecode::opcode;
+
+/// Condition that is hit during execution.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Breakpoint {
+    /// Instruction at the program counter of the code executed by the address.
+    Pc {
+        /// Address whose storage and balance the code is executed with.
+        address: Address,
+        /// Program counter of the instruction.
+        pc: usize,
+    },
+    /// Any instruction with the opcode.
+    Opcode(u8),
+    /// `SSTORE` to the storage of the address.
+    StorageWrite {
+        /// Address whose storage is written.
+        address: Address,
+        /// Written slot, `None` matches every slot.
+        slot: Option<StorageKey>,
+    },
+    /// Call that executes the code of the address.
+    Call(Address),
+}
+
+/// Set of [`Breakpoint`]s.
+///
+/// Heavyweight tracers can embed breakpoints to record only the interesting window of a huge
+/// transaction: they check [`Breakpoints::step_hit`] in [`Inspector::step`](crate::Inspector::step)
+/// and [`Breakpoints::call_hit`] in [`Inspector::call`](crate::Inspector::call), and start or stop
+/// the detailed capture when a breakpoint is hit.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Breakpoints {
+    breakpoints: Vec<Breakpoint>,
+}
+
+impl Breakpoints {
+    /// Creates an empty set of breakpoints.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the breakpoint.
+    pub fn with(mut self, breakpoint: Breakpoint) -> Self {
+        self.insert(breakpoint);
+        self
+    }
+
+    /// Adds the breakpoint, does nothing if it is already set.
+    pub fn insert(&mut self, breakpoint: Breakpoint) {
+        if !self.breakpoints.contains(&breakpoint) {
+            self.breakpoints.push(breakpoint);
+        }
+    }
+
+    /// Removes the breakpoint and returns `true` if it was set.
+    pub fn remove(&mut self, breakpoint: &Breakpoint) -> bool {
+        let len = self.breakpoints.len();
+        self.breakpoints.retain(|b| b != breakpoint);
+        self.breakpoints.len() != len
+    }
+
+    /// Removes all breakpoints.
+    pub fn clear(&mut self) {
+        self.breakpoints.clear();
+    }
+
+    /// Returns `true` if there are no breakpoints.
+    pub fn is_empty(&self) -> bool {
+        self.breakpoints.is_empty()
+    }
+
+    /// Returns the breakpoints.
+    pub fn breakpoints(&self) -> &[Breakpoint] {
+        &self.breakpoints
+    }
+
+    /// Returns the breakpoint hit by the instruction that is about to be executed.
+    ///
+    /// Call breakpoints are not checked, see [`Breakpoints::call_hit`].
+    pub fn step_hit<INTR: InterpreterTypes>(
+        &self,
+        interp: &Interpreter<INTR>,
+    ) -> Option<&Breakpoint> {
+        if self.breakpoints.is_empty() {
+            return None;
+        }
+        let opcode = interp.bytecode.opcode();
+        self.breakpoints
+            .iter()
+            .find(|breakpoint| match **breakpoint {
+                Breakpoint::Pc { address, pc } => {
+                    interp.bytecode.pc() == pc && interp.input.target_address() == address
+                }
+                Breakpoint::Opcode(op) => opcode == op,
+                Breakpoint::StorageWrite { address, slot } => {
+                    opcode == opcode::SSTORE
+                        && interp.input.target_address() == address
+                        && slot.is_none_or(|slot| interp.stack.data().last() == Some(&slot))
+                }
+                Breakpoint::Call(_) => false,
+            })
+    }
+
+    /// Returns the call breakpoint hit by the call.
+    ///
+    /// The call hits the breakpoint when it executes the code of the address, so a
+    /// `DELEGATECALL` to a library hits the breakpoint of the library.
+    pub fn call_hit(&self, inputs: &CallInputs) -> Option<&Breakpoint> {
+        self.breakpoints.iter().find(|breakpoint| {
+            matches!(breakpoint, Breakpoint::Call(address) if *address == inputs.bytecode_address)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{InspectEvm, Inspector};
+    use context::{Context, TxEnv};
+    use database::{BenchmarkDB, BENCH_CALLER, BENCH_TARGET};
+    use handler::{MainBuilder, MainContext};
+    use primitives::{TxKind, U256};
+    use state::bytecode::Bytecode;
+
+    /// Records the opcodes executed after the first breakpoint hit.
+    #[derive(Default)]
+    struct WindowInspector {
+        breakpoints: Breakpoints,
+        capturing: bool,
+        opcodes: Vec<u8>,
+    }
+
+    impl<CTX, INTR: InterpreterTypes> Inspector<CTX, INTR> for WindowInspector {
+        fn step(&mut self, interp: &mut Interpreter<INTR>, _context: &mut CTX) {
+            if self.breakpoints.step_hit(interp).is_some() {
+                self.capturing = true;
+            }
+            if self.capturing {
+                self.opcodes.push(interp.bytecode.opcode());
+            }
+        }
+    }
+
+    #[test]
+    fn capture_starts_at_breakpoint() {
+        // slot0 = 1; slot1 = 2
+        let bytecode = Bytecode::new_raw(
+            [
+                opcode::PUSH1,
+                0x1,
+                opcode::PUSH1,
+                0x0,
+                opcode::SSTORE,
+                opcode::PUSH1,
+                0x2,
+                opcode::PUSH1,
+                0x1,
+                opcode::SSTORE,
+                opcode::STOP,
+            ]
+            .to_vec()
+            .into(),
+        );
+        let tx = || {
+            TxEnv::builder()
+                .caller(BENCH_CALLER)
+                .kind(TxKind::Call(BENCH_TARGET))
+                .gas_limit(1_000_000)
+                .build()
+                .unwrap()
+        };
+        let run = |breakpoint| {
+            let inspector = WindowInspector {
+                breakpoints: Breakpoints::new().with(breakpoint),
+                ..Default::default()
+            };
+            let mut evm = Context::mainnet()
+                .with_db(BenchmarkDB::new_bytecode(bytecode.clone()))
+                .build_mainnet_with_inspector(inspector);
+            evm.inspect_one_tx(tx()).unwrap();
+            core::mem::take(&mut evm.inspector.opcodes)
+        };
+
+        let slot_write = run(Breakpoint::StorageWrite {
+            address: BENCH_TARGET,
+            slot: Some(U256::from(1)),
+        });
+        assert_eq!(slot_write, [opcode::SSTORE, opcode::STOP]);
+
+        let pc = run(Breakpoint::Pc {
+            address: BENCH_TARGET,
+            pc: 5,
+        });
+        assert_eq!(pc.len(), 4);
+        assert_eq!(pc[0], opcode::PUSH1);
+
+        assert!(run(Breakpoint::Opcode(opcode::CALL)).is_empty());
+    }
+}
@@ -0,0 +1,180 @@
+//! CallGraph - Aggregates traced call trees into a DOT call graph.
+extern crate alloc;
+
+use crate::{
+    gas::{CallGasInspector, FrameGas},
+    labels::Labels,
+};
+use alloc::{collections::BTreeMap, string::String, sync::Arc};
+use core::fmt::Write;
+use primitives::Address;
+
+/// Calls from one address to another.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CallEdge {
+    /// Number of calls.
+    pub calls: u64,
+    /// Gas spent by the calls, including the gas spent by their descendants.
+    pub gas_spent: u64,
+}
+
+/// Call graph aggregated over one or more traced transactions.
+///
+/// Nodes are addresses and edges are weighted by the number of calls and the gas spent by
+/// them. Call trees are added from a [`CallGasInspector`] and the graph is exported to the
+/// DOT format of Graphviz with [`CallGraph::to_dot`], e.g. for a quick visual audit of the
+/// protocol interactions of a block.
+///
+/// Creates that failed have no address and are skipped.
+#[derive(Clone, Debug, Default)]
+pub struct CallGraph {
+    /// Number of frames executed by every address.
+    nodes: BTreeMap<Address, u64>,
+    edges: BTreeMap<(Address, Address), CallEdge>,
+    labels: Option<Arc<Labels>>,
+}
+
+impl CallGraph {
+    /// Creates an empty call graph.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the labels used to name the nodes.
+    pub fn with_labels(mut self, labels: Arc<Labels>) -> Self {
+        self.labels = Some(labels);
+        self
+    }
+
+    /// Adds the call tree traced by the inspector.
+    pub fn add_trace(&mut self, inspector: &CallGasInspector) {
+        self.add_frames(inspector.frames());
+    }
+
+    /// Adds the call tree of the frames, see [`CallGasInspector::frames`].
+    pub fn add_frames(&mut self, frames: &[FrameGas]) {
+        for frame in frames {
+            let Some(callee) = frame.address else {
+                continue;
+            };
+            *self.nodes.entry(callee).or_default() += 1;
+            let Some(caller) = frame.parent.and_then(|parent| frames[parent].address) else {
+                continue;
+            };
+            let edge = self.edges.entry((caller, callee)).or_default();
+            edge.calls += 1;
+            edge.gas_spent += frame.gas_spent;
+        }
+    }
+
+    /// Returns the edges of the graph.
+    pub fn edges(&self) -> &BTreeMap<(Address, Address), CallEdge> {
+        &self.edges
+    }
+
+    /// Returns the calls from `caller` to `callee`.
+    pub fn edge(&self, caller: Address, callee: Address) -> Option<&CallEdge> {
+        self.edges.get(&(caller, callee))
+    }
+
+    /// Clears the graph.
+    pub fn clear(&mut self) {
+        self.nodes.clear();
+        self.edges.clear();
+    }
+
+    /// Renders the graph in the DOT format.
+    ///
+    /// Edges are labeled with the number of calls and the gas spent, and their width grows with
+    /// the number of calls.
+    pub fn to_dot(&self) -> String {
+        let default_labels = Labels::default();
+        let labels = self.labels.as_deref().unwrap_or(&default_labels);
+        let mut out = String::from("digraph calls {\n");
+        for address in self.nodes.keys() {
+            let _ = writeln!(
+                out,
+                "    \"{address}\" [label=\"{}\"];",
+                escape(&labels.format_address(address))
+            );
+        }
+        for ((caller, callee), edge) in &self.edges {
+            let _ = writeln!(
+                out,
+                "    \"{caller}\" -> \"{callee}\" [label=\"{} calls, {} gas\", penwidth={}];",
+                edge.calls,
+                edge.gas_spent,
+                1 + edge.calls.ilog2()
+            );
+        }
+        out.push_str("}\n");
+        out
+    }
+}
+
+/// Escapes a string for a quoted DOT identifier.
+fn escape(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gas::FrameKind;
+    use alloc::vec::Vec;
+    use interpreter::CallScheme;
+
+    fn frame(parent: Option<usize>, address: Address, gas_spent: u64) -> FrameGas {
+        FrameGas {
+            depth: parent.map_or(0, |_| 1),
+            parent,
+            children: Vec::new(),
+            kind: FrameKind::Call(CallScheme::Call),
+            address: Some(address),
+            selector: None,
+            gas_available: 0,
+            gas_limit: 0,
+            gas_spent,
+            refunded: 0,
+            result: None,
+            proxy: None,
+            precompile: None,
+        }
+    }
+
+    #[test]
+    fn aggregates_calls_across_traces() {
+        let router = Address::with_last_byte(1);
+        let pool = Address::with_last_byte(2);
+        let token = Address::with_last_byte(3);
+
+        let mut graph = CallGraph::new().with_labels(Arc::new(
+            Labels::new().with_address(router, "Router \"v2\""),
+        ));
+        graph.add_frames(&[
+            frame(None, router, 100),
+            frame(Some(0), pool, 40),
+            frame(Some(0), pool, 20),
+        ]);
+        graph.add_frames(&[frame(None, router, 50), frame(Some(0), token, 10)]);
+
+        assert_eq!(
+            graph.edge(router, pool),
+            Some(&CallEdge {
+                calls: 2,
+                gas_spent: 60
+            })
+        );
+        assert_eq!(graph.edge(router, token).unwrap().calls, 1);
+        assert_eq!(graph.edges().len(), 2);
+
+        let dot = graph.to_dot();
+        assert!(dot.starts_with("digraph calls {\n"));
+        assert!(dot.contains(&format!("\"{router}\" [label=\"Router \\\"v2\\\"\"];")));
+        assert!(dot.contains(&format!(
+            "\"{router}\" -> \"{pool}\" [label=\"2 calls, 60 gas\", penwidth=2];"
+        )));
+        // Header, three nodes, two edges and the closing brace.
+        assert_eq!(dot.lines().count(), 7);
+    }
+}
@@ -0,0 +1,530 @@
+//! CallTracer - Records the call frame tree in the format of geth's `callTracer`.
+use crate::{Inspector, RefundEvent, RefundSource};
+use context::{Cfg, ContextTr, JournalTr, Transaction};
+use interpreter::{
+    CallInputs, CallOutcome, CallScheme, CreateInputs, CreateOutcome, CreateScheme,
+    InstructionResult, InterpreterResult, InterpreterTypes,
+};
+use primitives::{hardfork::SpecId, hex, Address, Bytes, Log, B256, U256};
+use serde::{Serialize, Serializer};
+
+/// Selector of the `Error(string)` revert.
+const ERROR_SELECTOR: [u8; 4] = [0x08, 0xc3, 0x79, 0xa0];
+
+/// Type of a [`CallFrame`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum CallFrameType {
+    /// `CALL` or a call transaction.
+    Call,
+    /// `CALLCODE`.
+    CallCode,
+    /// `DELEGATECALL`.
+    DelegateCall,
+    /// `STATICCALL`.
+    StaticCall,
+    /// `CREATE` or a create transaction.
+    Create,
+    /// `CREATE2`.
+    Create2,
+    /// `SELFDESTRUCT`.
+    SelfDestruct,
+}
+
+/// Log emitted by a [`CallFrame`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct CallLog {
+    /// Address of the emitting contract.
+    #[serde(serialize_with = "serde_address")]
+    pub address: Address,
+    /// Topics of the log.
+    pub topics: Vec<B256>,
+    /// Data of the log.
+    pub data: Bytes,
+    /// Number of calls of the frame made before the log was emitted.
+    #[serde(serialize_with = "serde_hex_u64")]
+    pub position: u64,
+}
+
+/// Call frame of [`CallTracer`], serialized to the JSON shape of geth's `callTracer`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CallFrame {
+    /// Type of the frame.
+    #[serde(rename = "type")]
+    pub kind: CallFrameType,
+    /// Address of the caller.
+    #[serde(serialize_with = "serde_address")]
+    pub from: Address,
+    /// Address of the callee, for `DELEGATECALL` and `CALLCODE` the address of the code.
+    ///
+    /// For creates it is the created address, it is not set if the address is unknown.
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serde_option_address"
+    )]
+    pub to: Option<Address>,
+    /// Transferred value, not set for `DELEGATECALL` and `STATICCALL`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<U256>,
+    /// Gas available to the frame, the gas limit of the transaction for the top frame.
+    #[serde(serialize_with = "serde_hex_u64")]
+    pub gas: u64,
+    /// Gas used by the frame including its calls, the gas used by the transaction for the top
+    /// frame.
+    #[serde(serialize_with = "serde_hex_u64")]
+    pub gas_used: u64,
+    /// Input of the call or init code of the create.
+    pub input: Bytes,
+    /// Returned or reverted data, the deployed code of a successful create.
+    #[serde(skip_serializing_if = "Bytes::is_empty")]
+    pub output: Bytes,
+    /// Error of a frame that did not succeed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// Reason of a revert with an `Error(string)` payload.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub revert_reason: Option<String>,
+    /// Calls made by the frame.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub calls: Vec<CallFrame>,
+    /// Logs emitted by the frame, recorded only with [`CallTracer::with_logs`].
+    ///
+    /// Logs of frames that did not succeed are dropped, including the logs of their calls.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub logs: Vec<CallLog>,
+}
+
+impl CallFrame {
+    fn new(
+        kind: CallFrameType,
+        from: Address,
+        to: Option<Address>,
+        value: Option<U256>,
+        gas: u64,
+        input: Bytes,
+    ) -> Self {
+        Self {
+            kind,
+            from,
+            to,
+            value,
+            gas,
+            gas_used: 0,
+            input,
+            output: Bytes::new(),
+            error: None,
+            revert_reason: None,
+            calls: Vec::new(),
+            logs: Vec::new(),
+        }
+    }
+
+    /// Returns `true` if the frame succeeded.
+    pub const fn is_success(&self) -> bool {
+        self.error.is_none()
+    }
+
+    /// Serializes the frame to the JSON of geth's `callTracer`.
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::to_value(self).expect("call frame is serializable")
+    }
+
+    fn clear_logs(&mut self) {
+        self.logs.clear();
+        for call in &mut self.calls {
+            call.clear_logs();
+        }
+    }
+}
+
+/// Inspector that records the call frame tree of a transaction, a drop-in replacement of geth's
+/// `callTracer` for `debug_traceTransaction`.
+///
+/// Gas of the top frame is the gas limit and the gas used of the transaction, including the
+/// intrinsic gas, the refund and the EIP-7623 floor. The tree is cleared when the next
+/// transaction starts.
+#[derive(Clone, Debug, Default)]
+pub struct CallTracer {
+    with_logs: bool,
+    /// Frames that are still executing.
+    stack: Vec<CallFrame>,
+    root: Option<CallFrame>,
+    /// EIP-7702 refund reported before the first frame.
+    authorization_refund: i64,
+}
+
+impl CallTracer {
+    /// Creates a new call tracer.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records the logs of the frames, same as the `withLog` option of geth.
+    pub fn with_logs(mut self) -> Self {
+        self.with_logs = true;
+        self
+    }
+
+    /// Returns the top frame of the current or last transaction.
+    pub fn root(&self) -> Option<&CallFrame> {
+        self.root.as_ref()
+    }
+
+    /// Takes the top frame, leaving the tracer empty.
+    pub fn take(&mut self) -> Option<CallFrame> {
+        self.stack.clear();
+        self.root.take()
+    }
+
+    /// Serializes the top frame to the JSON of geth's `callTracer`, `null` if nothing was
+    /// traced.
+    pub fn to_json(&self) -> serde_json::Value {
+        self.root
+            .as_ref()
+            .map_or(serde_json::Value::Null, CallFrame::to_json)
+    }
+
+    /// Clears the recorded frames.
+    pub fn clear(&mut self) {
+        self.stack.clear();
+        self.root = None;
+    }
+
+    fn push_frame(&mut self, context: &mut impl ContextTr, mut frame: CallFrame) {
+        if context.journal().depth() == 0 {
+            self.clear();
+            frame.gas = context.tx().gas_limit();
+        }
+        self.stack.push(frame);
+    }
+
+    fn pop_frame(
+        &mut self,
+        context: &mut impl ContextTr,
+        frame_gas_limit: u64,
+        result: &InterpreterResult,
+        address: Option<Address>,
+    ) {
+        let Some(mut frame) = self.stack.pop() else {
+            return;
+        };
+        frame.gas_used = if result.result.is_halt() {
+            frame_gas_limit
+        } else {
+            result.gas.total_gas_spent()
+        };
+        frame.output = result.output.clone();
+        if let Some(address) = address {
+            frame.to = Some(address);
+        }
+        if !result.is_ok() {
+            frame.error = Some(error_message(result.result));
+            if result.is_revert() {
+                frame.revert_reason = revert_reason(&result.output);
+            }
+            frame.clear_logs();
+        }
+
+        match self.stack.last_mut() {
+            Some(parent) => parent.calls.push(frame),
+            None => {
+                frame.gas_used =
+                    self.tx_gas_used(&*context, frame_gas_limit, frame.gas_used, result);
+                self.authorization_refund = 0;
+                self.root = Some(frame);
+            }
+        }
+    }
+
+    /// Returns the gas used by the transaction, after the refund and the EIP-7623 floor.
+    fn tx_gas_used(
+        &self,
+        context: &impl ContextTr,
+        frame_gas_limit: u64,
+        frame_gas_used: u64,
+        result: &InterpreterResult,
+    ) -> u64 {
+        let tx = context.tx();
+        let cfg = context.cfg();
+        let intrinsic_gas = tx.gas_limit().saturating_sub(frame_gas_limit);
+        let gas_used = intrinsic_gas + frame_gas_used;
+
+        let frame_refund = if result.is_ok() {
+            result.gas.refunded()
+        } else {
+            0
+        };
+        let refund = frame_refund + self.authorization_refund;
+        let refund = cfg
+            .refund_policy()
+            .max_refund_quotient(cfg.gas_params())
+            .map_or(0, |quotient| {
+                (refund.max(0) as u64).min(gas_used / quotient)
+            });
+        let gas_used = gas_used - refund;
+
+        let spec: SpecId = cfg.spec().into();
+        if spec.is_enabled_in(SpecId::PRAGUE) && !cfg.is_eip7623_disabled() {
+            let floor_gas = cfg.gas_params().initial_tx_gas_for_tx(tx, None).floor_gas();
+            gas_used.max(floor_gas)
+        } else {
+            gas_used
+        }
+    }
+}
+
+impl<CTX: ContextTr, INTR: InterpreterTypes> Inspector<CTX, INTR> for CallTracer {
+    fn log(&mut self, _context: &mut CTX, log: Log) {
+        if !self.with_logs {
+            return;
+        }
+        let Some(frame) = self.stack.last_mut() else {
+            return;
+        };
+        frame.logs.push(CallLog {
+            address: log.address,
+            topics: log.topics().to_vec(),
+            data: log.data.data,
+            position: frame.calls.len() as u64,
+        });
+    }
+
+    fn call(&mut self, context: &mut CTX, inputs: &mut CallInputs) -> Option<CallOutcome> {
+        let (kind, to, value) = match inputs.scheme {
+            CallScheme::Call => (
+                CallFrameType::Call,
+                inputs.target_address,
+                Some(inputs.value.get()),
+            ),
+            CallScheme::CallCode => (
+                CallFrameType::CallCode,
+                inputs.bytecode_address,
+                Some(inputs.value.get()),
+            ),
+            CallScheme::DelegateCall => {
+                (CallFrameType::DelegateCall, inputs.bytecode_address, None)
+            }
+            CallScheme::StaticCall => (CallFrameType::StaticCall, inputs.target_address, None),
+        };
+        let input = Bytes::copy_from_slice(&inputs.input.as_bytes(context));
+        let frame = CallFrame::new(
+            kind,
+            inputs.caller,
+            Some(to),
+            value,
+            inputs.gas_limit,
+            input,
+        );
+        self.push_frame(context, frame);
+        None
+    }
+
+    fn call_end(&mut self, context: &mut CTX, inputs: &CallInputs, outcome: &mut CallOutcome) {
+        self.pop_frame(context, inputs.gas_limit, &outcome.result, None);
+    }
+
+    fn create(&mut self, context: &mut CTX, inputs: &mut CreateInputs) -> Option<CreateOutcome> {
+        let kind = match inputs.scheme() {
+            CreateScheme::Create2 { .. } => CallFrameType::Create2,
+            _ => CallFrameType::Create,
+        };
+        let frame = CallFrame::new(
+            kind,
+            inputs.caller(),
+            None,
+            Some(inputs.value()),
+            inputs.gas_limit(),
+            inputs.init_code().clone(),
+        );
+        self.push_frame(context, frame);
+        None
+    }
+
+    fn create_end(
+        &mut self,
+        context: &mut CTX,
+        inputs: &CreateInputs,
+        outcome: &mut CreateOutcome,
+    ) {
+        self.pop_frame(
+            context,
+            inputs.gas_limit(),
+            &outcome.result,
+            outcome.address,
+        );
+    }
+
+    fn selfdestruct(&mut self, contract: Address, target: Address, value: U256) {
+        if let Some(frame) = self.stack.last_mut() {
+            frame.calls.push(CallFrame::new(
+                CallFrameType::SelfDestruct,
+                contract,
+                Some(target),
+                Some(value),
+                0,
+                Bytes::new(),
+            ));
+        }
+    }
+
+    fn refund(&mut self, _context: &mut CTX, event: RefundEvent) {
+        if event.source == RefundSource::Eip7702Authorizations {
+            self.authorization_refund += event.amount;
+        }
+    }
+}
+
+/// Returns the error message geth reports for the result.
+fn error_message(result: InstructionResult) -> String {
+    match result {
+        InstructionResult::Revert => "execution reverted",
+        InstructionResult::CallTooDeep => "max call depth exceeded",
+        InstructionResult::OutOfFunds => "insufficient balance for transfer",
+        InstructionResult::OutOfGas
+        | InstructionResult::MemoryOOG
+        | InstructionResult::MemoryLimitOOG
+        | InstructionResult::PrecompileOOG
+        | InstructionResult::InvalidOperandOOG
+        | InstructionResult::ReentrancySentryOOG => "out of gas",
+        InstructionResult::OpcodeNotFound
+        | InstructionResult::InvalidFEOpcode
+        | InstructionResult::NotActivated => "invalid opcode",
+        InstructionResult::CallNotAllowedInsideStatic
+        | InstructionResult::StateChangeDuringStaticCall => "write protection",
+        InstructionResult::InvalidJump => "invalid jump destination",
+        InstructionResult::StackUnderflow => "stack underflow",
+        InstructionResult::StackOverflow => "stack limit reached 1024",
+        InstructionResult::CreateCollision => "contract address collision",
+        InstructionResult::CreateContractSizeLimit => "max code size exceeded",
+        InstructionResult::CreateContractStartingWithEF => "invalid code: must not begin with 0xef",
+        InstructionResult::CreateInitCodeSizeLimit => "max initcode size exceeded",
+        InstructionResult::NonceOverflow => "nonce uint64 overflow",
+        result => return format!("{result:?}"),
+    }
+    .to_string()
+}
+
+/// Decodes the reason of an `Error(string)` revert.
+fn revert_reason(output: &[u8]) -> Option<String> {
+    let data = output.strip_prefix(&ERROR_SELECTOR)?;
+    let word = |offset: usize| -> Option<usize> {
+        let word = U256::from_be_slice(data.get(offset..offset.checked_add(32)?)?);
+        usize::try_from(word).ok()
+    };
+    let offset = word(0)?;
+    let len = word(offset)?;
+    let start = offset.checked_add(32)?;
+    let reason = data.get(start..start.checked_add(len)?)?;
+    String::from_utf8(reason.to_vec()).ok()
+}
+
+pub(crate) fn serde_hex_u64<S: Serializer>(n: &u64, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&format!("{:#x}", *n))
+}
+
+pub(crate) fn serde_address<S: Serializer>(
+    address: &Address,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&hex::encode_prefixed(address))
+}
+
+fn serde_option_address<S: Serializer>(
+    address: &Option<Address>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    match address {
+        Some(address) => serde_address(address, serializer),
+        None => serializer.serialize_none(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::InspectEvm;
+    use context::{Context, TxEnv};
+    use database::{BenchmarkDB, BENCH_CALLER, BENCH_TARGET};
+    use handler::{MainBuilder, MainContext};
+    use primitives::TxKind;
+    use state::bytecode::{opcode, Bytecode};
+
+    #[test]
+    fn call_tree() {
+        // Calls the identity precompile, emits a log and stops.
+        let bytecode = Bytecode::new_raw(
+            [
+                opcode::PUSH1,
+                0x00,
+                opcode::PUSH1,
+                0x00,
+                opcode::PUSH1,
+                0x00,
+                opcode::PUSH1,
+                0x00,
+                opcode::PUSH1,
+                0x00,
+                opcode::PUSH1,
+                0x04,
+                opcode::GAS,
+                opcode::CALL,
+                opcode::POP,
+                opcode::PUSH1,
+                0x00,
+                opcode::PUSH1,
+                0x00,
+                opcode::LOG0,
+                opcode::STOP,
+            ]
+            .to_vec()
+            .into(),
+        );
+        let mut evm = Context::mainnet()
+            .with_db(BenchmarkDB::new_bytecode(bytecode))
+            .build_mainnet_with_inspector(CallTracer::new().with_logs());
+        let result = evm
+            .inspect_one_tx(
+                TxEnv::builder()
+                    .caller(BENCH_CALLER)
+                    .kind(TxKind::Call(BENCH_TARGET))
+                    .gas_limit(100_000)
+                    .build()
+                    .unwrap(),
+            )
+            .unwrap();
+
+        let root = evm.inspector.root().unwrap();
+        assert_eq!(root.kind, CallFrameType::Call);
+        assert_eq!(root.from, BENCH_CALLER);
+        assert_eq!(root.to, Some(BENCH_TARGET));
+        assert_eq!(root.gas, 100_000);
+        assert_eq!(root.gas_used, result.gas_used());
+        assert!(root.is_success());
+        assert_eq!(root.calls.len(), 1);
+        assert_eq!(root.logs.len(), 1);
+
+        let identity = &root.calls[0];
+        assert_eq!(identity.to, Some(Address::with_last_byte(4)));
+        assert_eq!(identity.gas_used, 15);
+
+        let json = evm.inspector.to_json();
+        assert_eq!(json["type"], "CALL");
+        assert_eq!(json["value"], "0x0");
+        assert_eq!(json["gas"], "0x186a0");
+        assert_eq!(json["calls"][0]["gasUsed"], "0xf");
+        assert_eq!(json["logs"][0]["position"], "0x1");
+        assert!(json.get("output").is_none());
+    }
+
+    #[test]
+    fn decodes_revert_reason() {
+        let mut output = ERROR_SELECTOR.to_vec();
+        output.extend_from_slice(&U256::from(32).to_be_bytes::<32>());
+        output.extend_from_slice(&U256::from(4).to_be_bytes::<32>());
+        output.extend_from_slice(&B256::right_padding_from(b"nope").0);
+        assert_eq!(revert_reason(&output), Some("nope".to_string()));
+        assert_eq!(revert_reason(&output[..40]), None);
+        assert_eq!(revert_reason(&[0xde, 0xad]), None);
+    }
+}
@@ -0,0 +1,339 @@
+//! Inspector that forwards hook events to an async consumer through a bounded channel.
+use crate::{Inspector, RefundEvent};
+use context::{ContextTr, JournalTr};
+use core::future::Future;
+use interpreter::{
+    interpreter_types::Jumps, CallInputs, CallOutcome, CreateInputs, CreateOutcome,
+    InstructionResult, Interpreter, InterpreterTypes,
+};
+use primitives::{Address, Bytes, Log, U256};
+use tokio::sync::mpsc::{self, error::TrySendError, Receiver, Sender};
+
+/// Owned hook event sent by [`ChannelInspector`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum HookEvent {
+    /// Interpreter is about to execute an instruction.
+    Step {
+        /// Call depth.
+        depth: usize,
+        /// Program counter.
+        pc: usize,
+        /// Opcode.
+        opcode: u8,
+        /// Gas remaining before the instruction.
+        gas_remaining: u64,
+    },
+    /// Call is about to start.
+    Call {
+        /// Call depth.
+        depth: usize,
+        /// Caller address.
+        caller: Address,
+        /// Target address.
+        target: Address,
+        /// Value transferred or apparent value for `DELEGATECALL`.
+        value: U256,
+        /// Call input data.
+        input: Bytes,
+        /// Gas limit of the call.
+        gas_limit: u64,
+    },
+    /// Call has ended.
+    CallEnd {
+        /// Call depth.
+        depth: usize,
+        /// Result of the call.
+        result: InstructionResult,
+        /// Output of the call.
+        output: Bytes,
+        /// Gas spent by the call.
+        gas_spent: u64,
+    },
+    /// Create is about to start.
+    Create {
+        /// Call depth.
+        depth: usize,
+        /// Caller address.
+        caller: Address,
+        /// Value transferred.
+        value: U256,
+        /// Init code.
+        init_code: Bytes,
+        /// Gas limit of the create.
+        gas_limit: u64,
+    },
+    /// Create has ended.
+    CreateEnd {
+        /// Call depth.
+        depth: usize,
+        /// Result of the create.
+        result: InstructionResult,
+        /// Created address.
+        address: Option<Address>,
+        /// Gas spent by the create.
+        gas_spent: u64,
+    },
+    /// Log was emitted.
+    Log(Log),
+    /// Contract was self-destructed.
+    Selfdestruct {
+        /// Self-destructed contract.
+        contract: Address,
+        /// Beneficiary of the balance.
+        target: Address,
+        /// Transferred balance.
+        value: U256,
+    },
+    /// Gas refund counter changed.
+    Refund(RefundEvent),
+}
+
+/// What [`ChannelInspector`] does when the channel is full.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Backpressure {
+    /// Block the interpreter thread until the consumer catches up.
+    #[default]
+    Block,
+    /// Drop the event and count it in [`ChannelInspector::dropped`].
+    Drop,
+}
+
+/// Inspector that sends [`HookEvent`]s through a bounded [`tokio`] channel.
+///
+/// This allows an [`AsyncInspector`] to perform async work (e.g. stream steps to a websocket)
+/// without blocking the interpreter on it. With [`Backpressure::Block`] the interpreter
+/// waits when the channel is full, so the EVM must not run on an async runtime thread,
+/// use `tokio::task::spawn_blocking` instead.
+///
+/// If the receiver is dropped, events are discarded.
+#[derive(Debug)]
+pub struct ChannelInspector {
+    sender: Sender<HookEvent>,
+    backpressure: Backpressure,
+    include_steps: bool,
+    dropped: u64,
+}
+
+impl ChannelInspector {
+    /// Creates a new inspector and the receiver with the given channel capacity.
+    ///
+    /// Step events are not sent by default, see [`ChannelInspector::with_steps`].
+    pub fn new(capacity: usize) -> (Self, Receiver<HookEvent>) {
+        let (sender, receiver) = mpsc::channel(capacity);
+        (
+            Self {
+                sender,
+                backpressure: Backpressure::default(),
+                include_steps: false,
+                dropped: 0,
+            },
+            receiver,
+        )
+    }
+
+    /// Sends a [`HookEvent::Step`] for every executed instruction.
+    pub const fn with_steps(mut self) -> Self {
+        self.include_steps = true;
+        self
+    }
+
+    /// Sets the behavior when the channel is full.
+    pub const fn with_backpressure(mut self, backpressure: Backpressure) -> Self {
+        self.backpressure = backpressure;
+        self
+    }
+
+    /// Returns the number of events dropped because the channel was full.
+    pub const fn dropped(&self) -> u64 {
+        self.dropped
+    }
+
+    fn send(&mut self, event: HookEvent) {
+        if self.sender.is_closed() {
+            return;
+        }
+        match self.backpressure {
+            Backpressure::Block => {
+                let _ = self.sender.blocking_send(event);
+            }
+            Backpressure::Drop => {
+                if let Err(TrySendError::Full(_)) = self.sender.try_send(event) {
+                    self.dropped += 1;
+                }
+            }
+        }
+    }
+}
+
+impl<CTX, INTR> Inspector<CTX, INTR> for ChannelInspector
+where
+    CTX: ContextTr,
+    INTR: InterpreterTypes,
+{
+    fn step(&mut self, interp: &mut Interpreter<INTR>, context: &mut CTX) {
+        if !self.include_steps {
+            return;
+        }
+        self.send(HookEvent::Step {
+            depth: context.journal().depth(),
+            pc: interp.bytecode.pc(),
+            opcode: interp.bytecode.opcode(),
+            gas_remaining: interp.gas.remaining(),
+        });
+    }
+
+    fn log(&mut self, _context: &mut CTX, log: Log) {
+        self.send(HookEvent::Log(log));
+    }
+
+    fn call(&mut self, context: &mut CTX, inputs: &mut CallInputs) -> Option<CallOutcome> {
+        self.send(HookEvent::Call {
+            depth: context.journal().depth(),
+            caller: inputs.caller,
+            target: inputs.target_address,
+            value: inputs.call_value(),
+            input: inputs.input.bytes(context),
+            gas_limit: inputs.gas_limit,
+        });
+        None
+    }
+
+    fn call_end(&mut self, context: &mut CTX, _inputs: &CallInputs, outcome: &mut CallOutcome) {
+        self.send(HookEvent::CallEnd {
+            depth: context.journal().depth(),
+            result: outcome.result.result,
+            output: outcome.result.output.clone(),
+            gas_spent: outcome.result.gas.total_gas_spent(),
+        });
+    }
+
+    fn create(&mut self, context: &mut CTX, inputs: &mut CreateInputs) -> Option<CreateOutcome> {
+        self.send(HookEvent::Create {
+            depth: context.journal().depth(),
+            caller: inputs.caller(),
+            value: inputs.value(),
+            init_code: inputs.init_code().clone(),
+            gas_limit: inputs.gas_limit(),
+        });
+        None
+    }
+
+    fn create_end(
+        &mut self,
+        context: &mut CTX,
+        _inputs: &CreateInputs,
+        outcome: &mut CreateOutcome,
+    ) {
+        self.send(HookEvent::CreateEnd {
+            depth: context.journal().depth(),
+            result: outcome.result.result,
+            address: outcome.address,
+            gas_spent: outcome.result.gas.total_gas_spent(),
+        });
+    }
+
+    fn selfdestruct(&mut self, contract: Address, target: Address, value: U256) {
+        self.send(HookEvent::Selfdestruct {
+            contract,
+            target,
+            value,
+        });
+    }
+
+    fn refund(&mut self, _context: &mut CTX, event: RefundEvent) {
+        self.send(HookEvent::Refund(event));
+    }
+}
+
+/// Inspector that handles [`HookEvent`]s asynchronously.
+///
+/// Events are produced by [`ChannelInspector`] and consumed with [`drive_async_inspector`].
+pub trait AsyncInspector {
+    /// Handles a single hook event.
+    fn on_event(&mut self, event: HookEvent) -> impl Future<Output = ()> + Send;
+}
+
+/// Receives events until the [`ChannelInspector`] is dropped and forwards them to the inspector.
+pub async fn drive_async_inspector<I: AsyncInspector>(
+    mut receiver: Receiver<HookEvent>,
+    inspector: &mut I,
+) {
+    while let Some(event) = receiver.recv().await {
+        inspector.on_event(event).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::InspectEvm;
+    use context::{Context, TxEnv};
+    use database::{BenchmarkDB, BENCH_CALLER, BENCH_TARGET};
+    use handler::{MainBuilder, MainContext};
+    use primitives::TxKind;
+    use state::bytecode::{opcode, Bytecode};
+
+    fn run(inspector: ChannelInspector) -> ChannelInspector {
+        let bytecode = Bytecode::new_raw(
+            [
+                opcode::PUSH1,
+                0x1,
+                opcode::PUSH1,
+                0x2,
+                opcode::ADD,
+                opcode::STOP,
+            ]
+            .to_vec()
+            .into(),
+        );
+        let mut evm = Context::mainnet()
+            .with_db(BenchmarkDB::new_bytecode(bytecode))
+            .build_mainnet_with_inspector(inspector);
+        evm.inspect_one_tx(
+            TxEnv::builder()
+                .caller(BENCH_CALLER)
+                .kind(TxKind::Call(BENCH_TARGET))
+                .gas_limit(100_000)
+                .build()
+                .unwrap(),
+        )
+        .unwrap();
+        evm.inspector
+    }
+
+    #[test]
+    fn sends_events() {
+        let (inspector, mut receiver) = ChannelInspector::new(64);
+        run(inspector.with_steps());
+
+        let mut events = Vec::new();
+        while let Ok(event) = receiver.try_recv() {
+            events.push(event);
+        }
+        // Call, four steps and call end.
+        assert_eq!(events.len(), 6);
+        assert!(matches!(events[0], HookEvent::Call { target, .. } if target == BENCH_TARGET));
+        assert!(matches!(
+            events[1],
+            HookEvent::Step {
+                pc: 0,
+                opcode: opcode::PUSH1,
+                ..
+            }
+        ));
+        assert!(matches!(
+            events[5],
+            HookEvent::CallEnd {
+                result: InstructionResult::Stop,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn drops_events_when_full() {
+        let (inspector, _receiver) = ChannelInspector::new(1);
+        let inspector = run(inspector.with_steps().with_backpressure(Backpressure::Drop));
+        assert_eq!(inspector.dropped(), 5);
+    }
+}
@@ -0,0 +1,373 @@
+//! DeploymentInspector - Inspector that records contract creations.
+extern crate alloc;
+
+use crate::{Inspector, JournalExt};
+use alloc::vec::Vec;
+use context::{Cfg, ContextTr, JournalTr};
+use interpreter::{
+    CallInputs, CallOutcome, CreateInputs, CreateOutcome, CreateScheme, InstructionResult,
+    InterpreterTypes,
+};
+use primitives::{Address, B256, U256};
+
+/// Single contract creation.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Deployment {
+    /// Call depth of the create frame.
+    pub depth: usize,
+    /// Address of the deployer.
+    pub caller: Address,
+    /// Creation scheme.
+    pub scheme: CreateScheme,
+    /// Salt of `CREATE2`.
+    pub salt: Option<U256>,
+    /// Address derived from the caller nonce or the `CREATE2` salt.
+    ///
+    /// It is set even if the creation failed.
+    pub address: Option<Address>,
+    /// Value transferred to the created contract.
+    pub value: U256,
+    /// Keccak256 hash of the init code.
+    pub init_code_hash: B256,
+    /// Hash of the deployed code, set only if the creation succeeded.
+    pub code_hash: Option<B256>,
+    /// Result of the create frame.
+    pub result: InstructionResult,
+}
+
+impl Deployment {
+    /// Returns `true` if the contract was deployed.
+    pub const fn is_success(&self) -> bool {
+        self.result.is_ok()
+    }
+
+    /// Returns `true` if the contract was created with `CREATE2`.
+    pub const fn is_create2(&self) -> bool {
+        matches!(self.scheme, CreateScheme::Create2 { .. })
+    }
+}
+
+/// Expected result of a `CREATE2`, see [`DeploymentInspector::verify_create2`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ExpectedCreate2 {
+    /// Address of the factory executing `CREATE2`.
+    pub factory: Address,
+    /// Salt passed to `CREATE2`.
+    pub salt: U256,
+    /// Address the contract is expected to be deployed at.
+    pub address: Address,
+}
+
+/// Reason an [`ExpectedCreate2`] was not met.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Create2Mismatch {
+    /// Factory did not execute `CREATE2` with the salt.
+    Missing(ExpectedCreate2),
+    /// Contract was deployed at another address.
+    Address {
+        /// Expected deployment.
+        expected: ExpectedCreate2,
+        /// Address the contract was deployed at.
+        got: Option<Address>,
+    },
+    /// `CREATE2` was executed at the expected address but the creation failed.
+    Failed {
+        /// Expected deployment.
+        expected: ExpectedCreate2,
+        /// Result of the create frame.
+        result: InstructionResult,
+    },
+}
+
+/// Inspector that records every `CREATE`/`CREATE2` of a transaction, including top level creates.
+///
+/// Deployments are ordered by the start of the create frame and are cleared when the next
+/// transaction starts.
+#[derive(Clone, Debug, Default)]
+pub struct DeploymentInspector {
+    deployments: Vec<Deployment>,
+    /// Indices of the deployments that are still executing.
+    stack: Vec<usize>,
+}
+
+impl DeploymentInspector {
+    /// Creates a new deployment inspector.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the deployments of the current or last transaction.
+    pub fn deployments(&self) -> &[Deployment] {
+        &self.deployments
+    }
+
+    /// Returns the successful deployments of the current or last transaction.
+    pub fn deployed(&self) -> impl Iterator<Item = &Deployment> {
+        self.deployments.iter().filter(|d| d.is_success())
+    }
+
+    /// Returns the `CREATE2` deployments of the current or last transaction.
+    ///
+    /// Each deployment contains the factory as the caller, the salt, the init code hash and the
+    /// resulting address.
+    pub fn create2_deployments(&self) -> impl Iterator<Item = &Deployment> {
+        self.deployments.iter().filter(|d| d.is_create2())
+    }
+
+    /// Verifies that every expected `CREATE2` was executed and deployed a contract at the
+    /// expected address.
+    ///
+    /// Expectations are matched to the deployments by the factory and the salt. If the factory
+    /// executed `CREATE2` with the same salt several times, the expectation is met if any of them
+    /// deployed at the expected address. Returns the expectations that were not met, in the order
+    /// they were given.
+    pub fn verify_create2<'a>(
+        &self,
+        expected: impl IntoIterator<Item = &'a ExpectedCreate2>,
+    ) -> Vec<Create2Mismatch> {
+        expected
+            .into_iter()
+            .filter_map(|expected| {
+                let mut candidates = self.deployments.iter().filter(|d| {
+                    d.is_create2() && d.caller == expected.factory && d.salt == Some(expected.salt)
+                });
+                let Some(first) = candidates.clone().next() else {
+                    return Some(Create2Mismatch::Missing(expected.clone()));
+                };
+                match candidates.find(|d| d.address == Some(expected.address)) {
+                    Some(d) if d.is_success() => None,
+                    Some(d) => Some(Create2Mismatch::Failed {
+                        expected: expected.clone(),
+                        result: d.result,
+                    }),
+                    None => Some(Create2Mismatch::Address {
+                        expected: expected.clone(),
+                        got: first.address,
+                    }),
+                }
+            })
+            .collect()
+    }
+
+    /// Takes the deployments, leaving the inspector empty.
+    pub fn take(&mut self) -> Vec<Deployment> {
+        self.stack.clear();
+        core::mem::take(&mut self.deployments)
+    }
+
+    /// Clears the recorded deployments.
+    pub fn clear(&mut self) {
+        self.deployments.clear();
+        self.stack.clear();
+    }
+}
+
+impl<CTX, INTR> Inspector<CTX, INTR> for DeploymentInspector
+where
+    CTX: ContextTr<Journal: JournalExt>,
+    INTR: InterpreterTypes,
+{
+    fn call(&mut self, context: &mut CTX, _inputs: &mut CallInputs) -> Option<CallOutcome> {
+        if context.journal().depth() == 0 {
+            self.clear();
+        }
+        None
+    }
+
+    fn create(&mut self, context: &mut CTX, inputs: &mut CreateInputs) -> Option<CreateOutcome> {
+        let depth = context.journal().depth();
+        if depth == 0 {
+            self.clear();
+        }
+
+        let caller = inputs.caller();
+        let init_code_hash = inputs.init_code_hash();
+        // The caller nonce is bumped when the frame is created, so the current nonce is the one
+        // used for the address. `CreateInputs::created_address` is not used as it caches the result.
+        let nonce = context
+            .journal()
+            .evm_state()
+            .get(&caller)
+            .map(|account| account.info.nonce);
+        let overridden = context.cfg().create_address_fn().and_then(|f| {
+            nonce.and_then(|nonce| f(caller, inputs.scheme(), nonce, inputs.init_code()))
+        });
+        let address = match inputs.scheme() {
+            _ if overridden.is_some() => overridden,
+            CreateScheme::Create => nonce.map(|nonce| caller.create(nonce)),
+            CreateScheme::Create2 { salt } => {
+                Some(caller.create2(salt.to_be_bytes(), init_code_hash))
+            }
+            CreateScheme::Custom { address } => Some(address),
+        };
+        let salt = match inputs.scheme() {
+            CreateScheme::Create2 { salt } => Some(salt),
+            _ => None,
+        };
+
+        self.stack.push(self.deployments.len());
+        self.deployments.push(Deployment {
+            depth,
+            caller,
+            scheme: inputs.scheme(),
+            salt,
+            address,
+            value: inputs.value(),
+            init_code_hash,
+            code_hash: None,
+            result: InstructionResult::Stop,
+        });
+        None
+    }
+
+    fn create_end(
+        &mut self,
+        context: &mut CTX,
+        _inputs: &CreateInputs,
+        outcome: &mut CreateOutcome,
+    ) {
+        let Some(index) = self.stack.pop() else {
+            return;
+        };
+        let deployment = &mut self.deployments[index];
+        deployment.result = outcome.result.result;
+        if let Some(address) = outcome.address {
+            deployment.address = Some(address);
+            if outcome.result.is_ok() {
+                deployment.code_hash = context
+                    .journal()
+                    .evm_state()
+                    .get(&address)
+                    .map(|account| account.info.code_hash);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::InspectEvm;
+    use context::{Context, TxEnv};
+    use database::{BenchmarkDB, BENCH_CALLER, BENCH_TARGET};
+    use handler::{MainBuilder, MainContext};
+    use primitives::{keccak256, TxKind, KECCAK_EMPTY};
+    use state::bytecode::{opcode, Bytecode};
+
+    #[test]
+    fn top_level_create() {
+        // Deploys `[STOP]`.
+        let init_code = [
+            opcode::PUSH1,
+            0x00,
+            opcode::PUSH1,
+            0x00,
+            opcode::MSTORE8,
+            opcode::PUSH1,
+            0x01,
+            opcode::PUSH1,
+            0x00,
+            opcode::RETURN,
+        ];
+        let mut evm = Context::mainnet()
+            .with_db(BenchmarkDB::new_bytecode(Bytecode::default()))
+            .build_mainnet_with_inspector(DeploymentInspector::new());
+        let result = evm
+            .inspect_one_tx(
+                TxEnv::builder()
+                    .caller(BENCH_CALLER)
+                    .kind(TxKind::Create)
+                    .data(init_code.to_vec().into())
+                    .gas_limit(1_000_000)
+                    .build()
+                    .unwrap(),
+            )
+            .unwrap();
+        assert!(result.is_success());
+
+        let deployments = evm.inspector.deployments();
+        assert_eq!(deployments.len(), 1);
+        let deployment = &deployments[0];
+        assert_eq!(deployment.depth, 0);
+        assert_eq!(deployment.scheme, CreateScheme::Create);
+        assert_eq!(deployment.address, Some(BENCH_CALLER.create(0)));
+        assert_eq!(deployment.init_code_hash, keccak256(init_code));
+        assert_eq!(deployment.code_hash, Some(keccak256([opcode::STOP])));
+        assert!(deployment.is_success());
+    }
+
+    #[test]
+    fn nested_create2() {
+        // CREATE2 with empty init code and salt 0x42.
+        let bytecode = Bytecode::new_raw(
+            [
+                opcode::PUSH1,
+                0x42,
+                opcode::PUSH1,
+                0x00,
+                opcode::PUSH1,
+                0x00,
+                opcode::PUSH1,
+                0x00,
+                opcode::CREATE2,
+                opcode::STOP,
+            ]
+            .to_vec()
+            .into(),
+        );
+        let mut evm = Context::mainnet()
+            .with_db(BenchmarkDB::new_bytecode(bytecode))
+            .build_mainnet_with_inspector(DeploymentInspector::new());
+        evm.inspect_one_tx(
+            TxEnv::builder()
+                .caller(BENCH_CALLER)
+                .kind(TxKind::Call(BENCH_TARGET))
+                .gas_limit(1_000_000)
+                .build()
+                .unwrap(),
+        )
+        .unwrap();
+
+        let deployments = evm.inspector.deployments();
+        assert_eq!(deployments.len(), 1);
+        let deployment = &deployments[0];
+        let salt = U256::from(0x42);
+        assert_eq!(deployment.depth, 1);
+        assert_eq!(deployment.caller, BENCH_TARGET);
+        assert_eq!(deployment.salt, Some(salt));
+        assert_eq!(
+            deployment.address,
+            Some(BENCH_TARGET.create2(salt.to_be_bytes(), KECCAK_EMPTY))
+        );
+        assert_eq!(deployment.init_code_hash, KECCAK_EMPTY);
+        assert_eq!(deployment.code_hash, Some(KECCAK_EMPTY));
+        assert!(deployment.is_success());
+        assert_eq!(evm.inspector.create2_deployments().count(), 1);
+
+        let address = BENCH_TARGET.create2(salt.to_be_bytes(), KECCAK_EMPTY);
+        let expected = ExpectedCreate2 {
+            factory: BENCH_TARGET,
+            salt,
+            address,
+        };
+        let other_salt = ExpectedCreate2 {
+            salt: U256::from(1),
+            ..expected.clone()
+        };
+        let other_address = ExpectedCreate2 {
+            address: Address::ZERO,
+            ..expected.clone()
+        };
+        assert_eq!(
+            evm.inspector
+                .verify_create2([&expected, &other_salt, &other_address]),
+            [
+                Create2Mismatch::Missing(other_salt.clone()),
+                Create2Mismatch::Address {
+                    expected: other_address.clone(),
+                    got: Some(address),
+                },
+            ]
+        );
+    }
+}
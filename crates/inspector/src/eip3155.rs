@@ -1,18 +1,57 @@
-use crate::{inspectors::GasInspector, Inspector};
+use crate::{inspectors::GasInspector, Inspector, PrecompileIo};
 use context::{Cfg, ContextTr, JournalTr, Transaction};
 use interpreter::{
-    interpreter_types::{Jumps, LoopControl, MemoryTr, StackTr},
+    interpreter_types::{InputsTr, Jumps, LoopControl, MemoryTr, ReturnData, StackTr},
     CallInputs, CallOutcome, CreateInputs, CreateOutcome, Interpreter, InterpreterResult,
     InterpreterTypes, Stack,
 };
-use primitives::{hex, HashMap, B256, U256};
-use serde::Serialize;
-use state::bytecode::opcode::OpCode;
-use std::io::Write;
+use primitives::{hex, Address, Bytes, HashMap, B256, U256};
+use serde::{Deserialize, Serialize};
+use state::bytecode::opcode::{self, OpCode};
+use std::{
+    borrow::Cow,
+    io::{self, Read, Write},
+};
+
+/// Encoding of the trace written by [`TracerEip3155`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum TraceFormat {
+    /// JSON object per line as specified by EIP-3155.
+    #[default]
+    Json,
+    /// CBOR encoded records, each prefixed by its length as a big-endian `u32`.
+    ///
+    /// Numbers are encoded as integers and the stack as bytes, which makes the trace
+    /// considerably smaller and faster to write than JSON. Use [`binary_trace_to_json`] to
+    /// convert it to the JSON format.
+    Cbor,
+}
+
+/// Optional fields captured by [`TracerEip3155`] for every step, similar to the configuration of
+/// geth's struct logger.
+///
+/// The default captures the full stack and nothing else.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct CaptureConfig {
+    /// Captures the memory.
+    pub memory: bool,
+    /// Maximum number of bytes captured from the start of the memory.
+    pub memory_limit: Option<usize>,
+    /// Maximum number of items captured from the top of the stack, `Some(0)` disables the stack.
+    pub stack_limit: Option<usize>,
+    /// Captures the return data of the last call, `0x` is written otherwise.
+    pub return_data: bool,
+    /// Captures the storage of the current contract at `SLOAD` and `SSTORE`.
+    ///
+    /// Only the slots read or written in the transaction are known, the storage is not loaded
+    /// from the database.
+    pub storage: bool,
+}
 
 /// [EIP-3155](https://eips.ethereum.org/EIPS/eip-3155) tracer [Inspector].
 pub struct TracerEip3155 {
     output: Box<dyn Write>,
+    format: TraceFormat,
     gas_inspector: GasInspector,
     /// Print summary of the execution.
     print_summary: bool,
@@ -24,13 +63,21 @@ pub struct TracerEip3155 {
     state_gas: u64,
     refunded: i64,
     mem_size: usize,
-    include_memory: bool,
+    capture: CaptureConfig,
     memory: Option<String>,
+    return_data: Option<String>,
+    /// Slot accessed by the current step and the written value for `SSTORE`.
+    storage_access: Option<(Address, U256, Option<U256>)>,
+    /// Slots read or written in the transaction.
+    storage: HashMap<Address, HashMap<U256, U256>>,
+    precompile_io_limit: Option<usize>,
+    precompile: Option<PrecompileIo>,
 }
 
 impl std::fmt::Debug for TracerEip3155 {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("TracerEip3155")
+            .field("format", &self.format)
             .field("gas_inspector", &self.gas_inspector)
             .field("print_summary", &self.print_summary)
             .field("stack", &self.stack)
@@ -41,15 +88,20 @@ impl std::fmt::Debug for TracerEip3155 {
             .field("state_gas", &self.state_gas)
             .field("refunded", &self.refunded)
             .field("mem_size", &self.mem_size)
-            .field("include_memory", &self.include_memory)
+            .field("capture", &self.capture)
             .field("memory", &self.memory)
+            .field("return_data", &self.return_data)
+            .field("storage_access", &self.storage_access)
+            .field("storage", &self.storage)
+            .field("precompile_io_limit", &self.precompile_io_limit)
+            .field("precompile", &self.precompile)
             .finish()
     }
 }
 
 // # Output
 // The CUT MUST output a `json` object for EACH operation.
-#[derive(Serialize)]
+#[derive(Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 struct Output<'a> {
     // Required fields:
@@ -59,30 +111,30 @@ struct Output<'a> {
     depth: u64,
     /// Name of the operation
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    op_name: Option<&'static str>,
+    op_name: Option<Cow<'static, str>>,
     /// OpCode
     op: u8,
     /// Gas left before executing this operation
-    #[serde(serialize_with = "serde_hex_u64")]
+    #[serde(with = "serde_hex_u64")]
     gas: u64,
     /// State gas reservoir (EIP-8037)
-    #[serde(serialize_with = "serde_hex_u64")]
+    #[serde(with = "serde_hex_u64")]
     reservoir: u64,
     /// State gas spent (EIP-8037)
-    #[serde(serialize_with = "serde_hex_u64")]
+    #[serde(with = "serde_hex_u64")]
     state_gas: u64,
     /// Gas cost of this operation
-    #[serde(serialize_with = "serde_hex_u64")]
+    #[serde(with = "serde_hex_u64")]
     gas_cost: u64,
     /// Array of all values on the stack
-    stack: &'a [U256],
+    stack: Cow<'a, [U256]>,
     /// Data returned by the function call
-    return_data: &'static str,
+    return_data: Cow<'static, str>,
     /// Amount of **global** gas refunded
-    #[serde(serialize_with = "serde_hex_u64")]
+    #[serde(with = "serde_hex_u64")]
     refund: u64,
     /// Size of memory array
-    #[serde(serialize_with = "serde_hex_u64")]
+    #[serde(with = "serde_hex_u64")]
     mem_size: u64,
 
     // Optional fields:
@@ -100,8 +152,30 @@ struct Output<'a> {
     return_stack: Option<Vec<String>>,
 }
 
+// Input and output of a precompile call, written after the step of the call instruction.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PrecompileOutput<'a> {
+    /// Address of the precompile
+    precompile: Address,
+    /// Input of the call, truncated to the limit
+    input: Cow<'a, Bytes>,
+    /// Length of the input
+    input_len: usize,
+    /// Output of the call, truncated to the limit
+    output: Cow<'a, Bytes>,
+    /// Length of the output
+    output_len: usize,
+    /// Gas spent by the precompile
+    #[serde(with = "serde_hex_u64")]
+    gas_cost: u64,
+    /// Description of an error
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
 // # Summary and error handling
-#[derive(Serialize)]
+#[derive(Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 struct Summary {
     // Required fields:
@@ -110,7 +184,7 @@ struct Summary {
     /// Return values of the function
     output: String,
     /// All gas used by the transaction
-    #[serde(serialize_with = "serde_hex_u64")]
+    #[serde(with = "serde_hex_u64")]
     gas_used: u64,
     /// Bool whether transaction was executed successfully
     pass: bool,
@@ -124,6 +198,14 @@ struct Summary {
     fork: Option<String>,
 }
 
+// Record of the binary formats, the JSON format writes the inner values.
+#[derive(Serialize, Deserialize)]
+enum Record<'a> {
+    Step(Output<'a>),
+    Precompile(PrecompileOutput<'a>),
+    Summary(Summary),
+}
+
 impl TracerEip3155 {
     /// Creates a new EIP-3155 tracer with the given output writer, by first wrapping it in a
     /// [`BufWriter`](std::io::BufWriter).
@@ -140,11 +222,15 @@ impl TracerEip3155 {
     pub fn new(output: Box<dyn Write>) -> Self {
         Self {
             output,
+            format: TraceFormat::Json,
             gas_inspector: GasInspector::new(),
             print_summary: true,
-            include_memory: false,
+            capture: CaptureConfig::default(),
             stack: Default::default(),
             memory: Default::default(),
+            return_data: None,
+            storage_access: None,
+            storage: Default::default(),
             pc: 0,
             opcode: 0,
             gas: 0,
@@ -152,6 +238,8 @@ impl TracerEip3155 {
             state_gas: 0,
             refunded: 0,
             mem_size: 0,
+            precompile_io_limit: None,
+            precompile: None,
         }
     }
 
@@ -160,6 +248,12 @@ impl TracerEip3155 {
         self.output = writer;
     }
 
+    /// Sets the encoding of the trace, [`TraceFormat::Json`] by default.
+    pub const fn with_format(mut self, format: TraceFormat) -> Self {
+        self.format = format;
+        self
+    }
+
     /// Don't include a summary at the end of the trace
     pub const fn without_summary(mut self) -> Self {
         self.print_summary = false;
@@ -168,7 +262,28 @@ impl TracerEip3155 {
 
     /// Include a memory field for each step. This significantly increases processing time and output size.
     pub const fn with_memory(mut self) -> Self {
-        self.include_memory = true;
+        self.capture.memory = true;
+        self
+    }
+
+    /// Sets the optional fields captured for each step, e.g. to limit the memory of huge
+    /// transactions.
+    pub const fn with_capture_config(mut self, capture: CaptureConfig) -> Self {
+        self.capture = capture;
+        self
+    }
+
+    /// Returns the optional fields captured for each step.
+    pub const fn capture_config(&self) -> &CaptureConfig {
+        &self.capture
+    }
+
+    /// Include the input and output of precompile calls, truncated to `limit` bytes, e.g.
+    /// [`DEFAULT_PRECOMPILE_IO_LIMIT`](crate::DEFAULT_PRECOMPILE_IO_LIMIT).
+    ///
+    /// They are written as a separate line after the step of the call instruction.
+    pub const fn with_precompile_io(mut self, limit: usize) -> Self {
+        self.precompile_io_limit = Some(limit);
         self
     }
 
@@ -186,6 +301,10 @@ impl TracerEip3155 {
             state_gas,
             refunded,
             mem_size,
+            return_data,
+            storage_access,
+            storage,
+            precompile,
             ..
         } = self;
         *gas_inspector = GasInspector::new();
@@ -197,6 +316,10 @@ impl TracerEip3155 {
         *state_gas = 0;
         *refunded = 0;
         *mem_size = 0;
+        *return_data = None;
+        *storage_access = None;
+        storage.clear();
+        *precompile = None;
     }
 
     fn print_summary(&mut self, result: &InterpreterResult, context: &mut impl ContextTr) {
@@ -213,11 +336,11 @@ impl TracerEip3155 {
             time: None,
             fork: Some(spec.to_string()),
         };
-        let _ = self.write_value(&value);
+        let _ = self.write_record(&Record::Summary(value));
     }
 
-    fn write_value(&mut self, value: &impl serde::Serialize) -> std::io::Result<()> {
-        write_value(&mut *self.output, value)
+    fn write_record(&mut self, record: &Record<'_>) -> io::Result<()> {
+        write_record(&mut *self.output, self.format, record)
     }
 }
 
@@ -244,10 +367,30 @@ where
         self.gas_inspector.step(&interp.gas);
         self.stack.clear();
         interp.stack.clone_into(&mut self.stack);
-        self.memory = if self.include_memory {
-            Some(hex::encode_prefixed(
-                interp.memory.slice(0..interp.memory.size()).as_ref(),
-            ))
+        if let Some(limit) = self.capture.stack_limit {
+            self.stack.drain(..self.stack.len().saturating_sub(limit));
+        }
+        self.memory = if self.capture.memory {
+            let size = interp.memory.size();
+            let len = self
+                .capture
+                .memory_limit
+                .map_or(size, |limit| size.min(limit));
+            Some(hex::encode_prefixed(interp.memory.slice(0..len).as_ref()))
+        } else {
+            None
+        };
+        self.return_data = self
+            .capture
+            .return_data
+            .then(|| hex::encode_prefixed(interp.return_data.buffer()));
+        self.storage_access = if self.capture.storage {
+            let address = interp.input.target_address();
+            match (interp.bytecode.opcode(), interp.stack.data()) {
+                (opcode::SLOAD, [.., key]) => Some((address, *key, None)),
+                (opcode::SSTORE, [.., value, key]) => Some((address, *key, Some(*value))),
+                _ => None,
+            }
         } else {
             None
         };
@@ -265,6 +408,34 @@ where
 
     fn step_end(&mut self, interp: &mut Interpreter<INTR>, context: &mut CTX) {
         self.gas_inspector.step_end(&interp.gas);
+        let error = interp
+            .bytecode
+            .action()
+            .as_ref()
+            .and_then(|a| a.instruction_result())
+            .map(|ir| format!("{ir:?}"));
+        let storage = match self.storage_access.take() {
+            Some((address, key, value)) if error.is_none() => {
+                // `SLOAD` pushed the value of the slot.
+                let value = value.or_else(|| interp.stack.data().last().copied());
+                let slots = self.storage.entry(address).or_default();
+                if let Some(value) = value {
+                    slots.insert(key, value);
+                }
+                Some(
+                    slots
+                        .iter()
+                        .map(|(key, value)| {
+                            (
+                                hex::encode_prefixed(B256::from(*key)),
+                                hex::encode_prefixed(B256::from(*value)),
+                            )
+                        })
+                        .collect(),
+                )
+            }
+            _ => None,
+        };
         let value = Output {
             pc: self.pc,
             op: self.opcode,
@@ -272,29 +443,54 @@ where
             reservoir: self.reservoir,
             state_gas: self.state_gas,
             gas_cost: self.gas_inspector.last_gas_cost(),
-            stack: &self.stack,
+            stack: Cow::Borrowed(&self.stack),
             depth: context.journal_mut().depth() as u64,
-            return_data: "0x",
+            return_data: self
+                .return_data
+                .take()
+                .map_or(Cow::Borrowed("0x"), Cow::Owned),
             refund: self.refunded as u64,
             mem_size: self.mem_size as u64,
 
-            op_name: OpCode::new(self.opcode).map(|i| i.as_str()),
-            error: interp
-                .bytecode
-                .action()
-                .as_ref()
-                .and_then(|a| a.instruction_result())
-                .map(|ir| format!("{ir:?}")),
+            op_name: OpCode::new(self.opcode).map(|i| Cow::Borrowed(i.as_str())),
+            error,
             memory: self.memory.take(),
-            storage: None,
+            storage,
             return_stack: None,
         };
-        let _ = write_value(&mut self.output, &value);
+        let _ = write_record(&mut *self.output, self.format, &Record::Step(value));
+    }
+
+    fn call(&mut self, context: &mut CTX, inputs: &mut CallInputs) -> Option<CallOutcome> {
+        self.precompile = self
+            .precompile_io_limit
+            .and_then(|limit| PrecompileIo::call(context, inputs, limit));
+        None
     }
 
     fn call_end(&mut self, context: &mut CTX, _: &CallInputs, outcome: &mut CallOutcome) {
         self.gas_inspector.call_end(outcome);
 
+        // Precompiles don't call other contracts, this is the end of the last call.
+        if let (Some(mut precompile), Some(limit)) =
+            (self.precompile.take(), self.precompile_io_limit)
+        {
+            precompile.call_end(outcome, limit);
+            let value = PrecompileOutput {
+                precompile: precompile.address,
+                input: Cow::Borrowed(&precompile.input),
+                input_len: precompile.input_len,
+                output: Cow::Borrowed(&precompile.output),
+                output_len: precompile.output_len,
+                gas_cost: precompile.gas_spent,
+                error: precompile
+                    .result
+                    .filter(|result| !result.is_ok())
+                    .map(|result| format!("{result:?}")),
+            };
+            let _ = self.write_record(&Record::Precompile(value));
+        }
+
         if context.journal_mut().depth() == 0 {
             self.print_summary(&outcome.result, context);
             let _ = self.output.flush();
@@ -315,14 +511,196 @@ where
     }
 }
 
-fn write_value(
-    output: &mut dyn std::io::Write,
-    value: &impl serde::Serialize,
-) -> std::io::Result<()> {
-    serde_json::to_writer(&mut *output, value)?;
-    output.write_all(b"\n")
+/// Converts a trace written in the [`TraceFormat::Cbor`] format to the JSON format.
+///
+/// Returns the number of converted records.
+pub fn binary_trace_to_json(mut input: impl Read, mut output: impl Write) -> io::Result<usize> {
+    let mut records = 0;
+    let mut buf = Vec::new();
+    loop {
+        let mut len = [0u8; 4];
+        match input.read_exact(&mut len) {
+            Ok(()) => {}
+            Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => break,
+            Err(e) => return Err(e),
+        }
+        buf.resize(u32::from_be_bytes(len) as usize, 0);
+        input.read_exact(&mut buf)?;
+        let record: Record<'_> = ciborium::from_reader(buf.as_slice())
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))?;
+        write_record(&mut output, TraceFormat::Json, &record)?;
+        records += 1;
+    }
+    output.flush()?;
+    Ok(records)
+}
+
+fn write_record(
+    output: &mut dyn Write,
+    format: TraceFormat,
+    record: &Record<'_>,
+) -> io::Result<()> {
+    match format {
+        TraceFormat::Json => {
+            match record {
+                Record::Step(value) => serde_json::to_writer(&mut *output, value)?,
+                Record::Precompile(value) => serde_json::to_writer(&mut *output, value)?,
+                Record::Summary(value) => serde_json::to_writer(&mut *output, value)?,
+            }
+            output.write_all(b"\n")
+        }
+        TraceFormat::Cbor => {
+            let mut buf = Vec::new();
+            ciborium::into_writer(record, &mut buf)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))?;
+            let len = u32::try_from(buf.len())
+                .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "record too large"))?;
+            output.write_all(&len.to_be_bytes())?;
+            output.write_all(&buf)
+        }
+    }
+}
+
+/// Hex string in human readable formats, integer in binary formats.
+mod serde_hex_u64 {
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    pub(super) fn serialize<S: Serializer>(n: &u64, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            serializer.serialize_str(&format!("{:#x}", *n))
+        } else {
+            serializer.serialize_u64(*n)
+        }
+    }
+
+    pub(super) fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+        if deserializer.is_human_readable() {
+            let s = String::deserialize(deserializer)?;
+            u64::from_str_radix(s.trim_start_matches("0x"), 16).map_err(D::Error::custom)
+        } else {
+            u64::deserialize(deserializer)
+        }
+    }
 }
 
-fn serde_hex_u64<S: serde::Serializer>(n: &u64, serializer: S) -> Result<S::Ok, S::Error> {
-    serializer.serialize_str(&format!("{:#x}", *n))
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::InspectEvm;
+    use context::{Context, TxEnv};
+    use database::{BenchmarkDB, BENCH_CALLER, BENCH_TARGET};
+    use handler::{MainBuilder, MainContext};
+    use primitives::TxKind;
+    use state::bytecode::{opcode, Bytecode};
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Clone, Default)]
+    struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn trace(configure: impl FnOnce(TracerEip3155) -> TracerEip3155) -> Vec<u8> {
+        let bytecode = Bytecode::new_raw(
+            [
+                opcode::PUSH1,
+                0x1,
+                opcode::PUSH1,
+                0x0,
+                opcode::MSTORE,
+                opcode::PUSH1,
+                0x2,
+                opcode::PUSH1,
+                0x0,
+                opcode::SSTORE,
+                opcode::PUSH1,
+                0x0,
+                opcode::SLOAD,
+                opcode::POP,
+                opcode::PUSH1,
+                0x20,
+                opcode::PUSH1,
+                0x0,
+                opcode::RETURN,
+            ]
+            .to_vec()
+            .into(),
+        );
+        let buffer = SharedBuffer::default();
+        let tracer = configure(TracerEip3155::buffered(buffer.clone()));
+        let mut evm = Context::mainnet()
+            .with_db(BenchmarkDB::new_bytecode(bytecode))
+            .build_mainnet_with_inspector(tracer);
+        evm.inspect_tx(
+            TxEnv::builder()
+                .caller(BENCH_CALLER)
+                .kind(TxKind::Call(BENCH_TARGET))
+                .gas_limit(100_000)
+                .build()
+                .unwrap(),
+        )
+        .unwrap();
+        drop(evm);
+        let output = buffer.0.lock().unwrap().clone();
+        output
+    }
+
+    #[test]
+    fn binary_trace_converts_to_json() {
+        let json = trace(|tracer| tracer.with_memory());
+        let binary = trace(|tracer| tracer.with_memory().with_format(TraceFormat::Cbor));
+        assert!(binary.len() < json.len());
+
+        let mut converted = Vec::new();
+        let records = binary_trace_to_json(binary.as_slice(), &mut converted).unwrap();
+        // Twelve steps and the summary.
+        assert_eq!(records, 13);
+        assert_eq!(
+            String::from_utf8(converted).unwrap(),
+            String::from_utf8(json).unwrap()
+        );
+    }
+
+    #[test]
+    fn capture_config() {
+        let capture = CaptureConfig {
+            memory: true,
+            memory_limit: Some(4),
+            stack_limit: Some(1),
+            return_data: true,
+            storage: true,
+        };
+        let output = trace(|tracer| tracer.with_capture_config(capture).without_summary());
+        let steps: Vec<serde_json::Value> = String::from_utf8(output)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(steps.len(), 12);
+        for step in &steps {
+            assert!(step["stack"].as_array().unwrap().len() <= 1);
+            assert_eq!(step["returnData"], "0x");
+        }
+
+        // Memory is captured before the step, the `MSTORE` is visible at the next one.
+        assert_eq!(steps[3]["memory"], "0x00000000");
+        assert_eq!(steps[3]["memSize"], "0x20");
+
+        let slot = serde_json::json!({
+            "0x0000000000000000000000000000000000000000000000000000000000000000":
+                "0x0000000000000000000000000000000000000000000000000000000000000002"
+        });
+        assert_eq!(steps[5]["op"], opcode::SSTORE);
+        assert_eq!(steps[5]["storage"], slot);
+        assert_eq!(steps[7]["op"], opcode::SLOAD);
+        assert_eq!(steps[7]["storage"], slot);
+        assert!(steps[8].get("storage").is_none());
+    }
 }
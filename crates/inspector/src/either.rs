@@ -1,4 +1,4 @@
-use crate::inspector::Inspector;
+use crate::inspector::{Inspector, RefundEvent};
 use either::Either;
 use interpreter::{
     CallInputs, CallOutcome, CreateInputs, CreateOutcome, Interpreter, InterpreterTypes,
@@ -94,6 +94,14 @@ where
             Either::Right(inspector) => inspector.selfdestruct(contract, target, value),
         }
     }
+
+    #[inline]
+    fn refund(&mut self, context: &mut CTX, event: RefundEvent) {
+        match self {
+            Either::Left(inspector) => inspector.refund(context, event),
+            Either::Right(inspector) => inspector.refund(context, event),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,227 @@
+//! ExecutionLimitInspector - Inspector that bounds execution by instruction count and wall-clock time.
+use crate::Inspector;
+use context::{ContextTr, JournalTr};
+use interpreter::{
+    CallInputs, CallOutcome, CreateInputs, CreateOutcome, Gas, InstructionResult, Interpreter,
+    InterpreterResult, InterpreterTypes,
+};
+use primitives::Bytes;
+#[cfg(feature = "std")]
+use std::time::{Duration, Instant};
+
+/// Inspector that aborts a transaction after a number of instructions or a wall-clock duration.
+///
+/// Gas bounds the work of a transaction only as far as it is priced correctly, so services that
+/// execute untrusted calls, e.g. `eth_call` with a large gas cap, need limits independent of gas.
+/// When a limit is exceeded the current frame is halted with
+/// [`InstructionResult::StepLimitExceeded`] or [`InstructionResult::TimeLimitExceeded`] and so is
+/// every parent frame, so the transaction ends with the matching
+/// [`HaltReason`](context::result::HaltReason).
+///
+/// Limits apply to each transaction, the counters restart when the next transaction starts.
+/// Instructions are counted over all frames, precompiles are not instructions and are only bounded
+/// by the time limit, which is checked before every instruction and call.
+#[derive(Clone, Debug, Default)]
+pub struct ExecutionLimitInspector {
+    max_steps: Option<u64>,
+    steps: u64,
+    #[cfg(feature = "std")]
+    timeout: Option<Duration>,
+    #[cfg(feature = "std")]
+    deadline: Option<Instant>,
+    exceeded: Option<InstructionResult>,
+}
+
+impl ExecutionLimitInspector {
+    /// Creates a new inspector without limits.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Halts the transaction when it executes more than `max_steps` instructions.
+    pub const fn with_max_steps(mut self, max_steps: u64) -> Self {
+        self.max_steps = Some(max_steps);
+        self
+    }
+
+    /// Halts the transaction when it runs longer than `timeout`.
+    #[cfg(feature = "std")]
+    pub const fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Returns the number of instructions executed by the current or last transaction.
+    pub const fn steps(&self) -> u64 {
+        self.steps
+    }
+
+    /// Returns the halt result of the exceeded limit, [`None`] if the current or last transaction
+    /// stayed within the limits.
+    pub const fn exceeded(&self) -> Option<InstructionResult> {
+        self.exceeded
+    }
+
+    fn tx_start<CTX: ContextTr>(&mut self, context: &mut CTX) {
+        if context.journal().depth() != 0 {
+            return;
+        }
+        self.steps = 0;
+        self.exceeded = None;
+        #[cfg(feature = "std")]
+        {
+            self.deadline = self.timeout.map(|timeout| Instant::now() + timeout);
+        }
+    }
+
+    /// Checks the time limit and returns the exceeded limit, if any.
+    fn check_time(&mut self) -> Option<InstructionResult> {
+        #[cfg(feature = "std")]
+        if self.exceeded.is_none()
+            && self
+                .deadline
+                .is_some_and(|deadline| Instant::now() >= deadline)
+        {
+            self.exceeded = Some(InstructionResult::TimeLimitExceeded);
+        }
+        self.exceeded
+    }
+}
+
+fn halted_result(result: InstructionResult, gas_limit: u64) -> InterpreterResult {
+    let mut gas = Gas::new(gas_limit);
+    gas.spend_all();
+    InterpreterResult::new(result, Bytes::new(), gas)
+}
+
+fn halt_result(result: &mut InterpreterResult, exceeded: InstructionResult) {
+    result.result = exceeded;
+    result.output = Bytes::new();
+    result.gas.spend_all();
+}
+
+impl<CTX: ContextTr, INTR: InterpreterTypes> Inspector<CTX, INTR> for ExecutionLimitInspector {
+    fn step(&mut self, interp: &mut Interpreter<INTR>, _context: &mut CTX) {
+        if self.exceeded.is_none() {
+            self.steps += 1;
+            if self
+                .max_steps
+                .is_some_and(|max_steps| self.steps > max_steps)
+            {
+                self.exceeded = Some(InstructionResult::StepLimitExceeded);
+            }
+        }
+        if let Some(exceeded) = self.check_time() {
+            interp.halt(exceeded);
+        }
+    }
+
+    fn call(&mut self, context: &mut CTX, inputs: &mut CallInputs) -> Option<CallOutcome> {
+        self.tx_start(context);
+        self.check_time().map(|exceeded| {
+            CallOutcome::new(
+                halted_result(exceeded, inputs.gas_limit),
+                inputs.return_memory_offset.clone(),
+            )
+        })
+    }
+
+    fn call_end(&mut self, _context: &mut CTX, _inputs: &CallInputs, outcome: &mut CallOutcome) {
+        if let Some(exceeded) = self.check_time() {
+            halt_result(&mut outcome.result, exceeded);
+        }
+    }
+
+    fn create(&mut self, context: &mut CTX, inputs: &mut CreateInputs) -> Option<CreateOutcome> {
+        self.tx_start(context);
+        self.check_time()
+            .map(|exceeded| CreateOutcome::new(halted_result(exceeded, inputs.gas_limit()), None))
+    }
+
+    fn create_end(
+        &mut self,
+        _context: &mut CTX,
+        _inputs: &CreateInputs,
+        outcome: &mut CreateOutcome,
+    ) {
+        if let Some(exceeded) = self.check_time() {
+            halt_result(&mut outcome.result, exceeded);
+            outcome.address = None;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::InspectEvm;
+    use context::{
+        result::{ExecutionResult, HaltReason},
+        Context, TxEnv,
+    };
+    use database::{BenchmarkDB, BENCH_CALLER, BENCH_TARGET};
+    use handler::{MainBuilder, MainContext};
+    use primitives::TxKind;
+    use state::bytecode::{opcode, Bytecode};
+
+    fn run(inspector: ExecutionLimitInspector) -> (ExecutionResult, ExecutionLimitInspector) {
+        // Loops until the gas runs out.
+        let bytecode = [opcode::JUMPDEST, opcode::PUSH0, opcode::JUMP];
+        let mut evm = Context::mainnet()
+            .with_db(BenchmarkDB::new_bytecode(Bytecode::new_raw(
+                bytecode.to_vec().into(),
+            )))
+            .build_mainnet_with_inspector(inspector);
+        let result = evm
+            .inspect_one_tx(
+                TxEnv::builder()
+                    .caller(BENCH_CALLER)
+                    .kind(TxKind::Call(BENCH_TARGET))
+                    .gas_limit(1_000_000)
+                    .build()
+                    .unwrap(),
+            )
+            .unwrap();
+        (result, evm.inspector)
+    }
+
+    #[test]
+    fn step_limit_halts() {
+        let (result, inspector) = run(ExecutionLimitInspector::new().with_max_steps(100));
+        assert!(matches!(
+            result,
+            ExecutionResult::Halt {
+                reason: HaltReason::StepLimitExceeded,
+                ..
+            }
+        ));
+        assert_eq!(inspector.steps(), 101);
+        assert_eq!(
+            inspector.exceeded(),
+            Some(InstructionResult::StepLimitExceeded)
+        );
+
+        let (result, inspector) = run(ExecutionLimitInspector::new());
+        assert!(matches!(
+            result,
+            ExecutionResult::Halt {
+                reason: HaltReason::OutOfGas(_),
+                ..
+            }
+        ));
+        assert_eq!(inspector.exceeded(), None);
+    }
+
+    #[test]
+    fn time_limit_halts() {
+        let (result, inspector) = run(ExecutionLimitInspector::new().with_timeout(Duration::ZERO));
+        assert!(matches!(
+            result,
+            ExecutionResult::Halt {
+                reason: HaltReason::TimeLimitExceeded,
+                ..
+            }
+        ));
+        assert_eq!(inspector.steps(), 0);
+    }
+}
@@ -1,5 +1,20 @@
 //! GasIspector. Helper Inspector to calculate gas for others.
-use interpreter::{CallOutcome, CreateOutcome, Gas};
+extern crate alloc;
+
+use crate::{
+    labels::{Labels, Selector},
+    Inspector, PrecompileIo,
+};
+use alloc::{collections::BTreeMap, string::String, sync::Arc, vec::Vec};
+use context::ContextTr;
+use core::fmt::Write;
+use interpreter::{
+    interpreter_types::{Jumps, LegacyBytecode, StackTr},
+    CallInputs, CallOutcome, CallScheme, CreateInputs, CreateOutcome, CreateScheme, Gas,
+    InstructionResult, Interpreter, InterpreterTypes,
+};
+use primitives::{b256, hex_literal::hex, Address, B256};
+use state::bytecode::opcode;
 
 /// Helper that keeps track of gas.
 #[derive(Clone, Copy, Debug)]
@@ -104,6 +119,418 @@ impl GasInspector {
     }
 }
 
+/// Kind of the frame tracked by [`CallGasInspector`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FrameKind {
+    /// Frame created by one of the call schemes.
+    Call(CallScheme),
+    /// Frame created by one of the create schemes.
+    Create(CreateScheme),
+}
+
+/// Storage slot of the implementation address of EIP-1967 proxies,
+/// `keccak256("eip1967.proxy.implementation") - 1`.
+pub const EIP1967_IMPLEMENTATION_SLOT: B256 =
+    b256!("0x360894a13ba1a3210667c828492db98dca3e2076cc3735a920a3ca505d382bbc");
+
+/// Storage slot of the beacon address of EIP-1967 beacon proxies,
+/// `keccak256("eip1967.proxy.beacon") - 1`.
+pub const EIP1967_BEACON_SLOT: B256 =
+    b256!("0xa3f0ad74e5423aebfd80d3ef4346578335a9a72aeaee59ff6cb3582b35133d50");
+
+/// Code of EIP-1167 minimal proxies before the implementation address.
+const EIP1167_PREFIX: [u8; 10] = hex!("363d3d373d3d3d363d73");
+
+/// Code of EIP-1167 minimal proxies after the implementation address.
+const EIP1167_SUFFIX: [u8; 15] = hex!("5af43d82803e903d91602b57fd5bf3");
+
+/// Proxy pattern detected by [`CallGasInspector`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProxyKind {
+    /// Proxy that reads [`EIP1967_IMPLEMENTATION_SLOT`] and delegates to the implementation.
+    Eip1967,
+    /// Proxy that reads [`EIP1967_BEACON_SLOT`] and delegates to the implementation returned
+    /// by the beacon.
+    Eip1967Beacon,
+    /// EIP-1167 minimal proxy with the implementation address embedded in its code.
+    Eip1167,
+}
+
+/// Proxy detected in a call frame.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Proxy {
+    /// Detected pattern.
+    pub kind: ProxyKind,
+    /// Address of the implementation the frame delegates to.
+    pub implementation: Address,
+}
+
+/// Gas attributed to a single call frame.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FrameGas {
+    /// Depth of the frame, `0` for the transaction frame.
+    pub depth: usize,
+    /// Index of the parent frame.
+    pub parent: Option<usize>,
+    /// Indices of the child frames in the order they were executed.
+    pub children: Vec<usize>,
+    /// Kind of the frame.
+    pub kind: FrameKind,
+    /// Target address for calls or created address for successful creates.
+    pub address: Option<Address>,
+    /// Function selector of calls with at least four bytes of calldata.
+    pub selector: Option<Selector>,
+    /// Gas available in the parent frame before the call instruction was executed.
+    ///
+    /// For the transaction frame this is the same as [`FrameGas::gas_limit`].
+    pub gas_available: u64,
+    /// Gas forwarded to the frame, after the 63/64 rule (EIP-150) was applied.
+    pub gas_limit: u64,
+    /// Gas spent by the frame, including the gas spent by its descendants.
+    pub gas_spent: u64,
+    /// Refund accumulated by the frame and its successful descendants.
+    pub refunded: i64,
+    /// Result of the frame, [`None`] while the frame is executing.
+    pub result: Option<InstructionResult>,
+    /// Proxy pattern of the frame, see [`CallGasInspector`].
+    pub proxy: Option<Proxy>,
+    /// Input and output of precompile calls, see [`CallGasInspector::with_precompile_io`].
+    pub precompile: Option<PrecompileIo>,
+}
+
+/// Inspector that attributes gas to each call frame and builds a gas tree.
+///
+/// Gas spent by a frame is split into gas spent by its own instructions ([`CallGasInspector::self_gas`])
+/// and gas spent by its descendants ([`CallGasInspector::descendant_gas`]).
+///
+/// Transaction level refund cap (EIP-3529) is applied after execution and is
+/// not part of the tree, see `ResultGas::final_refunded`.
+///
+/// Frames that behave like proxies are annotated with the implementation they delegate to
+/// ([`FrameGas::proxy`]). EIP-1167 minimal proxies are recognized by their code, EIP-1967
+/// proxies by a read of the implementation or beacon slot followed by a `DELEGATECALL`.
+/// The detection is a heuristic, a contract that reads the slot for other reasons before
+/// delegating is reported as a proxy as well.
+#[derive(Clone, Debug, Default)]
+pub struct CallGasInspector {
+    gas_inspector: GasInspector,
+    frames: Vec<FrameGas>,
+    stack: Vec<usize>,
+    /// EIP-1967 slot read by each frame of the stack and not yet followed by a `DELEGATECALL`.
+    proxy_slots: Vec<Option<ProxyKind>>,
+    labels: Option<Arc<Labels>>,
+    precompile_io_limit: Option<usize>,
+}
+
+impl CallGasInspector {
+    /// Creates a new call gas inspector.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the labels used by [`CallGasInspector::render`].
+    pub fn with_labels(mut self, labels: Arc<Labels>) -> Self {
+        self.labels = Some(labels);
+        self
+    }
+
+    /// Captures the input and output of precompile calls in [`FrameGas::precompile`], truncated
+    /// to `limit` bytes, e.g. [`DEFAULT_PRECOMPILE_IO_LIMIT`](crate::DEFAULT_PRECOMPILE_IO_LIMIT).
+    pub fn with_precompile_io(mut self, limit: usize) -> Self {
+        self.precompile_io_limit = Some(limit);
+        self
+    }
+
+    /// Returns all frames in the order they were entered.
+    ///
+    /// First frame is the transaction frame and the root of the tree.
+    pub fn frames(&self) -> &[FrameGas] {
+        &self.frames
+    }
+
+    /// Returns the transaction frame.
+    pub fn root(&self) -> Option<&FrameGas> {
+        self.frames.first()
+    }
+
+    /// Returns gas spent by the descendants of the frame.
+    pub fn descendant_gas(&self, index: usize) -> u64 {
+        self.frames[index]
+            .children
+            .iter()
+            .map(|&child| self.frames[child].gas_spent)
+            .sum()
+    }
+
+    /// Returns gas spent by the frame's own instructions.
+    ///
+    /// This includes the cost of call instructions but not the gas spent inside the called frames.
+    pub fn self_gas(&self, index: usize) -> u64 {
+        self.frames[index]
+            .gas_spent
+            .saturating_sub(self.descendant_gas(index))
+    }
+
+    /// Clears the tree so the inspector can be used for the next transaction.
+    pub fn clear(&mut self) {
+        self.gas_inspector = GasInspector::new();
+        self.frames.clear();
+        self.stack.clear();
+        self.proxy_slots.clear();
+    }
+
+    /// Returns the frames that were detected as proxies.
+    pub fn proxies(&self) -> impl Iterator<Item = (&FrameGas, &Proxy)> {
+        self.frames
+            .iter()
+            .filter_map(|frame| frame.proxy.as_ref().map(|proxy| (frame, proxy)))
+    }
+
+    /// Renders the gas tree, one frame per line indented by depth.
+    ///
+    /// Calls are rendered with the configured [`Labels`], e.g.
+    /// `Uniswap V3 Router.exactInput gas: 120000 (self: 5000)`, and proxies with their
+    /// implementation, e.g. `USDC (proxy to FiatTokenV2_2).transfer gas: ...`.
+    pub fn render(&self) -> String {
+        let default_labels = Labels::default();
+        let labels = self.labels.as_deref().unwrap_or(&default_labels);
+        let mut out = String::new();
+        for (index, frame) in self.frames.iter().enumerate() {
+            let _ = writeln!(
+                out,
+                "{:indent$}{} gas: {} (self: {})",
+                "",
+                frame_name(labels, frame),
+                frame.gas_spent,
+                self.self_gas(index),
+                indent = frame.depth * 2
+            );
+        }
+        out
+    }
+
+    /// Renders the gas tree in the folded stack format used by flame graph tools such as
+    /// `inferno` and `flamegraph.pl`.
+    ///
+    /// Every line is the path of frame names from the root, separated by `;`, followed by the
+    /// gas spent by the frame's own instructions, e.g. `Router.swap;Pool.swap 21000`. Frames
+    /// are named like in [`CallGasInspector::render`] and frames without own gas are skipped.
+    /// Identical paths are merged, flame graph tools merge them as well, so the output of many
+    /// transactions can be concatenated and rendered as one graph.
+    pub fn to_folded(&self) -> String {
+        let default_labels = Labels::default();
+        let labels = self.labels.as_deref().unwrap_or(&default_labels);
+        let mut paths: Vec<String> = Vec::with_capacity(self.frames.len());
+        let mut stacks = BTreeMap::<&str, u64>::new();
+        for frame in &self.frames {
+            // `;` separates the frames of a path.
+            let name = frame_name(labels, frame).replace(';', ",");
+            let path = match frame.parent {
+                Some(parent) => alloc::format!("{};{name}", paths[parent]),
+                None => name,
+            };
+            paths.push(path);
+        }
+        for (index, path) in paths.iter().enumerate() {
+            let self_gas = self.self_gas(index);
+            if self_gas > 0 {
+                *stacks.entry(path).or_default() += self_gas;
+            }
+        }
+        let mut out = String::new();
+        for (path, gas) in stacks {
+            let _ = writeln!(out, "{path} {gas}");
+        }
+        out
+    }
+
+    fn push_frame(
+        &mut self,
+        kind: FrameKind,
+        address: Option<Address>,
+        selector: Option<Selector>,
+        gas_limit: u64,
+    ) {
+        let parent = self.stack.last().copied();
+        // GasInspector was updated at the end of the call instruction so the remaining gas
+        // and the cost of the instruction give the gas available before it.
+        let gas_available = match parent {
+            Some(_) => self.gas_inspector.gas_remaining() + self.gas_inspector.last_gas_cost(),
+            None => gas_limit,
+        };
+        let index = self.frames.len();
+        self.frames.push(FrameGas {
+            depth: self.stack.len(),
+            parent,
+            children: Vec::new(),
+            kind,
+            address,
+            selector,
+            gas_available,
+            gas_limit,
+            gas_spent: 0,
+            refunded: 0,
+            result: None,
+            proxy: None,
+            precompile: None,
+        });
+        if let Some(parent) = parent {
+            self.frames[parent].children.push(index);
+        }
+        self.stack.push(index);
+        self.proxy_slots.push(None);
+    }
+
+    fn pop_frame(&mut self, gas: &Gas, result: InstructionResult, address: Option<Address>) {
+        let Some(index) = self.stack.pop() else {
+            return;
+        };
+        self.proxy_slots.pop();
+        let frame = &mut self.frames[index];
+        frame.gas_spent = if result.is_halt() {
+            frame.gas_limit
+        } else {
+            gas.total_gas_spent()
+        };
+        frame.refunded = if result.is_ok() { gas.refunded() } else { 0 };
+        frame.result = Some(result);
+        if address.is_some() && result.is_ok() {
+            frame.address = address;
+        }
+    }
+}
+
+impl<CTX: ContextTr, INTR: InterpreterTypes> Inspector<CTX, INTR> for CallGasInspector {
+    fn initialize_interp(&mut self, interp: &mut Interpreter<INTR>, _context: &mut CTX) {
+        self.gas_inspector.initialize_interp(&interp.gas);
+        let Some(&index) = self.stack.last() else {
+            return;
+        };
+        let frame = &mut self.frames[index];
+        if let FrameKind::Call(_) = frame.kind {
+            if let Some(implementation) = eip1167_implementation(interp.bytecode.bytecode_slice()) {
+                frame.proxy = Some(Proxy {
+                    kind: ProxyKind::Eip1167,
+                    implementation,
+                });
+            }
+        }
+    }
+
+    fn step(&mut self, interp: &mut Interpreter<INTR>, _context: &mut CTX) {
+        self.gas_inspector.step(&interp.gas);
+        if interp.bytecode.opcode() != opcode::SLOAD {
+            return;
+        }
+        let Some(key) = interp.stack.data().last() else {
+            return;
+        };
+        let kind = match B256::from(key.to_be_bytes()) {
+            EIP1967_IMPLEMENTATION_SLOT => ProxyKind::Eip1967,
+            EIP1967_BEACON_SLOT => ProxyKind::Eip1967Beacon,
+            _ => return,
+        };
+        if let Some(slot) = self.proxy_slots.last_mut() {
+            *slot = Some(kind);
+        }
+    }
+
+    fn step_end(&mut self, interp: &mut Interpreter<INTR>, _context: &mut CTX) {
+        self.gas_inspector.step_end(&interp.gas);
+    }
+
+    fn call(&mut self, context: &mut CTX, inputs: &mut CallInputs) -> Option<CallOutcome> {
+        let selector = inputs
+            .input
+            .as_bytes(context)
+            .get(..4)
+            .map(Selector::from_slice);
+        if inputs.scheme == CallScheme::DelegateCall {
+            let slot = self.proxy_slots.last_mut().and_then(Option::take);
+            if let (Some(kind), Some(&index)) = (slot, self.stack.last()) {
+                self.frames[index].proxy = Some(Proxy {
+                    kind,
+                    implementation: inputs.bytecode_address,
+                });
+            }
+        }
+        let precompile = self
+            .precompile_io_limit
+            .and_then(|limit| PrecompileIo::call(context, inputs, limit));
+        self.push_frame(
+            FrameKind::Call(inputs.scheme),
+            Some(inputs.target_address),
+            selector,
+            inputs.gas_limit,
+        );
+        if let Some(frame) = self.frames.last_mut() {
+            frame.precompile = precompile;
+        }
+        None
+    }
+
+    fn call_end(&mut self, _context: &mut CTX, _inputs: &CallInputs, outcome: &mut CallOutcome) {
+        if let (Some(&index), Some(limit)) = (self.stack.last(), self.precompile_io_limit) {
+            if let Some(precompile) = &mut self.frames[index].precompile {
+                precompile.call_end(outcome, limit);
+            }
+        }
+        self.pop_frame(&outcome.result.gas, outcome.result.result, None);
+    }
+
+    fn create(&mut self, _context: &mut CTX, inputs: &mut CreateInputs) -> Option<CreateOutcome> {
+        self.push_frame(
+            FrameKind::Create(inputs.scheme()),
+            None,
+            None,
+            inputs.gas_limit(),
+        );
+        None
+    }
+
+    fn create_end(
+        &mut self,
+        _context: &mut CTX,
+        _inputs: &CreateInputs,
+        outcome: &mut CreateOutcome,
+    ) {
+        self.pop_frame(&outcome.result.gas, outcome.result.result, outcome.address);
+    }
+}
+
+/// Returns the name of the frame, see [`CallGasInspector::render`].
+fn frame_name(labels: &Labels, frame: &FrameGas) -> String {
+    match (frame.kind, frame.address) {
+        (FrameKind::Call(_), Some(address)) => {
+            let selector = frame.selector.as_ref().map_or(&[][..], |s| s.as_slice());
+            let call = labels.format_call(&address, selector);
+            match frame.proxy {
+                Some(proxy) => {
+                    let target = labels.format_address(&address);
+                    let implementation = labels.format_address(&proxy.implementation);
+                    alloc::format!(
+                        "{target} (proxy to {implementation}){}",
+                        &call[target.len()..]
+                    )
+                }
+                None => call,
+            }
+        }
+        (FrameKind::Create(_), Some(address)) => {
+            alloc::format!("new {}", labels.format_address(&address))
+        }
+        (_, None) => String::from("new <failed>"),
+    }
+}
+
+/// Returns the implementation address if the code is an EIP-1167 minimal proxy.
+fn eip1167_implementation(code: &[u8]) -> Option<Address> {
+    let implementation = code
+        .strip_prefix(&EIP1167_PREFIX[..])?
+        .strip_suffix(&EIP1167_SUFFIX[..])?;
+    (implementation.len() == 20).then(|| Address::from_slice(implementation))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -296,4 +723,175 @@ mod tests {
             [Bytes::new(), Bytes::from([0x01]), Bytes::from([0x02])].to_vec()
         );
     }
+
+    #[test]
+    fn test_call_gas_inspector() {
+        let contract_data: Bytes = Bytes::from(vec![
+            opcode::PUSH1,
+            0x0,
+            opcode::PUSH1,
+            0x0,
+            opcode::PUSH1,
+            0x0,
+            opcode::PUSH1,
+            0x0,
+            opcode::PUSH1,
+            0x0,
+            // Identity precompile.
+            opcode::PUSH1,
+            0x4,
+            opcode::GAS,
+            opcode::CALL,
+            opcode::STOP,
+        ]);
+        let bytecode = Bytecode::new_raw(contract_data);
+
+        let mut evm = Context::mainnet()
+            .with_db(BenchmarkDB::new_bytecode(bytecode))
+            .build_mainnet_with_inspector(CallGasInspector::new());
+
+        evm.inspect_one_tx(
+            TxEnv::builder()
+                .caller(BENCH_CALLER)
+                .kind(TxKind::Call(BENCH_TARGET))
+                .gas_limit(100_000)
+                .build()
+                .unwrap(),
+        )
+        .unwrap();
+
+        let inspector = &evm.inspector;
+        let frames = inspector.frames();
+        assert_eq!(frames.len(), 2);
+
+        let root = &frames[0];
+        assert_eq!(root.depth, 0);
+        assert_eq!(root.children, vec![1]);
+        assert_eq!(root.kind, FrameKind::Call(CallScheme::Call));
+        assert_eq!(root.result, Some(InstructionResult::Stop));
+
+        let child = &frames[1];
+        assert_eq!(child.depth, 1);
+        assert_eq!(child.parent, Some(0));
+        assert_eq!(child.address, Some(Address::with_last_byte(4)));
+        // Identity precompile with empty input costs 15 gas.
+        assert_eq!(child.gas_spent, 15);
+        // 63/64 of the gas is forwarded.
+        assert!(child.gas_available > child.gas_limit);
+
+        assert_eq!(inspector.descendant_gas(0), 15);
+        assert_eq!(inspector.self_gas(0) + 15, root.gas_spent);
+
+        let labels = Arc::new(
+            Labels::new()
+                .with_address(BENCH_TARGET, "Target")
+                .with_address(Address::with_last_byte(4), "Identity"),
+        );
+        let rendered = inspector.clone().with_labels(labels.clone()).render();
+        let lines: Vec<_> = rendered.lines().collect();
+        assert_eq!(
+            lines[0],
+            format!(
+                "Target gas: {} (self: {})",
+                root.gas_spent,
+                root.gas_spent - 15
+            )
+        );
+        assert_eq!(lines[1], "  Identity gas: 15 (self: 15)");
+
+        let folded = inspector.clone().with_labels(labels).to_folded();
+        assert_eq!(
+            folded,
+            format!("Target {}\nTarget;Identity 15\n", root.gas_spent - 15)
+        );
+    }
+
+    fn run_proxy(code: Vec<u8>) -> CallGasInspector {
+        let mut evm = Context::mainnet()
+            .with_db(BenchmarkDB::new_bytecode(Bytecode::new_raw(code.into())))
+            .build_mainnet_with_inspector(CallGasInspector::new());
+        evm.inspect_one_tx(
+            TxEnv::builder()
+                .caller(BENCH_CALLER)
+                .kind(TxKind::Call(BENCH_TARGET))
+                .data(Bytes::from_static(&[0xaa, 0xbb, 0xcc, 0xdd]))
+                .gas_limit(100_000)
+                .build()
+                .unwrap(),
+        )
+        .unwrap();
+        evm.inspector
+    }
+
+    #[test]
+    fn test_minimal_proxy_is_detected() {
+        // Minimal proxy of the identity precompile.
+        let identity = Address::with_last_byte(4);
+        let code = [
+            &EIP1167_PREFIX[..],
+            identity.as_slice(),
+            &EIP1167_SUFFIX[..],
+        ]
+        .concat();
+        let inspector = run_proxy(code);
+
+        let frames = inspector.frames();
+        assert_eq!(frames.len(), 2);
+        assert_eq!(
+            frames[0].proxy,
+            Some(Proxy {
+                kind: ProxyKind::Eip1167,
+                implementation: identity,
+            })
+        );
+        assert_eq!(frames[1].kind, FrameKind::Call(CallScheme::DelegateCall));
+        assert_eq!(frames[1].proxy, None);
+        assert_eq!(inspector.proxies().count(), 1);
+
+        let labels = Labels::new()
+            .with_address(BENCH_TARGET, "Proxy")
+            .with_address(identity, "Identity");
+        let rendered = inspector.with_labels(Arc::new(labels)).render();
+        assert!(rendered.starts_with("Proxy (proxy to Identity).0xaabbccdd gas: "));
+    }
+
+    #[test]
+    fn test_eip1967_proxy_is_detected() {
+        // Read the implementation slot, then delegate to the identity precompile.
+        let mut code = vec![opcode::PUSH32];
+        code.extend_from_slice(EIP1967_IMPLEMENTATION_SLOT.as_slice());
+        code.extend_from_slice(&[
+            opcode::SLOAD,
+            opcode::POP,
+            opcode::PUSH1,
+            0x0,
+            opcode::PUSH1,
+            0x0,
+            opcode::PUSH1,
+            0x0,
+            opcode::PUSH1,
+            0x0,
+            opcode::PUSH1,
+            0x4,
+            opcode::GAS,
+            opcode::DELEGATECALL,
+            opcode::STOP,
+        ]);
+        let inspector = run_proxy(code);
+
+        let frames = inspector.frames();
+        assert_eq!(frames.len(), 2);
+        assert_eq!(
+            frames[0].proxy,
+            Some(Proxy {
+                kind: ProxyKind::Eip1967,
+                implementation: Address::with_last_byte(4),
+            })
+        );
+
+        // A delegate call without a slot read is not a proxy.
+        let inspector = run_proxy(code[35..].to_vec());
+        assert_eq!(inspector.frames().len(), 2);
+        assert_eq!(inspector.proxies().count(), 0);
+    }
 }
@@ -0,0 +1,330 @@
+//! GasGriefingInspector - Inspector that flags calls capped by the 63/64 rule.
+extern crate alloc;
+
+use crate::Inspector;
+use alloc::vec::Vec;
+use context::{Cfg, ContextTr};
+use interpreter::{
+    interpreter_types::{InputsTr, Jumps, StackTr},
+    CallInputs, CallOutcome, CallScheme, CreateInputs, CreateOutcome, InstructionResult,
+    Interpreter, InterpreterTypes,
+};
+use primitives::Address;
+use state::bytecode::opcode;
+
+/// Call that got less gas than the caller requested because of the 63/64 rule (EIP-150).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CappedCall {
+    /// Depth of the called frame, the transaction frame has depth zero.
+    pub depth: usize,
+    /// Address of the contract executing the call opcode.
+    pub caller: Address,
+    /// Called address.
+    pub target: Address,
+    /// Scheme of the call.
+    pub scheme: CallScheme,
+    /// Program counter of the call opcode in the caller.
+    pub pc: usize,
+    /// Gas requested on the stack.
+    pub requested: u64,
+    /// Gas forwarded to the called frame, without the value transfer stipend.
+    pub forwarded: u64,
+    /// Result of the called frame, [`None`] while it is executing.
+    pub result: Option<InstructionResult>,
+    /// Result of the caller frame, [`None`] while it is executing.
+    pub caller_result: Option<InstructionResult>,
+}
+
+impl CappedCall {
+    /// Returns the gas the called frame did not get.
+    pub fn shortfall(&self) -> u64 {
+        self.requested - self.forwarded
+    }
+
+    /// Returns `true` if the called frame ran out of gas.
+    pub fn ran_out_of_gas(&self) -> bool {
+        matches!(
+            self.result,
+            Some(
+                InstructionResult::OutOfGas
+                    | InstructionResult::MemoryOOG
+                    | InstructionResult::MemoryLimitOOG
+                    | InstructionResult::PrecompileOOG
+                    | InstructionResult::InvalidOperandOOG
+                    | InstructionResult::ReentrancySentryOOG
+            )
+        )
+    }
+
+    /// Returns `true` if the called frame ran out of gas while the caller succeeded.
+    ///
+    /// This is the "insufficient gas forwarded" pattern: whoever sends the transaction can pick
+    /// a gas limit that makes the sub call fail while the caller, e.g. a relayer marking a meta
+    /// transaction as executed, commits its own changes.
+    pub fn is_insufficient_gas_forwarded(&self) -> bool {
+        self.ran_out_of_gas() && self.caller_result.is_some_and(InstructionResult::is_ok)
+    }
+}
+
+/// Call opcode that is about to be executed.
+#[derive(Clone, Copy, Debug)]
+struct PendingCall {
+    caller: Address,
+    pc: usize,
+    requested: u64,
+}
+
+/// State of an executing frame.
+#[derive(Clone, Debug, Default)]
+struct Frame {
+    /// Call opcode executed by the frame.
+    pending: Option<PendingCall>,
+    /// If the frame is executing the `GAS` opcode.
+    executing_gas: bool,
+    /// Value pushed by the last `GAS` opcode.
+    gas_left: Option<u64>,
+    /// Capped calls made by the frame.
+    capped: Vec<usize>,
+    /// Capped call that created the frame.
+    capped_call: Option<usize>,
+}
+
+/// Inspector that flags calls where the 63/64 rule left the called frame with less gas than
+/// requested.
+///
+/// Only calls with an explicit gas amount are considered, calls forwarding all remaining gas
+/// (`gas()` in Solidity, or the maximum value) are always capped by design. The
+/// called and caller results are recorded, see [`CappedCall::is_insufficient_gas_forwarded`]
+/// for the pattern used by gas griefing attacks. As it works on real executions, it finds the
+/// griefing vectors reachable with the transaction gas limit, not the ones a static analysis
+/// assumes.
+#[derive(Clone, Debug, Default)]
+pub struct GasGriefingInspector {
+    min_shortfall: u64,
+    calls: Vec<CappedCall>,
+    stack: Vec<Frame>,
+}
+
+impl GasGriefingInspector {
+    /// Creates a new inspector flagging every capped call.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only flags calls that got at least `min_shortfall` less gas than requested.
+    pub fn with_min_shortfall(mut self, min_shortfall: u64) -> Self {
+        self.min_shortfall = min_shortfall;
+        self
+    }
+
+    /// Returns the capped calls in the order they were made.
+    pub fn calls(&self) -> &[CappedCall] {
+        &self.calls
+    }
+
+    /// Returns the capped calls that ran out of gas while the caller succeeded.
+    pub fn insufficient_gas_forwarded(&self) -> impl Iterator<Item = &CappedCall> {
+        self.calls
+            .iter()
+            .filter(|call| call.is_insufficient_gas_forwarded())
+    }
+
+    /// Clears the calls so the inspector can be used for the next transaction.
+    pub fn clear(&mut self) {
+        self.calls.clear();
+        self.stack.clear();
+    }
+
+    fn pop_frame(&mut self, result: InstructionResult) {
+        let Some(frame) = self.stack.pop() else {
+            return;
+        };
+        for index in frame.capped {
+            self.calls[index].caller_result = Some(result);
+        }
+        if let Some(index) = frame.capped_call {
+            self.calls[index].result = Some(result);
+        }
+    }
+}
+
+impl<CTX: ContextTr, INTR: InterpreterTypes> Inspector<CTX, INTR> for GasGriefingInspector {
+    fn step(&mut self, interp: &mut Interpreter<INTR>, _context: &mut CTX) {
+        let Some(frame) = self.stack.last_mut() else {
+            return;
+        };
+        let op = interp.bytecode.opcode();
+        frame.pending = None;
+        frame.executing_gas = op == opcode::GAS;
+        if !matches!(
+            op,
+            opcode::CALL | opcode::CALLCODE | opcode::DELEGATECALL | opcode::STATICCALL
+        ) {
+            return;
+        }
+        let Some(requested) = interp.stack.data().last() else {
+            return;
+        };
+        let requested = u64::try_from(*requested).unwrap_or(u64::MAX);
+        // Calls forwarding all gas are capped by design.
+        if requested == u64::MAX || frame.gas_left == Some(requested) {
+            return;
+        }
+        frame.pending = Some(PendingCall {
+            caller: interp.input.target_address(),
+            pc: interp.bytecode.pc(),
+            requested,
+        });
+    }
+
+    fn step_end(&mut self, interp: &mut Interpreter<INTR>, _context: &mut CTX) {
+        let Some(frame) = self.stack.last_mut() else {
+            return;
+        };
+        if frame.executing_gas {
+            frame.gas_left = interp
+                .stack
+                .data()
+                .last()
+                .map(|gas| u64::try_from(*gas).unwrap_or(u64::MAX));
+        }
+    }
+
+    fn call(&mut self, context: &mut CTX, inputs: &mut CallInputs) -> Option<CallOutcome> {
+        let pending = self.stack.last_mut().and_then(|frame| frame.pending.take());
+        let mut capped_call = None;
+        if let Some(pending) = pending {
+            let stipend = if inputs.transfers_value() {
+                context.cfg().gas_params().call_stipend()
+            } else {
+                0
+            };
+            let forwarded = inputs.gas_limit.saturating_sub(stipend);
+            if pending.requested > forwarded
+                && pending.requested - forwarded >= self.min_shortfall.max(1)
+            {
+                capped_call = Some(self.calls.len());
+                self.calls.push(CappedCall {
+                    depth: self.stack.len(),
+                    caller: pending.caller,
+                    target: inputs.target_address,
+                    scheme: inputs.scheme,
+                    pc: pending.pc,
+                    requested: pending.requested,
+                    forwarded,
+                    result: None,
+                    caller_result: None,
+                });
+                if let Some(frame) = self.stack.last_mut() {
+                    frame.capped.push(self.calls.len() - 1);
+                }
+            }
+        }
+        self.stack.push(Frame {
+            capped_call,
+            ..Default::default()
+        });
+        None
+    }
+
+    fn call_end(&mut self, _context: &mut CTX, _inputs: &CallInputs, outcome: &mut CallOutcome) {
+        self.pop_frame(outcome.result.result);
+    }
+
+    fn create(&mut self, _context: &mut CTX, _inputs: &mut CreateInputs) -> Option<CreateOutcome> {
+        self.stack.push(Frame::default());
+        None
+    }
+
+    fn create_end(
+        &mut self,
+        _context: &mut CTX,
+        _inputs: &CreateInputs,
+        outcome: &mut CreateOutcome,
+    ) {
+        self.pop_frame(outcome.result.result);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::InspectEvm;
+    use context::{Context, TxEnv};
+    use database::{InMemoryDB, BENCH_CALLER};
+    use handler::{MainBuilder, MainContext};
+    use primitives::{address, TxKind, U256};
+    use state::{bytecode::Bytecode, AccountInfo};
+
+    const RELAYER: Address = address!("0x00000000000000000000000000000000000000aa");
+    const TARGET: Address = address!("0x00000000000000000000000000000000000000bb");
+
+    /// Relayer calling the target with 100k gas, or with all gas, ignoring the result.
+    fn relayer(gas: &[u8]) -> Bytecode {
+        let mut code = Vec::from([opcode::PUSH1, 0, opcode::DUP1, opcode::DUP1, opcode::DUP1]);
+        code.extend([opcode::DUP1, opcode::PUSH20]);
+        code.extend_from_slice(TARGET.as_slice());
+        code.extend_from_slice(gas);
+        code.extend([opcode::CALL, opcode::POP, opcode::STOP]);
+        Bytecode::new_raw(code.into())
+    }
+
+    fn run(gas_limit: u64, gas: &[u8]) -> GasGriefingInspector {
+        let mut db = InMemoryDB::default();
+        db.insert_account_info(
+            BENCH_CALLER,
+            AccountInfo::from_balance(U256::from(u64::MAX)),
+        );
+        db.insert_account_info(RELAYER, AccountInfo::default().with_code(relayer(gas)));
+        // Infinite loop, burns all gas it gets.
+        db.insert_account_info(
+            TARGET,
+            AccountInfo::default().with_code(Bytecode::new_raw(
+                [opcode::JUMPDEST, opcode::PUSH1, 0, opcode::JUMP]
+                    .to_vec()
+                    .into(),
+            )),
+        );
+        let mut evm = Context::mainnet()
+            .with_db(db)
+            .build_mainnet_with_inspector(GasGriefingInspector::new());
+        evm.inspect_one_tx(
+            TxEnv::builder()
+                .caller(BENCH_CALLER)
+                .kind(TxKind::Call(RELAYER))
+                .gas_limit(gas_limit)
+                .build()
+                .unwrap(),
+        )
+        .unwrap();
+        evm.inspector
+    }
+
+    #[test]
+    fn flags_insufficient_gas_forwarded() {
+        let inspector = run(80_000, &[opcode::PUSH3, 0x01, 0x86, 0xa0]);
+        let [call] = inspector.calls() else {
+            panic!("expected one capped call: {:?}", inspector.calls());
+        };
+        assert_eq!(call.depth, 1);
+        assert_eq!(call.caller, RELAYER);
+        assert_eq!(call.target, TARGET);
+        assert_eq!(call.requested, 100_000);
+        assert!(call.shortfall() > 0);
+        assert_eq!(call.result, Some(InstructionResult::OutOfGas));
+        assert_eq!(call.caller_result, Some(InstructionResult::Stop));
+        assert_eq!(inspector.insufficient_gas_forwarded().count(), 1);
+    }
+
+    #[test]
+    fn ignores_calls_with_enough_gas() {
+        let inspector = run(1_000_000, &[opcode::PUSH3, 0x01, 0x86, 0xa0]);
+        assert!(inspector.calls().is_empty());
+    }
+
+    #[test]
+    fn ignores_calls_forwarding_all_gas() {
+        let inspector = run(80_000, &[opcode::GAS]);
+        assert!(inspector.calls().is_empty());
+    }
+}
@@ -1,4 +1,4 @@
-use crate::{Inspector, InspectorEvmTr, JournalExt};
+use crate::{Inspector, InspectorEvmTr, JournalExt, RefundEvent, RefundSource};
 use context::journaled_state::JournalCheckpoint;
 use context::{result::ExecutionResult, ContextTr, JournalEntry, JournalTr};
 use handler::{
@@ -73,6 +73,17 @@ where
         let mut exec_result = None;
         if let Some(pre_execution) = pre_execution {
             refund = pre_execution.eip7702_refund as i64;
+            if refund != 0 {
+                let (context, inspector) = evm.ctx_inspector();
+                inspector.refund(
+                    context,
+                    RefundEvent {
+                        source: RefundSource::Eip7702Authorizations,
+                        amount: refund,
+                        total: refund,
+                    },
+                );
+            }
             exec_result = self.inspect_execution(evm, pre_execution.checkpoint, &mut gas)?;
         }
         let mut frame_result = match exec_result {
@@ -124,6 +135,9 @@ where
         evm: &mut Self::Evm,
         first_frame_input: <<Self::Evm as EvmTr>::Frame as FrameTr>::FrameInit,
     ) -> Result<FrameResult, Self::Error> {
+        if let Some(frame_result) = self.frame_intercept(evm, &first_frame_input)? {
+            return Ok(self.inspect_intercepted_frame(evm, first_frame_input, frame_result));
+        }
         let res = evm.inspect_frame_init(first_frame_input)?;
 
         if let ItemOrResult::Result(frame_result) = res {
@@ -134,15 +148,17 @@ where
             let call_or_result = evm.inspect_frame_run()?;
 
             let result = match call_or_result {
-                ItemOrResult::Item(init) => {
-                    match evm.inspect_frame_init(init)? {
+                ItemOrResult::Item(init) => match self.frame_intercept(evm, &init)? {
+                    // Do not pop the frame since no new frame was created
+                    Some(result) => self.inspect_intercepted_frame(evm, init, result),
+                    None => match evm.inspect_frame_init(init)? {
                         ItemOrResult::Item(_) => {
                             continue;
                         }
                         // Do not pop the frame since no new frame was created
                         ItemOrResult::Result(result) => result,
-                    }
-                }
+                    },
+                },
                 ItemOrResult::Result(result) => result,
             };
 
@@ -152,6 +168,23 @@ where
         }
     }
 
+    /// Calls the frame start and end inspector hooks for a frame short-circuited by
+    /// [`Handler::frame_intercept`].
+    ///
+    /// The result returned by the inspector frame start hook takes precedence.
+    fn inspect_intercepted_frame(
+        &mut self,
+        evm: &mut Self::Evm,
+        mut frame_init: <<Self::Evm as EvmTr>::Frame as FrameTr>::FrameInit,
+        result: FrameResult,
+    ) -> FrameResult {
+        let (ctx, inspector) = evm.ctx_inspector();
+        let mut output = frame_start::<_, Self::IT>(ctx, inspector, &mut frame_init.frame_input)
+            .unwrap_or(result);
+        frame_end::<_, Self::IT>(ctx, inspector, &frame_init.frame_input, &mut output);
+        output
+    }
+
     /// Run system call with inspection support.
     ///
     /// This method acts as [`Handler::run_system_call`] method for inspection.
@@ -266,8 +299,10 @@ where
             break;
         }
 
-        instruction_journal_i = Some(context.journal().journal().len());
+        let journal_i = context.journal().journal().len();
+        instruction_journal_i = Some(journal_i);
         let logs_i = context.journal().logs().len();
+        let refunded = interpreter.gas.refunded();
         if let Err(e) = interpreter.step(instructions, gas_table, context) {
             cold_path();
             if interpreter.bytecode.action().is_none() {
@@ -280,6 +315,11 @@ where
             inspect_logs(Some(interpreter), context, &mut inspector, logs_i);
         }
 
+        if interpreter.gas.refunded() != refunded {
+            cold_path();
+            inspect_refund(interpreter, context, &mut inspector, journal_i, refunded);
+        }
+
         inspector.step_end(interpreter, context);
 
         if interpreter.bytecode.is_end() {
@@ -302,6 +342,44 @@ where
     next_action
 }
 
+/// Reports the refund change of the instruction that just ran to the inspector.
+///
+/// The refunded slot is the last storage change journaled since `journal_i`.
+#[inline(never)]
+#[cold]
+fn inspect_refund<CTX, IT>(
+    interpreter: &Interpreter<IT>,
+    context: &mut CTX,
+    inspector: &mut impl Inspector<CTX, IT>,
+    journal_i: usize,
+    refunded: i64,
+) where
+    CTX: ContextTr<Journal: JournalExt>,
+    IT: InterpreterTypes,
+{
+    let journal = context.journal().journal();
+    let source = journal[journal_i.min(journal.len())..]
+        .iter()
+        .rev()
+        .find_map(|entry| match entry {
+            JournalEntry::StorageChanged { address, key, .. } => Some(RefundSource::Sstore {
+                address: *address,
+                key: *key,
+            }),
+            _ => None,
+        })
+        .unwrap_or(RefundSource::Instruction);
+    let total = interpreter.gas.refunded();
+    inspector.refund(
+        context,
+        RefundEvent {
+            source,
+            amount: total - refunded,
+            total,
+        },
+    );
+}
+
 /// Forwards the logs journaled since `logs_i` to the inspector.
 ///
 /// `interpreter` is `Some` on the instruction path, where the logs belong to
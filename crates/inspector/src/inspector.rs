@@ -5,9 +5,39 @@ use interpreter::{
     interpreter::EthInterpreter, CallInputs, CallOutcome, CreateInputs, CreateOutcome, FrameInput,
     Interpreter, InterpreterTypes,
 };
-use primitives::{Address, Log, U256};
+use primitives::{Address, Log, StorageKey, U256};
 use state::EvmState;
 
+/// Operation that changed the gas refund counter, see [`RefundEvent`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum RefundSource {
+    /// `SSTORE` that cleared or restored a slot (EIP-2200/EIP-3529).
+    Sstore {
+        /// Address whose storage was written.
+        address: Address,
+        /// Written slot.
+        key: StorageKey,
+    },
+    /// EIP-7702 authorizations whose authority already existed.
+    Eip7702Authorizations,
+    /// Instruction that did not write storage, e.g. a custom instruction.
+    Instruction,
+}
+
+/// Change of the gas refund counter.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct RefundEvent {
+    /// Operation that changed the refund.
+    pub source: RefundSource,
+    /// Refund added by the operation, negative if a refund was taken back.
+    pub amount: i64,
+    /// Refund counter after the operation.
+    ///
+    /// Refunds are accumulated per frame and merged into the parent frame when the frame
+    /// succeeds, so for instructions this is the counter of the current frame.
+    pub total: i64,
+}
+
 /// EVM hooks into execution.
 ///
 /// This trait is used to enabled tracing of the EVM execution.
@@ -145,6 +175,17 @@ pub trait Inspector<CTX, INTR: InterpreterTypes = EthInterpreter, FI = FrameInpu
         let _ = target;
         let _ = value;
     }
+
+    /// Called when an operation changed the gas refund counter.
+    ///
+    /// Instruction refunds are reported after the instruction is executed and before
+    /// [`Inspector::step_end`], the EIP-7702 authorization refund is reported before the first
+    /// frame is created.
+    #[inline]
+    fn refund(&mut self, context: &mut CTX, event: RefundEvent) {
+        let _ = context;
+        let _ = event;
+    }
 }
 
 impl<CTX, INTR: InterpreterTypes, FI, FR, L, R> Inspector<CTX, INTR, FI, FR> for (L, R)
@@ -219,6 +260,11 @@ where
         self.0.selfdestruct(contract, target, value);
         self.1.selfdestruct(contract, target, value);
     }
+
+    fn refund(&mut self, context: &mut CTX, event: RefundEvent) {
+        self.0.refund(context, event);
+        self.1.refund(context, event);
+    }
 }
 
 /// Extends the journal with additional methods that are used by the inspector.
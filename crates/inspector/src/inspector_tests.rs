@@ -1,7 +1,8 @@
 #[cfg(test)]
 mod tests {
     use crate::{
-        InspectCommitEvm, InspectEvm, InspectSystemCallEvm, InspectorEvent, TestInspector,
+        CountInspector, InspectCommitEvm, InspectEvm, InspectSystemCallEvm, InspectorEvent,
+        RefundEvent, RefundSource, TestInspector,
     };
     use context::{CfgEnv, Context, TxEnv};
     use database::{BenchmarkDB, BENCH_CALLER, BENCH_TARGET};
@@ -79,6 +80,39 @@ mod tests {
         assert!(inspector.get_step_count() >= 5); // PUSH1, PUSH2, ADD, PUSH1, MSTORE, STOP
     }
 
+    #[test]
+    fn test_transact_does_not_call_hooks() {
+        // PUSH1 0x01, PUSH1 0x00, SSTORE, STOP
+        let code = Bytes::from(vec![
+            opcode::PUSH1,
+            0x01,
+            opcode::PUSH1,
+            0x00,
+            opcode::SSTORE,
+            opcode::STOP,
+        ]);
+        let bytecode = Bytecode::new_raw(code);
+        let ctx = Context::mainnet().with_db(BenchmarkDB::new_bytecode(bytecode));
+        let mut evm = ctx.build_mainnet_with_inspector(CountInspector::new());
+        let tx = TxEnv::builder()
+            .caller(BENCH_CALLER)
+            .kind(TxKind::Call(BENCH_TARGET))
+            .gas_limit(100_000)
+            .build()
+            .unwrap();
+
+        // Execution without inspection doesn't call any hook of the attached inspector.
+        let output = evm.transact(tx.clone()).unwrap();
+        assert!(output.result.is_success());
+        assert_eq!(evm.inspector.initialize_interp_count(), 0);
+        assert_eq!(evm.inspector.step_count(), 0);
+        assert_eq!(evm.inspector.call_count(), 0);
+
+        evm.inspect_one_tx(tx).unwrap();
+        assert!(evm.inspector.step_count() > 0);
+        assert_eq!(evm.inspector.call_count(), 1);
+    }
+
     #[test]
     fn test_jump_and_jumpi_control_flow() {
         // PUSH1 0x08, JUMP, INVALID, JUMPDEST, PUSH1 0x01, PUSH1 0x0F, JUMPI, INVALID, JUMPDEST, STOP
@@ -1167,4 +1201,53 @@ mod tests {
         assert_eq!(logs[0].0, BENCH_TARGET);
         assert_eq!(logs[0].2, U256::from(7));
     }
+
+    #[test]
+    fn test_sstore_refund_is_inspected() {
+        // slot0 = 1; slot0 = 0 restores the original value and refunds the write.
+        let code = Bytes::from(vec![
+            opcode::PUSH1,
+            0x01,
+            opcode::PUSH1,
+            0x00,
+            opcode::SSTORE,
+            opcode::PUSH1,
+            0x00,
+            opcode::PUSH1,
+            0x00,
+            opcode::SSTORE,
+            opcode::STOP,
+        ]);
+        let ctx = Context::mainnet().with_db(BenchmarkDB::new_bytecode(Bytecode::new_raw(code)));
+        let mut evm = ctx.build_mainnet_with_inspector(TestInspector::new());
+        evm.inspect_one_tx(
+            TxEnv::builder()
+                .caller(BENCH_CALLER)
+                .kind(TxKind::Call(BENCH_TARGET))
+                .gas_limit(100_000)
+                .build()
+                .unwrap(),
+        )
+        .unwrap();
+
+        let refunds: Vec<RefundEvent> = evm
+            .inspector
+            .get_events()
+            .into_iter()
+            .filter_map(|e| match e {
+                InspectorEvent::Refund(event) => Some(event),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(refunds.len(), 1, "{refunds:?}");
+        assert_eq!(
+            refunds[0].source,
+            RefundSource::Sstore {
+                address: BENCH_TARGET,
+                key: U256::ZERO,
+            }
+        );
+        assert!(refunds[0].amount > 0);
+        assert_eq!(refunds[0].total, refunds[0].amount);
+    }
 }
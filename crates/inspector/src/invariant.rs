@@ -0,0 +1,463 @@
+//! InvariantInspector - Inspector that checks user provided invariants and halts on violation.
+extern crate alloc;
+
+use crate::{Inspector, JournalExt};
+use alloc::{boxed::Box, format, string::String, vec::Vec};
+use context::{ContextTr, JournalTr};
+use core::fmt;
+use database_interface::Database;
+use interpreter::{
+    interpreter_types::{InputsTr, Jumps},
+    CallInputs, CallOutcome, CreateInputs, CreateOutcome, Gas, InstructionResult, Interpreter,
+    InterpreterResult, InterpreterTypes,
+};
+use primitives::{Address, Bytes, StorageKey, StorageValue, U256};
+
+/// Point of execution at which invariants are checked.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum HookPoint {
+    /// Before every instruction.
+    Step,
+    /// Before a call frame is created.
+    Call,
+    /// After a call frame has ended.
+    CallEnd,
+    /// Before a create frame is created.
+    Create,
+    /// After a create frame has ended.
+    CreateEnd,
+}
+
+impl HookPoint {
+    /// Hook points at the end of every frame.
+    pub const FRAME_END: &'static [HookPoint] = &[HookPoint::CallEnd, HookPoint::CreateEnd];
+}
+
+/// Data available to an invariant check.
+#[derive(Debug)]
+pub enum Checkpoint<'a> {
+    /// Instruction is about to be executed.
+    Step {
+        /// Address of the executing contract.
+        address: Address,
+        /// Program counter.
+        pc: usize,
+        /// Opcode.
+        opcode: u8,
+    },
+    /// Call is about to start.
+    Call(&'a CallInputs),
+    /// Call has ended.
+    CallEnd(&'a CallInputs, &'a CallOutcome),
+    /// Create is about to start.
+    Create(&'a CreateInputs),
+    /// Create has ended.
+    CreateEnd(&'a CreateInputs, &'a CreateOutcome),
+}
+
+impl Checkpoint<'_> {
+    /// Returns the hook point of this checkpoint.
+    pub const fn hook_point(&self) -> HookPoint {
+        match self {
+            Self::Step { .. } => HookPoint::Step,
+            Self::Call(_) => HookPoint::Call,
+            Self::CallEnd(..) => HookPoint::CallEnd,
+            Self::Create(_) => HookPoint::Create,
+            Self::CreateEnd(..) => HookPoint::CreateEnd,
+        }
+    }
+}
+
+/// Invariant that was violated.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Violation {
+    /// Name of the violated invariant.
+    pub name: String,
+    /// Message returned by the check.
+    pub message: String,
+    /// Hook point at which the violation was detected.
+    pub hook: HookPoint,
+    /// Call depth at which the violation was detected.
+    pub depth: usize,
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invariant `{}` violated at {:?} (depth {}): {}",
+            self.name, self.hook, self.depth, self.message
+        )
+    }
+}
+
+type CheckFn<CTX> = Box<dyn FnMut(&mut CTX, &Checkpoint<'_>) -> Result<(), String>>;
+
+struct Invariant<CTX> {
+    name: String,
+    hooks: Vec<HookPoint>,
+    check: CheckFn<CTX>,
+}
+
+/// Inspector that evaluates invariants during execution and aborts it when one is violated.
+///
+/// When a check fails, the current frame is halted with [`InstructionResult::InvariantViolation`]
+/// and so is every parent frame, so the transaction ends with
+/// [`HaltReason::InvariantViolation`](context::result::HaltReason::InvariantViolation).
+/// The first violation is available through [`InvariantInspector::violation`] until the next
+/// transaction starts.
+pub struct InvariantInspector<CTX> {
+    invariants: Vec<Invariant<CTX>>,
+    violation: Option<Violation>,
+}
+
+impl<CTX> fmt::Debug for InvariantInspector<CTX> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("InvariantInspector")
+            .field(
+                "invariants",
+                &self
+                    .invariants
+                    .iter()
+                    .map(|i| (&i.name, &i.hooks))
+                    .collect::<Vec<_>>(),
+            )
+            .field("violation", &self.violation)
+            .finish()
+    }
+}
+
+impl<CTX> Default for InvariantInspector<CTX> {
+    fn default() -> Self {
+        Self {
+            invariants: Vec::new(),
+            violation: None,
+        }
+    }
+}
+
+impl<CTX: ContextTr<Journal: JournalExt>> InvariantInspector<CTX> {
+    /// Creates a new inspector without invariants.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds an invariant that is checked at the given hook points.
+    ///
+    /// The check returns an error message if the invariant is violated.
+    pub fn with_check(
+        mut self,
+        name: impl Into<String>,
+        hooks: &[HookPoint],
+        check: impl FnMut(&mut CTX, &Checkpoint<'_>) -> Result<(), String> + 'static,
+    ) -> Self {
+        self.invariants.push(Invariant {
+            name: name.into(),
+            hooks: hooks.to_vec(),
+            check: Box::new(check),
+        });
+        self
+    }
+
+    /// Forbids calls to the given address, including `DELEGATECALL` and `CALLCODE` to its code.
+    pub fn forbid_callee(self, callee: Address) -> Self {
+        self.with_check(
+            format!("forbidden callee {callee}"),
+            &[HookPoint::Call],
+            move |_, checkpoint| match checkpoint {
+                Checkpoint::Call(inputs)
+                    if inputs.target_address == callee || inputs.bytecode_address == callee =>
+                {
+                    Err(format!("{} called {callee}", inputs.caller))
+                }
+                _ => Ok(()),
+            },
+        )
+    }
+
+    /// Requires the balance of `address` to stay at or above `min` after every frame.
+    pub fn min_balance(self, address: Address, min: U256) -> Self {
+        self.with_check(
+            format!("min balance of {address}"),
+            HookPoint::FRAME_END,
+            move |context, _| {
+                let balance = balance(context, address);
+                if balance < min {
+                    return Err(format!("balance {balance} is below {min}"));
+                }
+                Ok(())
+            },
+        )
+    }
+
+    /// Requires the storage slot `key` of `address` to satisfy `predicate` after every frame.
+    pub fn storage_invariant(
+        self,
+        address: Address,
+        key: StorageKey,
+        predicate: impl Fn(StorageValue) -> bool + 'static,
+    ) -> Self {
+        self.with_check(
+            format!("storage {address}[{key}]"),
+            HookPoint::FRAME_END,
+            move |context, _| {
+                let value = storage(context, address, key);
+                if !predicate(value) {
+                    return Err(format!("unexpected value {value}"));
+                }
+                Ok(())
+            },
+        )
+    }
+
+    /// Returns the first violation of the current or last transaction.
+    pub const fn violation(&self) -> Option<&Violation> {
+        self.violation.as_ref()
+    }
+
+    /// Runs all checks registered for the checkpoint and records the first violation.
+    fn check(&mut self, context: &mut CTX, checkpoint: Checkpoint<'_>) -> bool {
+        if self.violation.is_some() {
+            return true;
+        }
+        let hook = checkpoint.hook_point();
+        for invariant in &mut self.invariants {
+            if !invariant.hooks.contains(&hook) {
+                continue;
+            }
+            if let Err(message) = (invariant.check)(context, &checkpoint) {
+                self.violation = Some(Violation {
+                    name: invariant.name.clone(),
+                    message,
+                    hook,
+                    depth: context.journal().depth(),
+                });
+                return true;
+            }
+        }
+        false
+    }
+}
+
+/// Returns the balance from the journal, falling back to the database without warming the account.
+fn balance<CTX: ContextTr<Journal: JournalExt>>(context: &mut CTX, address: Address) -> U256 {
+    if let Some(account) = context.journal().evm_state().get(&address) {
+        return account.info.balance;
+    }
+    context
+        .db_mut()
+        .basic(address)
+        .ok()
+        .flatten()
+        .map(|info| info.balance)
+        .unwrap_or_default()
+}
+
+/// Returns the storage value from the journal, falling back to the database without warming the slot.
+fn storage<CTX: ContextTr<Journal: JournalExt>>(
+    context: &mut CTX,
+    address: Address,
+    key: StorageKey,
+) -> StorageValue {
+    if let Some(slot) = context
+        .journal()
+        .evm_state()
+        .get(&address)
+        .and_then(|account| account.storage.get(&key))
+    {
+        return slot.present_value;
+    }
+    context.db_mut().storage(address, key).unwrap_or_default()
+}
+
+fn violation_result(gas_limit: u64) -> InterpreterResult {
+    let mut gas = Gas::new(gas_limit);
+    gas.spend_all();
+    InterpreterResult::new(InstructionResult::InvariantViolation, Bytes::new(), gas)
+}
+
+fn halt_result(result: &mut InterpreterResult) {
+    result.result = InstructionResult::InvariantViolation;
+    result.output = Bytes::new();
+    result.gas.spend_all();
+}
+
+impl<CTX, INTR> Inspector<CTX, INTR> for InvariantInspector<CTX>
+where
+    CTX: ContextTr<Journal: JournalExt>,
+    INTR: InterpreterTypes,
+{
+    fn step(&mut self, interp: &mut Interpreter<INTR>, context: &mut CTX) {
+        let checkpoint = Checkpoint::Step {
+            address: interp.input.target_address(),
+            pc: interp.bytecode.pc(),
+            opcode: interp.bytecode.opcode(),
+        };
+        if self.check(context, checkpoint) {
+            interp.halt(InstructionResult::InvariantViolation);
+        }
+    }
+
+    fn call(&mut self, context: &mut CTX, inputs: &mut CallInputs) -> Option<CallOutcome> {
+        if context.journal().depth() == 0 {
+            self.violation = None;
+        }
+        self.check(context, Checkpoint::Call(inputs)).then(|| {
+            CallOutcome::new(
+                violation_result(inputs.gas_limit),
+                inputs.return_memory_offset.clone(),
+            )
+        })
+    }
+
+    fn call_end(&mut self, context: &mut CTX, inputs: &CallInputs, outcome: &mut CallOutcome) {
+        if self.check(context, Checkpoint::CallEnd(inputs, outcome)) {
+            halt_result(&mut outcome.result);
+        }
+    }
+
+    fn create(&mut self, context: &mut CTX, inputs: &mut CreateInputs) -> Option<CreateOutcome> {
+        if context.journal().depth() == 0 {
+            self.violation = None;
+        }
+        self.check(context, Checkpoint::Create(inputs))
+            .then(|| CreateOutcome::new(violation_result(inputs.gas_limit()), None))
+    }
+
+    fn create_end(
+        &mut self,
+        context: &mut CTX,
+        inputs: &CreateInputs,
+        outcome: &mut CreateOutcome,
+    ) {
+        if self.check(context, Checkpoint::CreateEnd(inputs, outcome)) {
+            halt_result(&mut outcome.result);
+            outcome.address = None;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::InspectEvm;
+    use context::{
+        result::{ExecutionResult, HaltReason},
+        BlockEnv, CfgEnv, Context, Journal, TxEnv,
+    };
+    use database::{BenchmarkDB, BENCH_CALLER, BENCH_TARGET};
+    use handler::{MainBuilder, MainContext};
+    use primitives::TxKind;
+    use state::bytecode::{opcode, Bytecode};
+
+    fn run<I>(bytecode: Vec<u8>, inspector: I) -> (ExecutionResult, I)
+    where
+        I: Inspector<Context<BlockEnv, TxEnv, CfgEnv, BenchmarkDB, Journal<BenchmarkDB>, ()>>,
+    {
+        let mut evm = Context::mainnet()
+            .with_db(BenchmarkDB::new_bytecode(Bytecode::new_raw(
+                bytecode.into(),
+            )))
+            .build_mainnet_with_inspector(inspector);
+        let result = evm
+            .inspect_one_tx(
+                TxEnv::builder()
+                    .caller(BENCH_CALLER)
+                    .kind(TxKind::Call(BENCH_TARGET))
+                    .gas_limit(1_000_000)
+                    .build()
+                    .unwrap(),
+            )
+            .unwrap();
+        (result, evm.inspector)
+    }
+
+    #[test]
+    fn storage_invariant_halts() {
+        // slot0 = 1
+        let bytecode = vec![
+            opcode::PUSH1,
+            0x1,
+            opcode::PUSH1,
+            0x0,
+            opcode::SSTORE,
+            opcode::STOP,
+        ];
+
+        let inspector =
+            InvariantInspector::new().storage_invariant(BENCH_TARGET, U256::ZERO, |v| v.is_zero());
+        let (result, inspector) = run(bytecode.clone(), inspector);
+        assert!(matches!(
+            result,
+            ExecutionResult::Halt {
+                reason: HaltReason::InvariantViolation,
+                ..
+            }
+        ));
+        let violation = inspector.violation().unwrap();
+        assert_eq!(violation.hook, HookPoint::CallEnd);
+        assert_eq!(violation.depth, 0);
+
+        let inspector =
+            InvariantInspector::new()
+                .storage_invariant(BENCH_TARGET, U256::ZERO, |v| v <= U256::ONE);
+        let (result, inspector) = run(bytecode, inspector);
+        assert!(result.is_success());
+        assert!(inspector.violation().is_none());
+    }
+
+    #[test]
+    fn forbidden_callee_halts_parent() {
+        // CALL(gas, 0x04, 0, 0, 0, 0, 0) and store the result in slot0.
+        let bytecode = vec![
+            opcode::PUSH1,
+            0x0,
+            opcode::PUSH1,
+            0x0,
+            opcode::PUSH1,
+            0x0,
+            opcode::PUSH1,
+            0x0,
+            opcode::PUSH1,
+            0x0,
+            opcode::PUSH1,
+            0x4,
+            opcode::GAS,
+            opcode::CALL,
+            opcode::PUSH1,
+            0x0,
+            opcode::SSTORE,
+            opcode::STOP,
+        ];
+        let identity = Address::with_last_byte(4);
+
+        let (result, inspector) = run(bytecode, InvariantInspector::new().forbid_callee(identity));
+        assert!(matches!(
+            result,
+            ExecutionResult::Halt {
+                reason: HaltReason::InvariantViolation,
+                ..
+            }
+        ));
+        let violation = inspector.violation().unwrap();
+        assert_eq!(violation.hook, HookPoint::Call);
+        assert_eq!(violation.depth, 1);
+    }
+
+    #[test]
+    fn step_check() {
+        let bytecode = vec![opcode::PUSH1, 0x1, opcode::POP, opcode::STOP];
+        let inspector =
+            InvariantInspector::new().with_check("no POP", &[HookPoint::Step], |_, checkpoint| {
+                match checkpoint {
+                    Checkpoint::Step { opcode: op, pc, .. } if *op == opcode::POP => {
+                        Err(format!("POP at {pc}"))
+                    }
+                    _ => Ok(()),
+                }
+            });
+        let (result, inspector) = run(bytecode, inspector);
+        assert!(result.is_halt());
+        assert_eq!(inspector.violation().unwrap().message, "POP at 2");
+    }
+}
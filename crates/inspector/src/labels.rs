@@ -0,0 +1,123 @@
+//! Labels - Registry of human readable names used by tracers when rendering output.
+extern crate alloc;
+
+use alloc::{format, string::String};
+use primitives::{hex, Address, AddressMap, FixedBytes, HashMap};
+
+/// Function selector, the first four bytes of the calldata.
+pub type Selector = FixedBytes<4>;
+
+/// Registry of human readable names for addresses and function selectors.
+///
+/// Tracers that render output consult the registry so traces can show
+/// `Uniswap V3 Router.exactInput` instead of raw hex. The registry is filled by the embedder
+/// and can be shared between tracers with an `Arc`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Labels {
+    /// Names of addresses.
+    addresses: AddressMap<String>,
+    /// Signatures of function selectors, e.g. `transfer(address,uint256)`.
+    selectors: HashMap<Selector, String>,
+}
+
+impl Labels {
+    /// Creates an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Names the address.
+    pub fn with_address(mut self, address: Address, name: impl Into<String>) -> Self {
+        self.insert_address(address, name);
+        self
+    }
+
+    /// Registers the signature of the selector.
+    pub fn with_selector(mut self, selector: Selector, signature: impl Into<String>) -> Self {
+        self.insert_selector(selector, signature);
+        self
+    }
+
+    /// Names the address and returns the previous name.
+    pub fn insert_address(&mut self, address: Address, name: impl Into<String>) -> Option<String> {
+        self.addresses.insert(address, name.into())
+    }
+
+    /// Registers the signature of the selector and returns the previous signature.
+    pub fn insert_selector(
+        &mut self,
+        selector: Selector,
+        signature: impl Into<String>,
+    ) -> Option<String> {
+        self.selectors.insert(selector, signature.into())
+    }
+
+    /// Returns the name of the address.
+    pub fn address(&self, address: &Address) -> Option<&str> {
+        self.addresses.get(address).map(String::as_str)
+    }
+
+    /// Returns the signature of the selector.
+    pub fn signature(&self, selector: &Selector) -> Option<&str> {
+        self.selectors.get(selector).map(String::as_str)
+    }
+
+    /// Returns the function name of the selector, the signature without its parameters.
+    pub fn function_name(&self, selector: &Selector) -> Option<&str> {
+        self.signature(selector)
+            .map(|signature| signature.split('(').next().unwrap_or(signature))
+    }
+
+    /// Renders the address as its name, or as hex if it has no name.
+    pub fn format_address(&self, address: &Address) -> String {
+        match self.address(address) {
+            Some(name) => name.into(),
+            None => format!("{address}"),
+        }
+    }
+
+    /// Renders a call to `target` with the given calldata as `target.function`.
+    ///
+    /// Unknown selectors are rendered as hex, calls without a selector only render the target.
+    pub fn format_call(&self, target: &Address, input: &[u8]) -> String {
+        let target = self.format_address(target);
+        let Some(selector) = input.get(..4).map(Selector::from_slice) else {
+            return target;
+        };
+        match self.function_name(&selector) {
+            Some(name) => format!("{target}.{name}"),
+            None => format!("{target}.{}", hex::encode_prefixed(selector)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use primitives::{address, fixed_bytes};
+
+    #[test]
+    fn renders_labels() {
+        let router = address!("0xE592427A0AEce92De3Edee1F18E0157C05861564");
+        let labels = Labels::new()
+            .with_address(router, "Uniswap V3 Router")
+            .with_selector(
+                fixed_bytes!("0xc04b8d59"),
+                "exactInput((bytes,address,uint256,uint256,uint256))",
+            );
+
+        assert_eq!(
+            labels.format_call(&router, &[0xc0, 0x4b, 0x8d, 0x59, 0x00]),
+            "Uniswap V3 Router.exactInput"
+        );
+        assert_eq!(
+            labels.format_call(&router, &[0x12, 0x34, 0x56, 0x78]),
+            "Uniswap V3 Router.0x12345678"
+        );
+        assert_eq!(labels.format_call(&router, &[]), "Uniswap V3 Router");
+        assert_eq!(
+            labels.format_address(&Address::ZERO),
+            "0x0000000000000000000000000000000000000000"
+        );
+    }
+}
@@ -5,29 +5,102 @@
 #![cfg_attr(not(test), warn(unused_crate_dependencies))]
 #![cfg_attr(not(feature = "std"), no_std)]
 
+mod access_list;
+mod access_stats;
+mod breakpoints;
+mod call_graph;
+#[cfg(feature = "tracer")]
+mod call_tracer;
+#[cfg(feature = "async")]
+mod channel;
 mod count_inspector;
+mod deployment;
 #[cfg(feature = "tracer")]
 mod eip3155;
 mod either;
+mod execution_limit;
 mod gas;
+mod gas_griefing;
 /// Handler implementations for inspector integration.
 pub mod handler;
 mod inspect;
 mod inspector;
+mod invariant;
+mod labels;
+mod log_filter;
 mod mainnet_inspect;
+mod memory_snapshot;
 mod noop;
+#[cfg(feature = "profiling")]
+mod opcode_profiler;
+#[cfg(feature = "tracer")]
+mod parity_tracer;
+mod precompile_io;
+mod resources;
+#[cfg(feature = "tracer")]
+mod sink;
+mod sstore_heatmap;
+mod storage_diff;
+#[cfg(feature = "tracer")]
+mod storage_layout;
 /// Test inspector for testing EVM execution.
 pub mod test_inspector;
+#[cfg(feature = "std")]
+mod timing;
+#[cfg(feature = "tracer")]
+mod trace_verifier;
 mod traits;
+mod transfers;
 
 #[cfg(test)]
 mod inspector_tests;
 
 /// Inspector implementations.
 pub mod inspectors {
+    pub use super::access_list::{create_access_list, AccessListInspector, AccessListResult};
+    pub use super::access_stats::{AccessStats, AccessStatsInspector};
+    pub use super::breakpoints::{Breakpoint, Breakpoints};
+    pub use super::call_graph::{CallEdge, CallGraph};
     #[cfg(feature = "tracer")]
-    pub use super::eip3155::TracerEip3155;
-    pub use super::gas::GasInspector;
+    pub use super::call_tracer::{CallFrame, CallFrameType, CallLog, CallTracer};
+    pub use super::deployment::{
+        Create2Mismatch, Deployment, DeploymentInspector, ExpectedCreate2,
+    };
+    #[cfg(feature = "tracer")]
+    pub use super::eip3155::{binary_trace_to_json, CaptureConfig, TraceFormat, TracerEip3155};
+    pub use super::execution_limit::ExecutionLimitInspector;
+    pub use super::gas::{
+        CallGasInspector, FrameGas, FrameKind, GasInspector, Proxy, ProxyKind, EIP1967_BEACON_SLOT,
+        EIP1967_IMPLEMENTATION_SLOT,
+    };
+    pub use super::gas_griefing::{CappedCall, GasGriefingInspector};
+    pub use super::invariant::{Checkpoint, HookPoint, InvariantInspector, Violation};
+    pub use super::log_filter::{FilteredLog, LogFilter, LogFilterInspector};
+    pub use super::memory_snapshot::{
+        MemorySnapshot, MemorySnapshotInspector, MemoryTrace, SnapshotReason, DEFAULT_CHUNK_SIZE,
+    };
+    #[cfg(feature = "profiling")]
+    pub use super::opcode_profiler::{OpcodeLatency, OpcodeProfiler};
+    #[cfg(feature = "tracer")]
+    pub use super::parity_tracer::{
+        AccountDiff, Action, CallAction, CallOutput, CallType, CreateAction, CreateOutput,
+        CreationMethod, Delta, MemoryDelta, ParityTracer, SelfdestructAction, StateDiff,
+        StorageDelta, TraceOutput, TraceResults, TraceType, TransactionTrace, VmExecutedOperation,
+        VmInstruction, VmTrace,
+    };
+    pub use super::resources::{FrameResources, ResourceInspector};
+    pub use super::sstore_heatmap::{SlotStats, SstoreHeatmapInspector};
+    pub use super::storage_diff::{SlotDiff, StorageDiffInspector, StorageWrite};
+    #[cfg(feature = "tracer")]
+    pub use super::storage_layout::{
+        DecodedVariable, StorageAccess, StorageLayout, StorageLayoutInspector, StorageOp,
+        StorageType, StorageVariable,
+    };
+    #[cfg(feature = "std")]
+    pub use super::timing::{FrameTiming, TimingInspector};
+    #[cfg(feature = "tracer")]
+    pub use super::trace_verifier::{Divergence, DivergenceKind, TraceStep, TraceVerifier};
+    pub use super::transfers::{Transfer, TransferInspector, TransferKind};
 }
 
 pub use context;
@@ -37,11 +110,21 @@ pub use interpreter;
 pub use primitives;
 pub use state;
 
+#[cfg(feature = "async")]
+pub use channel::{
+    drive_async_inspector, AsyncInspector, Backpressure, ChannelInspector, HookEvent,
+};
 pub use count_inspector::CountInspector;
 pub use handler::{inspect_instructions, InspectorHandler};
 pub use inspect::{InspectCommitEvm, InspectEvm, InspectSystemCallEvm};
 pub use inspector::*;
+pub use labels::{Labels, Selector};
 pub use noop::NoOpInspector;
+pub use precompile_io::{PrecompileIo, DEFAULT_PRECOMPILE_IO_LIMIT};
+#[cfg(all(feature = "tracer", feature = "async"))]
+pub use sink::LineSender;
+#[cfg(feature = "tracer")]
+pub use sink::TraceSink;
 pub use test_inspector::{InspectorEvent, InterpreterState, StepRecord, TestInspector};
 pub use traits::*;
 
@@ -0,0 +1,246 @@
+//! LogFilterInspector - Inspector that collects the logs matching a filter.
+extern crate alloc;
+
+use crate::Inspector;
+use alloc::vec::Vec;
+use context::{ContextTr, JournalTr};
+use interpreter::{
+    CallInputs, CallOutcome, CreateInputs, CreateOutcome, InterpreterResult, InterpreterTypes,
+};
+use primitives::{Address, Log, B256};
+
+/// Filter of [`LogFilterInspector`] with the semantics of `eth_getLogs`.
+///
+/// A log matches if it was emitted by one of the addresses and every topic position matches one
+/// of its values. Empty address or topic lists match anything, so the default filter matches all
+/// logs.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct LogFilter {
+    /// Addresses that emitted the log.
+    pub addresses: Vec<Address>,
+    /// Accepted values of the topics by position, the first topic is the event signature.
+    pub topics: [Vec<B256>; 4],
+}
+
+impl LogFilter {
+    /// Creates a filter that matches all logs.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Accepts logs emitted by the address.
+    pub fn address(mut self, address: Address) -> Self {
+        self.addresses.push(address);
+        self
+    }
+
+    /// Accepts logs of the event with the given signature hash, the first topic.
+    pub fn event(self, signature: B256) -> Self {
+        self.topic(0, signature)
+    }
+
+    /// Accepts logs with the value at the topic position.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the position is not lower than 4.
+    pub fn topic(mut self, position: usize, value: B256) -> Self {
+        self.topics[position].push(value);
+        self
+    }
+
+    /// Returns `true` if the log matches the filter.
+    pub fn matches(&self, log: &Log) -> bool {
+        if !self.addresses.is_empty() && !self.addresses.contains(&log.address) {
+            return false;
+        }
+        let topics = log.topics();
+        self.topics.iter().enumerate().all(|(position, accepted)| {
+            accepted.is_empty()
+                || topics
+                    .get(position)
+                    .is_some_and(|topic| accepted.contains(topic))
+        })
+    }
+}
+
+/// Log collected by [`LogFilterInspector`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FilteredLog {
+    /// The log.
+    pub log: Log,
+    /// Index of the log in the logs of the transaction.
+    pub log_index: usize,
+    /// Depth of the frame that emitted the log.
+    pub depth: usize,
+}
+
+/// Inspector that collects only the logs matching a [`LogFilter`].
+///
+/// Logs of reverted frames are dropped like they are dropped from the result of the transaction,
+/// so the collected logs are the matching subset of `ExecutionResult::logs`, which does not need
+/// to be kept for huge transactions. Logs are collected for the current or last transaction and
+/// cleared when the next transaction starts.
+#[derive(Clone, Debug, Default)]
+pub struct LogFilterInspector {
+    filter: LogFilter,
+    logs: Vec<FilteredLog>,
+    /// Number of logs of the transaction.
+    log_count: usize,
+    /// Number of collected logs and logs of the transaction at the start of every frame.
+    checkpoints: Vec<(usize, usize)>,
+}
+
+impl LogFilterInspector {
+    /// Creates a new inspector with the given filter.
+    pub fn new(filter: LogFilter) -> Self {
+        Self {
+            filter,
+            ..Default::default()
+        }
+    }
+
+    /// Returns the filter.
+    pub const fn filter(&self) -> &LogFilter {
+        &self.filter
+    }
+
+    /// Returns the collected logs in emission order.
+    pub fn logs(&self) -> &[FilteredLog] {
+        &self.logs
+    }
+
+    /// Takes the collected logs.
+    pub fn take_logs(&mut self) -> Vec<FilteredLog> {
+        core::mem::take(&mut self.logs)
+    }
+
+    /// Clears the collected logs.
+    pub fn clear(&mut self) {
+        self.logs.clear();
+        self.log_count = 0;
+        self.checkpoints.clear();
+    }
+
+    fn frame_start<CTX: ContextTr>(&mut self, context: &mut CTX) {
+        if context.journal().depth() == 0 {
+            self.clear();
+        }
+        self.checkpoints.push((self.logs.len(), self.log_count));
+    }
+
+    fn frame_end(&mut self, result: &InterpreterResult) {
+        let Some((logs, log_count)) = self.checkpoints.pop() else {
+            return;
+        };
+        if !result.is_ok() {
+            self.logs.truncate(logs);
+            self.log_count = log_count;
+        }
+    }
+}
+
+impl<CTX: ContextTr, INTR: InterpreterTypes> Inspector<CTX, INTR> for LogFilterInspector {
+    fn log(&mut self, context: &mut CTX, log: Log) {
+        let log_index = self.log_count;
+        self.log_count += 1;
+        if self.filter.matches(&log) {
+            self.logs.push(FilteredLog {
+                log,
+                log_index,
+                depth: context.journal().depth(),
+            });
+        }
+    }
+
+    fn call(&mut self, context: &mut CTX, _inputs: &mut CallInputs) -> Option<CallOutcome> {
+        self.frame_start(context);
+        None
+    }
+
+    fn call_end(&mut self, _context: &mut CTX, _inputs: &CallInputs, outcome: &mut CallOutcome) {
+        self.frame_end(&outcome.result);
+    }
+
+    fn create(&mut self, context: &mut CTX, _inputs: &mut CreateInputs) -> Option<CreateOutcome> {
+        self.frame_start(context);
+        None
+    }
+
+    fn create_end(
+        &mut self,
+        _context: &mut CTX,
+        _inputs: &CreateInputs,
+        outcome: &mut CreateOutcome,
+    ) {
+        self.frame_end(&outcome.result);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::InspectEvm;
+    use context::{Context, TxEnv};
+    use database::{BenchmarkDB, BENCH_CALLER, BENCH_TARGET};
+    use handler::{MainBuilder, MainContext};
+    use primitives::{Bytes, LogData, TxKind};
+    use state::bytecode::{opcode, Bytecode};
+
+    #[test]
+    fn filter_matches() {
+        let transfer = B256::with_last_byte(1);
+        let log = Log {
+            address: BENCH_TARGET,
+            data: LogData::new(std::vec![transfer, B256::with_last_byte(2)], Bytes::new()).unwrap(),
+        };
+        assert!(LogFilter::new().matches(&log));
+        assert!(LogFilter::new().event(transfer).matches(&log));
+        assert!(LogFilter::new()
+            .event(B256::ZERO)
+            .event(transfer)
+            .topic(1, B256::with_last_byte(2))
+            .matches(&log));
+        assert!(!LogFilter::new().topic(2, B256::ZERO).matches(&log));
+        assert!(!LogFilter::new().address(BENCH_CALLER).matches(&log));
+    }
+
+    #[test]
+    fn collects_matching_logs() {
+        // Emits logs with the topics 1, 2 and 1 without data.
+        let mut code = Vec::new();
+        for topic in [1, 2, 1] {
+            code.extend_from_slice(&[
+                opcode::PUSH1,
+                topic,
+                opcode::PUSH0,
+                opcode::PUSH0,
+                opcode::LOG1,
+            ]);
+        }
+        code.push(opcode::STOP);
+
+        let filter = LogFilter::new().event(B256::with_last_byte(1));
+        let mut evm = Context::mainnet()
+            .with_db(BenchmarkDB::new_bytecode(Bytecode::new_raw(code.into())))
+            .build_mainnet_with_inspector(LogFilterInspector::new(filter));
+        let result = evm
+            .inspect_tx(
+                TxEnv::builder()
+                    .caller(BENCH_CALLER)
+                    .kind(TxKind::Call(BENCH_TARGET))
+                    .gas_limit(100_000)
+                    .build()
+                    .unwrap(),
+            )
+            .unwrap();
+        assert_eq!(result.result.logs().len(), 3);
+
+        let logs = evm.inspector.logs();
+        assert_eq!(logs.len(), 2);
+        assert_eq!(logs[0].log_index, 0);
+        assert_eq!(logs[1].log_index, 2);
+        assert_eq!(logs[1].log, result.result.logs()[2]);
+        assert_eq!(logs[1].depth, 1);
+    }
+}
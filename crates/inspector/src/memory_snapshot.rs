@@ -0,0 +1,332 @@
+//! MemorySnapshotInspector - Inspector that captures memory with chunk deduplication.
+extern crate alloc;
+
+use crate::Inspector;
+use alloc::vec::Vec;
+use context::{ContextTr, JournalTr};
+use core::ops::Range;
+use interpreter::{
+    interpreter_types::{Jumps, MemoryTr, StackTr},
+    Interpreter, InterpreterTypes,
+};
+use primitives::{keccak256, Bytes, HashMap, B256};
+use state::bytecode::opcode;
+
+/// Default size of the memory chunks, see [`MemorySnapshotInspector::with_chunk_size`].
+pub const DEFAULT_CHUNK_SIZE: usize = 1024;
+
+/// Why a memory snapshot was taken.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SnapshotReason {
+    /// Frame is about to make a call or create.
+    Call,
+    /// Every N steps.
+    Interval,
+    /// `MSTORE` or `MSTORE8` wrote into a watched range, taken after the write.
+    Store,
+}
+
+/// Memory of a frame at a point of execution.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MemorySnapshot {
+    /// Number of steps executed before the snapshot.
+    pub step: u64,
+    /// Call depth.
+    pub depth: usize,
+    /// Program counter of the instruction.
+    pub pc: usize,
+    /// Why the snapshot was taken.
+    pub reason: SnapshotReason,
+    /// Size of the memory in bytes.
+    pub len: usize,
+    /// Indices of the memory chunks in [`MemoryTrace::chunks`].
+    pub chunks: Vec<u32>,
+}
+
+/// Memory snapshots of a transaction, the export format of [`MemorySnapshotInspector`].
+///
+/// Memory is split in chunks of `chunk_size` bytes and every distinct chunk is stored once,
+/// snapshots only reference the chunks. Memory that does not change between snapshots is
+/// shared, so full-memory traces of large transactions stay small.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MemoryTrace {
+    /// Size of the chunks in bytes, the last chunk of a snapshot can be shorter.
+    pub chunk_size: usize,
+    /// Distinct chunks.
+    pub chunks: Vec<Bytes>,
+    /// Snapshots in execution order.
+    pub snapshots: Vec<MemorySnapshot>,
+}
+
+impl MemoryTrace {
+    /// Returns the memory of the snapshot at `index`.
+    pub fn memory(&self, index: usize) -> Option<Vec<u8>> {
+        let snapshot = self.snapshots.get(index)?;
+        let mut memory = Vec::with_capacity(snapshot.len);
+        for &chunk in &snapshot.chunks {
+            memory.extend_from_slice(self.chunks.get(chunk as usize)?);
+        }
+        Some(memory)
+    }
+}
+
+/// Inspector that captures memory at configurable points of execution.
+///
+/// Snapshots can be taken before calls and creates ([`MemorySnapshotInspector::with_calls`]),
+/// every N steps ([`MemorySnapshotInspector::with_interval`]) and after `MSTORE`s into watched
+/// ranges ([`MemorySnapshotInspector::with_store_range`]). Chunks are deduplicated, see
+/// [`MemoryTrace`].
+#[derive(Clone, Debug)]
+pub struct MemorySnapshotInspector {
+    trace: MemoryTrace,
+    /// Index of every distinct chunk by its hash.
+    chunk_ids: HashMap<B256, u32>,
+    calls: bool,
+    interval: Option<u64>,
+    store_ranges: Vec<Range<usize>>,
+    steps: u64,
+    /// Store snapshot that is taken when the instruction has been executed.
+    pending: Option<(usize, usize)>,
+}
+
+impl Default for MemorySnapshotInspector {
+    fn default() -> Self {
+        Self {
+            trace: MemoryTrace {
+                chunk_size: DEFAULT_CHUNK_SIZE,
+                ..Default::default()
+            },
+            chunk_ids: HashMap::default(),
+            calls: false,
+            interval: None,
+            store_ranges: Vec::new(),
+            steps: 0,
+            pending: None,
+        }
+    }
+}
+
+impl MemorySnapshotInspector {
+    /// Creates an inspector that takes no snapshots until snapshot points are configured.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Takes a snapshot before every call and create.
+    pub fn with_calls(mut self) -> Self {
+        self.calls = true;
+        self
+    }
+
+    /// Takes a snapshot every `steps` steps.
+    pub fn with_interval(mut self, steps: u64) -> Self {
+        self.interval = Some(steps.max(1));
+        self
+    }
+
+    /// Takes a snapshot after every `MSTORE` or `MSTORE8` that writes into the range.
+    pub fn with_store_range(mut self, range: Range<usize>) -> Self {
+        self.store_ranges.push(range);
+        self
+    }
+
+    /// Sets the size of the memory chunks, smaller chunks deduplicate better but need more
+    /// indices per snapshot.
+    ///
+    /// # Panics
+    ///
+    /// Panics if snapshots were already taken.
+    pub fn with_chunk_size(mut self, chunk_size: usize) -> Self {
+        assert!(
+            self.trace.snapshots.is_empty(),
+            "chunk size can't change after snapshots were taken"
+        );
+        self.trace.chunk_size = chunk_size.max(1);
+        self
+    }
+
+    /// Returns the captured snapshots.
+    pub const fn trace(&self) -> &MemoryTrace {
+        &self.trace
+    }
+
+    /// Consumes the inspector and returns the captured snapshots.
+    pub fn into_trace(self) -> MemoryTrace {
+        self.trace
+    }
+
+    /// Clears the captured snapshots.
+    pub fn clear(&mut self) {
+        self.trace.chunks.clear();
+        self.trace.snapshots.clear();
+        self.chunk_ids.clear();
+        self.steps = 0;
+        self.pending = None;
+    }
+
+    fn snapshot<INTR: InterpreterTypes>(
+        &mut self,
+        interp: &Interpreter<INTR>,
+        depth: usize,
+        pc: usize,
+        reason: SnapshotReason,
+    ) {
+        let len = interp.memory.size();
+        let chunk_size = self.trace.chunk_size;
+        let mut chunks = Vec::with_capacity(len.div_ceil(chunk_size));
+        for start in (0..len).step_by(chunk_size) {
+            let chunk = interp.memory.slice(start..len.min(start + chunk_size));
+            let next_id = self.trace.chunks.len() as u32;
+            let id = *self.chunk_ids.entry(keccak256(&*chunk)).or_insert(next_id);
+            if id == next_id {
+                self.trace.chunks.push(Bytes::copy_from_slice(&chunk));
+            }
+            chunks.push(id);
+        }
+        self.trace.snapshots.push(MemorySnapshot {
+            step: self.steps,
+            depth,
+            pc,
+            reason,
+            len,
+            chunks,
+        });
+    }
+
+    /// Returns `true` if the store at the top of the stack writes into a watched range.
+    fn is_watched_store<INTR: InterpreterTypes>(&self, interp: &Interpreter<INTR>, op: u8) -> bool {
+        let len = match op {
+            opcode::MSTORE => 32,
+            opcode::MSTORE8 => 1,
+            _ => return false,
+        };
+        let Some(offset) = interp.stack.data().last() else {
+            return false;
+        };
+        let start = offset.saturating_to::<usize>();
+        let end = start.saturating_add(len);
+        self.store_ranges
+            .iter()
+            .any(|range| start < range.end && range.start < end)
+    }
+}
+
+impl<CTX, INTR> Inspector<CTX, INTR> for MemorySnapshotInspector
+where
+    CTX: ContextTr,
+    INTR: InterpreterTypes,
+{
+    fn step(&mut self, interp: &mut Interpreter<INTR>, context: &mut CTX) {
+        let op = interp.bytecode.opcode();
+        let pc = interp.bytecode.pc();
+        let depth = context.journal().depth();
+
+        if self.interval.is_some_and(|n| self.steps % n == 0) {
+            self.snapshot(interp, depth, pc, SnapshotReason::Interval);
+        } else if self.calls
+            && matches!(
+                op,
+                opcode::CALL
+                    | opcode::CALLCODE
+                    | opcode::DELEGATECALL
+                    | opcode::STATICCALL
+                    | opcode::CREATE
+                    | opcode::CREATE2
+            )
+        {
+            self.snapshot(interp, depth, pc, SnapshotReason::Call);
+        }
+        if !self.store_ranges.is_empty() && self.is_watched_store(interp, op) {
+            self.pending = Some((depth, pc));
+        }
+        self.steps += 1;
+    }
+
+    fn step_end(&mut self, interp: &mut Interpreter<INTR>, _context: &mut CTX) {
+        if let Some((depth, pc)) = self.pending.take() {
+            self.snapshot(interp, depth, pc, SnapshotReason::Store);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::InspectEvm;
+    use context::{Context, TxEnv};
+    use database::{BenchmarkDB, BENCH_CALLER, BENCH_TARGET};
+    use handler::{MainBuilder, MainContext};
+    use primitives::TxKind;
+    use state::bytecode::Bytecode;
+
+    fn run(inspector: MemorySnapshotInspector) -> MemoryTrace {
+        // mem[0..32] = 1; mem[32..64] = 1; mem[32..64] = 2
+        let bytecode = Bytecode::new_raw(
+            [
+                opcode::PUSH1,
+                0x1,
+                opcode::PUSH1,
+                0x0,
+                opcode::MSTORE,
+                opcode::PUSH1,
+                0x1,
+                opcode::PUSH1,
+                0x20,
+                opcode::MSTORE,
+                opcode::PUSH1,
+                0x2,
+                opcode::PUSH1,
+                0x20,
+                opcode::MSTORE,
+                opcode::STOP,
+            ]
+            .to_vec()
+            .into(),
+        );
+        let mut evm = Context::mainnet()
+            .with_db(BenchmarkDB::new_bytecode(bytecode))
+            .build_mainnet_with_inspector(inspector);
+        evm.inspect_one_tx(
+            TxEnv::builder()
+                .caller(BENCH_CALLER)
+                .kind(TxKind::Call(BENCH_TARGET))
+                .gas_limit(100_000)
+                .build()
+                .unwrap(),
+        )
+        .unwrap();
+        core::mem::take(&mut evm.inspector.trace)
+    }
+
+    #[test]
+    fn chunks_are_deduplicated() {
+        let trace = run(MemorySnapshotInspector::new()
+            .with_interval(1)
+            .with_chunk_size(32));
+        assert_eq!(trace.snapshots.len(), 10);
+        // Chunks holding `1` and `2`, empty memory has no chunks.
+        assert_eq!(trace.chunks.len(), 2);
+
+        let last = trace.snapshots.len() - 1;
+        let memory = trace.memory(last).unwrap();
+        assert_eq!(memory.len(), 64);
+        assert_eq!(memory[31], 1);
+        assert_eq!(memory[63], 2);
+        assert_eq!(trace.snapshots[last].chunks, [0, 1]);
+    }
+
+    #[test]
+    fn snapshots_watched_stores() {
+        let trace = run(MemorySnapshotInspector::new().with_store_range(0x30..0x31));
+        let pcs: Vec<_> = trace.snapshots.iter().map(|s| s.pc).collect();
+        assert_eq!(pcs, [9, 14]);
+        assert!(trace
+            .snapshots
+            .iter()
+            .all(|s| s.reason == SnapshotReason::Store && s.len == 64));
+        assert_eq!(trace.memory(1).unwrap()[63], 2);
+    }
+}
@@ -0,0 +1,237 @@
+//! OpcodeProfiler - Inspector that records the execution latency of every opcode.
+use crate::Inspector;
+use core::fmt::Write;
+use hdrhistogram::Histogram;
+use interpreter::{interpreter_types::Jumps, Interpreter, InterpreterTypes};
+use state::bytecode::opcode::OpCode;
+use std::{
+    string::String,
+    time::{Duration, Instant},
+    vec::Vec,
+};
+
+/// Highest recorded latency in nanoseconds, slower executions are recorded as this value.
+const MAX_LATENCY_NANOS: u64 = 60_000_000_000;
+
+/// Number of significant digits of the recorded latencies.
+const SIGNIFICANT_DIGITS: u8 = 3;
+
+/// Latency statistics of a single opcode, see [`OpcodeProfiler::report`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OpcodeLatency {
+    /// Opcode.
+    pub opcode: u8,
+    /// Name of the opcode, `UNKNOWN` for undefined opcodes.
+    pub name: &'static str,
+    /// Number of executions.
+    pub count: u64,
+    /// Total time spent executing the opcode, within the precision of the histogram.
+    pub total: Duration,
+    /// Fastest execution.
+    pub min: Duration,
+    /// Median execution.
+    pub p50: Duration,
+    /// 99th percentile of the executions.
+    pub p99: Duration,
+    /// Slowest execution.
+    pub max: Duration,
+}
+
+/// Inspector that records the latency of each executed opcode into a HDR histogram per opcode.
+///
+/// Latency is measured between the step and step end hooks. Call and create opcodes are measured
+/// up to the point the new frame is requested, the execution of the frame is not included.
+///
+/// Recorded latencies include the overhead of the inspection and the clock, they are meant to be
+/// compared between revisions of the interpreter on the same workload and machine. Profilers of
+/// several workloads can be combined with [`OpcodeProfiler::merge`].
+#[derive(Clone, Debug)]
+pub struct OpcodeProfiler {
+    /// Histograms of the latency in nanoseconds, indexed by opcode.
+    histograms: Vec<Option<Histogram<u64>>>,
+    /// Opcode being executed and the time its execution started.
+    pending: Option<(u8, Instant)>,
+}
+
+impl Default for OpcodeProfiler {
+    fn default() -> Self {
+        Self {
+            histograms: (0..256).map(|_| None).collect(),
+            pending: None,
+        }
+    }
+}
+
+impl OpcodeProfiler {
+    /// Creates a new profiler.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the histogram of the latency of the opcode in nanoseconds.
+    ///
+    /// Returns [`None`] if the opcode was not executed.
+    pub fn histogram(&self, opcode: u8) -> Option<&Histogram<u64>> {
+        self.histograms[opcode as usize].as_ref()
+    }
+
+    /// Records an execution of the opcode that took `elapsed`.
+    pub fn record(&mut self, opcode: u8, elapsed: Duration) {
+        let nanos = u64::try_from(elapsed.as_nanos()).unwrap_or(u64::MAX);
+        self.histograms[opcode as usize]
+            .get_or_insert_with(new_histogram)
+            .saturating_record(nanos.max(1));
+    }
+
+    /// Adds the latencies recorded by the other profiler.
+    pub fn merge(&mut self, other: &Self) {
+        for (histogram, other) in self.histograms.iter_mut().zip(&other.histograms) {
+            let Some(other) = other else {
+                continue;
+            };
+            match histogram {
+                // Both histograms have the same bounds, adding them can't fail.
+                Some(histogram) => histogram
+                    .add(other)
+                    .expect("histograms have the same bounds"),
+                None => *histogram = Some(other.clone()),
+            }
+        }
+    }
+
+    /// Returns the latency statistics of the executed opcodes, the most time consuming first.
+    pub fn report(&self) -> Vec<OpcodeLatency> {
+        let mut report: Vec<_> = self
+            .histograms
+            .iter()
+            .enumerate()
+            .filter_map(|(opcode, histogram)| {
+                let histogram = histogram.as_ref()?;
+                let opcode = opcode as u8;
+                let total = histogram.mean() * histogram.len() as f64;
+                Some(OpcodeLatency {
+                    opcode,
+                    name: OpCode::new(opcode).map_or("UNKNOWN", |op| op.as_str()),
+                    count: histogram.len(),
+                    total: Duration::from_nanos(total as u64),
+                    min: Duration::from_nanos(histogram.min()),
+                    p50: Duration::from_nanos(histogram.value_at_quantile(0.5)),
+                    p99: Duration::from_nanos(histogram.value_at_quantile(0.99)),
+                    max: Duration::from_nanos(histogram.max()),
+                })
+            })
+            .collect();
+        report.sort_by_key(|latency| core::cmp::Reverse(latency.total));
+        report
+    }
+
+    /// Renders the report as a table, one opcode per line.
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        let _ = writeln!(
+            out,
+            "{:<16} {:>12} {:>14} {:>10} {:>10} {:>10} {:>10}",
+            "opcode", "count", "total", "min", "p50", "p99", "max"
+        );
+        for latency in self.report() {
+            let _ = writeln!(
+                out,
+                "{:<16} {:>12} {:>14?} {:>10?} {:>10?} {:>10?} {:>10?}",
+                latency.name,
+                latency.count,
+                latency.total,
+                latency.min,
+                latency.p50,
+                latency.p99,
+                latency.max,
+            );
+        }
+        out
+    }
+
+    /// Clears all recorded latencies.
+    pub fn clear(&mut self) {
+        self.histograms
+            .iter_mut()
+            .for_each(|histogram| *histogram = None);
+        self.pending = None;
+    }
+}
+
+fn new_histogram() -> Histogram<u64> {
+    Histogram::new_with_bounds(1, MAX_LATENCY_NANOS, SIGNIFICANT_DIGITS)
+        .expect("valid histogram bounds")
+}
+
+impl<CTX, INTR: InterpreterTypes> Inspector<CTX, INTR> for OpcodeProfiler {
+    #[inline]
+    fn step(&mut self, interp: &mut Interpreter<INTR>, _context: &mut CTX) {
+        self.pending = Some((interp.bytecode.opcode(), Instant::now()));
+    }
+
+    #[inline]
+    fn step_end(&mut self, _interp: &mut Interpreter<INTR>, _context: &mut CTX) {
+        if let Some((opcode, start)) = self.pending.take() {
+            self.record(opcode, start.elapsed());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::InspectEvm;
+    use context::{Context, TxEnv};
+    use database::{BenchmarkDB, BENCH_CALLER, BENCH_TARGET};
+    use handler::{MainBuilder, MainContext};
+    use primitives::TxKind;
+    use state::bytecode::{opcode, Bytecode};
+
+    #[test]
+    fn records_opcode_latency() {
+        let bytecode = Bytecode::new_raw(
+            [
+                opcode::PUSH1,
+                0x01,
+                opcode::PUSH1,
+                0x02,
+                opcode::ADD,
+                opcode::POP,
+                opcode::STOP,
+            ]
+            .to_vec()
+            .into(),
+        );
+        let mut evm = Context::mainnet()
+            .with_db(BenchmarkDB::new_bytecode(bytecode))
+            .build_mainnet_with_inspector(OpcodeProfiler::new());
+        evm.inspect_one_tx(
+            TxEnv::builder()
+                .caller(BENCH_CALLER)
+                .kind(TxKind::Call(BENCH_TARGET))
+                .gas_limit(100_000)
+                .build()
+                .unwrap(),
+        )
+        .unwrap();
+
+        let profiler = evm.inspector.clone();
+        assert_eq!(profiler.histogram(opcode::PUSH1).unwrap().len(), 2);
+        assert_eq!(profiler.histogram(opcode::ADD).unwrap().len(), 1);
+        assert!(profiler.histogram(opcode::MUL).is_none());
+
+        let report = profiler.report();
+        assert_eq!(report.len(), 4);
+        let push = report.iter().find(|l| l.name == "PUSH1").unwrap();
+        assert_eq!(push.count, 2);
+        assert!(push.min <= push.p50 && push.p50 <= push.max);
+        assert_eq!(profiler.render().lines().count(), 5);
+
+        let mut merged = profiler.clone();
+        merged.merge(&profiler);
+        assert_eq!(merged.histogram(opcode::PUSH1).unwrap().len(), 4);
+
+        merged.clear();
+        assert!(merged.report().is_empty());
+    }
+}
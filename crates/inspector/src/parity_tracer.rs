@@ -0,0 +1,895 @@
+//! ParityTracer - Records `trace`, `vmTrace` and `stateDiff` in the format of Parity/OpenEthereum.
+use crate::{
+    call_tracer::{serde_address, serde_hex_u64},
+    Inspector,
+};
+use context::ContextTr;
+use database_interface::DatabaseRef;
+use interpreter::{
+    interpreter_types::{Jumps, LegacyBytecode, LoopControl, MemoryTr, StackTr},
+    CallInputs, CallOutcome, CallScheme, CreateInputs, CreateOutcome, CreateScheme,
+    InstructionResult, Interpreter, InterpreterResult, InterpreterTypes,
+};
+use primitives::{hex, Address, Bytes, B256, KECCAK_EMPTY, U256};
+use serde::{ser::SerializeMap, Serialize, Serializer};
+use state::{bytecode::opcode, EvmState};
+use std::collections::BTreeMap;
+
+/// Type of a [`TransactionTrace`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TraceType {
+    /// Call.
+    Call,
+    /// Create.
+    Create,
+    /// Selfdestruct.
+    #[serde(rename = "suicide")]
+    Selfdestruct,
+}
+
+/// Scheme of a [`CallAction`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CallType {
+    /// `CALL` or a call transaction.
+    Call,
+    /// `CALLCODE`.
+    CallCode,
+    /// `DELEGATECALL`.
+    DelegateCall,
+    /// `STATICCALL`.
+    StaticCall,
+}
+
+/// Scheme of a [`CreateAction`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CreationMethod {
+    /// `CREATE` or a create transaction.
+    Create,
+    /// `CREATE2`.
+    Create2,
+}
+
+/// Action of a call trace.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CallAction {
+    /// Address of the caller.
+    #[serde(serialize_with = "serde_address")]
+    pub from: Address,
+    /// Scheme of the call.
+    pub call_type: CallType,
+    /// Gas available to the frame.
+    #[serde(serialize_with = "serde_hex_u64")]
+    pub gas: u64,
+    /// Input of the call.
+    pub input: Bytes,
+    /// Address of the callee, for `DELEGATECALL` and `CALLCODE` the address of the code.
+    #[serde(serialize_with = "serde_address")]
+    pub to: Address,
+    /// Value of the call, the apparent value for `DELEGATECALL`.
+    pub value: U256,
+}
+
+/// Action of a create trace.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateAction {
+    /// Address of the creator.
+    #[serde(serialize_with = "serde_address")]
+    pub from: Address,
+    /// Gas available to the frame.
+    #[serde(serialize_with = "serde_hex_u64")]
+    pub gas: u64,
+    /// Init code.
+    pub init: Bytes,
+    /// Transferred value.
+    pub value: U256,
+    /// Scheme of the create.
+    pub creation_method: CreationMethod,
+}
+
+/// Action of a selfdestruct trace.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SelfdestructAction {
+    /// Address of the destroyed contract.
+    #[serde(serialize_with = "serde_address")]
+    pub address: Address,
+    /// Address that received the balance.
+    #[serde(serialize_with = "serde_address")]
+    pub refund_address: Address,
+    /// Balance of the destroyed contract.
+    pub balance: U256,
+}
+
+/// Action of a [`TransactionTrace`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(untagged)]
+pub enum Action {
+    /// Call.
+    Call(CallAction),
+    /// Create.
+    Create(CreateAction),
+    /// Selfdestruct.
+    Selfdestruct(SelfdestructAction),
+}
+
+/// Result of a successful call trace.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CallOutput {
+    /// Gas used by the frame including its calls.
+    #[serde(serialize_with = "serde_hex_u64")]
+    pub gas_used: u64,
+    /// Returned data.
+    pub output: Bytes,
+}
+
+/// Result of a successful create trace.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateOutput {
+    /// Created address.
+    #[serde(serialize_with = "serde_address")]
+    pub address: Address,
+    /// Deployed code.
+    pub code: Bytes,
+    /// Gas used by the frame including its calls.
+    #[serde(serialize_with = "serde_hex_u64")]
+    pub gas_used: u64,
+}
+
+/// Result of a [`TransactionTrace`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(untagged)]
+pub enum TraceOutput {
+    /// Call.
+    Call(CallOutput),
+    /// Create.
+    Create(CreateOutput),
+}
+
+/// Single entry of the flat `trace` list.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionTrace {
+    /// Action of the frame.
+    pub action: Action,
+    /// Result of the frame, not set if the frame failed or for selfdestructs.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<TraceOutput>,
+    /// Error of a failed frame.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// Number of direct child traces.
+    pub subtraces: usize,
+    /// Position of the trace in the call tree, empty for the top frame.
+    pub trace_address: Vec<usize>,
+    /// Type of the trace.
+    #[serde(rename = "type")]
+    pub kind: TraceType,
+}
+
+/// Memory written by an instruction.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct MemoryDelta {
+    /// Offset of the written memory.
+    pub off: usize,
+    /// Written data.
+    pub data: Bytes,
+}
+
+/// Storage slot written by an instruction.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct StorageDelta {
+    /// Slot.
+    pub key: U256,
+    /// Written value.
+    pub val: U256,
+}
+
+/// Effects of an executed instruction.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct VmExecutedOperation {
+    /// Gas remaining after the instruction.
+    pub used: u64,
+    /// Values pushed to the stack.
+    pub push: Vec<U256>,
+    /// Memory written by the instruction.
+    pub mem: Option<MemoryDelta>,
+    /// Storage written by the instruction.
+    pub store: Option<StorageDelta>,
+}
+
+/// Instruction of a [`VmTrace`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct VmInstruction {
+    /// Program counter.
+    pub pc: usize,
+    /// Gas cost of the instruction, including the gas forwarded by calls and creates.
+    pub cost: u64,
+    /// Effects of the instruction, not set if the instruction failed.
+    pub ex: Option<VmExecutedOperation>,
+    /// Trace of the frame created by the instruction.
+    pub sub: Option<VmTrace>,
+}
+
+/// Instruction trace of a frame, the `vmTrace` output.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct VmTrace {
+    /// Executed code, empty for precompiles.
+    pub code: Bytes,
+    /// Executed instructions.
+    pub ops: Vec<VmInstruction>,
+}
+
+/// Change of a value in a [`StateDiff`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Delta<T> {
+    /// Value did not change, serialized as `"="`.
+    Unchanged,
+    /// Account was created with the value, serialized as `{"+": value}`.
+    Added(T),
+    /// Account was removed with the value, serialized as `{"-": value}`.
+    Removed(T),
+    /// Value changed, serialized as `{"*": {"from": from, "to": to}}`.
+    Changed {
+        /// Value before the transaction.
+        from: T,
+        /// Value after the transaction.
+        to: T,
+    },
+}
+
+impl<T: PartialEq> Delta<T> {
+    fn new(from: T, to: T) -> Self {
+        if from == to {
+            Self::Unchanged
+        } else {
+            Self::Changed { from, to }
+        }
+    }
+
+    /// Returns `true` if the value did not change.
+    pub const fn is_unchanged(&self) -> bool {
+        matches!(self, Self::Unchanged)
+    }
+}
+
+impl<T: Serialize> Serialize for Delta<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        #[derive(Serialize)]
+        struct Change<'a, T> {
+            from: &'a T,
+            to: &'a T,
+        }
+
+        match self {
+            Self::Unchanged => serializer.serialize_str("="),
+            Self::Added(value) => {
+                let mut map = serializer.serialize_map(Some(1))?;
+                map.serialize_entry("+", value)?;
+                map.end()
+            }
+            Self::Removed(value) => {
+                let mut map = serializer.serialize_map(Some(1))?;
+                map.serialize_entry("-", value)?;
+                map.end()
+            }
+            Self::Changed { from, to } => {
+                let mut map = serializer.serialize_map(Some(1))?;
+                map.serialize_entry("*", &Change { from, to })?;
+                map.end()
+            }
+        }
+    }
+}
+
+/// Changes of an account in a [`StateDiff`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct AccountDiff {
+    /// Balance.
+    pub balance: Delta<U256>,
+    /// Nonce.
+    pub nonce: Delta<U256>,
+    /// Code.
+    pub code: Delta<Bytes>,
+    /// Changed storage slots.
+    pub storage: BTreeMap<B256, Delta<B256>>,
+}
+
+impl AccountDiff {
+    /// Returns `true` if nothing changed.
+    pub const fn is_unchanged(&self) -> bool {
+        self.balance.is_unchanged()
+            && self.nonce.is_unchanged()
+            && self.code.is_unchanged()
+            && self.storage.is_empty()
+    }
+}
+
+/// Changes of the accounts made by a transaction, the `stateDiff` output.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct StateDiff {
+    /// Changed accounts.
+    pub accounts: BTreeMap<Address, AccountDiff>,
+}
+
+impl StateDiff {
+    /// Compares the state changes of a transaction with the state before it.
+    ///
+    /// `db` has to contain the state before the transaction, e.g. the database of the EVM before
+    /// the changes are committed. Accounts that were selfdestructed or are empty after the
+    /// transaction are reported as removed.
+    pub fn new<DB: DatabaseRef>(db: &DB, state: &EvmState) -> Result<Self, DB::Error> {
+        let mut accounts = BTreeMap::new();
+        for (address, account) in state {
+            if !account.is_touched() {
+                continue;
+            }
+            let pre = db.basic_ref(*address)?.filter(|info| !info.is_empty());
+            let post =
+                (!account.is_selfdestructed() && !account.is_empty()).then_some(&account.info);
+
+            let pre_code = match &pre {
+                Some(info) if info.code_hash != KECCAK_EMPTY => match &info.code {
+                    Some(code) => code.original_bytes(),
+                    None => db.code_by_hash_ref(info.code_hash)?.original_bytes(),
+                },
+                _ => Bytes::new(),
+            };
+            let post_code = match post {
+                Some(info)
+                    if info.code_hash == pre.as_ref().map_or(KECCAK_EMPTY, |pre| pre.code_hash) =>
+                {
+                    pre_code.clone()
+                }
+                Some(info) => info
+                    .code
+                    .as_ref()
+                    .map(|code| code.original_bytes())
+                    .unwrap_or_default(),
+                None => Bytes::new(),
+            };
+
+            let diff = match (pre, post) {
+                (None, None) => continue,
+                (None, Some(post)) => AccountDiff {
+                    balance: Delta::Added(post.balance),
+                    nonce: Delta::Added(U256::from(post.nonce)),
+                    code: Delta::Added(post_code),
+                    storage: account
+                        .storage
+                        .iter()
+                        .filter(|(_, slot)| !slot.present_value().is_zero())
+                        .map(|(key, slot)| {
+                            ((*key).into(), Delta::Added(slot.present_value().into()))
+                        })
+                        .collect(),
+                },
+                (Some(pre), None) => AccountDiff {
+                    balance: Delta::Removed(pre.balance),
+                    nonce: Delta::Removed(U256::from(pre.nonce)),
+                    code: Delta::Removed(pre_code),
+                    storage: account
+                        .storage
+                        .iter()
+                        .filter(|(_, slot)| !slot.original_value().is_zero())
+                        .map(|(key, slot)| {
+                            ((*key).into(), Delta::Removed(slot.original_value().into()))
+                        })
+                        .collect(),
+                },
+                (Some(pre), Some(post)) => AccountDiff {
+                    balance: Delta::new(pre.balance, post.balance),
+                    nonce: Delta::new(U256::from(pre.nonce), U256::from(post.nonce)),
+                    code: Delta::new(pre_code, post_code),
+                    storage: account
+                        .changed_storage_slots()
+                        .filter(|(_, slot)| slot.original_value() != slot.present_value())
+                        .map(|(key, slot)| {
+                            let delta = Delta::new(
+                                slot.original_value().into(),
+                                slot.present_value().into(),
+                            );
+                            ((*key).into(), delta)
+                        })
+                        .collect(),
+                },
+            };
+            if !diff.is_unchanged() {
+                accounts.insert(*address, diff);
+            }
+        }
+        Ok(Self { accounts })
+    }
+}
+
+impl Serialize for StateDiff {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(self.accounts.len()))?;
+        for (address, diff) in &self.accounts {
+            map.serialize_entry(&hex::encode_prefixed(address), diff)?;
+        }
+        map.end()
+    }
+}
+
+/// Output of `trace_replayTransaction`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TraceResults {
+    /// Output of the transaction.
+    pub output: Bytes,
+    /// State changes, if requested.
+    pub state_diff: Option<StateDiff>,
+    /// Flat list of the frames, if requested.
+    pub trace: Vec<TransactionTrace>,
+    /// Instruction trace, if requested.
+    pub vm_trace: Option<VmTrace>,
+}
+
+/// Instruction that is executing, see [`ParityTracer`].
+#[derive(Clone, Debug)]
+struct PendingStep {
+    gas: u64,
+    opcode: u8,
+    /// Offset and length of the memory written by the instruction.
+    mem: Option<(usize, usize)>,
+    store: Option<StorageDelta>,
+}
+
+/// Frame of the instruction trace that is executing.
+#[derive(Clone, Debug, Default)]
+struct VmFrame {
+    trace: VmTrace,
+    step: Option<PendingStep>,
+    /// Index of the call or create instruction whose frame is executing.
+    pending_call: Option<usize>,
+}
+
+/// Inspector that records the `trace` and `vmTrace` outputs of Parity/OpenEthereum's
+/// `trace_replayTransaction`, the `stateDiff` output is created with [`StateDiff::new`].
+///
+/// Gas of the top frame does not include the intrinsic gas of the transaction. The instruction
+/// trace is recorded only with [`ParityTracer::with_vm_trace`], it does not contain the memory
+/// written by the returned data of calls. Traces are cleared when the next transaction starts.
+#[derive(Clone, Debug, Default)]
+pub struct ParityTracer {
+    record_vm_trace: bool,
+    traces: Vec<TransactionTrace>,
+    /// Indices of the traces of the frames that are still executing.
+    stack: Vec<usize>,
+    vm_stack: Vec<VmFrame>,
+    vm_trace: Option<VmTrace>,
+    output: Bytes,
+}
+
+impl ParityTracer {
+    /// Creates a new tracer that records the `trace` output.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records the `vmTrace` output.
+    pub fn with_vm_trace(mut self) -> Self {
+        self.record_vm_trace = true;
+        self
+    }
+
+    /// Returns the flat list of the frames of the current or last transaction, ordered by the
+    /// start of the frame.
+    pub fn traces(&self) -> &[TransactionTrace] {
+        &self.traces
+    }
+
+    /// Returns the instruction trace of the last transaction.
+    pub fn vm_trace(&self) -> Option<&VmTrace> {
+        self.vm_trace.as_ref()
+    }
+
+    /// Returns the output of `trace_replayTransaction` for the last transaction.
+    pub fn results(&self, state_diff: Option<StateDiff>) -> TraceResults {
+        TraceResults {
+            output: self.output.clone(),
+            state_diff,
+            trace: self.traces.clone(),
+            vm_trace: self.vm_trace.clone(),
+        }
+    }
+
+    /// Clears the recorded traces.
+    pub fn clear(&mut self) {
+        self.traces.clear();
+        self.stack.clear();
+        self.vm_stack.clear();
+        self.vm_trace = None;
+        self.output = Bytes::new();
+    }
+
+    fn push_trace(&mut self, context: &mut impl ContextTr, action: Action, kind: TraceType) {
+        if context.journal_ref().depth() == 0 {
+            self.clear();
+        }
+        let trace_address = match self.stack.last() {
+            Some(&parent) => {
+                let parent = &mut self.traces[parent];
+                let mut trace_address = parent.trace_address.clone();
+                trace_address.push(parent.subtraces);
+                parent.subtraces += 1;
+                trace_address
+            }
+            None => Vec::new(),
+        };
+        self.traces.push(TransactionTrace {
+            action,
+            result: None,
+            error: None,
+            subtraces: 0,
+            trace_address,
+            kind,
+        });
+        if kind != TraceType::Selfdestruct {
+            self.stack.push(self.traces.len() - 1);
+            if self.record_vm_trace {
+                self.vm_stack.push(VmFrame::default());
+            }
+        }
+    }
+
+    fn pop_trace(
+        &mut self,
+        frame_gas_limit: u64,
+        result: &InterpreterResult,
+        address: Option<Address>,
+    ) {
+        let Some(index) = self.stack.pop() else {
+            return;
+        };
+        let gas_used = if result.result.is_halt() {
+            frame_gas_limit
+        } else {
+            result.gas.total_gas_spent()
+        };
+        let trace = &mut self.traces[index];
+        if result.is_ok() {
+            trace.result = Some(match address {
+                Some(address) => TraceOutput::Create(CreateOutput {
+                    address,
+                    code: result.output.clone(),
+                    gas_used,
+                }),
+                None => TraceOutput::Call(CallOutput {
+                    gas_used,
+                    output: result.output.clone(),
+                }),
+            });
+        } else {
+            trace.error = Some(error_message(result.result));
+        }
+        if self.stack.is_empty() {
+            self.output = result.output.clone();
+        }
+
+        let Some(frame) = self.vm_stack.pop() else {
+            return;
+        };
+        match self.vm_stack.last_mut() {
+            Some(parent) => {
+                let Some(index) = parent.pending_call.take() else {
+                    return;
+                };
+                let op = &mut parent.trace.ops[index];
+                op.sub = Some(frame.trace);
+                if let Some(ex) = &mut op.ex {
+                    ex.used += result.gas.remaining();
+                    let pushed = match address {
+                        Some(address) if result.is_ok() => address.into_word().into(),
+                        Some(_) => U256::ZERO,
+                        None => U256::from(result.is_ok()),
+                    };
+                    ex.push = std::vec![pushed];
+                }
+            }
+            None => self.vm_trace = Some(frame.trace),
+        }
+    }
+}
+
+impl<CTX: ContextTr, INTR: InterpreterTypes> Inspector<CTX, INTR> for ParityTracer {
+    fn initialize_interp(&mut self, interp: &mut Interpreter<INTR>, _context: &mut CTX) {
+        if let Some(frame) = self.vm_stack.last_mut() {
+            frame.trace.code = Bytes::copy_from_slice(interp.bytecode.bytecode_slice());
+        }
+    }
+
+    fn step(&mut self, interp: &mut Interpreter<INTR>, _context: &mut CTX) {
+        let Some(frame) = self.vm_stack.last_mut() else {
+            return;
+        };
+        let opcode = interp.bytecode.opcode();
+        let stack = interp.stack.data();
+        let arg = |n: usize| {
+            stack
+                .len()
+                .checked_sub(n + 1)
+                .and_then(|i| usize::try_from(stack[i]).ok())
+        };
+        let mem = match opcode {
+            opcode::MSTORE => arg(0).map(|offset| (offset, 32)),
+            opcode::MSTORE8 => arg(0).map(|offset| (offset, 1)),
+            opcode::CALLDATACOPY | opcode::CODECOPY | opcode::RETURNDATACOPY | opcode::MCOPY => {
+                arg(0).zip(arg(2))
+            }
+            opcode::EXTCODECOPY => arg(1).zip(arg(3)),
+            _ => None,
+        };
+        let store = match (opcode, stack) {
+            (opcode::SSTORE, [.., val, key]) => Some(StorageDelta {
+                key: *key,
+                val: *val,
+            }),
+            _ => None,
+        };
+        frame.trace.ops.push(VmInstruction {
+            pc: interp.bytecode.pc(),
+            cost: 0,
+            ex: None,
+            sub: None,
+        });
+        frame.step = Some(PendingStep {
+            gas: interp.gas.remaining(),
+            opcode,
+            mem,
+            store,
+        });
+    }
+
+    fn step_end(&mut self, interp: &mut Interpreter<INTR>, _context: &mut CTX) {
+        let Some(frame) = self.vm_stack.last_mut() else {
+            return;
+        };
+        let (Some(step), Some(index)) = (frame.step.take(), frame.trace.ops.len().checked_sub(1))
+        else {
+            return;
+        };
+        let remaining = interp.gas.remaining();
+        frame.trace.ops[index].cost = step.gas.saturating_sub(remaining);
+        if interp
+            .bytecode
+            .instruction_result()
+            .is_some_and(|result| !result.is_ok())
+        {
+            return;
+        }
+
+        let is_new_frame = matches!(
+            step.opcode,
+            opcode::CALL
+                | opcode::CALLCODE
+                | opcode::DELEGATECALL
+                | opcode::STATICCALL
+                | opcode::CREATE
+                | opcode::CREATE2
+        );
+        let push = if is_new_frame {
+            // The result is pushed when the frame returns.
+            frame.pending_call = Some(index);
+            Vec::new()
+        } else {
+            let outputs = opcode::OpCode::new(step.opcode).map_or(0, |op| op.outputs() as usize);
+            let stack = interp.stack.data();
+            stack[stack.len().saturating_sub(outputs)..].to_vec()
+        };
+        let mem = step
+            .mem
+            .filter(|&(offset, len)| len > 0 && offset.saturating_add(len) <= interp.memory.size())
+            .map(|(offset, len)| MemoryDelta {
+                off: offset,
+                data: Bytes::copy_from_slice(&interp.memory.slice(offset..offset + len)),
+            });
+        frame.trace.ops[index].ex = Some(VmExecutedOperation {
+            used: remaining,
+            push,
+            mem,
+            store: step.store,
+        });
+    }
+
+    fn call(&mut self, context: &mut CTX, inputs: &mut CallInputs) -> Option<CallOutcome> {
+        let (call_type, to) = match inputs.scheme {
+            CallScheme::Call => (CallType::Call, inputs.target_address),
+            CallScheme::CallCode => (CallType::CallCode, inputs.bytecode_address),
+            CallScheme::DelegateCall => (CallType::DelegateCall, inputs.bytecode_address),
+            CallScheme::StaticCall => (CallType::StaticCall, inputs.target_address),
+        };
+        let action = Action::Call(CallAction {
+            from: inputs.caller,
+            call_type,
+            gas: inputs.gas_limit,
+            input: Bytes::copy_from_slice(&inputs.input.as_bytes(context)),
+            to,
+            value: inputs.value.get(),
+        });
+        self.push_trace(context, action, TraceType::Call);
+        None
+    }
+
+    fn call_end(&mut self, _context: &mut CTX, inputs: &CallInputs, outcome: &mut CallOutcome) {
+        self.pop_trace(inputs.gas_limit, &outcome.result, None);
+    }
+
+    fn create(&mut self, context: &mut CTX, inputs: &mut CreateInputs) -> Option<CreateOutcome> {
+        let creation_method = match inputs.scheme() {
+            CreateScheme::Create2 { .. } => CreationMethod::Create2,
+            _ => CreationMethod::Create,
+        };
+        let action = Action::Create(CreateAction {
+            from: inputs.caller(),
+            gas: inputs.gas_limit(),
+            init: inputs.init_code().clone(),
+            value: inputs.value(),
+            creation_method,
+        });
+        self.push_trace(context, action, TraceType::Create);
+        None
+    }
+
+    fn create_end(
+        &mut self,
+        _context: &mut CTX,
+        inputs: &CreateInputs,
+        outcome: &mut CreateOutcome,
+    ) {
+        let address = outcome.address.unwrap_or_default();
+        self.pop_trace(inputs.gas_limit(), &outcome.result, Some(address));
+    }
+
+    fn selfdestruct(&mut self, contract: Address, target: Address, value: U256) {
+        let Some(&parent) = self.stack.last() else {
+            return;
+        };
+        let parent = &mut self.traces[parent];
+        let mut trace_address = parent.trace_address.clone();
+        trace_address.push(parent.subtraces);
+        parent.subtraces += 1;
+        self.traces.push(TransactionTrace {
+            action: Action::Selfdestruct(SelfdestructAction {
+                address: contract,
+                refund_address: target,
+                balance: value,
+            }),
+            result: None,
+            error: None,
+            subtraces: 0,
+            trace_address,
+            kind: TraceType::Selfdestruct,
+        });
+    }
+}
+
+/// Returns the error message Parity reports for the result.
+fn error_message(result: InstructionResult) -> String {
+    match result {
+        InstructionResult::Revert => "Reverted",
+        InstructionResult::OutOfGas
+        | InstructionResult::MemoryOOG
+        | InstructionResult::MemoryLimitOOG
+        | InstructionResult::PrecompileOOG
+        | InstructionResult::InvalidOperandOOG
+        | InstructionResult::ReentrancySentryOOG => "Out of gas",
+        InstructionResult::OpcodeNotFound
+        | InstructionResult::InvalidFEOpcode
+        | InstructionResult::NotActivated => "Bad instruction",
+        InstructionResult::InvalidJump => "Bad jump destination",
+        InstructionResult::StackUnderflow => "Stack underflow",
+        InstructionResult::StackOverflow | InstructionResult::CallTooDeep => "Out of stack",
+        InstructionResult::CallNotAllowedInsideStatic
+        | InstructionResult::StateChangeDuringStaticCall => "Mutable Call In Static Context",
+        InstructionResult::PrecompileError => "Built-in failed",
+        result => return format!("{result:?}"),
+    }
+    .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::InspectEvm;
+    use context::{Context, TxEnv};
+    use database::{InMemoryDB, BENCH_CALLER, BENCH_TARGET};
+    use handler::{MainBuilder, MainContext};
+    use primitives::TxKind;
+    use state::{bytecode::Bytecode, AccountInfo};
+
+    #[test]
+    fn replay_transaction() {
+        // Stores 1 in slot 0, calls the identity precompile and stops.
+        let bytecode = Bytecode::new_raw(
+            [
+                opcode::PUSH1,
+                0x01,
+                opcode::PUSH0,
+                opcode::SSTORE,
+                opcode::PUSH0,
+                opcode::PUSH0,
+                opcode::PUSH0,
+                opcode::PUSH0,
+                opcode::PUSH0,
+                opcode::PUSH1,
+                0x04,
+                opcode::GAS,
+                opcode::CALL,
+                opcode::STOP,
+            ]
+            .to_vec()
+            .into(),
+        );
+        let mut db = InMemoryDB::default();
+        db.insert_account_info(
+            BENCH_TARGET,
+            AccountInfo::default().with_code(bytecode.clone()),
+        );
+        let mut evm = Context::mainnet()
+            .with_db(db.clone())
+            .build_mainnet_with_inspector(ParityTracer::new().with_vm_trace());
+        let result = evm
+            .inspect_tx(
+                TxEnv::builder()
+                    .caller(BENCH_CALLER)
+                    .kind(TxKind::Call(BENCH_TARGET))
+                    .gas_limit(100_000)
+                    .build()
+                    .unwrap(),
+            )
+            .unwrap();
+        assert!(result.result.is_success());
+
+        let traces = evm.inspector.traces();
+        assert_eq!(traces.len(), 2);
+        assert_eq!(traces[0].subtraces, 1);
+        assert!(traces[0].trace_address.is_empty());
+        assert_eq!(traces[1].trace_address, [0]);
+        assert!(matches!(
+            &traces[1].action,
+            Action::Call(CallAction { to, call_type: CallType::Call, .. })
+                if *to == Address::with_last_byte(4)
+        ));
+
+        let vm_trace = evm.inspector.vm_trace().unwrap();
+        assert_eq!(vm_trace.code, bytecode.original_bytes());
+        let sstore = &vm_trace.ops[2];
+        assert_eq!(
+            sstore.ex.as_ref().unwrap().store,
+            Some(StorageDelta {
+                key: U256::ZERO,
+                val: U256::from(1),
+            })
+        );
+        let call = &vm_trace.ops[10];
+        assert_eq!(call.ex.as_ref().unwrap().push, [U256::from(1)]);
+        assert_eq!(call.sub.as_ref().unwrap().ops.len(), 0);
+
+        let state_diff = StateDiff::new(&db, &result.state).unwrap();
+        let target = &state_diff.accounts[&BENCH_TARGET];
+        assert_eq!(
+            target.storage[&B256::ZERO],
+            Delta::Changed {
+                from: B256::ZERO,
+                to: B256::with_last_byte(1),
+            }
+        );
+        assert!(state_diff.accounts.contains_key(&BENCH_CALLER));
+
+        let json = serde_json::to_value(evm.inspector.results(Some(state_diff))).unwrap();
+        assert_eq!(json["trace"][0]["type"], "call");
+        assert_eq!(json["trace"][1]["action"]["callType"], "call");
+        assert_eq!(
+            json["stateDiff"][hex::encode_prefixed(BENCH_TARGET)]["balance"],
+            "="
+        );
+    }
+}
@@ -0,0 +1,201 @@
+//! Capture of precompile inputs and outputs for the tracers.
+use context::{ContextTr, JournalTr};
+use interpreter::{CallInputs, CallOutcome, InstructionResult};
+use primitives::{Address, Bytes};
+
+/// Default number of bytes of the input and output that are captured.
+pub const DEFAULT_PRECOMPILE_IO_LIMIT: usize = 4096;
+
+/// Input and output of a precompile call, captured by [`CallGasInspector`] and
+/// [`TracerEip3155`].
+///
+/// Inputs and outputs longer than the limit of the tracer are truncated, their original length
+/// is kept.
+///
+/// [`CallGasInspector`]: crate::inspectors::CallGasInspector
+/// [`TracerEip3155`]: crate::inspectors::TracerEip3155
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PrecompileIo {
+    /// Address of the precompile.
+    pub address: Address,
+    /// Input of the call, truncated to the limit.
+    pub input: Bytes,
+    /// Length of the input.
+    pub input_len: usize,
+    /// Output of the call, truncated to the limit, empty while the call is executing.
+    pub output: Bytes,
+    /// Length of the output.
+    pub output_len: usize,
+    /// Gas spent by the precompile.
+    pub gas_spent: u64,
+    /// Result of the call, [`None`] while the call is executing.
+    pub result: Option<InstructionResult>,
+}
+
+impl PrecompileIo {
+    /// Captures the input of the call if it calls a precompile.
+    pub fn call<CTX: ContextTr>(context: &CTX, inputs: &CallInputs, limit: usize) -> Option<Self> {
+        let address = inputs.bytecode_address;
+        if !context.journal().precompile_addresses().contains(&address) {
+            return None;
+        }
+        let input = inputs.input.as_bytes(context);
+        Some(Self {
+            address,
+            input: truncate(&input, limit),
+            input_len: input.len(),
+            output: Bytes::new(),
+            output_len: 0,
+            gas_spent: 0,
+            result: None,
+        })
+    }
+
+    /// Captures the output of the call.
+    pub fn call_end(&mut self, outcome: &CallOutcome, limit: usize) {
+        let output = &outcome.result.output;
+        self.output = truncate(output, limit);
+        self.output_len = output.len();
+        self.gas_spent = outcome.result.gas.total_gas_spent();
+        self.result = Some(outcome.result.result);
+    }
+
+    /// Returns `true` if the input or the output was truncated.
+    pub fn is_truncated(&self) -> bool {
+        self.input.len() < self.input_len || self.output.len() < self.output_len
+    }
+}
+
+fn truncate(bytes: &[u8], limit: usize) -> Bytes {
+    Bytes::copy_from_slice(&bytes[..bytes.len().min(limit)])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{inspectors::CallGasInspector, InspectEvm};
+    use context::{Context, TxEnv};
+    use database::{BenchmarkDB, BENCH_CALLER, BENCH_TARGET};
+    use handler::{MainBuilder, MainContext};
+    use primitives::{hex, TxKind};
+    use state::bytecode::{opcode, Bytecode};
+
+    /// Calls the identity precompile with `0xdeadbeef`.
+    fn bytecode() -> Bytecode {
+        Bytecode::new_raw(
+            [
+                opcode::PUSH4,
+                0xde,
+                0xad,
+                0xbe,
+                0xef,
+                opcode::PUSH1,
+                0x0,
+                opcode::MSTORE,
+                // retLength, retOffset, argsLength, argsOffset, value, address, gas.
+                opcode::PUSH1,
+                0x4,
+                opcode::PUSH1,
+                0x20,
+                opcode::PUSH1,
+                0x4,
+                opcode::PUSH1,
+                0x1c,
+                opcode::PUSH1,
+                0x0,
+                opcode::PUSH1,
+                0x4,
+                opcode::GAS,
+                opcode::CALL,
+                opcode::POP,
+                opcode::STOP,
+            ]
+            .to_vec()
+            .into(),
+        )
+    }
+
+    fn tx() -> TxEnv {
+        TxEnv::builder()
+            .caller(BENCH_CALLER)
+            .kind(TxKind::Call(BENCH_TARGET))
+            .gas_limit(100_000)
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn call_gas_inspector_captures_precompile_io() {
+        let mut evm = Context::mainnet()
+            .with_db(BenchmarkDB::new_bytecode(bytecode()))
+            .build_mainnet_with_inspector(CallGasInspector::new().with_precompile_io(2));
+        evm.inspect_tx(tx()).unwrap();
+
+        let frames = evm.inspector.frames();
+        assert_eq!(frames.len(), 2);
+        assert_eq!(frames[0].precompile, None);
+        let precompile = frames[1].precompile.as_ref().unwrap();
+        assert_eq!(precompile.address, Address::with_last_byte(4));
+        assert_eq!(precompile.input, Bytes::from_static(&hex!("dead")));
+        assert_eq!(precompile.input_len, 4);
+        assert_eq!(precompile.output, Bytes::from_static(&hex!("dead")));
+        assert_eq!(precompile.output_len, 4);
+        // 15 base and 3 per word.
+        assert_eq!(precompile.gas_spent, 18);
+        assert!(precompile.result.unwrap().is_ok());
+        assert!(precompile.is_truncated());
+    }
+
+    #[cfg(feature = "tracer")]
+    #[test]
+    fn eip3155_tracer_writes_precompile_io() {
+        use crate::inspectors::TracerEip3155;
+        use std::{
+            io::{self, Write},
+            sync::{Arc, Mutex},
+        };
+
+        #[derive(Clone, Default)]
+        struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+        impl Write for SharedBuffer {
+            fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+                self.0.lock().unwrap().write(buf)
+            }
+
+            fn flush(&mut self) -> io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let buffer = SharedBuffer::default();
+        let tracer = TracerEip3155::buffered(buffer.clone())
+            .without_summary()
+            .with_precompile_io(DEFAULT_PRECOMPILE_IO_LIMIT);
+        let mut evm = Context::mainnet()
+            .with_db(BenchmarkDB::new_bytecode(bytecode()))
+            .build_mainnet_with_inspector(tracer);
+        evm.inspect_tx(tx()).unwrap();
+        drop(evm);
+
+        let output = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+        let lines: Vec<serde_json::Value> = output
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        let index = lines
+            .iter()
+            .position(|line| line.get("precompile").is_some())
+            .unwrap();
+        // Written after the `CALL` step.
+        assert_eq!(lines[index - 1]["op"], opcode::CALL);
+        assert_eq!(
+            lines[index]["precompile"],
+            "0x0000000000000000000000000000000000000004"
+        );
+        assert_eq!(lines[index]["input"], "0xdeadbeef");
+        assert_eq!(lines[index]["output"], "0xdeadbeef");
+        assert_eq!(lines[index]["gasCost"], "0x12");
+        assert!(lines[index].get("error").is_none());
+    }
+}
@@ -0,0 +1,207 @@
+//! ResourceInspector - Inspector that records stack, memory and return data usage per frame.
+extern crate alloc;
+
+use crate::{inspectors::FrameKind, Inspector};
+use alloc::vec::Vec;
+use interpreter::{
+    interpreter_types::{MemoryTr, ReturnData, StackTr},
+    CallInputs, CallOutcome, CreateInputs, CreateOutcome, Interpreter, InterpreterTypes,
+};
+use primitives::Address;
+
+/// Resources used by a single call frame.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FrameResources {
+    /// Depth of the frame, the transaction frame has depth zero.
+    pub depth: usize,
+    /// Kind of the frame.
+    pub kind: FrameKind,
+    /// Called address, or the created address once a create succeeded.
+    pub address: Option<Address>,
+    /// Maximum number of stack items.
+    pub max_stack: usize,
+    /// Peak memory size in bytes.
+    pub peak_memory: usize,
+    /// Size of the largest return data received from a sub call.
+    pub max_return_data: usize,
+    /// Size of the data returned by the frame, [`None`] while the frame is executing.
+    pub output_len: Option<usize>,
+}
+
+/// Inspector that records the maximum stack depth, peak memory size and return data sizes of
+/// every frame.
+///
+/// It lets developers detect contracts that get close to the stack limit
+/// ([`STACK_LIMIT`](interpreter::STACK_LIMIT)), the call depth limit or the memory limit before
+/// they break in production. The statistics are only collected when the inspector is used.
+#[derive(Clone, Debug, Default)]
+pub struct ResourceInspector {
+    frames: Vec<FrameResources>,
+    stack: Vec<usize>,
+}
+
+impl ResourceInspector {
+    /// Creates a new resource inspector.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns all frames in the order they were entered.
+    pub fn frames(&self) -> &[FrameResources] {
+        &self.frames
+    }
+
+    /// Returns the maximum number of stack items of all frames.
+    pub fn max_stack(&self) -> usize {
+        self.frames.iter().map(|f| f.max_stack).max().unwrap_or(0)
+    }
+
+    /// Returns the peak memory size of all frames.
+    pub fn peak_memory(&self) -> usize {
+        self.frames.iter().map(|f| f.peak_memory).max().unwrap_or(0)
+    }
+
+    /// Returns the maximum call depth reached.
+    pub fn max_depth(&self) -> usize {
+        self.frames.iter().map(|f| f.depth).max().unwrap_or(0)
+    }
+
+    /// Returns the frames whose stack or memory usage reached the thresholds.
+    pub fn frames_over(
+        &self,
+        stack_threshold: usize,
+        memory_threshold: usize,
+    ) -> impl Iterator<Item = &FrameResources> {
+        self.frames
+            .iter()
+            .filter(move |f| f.max_stack >= stack_threshold || f.peak_memory >= memory_threshold)
+    }
+
+    /// Clears the frames so the inspector can be used for the next transaction.
+    pub fn clear(&mut self) {
+        self.frames.clear();
+        self.stack.clear();
+    }
+
+    fn push_frame(&mut self, kind: FrameKind, address: Option<Address>) {
+        self.stack.push(self.frames.len());
+        self.frames.push(FrameResources {
+            depth: self.stack.len() - 1,
+            kind,
+            address,
+            max_stack: 0,
+            peak_memory: 0,
+            max_return_data: 0,
+            output_len: None,
+        });
+    }
+
+    fn pop_frame(&mut self, output_len: usize, address: Option<Address>) {
+        let Some(index) = self.stack.pop() else {
+            return;
+        };
+        let frame = &mut self.frames[index];
+        frame.output_len = Some(output_len);
+        if address.is_some() {
+            frame.address = address;
+        }
+    }
+}
+
+impl<CTX, INTR: InterpreterTypes> Inspector<CTX, INTR> for ResourceInspector {
+    fn step_end(&mut self, interp: &mut Interpreter<INTR>, _context: &mut CTX) {
+        let Some(frame) = self.stack.last().map(|&index| &mut self.frames[index]) else {
+            return;
+        };
+        frame.max_stack = frame.max_stack.max(interp.stack.len());
+        frame.peak_memory = frame.peak_memory.max(interp.memory.size());
+        frame.max_return_data = frame.max_return_data.max(interp.return_data.buffer().len());
+    }
+
+    fn call(&mut self, _context: &mut CTX, inputs: &mut CallInputs) -> Option<CallOutcome> {
+        self.push_frame(FrameKind::Call(inputs.scheme), Some(inputs.target_address));
+        None
+    }
+
+    fn call_end(&mut self, _context: &mut CTX, _inputs: &CallInputs, outcome: &mut CallOutcome) {
+        self.pop_frame(outcome.result.output.len(), None);
+    }
+
+    fn create(&mut self, _context: &mut CTX, inputs: &mut CreateInputs) -> Option<CreateOutcome> {
+        self.push_frame(FrameKind::Create(inputs.scheme()), None);
+        None
+    }
+
+    fn create_end(
+        &mut self,
+        _context: &mut CTX,
+        _inputs: &CreateInputs,
+        outcome: &mut CreateOutcome,
+    ) {
+        self.pop_frame(outcome.result.output.len(), outcome.address);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::InspectEvm;
+    use context::{Context, TxEnv};
+    use database::{BenchmarkDB, BENCH_CALLER, BENCH_TARGET};
+    use handler::{MainBuilder, MainContext};
+    use interpreter::CallScheme;
+    use primitives::TxKind;
+    use state::bytecode::{opcode, Bytecode};
+
+    #[test]
+    fn records_frame_resources() {
+        // Push three items, store at 0x40 and return 0x20 bytes.
+        let bytecode = Bytecode::new_raw(
+            [
+                opcode::PUSH1,
+                0x1,
+                opcode::PUSH1,
+                0x2,
+                opcode::PUSH1,
+                0x40,
+                opcode::MSTORE,
+                opcode::POP,
+                opcode::PUSH1,
+                0x20,
+                opcode::PUSH1,
+                0x0,
+                opcode::RETURN,
+            ]
+            .to_vec()
+            .into(),
+        );
+        let mut evm = Context::mainnet()
+            .with_db(BenchmarkDB::new_bytecode(bytecode))
+            .build_mainnet_with_inspector(ResourceInspector::new());
+        evm.inspect_one_tx(
+            TxEnv::builder()
+                .caller(BENCH_CALLER)
+                .kind(TxKind::Call(BENCH_TARGET))
+                .gas_limit(100_000)
+                .build()
+                .unwrap(),
+        )
+        .unwrap();
+
+        let inspector = &evm.inspector;
+        assert_eq!(
+            inspector.frames(),
+            [FrameResources {
+                depth: 0,
+                kind: FrameKind::Call(CallScheme::Call),
+                address: Some(BENCH_TARGET),
+                max_stack: 3,
+                peak_memory: 0x60,
+                max_return_data: 0,
+                output_len: Some(0x20),
+            }]
+        );
+        assert_eq!(inspector.frames_over(3, usize::MAX).count(), 1);
+        assert_eq!(inspector.frames_over(4, 0x80).count(), 0);
+    }
+}
@@ -0,0 +1,170 @@
+//! Trace sinks that stream newline-delimited JSON traces to other processes.
+use std::{
+    io::{self, BufWriter, Write},
+    net::{TcpStream, ToSocketAddrs},
+};
+#[cfg(unix)]
+use std::{os::unix::net::UnixStream, path::Path};
+
+/// Destination for newline-delimited JSON traces, such as the output of
+/// [`TracerEip3155`](crate::inspectors::TracerEip3155).
+///
+/// Sink implements [`Write`] so it can be passed to any tracer that writes to a writer.
+/// Output is buffered, tracers flush it at the end of each transaction.
+#[derive(Debug)]
+pub enum TraceSink {
+    /// TCP connection.
+    Tcp(BufWriter<TcpStream>),
+    /// Unix domain socket connection.
+    #[cfg(unix)]
+    Unix(BufWriter<UnixStream>),
+    /// Tokio channel that receives one message per line.
+    #[cfg(feature = "async")]
+    Channel(LineSender),
+}
+
+impl TraceSink {
+    /// Connects to a TCP listener.
+    pub fn tcp(addr: impl ToSocketAddrs) -> io::Result<Self> {
+        let stream = TcpStream::connect(addr)?;
+        stream.set_nodelay(true)?;
+        Ok(Self::Tcp(BufWriter::new(stream)))
+    }
+
+    /// Connects to a Unix domain socket.
+    #[cfg(unix)]
+    pub fn unix(path: impl AsRef<Path>) -> io::Result<Self> {
+        Ok(Self::Unix(BufWriter::new(UnixStream::connect(path)?)))
+    }
+
+    /// Creates a sink that sends every line, without the trailing newline, to the returned receiver.
+    ///
+    /// The sink blocks when the channel is full, so the EVM must not run on an async runtime
+    /// thread. Writing fails with [`io::ErrorKind::BrokenPipe`] once the receiver is dropped.
+    #[cfg(feature = "async")]
+    pub fn channel(capacity: usize) -> (Self, tokio::sync::mpsc::Receiver<String>) {
+        let (sender, receiver) = tokio::sync::mpsc::channel(capacity);
+        (
+            Self::Channel(LineSender {
+                sender,
+                buffer: Vec::new(),
+            }),
+            receiver,
+        )
+    }
+
+    /// Connects to the sink given as `tcp://host:port` or `unix:///path/to/socket`.
+    pub fn connect(uri: &str) -> io::Result<Self> {
+        if let Some(addr) = uri.strip_prefix("tcp://") {
+            return Self::tcp(addr);
+        }
+        #[cfg(unix)]
+        if let Some(path) = uri.strip_prefix("unix://") {
+            return Self::unix(path);
+        }
+        Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("unsupported trace sink `{uri}`, expected `tcp://` or `unix://`"),
+        ))
+    }
+}
+
+impl Write for TraceSink {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Self::Tcp(writer) => writer.write(buf),
+            #[cfg(unix)]
+            Self::Unix(writer) => writer.write(buf),
+            #[cfg(feature = "async")]
+            Self::Channel(sender) => sender.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Self::Tcp(writer) => writer.flush(),
+            #[cfg(unix)]
+            Self::Unix(writer) => writer.flush(),
+            #[cfg(feature = "async")]
+            Self::Channel(sender) => sender.flush(),
+        }
+    }
+}
+
+/// Writer that splits the output into lines and sends them through a tokio channel.
+#[cfg(feature = "async")]
+#[derive(Debug)]
+pub struct LineSender {
+    sender: tokio::sync::mpsc::Sender<String>,
+    /// Incomplete line.
+    buffer: Vec<u8>,
+}
+
+#[cfg(feature = "async")]
+impl Write for LineSender {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.buffer.extend_from_slice(buf);
+        while let Some(end) = self.buffer.iter().position(|b| *b == b'\n') {
+            let line: Vec<u8> = self.buffer.drain(..=end).take(end).collect();
+            let line = String::from_utf8(line)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+            self.sender
+                .blocking_send(line)
+                .map_err(|_| io::Error::from(io::ErrorKind::BrokenPipe))?;
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        // Complete lines are sent eagerly, partial lines wait for their newline.
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{
+        io::{BufRead, BufReader},
+        net::TcpListener,
+    };
+
+    #[test]
+    fn tcp_sink() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        let mut sink = TraceSink::connect(&format!("tcp://{addr}")).unwrap();
+        let (stream, _) = listener.accept().unwrap();
+        sink.write_all(b"{\"pc\":0}\n{\"pc\":").unwrap();
+        sink.write_all(b"2}\n").unwrap();
+        sink.flush().unwrap();
+        drop(sink);
+
+        let lines: Vec<_> = BufReader::new(stream).lines().map(Result::unwrap).collect();
+        assert_eq!(lines, ["{\"pc\":0}", "{\"pc\":2}"]);
+    }
+
+    #[test]
+    fn unsupported_sink() {
+        let err = TraceSink::connect("http://localhost").unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn channel_sink() {
+        let (mut sink, mut receiver) = TraceSink::channel(4);
+        sink.write_all(b"{\"pc\":0}\n{\"pc\":").unwrap();
+        sink.write_all(b"2}\n").unwrap();
+        assert_eq!(receiver.try_recv().unwrap(), "{\"pc\":0}");
+        assert_eq!(receiver.try_recv().unwrap(), "{\"pc\":2}");
+        assert!(receiver.try_recv().is_err());
+
+        drop(receiver);
+        assert_eq!(
+            sink.write_all(b"\n").unwrap_err().kind(),
+            io::ErrorKind::BrokenPipe
+        );
+    }
+}
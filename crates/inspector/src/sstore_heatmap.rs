@@ -0,0 +1,267 @@
+//! SstoreHeatmapInspector - Inspector that aggregates storage writes across transactions.
+extern crate alloc;
+
+use crate::{Inspector, JournalExt};
+use alloc::vec::Vec;
+use context::{ContextTr, JournalEntry, JournalTr};
+use interpreter::{
+    interpreter_types::{InputsTr, Jumps, LoopControl, StackTr},
+    CallInputs, CallOutcome, CreateInputs, CreateOutcome, Interpreter, InterpreterTypes,
+};
+use primitives::{Address, HashMap, StorageKey, StorageValue};
+use state::bytecode::opcode;
+
+/// Storage write statistics of a single slot.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SlotStats {
+    /// Number of successful `SSTORE`s.
+    pub writes: u64,
+    /// Number of `SSTORE`s that wrote the value the slot already had.
+    pub noop_writes: u64,
+    /// Number of transactions that wrote to the slot.
+    pub txs: u64,
+    /// Number of transactions that wrote to the slot but ended with the original value.
+    ///
+    /// This includes reverted transactions and `0 -> x -> 0` style churn.
+    pub net_zero_txs: u64,
+    /// Gas spent on `SSTORE`s.
+    pub gas_spent: u64,
+    /// Net refund accumulated by `SSTORE`s (EIP-2200/EIP-3529), can be negative.
+    pub refund: i64,
+}
+
+/// `SSTORE` that is being executed.
+#[derive(Clone, Copy, Debug)]
+struct PendingWrite {
+    address: Address,
+    key: StorageKey,
+    gas_remaining: u64,
+    refunded: i64,
+    journal_len: usize,
+}
+
+/// Inspector that aggregates storage writes per `(address, slot)` across many transactions.
+///
+/// The inspector is meant to be reused for all transactions of a block range, transaction
+/// boundaries are detected when the top level frame ends.
+#[derive(Clone, Debug, Default)]
+pub struct SstoreHeatmapInspector {
+    slots: HashMap<(Address, StorageKey), SlotStats>,
+    /// Original values of the slots written in the current transaction.
+    tx_slots: HashMap<(Address, StorageKey), StorageValue>,
+    pending: Option<PendingWrite>,
+}
+
+impl SstoreHeatmapInspector {
+    /// Creates a new heat-map inspector.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns statistics of all written slots.
+    pub const fn slots(&self) -> &HashMap<(Address, StorageKey), SlotStats> {
+        &self.slots
+    }
+
+    /// Returns statistics of the given slot.
+    pub fn slot(&self, address: Address, key: StorageKey) -> Option<&SlotStats> {
+        self.slots.get(&(address, key))
+    }
+
+    /// Returns at most `n` slots with the most writes, in descending order.
+    pub fn hottest(&self, n: usize) -> Vec<((Address, StorageKey), SlotStats)> {
+        let mut slots: Vec<_> = self.slots.iter().map(|(k, v)| (*k, *v)).collect();
+        slots.sort_unstable_by(|a, b| b.1.writes.cmp(&a.1.writes).then(a.0.cmp(&b.0)));
+        slots.truncate(n);
+        slots
+    }
+
+    /// Returns slots sorted by the number of net-zero transactions, in descending order.
+    ///
+    /// Slots that never ended a transaction with the original value are skipped.
+    pub fn churn(&self) -> Vec<((Address, StorageKey), SlotStats)> {
+        let mut slots: Vec<_> = self
+            .slots
+            .iter()
+            .filter(|(_, v)| v.net_zero_txs > 0)
+            .map(|(k, v)| (*k, *v))
+            .collect();
+        slots.sort_unstable_by(|a, b| b.1.net_zero_txs.cmp(&a.1.net_zero_txs).then(a.0.cmp(&b.0)));
+        slots
+    }
+
+    /// Clears all collected statistics.
+    pub fn clear(&mut self) {
+        self.slots.clear();
+        self.tx_slots.clear();
+        self.pending = None;
+    }
+
+    fn tx_end<CTX: ContextTr<Journal: JournalExt>>(&mut self, context: &mut CTX) {
+        if context.journal().depth() != 0 {
+            return;
+        }
+        let state = context.journal().evm_state();
+        for ((address, key), original) in self.tx_slots.drain() {
+            let present = state
+                .get(&address)
+                .and_then(|account| account.storage.get(&key))
+                .map_or(original, |slot| slot.present_value);
+            let stats = self.slots.entry((address, key)).or_default();
+            stats.txs += 1;
+            if present == original {
+                stats.net_zero_txs += 1;
+            }
+        }
+    }
+}
+
+impl<CTX, INTR> Inspector<CTX, INTR> for SstoreHeatmapInspector
+where
+    CTX: ContextTr<Journal: JournalExt>,
+    INTR: InterpreterTypes,
+{
+    fn step(&mut self, interp: &mut Interpreter<INTR>, context: &mut CTX) {
+        if interp.bytecode.opcode() != opcode::SSTORE {
+            return;
+        }
+        let Some(&key) = interp.stack.data().last() else {
+            return;
+        };
+        self.pending = Some(PendingWrite {
+            address: interp.input.target_address(),
+            key,
+            gas_remaining: interp.gas.remaining(),
+            refunded: interp.gas.refunded(),
+            journal_len: context.journal().journal().len(),
+        });
+    }
+
+    fn step_end(&mut self, interp: &mut Interpreter<INTR>, context: &mut CTX) {
+        let Some(pending) = self.pending.take() else {
+            return;
+        };
+        // Failed `SSTORE` did not write anything.
+        if interp.bytecode.instruction_result().is_some() {
+            return;
+        }
+
+        let journal = context.journal().journal();
+        let changed = journal[pending.journal_len.min(journal.len())..]
+            .iter()
+            .any(|entry| {
+                matches!(entry, JournalEntry::StorageChanged { address, key, .. }
+                    if *address == pending.address && *key == pending.key)
+            });
+
+        let slot = (pending.address, pending.key);
+        let original = context
+            .journal()
+            .evm_state()
+            .get(&pending.address)
+            .and_then(|account| account.storage.get(&pending.key))
+            .map(|slot| slot.original_value)
+            .unwrap_or_default();
+        self.tx_slots.entry(slot).or_insert(original);
+
+        let stats = self.slots.entry(slot).or_default();
+        stats.writes += 1;
+        if !changed {
+            stats.noop_writes += 1;
+        }
+        stats.gas_spent += pending.gas_remaining.saturating_sub(interp.gas.remaining());
+        stats.refund += interp.gas.refunded() - pending.refunded;
+    }
+
+    fn call_end(&mut self, context: &mut CTX, _inputs: &CallInputs, _outcome: &mut CallOutcome) {
+        self.tx_end(context);
+    }
+
+    fn create_end(
+        &mut self,
+        context: &mut CTX,
+        _inputs: &CreateInputs,
+        _outcome: &mut CreateOutcome,
+    ) {
+        self.tx_end(context);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::InspectEvm;
+    use context::{Context, TxEnv};
+    use database::{BenchmarkDB, BENCH_CALLER, BENCH_TARGET};
+    use handler::{MainBuilder, MainContext};
+    use primitives::{TxKind, U256};
+    use state::bytecode::Bytecode;
+
+    #[test]
+    fn test_sstore_heatmap() {
+        // slot0 = 1; slot0 = 0; slot1 = 0; slot1 = 2
+        let bytecode = Bytecode::new_raw(
+            [
+                opcode::PUSH1,
+                0x1,
+                opcode::PUSH1,
+                0x0,
+                opcode::SSTORE,
+                opcode::PUSH1,
+                0x0,
+                opcode::PUSH1,
+                0x0,
+                opcode::SSTORE,
+                opcode::PUSH1,
+                0x0,
+                opcode::PUSH1,
+                0x1,
+                opcode::SSTORE,
+                opcode::PUSH1,
+                0x2,
+                opcode::PUSH1,
+                0x1,
+                opcode::SSTORE,
+                opcode::STOP,
+            ]
+            .to_vec()
+            .into(),
+        );
+
+        let mut evm = Context::mainnet()
+            .with_db(BenchmarkDB::new_bytecode(bytecode))
+            .build_mainnet_with_inspector(SstoreHeatmapInspector::new());
+
+        for nonce in 0..2 {
+            evm.inspect_one_tx(
+                TxEnv::builder()
+                    .caller(BENCH_CALLER)
+                    .kind(TxKind::Call(BENCH_TARGET))
+                    .nonce(nonce)
+                    .gas_limit(1_000_000)
+                    .build()
+                    .unwrap(),
+            )
+            .unwrap();
+        }
+
+        let inspector = &evm.inspector;
+        // `0 -> 1 -> 0` in every transaction.
+        let slot0 = inspector.slot(BENCH_TARGET, U256::ZERO).unwrap();
+        assert_eq!(slot0.writes, 4);
+        assert_eq!(slot0.noop_writes, 0);
+        assert_eq!(slot0.txs, 2);
+        assert_eq!(slot0.net_zero_txs, 2);
+        assert!(slot0.refund > 0);
+
+        let slot1 = inspector.slot(BENCH_TARGET, U256::from(1)).unwrap();
+        assert_eq!(slot1.writes, 4);
+        assert_eq!(slot1.txs, 2);
+        // First write of the first transaction doesn't change the value, the second transaction
+        // starts with `slot1 = 2` as the state is not finalized between the transactions.
+        assert_eq!(slot1.noop_writes, 1);
+
+        assert_eq!(inspector.hottest(1).len(), 1);
+        assert_eq!(inspector.churn()[0].0, (BENCH_TARGET, U256::ZERO));
+    }
+}
@@ -0,0 +1,293 @@
+//! StorageDiffInspector - Inspector that records storage writes and the storage diff of a transaction.
+extern crate alloc;
+
+use crate::{Inspector, JournalExt};
+use alloc::vec::Vec;
+use context::{ContextTr, JournalEntry, JournalTr};
+use interpreter::{
+    interpreter_types::{InputsTr, Jumps, LoopControl, StackTr},
+    CallInputs, CallOutcome, CreateInputs, CreateOutcome, Interpreter, InterpreterTypes,
+};
+use primitives::{Address, HashMap, StorageKey, StorageValue};
+use state::bytecode::opcode;
+
+/// Successful `SSTORE` recorded by [`StorageDiffInspector`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct StorageWrite {
+    /// Address of the written storage.
+    pub address: Address,
+    /// Written slot.
+    pub key: StorageKey,
+    /// Value of the slot at the start of the transaction.
+    pub original: StorageValue,
+    /// Value of the slot before the write.
+    pub previous: StorageValue,
+    /// Written value.
+    pub new: StorageValue,
+    /// Depth of the frame that executed the `SSTORE`.
+    pub depth: usize,
+}
+
+/// Change of a slot made by a transaction.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SlotDiff {
+    /// Value of the slot at the start of the transaction.
+    pub original: StorageValue,
+    /// Value of the slot at the end of the transaction.
+    pub present: StorageValue,
+}
+
+/// `SSTORE` that is being executed.
+#[derive(Clone, Copy, Debug)]
+struct PendingWrite {
+    address: Address,
+    key: StorageKey,
+    new: StorageValue,
+    depth: usize,
+    journal_len: usize,
+}
+
+/// Inspector that records every `SSTORE` of a transaction and the resulting storage diff.
+///
+/// The diff is computed from the journal when the top level frame ends, so writes of reverted
+/// frames are recorded in [`writes`](Self::writes) but are not part of the
+/// [`diff`](Self::diff). Slots that end the transaction with their original value are skipped.
+/// Both are cleared when the next transaction starts.
+#[derive(Clone, Debug, Default)]
+pub struct StorageDiffInspector {
+    writes: Vec<StorageWrite>,
+    diff: HashMap<Address, HashMap<StorageKey, SlotDiff>>,
+    pending: Option<PendingWrite>,
+}
+
+impl StorageDiffInspector {
+    /// Creates a new storage diff inspector.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns all successful `SSTORE`s of the current or last transaction in execution order.
+    pub fn writes(&self) -> &[StorageWrite] {
+        &self.writes
+    }
+
+    /// Returns the changed slots of the last transaction per address.
+    pub const fn diff(&self) -> &HashMap<Address, HashMap<StorageKey, SlotDiff>> {
+        &self.diff
+    }
+
+    /// Returns the changed slots of the given address in the last transaction.
+    pub fn address_diff(&self, address: Address) -> Option<&HashMap<StorageKey, SlotDiff>> {
+        self.diff.get(&address)
+    }
+
+    /// Consumes the inspector and returns the storage diff of the last transaction.
+    pub fn into_diff(self) -> HashMap<Address, HashMap<StorageKey, SlotDiff>> {
+        self.diff
+    }
+
+    /// Clears the recorded writes and diff.
+    pub fn clear(&mut self) {
+        self.writes.clear();
+        self.diff.clear();
+        self.pending = None;
+    }
+
+    fn tx_start<CTX: ContextTr>(&mut self, context: &mut CTX) {
+        if context.journal().depth() == 0 {
+            self.clear();
+        }
+    }
+
+    fn tx_end<CTX: ContextTr<Journal: JournalExt>>(&mut self, context: &mut CTX) {
+        if context.journal().depth() != 0 {
+            return;
+        }
+        let state = context.journal().evm_state();
+        for write in &self.writes {
+            let Some(slot) = state
+                .get(&write.address)
+                .and_then(|account| account.storage.get(&write.key))
+            else {
+                continue;
+            };
+            if slot.original_value == slot.present_value {
+                continue;
+            }
+            self.diff.entry(write.address).or_default().insert(
+                write.key,
+                SlotDiff {
+                    original: slot.original_value,
+                    present: slot.present_value,
+                },
+            );
+        }
+    }
+}
+
+impl<CTX, INTR> Inspector<CTX, INTR> for StorageDiffInspector
+where
+    CTX: ContextTr<Journal: JournalExt>,
+    INTR: InterpreterTypes,
+{
+    fn step(&mut self, interp: &mut Interpreter<INTR>, context: &mut CTX) {
+        if interp.bytecode.opcode() != opcode::SSTORE {
+            return;
+        }
+        let [.., new, key] = *interp.stack.data() else {
+            return;
+        };
+        self.pending = Some(PendingWrite {
+            address: interp.input.target_address(),
+            key,
+            new,
+            depth: context.journal().depth(),
+            journal_len: context.journal().journal().len(),
+        });
+    }
+
+    fn step_end(&mut self, interp: &mut Interpreter<INTR>, context: &mut CTX) {
+        let Some(pending) = self.pending.take() else {
+            return;
+        };
+        // Failed `SSTORE` did not write anything.
+        if interp.bytecode.instruction_result().is_some() {
+            return;
+        }
+
+        // Writes of the current value are not journaled.
+        let journal = context.journal().journal();
+        let previous = journal[pending.journal_len.min(journal.len())..]
+            .iter()
+            .find_map(|entry| match entry {
+                JournalEntry::StorageChanged {
+                    address,
+                    key,
+                    had_value,
+                } if *address == pending.address && *key == pending.key => Some(*had_value),
+                _ => None,
+            })
+            .unwrap_or(pending.new);
+        let original = context
+            .journal()
+            .evm_state()
+            .get(&pending.address)
+            .and_then(|account| account.storage.get(&pending.key))
+            .map_or(previous, |slot| slot.original_value);
+
+        self.writes.push(StorageWrite {
+            address: pending.address,
+            key: pending.key,
+            original,
+            previous,
+            new: pending.new,
+            depth: pending.depth,
+        });
+    }
+
+    fn call(&mut self, context: &mut CTX, _inputs: &mut CallInputs) -> Option<CallOutcome> {
+        self.tx_start(context);
+        None
+    }
+
+    fn call_end(&mut self, context: &mut CTX, _inputs: &CallInputs, _outcome: &mut CallOutcome) {
+        self.tx_end(context);
+    }
+
+    fn create(&mut self, context: &mut CTX, _inputs: &mut CreateInputs) -> Option<CreateOutcome> {
+        self.tx_start(context);
+        None
+    }
+
+    fn create_end(
+        &mut self,
+        context: &mut CTX,
+        _inputs: &CreateInputs,
+        _outcome: &mut CreateOutcome,
+    ) {
+        self.tx_end(context);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::InspectEvm;
+    use context::{Context, TxEnv};
+    use database::{BenchmarkDB, BENCH_CALLER, BENCH_TARGET};
+    use handler::{MainBuilder, MainContext};
+    use primitives::{TxKind, U256};
+    use state::bytecode::Bytecode;
+
+    #[test]
+    fn test_storage_diff() {
+        // slot0 = 1; slot0 = 2; slot1 = 1; slot1 = 0
+        let bytecode = Bytecode::new_raw(
+            [
+                opcode::PUSH1,
+                0x1,
+                opcode::PUSH1,
+                0x0,
+                opcode::SSTORE,
+                opcode::PUSH1,
+                0x2,
+                opcode::PUSH1,
+                0x0,
+                opcode::SSTORE,
+                opcode::PUSH1,
+                0x1,
+                opcode::PUSH1,
+                0x1,
+                opcode::SSTORE,
+                opcode::PUSH1,
+                0x0,
+                opcode::PUSH1,
+                0x1,
+                opcode::SSTORE,
+                opcode::STOP,
+            ]
+            .to_vec()
+            .into(),
+        );
+
+        let mut evm = Context::mainnet()
+            .with_db(BenchmarkDB::new_bytecode(bytecode))
+            .build_mainnet_with_inspector(StorageDiffInspector::new());
+        evm.inspect_one_tx(
+            TxEnv::builder()
+                .caller(BENCH_CALLER)
+                .kind(TxKind::Call(BENCH_TARGET))
+                .gas_limit(1_000_000)
+                .build()
+                .unwrap(),
+        )
+        .unwrap();
+
+        let inspector = &evm.inspector;
+        let writes = inspector.writes();
+        assert_eq!(writes.len(), 4);
+        assert_eq!(
+            writes[1],
+            StorageWrite {
+                address: BENCH_TARGET,
+                key: U256::ZERO,
+                original: U256::ZERO,
+                previous: U256::from(1),
+                new: U256::from(2),
+                depth: 1,
+            }
+        );
+        assert_eq!(writes[3].previous, U256::from(1));
+
+        // `0 -> 1 -> 0` of slot1 is not part of the diff.
+        let diff = inspector.address_diff(BENCH_TARGET).unwrap();
+        assert_eq!(diff.len(), 1);
+        assert_eq!(
+            diff[&U256::ZERO],
+            SlotDiff {
+                original: U256::ZERO,
+                present: U256::from(2),
+            }
+        );
+    }
+}
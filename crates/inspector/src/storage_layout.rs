@@ -0,0 +1,439 @@
+//! StorageLayoutInspector - Decodes storage accesses with solc storage layouts.
+use crate::Inspector;
+use context::ContextTr;
+use interpreter::{
+    interpreter_types::{InputsTr, Jumps, LoopControl, MemoryTr, StackTr},
+    Interpreter, InterpreterTypes,
+};
+use primitives::{hex, keccak256, Address, AddressMap, B256Map, B256, U256};
+use serde::{Deserialize, Serialize};
+use state::bytecode::opcode;
+use std::collections::BTreeMap;
+
+/// Maximum number of nested mappings that are resolved.
+const MAX_MAPPING_DEPTH: usize = 8;
+
+/// Storage layout of a contract as emitted by `solc --storage-layout`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StorageLayout {
+    /// State variables.
+    pub storage: Vec<StorageVariable>,
+    /// Types of the state variables by type identifier.
+    #[serde(default)]
+    pub types: BTreeMap<String, StorageType>,
+}
+
+impl StorageLayout {
+    /// Parses the storage layout JSON.
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+}
+
+/// State variable of a [`StorageLayout`].
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StorageVariable {
+    /// Name of the variable.
+    pub label: String,
+    /// Slot of the variable as a decimal string.
+    pub slot: String,
+    /// Offset of the variable in the slot in bytes, variables smaller than a slot are packed.
+    pub offset: usize,
+    /// Type identifier of the variable.
+    #[serde(rename = "type")]
+    pub ty: String,
+}
+
+/// Type of a [`StorageLayout`].
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageType {
+    /// How the type is stored: `inplace`, `mapping`, `dynamic_array` or `bytes`.
+    pub encoding: String,
+    /// Solidity name of the type, e.g. `mapping(address => uint256)`.
+    pub label: String,
+    /// Number of bytes used by the type as a decimal string.
+    pub number_of_bytes: String,
+    /// Key type identifier of mappings.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key: Option<String>,
+    /// Value type identifier of mappings.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
+}
+
+/// Kind of a storage access.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum StorageOp {
+    /// `SLOAD`.
+    Load,
+    /// `SSTORE`.
+    Store,
+}
+
+/// Variable stored in an accessed slot.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DecodedVariable {
+    /// Name of the variable, mapping elements are named like `balances[0x..]`.
+    pub name: String,
+    /// Solidity name of the type.
+    pub ty: String,
+    /// Value of the variable.
+    pub value: String,
+}
+
+/// Storage access recorded by [`StorageLayoutInspector`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StorageAccess {
+    /// Address whose storage was accessed.
+    pub address: Address,
+    /// Kind of the access.
+    pub op: StorageOp,
+    /// Accessed slot.
+    pub slot: U256,
+    /// Loaded or stored value.
+    pub value: U256,
+    /// Variables stored in the slot, empty if the slot could not be decoded.
+    pub variables: Vec<DecodedVariable>,
+}
+
+/// Storage layout with the variables indexed by slot.
+#[derive(Clone, Debug)]
+struct IndexedLayout {
+    layout: StorageLayout,
+    slots: BTreeMap<U256, Vec<usize>>,
+}
+
+/// Inspector that decodes `SLOAD`s and `SSTORE`s with solc storage layouts.
+///
+/// Layouts are registered per address with [`StorageLayoutInspector::with_layout`]. Slots of
+/// state variables are decoded into the variable names and typed values, slots of mapping
+/// elements are resolved from the preimages of the 64 byte `KECCAK256`s executed before the
+/// access, which is how solc computes them. Elements of dynamic arrays and members of structs
+/// stored in mappings are not decoded.
+#[derive(Clone, Debug, Default)]
+pub struct StorageLayoutInspector {
+    layouts: AddressMap<IndexedLayout>,
+    /// Preimages of the 64 byte hashes, a mapping key followed by the slot of the mapping.
+    preimages: B256Map<[u8; 64]>,
+    accesses: Vec<StorageAccess>,
+    /// `SLOAD` whose value is read when the instruction has been executed.
+    pending_load: Option<(Address, U256)>,
+}
+
+impl StorageLayoutInspector {
+    /// Creates an inspector without layouts.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers the storage layout of the address.
+    pub fn with_layout(mut self, address: Address, layout: StorageLayout) -> Self {
+        self.insert_layout(address, layout);
+        self
+    }
+
+    /// Registers the storage layout of the address, replacing the previous layout.
+    pub fn insert_layout(&mut self, address: Address, layout: StorageLayout) {
+        let mut slots = BTreeMap::<U256, Vec<usize>>::new();
+        for (index, variable) in layout.storage.iter().enumerate() {
+            if let Ok(slot) = variable.slot.parse() {
+                slots.entry(slot).or_default().push(index);
+            }
+        }
+        self.layouts
+            .insert(address, IndexedLayout { layout, slots });
+    }
+
+    /// Records a `KECCAK256` preimage used to resolve mapping slots.
+    ///
+    /// Preimages that are not 64 bytes long are ignored.
+    pub fn insert_preimage(&mut self, preimage: &[u8]) {
+        if let Ok(preimage) = <[u8; 64]>::try_from(preimage) {
+            self.preimages.insert(keccak256(preimage), preimage);
+        }
+    }
+
+    /// Returns the recorded storage accesses.
+    pub fn accesses(&self) -> &[StorageAccess] {
+        &self.accesses
+    }
+
+    /// Clears the recorded accesses and preimages, layouts are kept.
+    pub fn clear(&mut self) {
+        self.accesses.clear();
+        self.preimages.clear();
+        self.pending_load = None;
+    }
+
+    /// Decodes the value of the slot of the address.
+    pub fn decode(&self, address: Address, slot: U256, value: U256) -> Vec<DecodedVariable> {
+        let Some(layout) = self.layouts.get(&address) else {
+            return Vec::new();
+        };
+        if let Some(indices) = layout.slots.get(&slot) {
+            return indices
+                .iter()
+                .map(|&index| {
+                    let variable = &layout.layout.storage[index];
+                    let ty = layout.layout.types.get(&variable.ty);
+                    DecodedVariable {
+                        name: variable.label.clone(),
+                        ty: type_label(ty, &variable.ty),
+                        value: decode_value(ty, value, variable.offset),
+                    }
+                })
+                .collect();
+        }
+        let Some((name, ty_id)) = self.resolve_mapping(layout, slot, 0) else {
+            return Vec::new();
+        };
+        let ty = layout.layout.types.get(ty_id);
+        Vec::from([DecodedVariable {
+            name,
+            ty: type_label(ty, ty_id),
+            value: decode_value(ty, value, 0),
+        }])
+    }
+
+    /// Resolves a slot computed as `keccak256(key . mapping_slot)` into the name of the
+    /// element and its value type identifier.
+    fn resolve_mapping<'a>(
+        &self,
+        layout: &'a IndexedLayout,
+        slot: U256,
+        depth: usize,
+    ) -> Option<(String, &'a str)> {
+        let preimage = self.preimages.get(&B256::from(slot))?;
+        let base = U256::from_be_slice(&preimage[32..]);
+        let types = &layout.layout.types;
+        let is_mapping = |ty: &str| types.get(ty).is_some_and(|ty| ty.encoding == "mapping");
+
+        let (base_name, base_ty) = match layout.slots.get(&base).and_then(|indices| {
+            indices
+                .iter()
+                .map(|&index| &layout.layout.storage[index])
+                .find(|variable| is_mapping(&variable.ty))
+        }) {
+            Some(variable) => (variable.label.clone(), variable.ty.as_str()),
+            None if depth < MAX_MAPPING_DEPTH => {
+                let (name, ty) = self.resolve_mapping(layout, base, depth + 1)?;
+                if !is_mapping(ty) {
+                    return None;
+                }
+                (name, ty)
+            }
+            None => return None,
+        };
+
+        let mapping = types.get(base_ty)?;
+        let key_ty = mapping.key.as_deref().and_then(|key| types.get(key));
+        let key = decode_value(key_ty, U256::from_be_slice(&preimage[..32]), 0);
+        Some((format!("{base_name}[{key}]"), mapping.value.as_deref()?))
+    }
+
+    fn record(&mut self, address: Address, op: StorageOp, slot: U256, value: U256) {
+        let variables = self.decode(address, slot, value);
+        self.accesses.push(StorageAccess {
+            address,
+            op,
+            slot,
+            value,
+            variables,
+        });
+    }
+}
+
+/// Returns the Solidity name of the type, or its identifier if the type is unknown.
+fn type_label(ty: Option<&StorageType>, id: &str) -> String {
+    ty.map_or(id, |ty| ty.label.as_str()).to_string()
+}
+
+/// Decodes the value of the type stored at `offset` bytes of the word.
+fn decode_value(ty: Option<&StorageType>, word: U256, offset: usize) -> String {
+    let size = ty
+        .and_then(|ty| ty.number_of_bytes.parse::<usize>().ok())
+        .unwrap_or(32)
+        .clamp(1, 32);
+    let word = if size < 32 {
+        (word >> (offset * 8)) & ((U256::from(1) << (size * 8)) - U256::from(1))
+    } else {
+        word
+    };
+    let Some(ty) = ty.filter(|ty| ty.encoding == "inplace") else {
+        return format!("{word:#x}");
+    };
+    let label = ty.label.as_str();
+    if label == "bool" {
+        (!word.is_zero()).to_string()
+    } else if label == "address" || label.starts_with("contract ") {
+        Address::from_word(B256::from(word)).to_string()
+    } else if label.starts_with("uint") || label.starts_with("enum ") {
+        word.to_string()
+    } else if label.starts_with("int") {
+        let bits = size * 8;
+        if word.bit(bits - 1) {
+            let mask = U256::MAX >> (256 - bits);
+            format!("-{}", (mask - word).wrapping_add(U256::from(1)))
+        } else {
+            word.to_string()
+        }
+    } else if label.starts_with("bytes") {
+        hex::encode_prefixed(&word.to_be_bytes::<32>()[32 - size..])
+    } else {
+        format!("{word:#x}")
+    }
+}
+
+impl<CTX, INTR> Inspector<CTX, INTR> for StorageLayoutInspector
+where
+    CTX: ContextTr,
+    INTR: InterpreterTypes,
+{
+    fn step(&mut self, interp: &mut Interpreter<INTR>, _context: &mut CTX) {
+        let stack = interp.stack.data();
+        let arg = |i: usize| stack.len().checked_sub(i + 1).map(|i| stack[i]);
+        match interp.bytecode.opcode() {
+            opcode::KECCAK256 => {
+                let (Some(offset), Some(size)) = (arg(0), arg(1)) else {
+                    return;
+                };
+                let offset = offset.saturating_to::<usize>();
+                if size == U256::from(64) && offset.saturating_add(64) <= interp.memory.size() {
+                    let preimage = interp.memory.slice_len(offset, 64).to_vec();
+                    self.insert_preimage(&preimage);
+                }
+            }
+            opcode::SLOAD => {
+                if let Some(slot) = arg(0) {
+                    self.pending_load = Some((interp.input.target_address(), slot));
+                }
+            }
+            opcode::SSTORE => {
+                if let (Some(slot), Some(value)) = (arg(0), arg(1)) {
+                    let address = interp.input.target_address();
+                    self.record(address, StorageOp::Store, slot, value);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn step_end(&mut self, interp: &mut Interpreter<INTR>, _context: &mut CTX) {
+        let Some((address, slot)) = self.pending_load.take() else {
+            return;
+        };
+        if interp.bytecode.instruction_result().is_some() {
+            return;
+        }
+        if let Some(&value) = interp.stack.data().last() {
+            self.record(address, StorageOp::Load, slot, value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::InspectEvm;
+    use context::{Context, TxEnv};
+    use database::{BenchmarkDB, BENCH_CALLER, BENCH_TARGET};
+    use handler::{MainBuilder, MainContext};
+    use primitives::{address, TxKind};
+    use state::bytecode::Bytecode;
+
+    const LAYOUT: &str = r#"{
+        "storage": [
+            {"astId": 1, "contract": "Token", "label": "totalSupply", "offset": 0, "slot": "0", "type": "t_uint256"},
+            {"astId": 2, "contract": "Token", "label": "balances", "offset": 0, "slot": "1", "type": "t_mapping(t_address,t_uint256)"},
+            {"astId": 3, "contract": "Token", "label": "paused", "offset": 0, "slot": "2", "type": "t_bool"},
+            {"astId": 4, "contract": "Token", "label": "delta", "offset": 1, "slot": "2", "type": "t_int8"}
+        ],
+        "types": {
+            "t_address": {"encoding": "inplace", "label": "address", "numberOfBytes": "20"},
+            "t_bool": {"encoding": "inplace", "label": "bool", "numberOfBytes": "1"},
+            "t_int8": {"encoding": "inplace", "label": "int8", "numberOfBytes": "1"},
+            "t_mapping(t_address,t_uint256)": {"encoding": "mapping", "key": "t_address", "label": "mapping(address => uint256)", "numberOfBytes": "32", "value": "t_uint256"},
+            "t_uint256": {"encoding": "inplace", "label": "uint256", "numberOfBytes": "32"}
+        }
+    }"#;
+
+    #[test]
+    fn decodes_packed_variables() {
+        let inspector = StorageLayoutInspector::new()
+            .with_layout(BENCH_TARGET, StorageLayout::from_json(LAYOUT).unwrap());
+        let variables = inspector.decode(BENCH_TARGET, U256::from(2), U256::from(0xfe01));
+        let decoded: Vec<_> = variables
+            .iter()
+            .map(|v| (v.name.as_str(), v.value.as_str()))
+            .collect();
+        assert_eq!(decoded, [("paused", "true"), ("delta", "-2")]);
+        assert!(inspector
+            .decode(BENCH_CALLER, U256::from(2), U256::ZERO)
+            .is_empty());
+    }
+
+    #[test]
+    fn decodes_mapping_elements() {
+        let holder = address!("0x00000000000000000000000000000000000000aa");
+        // totalSupply = 5; balances[holder] = 7
+        let mut code = Vec::from([opcode::PUSH1, 0x5, opcode::PUSH1, 0x0, opcode::SSTORE]);
+        code.push(opcode::PUSH20);
+        code.extend_from_slice(holder.as_slice());
+        code.extend_from_slice(&[
+            opcode::PUSH1,
+            0x0,
+            opcode::MSTORE,
+            opcode::PUSH1,
+            0x1,
+            opcode::PUSH1,
+            0x20,
+            opcode::MSTORE,
+            opcode::PUSH1,
+            0x40,
+            opcode::PUSH1,
+            0x0,
+            opcode::KECCAK256,
+            opcode::PUSH1,
+            0x7,
+            opcode::SWAP1,
+            opcode::SSTORE,
+            opcode::STOP,
+        ]);
+
+        let inspector = StorageLayoutInspector::new()
+            .with_layout(BENCH_TARGET, StorageLayout::from_json(LAYOUT).unwrap());
+        let mut evm = Context::mainnet()
+            .with_db(BenchmarkDB::new_bytecode(Bytecode::new_raw(code.into())))
+            .build_mainnet_with_inspector(inspector);
+        evm.inspect_one_tx(
+            TxEnv::builder()
+                .caller(BENCH_CALLER)
+                .kind(TxKind::Call(BENCH_TARGET))
+                .gas_limit(100_000)
+                .build()
+                .unwrap(),
+        )
+        .unwrap();
+
+        let accesses = evm.inspector.accesses();
+        assert_eq!(accesses.len(), 2);
+        assert_eq!(
+            accesses[0].variables,
+            [DecodedVariable {
+                name: "totalSupply".into(),
+                ty: "uint256".into(),
+                value: "5".into(),
+            }]
+        );
+        assert_eq!(accesses[1].op, StorageOp::Store);
+        assert_eq!(
+            accesses[1].variables,
+            [DecodedVariable {
+                name: format!("balances[{holder}]"),
+                ty: "uint256".into(),
+                value: "7".into(),
+            }]
+        );
+    }
+}
@@ -2,7 +2,7 @@
 
 extern crate alloc;
 
-use crate::Inspector;
+use crate::{Inspector, RefundEvent};
 use alloc::{format, string::String, vec::Vec};
 use interpreter::{
     interpreter_types::{Jumps, MemoryTr, StackTr},
@@ -62,6 +62,8 @@ pub enum InspectorEvent {
         /// Value transferred.
         value: U256,
     },
+    /// Gas refund change.
+    Refund(RefundEvent),
 }
 
 /// Test inspector that records execution events.
@@ -197,6 +199,10 @@ where
             value,
         });
     }
+
+    fn refund(&mut self, _context: &mut CTX, event: RefundEvent) {
+        self.events.push(InspectorEvent::Refund(event));
+    }
 }
 
 /// Default tests for EVM implementations.
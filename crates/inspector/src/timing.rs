@@ -0,0 +1,251 @@
+//! TimingInspector - Inspector that measures the wall-clock time of every call frame.
+use crate::{inspectors::FrameKind, Inspector};
+use core::fmt::Write;
+use interpreter::{
+    CallInputs, CallOutcome, CreateInputs, CreateOutcome, Gas, InstructionResult, InterpreterTypes,
+};
+use primitives::Address;
+use std::{
+    string::String,
+    time::{Duration, Instant},
+    vec::Vec,
+};
+
+/// Time and gas spent by a single call frame.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FrameTiming {
+    /// Depth of the frame, `0` for the transaction frame.
+    pub depth: usize,
+    /// Index of the parent frame.
+    pub parent: Option<usize>,
+    /// Indices of the child frames in the order they were executed.
+    pub children: Vec<usize>,
+    /// Kind of the frame.
+    pub kind: FrameKind,
+    /// Target address for calls or created address for successful creates.
+    pub address: Option<Address>,
+    /// Gas forwarded to the frame.
+    pub gas_limit: u64,
+    /// Gas spent by the frame, including the gas spent by its descendants.
+    pub gas_spent: u64,
+    /// Time spent in the frame, including the time spent in its descendants.
+    pub elapsed: Duration,
+    /// Result of the frame, [`None`] while the frame is executing.
+    pub result: Option<InstructionResult>,
+}
+
+/// Inspector that measures the wall-clock time spent in each call frame and builds a call tree.
+///
+/// Gas prices execution, not the time it takes, precompiles and memory expansion in particular
+/// can take much longer than their gas suggests. Time of a frame is split into time spent by its
+/// own instructions ([`TimingInspector::self_time`]) and by its descendants
+/// ([`TimingInspector::descendant_time`]), next to the gas spent, so the frames where execution
+/// time goes can be found in large simulations.
+///
+/// Time is measured between the call and call end hooks and includes the overhead of the
+/// inspection, compare frames with each other rather than with uninspected execution.
+#[derive(Clone, Debug, Default)]
+pub struct TimingInspector {
+    frames: Vec<FrameTiming>,
+    stack: Vec<(usize, Instant)>,
+}
+
+impl TimingInspector {
+    /// Creates a new timing inspector.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns all frames in the order they were entered.
+    ///
+    /// First frame is the transaction frame and the root of the tree.
+    pub fn frames(&self) -> &[FrameTiming] {
+        &self.frames
+    }
+
+    /// Returns the transaction frame.
+    pub fn root(&self) -> Option<&FrameTiming> {
+        self.frames.first()
+    }
+
+    /// Returns time spent by the descendants of the frame.
+    pub fn descendant_time(&self, index: usize) -> Duration {
+        self.frames[index]
+            .children
+            .iter()
+            .map(|&child| self.frames[child].elapsed)
+            .sum()
+    }
+
+    /// Returns time spent by the frame's own instructions.
+    pub fn self_time(&self, index: usize) -> Duration {
+        self.frames[index]
+            .elapsed
+            .saturating_sub(self.descendant_time(index))
+    }
+
+    /// Returns the indices of the frames sorted by their self time, slowest first.
+    pub fn slowest(&self) -> Vec<usize> {
+        let mut indices: Vec<usize> = (0..self.frames.len()).collect();
+        indices.sort_by_key(|&index| core::cmp::Reverse(self.self_time(index)));
+        indices
+    }
+
+    /// Clears the tree so the inspector can be used for the next transaction.
+    pub fn clear(&mut self) {
+        self.frames.clear();
+        self.stack.clear();
+    }
+
+    /// Renders the call tree, one frame per line indented by depth.
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        for (index, frame) in self.frames.iter().enumerate() {
+            let target = frame
+                .address
+                .map(|address| address.to_string())
+                .unwrap_or_else(|| "<create>".into());
+            let _ = writeln!(
+                out,
+                "{:indent$}{target} time: {:?} (self: {:?}) gas: {}",
+                "",
+                frame.elapsed,
+                self.self_time(index),
+                frame.gas_spent,
+                indent = frame.depth * 2,
+            );
+        }
+        out
+    }
+
+    fn push_frame(&mut self, kind: FrameKind, address: Option<Address>, gas_limit: u64) {
+        let parent = self.stack.last().map(|&(index, _)| index);
+        let index = self.frames.len();
+        self.frames.push(FrameTiming {
+            depth: self.stack.len(),
+            parent,
+            children: Vec::new(),
+            kind,
+            address,
+            gas_limit,
+            gas_spent: 0,
+            elapsed: Duration::ZERO,
+            result: None,
+        });
+        if let Some(parent) = parent {
+            self.frames[parent].children.push(index);
+        }
+        self.stack.push((index, Instant::now()));
+    }
+
+    fn pop_frame(&mut self, gas: &Gas, result: InstructionResult, address: Option<Address>) {
+        let Some((index, start)) = self.stack.pop() else {
+            return;
+        };
+        let frame = &mut self.frames[index];
+        frame.elapsed = start.elapsed();
+        frame.gas_spent = if result.is_halt() {
+            frame.gas_limit
+        } else {
+            gas.total_gas_spent()
+        };
+        frame.result = Some(result);
+        if address.is_some() && result.is_ok() {
+            frame.address = address;
+        }
+    }
+}
+
+impl<CTX, INTR: InterpreterTypes> Inspector<CTX, INTR> for TimingInspector {
+    fn call(&mut self, _context: &mut CTX, inputs: &mut CallInputs) -> Option<CallOutcome> {
+        self.push_frame(
+            FrameKind::Call(inputs.scheme),
+            Some(inputs.target_address),
+            inputs.gas_limit,
+        );
+        None
+    }
+
+    fn call_end(&mut self, _context: &mut CTX, _inputs: &CallInputs, outcome: &mut CallOutcome) {
+        self.pop_frame(&outcome.result.gas, outcome.result.result, None);
+    }
+
+    fn create(&mut self, _context: &mut CTX, inputs: &mut CreateInputs) -> Option<CreateOutcome> {
+        self.push_frame(FrameKind::Create(inputs.scheme()), None, inputs.gas_limit());
+        None
+    }
+
+    fn create_end(
+        &mut self,
+        _context: &mut CTX,
+        _inputs: &CreateInputs,
+        outcome: &mut CreateOutcome,
+    ) {
+        self.pop_frame(&outcome.result.gas, outcome.result.result, outcome.address);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::InspectEvm;
+    use context::{Context, TxEnv};
+    use database::{BenchmarkDB, BENCH_CALLER, BENCH_TARGET};
+    use handler::{MainBuilder, MainContext};
+    use interpreter::CallScheme;
+    use primitives::TxKind;
+    use state::bytecode::{opcode, Bytecode};
+
+    #[test]
+    fn measures_call_tree() {
+        // Copy 32 bytes with the identity precompile and stop.
+        let bytecode = Bytecode::new_raw(
+            [
+                opcode::PUSH1,
+                0x20,
+                opcode::PUSH1,
+                0x0,
+                opcode::PUSH1,
+                0x20,
+                opcode::PUSH1,
+                0x0,
+                opcode::PUSH1,
+                0x04,
+                opcode::GAS,
+                opcode::STATICCALL,
+                opcode::STOP,
+            ]
+            .to_vec()
+            .into(),
+        );
+        let mut evm = Context::mainnet()
+            .with_db(BenchmarkDB::new_bytecode(bytecode))
+            .build_mainnet_with_inspector(TimingInspector::new());
+        evm.inspect_one_tx(
+            TxEnv::builder()
+                .caller(BENCH_CALLER)
+                .kind(TxKind::Call(BENCH_TARGET))
+                .gas_limit(100_000)
+                .build()
+                .unwrap(),
+        )
+        .unwrap();
+
+        let inspector = &evm.inspector;
+        let [root, child] = inspector.frames() else {
+            panic!("expected two frames: {:?}", inspector.frames());
+        };
+        assert_eq!(root.children, [1]);
+        assert_eq!(child.parent, Some(0));
+        assert_eq!(child.kind, FrameKind::Call(CallScheme::StaticCall));
+        assert_eq!(child.address, Some(Address::with_last_byte(4)));
+        assert!(child.gas_spent > 0);
+        assert!(child.elapsed <= root.elapsed);
+        assert_eq!(
+            inspector.self_time(0) + inspector.descendant_time(0),
+            root.elapsed
+        );
+        assert_eq!(inspector.slowest().len(), 2);
+        assert_eq!(inspector.render().lines().count(), 2);
+    }
+}
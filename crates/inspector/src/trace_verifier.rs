@@ -0,0 +1,396 @@
+//! TraceVerifier - Re-executes a transaction step-locked against an EIP-3155 trace.
+use crate::{eip3155::CloneStack, Inspector};
+use context::{ContextTr, JournalTr};
+use core::fmt;
+use interpreter::{
+    interpreter_types::{Jumps, StackTr},
+    Interpreter, InterpreterTypes,
+};
+use primitives::U256;
+use serde::{de, Deserialize, Deserializer, Serialize};
+use state::bytecode::opcode::OpCode;
+use std::io::BufRead;
+
+/// Step of an [EIP-3155](https://eips.ethereum.org/EIPS/eip-3155) trace.
+///
+/// Only the fields that are compared by the [`TraceVerifier`] are kept. Numbers are accepted both
+/// as JSON numbers and as hex strings, as clients differ in how they print them.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TraceStep {
+    /// Program counter.
+    #[serde(deserialize_with = "deserialize_u64")]
+    pub pc: u64,
+    /// Opcode.
+    #[serde(deserialize_with = "deserialize_u64_as_u8")]
+    pub op: u8,
+    /// Gas left before executing the operation.
+    #[serde(deserialize_with = "deserialize_u64")]
+    pub gas: u64,
+    /// Depth of the call stack, starting at one.
+    #[serde(deserialize_with = "deserialize_u64")]
+    pub depth: u64,
+    /// Values on the stack, `None` if the trace was recorded without the stack.
+    #[serde(default)]
+    pub stack: Option<Vec<U256>>,
+}
+
+impl TraceStep {
+    /// Reads the steps of a trace with one JSON object per line.
+    ///
+    /// Lines that are not steps, like the summary printed at the end of the trace, are skipped.
+    pub fn read_trace(reader: impl BufRead) -> std::io::Result<Vec<Self>> {
+        let mut steps = Vec::new();
+        for line in reader.lines() {
+            let line = line?;
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            let value: serde_json::Value = serde_json::from_str(line)?;
+            if value.get("pc").is_none() {
+                continue;
+            }
+            steps.push(serde_json::from_value(value)?);
+        }
+        Ok(steps)
+    }
+}
+
+impl fmt::Display for TraceStep {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "pc={} op=", self.pc)?;
+        match OpCode::new(self.op) {
+            Some(op) => write!(f, "{op}")?,
+            None => write!(f, "{:#04x}", self.op)?,
+        }
+        write!(f, " gas={} depth={}", self.gas, self.depth)?;
+        if let Some(stack) = &self.stack {
+            write!(f, " stack=[")?;
+            for (i, value) in stack.iter().enumerate() {
+                if i > 0 {
+                    write!(f, ", ")?;
+                }
+                write!(f, "{value:#x}")?;
+            }
+            write!(f, "]")?;
+        }
+        Ok(())
+    }
+}
+
+/// Field in which the execution diverged from the trace.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DivergenceKind {
+    /// Different program counter.
+    Pc,
+    /// Different opcode.
+    Opcode,
+    /// Different gas left.
+    Gas,
+    /// Different call depth.
+    Depth,
+    /// Different stack.
+    Stack,
+    /// Execution ended before the end of the trace.
+    MissingStep,
+    /// Execution continued after the end of the trace.
+    ExtraStep,
+}
+
+/// First step in which the execution diverged from the trace.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Divergence {
+    /// Index of the step.
+    pub step: usize,
+    /// Field that diverged.
+    pub kind: DivergenceKind,
+    /// Step of the trace, `None` if the execution continued after the end of the trace.
+    pub expected: Option<TraceStep>,
+    /// Step of the execution, `None` if the execution ended before the end of the trace.
+    pub actual: Option<TraceStep>,
+}
+
+impl fmt::Display for Divergence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "execution diverged at step {}: {:?}",
+            self.step, self.kind
+        )?;
+        match &self.expected {
+            Some(step) => writeln!(f, "  expected: {step}")?,
+            None => writeln!(f, "  expected: end of execution")?,
+        }
+        match &self.actual {
+            Some(step) => write!(f, "  actual:   {step}"),
+            None => write!(f, "  actual:   end of execution"),
+        }
+    }
+}
+
+/// Inspector that compares every executed step with an EIP-3155 trace, e.g. one written by
+/// another client for the same transaction.
+///
+/// Comparing every step is a stronger differential check than comparing the final state, the
+/// first divergence points to the instruction that behaves differently. Once the execution
+/// diverged, the remaining steps are not compared.
+///
+/// Call [`TraceVerifier::finish`] after the execution to get the result.
+#[derive(Clone, Debug)]
+pub struct TraceVerifier {
+    expected: Vec<TraceStep>,
+    next: usize,
+    compare_gas: bool,
+    compare_stack: bool,
+    divergence: Option<Divergence>,
+}
+
+impl TraceVerifier {
+    /// Creates a verifier of the steps of a trace.
+    pub fn new(expected: Vec<TraceStep>) -> Self {
+        Self {
+            expected,
+            next: 0,
+            compare_gas: true,
+            compare_stack: true,
+            divergence: None,
+        }
+    }
+
+    /// Don't compare the gas left, e.g. to compare a trace of a client with a different gas
+    /// schedule.
+    pub fn without_gas(mut self) -> Self {
+        self.compare_gas = false;
+        self
+    }
+
+    /// Don't compare the stack.
+    pub fn without_stack(mut self) -> Self {
+        self.compare_stack = false;
+        self
+    }
+
+    /// Returns the first divergence found so far.
+    pub fn divergence(&self) -> Option<&Divergence> {
+        self.divergence.as_ref()
+    }
+
+    /// Returns the number of steps compared so far.
+    pub fn compared_steps(&self) -> usize {
+        self.next
+    }
+
+    /// Returns the first divergence, including steps of the trace that were not executed.
+    ///
+    /// Resets the verifier so it can be used again for the same trace.
+    pub fn finish(&mut self) -> Result<usize, Divergence> {
+        let steps = self.next;
+        self.next = 0;
+        if let Some(divergence) = self.divergence.take() {
+            return Err(divergence);
+        }
+        if let Some(expected) = self.expected.get(steps) {
+            return Err(Divergence {
+                step: steps,
+                kind: DivergenceKind::MissingStep,
+                expected: Some(expected.clone()),
+                actual: None,
+            });
+        }
+        Ok(steps)
+    }
+
+    fn compare(&self, expected: &TraceStep, actual: &TraceStep) -> Option<DivergenceKind> {
+        if expected.depth != actual.depth {
+            Some(DivergenceKind::Depth)
+        } else if expected.pc != actual.pc {
+            Some(DivergenceKind::Pc)
+        } else if expected.op != actual.op {
+            Some(DivergenceKind::Opcode)
+        } else if self.compare_gas && expected.gas != actual.gas {
+            Some(DivergenceKind::Gas)
+        } else if self.compare_stack
+            && expected
+                .stack
+                .as_ref()
+                .is_some_and(|stack| Some(stack) != actual.stack.as_ref())
+        {
+            Some(DivergenceKind::Stack)
+        } else {
+            None
+        }
+    }
+}
+
+impl<CTX, INTR> Inspector<CTX, INTR> for TraceVerifier
+where
+    CTX: ContextTr,
+    INTR: InterpreterTypes<Stack: StackTr + CloneStack>,
+{
+    fn step(&mut self, interp: &mut Interpreter<INTR>, context: &mut CTX) {
+        if self.divergence.is_some() {
+            return;
+        }
+        let step = self.next;
+        self.next += 1;
+
+        let stack = self.compare_stack.then(|| {
+            let mut stack = Vec::new();
+            interp.stack.clone_into(&mut stack);
+            stack
+        });
+        let actual = TraceStep {
+            pc: interp.bytecode.pc() as u64,
+            op: interp.bytecode.opcode(),
+            gas: interp.gas.remaining(),
+            depth: context.journal_mut().depth() as u64,
+            stack,
+        };
+        let Some(expected) = self.expected.get(step) else {
+            self.divergence = Some(Divergence {
+                step,
+                kind: DivergenceKind::ExtraStep,
+                expected: None,
+                actual: Some(actual),
+            });
+            return;
+        };
+        if let Some(kind) = self.compare(expected, &actual) {
+            self.divergence = Some(Divergence {
+                step,
+                kind,
+                expected: Some(expected.clone()),
+                actual: Some(actual),
+            });
+        }
+    }
+}
+
+fn deserialize_u64<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum NumberOrHex {
+        Number(u64),
+        Hex(String),
+    }
+
+    match NumberOrHex::deserialize(deserializer)? {
+        NumberOrHex::Number(n) => Ok(n),
+        NumberOrHex::Hex(s) => match s.strip_prefix("0x") {
+            Some(hex) => u64::from_str_radix(hex, 16),
+            None => s.parse(),
+        }
+        .map_err(de::Error::custom),
+    }
+}
+
+fn deserialize_u64_as_u8<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u8, D::Error> {
+    let n = deserialize_u64(deserializer)?;
+    u8::try_from(n).map_err(de::Error::custom)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{inspectors::TracerEip3155, InspectEvm};
+    use context::{Context, TxEnv};
+    use database::{BenchmarkDB, BENCH_CALLER, BENCH_TARGET};
+    use handler::{MainBuilder, MainContext};
+    use primitives::TxKind;
+    use state::{bytecode::opcode, Bytecode};
+    use std::{
+        io::{self, Write},
+        sync::{Arc, Mutex},
+    };
+
+    #[derive(Clone, Default)]
+    struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    const CODE: [u8; 6] = [
+        opcode::PUSH1,
+        0x01,
+        opcode::PUSH1,
+        0x02,
+        opcode::ADD,
+        opcode::STOP,
+    ];
+
+    fn tx() -> TxEnv {
+        TxEnv::builder()
+            .caller(BENCH_CALLER)
+            .kind(TxKind::Call(BENCH_TARGET))
+            .gas_limit(100_000)
+            .build()
+            .unwrap()
+    }
+
+    /// Records the trace of the code with the EIP-3155 tracer.
+    fn record_trace(code: &[u8]) -> Vec<TraceStep> {
+        let buffer = SharedBuffer::default();
+        let bytecode = Bytecode::new_legacy(code.to_vec().into());
+        let mut evm = Context::mainnet()
+            .with_db(BenchmarkDB::new_bytecode(bytecode))
+            .build_mainnet_with_inspector(TracerEip3155::buffered(buffer.clone()));
+        evm.inspect_tx(tx()).unwrap();
+        drop(evm);
+        let output = buffer.0.lock().unwrap().clone();
+        TraceStep::read_trace(output.as_slice()).unwrap()
+    }
+
+    fn verify(code: &[u8], expected: Vec<TraceStep>) -> Result<usize, Divergence> {
+        let bytecode = Bytecode::new_legacy(code.to_vec().into());
+        let mut evm = Context::mainnet()
+            .with_db(BenchmarkDB::new_bytecode(bytecode))
+            .build_mainnet_with_inspector(TraceVerifier::new(expected));
+        evm.inspect_tx(tx()).unwrap();
+        evm.inspector.finish()
+    }
+
+    #[test]
+    fn own_trace_matches() {
+        let trace = record_trace(&CODE);
+        assert_eq!(trace.len(), 4);
+        assert_eq!(verify(&CODE, trace), Ok(4));
+    }
+
+    #[test]
+    fn reports_first_divergence() {
+        let mut trace = record_trace(&CODE);
+        // The other client computed a different sum.
+        trace[3].stack = Some(vec![U256::from(4)]);
+        let divergence = verify(&CODE, trace.clone()).unwrap_err();
+        assert_eq!(divergence.step, 3);
+        assert_eq!(divergence.kind, DivergenceKind::Stack);
+        assert_eq!(divergence.actual.unwrap().stack, Some(vec![U256::from(3)]));
+
+        // The other client executed one more step.
+        trace.push(trace[3].clone());
+        trace[3].stack = Some(vec![U256::from(3)]);
+        let divergence = verify(&CODE, trace).unwrap_err();
+        assert_eq!(divergence.step, 4);
+        assert_eq!(divergence.kind, DivergenceKind::MissingStep);
+    }
+
+    #[test]
+    fn reads_hex_and_number_fields() {
+        let trace = r#"{"pc":0,"op":96,"gas":"0x2540be400","gasCost":"0x3","memSize":0,"stack":[],"depth":1,"refund":0,"opName":"PUSH1"}
+{"pc":"0x2","op":"0x60","gas":9999999997,"depth":1,"stack":["0x1"]}
+{"output":"","gasUsed":"0x6","pass":true}"#;
+        let steps = TraceStep::read_trace(trace.as_bytes()).unwrap();
+        assert_eq!(steps.len(), 2);
+        assert_eq!(steps[0].gas, 10_000_000_000);
+        assert_eq!(steps[1].pc, 2);
+        assert_eq!(steps[1].op, opcode::PUSH1);
+        assert_eq!(steps[1].stack, Some(vec![U256::from(1)]));
+    }
+}
@@ -0,0 +1,248 @@
+//! TransferInspector - Inspector that records native value transfers.
+extern crate alloc;
+
+use crate::Inspector;
+use alloc::vec::Vec;
+use context::ContextTr;
+use interpreter::{
+    CallInputs, CallOutcome, CallScheme, CreateInputs, CreateOutcome, InterpreterResult,
+    InterpreterTypes,
+};
+use primitives::{Address, U256};
+
+/// Origin of a [`Transfer`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum TransferKind {
+    /// Value of a call transaction, `CALL` or `CALLCODE`.
+    ///
+    /// `CALLCODE` transfers the value from the caller to itself.
+    Call(CallScheme),
+    /// Value of a create transaction, `CREATE` or `CREATE2`.
+    Create,
+    /// Balance sent to the beneficiary of a `SELFDESTRUCT`.
+    SelfDestruct,
+    /// Block or uncle reward, recorded with [`TransferInspector::record_reward`].
+    Reward,
+}
+
+/// Native value transfer recorded by [`TransferInspector`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Transfer {
+    /// Origin of the transfer.
+    pub kind: TransferKind,
+    /// Sender, the zero address for rewards.
+    pub from: Address,
+    /// Receiver.
+    pub to: Address,
+    /// Transferred value.
+    pub value: U256,
+    /// Depth of the frame that received the value, `0` for the transaction and rewards.
+    ///
+    /// For `SELFDESTRUCT` this is the depth of the destroyed frame.
+    pub depth: usize,
+}
+
+/// Frame that is executing.
+#[derive(Clone, Copy, Debug)]
+struct Checkpoint {
+    /// Number of transfers at the start of the frame.
+    transfers: usize,
+    /// Index of the transfer of a create, the address is known when the frame ends.
+    create: Option<usize>,
+}
+
+/// Inspector that records every native value transfer, including the internal ones, e.g. for
+/// balance indexers.
+///
+/// Transfers of reverted frames are dropped as they did not happen. Transfers are accumulated over
+/// transactions, e.g. for a whole block, until they are taken with
+/// [`TransferInspector::take_transfers`]. Block rewards are not part of the EVM execution and are
+/// recorded by the block executor with [`TransferInspector::record_reward`]. Zero value transfers
+/// are skipped.
+#[derive(Clone, Debug, Default)]
+pub struct TransferInspector {
+    transfers: Vec<Transfer>,
+    checkpoints: Vec<Checkpoint>,
+}
+
+impl TransferInspector {
+    /// Creates a new transfer inspector.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the recorded transfers in execution order.
+    pub fn transfers(&self) -> &[Transfer] {
+        &self.transfers
+    }
+
+    /// Takes the recorded transfers.
+    pub fn take_transfers(&mut self) -> Vec<Transfer> {
+        core::mem::take(&mut self.transfers)
+    }
+
+    /// Records a block or uncle reward paid to the beneficiary.
+    pub fn record_reward(&mut self, beneficiary: Address, value: U256) {
+        if value.is_zero() {
+            return;
+        }
+        self.transfers.push(Transfer {
+            kind: TransferKind::Reward,
+            from: Address::ZERO,
+            to: beneficiary,
+            value,
+            depth: 0,
+        });
+    }
+
+    fn frame_start(&mut self, transfer: Option<Transfer>) {
+        let transfers = self.transfers.len();
+        let mut create = None;
+        if let Some(transfer) = transfer.filter(|transfer| !transfer.value.is_zero()) {
+            if transfer.kind == TransferKind::Create {
+                create = Some(transfers);
+            }
+            self.transfers.push(transfer);
+        }
+        self.checkpoints.push(Checkpoint { transfers, create });
+    }
+
+    fn frame_end(&mut self, result: &InterpreterResult, address: Option<Address>) {
+        let Some(checkpoint) = self.checkpoints.pop() else {
+            return;
+        };
+        if !result.is_ok() {
+            self.transfers.truncate(checkpoint.transfers);
+            return;
+        }
+        if let (Some(index), Some(address)) = (checkpoint.create, address) {
+            self.transfers[index].to = address;
+        }
+    }
+}
+
+impl<CTX: ContextTr, INTR: InterpreterTypes> Inspector<CTX, INTR> for TransferInspector {
+    fn call(&mut self, _context: &mut CTX, inputs: &mut CallInputs) -> Option<CallOutcome> {
+        let transfer = inputs.transfer_value().map(|value| Transfer {
+            kind: TransferKind::Call(inputs.scheme),
+            from: inputs.transfer_from(),
+            to: inputs.transfer_to(),
+            value,
+            depth: self.checkpoints.len(),
+        });
+        self.frame_start(transfer);
+        None
+    }
+
+    fn call_end(&mut self, _context: &mut CTX, _inputs: &CallInputs, outcome: &mut CallOutcome) {
+        self.frame_end(&outcome.result, None);
+    }
+
+    fn create(&mut self, _context: &mut CTX, inputs: &mut CreateInputs) -> Option<CreateOutcome> {
+        let transfer = Transfer {
+            kind: TransferKind::Create,
+            from: inputs.caller(),
+            // Set when the frame ends.
+            to: Address::ZERO,
+            value: inputs.value(),
+            depth: self.checkpoints.len(),
+        };
+        self.frame_start(Some(transfer));
+        None
+    }
+
+    fn create_end(
+        &mut self,
+        _context: &mut CTX,
+        _inputs: &CreateInputs,
+        outcome: &mut CreateOutcome,
+    ) {
+        self.frame_end(&outcome.result, outcome.address);
+    }
+
+    fn selfdestruct(&mut self, contract: Address, target: Address, value: U256) {
+        if value.is_zero() {
+            return;
+        }
+        self.transfers.push(Transfer {
+            kind: TransferKind::SelfDestruct,
+            from: contract,
+            to: target,
+            value,
+            depth: self.checkpoints.len().saturating_sub(1),
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::InspectEvm;
+    use context::{Context, TxEnv};
+    use database::{BenchmarkDB, BENCH_CALLER, BENCH_TARGET};
+    use handler::{MainBuilder, MainContext};
+    use primitives::TxKind;
+    use state::bytecode::{opcode, Bytecode};
+
+    #[test]
+    fn records_internal_transfers() {
+        let first = Address::with_last_byte(0xAA);
+        let second = Address::with_last_byte(0xBB);
+        let mut code = Vec::new();
+        // Sends 1 wei to `first` and the maximum value to `second`, which fails.
+        for (address, value) in [
+            (first, &[opcode::PUSH1, 0x1][..]),
+            (second, &[opcode::PUSH0, opcode::NOT]),
+        ] {
+            code.extend_from_slice(&[opcode::PUSH0, opcode::PUSH0, opcode::PUSH0, opcode::PUSH0]);
+            code.extend_from_slice(value);
+            code.push(opcode::PUSH20);
+            code.extend_from_slice(address.as_slice());
+            code.extend_from_slice(&[opcode::GAS, opcode::CALL, opcode::POP]);
+        }
+        code.push(opcode::STOP);
+
+        let mut evm = Context::mainnet()
+            .with_db(BenchmarkDB::new_bytecode(Bytecode::new_raw(code.into())))
+            .build_mainnet_with_inspector(TransferInspector::new());
+        evm.inspect_tx(
+            TxEnv::builder()
+                .caller(BENCH_CALLER)
+                .kind(TxKind::Call(BENCH_TARGET))
+                .value(U256::from(10))
+                .gas_limit(100_000)
+                .build()
+                .unwrap(),
+        )
+        .unwrap();
+        evm.inspector
+            .record_reward(Address::with_last_byte(0xCC), U256::from(2));
+
+        assert_eq!(
+            evm.inspector.transfers(),
+            [
+                Transfer {
+                    kind: TransferKind::Call(CallScheme::Call),
+                    from: BENCH_CALLER,
+                    to: BENCH_TARGET,
+                    value: U256::from(10),
+                    depth: 0,
+                },
+                Transfer {
+                    kind: TransferKind::Call(CallScheme::Call),
+                    from: BENCH_TARGET,
+                    to: first,
+                    value: U256::from(1),
+                    depth: 1,
+                },
+                Transfer {
+                    kind: TransferKind::Reward,
+                    from: Address::ZERO,
+                    to: Address::with_last_byte(0xCC),
+                    value: U256::from(2),
+                    depth: 0,
+                },
+            ]
+        );
+    }
+}
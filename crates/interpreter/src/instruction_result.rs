@@ -92,6 +92,12 @@ pub enum InstructionResult {
     FatalExternalError,
     /// Invalid encoding of an instruction's immediate operand.
     InvalidImmediateEncoding,
+    /// Invariant checked by an inspector was violated.
+    InvariantViolation,
+    /// Instruction budget set by an inspector was exhausted.
+    StepLimitExceeded,
+    /// Wall-clock time limit set by an inspector was exceeded.
+    TimeLimitExceeded,
 }
 
 impl From<TransferError> for InstructionResult {
@@ -144,6 +150,9 @@ impl From<HaltReason> for InstructionResult {
             HaltReason::CallNotAllowedInsideStatic => Self::CallNotAllowedInsideStatic,
             HaltReason::OutOfFunds => Self::OutOfFunds,
             HaltReason::CallTooDeep => Self::CallTooDeep,
+            HaltReason::InvariantViolation => Self::InvariantViolation,
+            HaltReason::StepLimitExceeded => Self::StepLimitExceeded,
+            HaltReason::TimeLimitExceeded => Self::TimeLimitExceeded,
         }
     }
 }
@@ -212,6 +221,9 @@ macro_rules! return_error {
             | $crate::InstructionResult::CreateInitCodeSizeLimit
             | $crate::InstructionResult::FatalExternalError
             | $crate::InstructionResult::InvalidImmediateEncoding
+            | $crate::InstructionResult::InvariantViolation
+            | $crate::InstructionResult::StepLimitExceeded
+            | $crate::InstructionResult::TimeLimitExceeded
     };
 }
 
@@ -383,6 +395,15 @@ impl<HaltReasonTr: From<HaltReason>> From<InstructionResult> for SuccessOrHalt<H
             InstructionResult::InvalidImmediateEncoding => {
                 Self::Halt(HaltReason::OpcodeNotFound.into())
             }
+            InstructionResult::InvariantViolation => {
+                Self::Halt(HaltReason::InvariantViolation.into())
+            }
+            InstructionResult::StepLimitExceeded => {
+                Self::Halt(HaltReason::StepLimitExceeded.into())
+            }
+            InstructionResult::TimeLimitExceeded => {
+                Self::Halt(HaltReason::TimeLimitExceeded.into())
+            }
         }
     }
 }
@@ -447,6 +468,9 @@ mod tests {
             InstructionResult::CreateContractStartingWithEF,
             InstructionResult::CreateInitCodeSizeLimit,
             InstructionResult::FatalExternalError,
+            InstructionResult::InvariantViolation,
+            InstructionResult::StepLimitExceeded,
+            InstructionResult::TimeLimitExceeded,
         ];
         for result in error_results {
             assert!(!result.is_ok());
@@ -58,6 +58,15 @@ impl<W: InterpreterTypes, H: Host + ?Sized> Instruction<W, H> {
     pub fn execute(self, ctx: InstructionContext<'_, H, W>) -> InstructionExecResult {
         (self.fn_)(ctx)
     }
+
+    /// Returns `true` if both instructions point to the same function.
+    ///
+    /// Same as with any function pointer comparison, the same function can have different
+    /// addresses in different codegen units.
+    #[inline]
+    pub fn is_same(self, other: Self) -> bool {
+        core::ptr::fn_addr_eq(self.fn_, other.fn_)
+    }
 }
 
 impl<W: InterpreterTypes, H: Host + ?Sized> Copy for Instruction<W, H> {}
@@ -120,7 +120,8 @@ pub fn create<const IS_CREATE2: bool, IT: ITy, H: Host + ?Sized>(
         // Single read of the destination: decides the charge by existence
         // alone (independently of the collision outcome checked at frame
         // creation) and adds it to the accessed addresses.
-        let created_address = create_inputs.created_address(caller_nonce);
+        let created_address =
+            create_inputs.created_address_with(caller_nonce, context.host.create_address_fn());
         let destination_alive = !context
             .host
             .load_account_info_skip_cold_load(created_address, false, false)?
@@ -144,7 +145,10 @@ pub fn create<const IS_CREATE2: bool, IT: ITy, H: Host + ?Sized>(
         .is_enabled_in(SpecId::TANGERINE)
     {
         // Take remaining gas and deduce l64 part of it.
-        gas_limit = context.host.gas_params().call_stipend_reduction(gas_limit);
+        gas_limit = context
+            .host
+            .call_gas_policy()
+            .max_forwarded_gas(context.host.gas_params(), gas_limit);
     }
     gas!(context.interpreter, gas_limit);
 
@@ -92,8 +92,8 @@ pub fn load_acc_and_calc_gas<H: Host + ?Sized>(
     let mut gas_limit = if interpreter.runtime_flag.spec_id().is_enabled_in(TANGERINE) {
         // On mainnet this will take return 63/64 of gas_limit.
         let reduced_gas_limit = host
-            .gas_params()
-            .call_stipend_reduction(interpreter.gas.remaining());
+            .call_gas_policy()
+            .max_forwarded_gas(host.gas_params(), interpreter.gas.remaining());
         min(reduced_gas_limit, stack_gas_limit)
     } else {
         stack_gas_limit
@@ -1,4 +1,3 @@
-use crate::interpreter::resize_memory;
 use crate::interpreter_types::{InterpreterTypes as ITy, MemoryTr, RuntimeFlag, StackTr};
 use crate::{InstructionContext as Ictx, InstructionExecResult as Result};
 use context_interface::Host;
@@ -11,13 +10,9 @@ use primitives::U256;
 pub fn mload<IT: ITy, H: Host + ?Sized>(context: Ictx<'_, H, IT>) -> Result {
     popn_top!([], top, context.interpreter);
     let offset = as_usize_or_fail!(context.interpreter, top);
-    resize_memory(
-        &mut context.interpreter.gas,
-        &mut context.interpreter.memory,
-        context.host.gas_params(),
-        offset,
-        32,
-    )?;
+    context
+        .interpreter
+        .resize_memory(context.host.gas_params(), offset, 32)?;
     *top =
         U256::try_from_be_slice(context.interpreter.memory.slice_len(offset, 32).as_ref()).unwrap();
     Ok(())
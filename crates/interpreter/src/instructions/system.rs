@@ -1,5 +1,5 @@
 use crate::{
-    interpreter::{resize_memory, Interpreter},
+    interpreter::Interpreter,
     interpreter_types::{
         InputsTr, InterpreterTypes as ITy, LegacyBytecode, MemoryTr, ReturnData, RuntimeFlag,
         StackTr,
@@ -26,13 +26,9 @@ pub fn keccak256<IT: ITy, H: Host + ?Sized>(context: Ictx<'_, H, IT>) -> Result
         KECCAK_EMPTY
     } else {
         let from = as_usize_or_fail!(context.interpreter, offset);
-        resize_memory(
-            &mut context.interpreter.gas,
-            &mut context.interpreter.memory,
-            context.host.gas_params(),
-            from,
-            len,
-        )?;
+        context
+            .interpreter
+            .resize_memory(context.host.gas_params(), from, len)?;
         primitives::keccak256(context.interpreter.memory.slice_len(from, len).as_ref())
     };
     *top = hash.into();
@@ -2,6 +2,7 @@
 
 /// Extended bytecode functionality.
 pub mod ext_bytecode;
+mod frame_extensions;
 mod input;
 mod return_data;
 mod runtime_flags;
@@ -10,6 +11,7 @@ mod stack;
 
 // re-exports
 pub use ext_bytecode::ExtBytecode;
+pub use frame_extensions::{FrameExtension, FrameExtensions};
 pub use input::InputsImpl;
 pub use return_data::ReturnDataImpl;
 pub use runtime_flags::RuntimeFlags;
@@ -22,6 +24,8 @@ use crate::{
     InstructionExecResult, InstructionResult, InstructionTable, InterpreterAction,
 };
 use bytecode::Bytecode;
+#[cfg(feature = "counters")]
+use context_interface::ExecutionCounters;
 use context_interface::{cfg::GasParams, host::LoadError};
 use primitives::{hardfork::SpecId, hints_util::cold_path, Bytes};
 
@@ -45,6 +49,13 @@ pub struct Interpreter<WIRE: InterpreterTypes = EthInterpreter> {
     pub runtime_flag: WIRE::RuntimeFlag,
     /// Extended functionality and customizations.
     pub extend: WIRE::Extend,
+    /// Type-erased data of the frame used by custom instructions and handlers.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub extensions: FrameExtensions,
+    /// Instructions and memory expansions counted since the counters were last taken.
+    #[cfg(feature = "counters")]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub counters: ExecutionCounters,
 }
 
 impl<EXT: Default> Interpreter<EthInterpreter<EXT>> {
@@ -108,6 +119,9 @@ impl<EXT: Default> Interpreter<EthInterpreter<EXT>> {
             input,
             runtime_flag: RuntimeFlags { is_static, spec_id },
             extend: Default::default(),
+            extensions: FrameExtensions::new(),
+            #[cfg(feature = "counters")]
+            counters: ExecutionCounters::new(),
         }
     }
 
@@ -133,6 +147,9 @@ impl<EXT: Default> Interpreter<EthInterpreter<EXT>> {
             input: input_ref,
             runtime_flag,
             extend,
+            extensions,
+            #[cfg(feature = "counters")]
+            counters,
         } = self;
         *bytecode_ref = bytecode;
         *gas = Gas::new_with_regular_gas_and_reservoir(gas_limit, reservoir_remaining_gas);
@@ -146,6 +163,11 @@ impl<EXT: Default> Interpreter<EthInterpreter<EXT>> {
         *input_ref = input;
         *runtime_flag = RuntimeFlags { spec_id, is_static };
         *extend = EXT::default();
+        extensions.clear();
+        #[cfg(feature = "counters")]
+        {
+            *counters = ExecutionCounters::new();
+        }
     }
 
     /// Sets the bytecode that is going to be executed
@@ -187,7 +209,21 @@ impl<IW: InterpreterTypes> Interpreter<IW> {
         offset: usize,
         len: usize,
     ) -> Result<(), InstructionResult> {
-        resize_memory(&mut self.gas, &mut self.memory, gas_params, offset, len)
+        #[cfg(feature = "counters")]
+        let words_num = self.gas.memory().words_num;
+        let res = resize_memory(&mut self.gas, &mut self.memory, gas_params, offset, len);
+        #[cfg(feature = "counters")]
+        if self.gas.memory().words_num != words_num {
+            self.counters.memory_expansions += 1;
+        }
+        res
+    }
+
+    /// Takes the instructions and memory expansions counted since the last call.
+    #[cfg(feature = "counters")]
+    #[inline]
+    pub fn take_counters(&mut self) -> ExecutionCounters {
+        core::mem::take(&mut self.counters)
     }
 
     /// Takes the next action from the control and returns it.
@@ -301,6 +337,11 @@ impl<IW: InterpreterTypes> Interpreter<IW> {
         // Get current opcode.
         let opcode = self.bytecode.opcode();
 
+        #[cfg(feature = "counters")]
+        {
+            self.counters.instructions += 1;
+        }
+
         // SAFETY: In analysis we are doing padding of bytecode so that we are sure that last
         // byte instruction is STOP so we are safe to just increment program_counter bcs on last instruction
         // it will do noop and just stop execution of this contract
@@ -321,6 +362,10 @@ impl<IW: InterpreterTypes> Interpreter<IW> {
     }
 
     /// Executes the interpreter until it returns or stops.
+    ///
+    /// This loop has no inspector hooks, it is used whenever the transaction is not inspected so
+    /// execution without an inspector doesn't pay for hook checks. Inspection runs its own loop
+    /// around [`Interpreter::step`].
     #[inline]
     pub fn run_plain<H: Host + ?Sized>(
         &mut self,
@@ -334,8 +379,16 @@ impl<IW: InterpreterTypes> Interpreter<IW> {
                 break e;
             }
         };
+        self.take_step_action(e)
+    }
+
+    /// Takes the action of the step that stopped the execution.
+    ///
+    /// If the step did not set an action the interpreter is halted with `result`.
+    #[inline]
+    pub fn take_step_action(&mut self, result: InstructionResult) -> InterpreterAction {
         if self.bytecode.action().is_none() {
-            self.halt(e);
+            self.halt(result);
         }
         debug_assert!(self.bytecode.is_end());
         self.take_next_action()
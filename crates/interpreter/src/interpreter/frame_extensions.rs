@@ -0,0 +1,151 @@
+use core::{any::Any, fmt};
+use std::{boxed::Box, vec::Vec};
+
+/// Value that can be stored in [`FrameExtensions`].
+///
+/// Implemented for every `Clone + Debug + Send + Sync` type.
+pub trait FrameExtension: Any + fmt::Debug + Send + Sync {
+    /// Clones the value into a new box.
+    fn clone_box(&self) -> Box<dyn FrameExtension>;
+}
+
+impl<T: Any + Clone + fmt::Debug + Send + Sync> FrameExtension for T {
+    fn clone_box(&self) -> Box<dyn FrameExtension> {
+        Box::new(self.clone())
+    }
+}
+
+/// Type-erased data of a single call frame, at most one value per type.
+///
+/// Custom instructions and handlers use it to carry chain-specific data of the frame, e.g.
+/// metering counters or capability tokens, without custom
+/// [`InterpreterTypes`][crate::InterpreterTypes].
+///
+/// Every frame starts with empty extensions and they are cleared when the interpreter is reused
+/// for the next frame, so the data does not outlive the frame, whether it returned or reverted.
+/// Data that has to reach the parent frame has to be copied by a custom handler when the frame
+/// returns.
+#[derive(Default)]
+pub struct FrameExtensions {
+    entries: Vec<Box<dyn FrameExtension>>,
+}
+
+impl FrameExtensions {
+    /// Creates empty extensions, it does not allocate.
+    pub const fn new() -> Self {
+        Self {
+            entries: Vec::new(),
+        }
+    }
+
+    /// Inserts the value, returning the previous value of the same type.
+    pub fn insert<T: FrameExtension>(&mut self, value: T) -> Option<T> {
+        let previous = self.remove::<T>();
+        self.entries.push(Box::new(value));
+        previous
+    }
+
+    /// Returns the value of the type.
+    pub fn get<T: FrameExtension>(&self) -> Option<&T> {
+        self.entries.iter().find_map(|entry| {
+            let entry: &dyn Any = &**entry;
+            entry.downcast_ref()
+        })
+    }
+
+    /// Returns the mutable value of the type.
+    pub fn get_mut<T: FrameExtension>(&mut self) -> Option<&mut T> {
+        self.entries.iter_mut().find_map(|entry| {
+            let entry: &mut dyn Any = &mut **entry;
+            entry.downcast_mut()
+        })
+    }
+
+    /// Returns the mutable value of the type, inserting the value returned by `f` if there is
+    /// none.
+    pub fn get_or_insert_with<T: FrameExtension>(&mut self, f: impl FnOnce() -> T) -> &mut T {
+        if self.get::<T>().is_none() {
+            self.entries.push(Box::new(f()));
+        }
+        self.get_mut().expect("value was inserted")
+    }
+
+    /// Removes and returns the value of the type.
+    pub fn remove<T: FrameExtension>(&mut self) -> Option<T> {
+        let index = self.entries.iter().position(|entry| {
+            let entry: &dyn Any = &**entry;
+            entry.is::<T>()
+        })?;
+        let entry: Box<dyn Any> = self.entries.swap_remove(index);
+        entry.downcast().ok().map(|value| *value)
+    }
+
+    /// Returns `true` if there is a value of the type.
+    pub fn contains<T: FrameExtension>(&self) -> bool {
+        self.get::<T>().is_some()
+    }
+
+    /// Returns the number of values.
+    pub const fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if there are no values.
+    pub const fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Removes all values, keeping the allocation.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+}
+
+impl Clone for FrameExtensions {
+    fn clone(&self) -> Self {
+        Self {
+            entries: self.entries.iter().map(|entry| entry.clone_box()).collect(),
+        }
+    }
+}
+
+impl fmt::Debug for FrameExtensions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(&self.entries).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Clone, Debug, PartialEq)]
+    struct Meter(u64);
+
+    #[derive(Clone, Debug, PartialEq)]
+    struct Token(&'static str);
+
+    #[test]
+    fn typed_values() {
+        let mut extensions = FrameExtensions::new();
+        assert!(extensions.is_empty());
+        assert_eq!(extensions.insert(Meter(1)), None);
+        assert_eq!(extensions.insert(Token("admin")), None);
+        assert_eq!(extensions.insert(Meter(2)), Some(Meter(1)));
+        assert_eq!(extensions.len(), 2);
+
+        extensions.get_mut::<Meter>().unwrap().0 += 1;
+        assert_eq!(extensions.get::<Meter>(), Some(&Meter(3)));
+        extensions.get_or_insert_with(|| Meter(0)).0 += 1;
+        assert_eq!(extensions.get::<Meter>(), Some(&Meter(4)));
+
+        let cloned = extensions.clone();
+        assert_eq!(extensions.remove::<Token>(), Some(Token("admin")));
+        assert!(!extensions.contains::<Token>());
+        assert_eq!(cloned.get::<Token>(), Some(&Token("admin")));
+
+        extensions.clear();
+        assert!(extensions.get::<Meter>().is_none());
+    }
+}
@@ -1,4 +1,4 @@
-use context_interface::CreateScheme;
+use context_interface::{CreateAddressFn, CreateScheme};
 use core::cell::OnceCell;
 use primitives::{keccak256, Address, Bytes, B256, U256};
 
@@ -70,6 +70,20 @@ impl CreateInputs {
         })
     }
 
+    /// Returns the address that this create call will create, derived by `create_address_fn`
+    /// if it is set and returns an address.
+    ///
+    /// Only the standard address is cached, see [`CreateInputs::created_address`].
+    pub fn created_address_with(
+        &self,
+        nonce: u64,
+        create_address_fn: Option<CreateAddressFn>,
+    ) -> Address {
+        create_address_fn
+            .and_then(|f| f(self.caller, self.scheme, nonce, &self.init_code))
+            .unwrap_or_else(|| self.created_address(nonce))
+    }
+
     /// Returns the keccak256 hash of the init code.
     ///
     /// The result is cached so that `created_address()` and frame initialization
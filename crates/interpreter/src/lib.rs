@@ -24,6 +24,8 @@ pub mod interpreter;
 pub mod interpreter_action;
 /// Type traits and definitions for interpreter customization.
 pub mod interpreter_types;
+/// In-memory host for unit-testing instructions.
+pub mod mock_host;
 
 pub use bytecode;
 pub use context_interface;
@@ -48,3 +50,4 @@ pub use interpreter_action::{
     FrameInput, InterpreterAction,
 };
 pub use interpreter_types::InterpreterTypes;
+pub use mock_host::MockHost;
@@ -0,0 +1,481 @@
+//! Configurable [`Host`] for unit-testing instructions.
+//!
+//! [`MockHost`] keeps accounts, storage and block hashes in memory and records the logs,
+//! selfdestructs and calls of the executed code, so custom instructions can be tested without
+//! building a full EVM with a database.
+use crate::{
+    instructions::{GasTable, InstructionTable},
+    interpreter::EthInterpreter,
+    interpreter_types::ReturnData,
+    CallInputs, CallOutcome, FrameInput, Gas, InstructionResult, Interpreter, InterpreterAction,
+    InterpreterResult,
+};
+use context_interface::{
+    cfg::GasParams,
+    context::{SStoreResult, SelfDestructResult, StateLoad},
+    host::LoadError,
+    journaled_state::AccountInfoLoad,
+    Host,
+};
+use core::cmp::min;
+use primitives::{
+    hardfork::SpecId, Address, AddressMap, AddressSet, Bytes, HashMap, HashSet, Log, StorageKey,
+    StorageKeyMap, StorageValue, B256, U256,
+};
+use state::AccountInfo;
+use std::{borrow::Cow, vec::Vec};
+
+/// In-memory [`Host`] with scriptable state for unit-testing instructions.
+///
+/// Accounts and storage slots are cold on first access unless warmed with
+/// [`MockHost::warm_account`] or [`MockHost::warm_slot`]. Calls of the executed code are answered
+/// with the results set by [`MockHost::with_call_result`] when run with [`MockHost::run`].
+#[derive(Clone, Debug, Default)]
+pub struct MockHost {
+    /// Gas params of the spec.
+    pub gas_params: GasParams,
+    /// Whether state gas (EIP-8037) is enabled.
+    pub amsterdam_eip8037_enabled: bool,
+    /// Block basefee.
+    pub basefee: U256,
+    /// Block blob gasprice.
+    pub blob_gasprice: U256,
+    /// Block gas limit.
+    pub gas_limit: U256,
+    /// Block difficulty.
+    pub difficulty: U256,
+    /// Block prevrandao.
+    pub prevrandao: Option<U256>,
+    /// Block number.
+    pub block_number: U256,
+    /// Block timestamp.
+    pub timestamp: U256,
+    /// Block beneficiary.
+    pub beneficiary: Address,
+    /// Block slot number.
+    pub slot_num: U256,
+    /// Chain id.
+    pub chain_id: U256,
+    /// Transaction effective gas price.
+    pub effective_gas_price: U256,
+    /// Transaction caller.
+    pub caller: Address,
+    /// Transaction blob hashes.
+    pub blob_hashes: Vec<U256>,
+    /// Max initcode size.
+    pub max_initcode_size: usize,
+    /// Block hashes by number, missing numbers return `None`.
+    pub block_hashes: HashMap<u64, B256>,
+    /// Accounts, missing accounts are empty.
+    pub accounts: AddressMap<AccountInfo>,
+    /// Present storage values, missing slots are zero.
+    pub storage: AddressMap<StorageKeyMap<StorageValue>>,
+    /// Storage values before the first write.
+    pub original_storage: AddressMap<StorageKeyMap<StorageValue>>,
+    /// Transient storage.
+    pub transient_storage: AddressMap<StorageKeyMap<StorageValue>>,
+    /// Warm accounts.
+    pub warm_accounts: AddressSet,
+    /// Warm storage slots.
+    pub warm_slots: HashSet<(Address, StorageKey)>,
+    /// Results of calls by target address, calls to other addresses succeed with empty output.
+    pub call_results: AddressMap<InterpreterResult>,
+    /// Emitted logs.
+    pub logs: Vec<Log>,
+    /// Selfdestructed accounts and their targets.
+    pub selfdestructs: Vec<(Address, Address)>,
+    /// Inputs of the calls made by [`MockHost::run`].
+    pub calls: Vec<CallInputs>,
+}
+
+impl MockHost {
+    /// Creates a new mock host with the gas params of the given spec.
+    pub fn new(spec: SpecId) -> Self {
+        Self {
+            gas_params: GasParams::new_spec(spec),
+            ..Default::default()
+        }
+    }
+
+    /// Sets the account info.
+    pub fn with_account(mut self, address: Address, info: AccountInfo) -> Self {
+        self.accounts.insert(address, info);
+        self
+    }
+
+    /// Sets the balance of the account.
+    pub fn with_balance(mut self, address: Address, balance: U256) -> Self {
+        self.accounts.entry(address).or_default().balance = balance;
+        self
+    }
+
+    /// Sets the storage value of the account.
+    pub fn with_storage(mut self, address: Address, key: StorageKey, value: StorageValue) -> Self {
+        self.storage.entry(address).or_default().insert(key, value);
+        self
+    }
+
+    /// Sets the hash of the block.
+    pub fn with_block_hash(mut self, number: u64, hash: B256) -> Self {
+        self.block_hashes.insert(number, hash);
+        self
+    }
+
+    /// Sets the result of calls to `address`.
+    pub fn with_call_result(mut self, address: Address, result: InterpreterResult) -> Self {
+        self.call_results.insert(address, result);
+        self
+    }
+
+    /// Marks the account as warm.
+    pub fn warm_account(&mut self, address: Address) {
+        self.warm_accounts.insert(address);
+    }
+
+    /// Marks the storage slot as warm.
+    pub fn warm_slot(&mut self, address: Address, key: StorageKey) {
+        self.warm_slots.insert((address, key));
+    }
+
+    /// Returns the present storage value.
+    pub fn storage_value(&self, address: Address, key: StorageKey) -> StorageValue {
+        self.storage
+            .get(&address)
+            .and_then(|storage| storage.get(&key))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Warms the account, returns `true` if it was cold.
+    fn touch_account(&mut self, address: Address) -> bool {
+        self.warm_accounts.insert(address)
+    }
+
+    /// Warms the storage slot, returns `true` if it was cold.
+    fn touch_slot(&mut self, address: Address, key: StorageKey) -> bool {
+        self.warm_slots.insert((address, key))
+    }
+
+    /// Records the call and returns its scripted outcome.
+    ///
+    /// Calls without a scripted result succeed with empty output and return all gas.
+    pub fn call(&mut self, inputs: CallInputs) -> CallOutcome {
+        let result = self
+            .call_results
+            .get(&inputs.target_address)
+            .cloned()
+            .unwrap_or_else(|| {
+                InterpreterResult::new(
+                    InstructionResult::Stop,
+                    Bytes::new(),
+                    Gas::new(inputs.gas_limit),
+                )
+            });
+        let outcome = CallOutcome::new(result, inputs.return_memory_offset.clone());
+        self.calls.push(inputs);
+        outcome
+    }
+
+    /// Runs the interpreter, answering its calls with [`MockHost::call`].
+    ///
+    /// Returns the first action that is not a call, i.e. the result of the execution or a
+    /// create.
+    pub fn run(
+        &mut self,
+        interpreter: &mut Interpreter<EthInterpreter>,
+        instruction_table: &InstructionTable<EthInterpreter, Self>,
+        gas_table: &GasTable,
+    ) -> InterpreterAction {
+        loop {
+            match interpreter.run_plain(instruction_table, gas_table, self) {
+                InterpreterAction::NewFrame(FrameInput::Call(inputs)) => {
+                    let outcome = self.call(*inputs);
+                    insert_call_outcome(interpreter, outcome);
+                }
+                action => return action,
+            }
+        }
+    }
+}
+
+/// Returns the outcome of a call to the calling interpreter.
+///
+/// Simplified version of the handler logic, state gas and refunds are not settled.
+fn insert_call_outcome(interpreter: &mut Interpreter<EthInterpreter>, outcome: CallOutcome) {
+    let result = *outcome.instruction_result();
+    let target_len = min(outcome.memory_length(), outcome.result.output.len());
+    let memory_start = outcome.memory_start();
+    interpreter.return_data.set_buffer(outcome.result.output);
+
+    let _ = interpreter.stack.push(U256::from(result.is_ok()));
+    if result.is_ok_or_revert() {
+        interpreter.memory.set(
+            memory_start,
+            &interpreter.return_data.buffer()[..target_len],
+        );
+        interpreter.gas.erase_cost(outcome.result.gas.remaining());
+    }
+}
+
+impl Host for MockHost {
+    fn basefee(&self) -> U256 {
+        self.basefee
+    }
+
+    fn blob_gasprice(&self) -> U256 {
+        self.blob_gasprice
+    }
+
+    fn gas_limit(&self) -> U256 {
+        self.gas_limit
+    }
+
+    fn difficulty(&self) -> U256 {
+        self.difficulty
+    }
+
+    fn prevrandao(&self) -> Option<U256> {
+        self.prevrandao
+    }
+
+    fn block_number(&self) -> U256 {
+        self.block_number
+    }
+
+    fn timestamp(&self) -> U256 {
+        self.timestamp
+    }
+
+    fn beneficiary(&self) -> Address {
+        self.beneficiary
+    }
+
+    fn slot_num(&self) -> U256 {
+        self.slot_num
+    }
+
+    fn chain_id(&self) -> U256 {
+        self.chain_id
+    }
+
+    fn effective_gas_price(&self) -> U256 {
+        self.effective_gas_price
+    }
+
+    fn caller(&self) -> Address {
+        self.caller
+    }
+
+    fn blob_hash(&self, number: usize) -> Option<U256> {
+        self.blob_hashes.get(number).copied()
+    }
+
+    fn max_initcode_size(&self) -> usize {
+        self.max_initcode_size
+    }
+
+    fn gas_params(&self) -> &GasParams {
+        &self.gas_params
+    }
+
+    fn is_amsterdam_eip8037_enabled(&self) -> bool {
+        self.amsterdam_eip8037_enabled
+    }
+
+    fn block_hash(&mut self, number: u64) -> Option<B256> {
+        self.block_hashes.get(&number).copied()
+    }
+
+    fn selfdestruct(
+        &mut self,
+        address: Address,
+        target: Address,
+        skip_cold_load: bool,
+    ) -> Result<StateLoad<SelfDestructResult>, LoadError> {
+        if skip_cold_load && !self.warm_accounts.contains(&target) {
+            return Err(LoadError::ColdLoadSkipped);
+        }
+        let is_cold = self.touch_account(target);
+        let previously_destroyed = self.selfdestructs.iter().any(|(a, _)| *a == address);
+        let target_exists = self
+            .accounts
+            .get(&target)
+            .is_some_and(|info| !info.is_empty());
+        let balance = self
+            .accounts
+            .get_mut(&address)
+            .map(|info| core::mem::take(&mut info.balance))
+            .unwrap_or_default();
+        if address != target {
+            let target = self.accounts.entry(target).or_default();
+            target.balance = target.balance.saturating_add(balance);
+        }
+        self.selfdestructs.push((address, target));
+        Ok(StateLoad::new(
+            SelfDestructResult {
+                had_value: !balance.is_zero(),
+                target_exists,
+                previously_destroyed,
+            },
+            is_cold,
+        ))
+    }
+
+    fn log(&mut self, log: Log) {
+        self.logs.push(log);
+    }
+
+    fn sstore_skip_cold_load(
+        &mut self,
+        address: Address,
+        key: StorageKey,
+        value: StorageValue,
+        skip_cold_load: bool,
+    ) -> Result<StateLoad<SStoreResult>, LoadError> {
+        if skip_cold_load && !self.warm_slots.contains(&(address, key)) {
+            return Err(LoadError::ColdLoadSkipped);
+        }
+        let is_cold = self.touch_slot(address, key);
+        let present_value = self.storage_value(address, key);
+        let original_value = *self
+            .original_storage
+            .entry(address)
+            .or_default()
+            .entry(key)
+            .or_insert(present_value);
+        self.storage.entry(address).or_default().insert(key, value);
+        Ok(StateLoad::new(
+            SStoreResult {
+                original_value,
+                present_value,
+                new_value: value,
+            },
+            is_cold,
+        ))
+    }
+
+    fn sload_skip_cold_load(
+        &mut self,
+        address: Address,
+        key: StorageKey,
+        skip_cold_load: bool,
+    ) -> Result<StateLoad<StorageValue>, LoadError> {
+        if skip_cold_load && !self.warm_slots.contains(&(address, key)) {
+            return Err(LoadError::ColdLoadSkipped);
+        }
+        let is_cold = self.touch_slot(address, key);
+        Ok(StateLoad::new(self.storage_value(address, key), is_cold))
+    }
+
+    fn tstore(&mut self, address: Address, key: StorageKey, value: StorageValue) {
+        self.transient_storage
+            .entry(address)
+            .or_default()
+            .insert(key, value);
+    }
+
+    fn tload(&mut self, address: Address, key: StorageKey) -> StorageValue {
+        self.transient_storage
+            .get(&address)
+            .and_then(|storage| storage.get(&key))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    fn load_account_info_skip_cold_load(
+        &mut self,
+        address: Address,
+        _load_code: bool,
+        skip_cold_load: bool,
+    ) -> Result<AccountInfoLoad<'_>, LoadError> {
+        if skip_cold_load && !self.warm_accounts.contains(&address) {
+            return Err(LoadError::ColdLoadSkipped);
+        }
+        let is_cold = self.touch_account(address);
+        Ok(match self.accounts.get(&address) {
+            Some(info) => AccountInfoLoad::new(info, is_cold, info.is_empty()),
+            None => AccountInfoLoad {
+                account: Cow::Owned(AccountInfo::default()),
+                is_cold,
+                is_empty: true,
+            },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        instructions::{gas_table, instruction_table},
+        interpreter::{ExtBytecode, InputsImpl, SharedMemory},
+    };
+    use bytecode::{opcode::*, Bytecode};
+
+    fn interpreter(code: &[u8]) -> Interpreter<EthInterpreter> {
+        Interpreter::<EthInterpreter>::new(
+            SharedMemory::new(),
+            ExtBytecode::new(Bytecode::new_raw(Bytes::copy_from_slice(code))),
+            InputsImpl::default(),
+            false,
+            SpecId::PRAGUE,
+            1_000_000,
+        )
+    }
+
+    #[test]
+    fn storage_and_block_hash() {
+        let address = InputsImpl::default().target_address;
+        let mut host = MockHost::new(SpecId::PRAGUE)
+            .with_storage(address, U256::from(1), U256::from(7))
+            .with_block_hash(5, B256::repeat_byte(0xaa));
+        host.block_number = U256::from(10);
+
+        // SSTORE(2, SLOAD(1)), then BLOCKHASH(5).
+        let mut interpreter =
+            interpreter(&[PUSH1, 1, SLOAD, PUSH1, 2, SSTORE, PUSH1, 5, BLOCKHASH, STOP]);
+        let action = host.run(&mut interpreter, &instruction_table(), &gas_table());
+
+        assert!(action.instruction_result().unwrap().is_ok());
+        assert_eq!(host.storage_value(address, U256::from(2)), U256::from(7));
+        assert_eq!(
+            interpreter.stack.data()[0],
+            U256::from_be_bytes(B256::repeat_byte(0xaa).0)
+        );
+        // First access of both slots was cold.
+        assert!(host.warm_slots.contains(&(address, U256::from(1))));
+        assert!(host.warm_slots.contains(&(address, U256::from(2))));
+        // Three PUSH1, cold SLOAD, cold SSTORE of a new value and BLOCKHASH.
+        assert_eq!(
+            interpreter.gas.spent(),
+            3 * 3 + 2_100 + (20_000 + 2_100) + 20
+        );
+    }
+
+    #[test]
+    fn scripted_call_result() {
+        let target = Address::with_last_byte(0xca);
+        let mut host = MockHost::new(SpecId::PRAGUE).with_call_result(
+            target,
+            InterpreterResult::new(
+                InstructionResult::Revert,
+                Bytes::from_static(&[0x42]),
+                Gas::new(0),
+            ),
+        );
+
+        // CALL(gas, target, 0, 0, 0, 0, 1), then MLOAD(0).
+        let mut interpreter = interpreter(&[
+            PUSH1, 1, PUSH1, 0, PUSH1, 0, PUSH1, 0, PUSH1, 0, PUSH1, 0xca, GAS, CALL, PUSH1, 0,
+            MLOAD, STOP,
+        ]);
+        let action = host.run(&mut interpreter, &instruction_table(), &gas_table());
+
+        assert!(action.instruction_result().unwrap().is_ok());
+        assert_eq!(host.calls.len(), 1);
+        assert_eq!(host.calls[0].target_address, target);
+        // Call failed and the revert data was copied to memory.
+        assert_eq!(interpreter.stack.data()[0], U256::ZERO);
+        assert_eq!(interpreter.stack.data()[1], U256::from(0x42) << 248);
+    }
+}
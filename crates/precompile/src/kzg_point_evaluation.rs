@@ -5,6 +5,7 @@ use crate::{
     PrecompileHalt, PrecompileId,
 };
 pub mod arkworks;
+pub mod sidecar;
 
 #[cfg(feature = "blst")]
 pub mod blst;
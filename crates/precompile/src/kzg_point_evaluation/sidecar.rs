@@ -0,0 +1,164 @@
+//! Validation of [EIP-4844](https://eips.ethereum.org/EIPS/eip-4844) blob sidecars.
+//!
+//! Checks that the blobs, commitments and proofs gossiped alongside a blob transaction
+//! match its versioned hashes. The batch verification of the blob proofs requires the
+//! `c-kzg` feature.
+use super::kzg_to_versioned_hash;
+use context_interface::transaction::{BlobSidecar, BlobSidecarError};
+use primitives::{eip4844::BYTES_PER_BLOB, FixedBytes, B256};
+
+/// Checks that every versioned hash is derived from the commitment at the same index.
+pub fn validate_versioned_hashes(
+    versioned_hashes: &[B256],
+    commitments: &[FixedBytes<48>],
+) -> Result<(), BlobSidecarError> {
+    if versioned_hashes.len() != commitments.len() {
+        return Err(BlobSidecarError::LengthMismatch {
+            versioned_hashes: versioned_hashes.len(),
+            blobs: commitments.len(),
+            commitments: commitments.len(),
+            proofs: commitments.len(),
+        });
+    }
+    for (index, (hash, commitment)) in versioned_hashes.iter().zip(commitments).enumerate() {
+        if kzg_to_versioned_hash(commitment.as_slice()) != hash.0 {
+            return Err(BlobSidecarError::VersionedHashMismatch { index });
+        }
+    }
+    Ok(())
+}
+
+/// Validates the sidecar against the versioned hashes of the transaction.
+///
+/// Checks the number of blobs, commitments and proofs, the length of the blobs, that the
+/// versioned hashes match the commitments, and verifies all blob proofs in a single batch.
+///
+/// Returns [`BlobSidecarError::Unsupported`] if the crate is compiled without `c-kzg`, after
+/// the versioned hashes were checked.
+pub fn validate_blob_sidecar(
+    versioned_hashes: &[B256],
+    sidecar: &BlobSidecar,
+) -> Result<(), BlobSidecarError> {
+    let len = versioned_hashes.len();
+    if sidecar.blobs.len() != len || sidecar.commitments.len() != len || sidecar.proofs.len() != len
+    {
+        return Err(BlobSidecarError::LengthMismatch {
+            versioned_hashes: len,
+            blobs: sidecar.blobs.len(),
+            commitments: sidecar.commitments.len(),
+            proofs: sidecar.proofs.len(),
+        });
+    }
+    if let Some(index) = sidecar
+        .blobs
+        .iter()
+        .position(|blob| blob.len() != BYTES_PER_BLOB)
+    {
+        return Err(BlobSidecarError::InvalidBlobLength { index });
+    }
+    validate_versioned_hashes(versioned_hashes, &sidecar.commitments)?;
+
+    if verify_blob_kzg_proof_batch(sidecar)? {
+        Ok(())
+    } else {
+        Err(BlobSidecarError::InvalidProof)
+    }
+}
+
+/// Verifies the proofs of all blobs of the sidecar, the lengths must have been checked.
+fn verify_blob_kzg_proof_batch(sidecar: &BlobSidecar) -> Result<bool, BlobSidecarError> {
+    cfg_if::cfg_if! {
+        if #[cfg(feature = "c-kzg")] {
+            use c_kzg::{Blob, Bytes48};
+            use std::vec::Vec;
+
+            let blobs = sidecar
+                .blobs
+                .iter()
+                .map(|blob| Blob::from_bytes(blob))
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|_| BlobSidecarError::Kzg)?;
+            let to_bytes48 = |bytes: &[FixedBytes<48>]| {
+                bytes
+                    .iter()
+                    .map(|bytes| Bytes48::from_bytes(bytes.as_slice()))
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(|_| BlobSidecarError::Kzg)
+            };
+            let commitments = to_bytes48(&sidecar.commitments)?;
+            let proofs = to_bytes48(&sidecar.proofs)?;
+
+            c_kzg::ethereum_kzg_settings(8)
+                .verify_blob_kzg_proof_batch(&blobs, &commitments, &proofs)
+                .map_err(|_| BlobSidecarError::Kzg)
+        } else {
+            let _ = sidecar;
+            Err(BlobSidecarError::Unsupported)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use primitives::{hex, Bytes};
+    use std::vec;
+
+    /// Commitment and proof of the zero blob, the point at infinity.
+    const INFINITY: [u8; 48] = hex!("c00000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000");
+
+    fn zero_blob_sidecar() -> (B256, BlobSidecar) {
+        let hash = B256::from(kzg_to_versioned_hash(&INFINITY));
+        let sidecar = BlobSidecar::new(
+            vec![Bytes::from(vec![0; BYTES_PER_BLOB])],
+            vec![INFINITY.into()],
+            vec![INFINITY.into()],
+        );
+        (hash, sidecar)
+    }
+
+    #[test]
+    fn rejects_mismatched_sidecar() {
+        let (hash, sidecar) = zero_blob_sidecar();
+        assert_eq!(
+            validate_blob_sidecar(&[hash, hash], &sidecar),
+            Err(BlobSidecarError::LengthMismatch {
+                versioned_hashes: 2,
+                blobs: 1,
+                commitments: 1,
+                proofs: 1,
+            })
+        );
+
+        let mut short = sidecar.clone();
+        short.blobs[0] = Bytes::from_static(&[0; 32]);
+        assert_eq!(
+            validate_blob_sidecar(&[hash], &short),
+            Err(BlobSidecarError::InvalidBlobLength { index: 0 })
+        );
+
+        let mut wrong_hash = hash;
+        wrong_hash.0[31] ^= 1;
+        assert_eq!(
+            validate_blob_sidecar(&[wrong_hash], &sidecar),
+            Err(BlobSidecarError::VersionedHashMismatch { index: 0 })
+        );
+    }
+
+    #[cfg(feature = "c-kzg")]
+    #[test]
+    fn verifies_blob_proofs() {
+        let (hash, sidecar) = zero_blob_sidecar();
+        assert_eq!(validate_blob_sidecar(&[hash], &sidecar), Ok(()));
+
+        // Proof of a non zero blob can't be the point at infinity.
+        let mut invalid = sidecar;
+        let mut blob = vec![0; BYTES_PER_BLOB];
+        blob[31] = 1;
+        invalid.blobs[0] = blob.into();
+        assert_eq!(
+            validate_blob_sidecar(&[hash], &invalid),
+            Err(BlobSidecarError::InvalidProof)
+        );
+    }
+}
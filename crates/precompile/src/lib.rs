@@ -26,6 +26,7 @@ pub mod identity;
 pub mod interface;
 pub mod kzg_point_evaluation;
 pub mod modexp;
+pub mod remap;
 pub mod secp256k1;
 pub mod secp256r1;
 pub mod utilities;
@@ -34,6 +35,7 @@ pub use primitives;
 
 pub use id::PrecompileId;
 pub use interface::*;
+pub use remap::{PrecompileChange, PrecompileTable};
 
 use core::fmt::{self, Debug};
 
@@ -233,6 +235,15 @@ impl Precompiles {
         }
     }
 
+    /// Removes the precompile at the given address and returns it.
+    pub fn remove(&mut self, address: &Address) -> Option<Precompile> {
+        if let Some(short_idx) = short_address(address) {
+            self.optimized_access[short_idx] = None;
+        }
+        self.addresses.remove(address);
+        self.inner.remove(address)
+    }
+
     /// Returns complement of `other` in `self`.
     ///
     /// Two entries are considered equal if the precompile addresses are equal.
@@ -379,6 +390,22 @@ impl Precompile {
         &self.address
     }
 
+    /// Returns the precompile moved to the given address.
+    #[inline]
+    pub const fn with_address(mut self, address: Address) -> Self {
+        self.address = address;
+        self
+    }
+
+    /// Returns `true` if both precompiles are implemented by the same function.
+    ///
+    /// Same as with any function pointer comparison, the same function can have different
+    /// addresses in different codegen units.
+    #[inline]
+    pub fn is_same(&self, other: &Self) -> bool {
+        core::ptr::fn_addr_eq(self.fn_, other.fn_)
+    }
+
     /// Executes the precompile.
     ///
     /// Returns `Ok(PrecompileOutput)` on success or non-fatal halt,
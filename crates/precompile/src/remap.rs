@@ -0,0 +1,122 @@
+//! Declarative changes to a set of precompiles, see [`PrecompileTable`].
+use crate::{Precompile, Precompiles};
+use primitives::Address;
+use std::vec::Vec;
+
+/// Single change applied by a [`PrecompileTable`].
+#[derive(Clone, Debug)]
+pub enum PrecompileChange {
+    /// Removes the precompile at the address.
+    Remove(Address),
+    /// Moves the precompile to another address.
+    Move {
+        /// Current address of the precompile.
+        from: Address,
+        /// New address of the precompile.
+        to: Address,
+    },
+    /// Adds the precompile, replacing the precompile at the same address.
+    Add(Precompile),
+}
+
+/// Table of chain-specific changes to the precompiles of a spec.
+///
+/// Chains that relocate, disable or add precompiles can describe the changes declaratively
+/// instead of implementing a custom precompile provider. Changes are applied in order, so a
+/// precompile can be moved away and its old address reused by an added precompile.
+#[derive(Clone, Debug, Default)]
+pub struct PrecompileTable {
+    changes: Vec<PrecompileChange>,
+}
+
+impl PrecompileTable {
+    /// Creates an empty table.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a table from the changes.
+    pub fn from_changes(changes: impl IntoIterator<Item = PrecompileChange>) -> Self {
+        Self {
+            changes: changes.into_iter().collect(),
+        }
+    }
+
+    /// Removes the precompile at the address.
+    pub fn remove(mut self, address: Address) -> Self {
+        self.changes.push(PrecompileChange::Remove(address));
+        self
+    }
+
+    /// Moves the precompile at `from` to `to`.
+    pub fn relocate(mut self, from: Address, to: Address) -> Self {
+        self.changes.push(PrecompileChange::Move { from, to });
+        self
+    }
+
+    /// Adds the precompile.
+    pub fn add(mut self, precompile: Precompile) -> Self {
+        self.changes.push(PrecompileChange::Add(precompile));
+        self
+    }
+
+    /// Returns the changes of the table.
+    pub fn changes(&self) -> &[PrecompileChange] {
+        &self.changes
+    }
+
+    /// Returns `true` if the table has no changes.
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// Applies the changes to the precompiles and returns the result.
+    ///
+    /// Removing or moving an address without a precompile does nothing.
+    pub fn apply(&self, precompiles: &Precompiles) -> Precompiles {
+        let mut precompiles = precompiles.clone();
+        for change in &self.changes {
+            match change {
+                PrecompileChange::Remove(address) => {
+                    precompiles.remove(address);
+                }
+                PrecompileChange::Move { from, to } => {
+                    if let Some(precompile) = precompiles.remove(from) {
+                        precompiles.extend([precompile.with_address(*to)]);
+                    }
+                }
+                PrecompileChange::Add(precompile) => precompiles.extend([precompile.clone()]),
+            }
+        }
+        precompiles
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{hash, identity, secp256k1};
+
+    #[test]
+    fn applies_changes_in_order() {
+        let relocated = Address::with_last_byte(0x42);
+        let table = PrecompileTable::new()
+            .remove(*hash::RIPEMD160.address())
+            .relocate(*identity::FUN.address(), relocated)
+            .add(hash::SHA256.with_address(*identity::FUN.address()));
+        let precompiles = table.apply(Precompiles::homestead());
+
+        assert_eq!(precompiles.len(), 3);
+        assert!(!precompiles.contains(hash::RIPEMD160.address()));
+        assert!(precompiles.contains(secp256k1::ECRECOVER.address()));
+        assert_eq!(
+            precompiles.get(&relocated).unwrap().id(),
+            identity::FUN.id()
+        );
+        assert_eq!(
+            precompiles.get(identity::FUN.address()).unwrap().id(),
+            hash::SHA256.id()
+        );
+        assert!(precompiles.addresses_set().contains(&relocated));
+    }
+}
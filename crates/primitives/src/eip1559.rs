@@ -0,0 +1,130 @@
+//! EIP-1559: Fee market change for ETH 1.0 chain
+//!
+//! Introduces a per-block base fee that is adjusted up or down depending on how full the
+//! parent block was compared to its gas target.
+
+/// Default maximum base fee change denominator, the base fee can change by at most 12.5%
+/// between blocks.
+pub const DEFAULT_BASE_FEE_MAX_CHANGE_DENOMINATOR: u64 = 8;
+
+/// Default elasticity multiplier, the gas target is half of the block gas limit.
+pub const DEFAULT_ELASTICITY_MULTIPLIER: u64 = 2;
+
+/// Base fee of the first EIP-1559 block.
+pub const INITIAL_BASE_FEE: u64 = 1_000_000_000;
+
+/// Parameters of the base fee calculation.
+///
+/// Several L2s tune these values, Ethereum uses [`BaseFeeParams::ETHEREUM`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BaseFeeParams {
+    /// Maximum change of the base fee between blocks is `1 / max_change_denominator`.
+    pub max_change_denominator: u64,
+    /// Gas target of a block is `gas_limit / elasticity_multiplier`.
+    pub elasticity_multiplier: u64,
+}
+
+impl Default for BaseFeeParams {
+    fn default() -> Self {
+        Self::ETHEREUM
+    }
+}
+
+impl BaseFeeParams {
+    /// Ethereum mainnet parameters.
+    pub const ETHEREUM: Self = Self::new(
+        DEFAULT_BASE_FEE_MAX_CHANGE_DENOMINATOR,
+        DEFAULT_ELASTICITY_MULTIPLIER,
+    );
+
+    /// OP Stack parameters since the Canyon hardfork.
+    pub const OPTIMISM_CANYON: Self = Self::new(250, 6);
+
+    /// Creates new base fee parameters.
+    pub const fn new(max_change_denominator: u64, elasticity_multiplier: u64) -> Self {
+        Self {
+            max_change_denominator,
+            elasticity_multiplier,
+        }
+    }
+
+    /// Calculates the base fee of the next block, see [`calc_next_block_base_fee`].
+    #[inline]
+    pub const fn next_base_fee(&self, gas_used: u64, gas_limit: u64, base_fee: u64) -> u64 {
+        calc_next_block_base_fee(gas_used, gas_limit, base_fee, *self)
+    }
+}
+
+/// Calculates the base fee of the next block from the gas used, gas limit and base fee of
+/// the parent block.
+///
+/// The base fee increases if the parent block used more gas than its target, decreases if it
+/// used less and stays the same otherwise. An increase is at least `1`.
+///
+/// See also [the EIP-1559 specification](https://eips.ethereum.org/EIPS/eip-1559#specification).
+pub const fn calc_next_block_base_fee(
+    gas_used: u64,
+    gas_limit: u64,
+    base_fee: u64,
+    params: BaseFeeParams,
+) -> u64 {
+    let gas_target = gas_limit / params.elasticity_multiplier;
+    if gas_target == 0 || gas_used == gas_target {
+        return base_fee;
+    }
+
+    let base_fee_wide = base_fee as u128;
+    let denominator = gas_target as u128 * params.max_change_denominator as u128;
+    if gas_used > gas_target {
+        let delta = base_fee_wide * (gas_used - gas_target) as u128 / denominator;
+        let delta = if delta == 0 { 1 } else { delta };
+        let next = base_fee_wide + delta;
+        if next > u64::MAX as u128 {
+            u64::MAX
+        } else {
+            next as u64
+        }
+    } else {
+        let delta = base_fee_wide * (gas_target - gas_used) as u128 / denominator;
+        base_fee.saturating_sub(delta as u64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ethereum_base_fee() {
+        let params = BaseFeeParams::ETHEREUM;
+        let gas_limit = 20_000_000;
+        assert_eq!(
+            params.next_base_fee(10_000_000, gas_limit, INITIAL_BASE_FEE),
+            INITIAL_BASE_FEE
+        );
+        assert_eq!(
+            params.next_base_fee(gas_limit, gas_limit, INITIAL_BASE_FEE),
+            1_125_000_000
+        );
+        assert_eq!(
+            params.next_base_fee(0, gas_limit, INITIAL_BASE_FEE),
+            875_000_000
+        );
+        // Minimum increase is one wei.
+        assert_eq!(params.next_base_fee(10_000_001, gas_limit, 7), 8);
+    }
+
+    #[test]
+    fn custom_base_fee_params() {
+        let params = BaseFeeParams::new(50, 6);
+        assert_eq!(
+            params.next_base_fee(30_000_000, 30_000_000, INITIAL_BASE_FEE),
+            1_100_000_000
+        );
+        assert_eq!(
+            params.next_base_fee(0, 30_000_000, INITIAL_BASE_FEE),
+            980_000_000
+        );
+    }
+}
@@ -0,0 +1,11 @@
+//! Constants for [EIP-2935](https://eips.ethereum.org/EIPS/eip-2935): Serve historical block hashes from state.
+
+use alloy_primitives::{address, Address};
+
+/// Address of the history storage contract that keeps the block hashes.
+pub const HISTORY_STORAGE_ADDRESS: Address = address!("0x0000F90827F1C53a10cb7A02335B175320002935");
+
+/// Number of block hashes kept in the ring buffer of the history storage contract.
+///
+/// Hash of block `n` is stored in the slot `n % HISTORY_SERVE_WINDOW`.
+pub const HISTORY_SERVE_WINDOW: u64 = 8191;
@@ -2,12 +2,18 @@
 //!
 //! Constants for blob transaction support in Cancun and Prague hard forks.
 
+use crate::hardfork::SpecId;
+use std::vec::Vec;
+
 /// First version of the blob
 pub const VERSIONED_HASH_VERSION_KZG: u8 = 0x01;
 
 /// Gas consumption of a single data blob (== blob byte size)
 pub const GAS_PER_BLOB: u64 = 1 << 17;
 
+/// Size of a blob in bytes.
+pub const BYTES_PER_BLOB: usize = 131_072;
+
 /// Min blob gas price
 pub const MIN_BLOB_GASPRICE: u64 = 1;
 
@@ -42,3 +48,188 @@ pub const TARGET_BLOB_GAS_PER_BLOCK_PRAGUE: u64 =
 
 /// Controls the maximum rate of change for blob gas price. Hex 0x4c6964.
 pub const BLOB_BASE_FEE_UPDATE_FRACTION_PRAGUE: u64 = 5_007_716;
+
+/// Blob base fee reserve price of Osaka is `BLOB_BASE_COST * base_fee / GAS_PER_BLOB`,
+/// introduced in EIP-7918.
+pub const BLOB_BASE_COST: u64 = 1 << 13;
+
+/// Maximum number of blobs per transaction since Osaka, introduced in EIP-7594.
+pub const MAX_BLOBS_PER_TX_OSAKA: u64 = 6;
+
+/// Blob parameters of a hardfork, configured in the `blobSchedule` of the chain config
+/// since [EIP-7840](https://eips.ethereum.org/EIPS/eip-7840).
+///
+/// Blob parameter only (BPO) forks change them without changing the EVM.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct BlobParams {
+    /// Target number of blobs per block.
+    pub target: u64,
+    /// Maximum number of blobs per block.
+    pub max: u64,
+    /// Maximum number of blobs per transaction.
+    pub max_blobs_per_tx: u64,
+    /// Controls the maximum rate of change of the blob gas price.
+    pub base_fee_update_fraction: u64,
+}
+
+impl BlobParams {
+    /// Parameters of Cancun.
+    pub const CANCUN: Self = Self {
+        target: TARGET_BLOB_NUMBER_PER_BLOCK_CANCUN,
+        max: MAX_BLOB_NUMBER_PER_BLOCK_CANCUN,
+        max_blobs_per_tx: MAX_BLOB_NUMBER_PER_BLOCK_CANCUN,
+        base_fee_update_fraction: BLOB_BASE_FEE_UPDATE_FRACTION_CANCUN,
+    };
+
+    /// Parameters of Prague, [EIP-7691](https://eips.ethereum.org/EIPS/eip-7691) increased the
+    /// blob throughput.
+    pub const PRAGUE: Self = Self {
+        target: TARGET_BLOB_NUMBER_PER_BLOCK_PRAGUE,
+        max: MAX_BLOB_NUMBER_PER_BLOCK_PRAGUE,
+        max_blobs_per_tx: MAX_BLOB_NUMBER_PER_BLOCK_PRAGUE,
+        base_fee_update_fraction: BLOB_BASE_FEE_UPDATE_FRACTION_PRAGUE,
+    };
+
+    /// Parameters of Osaka, [EIP-7594](https://eips.ethereum.org/EIPS/eip-7594) limits the
+    /// number of blobs per transaction.
+    pub const OSAKA: Self = Self {
+        max_blobs_per_tx: MAX_BLOBS_PER_TX_OSAKA,
+        ..Self::PRAGUE
+    };
+
+    /// Returns the Ethereum mainnet parameters of the spec, [`None`] before Cancun.
+    pub const fn mainnet(spec: SpecId) -> Option<Self> {
+        if spec.is_enabled_in(SpecId::OSAKA) {
+            Some(Self::OSAKA)
+        } else if spec.is_enabled_in(SpecId::PRAGUE) {
+            Some(Self::PRAGUE)
+        } else if spec.is_enabled_in(SpecId::CANCUN) {
+            Some(Self::CANCUN)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the target blob gas per block.
+    pub const fn target_blob_gas_per_block(&self) -> u64 {
+        self.target * GAS_PER_BLOB
+    }
+
+    /// Returns the maximum blob gas per block.
+    pub const fn max_blob_gas_per_block(&self) -> u64 {
+        self.max * GAS_PER_BLOB
+    }
+
+    /// Calculates the excess blob gas of the next block from the excess blob gas and the blob
+    /// gas used of the parent block.
+    ///
+    /// This is the [EIP-4844](https://eips.ethereum.org/EIPS/eip-4844#helpers) formula, it
+    /// does not account for the blob base fee reserve price of
+    /// [EIP-7918](https://eips.ethereum.org/EIPS/eip-7918).
+    pub const fn next_excess_blob_gas(
+        &self,
+        parent_excess_blob_gas: u64,
+        parent_blob_gas_used: u64,
+    ) -> u64 {
+        (parent_excess_blob_gas + parent_blob_gas_used)
+            .saturating_sub(self.target_blob_gas_per_block())
+    }
+}
+
+/// Blob parameters of the hardforks of a chain.
+///
+/// The parameters of a spec are the ones of the latest spec of the schedule that is enabled in
+/// it, there are no blobs before the first spec of the schedule.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BlobSchedule {
+    params: Vec<(SpecId, BlobParams)>,
+}
+
+impl Default for BlobSchedule {
+    fn default() -> Self {
+        Self::mainnet()
+    }
+}
+
+impl BlobSchedule {
+    /// Creates a schedule from the parameters of the specs.
+    ///
+    /// Parameters are sorted by spec, the last parameters of a spec are kept.
+    pub fn new(params: impl IntoIterator<Item = (SpecId, BlobParams)>) -> Self {
+        let mut schedule = Self { params: Vec::new() };
+        for (spec, params) in params {
+            schedule.set(spec, params);
+        }
+        schedule
+    }
+
+    /// Returns the schedule of Ethereum mainnet.
+    pub fn mainnet() -> Self {
+        Self::new([
+            (SpecId::CANCUN, BlobParams::CANCUN),
+            (SpecId::PRAGUE, BlobParams::PRAGUE),
+            (SpecId::OSAKA, BlobParams::OSAKA),
+        ])
+    }
+
+    /// Sets the parameters of the spec, e.g. when a BPO fork activates.
+    pub fn set(&mut self, spec: SpecId, params: BlobParams) {
+        match self.params.binary_search_by_key(&spec, |(spec, _)| *spec) {
+            Ok(index) => self.params[index].1 = params,
+            Err(index) => self.params.insert(index, (spec, params)),
+        }
+    }
+
+    /// Returns the parameters sorted by spec.
+    pub fn params(&self) -> &[(SpecId, BlobParams)] {
+        &self.params
+    }
+
+    /// Returns the parameters of the spec, [`None`] if blobs are not enabled in it.
+    pub fn get(&self, spec: SpecId) -> Option<BlobParams> {
+        self.params
+            .iter()
+            .rev()
+            .find(|(activation, _)| spec.is_enabled_in(*activation))
+            .map(|(_, params)| *params)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn blob_schedule() {
+        let mut schedule = BlobSchedule::mainnet();
+        assert_eq!(schedule.get(SpecId::SHANGHAI), None);
+        assert_eq!(schedule.get(SpecId::CANCUN), Some(BlobParams::CANCUN));
+        assert_eq!(schedule.get(SpecId::OSAKA), Some(BlobParams::OSAKA));
+        for (spec, params) in schedule.params() {
+            assert_eq!(BlobParams::mainnet(*spec), Some(*params));
+        }
+
+        // BPO fork on top of Osaka.
+        let bpo = BlobParams {
+            target: 10,
+            max: 15,
+            ..BlobParams::OSAKA
+        };
+        schedule.set(SpecId::OSAKA, bpo);
+        assert_eq!(schedule.get(SpecId::AMSTERDAM), Some(bpo));
+        assert_eq!(schedule.get(SpecId::PRAGUE), Some(BlobParams::PRAGUE));
+    }
+
+    #[test]
+    fn next_excess_blob_gas() {
+        let params = BlobParams::PRAGUE;
+        assert_eq!(params.next_excess_blob_gas(0, 3 * GAS_PER_BLOB), 0);
+        assert_eq!(
+            params.next_excess_blob_gas(GAS_PER_BLOB, params.max_blob_gas_per_block()),
+            4 * GAS_PER_BLOB
+        );
+    }
+}
@@ -211,3 +211,94 @@ impl core::fmt::Display for SpecId {
         write!(f, "{}", <&'static str>::from(*self))
     }
 }
+
+/// Condition that activates a hardfork.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ForkCondition {
+    /// Activated at the block number.
+    Block(u64),
+    /// Activated at the block timestamp.
+    Timestamp(u64),
+}
+
+impl ForkCondition {
+    /// Returns `true` if the condition is met by the block.
+    #[inline]
+    pub const fn is_active(&self, block_number: u64, timestamp: u64) -> bool {
+        match *self {
+            Self::Block(number) => block_number >= number,
+            Self::Timestamp(time) => timestamp >= time,
+        }
+    }
+}
+
+/// Activation points of the hardforks of a chain.
+///
+/// Used to pick the [`SpecId`] of a block when replaying a range of blocks that spans hardforks.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct HardforkSchedule {
+    activations: std::vec::Vec<(SpecId, ForkCondition)>,
+}
+
+impl HardforkSchedule {
+    /// Creates a schedule from the activation points.
+    ///
+    /// Activations are sorted by spec, a spec without an activation is never active.
+    pub fn new(activations: impl IntoIterator<Item = (SpecId, ForkCondition)>) -> Self {
+        let mut activations: std::vec::Vec<_> = activations.into_iter().collect();
+        activations.sort_by_key(|(spec, _)| *spec);
+        Self { activations }
+    }
+
+    /// Returns the schedule of Ethereum mainnet.
+    pub fn mainnet() -> Self {
+        Self::new([
+            (FRONTIER, ForkCondition::Block(0)),
+            (HOMESTEAD, ForkCondition::Block(1_150_000)),
+            (TANGERINE, ForkCondition::Block(2_463_000)),
+            (SPURIOUS_DRAGON, ForkCondition::Block(2_675_000)),
+            (BYZANTIUM, ForkCondition::Block(4_370_000)),
+            (PETERSBURG, ForkCondition::Block(7_280_000)),
+            (ISTANBUL, ForkCondition::Block(9_069_000)),
+            (BERLIN, ForkCondition::Block(12_244_000)),
+            (LONDON, ForkCondition::Block(12_965_000)),
+            (MERGE, ForkCondition::Block(15_537_394)),
+            (SHANGHAI, ForkCondition::Timestamp(1_681_338_455)),
+            (CANCUN, ForkCondition::Timestamp(1_710_338_135)),
+            (PRAGUE, ForkCondition::Timestamp(1_746_612_311)),
+            (OSAKA, ForkCondition::Timestamp(1_764_798_551)),
+        ])
+    }
+
+    /// Returns the activation points sorted by spec.
+    pub fn activations(&self) -> &[(SpecId, ForkCondition)] {
+        &self.activations
+    }
+
+    /// Returns the latest spec that is active at the block, or [`FRONTIER`] if none is.
+    pub fn spec_at(&self, block_number: u64, timestamp: u64) -> SpecId {
+        self.activations
+            .iter()
+            .rev()
+            .find(|(_, condition)| condition.is_active(block_number, timestamp))
+            .map_or(FRONTIER, |(spec, _)| *spec)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mainnet_schedule() {
+        let schedule = HardforkSchedule::mainnet();
+        assert_eq!(schedule.spec_at(0, 0), FRONTIER);
+        assert_eq!(schedule.spec_at(12_965_000, 1_628_166_822), LONDON);
+        // Last block before the merge.
+        assert_eq!(schedule.spec_at(15_537_393, 1_663_224_162), LONDON);
+        assert_eq!(schedule.spec_at(17_034_870, 1_681_338_455), SHANGHAI);
+        assert_eq!(schedule.spec_at(22_431_084, 1_746_612_311), PRAGUE);
+    }
+}
@@ -16,8 +16,10 @@
 extern crate alloc as std;
 
 pub mod constants;
+pub mod eip1559;
 pub mod eip170;
 pub mod eip2780;
+pub mod eip2935;
 pub mod eip3860;
 pub mod eip4844;
 pub mod eip7702;
@@ -30,6 +30,9 @@ pub use state;
 #[doc(inline)]
 pub use statetest_types;
 
+pub mod replay;
+pub mod reprice;
+
 // Export items.
 
 pub use context::{
@@ -40,8 +43,8 @@ pub use context::{
 pub use database_interface::{AsyncDb, AsyncError, AsyncResult, DatabaseAsync, WrapDatabaseAsync};
 pub use database_interface::{Database, DatabaseCommit, DatabaseRef, NoopHook, OnStateHook};
 pub use handler::{
-    ExecuteCommitEvm, ExecuteEvm, MainBuilder, MainContext, MainnetEvm, SystemCallCommitEvm,
-    SystemCallEvm,
+    DeterminismCheckEvm, ExecuteCommitEvm, ExecuteEvm, MainBuilder, MainContext, MainnetEvm,
+    SystemCallCommitEvm, SystemCallEvm,
 };
 #[cfg(feature = "asyncdb")]
 pub use handler::{ExecuteEvmAsync, SystemCallEvmAsync};
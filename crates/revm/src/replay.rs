@@ -0,0 +1,316 @@
+//! Replay of a range of blocks on top of a [`State`].
+//!
+//! [`replay_range`] is the loop that is usually written around the `block_traces` example: it
+//! fetches every block from a [`BlockSource`], picks the [`SpecId`] of the block from a
+//! [`HardforkSchedule`], executes the transactions, merges the transitions of the block into the
+//! bundle and feeds the block hash back into the [`State`] so `BLOCKHASH` of the following
+//! blocks is served without a database lookup.
+extern crate alloc;
+
+use crate::{
+    context::{
+        result::{EVMError, ExecutionResult, TransactionIndexedError},
+        BlockEnv, CfgEnv, TxEnv,
+    },
+    database::{states::bundle_state::BundleRetention, State},
+    primitives::{
+        hardfork::{HardforkSchedule, SpecId},
+        B256,
+    },
+    Context, Database, ExecuteCommitEvm, MainBuilder, MainContext,
+};
+use alloc::vec::Vec;
+use core::{fmt, ops::RangeInclusive};
+
+/// Block to replay.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ReplayBlock {
+    /// Environment of the block.
+    pub block: BlockEnv,
+    /// Hash of the block.
+    pub hash: B256,
+    /// Transactions of the block in execution order.
+    pub transactions: Vec<TxEnv>,
+}
+
+/// Source of the blocks replayed by [`replay_range`], e.g. an RPC client or a local archive.
+pub trait BlockSource {
+    /// Error of the source.
+    type Error;
+
+    /// Returns the block with the number.
+    fn block(&mut self, number: u64) -> Result<ReplayBlock, Self::Error>;
+}
+
+impl<F, E> BlockSource for F
+where
+    F: FnMut(u64) -> Result<ReplayBlock, E>,
+{
+    type Error = E;
+
+    fn block(&mut self, number: u64) -> Result<ReplayBlock, Self::Error> {
+        self(number)
+    }
+}
+
+/// Outcome of a replayed block.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BlockOutcome {
+    /// Number of the block.
+    pub number: u64,
+    /// Hash of the block.
+    pub hash: B256,
+    /// Spec the block was executed with.
+    pub spec: SpecId,
+    /// Results of the transactions in execution order.
+    pub results: Vec<ExecutionResult>,
+    /// Gas used by the transactions of the block.
+    pub gas_used: u64,
+}
+
+/// Error of [`Replay`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ReplayError<SourceError, DBError> {
+    /// Block could not be fetched from the source.
+    Source {
+        /// Number of the block.
+        number: u64,
+        /// Error of the source.
+        error: SourceError,
+    },
+    /// Transaction of the block could not be executed, none of the block's changes are applied.
+    Transaction {
+        /// Number of the block.
+        number: u64,
+        /// Error of the transaction and its index in the block.
+        error: TransactionIndexedError<EVMError<DBError>>,
+    },
+}
+
+impl<SourceError, DBError> fmt::Display for ReplayError<SourceError, DBError>
+where
+    SourceError: fmt::Display,
+    DBError: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Source { number, error } => write!(f, "failed to fetch block {number}: {error}"),
+            Self::Transaction { number, error } => write!(
+                f,
+                "transaction {} of block {number} failed: {}",
+                error.transaction_index, error.error
+            ),
+        }
+    }
+}
+
+impl<SourceError, DBError> core::error::Error for ReplayError<SourceError, DBError>
+where
+    SourceError: fmt::Debug + fmt::Display,
+    DBError: fmt::Debug + fmt::Display,
+{
+}
+
+/// Iterator that replays a range of blocks, created by [`replay_range`].
+///
+/// Every call to [`Iterator::next`] executes one block and yields its [`BlockOutcome`].
+/// Iteration stops after the last block of the range or after the first error.
+///
+/// Transitions are only merged into the bundle if the [`State`] was built with
+/// [`StateBuilder::with_bundle_update`](crate::database::StateBuilder::with_bundle_update).
+/// System calls, withdrawals and block rewards are not applied.
+#[derive(Debug)]
+pub struct Replay<'a, DB, S> {
+    state: &'a mut State<DB>,
+    source: S,
+    blocks: RangeInclusive<u64>,
+    schedule: HardforkSchedule,
+    cfg: CfgEnv,
+    reverts: bool,
+    failed: bool,
+}
+
+/// Replays the blocks from `start_block` to `end_block` (inclusive) on top of the state.
+///
+/// Blocks are executed with the [mainnet schedule](HardforkSchedule::mainnet) and chain id `1`,
+/// see [`Replay::with_schedule`] and [`Replay::with_cfg`] for other chains.
+pub fn replay_range<DB: Database, S: BlockSource>(
+    state: &mut State<DB>,
+    source: S,
+    start_block: u64,
+    end_block: u64,
+) -> Replay<'_, DB, S> {
+    Replay {
+        state,
+        source,
+        blocks: start_block..=end_block,
+        schedule: HardforkSchedule::mainnet(),
+        cfg: CfgEnv::default(),
+        reverts: true,
+        failed: false,
+    }
+}
+
+impl<DB: Database, S: BlockSource> Replay<'_, DB, S> {
+    /// Sets the schedule used to pick the spec of every block.
+    pub fn with_schedule(mut self, schedule: HardforkSchedule) -> Self {
+        self.schedule = schedule;
+        self
+    }
+
+    /// Sets the configuration the blocks are executed with, its spec is overwritten per block.
+    pub fn with_cfg(mut self, cfg: CfgEnv) -> Self {
+        self.cfg = cfg;
+        self
+    }
+
+    /// Sets whether the reverts of every block are retained in the bundle, enabled by default.
+    pub fn with_reverts(mut self, reverts: bool) -> Self {
+        self.reverts = reverts;
+        self
+    }
+
+    fn replay_block(
+        &mut self,
+        number: u64,
+    ) -> Result<BlockOutcome, ReplayError<S::Error, DB::Error>> {
+        let ReplayBlock {
+            block,
+            hash,
+            transactions,
+        } = self
+            .source
+            .block(number)
+            .map_err(|error| ReplayError::Source { number, error })?;
+
+        let spec = self
+            .schedule
+            .spec_at(number, block.timestamp.saturating_to());
+        let mut cfg = self.cfg.clone();
+        cfg.set_spec_and_mainnet_gas_params(spec);
+
+        let mut evm = Context::mainnet()
+            .with_db(&mut *self.state)
+            .with_block(block)
+            .with_cfg(cfg)
+            .build_mainnet();
+        let results = evm
+            .transact_many_commit(transactions.into_iter())
+            .map_err(|error| ReplayError::Transaction { number, error })?;
+
+        self.state.merge_transitions(if self.reverts {
+            BundleRetention::Reverts
+        } else {
+            BundleRetention::PlainState
+        });
+        self.state.block_hashes.insert(number, hash);
+
+        Ok(BlockOutcome {
+            number,
+            hash,
+            spec,
+            gas_used: results.iter().map(ExecutionResult::tx_gas_used).sum(),
+            results,
+        })
+    }
+}
+
+impl<DB: Database, S: BlockSource> Iterator for Replay<'_, DB, S> {
+    type Item = Result<BlockOutcome, ReplayError<S::Error, DB::Error>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
+        }
+        let number = self.blocks.next()?;
+        let outcome = self.replay_block(number);
+        self.failed = outcome.is_err();
+        Some(outcome)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        database::{EmptyDB, StateBuilder},
+        primitives::{hardfork::ForkCondition, Address, TxKind, U256},
+        state::AccountInfo,
+    };
+    use core::convert::Infallible;
+
+    #[test]
+    fn replays_blocks_across_hardforks() {
+        let caller = Address::with_last_byte(0xca);
+        let receiver = Address::with_last_byte(0xbe);
+        let mut state = StateBuilder::new_with_database(EmptyDB::new())
+            .with_bundle_update()
+            .build();
+        state.insert_account(caller, AccountInfo::from_balance(U256::from(10u64.pow(18))));
+
+        let source = |number: u64| -> Result<ReplayBlock, Infallible> {
+            Ok(ReplayBlock {
+                block: BlockEnv {
+                    number: U256::from(number),
+                    timestamp: U256::from(number * 12),
+                    ..Default::default()
+                },
+                hash: B256::with_last_byte(number as u8),
+                transactions: vec![TxEnv::builder()
+                    .caller(caller)
+                    .kind(TxKind::Call(receiver))
+                    .value(U256::from(1))
+                    .nonce(number - 1)
+                    .gas_limit(21_000)
+                    .build()
+                    .unwrap()],
+            })
+        };
+        let schedule = HardforkSchedule::new([
+            (SpecId::LONDON, ForkCondition::Block(0)),
+            (SpecId::SHANGHAI, ForkCondition::Timestamp(36)),
+        ]);
+
+        let outcomes: Vec<_> = replay_range(&mut state, source, 1, 3)
+            .with_schedule(schedule)
+            .collect::<Result<_, _>>()
+            .unwrap();
+
+        let specs: Vec<_> = outcomes.iter().map(|outcome| outcome.spec).collect();
+        assert_eq!(specs, [SpecId::LONDON, SpecId::LONDON, SpecId::SHANGHAI]);
+        assert!(outcomes
+            .iter()
+            .all(|outcome| outcome.results[0].is_success() && outcome.gas_used == 21_000));
+        assert_eq!(state.block_hashes.get(2), Some(B256::with_last_byte(2)));
+
+        let bundle = state.take_bundle();
+        assert_eq!(
+            bundle
+                .account(&receiver)
+                .unwrap()
+                .info
+                .as_ref()
+                .unwrap()
+                .balance,
+            U256::from(3)
+        );
+        // One revert per block.
+        assert_eq!(bundle.reverts.len(), 3);
+    }
+
+    #[test]
+    fn stops_at_first_error() {
+        let mut state = StateBuilder::new_with_database(EmptyDB::new()).build();
+        let source = |number: u64| match number {
+            1 => Ok(ReplayBlock::default()),
+            _ => Err("missing block"),
+        };
+        let mut replay = replay_range(&mut state, source, 1, 3);
+        assert!(replay.next().unwrap().is_ok());
+        assert!(matches!(
+            replay.next(),
+            Some(Err(ReplayError::Source { number: 2, .. }))
+        ));
+        assert!(replay.next().is_none());
+    }
+}
@@ -0,0 +1,406 @@
+//! Replay of a range of blocks under a modified gas schedule.
+//!
+//! [`reprice_range`] executes every block of a [`BlockSource`] twice: on a baseline [`State`]
+//! with the gas params of the block's spec, and on a second [`State`] with gas params modified
+//! by a callback. It reports the change of the gas used by every block and the transactions that
+//! succeed at the baseline but revert, halt or become invalid when repriced, to evaluate a
+//! repricing EIP against historical blocks.
+extern crate alloc;
+
+use crate::{
+    context::{
+        result::{EVMError, ExecutionResult, InvalidTransaction, TransactionIndexedError},
+        CfgEnv,
+    },
+    context_interface::cfg::GasParams,
+    database::{states::bundle_state::BundleRetention, State},
+    primitives::{
+        hardfork::{HardforkSchedule, SpecId},
+        B256,
+    },
+    replay::{BlockSource, ReplayBlock, ReplayError},
+    Context, Database, ExecuteCommitEvm, MainBuilder, MainContext,
+};
+use alloc::vec::Vec;
+use core::{fmt, ops::RangeInclusive};
+
+/// Result of a transaction executed with the repriced gas params.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RepricedResult {
+    /// Transaction was executed.
+    Executed(ExecutionResult),
+    /// Transaction became invalid, e.g. its gas limit is below the repriced intrinsic gas.
+    Invalid(InvalidTransaction),
+}
+
+impl RepricedResult {
+    /// Returns `true` if the transaction was executed successfully.
+    pub fn is_success(&self) -> bool {
+        matches!(self, Self::Executed(result) if result.is_success())
+    }
+
+    /// Returns the gas used by the transaction, zero for invalid transactions.
+    pub fn tx_gas_used(&self) -> u64 {
+        match self {
+            Self::Executed(result) => result.tx_gas_used(),
+            Self::Invalid(_) => 0,
+        }
+    }
+}
+
+/// Transaction that succeeds at the baseline and fails with the repriced gas params.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NewlyFailing {
+    /// Index of the transaction in the block.
+    pub index: usize,
+    /// Result of the transaction at the baseline.
+    pub baseline: ExecutionResult,
+    /// Result of the transaction with the repriced gas params.
+    pub repriced: RepricedResult,
+}
+
+/// Outcome of a block executed at the baseline and repriced.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RepricedBlock {
+    /// Number of the block.
+    pub number: u64,
+    /// Hash of the block.
+    pub hash: B256,
+    /// Spec the block was executed with.
+    pub spec: SpecId,
+    /// Gas used by the transactions of the block at the baseline.
+    pub baseline_gas_used: u64,
+    /// Gas used by the transactions of the block with the repriced gas params.
+    pub repriced_gas_used: u64,
+    /// Transactions that fail only with the repriced gas params, in execution order.
+    pub newly_failing: Vec<NewlyFailing>,
+}
+
+impl RepricedBlock {
+    /// Returns the change of the gas used by the block, negative if repricing made it cheaper.
+    pub fn gas_delta(&self) -> i128 {
+        self.repriced_gas_used as i128 - self.baseline_gas_used as i128
+    }
+}
+
+impl fmt::Display for RepricedBlock {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "block {}: {} -> {} gas ({:+}), {} newly failing",
+            self.number,
+            self.baseline_gas_used,
+            self.repriced_gas_used,
+            self.gas_delta(),
+            self.newly_failing.len()
+        )
+    }
+}
+
+/// Iterator that replays a range of blocks at the baseline and repriced, created by
+/// [`reprice_range`].
+///
+/// Every call to [`Iterator::next`] fetches one block, executes it on both states and yields its
+/// [`RepricedBlock`]. Iteration stops after the last block of the range or after the first
+/// error. Transactions that become invalid when repriced are reported and skipped, errors of
+/// the baseline and database errors stop the iteration.
+///
+/// Like [`Replay`](crate::replay::Replay), system calls, withdrawals and block rewards are not
+/// applied.
+#[derive(Debug)]
+pub struct Repricing<'a, DB, S, F> {
+    baseline: &'a mut State<DB>,
+    repriced: &'a mut State<DB>,
+    source: S,
+    reprice: F,
+    blocks: RangeInclusive<u64>,
+    schedule: HardforkSchedule,
+    cfg: CfgEnv,
+    failed: bool,
+}
+
+/// Replays the blocks from `start_block` to `end_block` (inclusive) on the `baseline` state and,
+/// with the gas params modified by `reprice`, on the `repriced` state.
+///
+/// Both states are expected to hold the same prestate. `reprice` is called for every block with
+/// its spec and the mainnet gas params of the spec:
+///
+/// ```rust,ignore
+/// let repricing = reprice_range(&mut baseline, &mut repriced, source, start, end, |_, params| {
+///     params.override_gas([(GasId::cold_storage_cost(), 4_200)])
+/// });
+/// for block in repricing {
+///     println!("{}", block?);
+/// }
+/// ```
+///
+/// Blocks are executed with the [mainnet schedule](HardforkSchedule::mainnet) and chain id `1`,
+/// see [`Repricing::with_schedule`] and [`Repricing::with_cfg`] for other chains.
+pub fn reprice_range<'a, DB, S, F>(
+    baseline: &'a mut State<DB>,
+    repriced: &'a mut State<DB>,
+    source: S,
+    start_block: u64,
+    end_block: u64,
+    reprice: F,
+) -> Repricing<'a, DB, S, F>
+where
+    DB: Database,
+    S: BlockSource,
+    F: FnMut(SpecId, &mut GasParams),
+{
+    Repricing {
+        baseline,
+        repriced,
+        source,
+        reprice,
+        blocks: start_block..=end_block,
+        schedule: HardforkSchedule::mainnet(),
+        cfg: CfgEnv::default(),
+        failed: false,
+    }
+}
+
+impl<DB, S, F> Repricing<'_, DB, S, F>
+where
+    DB: Database,
+    S: BlockSource,
+    F: FnMut(SpecId, &mut GasParams),
+{
+    /// Sets the schedule used to pick the spec of every block.
+    pub fn with_schedule(mut self, schedule: HardforkSchedule) -> Self {
+        self.schedule = schedule;
+        self
+    }
+
+    /// Sets the configuration the blocks are executed with, its spec and gas params are
+    /// overwritten per block.
+    pub fn with_cfg(mut self, cfg: CfgEnv) -> Self {
+        self.cfg = cfg;
+        self
+    }
+
+    fn reprice_block(
+        &mut self,
+        number: u64,
+    ) -> Result<RepricedBlock, ReplayError<S::Error, DB::Error>> {
+        let ReplayBlock {
+            block,
+            hash,
+            transactions,
+        } = self
+            .source
+            .block(number)
+            .map_err(|error| ReplayError::Source { number, error })?;
+
+        let spec = self
+            .schedule
+            .spec_at(number, block.timestamp.saturating_to());
+        let mut cfg = self.cfg.clone();
+        cfg.set_spec_and_mainnet_gas_params(spec);
+        let mut repriced_cfg = cfg.clone();
+        (self.reprice)(spec, &mut repriced_cfg.gas_params);
+
+        let baseline = Context::mainnet()
+            .with_db(&mut *self.baseline)
+            .with_block(block.clone())
+            .with_cfg(cfg)
+            .build_mainnet()
+            .transact_many_commit(transactions.iter().cloned())
+            .map_err(|error| ReplayError::Transaction { number, error })?;
+
+        let repriced = {
+            let mut evm = Context::mainnet()
+                .with_db(&mut *self.repriced)
+                .with_block(block)
+                .with_cfg(repriced_cfg)
+                .build_mainnet();
+            let mut repriced = Vec::with_capacity(transactions.len());
+            for (index, tx) in transactions.into_iter().enumerate() {
+                // Invalid transactions don't change the state and are reported instead.
+                let result = match evm.transact_commit(tx) {
+                    Ok(result) => RepricedResult::Executed(result),
+                    Err(EVMError::Transaction(error)) => RepricedResult::Invalid(error),
+                    Err(error) => {
+                        return Err(ReplayError::Transaction {
+                            number,
+                            error: TransactionIndexedError::new(error, index),
+                        })
+                    }
+                };
+                repriced.push(result);
+            }
+            repriced
+        };
+
+        for state in [&mut *self.baseline, &mut *self.repriced] {
+            state.merge_transitions(BundleRetention::PlainState);
+            state.block_hashes.insert(number, hash);
+        }
+
+        let newly_failing = baseline
+            .iter()
+            .zip(&repriced)
+            .enumerate()
+            .filter(|(_, (baseline, repriced))| baseline.is_success() && !repriced.is_success())
+            .map(|(index, (baseline, repriced))| NewlyFailing {
+                index,
+                baseline: baseline.clone(),
+                repriced: repriced.clone(),
+            })
+            .collect();
+
+        Ok(RepricedBlock {
+            number,
+            hash,
+            spec,
+            baseline_gas_used: baseline.iter().map(ExecutionResult::tx_gas_used).sum(),
+            repriced_gas_used: repriced.iter().map(RepricedResult::tx_gas_used).sum(),
+            newly_failing,
+        })
+    }
+}
+
+impl<DB, S, F> Iterator for Repricing<'_, DB, S, F>
+where
+    DB: Database,
+    S: BlockSource,
+    F: FnMut(SpecId, &mut GasParams),
+{
+    type Item = Result<RepricedBlock, ReplayError<S::Error, DB::Error>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
+        }
+        let number = self.blocks.next()?;
+        let outcome = self.reprice_block(number);
+        self.failed = outcome.is_err();
+        Some(outcome)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        bytecode::{
+            opcode::{PUSH1, SSTORE, STOP},
+            Bytecode,
+        },
+        context::{BlockEnv, TxEnv},
+        context_interface::cfg::GasId,
+        database::{EmptyDB, StateBuilder},
+        primitives::{hardfork::ForkCondition, Address, Bytes, TxKind, U256},
+        state::AccountInfo,
+    };
+    use core::convert::Infallible;
+
+    #[test]
+    fn reports_gas_delta_and_newly_failing_transactions() {
+        let caller = Address::with_last_byte(0xca);
+        let contract = Address::with_last_byte(0xc0);
+        // Stores 1 in the empty slot 0.
+        let code = Bytecode::new_raw(Bytes::from_static(&[
+            PUSH1, 0x01, PUSH1, 0x00, SSTORE, STOP,
+        ]));
+        let new_state = || {
+            let mut state = StateBuilder::new_with_database(EmptyDB::new())
+                .with_bundle_update()
+                .build();
+            state.insert_account(caller, AccountInfo::from_balance(U256::from(10u64.pow(18))));
+            state.insert_account(contract, AccountInfo::default().with_code(code.clone()));
+            state
+        };
+        let (mut baseline, mut repriced) = (new_state(), new_state());
+
+        let source = |number: u64| -> Result<ReplayBlock, Infallible> {
+            let tx = |nonce, kind, gas_limit| {
+                TxEnv::builder()
+                    .caller(caller)
+                    .kind(kind)
+                    .nonce(nonce)
+                    .gas_limit(gas_limit)
+                    .build()
+                    .unwrap()
+            };
+            Ok(ReplayBlock {
+                block: BlockEnv {
+                    number: U256::from(number),
+                    ..Default::default()
+                },
+                hash: B256::with_last_byte(number as u8),
+                transactions: vec![
+                    // Transfer that isn't affected by the repricing.
+                    tx(0, TxKind::Call(Address::with_last_byte(0xbe)), 21_000),
+                    // Store with little headroom over the baseline cost.
+                    tx(1, TxKind::Call(contract), 50_000),
+                ],
+            })
+        };
+
+        let outcomes: Vec<_> =
+            reprice_range(&mut baseline, &mut repriced, source, 1, 1, |_, params| {
+                params.override_gas([(GasId::sstore_set_without_load_cost(), 40_000)])
+            })
+            .with_schedule(HardforkSchedule::new([(
+                SpecId::PRAGUE,
+                ForkCondition::Block(0),
+            )]))
+            .collect::<Result<_, _>>()
+            .unwrap();
+
+        let [outcome] = outcomes.as_slice() else {
+            panic!("expected one block: {outcomes:?}");
+        };
+        assert_eq!(outcome.spec, SpecId::PRAGUE);
+        assert!(outcome.gas_delta() > 0, "{outcome}");
+        assert_eq!(outcome.repriced_gas_used, 21_000 + 50_000);
+        let [failing] = outcome.newly_failing.as_slice() else {
+            panic!("expected one newly failing transaction: {outcome:?}");
+        };
+        assert_eq!(failing.index, 1);
+        assert!(failing.baseline.is_success());
+        assert!(matches!(
+            failing.repriced,
+            RepricedResult::Executed(ExecutionResult::Halt { .. })
+        ));
+        assert_eq!(repriced.block_hashes.get(1), Some(B256::with_last_byte(1)));
+    }
+
+    #[test]
+    fn reports_transactions_that_become_invalid() {
+        let caller = Address::with_last_byte(0xca);
+        let mut baseline = StateBuilder::new_with_database(EmptyDB::new()).build();
+        let mut repriced = StateBuilder::new_with_database(EmptyDB::new()).build();
+        for state in [&mut baseline, &mut repriced] {
+            state.insert_account(caller, AccountInfo::from_balance(U256::from(10u64.pow(18))));
+        }
+        let source = |_| -> Result<ReplayBlock, Infallible> {
+            Ok(ReplayBlock {
+                transactions: vec![TxEnv::builder()
+                    .caller(caller)
+                    .kind(TxKind::Call(Address::with_last_byte(0xbe)))
+                    .gas_limit(21_000)
+                    .build()
+                    .unwrap()],
+                ..Default::default()
+            })
+        };
+
+        let outcome = reprice_range(&mut baseline, &mut repriced, source, 1, 1, |_, params| {
+            params.override_gas([(GasId::tx_base_stipend(), 30_000)])
+        })
+        .next()
+        .unwrap()
+        .unwrap();
+
+        assert_eq!(outcome.baseline_gas_used, 21_000);
+        assert_eq!(outcome.repriced_gas_used, 0);
+        assert_eq!(outcome.gas_delta(), -21_000);
+        assert!(matches!(
+            outcome.newly_failing[0].repriced,
+            RepricedResult::Invalid(_)
+        ));
+    }
+}
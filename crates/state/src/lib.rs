@@ -7,6 +7,7 @@ extern crate alloc as std;
 
 mod account_info;
 pub mod bal;
+mod touched;
 mod types;
 
 pub use bytecode;
@@ -14,6 +15,7 @@ pub use bytecode;
 pub use account_info::{AccountId, AccountInfo};
 pub use bytecode::Bytecode;
 pub use primitives;
+pub use touched::TouchedAccounts;
 pub use types::{EvmState, EvmStorage, TransientStorage};
 
 use bitflags::bitflags;
@@ -0,0 +1,128 @@
+use crate::EvmState;
+use primitives::Address;
+use std::vec::Vec;
+
+/// Summary of the accounts touched by an execution.
+///
+/// Schedulers and indexers often only need to know which accounts were accessed and how,
+/// this is a compact view of the [`EvmState`] without account info and storage. Every list is
+/// sorted by address.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TouchedAccounts {
+    /// Accounts that were loaded but not changed.
+    pub read: Vec<Address>,
+    /// Accounts whose balance, nonce, code or storage was changed, including created and
+    /// destroyed accounts.
+    pub written: Vec<Address>,
+    /// Accounts that were created.
+    pub created: Vec<Address>,
+    /// Accounts that were selfdestructed.
+    pub destroyed: Vec<Address>,
+}
+
+impl TouchedAccounts {
+    /// Creates the summary of the accounts of the state.
+    pub fn from_state(state: &EvmState) -> Self {
+        let mut touched = Self::default();
+        for (address, account) in state {
+            // Only changes of touched accounts are committed.
+            let is_touched = account.is_touched();
+            let created = is_touched && account.is_created();
+            let destroyed = is_touched && account.is_selfdestructed();
+            let changed = account.is_changed() || account.changed_storage_slots().next().is_some();
+            let written = created || destroyed || is_touched && changed;
+            if written {
+                touched.written.push(*address);
+            } else {
+                touched.read.push(*address);
+            }
+            if created {
+                touched.created.push(*address);
+            }
+            if destroyed {
+                touched.destroyed.push(*address);
+            }
+        }
+        for addresses in [
+            &mut touched.read,
+            &mut touched.written,
+            &mut touched.created,
+            &mut touched.destroyed,
+        ] {
+            addresses.sort_unstable();
+        }
+        touched
+    }
+
+    /// Returns `true` if the account was read or written.
+    pub fn contains(&self, address: &Address) -> bool {
+        self.is_written(address) || self.read.binary_search(address).is_ok()
+    }
+
+    /// Returns `true` if the account was written.
+    pub fn is_written(&self, address: &Address) -> bool {
+        self.written.binary_search(address).is_ok()
+    }
+
+    /// Returns the number of touched accounts.
+    pub fn len(&self) -> usize {
+        self.read.len() + self.written.len()
+    }
+
+    /// Returns `true` if no account was touched.
+    pub fn is_empty(&self) -> bool {
+        self.read.is_empty() && self.written.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Account, AccountInfo, EvmStorageSlot, TransactionId};
+    use primitives::{StorageKey, StorageValue, U256};
+
+    #[test]
+    fn classifies_accounts() {
+        let [loaded, funded, stored, created, destroyed] =
+            [1, 2, 3, 4, 5].map(Address::with_last_byte);
+        let mut state = EvmState::default();
+
+        // Loaded and touched without changes, e.g. a zero value transfer.
+        let mut account = Account::from(AccountInfo::from_balance(U256::from(1)));
+        account.mark_touch();
+        state.insert(loaded, account);
+
+        let mut account = Account::default();
+        account.info.balance = U256::from(1);
+        account.mark_touch();
+        state.insert(funded, account);
+
+        let mut account = Account::from(AccountInfo::from_balance(U256::from(1)));
+        account.storage.insert(
+            StorageKey::ZERO,
+            EvmStorageSlot::new_changed(StorageValue::ZERO, U256::from(1), TransactionId::ZERO),
+        );
+        account.mark_touch();
+        state.insert(stored, account);
+
+        let mut account = Account::default();
+        account.mark_created();
+        account.mark_touch();
+        state.insert(created, account);
+
+        let mut account = Account::default();
+        account.mark_selfdestruct();
+        account.mark_touch();
+        state.insert(destroyed, account);
+
+        let touched = TouchedAccounts::from_state(&state);
+        assert_eq!(touched.read, [loaded]);
+        assert_eq!(touched.written, [funded, stored, created, destroyed]);
+        assert_eq!(touched.created, [created]);
+        assert_eq!(touched.destroyed, [destroyed]);
+        assert_eq!(touched.len(), 5);
+        assert!(touched.contains(&loaded) && !touched.is_written(&loaded));
+        assert!(!touched.contains(&Address::ZERO));
+    }
+}
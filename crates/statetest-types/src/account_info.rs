@@ -1,10 +1,10 @@
 use primitives::{Bytes, StorageKeyMap, StorageValue, U256};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
-use crate::deserializer::deserialize_str_as_u64;
+use crate::deserializer::{deserialize_str_as_u64, serialize_u64_as_str};
 
 /// Account information
-#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase", deny_unknown_fields)]
 pub struct AccountInfo {
     /// Account balance in wei
@@ -12,7 +12,10 @@ pub struct AccountInfo {
     /// Account bytecode
     pub code: Bytes,
     /// Account nonce (transaction count)
-    #[serde(deserialize_with = "deserialize_str_as_u64")]
+    #[serde(
+        deserialize_with = "deserialize_str_as_u64",
+        serialize_with = "serialize_u64_as_str"
+    )]
     pub nonce: u64,
     /// Account storage (key-value pairs)
     pub storage: StorageKeyMap<StorageValue>,
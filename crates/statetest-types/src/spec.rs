@@ -1,8 +1,8 @@
 use primitives::hardfork::SpecId;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
 /// Ethereum specification names
-#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Deserialize, Hash)]
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, Hash)]
 pub enum SpecName {
     /// Frontier hardfork (Ethereum launch, July 2015)
     Frontier,
@@ -1,13 +1,13 @@
 use context::tx::TxEnv;
 use primitives::{AddressMap, Bytes, TxKind, B256};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
 use crate::{
     error::TestError, transaction::TxPartIndices, utils::recover_address, AccountInfo, TestUnit,
 };
 
 /// State test indexed state result deserialization.
-#[derive(Debug, PartialEq, Eq, Deserialize)]
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Test {
     /// Expected exception for this test case, if any.
@@ -31,7 +31,7 @@ pub struct Test {
     /// Output state.
     ///
     /// Note: Not used.
-    #[serde(default)]
+    #[serde(default, skip_serializing)]
     state: AddressMap<AccountInfo>,
 
     /// Tx bytes
@@ -39,6 +39,20 @@ pub struct Test {
 }
 
 impl Test {
+    /// Creates the expected result of the transaction at `indexes`, with the post state hash and
+    /// the logs root.
+    pub fn new(indexes: TxPartIndices, hash: B256, logs: B256) -> Self {
+        Self {
+            expect_exception: None,
+            indexes,
+            hash,
+            post_state: AddressMap::default(),
+            logs,
+            state: AddressMap::default(),
+            txbytes: None,
+        }
+    }
+
     /// Create a transaction environment from this test and the test unit.
     ///
     /// This function sets up the transaction environment using the test's
@@ -108,6 +122,7 @@ impl Test {
                 .max_fee_per_blob_gas
                 .map(|b| u128::try_from(b).expect("max fee less than u128::MAX"))
                 .unwrap_or(u128::MAX),
+            blob_sidecar: None,
             tx_type: tx_type as u8,
             gas_limit: unit.transaction.gas_limit[self.indexes.gas].saturating_to(),
             data: unit.transaction.data[self.indexes.data].clone(),
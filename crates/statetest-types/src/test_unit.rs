@@ -2,12 +2,12 @@ use crate::{AccountInfo, Env, SpecName, Test, TransactionParts};
 use context::{block::BlockEnv, cfg::CfgEnv};
 use database::CacheState;
 use primitives::{hardfork::SpecId, keccak256, AddressMap, Bytes, B256};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use state::Bytecode;
 use std::collections::BTreeMap;
 
 /// Single test unit struct
-#[derive(Debug, PartialEq, Eq, Deserialize)]
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
 //#[serde(deny_unknown_fields)]
 // field config
 pub struct TestUnit {
@@ -68,13 +68,26 @@ impl TestUnit {
     ///
     /// A [`CacheState`] object containing the pre-state accounts, storages and contracts.
     pub fn state(&self) -> CacheState {
+        self.state_with_bytecode(|_, code| {
+            Bytecode::new_raw_checked(code.clone()).unwrap_or(Bytecode::new_legacy(code.clone()))
+        })
+    }
+
+    /// Prepare the state from the test unit, see [`TestUnit::state`].
+    ///
+    /// `bytecode` is called with the code hash and the code of every contract and returns its
+    /// analyzed bytecode, e.g. from a cache shared between tests.
+    pub fn state_with_bytecode(
+        &self,
+        mut bytecode: impl FnMut(B256, &Bytes) -> Bytecode,
+    ) -> CacheState {
         let mut cache_state = CacheState::new();
         for (address, info) in &self.pre {
             let code_hash = keccak256(&info.code);
             if !info.code.is_empty() {
-                let bytecode = Bytecode::new_raw_checked(info.code.clone())
-                    .unwrap_or(Bytecode::new_legacy(info.code.clone()));
-                cache_state.contracts.insert(code_hash, bytecode);
+                cache_state
+                    .contracts
+                    .insert(code_hash, bytecode(code_hash, &info.code));
             }
             let acc_info = state::AccountInfo {
                 balance: info.balance,
@@ -1,4 +1,7 @@
-use crate::{deserializer::deserialize_maybe_empty, TestAuthorization};
+use crate::{
+    deserializer::{deserialize_maybe_empty, serialize_maybe_empty},
+    TestAuthorization,
+};
 use context::TransactionType;
 use context_interface::transaction::AccessList;
 use primitives::{Address, Bytes, B256, U256};
@@ -32,7 +35,11 @@ pub struct TransactionParts {
     #[serde(default)]
     pub sender: Option<Address>,
     /// Recipient address (None for contract creation)
-    #[serde(default, deserialize_with = "deserialize_maybe_empty")]
+    #[serde(
+        default,
+        deserialize_with = "deserialize_maybe_empty",
+        serialize_with = "serialize_maybe_empty"
+    )]
     pub to: Option<Address>,
     /// Ether value to transfer (multiple variants for different test cases)
     pub value: Vec<U256>,
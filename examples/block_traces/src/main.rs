@@ -1,6 +1,8 @@
 //! Example that show how to replay a block and trace the execution of each transaction.
 //!
 //! The EIP3155 trace of each transaction is saved into file `traces/{tx_number}.json`.
+//! If `TRACE_SINK` is set (e.g. `tcp://127.0.0.1:9000` or `unix:///tmp/traces.sock`),
+//! traces are streamed there as newline-delimited JSON instead.
 #![cfg_attr(not(test), warn(unused_crate_dependencies))]
 
 use alloy_consensus::Transaction;
@@ -11,7 +13,7 @@ use revm::{
     context::TxEnv,
     database::{AlloyDB, CacheDB, StateBuilder},
     database_interface::WrapDatabaseAsync,
-    inspector::{inspectors::TracerEip3155, InspectEvm},
+    inspector::{inspectors::TracerEip3155, InspectEvm, TraceSink},
     primitives::{TxKind, U256},
     Context, MainBuilder, MainContext,
 };
@@ -22,17 +24,17 @@ use std::{
     time::Instant,
 };
 
-struct FlushWriter {
-    writer: Arc<Mutex<BufWriter<std::fs::File>>>,
+struct FlushWriter<W> {
+    writer: Arc<Mutex<W>>,
 }
 
-impl FlushWriter {
-    const fn new(writer: Arc<Mutex<BufWriter<std::fs::File>>>) -> Self {
+impl<W> FlushWriter<W> {
+    const fn new(writer: Arc<Mutex<W>>) -> Self {
         Self { writer }
     }
 }
 
-impl Write for FlushWriter {
+impl<W: Write> Write for FlushWriter<W> {
     fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
         self.writer.lock().unwrap().write(buf)
     }
@@ -44,7 +46,13 @@ impl Write for FlushWriter {
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
-    create_dir_all("traces")?;
+    let sink = match std::env::var("TRACE_SINK") {
+        Ok(uri) => Some(Arc::new(Mutex::new(TraceSink::connect(&uri)?))),
+        Err(_) => {
+            create_dir_all("traces")?;
+            None
+        }
+    };
 
     // Set up the HTTP transport which is consumed by the RPC client.
     let rpc_url = "https://mainnet.infura.io/v3/c60b0bb42f8a4c6481ecd229eddaca27".parse()?;
@@ -91,6 +99,9 @@ async fn main() -> anyhow::Result<()> {
             c.chain_id = chain_id;
         });
 
+    // A single EVM is reused for the whole block, it keeps its buffers and the loaded accounts
+    // between transactions. `Evm::reset_for_next_tx` and `Evm::reset_for_next_block` bring it
+    // back to a clean state without rebuilding it.
     let mut evm = ctx.build_mainnet_with_inspector(TracerEip3155::new(Box::new(std::io::sink())));
 
     let txs = block.transactions.len();
@@ -125,6 +136,16 @@ async fn main() -> anyhow::Result<()> {
             .build()
             .unwrap();
 
+        if let Some(sink) = &sink {
+            let writer = FlushWriter::new(Arc::clone(sink));
+            // Stream the trace, the tracer flushes the sink at the end of the transaction.
+            if let Err(error) = evm.inspect_one(tx, TracerEip3155::new(Box::new(writer))) {
+                println!("Got error: {error:?}");
+            }
+            console_bar.inc(1);
+            continue;
+        }
+
         let file_name = format!("traces/{tx_number}.json");
         let write = OpenOptions::new()
             .write(true)